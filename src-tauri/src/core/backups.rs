@@ -0,0 +1,371 @@
+//! Database backups.
+//!
+//! A backup is a consistent copy of the SQLite database taken with
+//! `VACUUM INTO`, so it holds the inventory, settings and vault ciphertexts.
+//! The vault master key stays in the OS keychain and is not part of it, which
+//! is why verification checks that the secrets still decrypt with the current
+//! key. Automatic backups follow the `backup_schedule` preference (`daily` or
+//! `weekly`) and only the newest `backup_retain` of them are kept.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OpenFlags};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::scheduler::{self, BackgroundTask};
+use crate::core::types::{BackupResult, BackupVerification};
+use crate::secrets::manager::SecretManager;
+use crate::storage::db::Db;
+use crate::storage::migrations::{SCHEMA_VERSION, schema_version};
+use crate::storage::preferences::PreferencesStore;
+
+/// Preference holding the schedule: `daily`, `weekly`, or anything else for off.
+pub const BACKUP_SCHEDULE_PREFERENCE: &str = "backup_schedule";
+/// Preference holding the folder backups are written to.
+pub const BACKUP_FOLDER_PREFERENCE: &str = "backup_folder";
+/// Preference holding how many automatic backups to keep.
+pub const BACKUP_RETAIN_PREFERENCE: &str = "backup_retain";
+/// Preference recording when the last backup was written (RFC 3339).
+pub const BACKUP_LAST_RUN_PREFERENCE: &str = "backup_last_created_at";
+pub const BACKUP_CREATED_EVENT: &str = "backup-created";
+
+const DEFAULT_RETAIN: usize = 7;
+const SCHEDULE_TICK: Duration = Duration::from_secs(60 * 60);
+const MANUAL_PREFIX: &str = "sslboard-backup-";
+const AUTOMATIC_PREFIX: &str = "sslboard-backup-auto-";
+const EXTENSION: &str = ".sqlite";
+/// Tables a backup must contain to be restorable.
+const REQUIRED_TABLES: &[&str] = &[
+    "certificate_records",
+    "issuer_configs",
+    "dns_providers",
+    "preferences",
+    "secret_metadata",
+];
+
+fn read_preference(prefs: &PreferencesStore, name: &str) -> Option<String> {
+    match prefs.get(name) {
+        Ok(Some(pref)) => Some(pref.value.trim().to_string()).filter(|value| !value.is_empty()),
+        Ok(None) => None,
+        Err(err) => {
+            log::warn!("[backups] failed to read preference {name}: {err}");
+            None
+        }
+    }
+}
+
+fn scheduled_interval(prefs: &PreferencesStore) -> Option<chrono::Duration> {
+    match read_preference(prefs, BACKUP_SCHEDULE_PREFERENCE)?.as_str() {
+        "daily" => Some(chrono::Duration::days(1)),
+        "weekly" => Some(chrono::Duration::days(7)),
+        _ => None,
+    }
+}
+
+fn retain_count(prefs: &PreferencesStore) -> usize {
+    read_preference(prefs, BACKUP_RETAIN_PREFERENCE)
+        .and_then(|raw| raw.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_RETAIN)
+        .max(1)
+}
+
+/// Writes a backup into `folder`, falling back to the `backup_folder`
+/// preference. Automatic backups beyond the retention count are removed.
+pub fn create_backup(
+    db: &Db,
+    prefs: &PreferencesStore,
+    folder: Option<String>,
+    automatic: bool,
+) -> Result<BackupResult> {
+    let folder = folder
+        .map(|folder| folder.trim().to_string())
+        .filter(|folder| !folder.is_empty())
+        .or_else(|| read_preference(prefs, BACKUP_FOLDER_PREFERENCE))
+        .ok_or_else(|| anyhow!("No backup folder configured"))?;
+    let folder = PathBuf::from(folder);
+    fs::create_dir_all(&folder)
+        .with_context(|| format!("failed to create backup folder {}", folder.display()))?;
+
+    let now = Utc::now();
+    let prefix = if automatic { AUTOMATIC_PREFIX } else { MANUAL_PREFIX };
+    let path = folder.join(format!("{prefix}{}{EXTENSION}", now.format("%Y%m%d-%H%M%S")));
+    snapshot_database(db, &path)?;
+
+    let removed = if automatic {
+        prune_automatic_backups(&folder, retain_count(prefs))?
+    } else {
+        Vec::new()
+    };
+    prefs.set(BACKUP_LAST_RUN_PREFERENCE, &now.to_rfc3339())?;
+
+    Ok(BackupResult {
+        path: path.display().to_string(),
+        created_at: now,
+        removed,
+    })
+}
+
+fn snapshot_database(db: &Db, path: &Path) -> Result<()> {
+    if path.exists() {
+        return Err(anyhow!("backup already exists: {}", path.display()));
+    }
+    let target = path
+        .to_str()
+        .ok_or_else(|| anyhow!("backup path is not valid utf-8: {}", path.display()))?;
+    db.conn()?
+        .execute("VACUUM INTO ?1", [target])
+        .with_context(|| format!("failed to write backup {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+/// Deletes the oldest automatic backups so that `retain` remain.
+fn prune_automatic_backups(folder: &Path, retain: usize) -> Result<Vec<String>> {
+    let mut backups: Vec<PathBuf> = fs::read_dir(folder)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(AUTOMATIC_PREFIX) && name.ends_with(EXTENSION))
+        })
+        .collect();
+    // Timestamped names sort chronologically
+    backups.sort();
+    let excess = backups.len().saturating_sub(retain);
+    let mut removed = Vec::new();
+    for path in backups.into_iter().take(excess) {
+        match fs::remove_file(&path) {
+            Ok(()) => removed.push(path.display().to_string()),
+            Err(err) => log::warn!("[backups] failed to remove {}: {err}", path.display()),
+        }
+    }
+    Ok(removed)
+}
+
+/// Opens a backup read-only and checks that it is intact, that this build
+/// understands its schema, and that its vault secrets decrypt with the
+/// current master key.
+pub fn verify_backup(path: &Path, secrets: &SecretManager) -> Result<BackupVerification> {
+    inspect_backup(path, |payload| Ok(secrets.can_open_ciphertext(payload)?))
+}
+
+fn inspect_backup(
+    path: &Path,
+    decrypts: impl Fn(&[u8]) -> Result<bool>,
+) -> Result<BackupVerification> {
+    if !path.is_file() {
+        return Err(anyhow!("Backup not found: {}", path.display()));
+    }
+    let conn = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .with_context(|| format!("failed to open backup {}", path.display()))?;
+
+    let integrity: String = conn
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .context("file is not a readable SQLite database")?;
+    let mut report = BackupVerification {
+        path: path.display().to_string(),
+        valid: false,
+        schema_version: schema_version(&conn)?,
+        supported_schema_version: SCHEMA_VERSION,
+        integrity_ok: integrity == "ok",
+        certificates: 0,
+        secrets_in_backup: 0,
+        secrets_decryptable: 0,
+        secrets_external: 0,
+        problems: Vec::new(),
+    };
+    if !report.integrity_ok {
+        report.problems.push(format!("Integrity check failed: {integrity}"));
+    }
+    if report.schema_version > SCHEMA_VERSION {
+        report.problems.push(format!(
+            "Backup uses schema version {}, newer than this version supports ({SCHEMA_VERSION})",
+            report.schema_version
+        ));
+    }
+
+    let missing: Vec<&str> = REQUIRED_TABLES
+        .iter()
+        .copied()
+        .filter(|table| !table_exists(&conn, table).unwrap_or(false))
+        .collect();
+    if !missing.is_empty() {
+        report.problems.push(format!("Missing tables: {}", missing.join(", ")));
+        return Ok(report);
+    }
+
+    report.certificates =
+        conn.query_row("SELECT COUNT(*) FROM certificate_records", [], |row| row.get(0))?;
+    check_secrets(&conn, &mut report, decrypts)?;
+    report.valid = report.problems.is_empty();
+    Ok(report)
+}
+
+fn table_exists(conn: &Connection, table: &str) -> Result<bool> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+fn check_secrets(
+    conn: &Connection,
+    report: &mut BackupVerification,
+    decrypts: impl Fn(&[u8]) -> Result<bool>,
+) -> Result<()> {
+    let has_backend = conn
+        .prepare("SELECT storage_backend FROM secret_metadata LIMIT 0")
+        .is_ok();
+    let query = if has_backend {
+        "SELECT id, ciphertext, storage_backend FROM secret_metadata"
+    } else {
+        "SELECT id, ciphertext, 'vault' FROM secret_metadata"
+    };
+    let mut stmt = conn.prepare(query)?;
+    let mut rows = stmt.query([])?;
+    let mut undecryptable = Vec::new();
+    let mut missing = Vec::new();
+    let mut check_failed = None;
+    while let Some(row) = rows.next()? {
+        let id: String = row.get(0)?;
+        let ciphertext: Option<Vec<u8>> = row.get(1)?;
+        let backend: String = row.get(2)?;
+        if backend != "vault" {
+            report.secrets_external += 1;
+            continue;
+        }
+        let Some(ciphertext) = ciphertext else {
+            missing.push(id);
+            continue;
+        };
+        report.secrets_in_backup += 1;
+        if check_failed.is_some() {
+            continue;
+        }
+        match decrypts(&ciphertext) {
+            Ok(true) => report.secrets_decryptable += 1,
+            Ok(false) => undecryptable.push(id),
+            Err(err) => check_failed = Some(err),
+        }
+    }
+
+    if let Some(err) = check_failed {
+        report.problems.push(format!("Could not check vault secrets: {err}"));
+    }
+    if !undecryptable.is_empty() {
+        report.problems.push(format!(
+            "{} secret(s) do not decrypt with this device's master key: {}",
+            undecryptable.len(),
+            undecryptable.join(", ")
+        ));
+    }
+    if !missing.is_empty() {
+        report.problems.push(format!(
+            "{} secret(s) have no stored value: {}",
+            missing.len(),
+            missing.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Starts the background backup schedule. Checks hourly and writes a backup
+/// once the configured interval has elapsed; skipped in read-only mode.
+pub fn spawn_scheduled_backups(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let handle = app.clone();
+            let outcome = tauri::async_runtime::spawn_blocking(move || {
+                if handle.state::<ReadOnlyMode>().is_enabled() {
+                    return Ok(None);
+                }
+                let prefs = handle.state::<PreferencesStore>().inner().clone();
+                let Some(interval) = scheduled_interval(&prefs) else {
+                    return Ok(None);
+                };
+                let last_run = read_preference(&prefs, BACKUP_LAST_RUN_PREFERENCE)
+                    .and_then(|raw| DateTime::parse_from_rfc3339(&raw).ok())
+                    .map(|dt| dt.with_timezone(&Utc));
+                if last_run.is_some_and(|last| Utc::now() - last < interval)
+                    || !scheduler::should_run(BackgroundTask::Backups, &prefs)
+                {
+                    return Ok(None);
+                }
+                let db = handle.state::<Db>().inner().clone();
+                create_backup(&db, &prefs, None, true).map(Some)
+            })
+            .await;
+
+            match outcome {
+                Ok(Ok(Some(result))) => {
+                    log::info!("[backups] wrote scheduled backup to {}", result.path);
+                    if let Err(err) = app.emit(BACKUP_CREATED_EVENT, &result) {
+                        log::warn!("[backups] failed to emit backup event: {err}");
+                    }
+                }
+                Ok(Ok(None)) => {}
+                Ok(Err(err)) => log::warn!("[backups] scheduled backup failed: {err}"),
+                Err(err) => log::warn!("[backups] scheduled backup join error: {err}"),
+            }
+
+            scheduler::wait(BackgroundTask::Backups, SCHEDULE_TICK).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backups_verify_and_rotate() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("sslboard-backup-{}", uuid::Uuid::new_v4()));
+        let db = Db::initialize_with_path(&dir.join("data"))?;
+        let prefs = PreferencesStore::initialize(db.clone())?;
+        prefs.set(BACKUP_RETAIN_PREFERENCE, "2")?;
+        db.conn()?.execute(
+            "INSERT INTO secret_metadata (id, kind, label, created_at, ciphertext) \
+             VALUES ('sec_1', 'api_token', 'token', '2024-01-01T00:00:00Z', x'00')",
+            [],
+        )?;
+        let folder = dir.join("backups");
+
+        let result = create_backup(&db, &prefs, Some(folder.display().to_string()), false)?;
+        let report = inspect_backup(Path::new(&result.path), |_| Ok(true))?;
+        assert!(report.valid, "{:?}", report.problems);
+        assert_eq!(report.schema_version, SCHEMA_VERSION);
+        assert_eq!((report.secrets_in_backup, report.secrets_decryptable), (1, 1));
+
+        let wrong_key = inspect_backup(Path::new(&result.path), |_| Ok(false))?;
+        assert!(!wrong_key.valid);
+
+        let corrupt = folder.join("corrupt.sqlite");
+        fs::write(&corrupt, b"not a database")?;
+        assert!(inspect_backup(&corrupt, |_| Ok(true)).is_err());
+
+        for name in ["20240101-000000", "20240102-000000", "20240103-000000"] {
+            fs::write(folder.join(format!("{AUTOMATIC_PREFIX}{name}{EXTENSION}")), b"")?;
+        }
+        let removed = prune_automatic_backups(&folder, 2)?;
+        assert_eq!(removed.len(), 1);
+        assert!(removed[0].contains("20240101"));
+        assert!(Path::new(&result.path).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use tauri::{async_runtime::spawn_blocking, State};
+
+use crate::core::backups;
+use crate::core::errors::CommandError;
+use crate::core::metrics::CommandTimer;
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::types::{BackupResult, BackupVerification};
+use crate::secrets::manager::SecretManager;
+use crate::storage::{db::Db, preferences::PreferencesStore};
+
+/// Writes a backup of the database into `folder` (or the `backup_folder`
+/// preference) and returns where it was saved.
+#[tauri::command]
+pub async fn create_backup(
+    read_only: State<'_, ReadOnlyMode>,
+    db: State<'_, Db>,
+    prefs: State<'_, PreferencesStore>,
+    folder: Option<String>,
+) -> Result<BackupResult, CommandError> {
+    read_only.ensure_writable()?;
+    let db = db.inner().clone();
+    let prefs = prefs.inner().clone();
+    let timer = CommandTimer::start("create_backup");
+    let result = spawn_blocking(move || backups::create_backup(&db, &prefs, folder, false))
+        .await
+        .map_err(|err| format!("Backup join error: {err}"))?
+        .map_err(CommandError::from);
+    timer.finish(result)
+}
+
+/// Checks a backup file without restoring it.
+#[tauri::command]
+pub async fn verify_backup(
+    secrets: State<'_, SecretManager>,
+    path: String,
+) -> Result<BackupVerification, String> {
+    let secrets = secrets.inner().clone();
+    spawn_blocking(move || backups::verify_backup(&PathBuf::from(path), &secrets))
+        .await
+        .map_err(|err| format!("Backup verify join error: {err}"))?
+        .map_err(|err| err.to_string())
+}
@@ -0,0 +1,56 @@
+use anyhow::anyhow;
+use tauri::{async_runtime::spawn_blocking, State};
+
+use crate::core::config_check::{find_config_issues, repair_config_issue as apply_repair};
+use crate::core::errors::CommandError;
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::types::ConfigIssue;
+use crate::secrets::manager::SecretManager;
+use crate::storage::{dns::DnsConfigStore, inventory::InventoryStore, issuer::IssuerConfigStore};
+
+/// Lists stored configuration that refers to deleted secrets, with the
+/// repair offered for each finding.
+#[tauri::command]
+pub async fn get_config_issues(
+    dns_store: State<'_, DnsConfigStore>,
+    issuer_store: State<'_, IssuerConfigStore>,
+    inventory: State<'_, InventoryStore>,
+    secrets: State<'_, SecretManager>,
+) -> Result<Vec<ConfigIssue>, String> {
+    let dns_store = dns_store.inner().clone();
+    let issuer_store = issuer_store.inner().clone();
+    let inventory = inventory.inner().clone();
+    let secrets = secrets.inner().clone();
+    spawn_blocking(move || find_config_issues(&dns_store, &issuer_store, &inventory, &secrets))
+        .await
+        .map_err(|err| format!("Config check join error: {err}"))?
+        .map_err(|err| err.to_string())
+}
+
+/// Applies the repair of a finding. The check runs again first, so an issue
+/// fixed in the meantime is not repaired twice.
+#[tauri::command]
+pub async fn repair_config_issue(
+    read_only: State<'_, ReadOnlyMode>,
+    dns_store: State<'_, DnsConfigStore>,
+    issuer_store: State<'_, IssuerConfigStore>,
+    inventory: State<'_, InventoryStore>,
+    secrets: State<'_, SecretManager>,
+    issue_id: String,
+) -> Result<(), CommandError> {
+    read_only.ensure_writable()?;
+    let dns_store = dns_store.inner().clone();
+    let issuer_store = issuer_store.inner().clone();
+    let inventory = inventory.inner().clone();
+    let secrets = secrets.inner().clone();
+    spawn_blocking(move || -> Result<(), anyhow::Error> {
+        let issue = find_config_issues(&dns_store, &issuer_store, &inventory, &secrets)?
+            .into_iter()
+            .find(|issue| issue.id == issue_id)
+            .ok_or_else(|| anyhow!("configuration issue not found: {issue_id}"))?;
+        apply_repair(&issue, &issuer_store, &inventory, &secrets)
+    })
+    .await
+    .map_err(|err| format!("Config repair join error: {err}"))?
+    .map_err(CommandError::from)
+}
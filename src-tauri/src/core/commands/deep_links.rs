@@ -0,0 +1,9 @@
+use crate::core::deep_links::take_pending_action;
+use crate::core::types::DeepLinkAction;
+
+/// Returns the latest `sslboard://` action the UI has not handled yet, so a
+/// link that launched the app is picked up once the window is ready.
+#[tauri::command]
+pub async fn take_pending_deep_link() -> Result<Option<DeepLinkAction>, String> {
+    Ok(take_pending_action())
+}
@@ -0,0 +1,69 @@
+use tauri::{async_runtime::spawn_blocking, State};
+
+use crate::core::errors::CommandError;
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::types::{DemoModeStatus, SetDemoModeRequest};
+use crate::storage::{
+    db::Db,
+    demo::{self, DEMO_MODE_PREFERENCE},
+    inventory::InventoryStore,
+    preferences::PreferencesStore,
+};
+
+/// Reports whether demo mode is on and how much demo data is present.
+#[tauri::command]
+pub async fn get_demo_mode(
+    db: State<'_, Db>,
+    prefs: State<'_, PreferencesStore>,
+) -> Result<DemoModeStatus, String> {
+    let db = db.inner().clone();
+    let prefs = prefs.inner().clone();
+    spawn_blocking(move || -> Result<DemoModeStatus, anyhow::Error> {
+        let counts = demo::count_demo_data(&db)?;
+        Ok(DemoModeStatus {
+            enabled: demo::is_enabled(&prefs)?,
+            certificates: counts.certificates,
+            issuers: counts.issuers,
+            providers: counts.providers,
+        })
+    })
+    .await
+    .map_err(|err| format!("Demo mode status join error: {err}"))?
+    .map_err(|err: anyhow::Error| err.to_string())
+}
+
+/// Turns demo mode on (seeding demo data) or off (removing it).
+#[tauri::command]
+pub async fn set_demo_mode(
+    read_only: State<'_, ReadOnlyMode>,
+    db: State<'_, Db>,
+    inventory: State<'_, InventoryStore>,
+    prefs: State<'_, PreferencesStore>,
+    demo_req: SetDemoModeRequest,
+) -> Result<DemoModeStatus, CommandError> {
+    read_only.ensure_writable()?;
+    let db = db.inner().clone();
+    let inventory = inventory.inner().clone();
+    let prefs = prefs.inner().clone();
+    spawn_blocking(move || -> Result<DemoModeStatus, anyhow::Error> {
+        let counts = if demo_req.enabled {
+            demo::seed_demo_data(&db, &inventory)?
+        } else {
+            demo::clear_demo_data(&db)?;
+            demo::count_demo_data(&db)?
+        };
+        prefs.set(
+            DEMO_MODE_PREFERENCE,
+            if demo_req.enabled { "true" } else { "false" },
+        )?;
+        Ok(DemoModeStatus {
+            enabled: demo_req.enabled,
+            certificates: counts.certificates,
+            issuers: counts.issuers,
+            providers: counts.providers,
+        })
+    })
+    .await
+    .map_err(|err| format!("Set demo mode join error: {err}"))?
+    .map_err(CommandError::from)
+}
@@ -0,0 +1,276 @@
+use anyhow::anyhow;
+use chrono::Utc;
+use tauri::{async_runtime::spawn_blocking, State};
+
+use crate::core::confirmation_pin::ConfirmationPin;
+use crate::core::errors::CommandError;
+use crate::core::metrics::CommandTimer;
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::types::{
+    CreateDeployTargetRequest, DeployCertificateRequest, DeployResult, DeployTargetDto,
+    JobKind, UpdateDeployTargetRequest,
+};
+use crate::distribution::deploy::{
+    deploy_certificate as deploy_to_target, target_type_from_str, target_type_to_str,
+    validate_target_config,
+};
+use crate::distribution::key_policy::policy_from_preferences;
+use crate::secrets::{
+    manager::{SecretError, SecretManager},
+    types::SecretKind,
+};
+use crate::storage::{
+    deploy::{DeployTarget, DeployTargetStore},
+    dns::DnsConfigStore,
+    inventory::InventoryStore,
+    jobs::{new_job_id, JobStore},
+    preferences::PreferencesStore,
+    usages::CertificateUsageStore,
+};
+
+use super::dns_provider_helpers::validate_label;
+
+/// Lists deployment targets.
+#[tauri::command]
+pub async fn deploy_target_list(
+    store: State<'_, DeployTargetStore>,
+) -> Result<Vec<DeployTargetDto>, String> {
+    let store = store.inner().clone();
+    spawn_blocking(move || -> Result<Vec<DeployTargetDto>, anyhow::Error> {
+        store.list_targets()?.into_iter().map(target_to_dto).collect()
+    })
+    .await
+    .map_err(|err| format!("Deploy target list join error: {err}"))?
+    .map_err(|err: anyhow::Error| err.to_string())
+}
+
+/// Creates a deployment target; credentials are stored in the secret store.
+#[tauri::command]
+pub async fn deploy_target_create(
+    read_only: State<'_, ReadOnlyMode>,
+    store: State<'_, DeployTargetStore>,
+    secrets: State<'_, SecretManager>,
+    create_req: CreateDeployTargetRequest,
+) -> Result<DeployTargetDto, CommandError> {
+    read_only.ensure_writable()?;
+    let store = store.inner().clone();
+    let secrets = secrets.inner().clone();
+    spawn_blocking(move || -> Result<DeployTargetDto, anyhow::Error> {
+        let label = create_req.label.trim();
+        validate_label(label)?;
+        validate_target_config(create_req.target_type, &create_req.config)?;
+        let secret_ref = match &create_req.credentials {
+            Some(credentials) => Some(store_credentials(&secrets, label, credentials)?),
+            None => None,
+        };
+        let target = store.create_target(
+            target_type_to_str(create_req.target_type),
+            label,
+            &create_req.config,
+            secret_ref.as_deref(),
+        )?;
+        target_to_dto(target)
+    })
+    .await
+    .map_err(|err| format!("Deploy target create join error: {err}"))?
+    .map_err(CommandError::from)
+}
+
+/// Updates a deployment target, replacing credentials only when new ones are supplied.
+#[tauri::command]
+pub async fn deploy_target_update(
+    read_only: State<'_, ReadOnlyMode>,
+    store: State<'_, DeployTargetStore>,
+    secrets: State<'_, SecretManager>,
+    update_req: UpdateDeployTargetRequest,
+) -> Result<DeployTargetDto, CommandError> {
+    read_only.ensure_writable()?;
+    let store = store.inner().clone();
+    let secrets = secrets.inner().clone();
+    spawn_blocking(move || -> Result<DeployTargetDto, anyhow::Error> {
+        let label = update_req.label.trim();
+        validate_label(label)?;
+        let existing = store
+            .get_target(&update_req.target_id)?
+            .ok_or_else(|| anyhow!("deploy target not found: {}", update_req.target_id))?;
+        validate_target_config(target_type_from_str(&existing.target_type)?, &update_req.config)?;
+
+        let secret_ref = match &update_req.credentials {
+            Some(credentials) => {
+                let created = store_credentials(&secrets, label, credentials)?;
+                if let Some(previous) = existing.secret_ref.as_deref() {
+                    delete_credentials(&secrets, previous)?;
+                }
+                Some(created)
+            }
+            None => existing.secret_ref.clone(),
+        };
+        let target = store.update_target(
+            &existing.id,
+            label,
+            &update_req.config,
+            secret_ref.as_deref(),
+        )?;
+        target_to_dto(target)
+    })
+    .await
+    .map_err(|err| format!("Deploy target update join error: {err}"))?
+    .map_err(CommandError::from)
+}
+
+/// Deletes a deployment target and its stored credentials.
+/// Requires the confirmation PIN when one is set.
+#[tauri::command]
+pub async fn deploy_target_delete(
+    read_only: State<'_, ReadOnlyMode>,
+    pin: State<'_, ConfirmationPin>,
+    store: State<'_, DeployTargetStore>,
+    secrets: State<'_, SecretManager>,
+    usages: State<'_, CertificateUsageStore>,
+    target_id: String,
+    confirmation_pin: Option<String>,
+) -> Result<(), CommandError> {
+    read_only.ensure_writable()?;
+    pin.ensure_confirmed(confirmation_pin.as_deref())?;
+    let store = store.inner().clone();
+    let secrets = secrets.inner().clone();
+    let usages = usages.inner().clone();
+    spawn_blocking(move || -> Result<(), anyhow::Error> {
+        let removed = store.delete_target(&target_id)?;
+        usages.remove_for_target(&target_id)?;
+        if let Some(secret_ref) = removed.secret_ref.as_deref() {
+            delete_credentials(&secrets, secret_ref)?;
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|err| format!("Deploy target delete join error: {err}"))?
+    .map_err(CommandError::from)
+}
+
+/// Deploys a managed certificate to a configured target.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn deploy_certificate(
+    read_only: State<'_, ReadOnlyMode>,
+    store: State<'_, DeployTargetStore>,
+    inventory: State<'_, InventoryStore>,
+    secrets: State<'_, SecretManager>,
+    dns_store: State<'_, DnsConfigStore>,
+    jobs: State<'_, JobStore>,
+    usages: State<'_, CertificateUsageStore>,
+    prefs: State<'_, PreferencesStore>,
+    deploy_req: DeployCertificateRequest,
+) -> Result<DeployResult, CommandError> {
+    read_only.ensure_writable()?;
+    let store = store.inner().clone();
+    let inventory = inventory.inner().clone();
+    let secrets = secrets.inner().clone();
+    let dns_store = dns_store.inner().clone();
+    let jobs = jobs.inner().clone();
+    let usages = usages.inner().clone();
+    let prefs = prefs.inner().clone();
+    let timer = CommandTimer::start("deploy_certificate");
+    let result = spawn_blocking(move || -> Result<DeployResult, anyhow::Error> {
+        let job_id = new_job_id();
+        jobs.start(
+            &job_id,
+            JobKind::Deployment,
+            &serde_json::json!({
+                "target_id": deploy_req.target_id,
+                "certificate_id": deploy_req.certificate_id,
+            }),
+        )?;
+        let result = run_deployment(
+            &store,
+            &inventory,
+            &secrets,
+            &dns_store,
+            &usages,
+            &prefs,
+            &deploy_req.target_id,
+            &deploy_req.certificate_id,
+        );
+        jobs.finish_quietly(&job_id);
+        result
+    })
+    .await
+    .map_err(|err| format!("Deploy join error: {err}"))?
+    .map_err(CommandError::from);
+    timer.finish(result)
+}
+
+/// Deploys a certificate to a target by id and links the two so the
+/// certificate's usages list the target; shared with crash recovery.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run_deployment(
+    store: &DeployTargetStore,
+    inventory: &InventoryStore,
+    secrets: &SecretManager,
+    dns_store: &DnsConfigStore,
+    usages: &CertificateUsageStore,
+    prefs: &PreferencesStore,
+    target_id: &str,
+    certificate_id: &str,
+) -> Result<DeployResult, anyhow::Error> {
+    let target = store
+        .get_target(target_id)?
+        .ok_or_else(|| anyhow!("deploy target not found: {}", target_id))?;
+    let record = inventory
+        .get_certificate(certificate_id)?
+        .ok_or_else(|| anyhow!("Certificate not found: {}", certificate_id))?;
+    let locations =
+        deploy_to_target(&target, &record, secrets, dns_store, policy_from_preferences(prefs))?;
+    log::info!(
+        "[deploy] deployed {} to {} ({} location(s))",
+        record.id,
+        target.label,
+        locations.len()
+    );
+    if let Err(err) = usages.add(&record.id, None, Some(&target.id)) {
+        log::warn!("[deploy] failed to link {} to {}: {err}", record.id, target.id);
+    }
+    Ok(DeployResult {
+        target_id: target.id,
+        certificate_id: record.id,
+        locations,
+        deployed_at: Utc::now(),
+    })
+}
+
+fn target_to_dto(target: DeployTarget) -> Result<DeployTargetDto, anyhow::Error> {
+    Ok(DeployTargetDto {
+        target_type: target_type_from_str(&target.target_type)?,
+        has_credentials: target.secret_ref.is_some(),
+        id: target.id,
+        label: target.label,
+        config: target.config,
+        created_at: target.created_at,
+        updated_at: target.updated_at,
+    })
+}
+
+fn store_credentials(
+    secrets: &SecretManager,
+    label: &str,
+    credentials: &serde_json::Value,
+) -> Result<String, anyhow::Error> {
+    if !credentials.is_object() {
+        return Err(anyhow!("deploy target credentials must be an object"));
+    }
+    let record = secrets
+        .create_secret(
+            SecretKind::DeployTargetCredentials,
+            format!("Deploy target credentials: {label}"),
+            credentials.to_string(),
+        )
+        .map_err(|err| anyhow!(err.to_string()))?;
+    Ok(record.id)
+}
+
+fn delete_credentials(secrets: &SecretManager, secret_ref: &str) -> Result<(), anyhow::Error> {
+    match secrets.delete_secret(secret_ref) {
+        Ok(()) | Err(SecretError::NotFound(_)) => Ok(()),
+        Err(err) => Err(anyhow!(err.to_string())),
+    }
+}
@@ -0,0 +1,62 @@
+use tauri::{AppHandle, State};
+
+use crate::core::types::{AuthoritativePropagationResult, WatchDnsPropagationRequest};
+use crate::issuance::authoritative_dns::check_authoritative;
+use crate::issuance::propagation_presets::{PropagationWaits, MANUAL_PROVIDER_TYPE};
+use crate::issuance::propagation_resolvers::ResolverSet;
+use crate::issuance::propagation_watch;
+use crate::storage::preferences::PreferencesStore;
+
+/// Starts polling public DNS, or the zone's authoritative nameservers, for a
+/// TXT record in the background and returns the watch id. Progress arrives
+/// as `dns-propagation` events until the record is found, the provider's
+/// propagation wait runs out or the watch is cancelled.
+#[tauri::command]
+pub async fn watch_dns_propagation(
+    app: AppHandle,
+    prefs: State<'_, PreferencesStore>,
+    watch_req: WatchDnsPropagationRequest,
+) -> Result<String, String> {
+    let record_name = watch_req.record_name.trim().trim_end_matches('.').to_string();
+    if record_name.is_empty() || watch_req.value.trim().is_empty() {
+        return Err("A record name and value are required".to_string());
+    }
+    let provider_type = watch_req.provider_type.as_deref().unwrap_or(MANUAL_PROVIDER_TYPE);
+    let wait = PropagationWaits::from_preferences(prefs.inner()).for_type(provider_type);
+    Ok(propagation_watch::start(
+        app,
+        record_name,
+        watch_req.value.trim().to_string(),
+        wait,
+        watch_req.mode,
+        ResolverSet::from_preferences(prefs.inner()),
+    ))
+}
+
+/// Stops a propagation watch. Returns false when it had already ended.
+#[tauri::command]
+pub async fn cancel_dns_propagation_watch(watch_id: String) -> Result<bool, String> {
+    Ok(propagation_watch::cancel(&watch_id))
+}
+
+/// Queries each authoritative nameserver of the record's zone directly and
+/// reports what every one of them serves. With `compare_serials`, also
+/// reports whether every secondary has loaded the zone serial holding the
+/// record.
+#[tauri::command]
+pub async fn check_authoritative_propagation(
+    record_name: String,
+    value: String,
+    compare_serials: Option<bool>,
+) -> Result<AuthoritativePropagationResult, String> {
+    let record_name = record_name.trim().trim_end_matches('.').to_string();
+    if record_name.is_empty() || value.trim().is_empty() {
+        return Err("A record name and value are required".to_string());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        check_authoritative(&record_name, value.trim(), compare_serials.unwrap_or(false))
+            .map_err(|err| err.to_string())
+    })
+    .await
+    .map_err(|err| format!("authoritative propagation join error: {err}"))?
+}
@@ -1,87 +1,244 @@
 use anyhow::anyhow;
 use tauri::{async_runtime::spawn_blocking, State};
 
-use crate::core::types::{CreateDnsProviderRequest, DnsProviderDto, DnsProviderType};
+use crate::core::errors::{CommandError, ConflictError};
+use crate::core::mappers::{
+    provider_record_to_dto, provider_type_to_string, suffix_shadow_warnings,
+};
+use crate::core::messages::MessageCode;
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::types::{
+    ConflictEntity, ConflictResolution, CreateDnsProviderRequest, DnsProviderDto,
+    DnsProviderType, DomainSuffixVerification, DuplicateConflict,
+};
+use crate::issuance::dns_providers::verify_domain_suffix;
+use crate::issuance::propagation_presets::ProviderWait;
 use crate::secrets::{manager::SecretManager, types::SecretKind};
-use crate::storage::dns::DnsConfigStore;
+use crate::storage::dns::{DnsConfigStore, DnsProvider};
 
-use super::dns_provider_helpers::{validate_domain_suffixes, validate_label};
-use super::dns_provider_management::provider_record_to_dto;
+use super::dns_provider_helpers::{
+    acme_dns_registrations, validate_api_url, validate_domain_suffixes, validate_label,
+    validate_rfc2136_config, validate_route53_config, validate_script_config, TestRecordSettings,
+};
 
 /// Creates a DNS provider configuration.
 #[tauri::command]
 pub async fn dns_provider_create(
+    read_only: State<'_, ReadOnlyMode>,
     store: State<'_, DnsConfigStore>,
     secrets: State<'_, SecretManager>,
     create_req: CreateDnsProviderRequest,
-) -> Result<DnsProviderDto, String> {
+) -> Result<DnsProviderDto, CommandError> {
+    read_only.ensure_writable()?;
     let store = store.inner().clone();
     let secrets = secrets.inner().clone();
-    spawn_blocking(move || -> Result<DnsProviderDto, anyhow::Error> {
-        let label = create_req.label.trim();
-        validate_label(label)?;
-        let domain_suffixes = validate_domain_suffixes(&create_req.domain_suffixes)?;
-        let provider_type = provider_type_to_string(&create_req.provider_type);
-        let needs_token = !matches!(create_req.provider_type, DnsProviderType::Manual);
-        let mut secret_refs = Vec::new();
+    spawn_blocking(move || create_provider(&store, &secrets, create_req))
+        .await
+        .map_err(|err| format!("DNS provider create join error: {err}"))?
+        .map_err(CommandError::from)
+}
+
+/// Validates and saves a new provider with its secrets; the body of
+/// `dns_provider_create`, shared with the provider file import.
+pub(super) fn create_provider(
+    store: &DnsConfigStore,
+    secrets: &SecretManager,
+    create_req: CreateDnsProviderRequest,
+) -> Result<DnsProviderDto, anyhow::Error> {
+    let label = create_req.label.trim();
+    validate_label(label)?;
+    let domain_suffixes = validate_domain_suffixes(&create_req.domain_suffixes)?;
+    TestRecordSettings::from_config(create_req.config.as_ref())?;
+    ProviderWait::from_config(create_req.config.as_ref())?;
+    match create_req.provider_type {
+        DnsProviderType::AcmeDns | DnsProviderType::PowerDns => {
+            validate_api_url(create_req.config.as_ref())?
+        }
+        DnsProviderType::Rfc2136 => validate_rfc2136_config(
+            create_req.config.as_ref(),
+            create_req.api_token.as_deref(),
+        )?,
+        DnsProviderType::Script => validate_script_config(create_req.config.as_ref())?,
+        _ => {}
+    }
+    let provider_type = provider_type_to_string(&create_req.provider_type);
+
+    let overlaps = store.find_suffix_overlaps(&domain_suffixes)?;
+    if !overlaps.is_empty() {
+        match create_req.on_conflict {
+            None => return Err(suffix_conflict(&overlaps, &provider_type).into()),
+            Some(ConflictResolution::CreateAnyway) => {}
+            Some(ConflictResolution::Merge) => {
+                let merged = merge_into_existing(
+                    store,
+                    &overlaps,
+                    &provider_type,
+                    domain_suffixes.clone(),
+                )?;
+                let verification = create_req
+                    .verify_suffixes
+                    .then(|| verify_suffixes(&merged, &domain_suffixes, secrets));
+                let shadowing =
+                    store.find_suffix_shadowing(&merged.domain_suffixes, Some(&merged.id))?;
+                let mut dto = provider_record_to_dto(merged);
+                dto.verification = verification;
+                dto.suffix_warnings = suffix_shadow_warnings(&shadowing);
+                return Ok(dto);
+            }
+        }
+    }
+
+    let needs_token = !matches!(create_req.provider_type, DnsProviderType::Manual);
+    let mut secret_refs = Vec::new();
 
-        if needs_token {
-            match create_req.provider_type {
-                DnsProviderType::Route53 => {
-                    let access_key = create_req
-                        .route53_access_key
-                        .clone()
-                        .filter(|value| !value.trim().is_empty())
-                        .ok_or_else(|| anyhow!("Route 53 access key is required"))?;
-                    let secret_key = create_req
-                        .route53_secret_key
-                        .clone()
-                        .filter(|value| !value.trim().is_empty())
-                        .ok_or_else(|| anyhow!("Route 53 secret key is required"))?;
-                    let mut route53_refs = create_route53_credentials(
-                        &secrets,
-                        label,
-                        access_key,
-                        secret_key,
-                    )?;
-                    secret_refs.append(&mut route53_refs);
+    if needs_token {
+        match create_req.provider_type {
+            DnsProviderType::Route53 => {
+                let settings = validate_route53_config(create_req.config.as_ref())?;
+                let access_key = create_req
+                    .route53_access_key
+                    .clone()
+                    .filter(|value| !value.trim().is_empty());
+                let secret_key = create_req
+                    .route53_secret_key
+                    .clone()
+                    .filter(|value| !value.trim().is_empty());
+                match (access_key, secret_key) {
+                    (Some(_), _) | (_, Some(_)) if settings.profile.is_some() => {
+                        return Err(anyhow!(
+                            "Leave the access keys empty when using an AWS profile"
+                        ));
+                    }
+                    (Some(access_key), Some(secret_key)) => {
+                        let mut route53_refs = create_route53_credentials(
+                            secrets,
+                            label,
+                            access_key,
+                            secret_key,
+                        )?;
+                        secret_refs.append(&mut route53_refs);
+                    }
+                    // Credentials come from the default AWS chain
+                    (None, None) if settings.assumes_role || settings.profile.is_some() => {}
+                    (None, _) => return Err(anyhow!("Route 53 access key is required")),
+                    (_, None) => return Err(anyhow!("Route 53 secret key is required")),
                 }
-                _ => {
-                    let token = create_req
-                        .api_token
-                        .clone()
-                        .filter(|value| !value.trim().is_empty())
-                        .ok_or_else(|| anyhow!("API token is required for this provider"))?;
-                    let mut token_refs = create_api_token_credential(&secrets, label, token)?;
+            }
+            DnsProviderType::AcmeDns => {
+                // Registering waits until the suffixes are known not to
+                // conflict, so a merge never creates unused accounts.
+                let registrations = acme_dns_registrations(
+                    create_req.config.as_ref(),
+                    create_req.api_token.as_deref(),
+                    &domain_suffixes,
+                )?;
+                let mut token_refs =
+                    create_api_token_credential(secrets, label, registrations)?;
+                secret_refs.append(&mut token_refs);
+            }
+            DnsProviderType::Script => {
+                // Only webhooks that check a bearer token need one
+                if let Some(token) = create_req
+                    .api_token
+                    .clone()
+                    .filter(|value| !value.trim().is_empty())
+                {
+                    let mut token_refs = create_api_token_credential(secrets, label, token)?;
                     secret_refs.append(&mut token_refs);
                 }
             }
+            _ => {
+                let token = create_req
+                    .api_token
+                    .clone()
+                    .filter(|value| !value.trim().is_empty())
+                    .ok_or_else(|| anyhow::Error::from(MessageCode::ApiTokenRequired))?;
+                let mut token_refs = create_api_token_credential(secrets, label, token)?;
+                secret_refs.append(&mut token_refs);
+            }
         }
+    }
 
-        let record = store.create_provider(
-            provider_type,
-            label.to_string(),
-            domain_suffixes,
-            secret_refs,
-            create_req.config.clone(),
-        )?;
-        Ok(provider_record_to_dto(record))
-    })
-    .await
-    .map_err(|err| format!("DNS provider create join error: {err}"))?
-    .map_err(|err: anyhow::Error| err.to_string())
+    let record = store.create_provider(
+        provider_type,
+        label.to_string(),
+        domain_suffixes,
+        secret_refs,
+        create_req.config.clone(),
+    )?;
+    let verification = create_req
+        .verify_suffixes
+        .then(|| verify_suffixes(&record, &record.domain_suffixes, secrets));
+    let shadowing = store.find_suffix_shadowing(&record.domain_suffixes, Some(&record.id))?;
+    let mut dto = provider_record_to_dto(record);
+    dto.verification = verification;
+    dto.suffix_warnings = suffix_shadow_warnings(&shadowing);
+    Ok(dto)
 }
 
-fn provider_type_to_string(provider_type: &DnsProviderType) -> String {
-    match provider_type {
-        DnsProviderType::Cloudflare => "cloudflare".to_string(),
-        DnsProviderType::DigitalOcean => "digitalocean".to_string(),
-        DnsProviderType::Route53 => "route53".to_string(),
-        DnsProviderType::Manual => "manual".to_string(),
+/// Runs the NS delegation and zone access checks for `suffixes`. The provider
+/// is kept even when checks fail; the results tell the user what to fix.
+fn verify_suffixes(
+    provider: &DnsProvider,
+    suffixes: &[String],
+    secrets: &SecretManager,
+) -> Vec<DomainSuffixVerification> {
+    suffixes
+        .iter()
+        .map(|suffix| verify_domain_suffix(provider, suffix, secrets))
+        .collect()
+}
+
+fn suffix_conflict(overlaps: &[(DnsProvider, Vec<String>)], provider_type: &str) -> ConflictError {
+    let labels: Vec<&str> = overlaps
+        .iter()
+        .map(|(provider, _)| provider.label.as_str())
+        .collect();
+    ConflictError {
+        message: format!(
+            "domain suffixes are already handled by {}; merge into it or create anyway",
+            labels.join(", ")
+        ),
+        conflicts: overlaps
+            .iter()
+            .map(|(provider, shared)| DuplicateConflict {
+                entity: ConflictEntity::DnsProvider,
+                existing_id: provider.id.clone(),
+                existing_label: provider.label.clone(),
+                overlapping: shared.clone(),
+                can_merge: provider.provider_type == provider_type,
+            })
+            .collect(),
+    }
+}
+
+/// Adds the requested suffixes to the first overlapping provider of the same
+/// type. Its label, config, and credentials are kept as they are.
+fn merge_into_existing(
+    store: &DnsConfigStore,
+    overlaps: &[(DnsProvider, Vec<String>)],
+    provider_type: &str,
+    domain_suffixes: Vec<String>,
+) -> Result<DnsProvider, anyhow::Error> {
+    let (existing, _) = overlaps
+        .iter()
+        .find(|(provider, _)| provider.provider_type == provider_type)
+        .ok_or_else(|| anyhow!("no overlapping provider of the same type to merge into"))?;
+    let mut suffixes = existing.domain_suffixes.clone();
+    for suffix in domain_suffixes {
+        if !suffixes.contains(&suffix) {
+            suffixes.push(suffix);
+        }
     }
+    let config = existing
+        .config_json
+        .as_deref()
+        .map(serde_json::from_str)
+        .transpose()?;
+    store.update_provider(&existing.id, existing.label.clone(), suffixes, config)
 }
 
-fn create_route53_credentials(
+pub(super) fn create_route53_credentials(
     secrets: &SecretManager,
     label: &str,
     access_key: String,
@@ -108,7 +265,7 @@ fn create_route53_credentials(
     Ok(vec![access_key_record.id, secret_key_record.id])
 }
 
-fn create_api_token_credential(
+pub(super) fn create_api_token_credential(
     secrets: &SecretManager,
     label: &str,
     token: String,
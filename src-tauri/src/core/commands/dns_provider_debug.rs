@@ -0,0 +1,18 @@
+use crate::core::types::ProviderDebugEntry;
+use crate::issuance::dns_providers::{captured_calls, clear_captured_calls};
+
+/// Returns the provider's captured API calls, newest first. Calls are only
+/// captured while `debug_capture` is set in the provider config.
+#[tauri::command]
+pub async fn get_provider_debug_log(
+    provider_id: String,
+) -> Result<Vec<ProviderDebugEntry>, String> {
+    Ok(captured_calls(&provider_id))
+}
+
+/// Discards the provider's captured API calls.
+#[tauri::command]
+pub async fn clear_provider_debug_log(provider_id: String) -> Result<(), String> {
+    clear_captured_calls(&provider_id);
+    Ok(())
+}
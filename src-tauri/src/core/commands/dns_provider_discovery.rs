@@ -0,0 +1,51 @@
+use tauri::{async_runtime::spawn_blocking, State};
+
+use crate::core::metrics::CommandTimer;
+use crate::core::types::{DiscoverProviderZonesRequest, DiscoveredZoneDto};
+use crate::domain::{normalize_domain_for_display, normalize_domain_for_storage};
+use crate::issuance::dns_providers::adapter_for_provider;
+use crate::secrets::manager::SecretManager;
+use crate::storage::dns::DnsConfigStore;
+
+/// Lists every zone the provider's credentials can see, flagging the ones that
+/// are already configured so the UI can offer the rest for bulk adding.
+#[tauri::command]
+pub async fn discover_provider_zones(
+    store: State<'_, DnsConfigStore>,
+    secrets: State<'_, SecretManager>,
+    discover_req: DiscoverProviderZonesRequest,
+) -> Result<Vec<DiscoveredZoneDto>, String> {
+    let store = store.inner().clone();
+    let secrets = secrets.inner().clone();
+    let timer = CommandTimer::start("discover_provider_zones");
+    let result = spawn_blocking(move || -> Result<Vec<DiscoveredZoneDto>, anyhow::Error> {
+        let provider = store
+            .get_provider(&discover_req.provider_id)?
+            .ok_or_else(|| anyhow::anyhow!("provider not found: {}", discover_req.provider_id))?;
+        let others = store.list_providers()?;
+
+        let mut zones = adapter_for_provider(&provider, &secrets)
+            .list_zones()?
+            .into_iter()
+            .filter_map(|zone| normalize_domain_for_storage(&zone).ok())
+            .collect::<Vec<_>>();
+        zones.sort();
+        zones.dedup();
+
+        Ok(zones
+            .into_iter()
+            .map(|zone| DiscoveredZoneDto {
+                configured: provider.domain_suffixes.contains(&zone),
+                claimed_by: others
+                    .iter()
+                    .find(|other| other.id != provider.id && other.domain_suffixes.contains(&zone))
+                    .map(|other| other.label.clone()),
+                name: normalize_domain_for_display(&zone),
+            })
+            .collect())
+    })
+    .await
+    .map_err(|err| format!("DNS zone discovery join error: {err}"))?
+    .map_err(|err: anyhow::Error| err.to_string());
+    timer.finish(result)
+}
@@ -0,0 +1,461 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use anyhow::{anyhow, Context};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::Utc;
+use serde_json::{json, Value};
+use tauri::{async_runtime::spawn_blocking, State};
+
+use crate::core::errors::CommandError;
+use crate::core::mappers::provider_type_to_string;
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::types::{
+    DnsProviderType, EnvProviderImport, EnvProviderImportResult, ImportEnvProvidersRequest,
+};
+use crate::domain::normalize_domain_for_storage;
+use crate::issuance::dns_providers::adapter_for_provider;
+use crate::secrets::manager::SecretManager;
+use crate::storage::dns::{DnsConfigStore, DnsProvider};
+
+use super::dns_provider_creation::{create_api_token_credential, create_route53_credentials};
+use super::dns_provider_helpers::{
+    validate_api_url, validate_domain_suffixes, validate_rfc2136_config, validate_route53_config,
+};
+
+const ACME_SH_BASE64_START: &str = "__ACME_BASE64__START_";
+const ACME_SH_BASE64_END: &str = "__ACME_BASE64__END_";
+
+/// Creates DNS providers and their secrets from the credentials in a lego
+/// `.env` file or an acme.sh `account.conf`, and reports each provider found
+/// as imported or skipped.
+#[tauri::command]
+pub async fn import_dns_providers_from_env(
+    read_only: State<'_, ReadOnlyMode>,
+    store: State<'_, DnsConfigStore>,
+    secrets: State<'_, SecretManager>,
+    import_req: ImportEnvProvidersRequest,
+) -> Result<EnvProviderImportResult, CommandError> {
+    read_only.ensure_writable()?;
+    let store = store.inner().clone();
+    let secrets = secrets.inner().clone();
+    spawn_blocking(move || -> Result<EnvProviderImportResult, anyhow::Error> {
+        let raw = fs::read_to_string(&import_req.path)
+            .with_context(|| format!("failed to read {}", import_req.path))?;
+        let suffixes = import_req
+            .domain_suffixes
+            .as_deref()
+            .filter(|raw| !raw.trim().is_empty())
+            .map(validate_domain_suffixes)
+            .transpose()?;
+        let candidates = env_candidates(&parse_env_file(&raw));
+        if candidates.is_empty() {
+            return Err(anyhow!("No supported DNS provider credentials found in the file"));
+        }
+        let source = Path::new(&import_req.path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| import_req.path.clone());
+
+        let mut result = EnvProviderImportResult {
+            imported: Vec::new(),
+            skipped: Vec::new(),
+        };
+        for candidate in candidates {
+            let entry = import_candidate(&store, &secrets, candidate, &source, suffixes.as_deref());
+            if entry.provider_id.is_some() {
+                result.imported.push(entry);
+            } else {
+                result.skipped.push(entry);
+            }
+        }
+        log::info!(
+            "[dns] imported {} provider(s) from {source}, skipped {}",
+            result.imported.len(),
+            result.skipped.len()
+        );
+        Ok(result)
+    })
+    .await
+    .map_err(|err| format!("DNS provider env import join error: {err}"))?
+    .map_err(CommandError::from)
+}
+
+/// Credentials for one provider, read from environment variables.
+#[derive(Debug)]
+struct EnvCandidate {
+    provider_type: DnsProviderType,
+    source_keys: Vec<String>,
+    api_token: Option<String>,
+    /// Route 53 access key and secret key
+    access_keys: Option<(String, String)>,
+    config: Option<Value>,
+    /// Set when the variables name a provider in a form that cannot be imported
+    unsupported: Option<String>,
+}
+
+impl EnvCandidate {
+    fn new(provider_type: DnsProviderType) -> Self {
+        Self {
+            provider_type,
+            source_keys: Vec::new(),
+            api_token: None,
+            access_keys: None,
+            config: None,
+            unsupported: None,
+        }
+    }
+}
+
+/// Variables of a lego `.env` file or acme.sh `account.conf`: `KEY=value`
+/// lines, optionally prefixed by `export`, with quotes removed. acme.sh's
+/// `SAVED_` prefix is dropped and its base64-wrapped values are decoded.
+fn parse_env_file(raw: &str) -> BTreeMap<String, String> {
+    let mut vars = BTreeMap::new();
+    for line in raw.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line).trim_start();
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        let key = key.strip_prefix("SAVED_").unwrap_or(key);
+        let value = value.trim();
+        let value = value
+            .strip_prefix('\'')
+            .and_then(|value| value.strip_suffix('\''))
+            .or_else(|| value.strip_prefix('"').and_then(|value| value.strip_suffix('"')))
+            .unwrap_or(value);
+        let value = value
+            .strip_prefix(ACME_SH_BASE64_START)
+            .and_then(|encoded| encoded.strip_suffix(ACME_SH_BASE64_END))
+            .and_then(|encoded| STANDARD.decode(encoded).ok())
+            .and_then(|decoded| String::from_utf8(decoded).ok())
+            .unwrap_or_else(|| value.to_string());
+        if !key.is_empty() && !value.is_empty() {
+            vars.insert(key.to_string(), value);
+        }
+    }
+    vars
+}
+
+/// Providers whose credentials appear in `vars`, using the variable names
+/// of lego and acme.sh.
+fn env_candidates(vars: &BTreeMap<String, String>) -> Vec<EnvCandidate> {
+    let find = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| vars.get(*key).map(|value| (key.to_string(), value.clone())))
+    };
+    let mut candidates = Vec::new();
+
+    let cf_token = find(&[
+        "CF_DNS_API_TOKEN",
+        "CLOUDFLARE_DNS_API_TOKEN",
+        "CF_API_TOKEN",
+        "CF_Token",
+    ]);
+    let cf_global_key = find(&["CF_API_KEY", "CLOUDFLARE_API_KEY", "CF_Key"]);
+    if let Some((key, token)) = cf_token {
+        let mut candidate = EnvCandidate::new(DnsProviderType::Cloudflare);
+        candidate.source_keys.push(key);
+        candidate.api_token = Some(token);
+        candidates.push(candidate);
+    } else if let Some((key, _)) = cf_global_key {
+        let mut candidate = EnvCandidate::new(DnsProviderType::Cloudflare);
+        candidate.source_keys.push(key);
+        candidate.unsupported = Some(
+            "Cloudflare global API keys are not supported; create a scoped API token".to_string(),
+        );
+        candidates.push(candidate);
+    }
+
+    if let Some((key, token)) = find(&["DO_AUTH_TOKEN", "DO_API_KEY"]) {
+        let mut candidate = EnvCandidate::new(DnsProviderType::DigitalOcean);
+        candidate.source_keys.push(key);
+        candidate.api_token = Some(token);
+        candidates.push(candidate);
+    }
+
+    if let Some((key, token)) = find(&["DESEC_TOKEN", "DEDYN_TOKEN"]) {
+        let mut candidate = EnvCandidate::new(DnsProviderType::Desec);
+        candidate.source_keys.push(key);
+        candidate.api_token = Some(token);
+        candidates.push(candidate);
+    }
+
+    let access_key = find(&["AWS_ACCESS_KEY_ID"]);
+    let secret_key = find(&["AWS_SECRET_ACCESS_KEY"]);
+    let profile = find(&["AWS_PROFILE"]);
+    let role_arn = find(&["AWS_ASSUME_ROLE_ARN"]);
+    if access_key.is_some() || secret_key.is_some() || profile.is_some() || role_arn.is_some() {
+        let mut candidate = EnvCandidate::new(DnsProviderType::Route53);
+        let mut config = serde_json::Map::new();
+        for (found, field) in [(&profile, "profile"), (&role_arn, "role_arn")] {
+            if let Some((key, value)) = found {
+                candidate.source_keys.push(key.clone());
+                config.insert(field.to_string(), json!(value));
+            }
+        }
+        match (access_key, secret_key) {
+            (Some((access_name, access)), Some((secret_name, secret))) if profile.is_none() => {
+                candidate.source_keys.extend([access_name, secret_name]);
+                candidate.access_keys = Some((access, secret));
+            }
+            (None, None) if !config.is_empty() => {}
+            (access, secret) => {
+                candidate.source_keys.extend(access.into_iter().chain(secret).map(|(key, _)| key));
+                candidate.unsupported = Some(if profile.is_some() {
+                    "Set either AWS_PROFILE or the access keys, not both".to_string()
+                } else {
+                    "Both AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY are required".to_string()
+                });
+            }
+        }
+        candidate.config = (!config.is_empty()).then_some(Value::Object(config));
+        candidates.push(candidate);
+    }
+
+    let pdns_url = find(&["PDNS_API_URL", "PDNS_Url"]);
+    let pdns_key = find(&["PDNS_API_KEY", "PDNS_Token"]);
+    if pdns_url.is_some() || pdns_key.is_some() {
+        let mut candidate = EnvCandidate::new(DnsProviderType::PowerDns);
+        let mut config = serde_json::Map::new();
+        if let Some((key, url)) = pdns_url {
+            candidate.source_keys.push(key);
+            config.insert("api_url".to_string(), json!(url));
+        }
+        if let Some((key, server)) = find(&["PDNS_SERVER_NAME", "PDNS_ServerId"]) {
+            candidate.source_keys.push(key);
+            config.insert("server_id".to_string(), json!(server));
+        }
+        if let Some((key, token)) = pdns_key {
+            candidate.source_keys.push(key);
+            candidate.api_token = Some(token);
+        }
+        candidate.config = Some(Value::Object(config));
+        candidates.push(candidate);
+    }
+
+    if let Some((key, server)) = find(&["RFC2136_NAMESERVER"]) {
+        let mut candidate = EnvCandidate::new(DnsProviderType::Rfc2136);
+        candidate.source_keys.push(key);
+        let mut config = serde_json::Map::new();
+        config.insert("server".to_string(), json!(server));
+        for (names, field) in [
+            (&["RFC2136_TSIG_KEY"][..], "tsig_key_name"),
+            (&["RFC2136_TSIG_ALGORITHM"][..], "tsig_algorithm"),
+        ] {
+            if let Some((key, value)) = find(names) {
+                candidate.source_keys.push(key);
+                config.insert(field.to_string(), json!(value));
+            }
+        }
+        if let Some((key, secret)) = find(&["RFC2136_TSIG_SECRET"]) {
+            candidate.source_keys.push(key);
+            candidate.api_token = Some(secret);
+        }
+        candidate.config = Some(Value::Object(config));
+        candidates.push(candidate);
+    } else if let Some((key, _)) = find(&["NSUPDATE_SERVER"]) {
+        let mut candidate = EnvCandidate::new(DnsProviderType::Rfc2136);
+        candidate.source_keys.push(key);
+        candidate.unsupported = Some(
+            "acme.sh nsupdate keeps its TSIG key in a separate file; add this provider by hand"
+                .to_string(),
+        );
+        candidates.push(candidate);
+    }
+
+    candidates
+}
+
+fn type_label(provider_type: &DnsProviderType) -> &'static str {
+    match provider_type {
+        DnsProviderType::AcmeDns => "acme-dns",
+        DnsProviderType::Cloudflare => "Cloudflare",
+        DnsProviderType::DigitalOcean => "DigitalOcean",
+        DnsProviderType::Desec => "deSEC",
+        DnsProviderType::PowerDns => "PowerDNS",
+        DnsProviderType::Rfc2136 => "RFC 2136",
+        DnsProviderType::Route53 => "Route 53",
+        DnsProviderType::Script => "Script",
+        DnsProviderType::Manual => "Manual",
+    }
+}
+
+/// Creates one provider, removing its new secrets again when it is skipped.
+fn import_candidate(
+    store: &DnsConfigStore,
+    secrets: &SecretManager,
+    candidate: EnvCandidate,
+    source: &str,
+    suffixes: Option<&[String]>,
+) -> EnvProviderImport {
+    let label = format!("{} ({source})", type_label(&candidate.provider_type));
+    let mut entry = EnvProviderImport {
+        provider_type: candidate.provider_type.clone(),
+        label: label.clone(),
+        source_keys: candidate.source_keys.clone(),
+        provider_id: None,
+        domain_suffixes: Vec::new(),
+        skipped_reason: candidate.unsupported.clone(),
+    };
+    if entry.skipped_reason.is_some() {
+        return entry;
+    }
+
+    let mut secret_refs = Vec::new();
+    let outcome = create_candidate(store, secrets, &candidate, &label, suffixes, &mut secret_refs);
+    match outcome {
+        Ok(provider) => {
+            entry.provider_id = Some(provider.id);
+            entry.domain_suffixes = provider.domain_suffixes;
+        }
+        Err(err) => {
+            for secret_ref in &secret_refs {
+                if let Err(err) = secrets.delete_secret(secret_ref) {
+                    log::warn!("[dns] failed to remove unused secret {secret_ref}: {err}");
+                }
+            }
+            entry.skipped_reason = Some(err.to_string());
+        }
+    }
+    entry
+}
+
+fn create_candidate(
+    store: &DnsConfigStore,
+    secrets: &SecretManager,
+    candidate: &EnvCandidate,
+    label: &str,
+    suffixes: Option<&[String]>,
+    secret_refs: &mut Vec<String>,
+) -> Result<DnsProvider, anyhow::Error> {
+    let config = candidate.config.as_ref();
+    match candidate.provider_type {
+        DnsProviderType::PowerDns => validate_api_url(config)?,
+        DnsProviderType::Rfc2136 => {
+            validate_rfc2136_config(config, candidate.api_token.as_deref())?
+        }
+        DnsProviderType::Route53 => {
+            validate_route53_config(config)?;
+        }
+        _ => {}
+    }
+
+    if let Some((access_key, secret_key)) = candidate.access_keys.clone() {
+        secret_refs.extend(create_route53_credentials(secrets, label, access_key, secret_key)?);
+    } else if let Some(token) = candidate.api_token.clone() {
+        secret_refs.extend(create_api_token_credential(secrets, label, token)?);
+    } else if !matches!(candidate.provider_type, DnsProviderType::Route53) {
+        return Err(anyhow!("No API token found for this provider"));
+    }
+
+    let provider_type = provider_type_to_string(&candidate.provider_type);
+    let suffixes = match suffixes {
+        Some(suffixes) => suffixes.to_vec(),
+        None => unclaimed_zones(store, secrets, &provider_type, label, secret_refs, config)?,
+    };
+    if suffixes.is_empty() {
+        return Err(anyhow!("Every zone these credentials can see already has a provider"));
+    }
+    let overlaps = store.find_suffix_overlaps(&suffixes)?;
+    if !overlaps.is_empty() {
+        let labels: Vec<&str> =
+            overlaps.iter().map(|(provider, _)| provider.label.as_str()).collect();
+        return Err(anyhow!("Domain suffixes are already handled by {}", labels.join(", ")));
+    }
+    store.create_provider(
+        provider_type,
+        label.to_string(),
+        suffixes,
+        secret_refs.clone(),
+        candidate.config.clone(),
+    )
+}
+
+/// Zones the credentials can see that no configured provider claims yet.
+fn unclaimed_zones(
+    store: &DnsConfigStore,
+    secrets: &SecretManager,
+    provider_type: &str,
+    label: &str,
+    secret_refs: &[String],
+    config: Option<&Value>,
+) -> Result<Vec<String>, anyhow::Error> {
+    let now = Utc::now();
+    let probe = DnsProvider {
+        id: String::new(),
+        provider_type: provider_type.to_string(),
+        label: label.to_string(),
+        domain_suffixes: Vec::new(),
+        secret_refs: secret_refs.to_vec(),
+        config_json: config.map(Value::to_string),
+        priority: 0,
+        created_at: now,
+        updated_at: now,
+    };
+    let claimed: Vec<String> = store
+        .list_providers()?
+        .into_iter()
+        .flat_map(|provider| provider.domain_suffixes)
+        .collect();
+    let mut zones: Vec<String> = adapter_for_provider(&probe, secrets)
+        .list_zones()
+        .context("Could not list the zones for these credentials")?
+        .iter()
+        .filter_map(|zone| normalize_domain_for_storage(zone).ok())
+        .filter(|zone| !claimed.contains(zone))
+        .collect();
+    zones.sort();
+    zones.dedup();
+    Ok(zones)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_lego_and_acme_sh_credentials() {
+        let acme_sh = "\
+            SAVED_CF_Token='__ACME_BASE64__START_Y2YtdG9rZW4=__ACME_BASE64__END_'\n\
+            SAVED_DO_API_KEY=\"do-token\"\n\
+            # comment\n\
+            SAVED_PDNS_Url='https://pdns.example.com:8081'\n\
+            SAVED_PDNS_Token='pdns-key'\n\
+            SAVED_NSUPDATE_SERVER='ns1.example.com'\n";
+        let vars = parse_env_file(acme_sh);
+        assert_eq!(vars["CF_Token"], "cf-token");
+        let candidates = env_candidates(&vars);
+        let types: Vec<String> = candidates
+            .iter()
+            .map(|candidate| provider_type_to_string(&candidate.provider_type))
+            .collect();
+        assert_eq!(types, ["cloudflare", "digitalocean", "powerdns", "rfc2136"]);
+        assert_eq!(candidates[0].api_token.as_deref(), Some("cf-token"));
+        assert_eq!(candidates[1].api_token.as_deref(), Some("do-token"));
+        let pdns_config = candidates[2].config.as_ref().unwrap();
+        assert_eq!(pdns_config["api_url"], "https://pdns.example.com:8081");
+        assert!(candidates[3].unsupported.is_some());
+
+        let lego = "\
+            export AWS_ACCESS_KEY_ID=AKIAEXAMPLE\n\
+            export AWS_SECRET_ACCESS_KEY=secret\n\
+            CF_API_KEY=global\n";
+        let candidates = env_candidates(&parse_env_file(lego));
+        assert!(candidates[0].unsupported.is_some());
+        let route53 = &candidates[1];
+        assert_eq!(
+            route53.access_keys,
+            Some(("AKIAEXAMPLE".to_string(), "secret".to_string()))
+        );
+        assert!(route53.unsupported.is_none() && route53.config.is_none());
+
+        let half = env_candidates(&parse_env_file("AWS_ACCESS_KEY_ID=AKIAEXAMPLE"));
+        assert!(half[0].unsupported.is_some());
+        assert!(env_candidates(&parse_env_file("UNRELATED=1")).is_empty());
+    }
+}
@@ -0,0 +1,23 @@
+use tauri::{async_runtime::spawn_blocking, State};
+
+use crate::core::types::DnsProviderHealthCheck;
+use crate::storage::provider_health::ProviderHealthStore;
+
+const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+/// Returns the scheduled health checks, newest first, for one provider or
+/// all of them.
+#[tauri::command]
+pub async fn dns_provider_health_history(
+    store: State<'_, ProviderHealthStore>,
+    provider_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<DnsProviderHealthCheck>, String> {
+    let store = store.inner().clone();
+    spawn_blocking(move || {
+        store.history(provider_id.as_deref(), limit.unwrap_or(DEFAULT_HISTORY_LIMIT))
+    })
+    .await
+    .map_err(|err| format!("Provider health join error: {err}"))?
+    .map_err(|err| err.to_string())
+}
@@ -1,10 +1,18 @@
 use anyhow::anyhow;
+use serde_json::{json, Value};
 
+use crate::core::messages::MessageCode;
+use crate::core::types::{LeftoverRecordKind, LeftoverTxtRecord};
+use crate::issuance::dns_providers::{
+    decode_tsig_secret, parse_acme_dns_registrations, profile_from_settings,
+    register_acme_dns_account, AssumeRole, TsigAlgorithm, TxtRecord,
+};
 use crate::storage::dns::parse_domain_suffixes;
+use crate::storage::preferences::PreferencesStore;
 
 pub(crate) fn validate_label(label: &str) -> Result<(), anyhow::Error> {
     if label.trim().is_empty() {
-        return Err(anyhow!("provider label is required"));
+        return Err(MessageCode::ProviderLabelRequired.into());
     }
     Ok(())
 }
@@ -12,7 +20,399 @@ pub(crate) fn validate_label(label: &str) -> Result<(), anyhow::Error> {
 pub(crate) fn validate_domain_suffixes(raw: &str) -> Result<Vec<String>, anyhow::Error> {
     let domain_suffixes = parse_domain_suffixes(raw)?;
     if domain_suffixes.is_empty() {
-        return Err(anyhow!("at least one domain suffix is required"));
+        return Err(MessageCode::DomainSuffixRequired.into());
     }
     Ok(domain_suffixes)
 }
+
+/// Checks the `api_url` of self-hosted providers is an http(s) URL.
+pub(crate) fn validate_api_url(config: Option<&Value>) -> Result<(), anyhow::Error> {
+    let api_url = config
+        .and_then(|value| value.get("api_url"))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .ok_or_else(|| anyhow!("API URL is required"))?;
+    let parsed = reqwest::Url::parse(api_url).map_err(|err| anyhow!("invalid API URL: {err}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(anyhow!("API URL must use http or https"));
+    }
+    Ok(())
+}
+
+/// Checks a script provider has either a `command` or an http(s)
+/// `webhook_url`, but not both.
+pub(crate) fn validate_script_config(config: Option<&Value>) -> Result<(), anyhow::Error> {
+    let text = |key: &str| {
+        config
+            .and_then(|value| value.get(key))
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+    match (text("command"), text("webhook_url")) {
+        (Some(_), None) => Ok(()),
+        (None, Some(url)) => {
+            let parsed =
+                reqwest::Url::parse(url).map_err(|err| anyhow!("invalid webhook URL: {err}"))?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(anyhow!("webhook URL must use http or https"));
+            }
+            Ok(())
+        }
+        (Some(_), Some(_)) => Err(anyhow!("set either a command or a webhook URL, not both")),
+        (None, None) => Err(anyhow!("a command or a webhook URL is required")),
+    }
+}
+
+/// Checks the RFC 2136 settings (`server`, `tsig_key_name`, optional
+/// `tsig_algorithm`) and, when given, that the TSIG secret is base64.
+pub(crate) fn validate_rfc2136_config(
+    config: Option<&Value>,
+    tsig_secret: Option<&str>,
+) -> Result<(), anyhow::Error> {
+    let text = |key: &str| {
+        config
+            .and_then(|value| value.get(key))
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+    if text("server").is_none() {
+        return Err(anyhow!("DNS server is required"));
+    }
+    if text("tsig_key_name").is_none() {
+        return Err(anyhow!("TSIG key name is required"));
+    }
+    TsigAlgorithm::from_name(text("tsig_algorithm"))?;
+    if let Some(secret) = tsig_secret.filter(|secret| !secret.trim().is_empty()) {
+        decode_tsig_secret(secret)?;
+    }
+    Ok(())
+}
+
+/// Credential settings of a Route 53 provider's `config_json`.
+pub(crate) struct Route53Settings {
+    pub(crate) assumes_role: bool,
+    /// Named AWS profile used instead of stored access keys
+    pub(crate) profile: Option<String>,
+}
+
+/// Checks the optional `role_arn`, `external_id` and `profile` of a Route 53
+/// provider.
+pub(crate) fn validate_route53_config(
+    config: Option<&Value>,
+) -> Result<Route53Settings, anyhow::Error> {
+    let text = |key: &str| config.and_then(|value| value.get(key)).and_then(Value::as_str);
+    Ok(Route53Settings {
+        assumes_role: AssumeRole::from_settings(text("role_arn"), text("external_id"))?.is_some(),
+        profile: profile_from_settings(text("profile"))?,
+    })
+}
+
+/// Returns the acme-dns registrations to store for a new provider: the JSON
+/// supplied by the user (keyed by domain, as acme-dns clients save it), or a
+/// fresh registration per domain suffix when none is given.
+pub(crate) fn acme_dns_registrations(
+    config: Option<&Value>,
+    registrations: Option<&str>,
+    domain_suffixes: &[String],
+) -> Result<String, anyhow::Error> {
+    validate_api_url(config)?;
+    if let Some(raw) = registrations.filter(|raw| !raw.trim().is_empty()) {
+        parse_acme_dns_registrations(raw)?;
+        return Ok(raw.trim().to_string());
+    }
+    let api_url = config
+        .and_then(|value| value.get("api_url"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let mut registered = serde_json::Map::new();
+    for suffix in domain_suffixes {
+        let registration = register_acme_dns_account(api_url)?;
+        registered.insert(suffix.clone(), serde_json::to_value(registration)?);
+    }
+    Ok(Value::Object(registered).to_string())
+}
+
+/// Preference holding the test record prefix used when a provider sets none.
+pub const TEST_RECORD_PREFIX_PREFERENCE: &str = "dns_test_record_prefix";
+/// Preference holding the test record TTL used when a provider sets none.
+pub const TEST_RECORD_TTL_PREFERENCE: &str = "dns_test_record_ttl";
+
+const DEFAULT_TEST_RECORD_PREFIX: &str = "_sslboard-test";
+const MIN_TEST_RECORD_TTL: u32 = 30;
+const MAX_TEST_RECORD_TTL: u32 = 86_400;
+
+/// Where and how `dns_provider_test` writes its temporary record, read from
+/// the provider config (`test_record_prefix`, `test_record_subdomain`,
+/// `test_record_ttl`). Some zones only allow automation under specific names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TestRecordSettings {
+    pub prefix: String,
+    pub subdomain: Option<String>,
+    pub ttl: Option<u32>,
+}
+
+impl Default for TestRecordSettings {
+    fn default() -> Self {
+        Self {
+            prefix: DEFAULT_TEST_RECORD_PREFIX.to_string(),
+            subdomain: None,
+            ttl: None,
+        }
+    }
+}
+
+impl TestRecordSettings {
+    /// App-wide defaults from the test record preferences; invalid values
+    /// are logged and ignored.
+    pub(crate) fn from_preferences(prefs: &PreferencesStore) -> Self {
+        let read = |name: &str| match prefs.get(name) {
+            Ok(Some(pref)) => Some(pref.value.trim().to_string()).filter(|v| !v.is_empty()),
+            Ok(None) => None,
+            Err(err) => {
+                log::warn!("[dns-test] failed to read {name} preference: {err}");
+                None
+            }
+        };
+        let mut defaults = Self::default();
+        if let Some(prefix) = read(TEST_RECORD_PREFIX_PREFERENCE) {
+            match Self::from_config(Some(&json!({ "test_record_prefix": prefix }))) {
+                Ok(settings) => defaults.prefix = settings.prefix,
+                Err(err) => {
+                    log::warn!("[dns-test] ignoring {TEST_RECORD_PREFIX_PREFERENCE}: {err}")
+                }
+            }
+        }
+        if let Some(ttl) = read(TEST_RECORD_TTL_PREFERENCE) {
+            let parsed = ttl.parse::<u64>().map_err(anyhow::Error::from).and_then(|ttl| {
+                Self::from_config(Some(&json!({ "test_record_ttl": ttl })))
+            });
+            match parsed {
+                Ok(settings) => defaults.ttl = settings.ttl,
+                Err(err) => {
+                    log::warn!("[dns-test] ignoring {TEST_RECORD_TTL_PREFERENCE}: {err}")
+                }
+            }
+        }
+        defaults
+    }
+
+    pub(crate) fn from_config(config: Option<&Value>) -> Result<Self, anyhow::Error> {
+        Self::from_config_with(config, &Self::default())
+    }
+
+    /// Like [`Self::from_config`], falling back to `defaults` for the prefix
+    /// and TTL the provider leaves unset.
+    pub(crate) fn from_config_with(
+        config: Option<&Value>,
+        defaults: &Self,
+    ) -> Result<Self, anyhow::Error> {
+        let field = |key: &str| config.and_then(|value| value.get(key)).filter(|v| !v.is_null());
+        let text = |key: &str| -> Result<Option<String>, anyhow::Error> {
+            match field(key) {
+                None => Ok(None),
+                Some(value) => value
+                    .as_str()
+                    .map(|raw| raw.trim().trim_matches('.').to_ascii_lowercase())
+                    .filter(|raw| !raw.is_empty())
+                    .map(Some)
+                    .ok_or_else(|| anyhow!("{key} must be a non-empty string")),
+            }
+        };
+
+        let prefix = text("test_record_prefix")?.unwrap_or_else(|| defaults.prefix.clone());
+        if prefix.contains('.') || !is_record_label(&prefix) {
+            return Err(anyhow!(
+                "test record prefix must be a single DNS label (letters, digits, '-' or '_')"
+            ));
+        }
+        let subdomain = text("test_record_subdomain")?;
+        if let Some(subdomain) = &subdomain
+            && !subdomain.split('.').all(is_record_label)
+        {
+            return Err(anyhow!("test record subdomain is not a valid DNS name"));
+        }
+        let ttl = match field("test_record_ttl") {
+            None => defaults.ttl,
+            Some(value) => {
+                let ttl = value
+                    .as_u64()
+                    .and_then(|ttl| u32::try_from(ttl).ok())
+                    .filter(|ttl| (MIN_TEST_RECORD_TTL..=MAX_TEST_RECORD_TTL).contains(ttl))
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "test record TTL must be between {MIN_TEST_RECORD_TTL} and {MAX_TEST_RECORD_TTL} seconds"
+                        )
+                    })?;
+                Some(ttl)
+            }
+        };
+        Ok(Self {
+            prefix,
+            subdomain,
+            ttl,
+        })
+    }
+
+    /// Full record name for a test run, e.g. `_sslboard-test-ab12.ops.example.com`.
+    pub(crate) fn record_name(&self, token: &str, suffix: &str) -> String {
+        match &self.subdomain {
+            Some(subdomain) => format!("{}-{token}.{subdomain}.{suffix}", self.prefix),
+            None => format!("{}-{token}.{suffix}", self.prefix),
+        }
+    }
+}
+
+/// Value prefix of every record `dns_provider_test` writes outside acme-dns.
+pub(crate) const TEST_RECORD_VALUE_PREFIX: &str = "sslboard-test-";
+
+/// Picks the provider-test and stale challenge records out of a zone listing,
+/// grouped by name. `pending_domains` are the names of issuances in progress,
+/// whose `_acme-challenge` records are still needed.
+pub(crate) fn leftover_records(
+    records: Vec<TxtRecord>,
+    settings: &TestRecordSettings,
+    pending_domains: &[String],
+) -> Vec<LeftoverTxtRecord> {
+    let test_label = format!("{}-", settings.prefix);
+    let default_label = format!("{DEFAULT_TEST_RECORD_PREFIX}-");
+    let mut leftovers: Vec<LeftoverTxtRecord> = Vec::new();
+    for record in records {
+        let (first_label, rest) = record.name.split_once('.').unwrap_or((&record.name, ""));
+        let kind = if first_label.starts_with(&test_label)
+            || first_label.starts_with(&default_label)
+            || record.value.starts_with(TEST_RECORD_VALUE_PREFIX)
+        {
+            LeftoverRecordKind::ProviderTest
+        } else if first_label == "_acme-challenge"
+            && !pending_domains
+                .iter()
+                .any(|domain| domain.trim_start_matches("*.").eq_ignore_ascii_case(rest))
+        {
+            LeftoverRecordKind::StaleChallenge
+        } else {
+            continue;
+        };
+        match leftovers.iter_mut().find(|leftover| leftover.record_name == record.name) {
+            Some(leftover) => leftover.values.push(record.value),
+            None => leftovers.push(LeftoverTxtRecord {
+                record_name: record.name,
+                values: vec![record.value],
+                kind,
+            }),
+        }
+    }
+    leftovers
+}
+
+fn is_record_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.len() <= 63
+        && label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_test_record_settings_from_config() {
+        let defaults = TestRecordSettings::from_config(None).unwrap();
+        assert_eq!(defaults.record_name("abc", "example.com"), "_sslboard-test-abc.example.com");
+        assert_eq!(defaults.ttl, None);
+
+        let config = json!({
+            "test_record_prefix": "_probe",
+            "test_record_subdomain": "_automation.ops",
+            "test_record_ttl": 60
+        });
+        let settings = TestRecordSettings::from_config(Some(&config)).unwrap();
+        assert_eq!(
+            settings.record_name("abc", "example.com"),
+            "_probe-abc._automation.ops.example.com"
+        );
+        assert_eq!(settings.ttl, Some(60));
+
+        assert!(TestRecordSettings::from_config(Some(&json!({ "test_record_prefix": "a.b" }))).is_err());
+        assert!(TestRecordSettings::from_config(Some(&json!({ "test_record_ttl": 5 }))).is_err());
+        assert!(TestRecordSettings::from_config(Some(&json!({ "test_record_subdomain": "bad name" }))).is_err());
+
+        let defaults = TestRecordSettings {
+            prefix: "_zone-check".to_string(),
+            subdomain: None,
+            ttl: Some(600),
+        };
+        let inherited = TestRecordSettings::from_config_with(None, &defaults).unwrap();
+        assert_eq!(inherited.record_name("abc", "example.com"), "_zone-check-abc.example.com");
+        assert_eq!(inherited.ttl, Some(600));
+        let overridden = TestRecordSettings::from_config_with(Some(&config), &defaults).unwrap();
+        assert_eq!(overridden, settings);
+    }
+
+    #[test]
+    fn finds_leftover_test_and_challenge_records() {
+        let config = json!({ "test_record_prefix": "_probe" });
+        let settings = TestRecordSettings::from_config(Some(&config)).unwrap();
+        let records = vec![
+            TxtRecord::new("_probe-ab12.example.com.", "\"sslboard-test-ab12\""),
+            TxtRecord::new("_sslboard-test-cd34.example.com", "sslboard-test-cd34"),
+            TxtRecord::new("_acme-challenge.old.example.com", "digest-a"),
+            TxtRecord::new("_acme-challenge.old.example.com", "digest-b"),
+            TxtRecord::new("_acme-challenge.busy.example.com", "digest-c"),
+            TxtRecord::new("example.com", "v=spf1 -all"),
+        ];
+        let pending = vec!["*.busy.example.com".to_string()];
+
+        let leftovers = leftover_records(records, &settings, &pending);
+        let summary: Vec<_> = leftovers
+            .iter()
+            .map(|leftover| (leftover.record_name.as_str(), leftover.values.len(), leftover.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("_probe-ab12.example.com", 1, LeftoverRecordKind::ProviderTest),
+                ("_sslboard-test-cd34.example.com", 1, LeftoverRecordKind::ProviderTest),
+                ("_acme-challenge.old.example.com", 2, LeftoverRecordKind::StaleChallenge),
+            ]
+        );
+        assert_eq!(leftovers[0].values, ["sslboard-test-ab12"]);
+    }
+
+    #[test]
+    fn validates_api_url() {
+        assert!(validate_api_url(Some(&json!({ "api_url": "https://pdns.internal:8081" }))).is_ok());
+        assert!(validate_api_url(Some(&json!({ "api_url": "ftp://pdns.internal" }))).is_err());
+        assert!(validate_api_url(Some(&json!({ "api_url": " " }))).is_err());
+        assert!(validate_api_url(None).is_err());
+    }
+
+    #[test]
+    fn validates_rfc2136_config() {
+        let config = json!({ "server": "ns1.internal", "tsig_key_name": "acme" });
+        assert!(validate_rfc2136_config(Some(&config), Some("c2VjcmV0")).is_ok());
+        assert!(validate_rfc2136_config(Some(&config), Some("not base64!")).is_err());
+        assert!(validate_rfc2136_config(Some(&json!({ "server": "ns1.internal" })), None).is_err());
+        let config = json!({ "server": "ns1", "tsig_key_name": "acme", "tsig_algorithm": "rot13" });
+        assert!(validate_rfc2136_config(Some(&config), None).is_err());
+    }
+
+    #[test]
+    fn validates_route53_config() {
+        let settings = validate_route53_config(None).unwrap();
+        assert!(!settings.assumes_role && settings.profile.is_none());
+        let config = json!({
+            "profile": "dns",
+            "role_arn": "arn:aws:iam::123456789012:role/sslboard"
+        });
+        let settings = validate_route53_config(Some(&config)).unwrap();
+        assert!(settings.assumes_role);
+        assert_eq!(settings.profile.as_deref(), Some("dns"));
+        assert!(validate_route53_config(Some(&json!({ "profile": "a b" }))).is_err());
+    }
+}
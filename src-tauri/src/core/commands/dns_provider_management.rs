@@ -1,15 +1,27 @@
-use log::warn;
 use tauri::{async_runtime::spawn_blocking, State};
 
+use crate::core::confirmation_pin::ConfirmationPin;
+use crate::core::errors::CommandError;
+use crate::core::mappers::{
+    provider_record_to_dto, provider_type_from_str, suffix_shadow_warnings,
+};
+use crate::core::read_only::ReadOnlyMode;
 use crate::core::types::{
     DeleteDnsProviderRequest, DnsProviderDto, DnsProviderResolutionDto, DnsProviderType,
-    ResolveDnsProviderRequest, UpdateDnsProviderRequest,
+    PropagationPreset, ResolveDnsProviderRequest, SetDnsProviderPriorityRequest,
+    UpdateDnsProviderRequest,
 };
 use crate::domain::normalize_domain_for_display;
+use crate::issuance::dns_providers::{parse_acme_dns_registrations, resolve_provider_by_zone_access};
+use crate::issuance::propagation_presets::{PropagationWaits, ProviderWait};
 use crate::secrets::manager::{SecretError, SecretManager};
-use crate::storage::dns::{DnsConfigStore, DnsProvider};
+use crate::storage::{dns::DnsConfigStore, preferences::PreferencesStore};
+
+use super::dns_provider_helpers::{
+    validate_api_url, validate_domain_suffixes, validate_label, validate_rfc2136_config,
+    validate_route53_config, validate_script_config, TestRecordSettings,
+};
 
-use super::dns_provider_helpers::{validate_domain_suffixes, validate_label};
 /// Lists DNS providers.
 #[tauri::command]
 pub async fn dns_provider_list(
@@ -28,26 +40,53 @@ pub async fn dns_provider_list(
 /// Updates a DNS provider configuration.
 #[tauri::command]
 pub async fn dns_provider_update(
+    read_only: State<'_, ReadOnlyMode>,
     store: State<'_, DnsConfigStore>,
     secrets: State<'_, SecretManager>,
     update_req: UpdateDnsProviderRequest,
-) -> Result<DnsProviderDto, String> {
+) -> Result<DnsProviderDto, CommandError> {
+    read_only.ensure_writable()?;
     let store = store.inner().clone();
     let secrets = secrets.inner().clone();
     spawn_blocking(move || -> Result<DnsProviderDto, anyhow::Error> {
         let label = update_req.label.trim();
         validate_label(label)?;
         let domain_suffixes = validate_domain_suffixes(&update_req.domain_suffixes)?;
+        TestRecordSettings::from_config(update_req.config.as_ref())?;
+        ProviderWait::from_config(update_req.config.as_ref())?;
 
         let existing = store
             .get_provider(&update_req.provider_id)?
             .ok_or_else(|| anyhow::anyhow!("provider not found: {}", update_req.provider_id))?;
 
         let mut secret_refs = existing.secret_refs.clone();
-        let provider_type = provider_type_from_string(&existing.provider_type);
+        let provider_type = provider_type_from_str(&existing.provider_type);
+        let mut route53_settings = None;
+        match provider_type {
+            DnsProviderType::AcmeDns => {
+                validate_api_url(update_req.config.as_ref())?;
+                if let Some(raw) = update_req
+                    .api_token
+                    .as_deref()
+                    .filter(|raw| !raw.trim().is_empty())
+                {
+                    parse_acme_dns_registrations(raw)?;
+                }
+            }
+            DnsProviderType::PowerDns => validate_api_url(update_req.config.as_ref())?,
+            DnsProviderType::Rfc2136 => validate_rfc2136_config(
+                update_req.config.as_ref(),
+                update_req.api_token.as_deref(),
+            )?,
+            DnsProviderType::Script => validate_script_config(update_req.config.as_ref())?,
+            DnsProviderType::Route53 => {
+                route53_settings = Some(validate_route53_config(update_req.config.as_ref())?);
+            }
+            _ => {}
+        }
 
-        if matches!(provider_type, DnsProviderType::Route53) {
-            if let (Some(access_key), Some(secret_key)) = (
+        if let Some(settings) = route53_settings {
+            let new_keys = (
                 update_req
                     .route53_access_key
                     .clone()
@@ -56,7 +95,21 @@ pub async fn dns_provider_update(
                     .route53_secret_key
                     .clone()
                     .filter(|value| !value.trim().is_empty()),
-            ) {
+            );
+            if settings.profile.is_some() {
+                // A named profile replaces stored keys, so they are removed
+                for secret_ref in &secret_refs {
+                    match secrets.delete_secret(secret_ref) {
+                        Ok(()) => {}
+                        Err(SecretError::NotFound(_)) => {}
+                        Err(err) => return Err(anyhow::anyhow!(err.to_string())),
+                    }
+                }
+                if !secret_refs.is_empty() {
+                    secret_refs.clear();
+                    store.update_provider_secret_refs(&update_req.provider_id, Vec::new())?;
+                }
+            } else if let (Some(access_key), Some(secret_key)) = new_keys {
                 for secret_ref in &secret_refs {
                     match secrets.delete_secret(secret_ref) {
                         Ok(()) => {}
@@ -86,6 +139,8 @@ pub async fn dns_provider_update(
 
                 secret_refs.push(access_key_record.id);
                 secret_refs.push(secret_key_record.id);
+            } else if secret_refs.len() < 2 && !settings.assumes_role {
+                return Err(anyhow::anyhow!("Route 53 access key and secret key are required"));
             }
         } else if let Some(token) = update_req
             .api_token
@@ -119,20 +174,28 @@ pub async fn dns_provider_update(
             domain_suffixes,
             update_req.config.clone(),
         )?;
-        Ok(provider_record_to_dto(record))
+        let shadowing = store.find_suffix_shadowing(&record.domain_suffixes, Some(&record.id))?;
+        let mut dto = provider_record_to_dto(record);
+        dto.suffix_warnings = suffix_shadow_warnings(&shadowing);
+        Ok(dto)
     })
     .await
     .map_err(|err| format!("DNS provider update join error: {err}"))?
-    .map_err(|err: anyhow::Error| err.to_string())
+    .map_err(CommandError::from)
 }
 
-/// Deletes a DNS provider configuration.
+/// Deletes a DNS provider configuration and its stored credentials.
+/// Requires the confirmation PIN when one is set.
 #[tauri::command]
 pub async fn dns_provider_delete(
+    read_only: State<'_, ReadOnlyMode>,
+    pin: State<'_, ConfirmationPin>,
     store: State<'_, DnsConfigStore>,
     secrets: State<'_, SecretManager>,
     delete_req: DeleteDnsProviderRequest,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
+    read_only.ensure_writable()?;
+    pin.ensure_confirmed(delete_req.confirmation_pin.as_deref())?;
     let store = store.inner().clone();
     let secrets = secrets.inner().clone();
     spawn_blocking(move || -> Result<String, anyhow::Error> {
@@ -151,18 +214,42 @@ pub async fn dns_provider_delete(
     })
     .await
     .map_err(|err| format!("DNS provider delete join error: {err}"))?
-    .map_err(|err: anyhow::Error| err.to_string())
+    .map_err(CommandError::from)
+}
+
+/// Sets the priority that decides between providers whose suffixes match a
+/// hostname equally well.
+#[tauri::command]
+pub async fn dns_provider_set_priority(
+    read_only: State<'_, ReadOnlyMode>,
+    store: State<'_, DnsConfigStore>,
+    priority_req: SetDnsProviderPriorityRequest,
+) -> Result<DnsProviderDto, CommandError> {
+    read_only.ensure_writable()?;
+    let store = store.inner().clone();
+    spawn_blocking(move || -> Result<DnsProviderDto, anyhow::Error> {
+        let record =
+            store.set_provider_priority(&priority_req.provider_id, priority_req.priority)?;
+        Ok(provider_record_to_dto(record))
+    })
+    .await
+    .map_err(|err| format!("DNS provider priority join error: {err}"))?
+    .map_err(CommandError::from)
 }
 
-/// Resolves a DNS provider for a hostname.
+/// Resolves a DNS provider for a hostname. Providers tied on suffix and
+/// priority are narrowed to those whose account can see the zone.
 #[tauri::command]
 pub async fn dns_resolve_provider(
     store: State<'_, DnsConfigStore>,
+    secrets: State<'_, SecretManager>,
     resolve_req: ResolveDnsProviderRequest,
 ) -> Result<DnsProviderResolutionDto, String> {
     let store = store.inner().clone();
+    let secrets = secrets.inner().clone();
     spawn_blocking(move || -> Result<DnsProviderResolutionDto, anyhow::Error> {
-        let resolution = store.resolve_provider_for_domain(&resolve_req.hostname)?;
+        let resolution =
+            resolve_provider_by_zone_access(&store, &secrets, &resolve_req.hostname)?;
         Ok(DnsProviderResolutionDto {
             provider: resolution.provider.map(provider_record_to_dto),
             matched_suffix: resolution
@@ -180,41 +267,14 @@ pub async fn dns_resolve_provider(
     .map_err(|err: anyhow::Error| err.to_string())
 }
 
-pub(crate) fn provider_record_to_dto(record: DnsProvider) -> DnsProviderDto {
-    let provider_type = provider_type_from_string(&record.provider_type);
-    let config = match record.config_json.as_ref() {
-        Some(raw) => match serde_json::from_str::<serde_json::Value>(raw) {
-            Ok(value) => Some(value),
-            Err(err) => {
-                warn!(
-                    "[dns] invalid provider config_json for {}: {}",
-                    record.id, err
-                );
-                None
-            }
-        },
-        None => None,
-    };
-    DnsProviderDto {
-        id: record.id,
-        provider_type,
-        label: record.label,
-        domain_suffixes: record
-            .domain_suffixes
-            .into_iter()
-            .map(|suffix| normalize_domain_for_display(&suffix))
-            .collect(),
-        config,
-        created_at: record.created_at,
-        updated_at: record.updated_at,
-    }
-}
-
-fn provider_type_from_string(raw: &str) -> DnsProviderType {
-    match raw {
-        "cloudflare" => DnsProviderType::Cloudflare,
-        "digitalocean" => DnsProviderType::DigitalOcean,
-        "route53" => DnsProviderType::Route53,
-        _ => DnsProviderType::Manual,
-    }
+/// Propagation waits per provider type: built-in presets and the user's
+/// overrides from the `propagation_wait_overrides` preference.
+#[tauri::command]
+pub async fn dns_propagation_presets(
+    prefs: State<'_, PreferencesStore>,
+) -> Result<Vec<PropagationPreset>, String> {
+    let prefs = prefs.inner().clone();
+    spawn_blocking(move || PropagationWaits::from_preferences(&prefs).presets())
+        .await
+        .map_err(|err| format!("DNS propagation presets join error: {err}"))
 }
@@ -0,0 +1,381 @@
+use std::{collections::HashSet, fs};
+
+use anyhow::{anyhow, Context};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime::spawn_blocking, State};
+use zeroize::Zeroizing;
+
+use crate::core::errors::CommandError;
+use crate::core::mappers::{provider_record_to_dto, provider_type_to_string};
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::types::{
+    CreateDnsProviderRequest, DnsCredentialField, DnsCredentialPlaceholder, DnsProviderDto,
+    DnsProviderExportFile, DnsProviderExportResult, DnsProviderFileImport,
+    DnsProviderFileImportResult, DnsProviderImportCandidate, DnsProviderType,
+    ExportDnsProvidersRequest, ImportDnsProvidersRequest, PortableDnsProvider,
+};
+use crate::secrets::manager::SecretManager;
+use crate::secrets::sealed::{self, SealedSecret};
+use crate::storage::dns::{DnsConfigStore, DnsProvider};
+
+use super::dns_provider_creation::create_provider;
+use super::dns_provider_helpers::validate_domain_suffixes;
+
+const EXPORT_FORMAT: &str = "sslboard-dns-providers";
+const EXPORT_VERSION: u32 = 1;
+
+/// Credentials of one provider as sealed into an export file.
+#[derive(Default, Serialize, Deserialize)]
+struct ProviderCredentials {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    api_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    route53_access_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    route53_secret_key: Option<String>,
+}
+
+/// Writes DNS provider configurations to a JSON file for use on another
+/// machine. Credentials are replaced by placeholders naming what to enter,
+/// and with a passphrase are also included, encrypted to it.
+#[tauri::command]
+pub async fn dns_provider_export(
+    read_only: State<'_, ReadOnlyMode>,
+    store: State<'_, DnsConfigStore>,
+    secrets: State<'_, SecretManager>,
+    export_req: ExportDnsProvidersRequest,
+) -> Result<DnsProviderExportResult, CommandError> {
+    read_only.ensure_writable()?;
+    let store = store.inner().clone();
+    let secrets = secrets.inner().clone();
+    spawn_blocking(move || -> Result<DnsProviderExportResult, anyhow::Error> {
+        let passphrase = export_req.passphrase.as_deref().filter(|value| !value.is_empty());
+        let providers = store
+            .list_providers()?
+            .into_iter()
+            .filter(|provider| {
+                export_req.provider_ids.is_empty() || export_req.provider_ids.contains(&provider.id)
+            })
+            .map(|provider| {
+                let sealed_credentials = passphrase
+                    .map(|passphrase| seal_credentials(&secrets, &provider, passphrase))
+                    .transpose()?;
+                let mut portable = portable_provider(provider_record_to_dto(provider));
+                portable.sealed_credentials = sealed_credentials;
+                Ok(portable)
+            })
+            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+        if providers.is_empty() {
+            return Err(anyhow!("No DNS providers to export"));
+        }
+        let file = DnsProviderExportFile {
+            format: EXPORT_FORMAT.to_string(),
+            version: EXPORT_VERSION,
+            exported_at: Utc::now(),
+            providers,
+        };
+        fs::write(&export_req.path, serde_json::to_string_pretty(&file)?)
+            .with_context(|| format!("failed to write {}", export_req.path))?;
+        Ok(DnsProviderExportResult {
+            path: export_req.path,
+            exported: file.providers.len(),
+            credentials_included: passphrase.is_some(),
+        })
+    })
+    .await
+    .map_err(|err| format!("DNS provider export join error: {err}"))?
+    .map_err(CommandError::from)
+}
+
+/// Reads an export file and lists its providers with the credentials each
+/// one needs. Nothing is saved; providers are then created with
+/// `dns_provider_import` or one by one with `dns_provider_create`.
+#[tauri::command]
+pub async fn preview_dns_provider_import(
+    store: State<'_, DnsConfigStore>,
+    path: String,
+) -> Result<Vec<DnsProviderImportCandidate>, String> {
+    let store = store.inner().clone();
+    spawn_blocking(move || -> Result<Vec<DnsProviderImportCandidate>, anyhow::Error> {
+        let raw = fs::read_to_string(&path).with_context(|| format!("failed to read {path}"))?;
+        let file = parse_export_file(&raw)?;
+        let existing = store.list_providers()?;
+        file.providers
+            .into_iter()
+            .map(|provider| {
+                let existing_label = find_existing(&existing, &provider)?;
+                Ok(DnsProviderImportCandidate {
+                    provider,
+                    existing_label,
+                })
+            })
+            .collect()
+    })
+    .await
+    .map_err(|err| format!("DNS provider import join error: {err}"))?
+    .map_err(|err| err.to_string())
+}
+
+/// Creates every provider of an export file whose credentials the file
+/// carries or that needs none. Providers already configured are skipped,
+/// as are those without credentials; the preview adds them one by one.
+#[tauri::command]
+pub async fn dns_provider_import(
+    read_only: State<'_, ReadOnlyMode>,
+    store: State<'_, DnsConfigStore>,
+    secrets: State<'_, SecretManager>,
+    import_req: ImportDnsProvidersRequest,
+) -> Result<DnsProviderFileImportResult, CommandError> {
+    read_only.ensure_writable()?;
+    let store = store.inner().clone();
+    let secrets = secrets.inner().clone();
+    spawn_blocking(move || -> Result<DnsProviderFileImportResult, anyhow::Error> {
+        let raw = fs::read_to_string(&import_req.path)
+            .with_context(|| format!("failed to read {}", import_req.path))?;
+        let file = parse_export_file(&raw)?;
+        let passphrase = import_req.passphrase.as_deref().filter(|value| !value.is_empty());
+        // Open everything first so a wrong passphrase fails before anything is created
+        let credentials = file
+            .providers
+            .iter()
+            .map(|provider| open_credentials(provider, passphrase))
+            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+        let existing = store.list_providers()?;
+
+        let mut result = DnsProviderFileImportResult {
+            imported: Vec::new(),
+            skipped: Vec::new(),
+        };
+        for (provider, credentials) in file.providers.into_iter().zip(credentials) {
+            let mut entry = DnsProviderFileImport {
+                provider_type: provider.provider_type.clone(),
+                label: provider.label.clone(),
+                provider_id: None,
+                skipped_reason: None,
+            };
+            match import_provider(&store, &secrets, &existing, provider, credentials) {
+                Ok(provider_id) => {
+                    entry.provider_id = Some(provider_id);
+                    result.imported.push(entry);
+                }
+                Err(err) => {
+                    entry.skipped_reason = Some(err.to_string());
+                    result.skipped.push(entry);
+                }
+            }
+        }
+        log::info!(
+            "[dns] imported {} provider(s) from {}, skipped {}",
+            result.imported.len(),
+            import_req.path,
+            result.skipped.len()
+        );
+        Ok(result)
+    })
+    .await
+    .map_err(|err| format!("DNS provider import join error: {err}"))?
+    .map_err(CommandError::from)
+}
+
+fn portable_provider(dto: DnsProviderDto) -> PortableDnsProvider {
+    PortableDnsProvider {
+        credentials: credential_placeholders(&dto.provider_type),
+        provider_type: dto.provider_type,
+        label: dto.label,
+        domain_suffixes: dto.domain_suffixes,
+        config: dto.config,
+        sealed_credentials: None,
+    }
+}
+
+/// Seals the provider's stored credentials, or an empty set for providers
+/// without any (AWS profiles, manual), so the import knows none are needed.
+fn seal_credentials(
+    secrets: &SecretManager,
+    provider: &DnsProvider,
+    passphrase: &str,
+) -> Result<SealedSecret, anyhow::Error> {
+    let resolve = |secret_ref: &String| -> Result<Option<String>, anyhow::Error> {
+        let value = secrets
+            .resolve_secret(secret_ref)
+            .map_err(|err| anyhow!("{}: {err}", provider.label))?;
+        let value = String::from_utf8(value)
+            .with_context(|| format!("{}: stored credential is not text", provider.label))?;
+        Ok(Some(value))
+    };
+    let mut credentials = ProviderCredentials::default();
+    match (provider.provider_type.as_str(), provider.secret_refs.as_slice()) {
+        ("route53", [access_key, secret_key, ..]) => {
+            credentials.route53_access_key = resolve(access_key)?;
+            credentials.route53_secret_key = resolve(secret_key)?;
+        }
+        ("route53", _) | (_, []) => {}
+        (_, [token, ..]) => credentials.api_token = resolve(token)?,
+    }
+    let payload = Zeroizing::new(serde_json::to_vec(&credentials)?);
+    sealed::seal(&payload, passphrase)
+}
+
+fn open_credentials(
+    provider: &PortableDnsProvider,
+    passphrase: Option<&str>,
+) -> Result<Option<ProviderCredentials>, anyhow::Error> {
+    let (Some(sealed_credentials), Some(passphrase)) = (&provider.sealed_credentials, passphrase)
+    else {
+        return Ok(None);
+    };
+    let payload = sealed::open(sealed_credentials, passphrase)
+        .with_context(|| format!("failed to open the credentials of {}", provider.label))?;
+    Ok(Some(serde_json::from_slice(&payload)?))
+}
+
+/// Creates one provider from an export file and returns its id.
+fn import_provider(
+    store: &DnsConfigStore,
+    secrets: &SecretManager,
+    existing: &[DnsProvider],
+    provider: PortableDnsProvider,
+    credentials: Option<ProviderCredentials>,
+) -> Result<String, anyhow::Error> {
+    if let Some(label) = find_existing(existing, &provider)? {
+        return Err(anyhow!("Already configured as {label}"));
+    }
+    let credentials = match credentials {
+        Some(credentials) => credentials,
+        None if provider.sealed_credentials.is_some() => {
+            return Err(anyhow!("Credentials are encrypted; enter the export passphrase"));
+        }
+        None if provider.credentials.iter().any(|placeholder| placeholder.required) => {
+            return Err(anyhow!("The file has no credentials for this provider"));
+        }
+        None => ProviderCredentials::default(),
+    };
+    let created = create_provider(
+        store,
+        secrets,
+        CreateDnsProviderRequest {
+            provider_type: provider.provider_type,
+            label: provider.label,
+            domain_suffixes: provider.domain_suffixes.join(","),
+            api_token: credentials.api_token,
+            route53_access_key: credentials.route53_access_key,
+            route53_secret_key: credentials.route53_secret_key,
+            config: provider.config,
+            on_conflict: None,
+            verify_suffixes: false,
+        },
+    )?;
+    Ok(created.id)
+}
+
+/// Credentials `dns_provider_create` takes for each provider type.
+fn credential_placeholders(provider_type: &DnsProviderType) -> Vec<DnsCredentialPlaceholder> {
+    let placeholder = |field, required| DnsCredentialPlaceholder { field, required };
+    match provider_type {
+        DnsProviderType::Manual => Vec::new(),
+        DnsProviderType::Route53 => vec![
+            placeholder(DnsCredentialField::Route53AccessKey, true),
+            placeholder(DnsCredentialField::Route53SecretKey, true),
+        ],
+        // acme-dns registers fresh accounts and a webhook may not check a token
+        DnsProviderType::AcmeDns | DnsProviderType::Script => {
+            vec![placeholder(DnsCredentialField::ApiToken, false)]
+        }
+        _ => vec![placeholder(DnsCredentialField::ApiToken, true)],
+    }
+}
+
+fn parse_export_file(raw: &str) -> Result<DnsProviderExportFile, anyhow::Error> {
+    let file: DnsProviderExportFile =
+        serde_json::from_str(raw).context("not a DNS provider export file")?;
+    if file.format != EXPORT_FORMAT {
+        return Err(anyhow!("not a DNS provider export file"));
+    }
+    if file.version > EXPORT_VERSION {
+        return Err(anyhow!(
+            "export file version {} is newer than this version supports",
+            file.version
+        ));
+    }
+    Ok(file)
+}
+
+/// A configured provider of the same type that already covers every suffix.
+fn find_existing(
+    existing: &[DnsProvider],
+    provider: &PortableDnsProvider,
+) -> Result<Option<String>, anyhow::Error> {
+    let provider_type = provider_type_to_string(&provider.provider_type);
+    let suffixes = validate_domain_suffixes(&provider.domain_suffixes.join(","))?;
+    Ok(existing
+        .iter()
+        .filter(|candidate| candidate.provider_type == provider_type)
+        .find(|candidate| {
+            let configured: HashSet<&String> = candidate.domain_suffixes.iter().collect();
+            suffixes.iter().all(|suffix| configured.contains(suffix))
+        })
+        .map(|candidate| candidate.label.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn export_files_carry_placeholders_not_secrets() {
+        let raw = json!({
+            "format": EXPORT_FORMAT,
+            "version": 1,
+            "exported_at": "2024-05-01T00:00:00Z",
+            "providers": [{
+                "provider_type": "route53",
+                "label": "AWS",
+                "domain_suffixes": ["example.com"],
+                "credentials": [{ "field": "route53_access_key", "required": true }]
+            }]
+        })
+        .to_string();
+        let file = parse_export_file(&raw).unwrap();
+        assert_eq!(file.providers[0].credentials[0].field, DnsCredentialField::Route53AccessKey);
+        assert!(file.providers[0].config.is_none());
+
+        let newer = raw.replace("\"version\":1", "\"version\":2");
+        assert!(parse_export_file(&newer).is_err());
+        assert!(parse_export_file(r#"{"format":"other"}"#).is_err());
+
+        assert!(credential_placeholders(&DnsProviderType::Manual).is_empty());
+        let script = credential_placeholders(&DnsProviderType::Script);
+        assert!(!script[0].required);
+        assert!(credential_placeholders(&DnsProviderType::Cloudflare)[0].required);
+    }
+
+    #[test]
+    fn sealed_credentials_need_the_export_passphrase() {
+        let credentials = ProviderCredentials {
+            api_token: Some("cf-token".to_string()),
+            ..Default::default()
+        };
+        let payload = serde_json::to_vec(&credentials).unwrap();
+        let mut provider = PortableDnsProvider {
+            provider_type: DnsProviderType::Cloudflare,
+            label: "Cloudflare".to_string(),
+            domain_suffixes: vec!["example.com".to_string()],
+            config: None,
+            credentials: credential_placeholders(&DnsProviderType::Cloudflare),
+            sealed_credentials: None,
+        };
+        assert!(open_credentials(&provider, Some("correct horse battery")).unwrap().is_none());
+
+        provider.sealed_credentials =
+            Some(sealed::seal(&payload, "correct horse battery").unwrap());
+        let raw = serde_json::to_string(&provider).unwrap();
+        let provider: PortableDnsProvider = serde_json::from_str(&raw).unwrap();
+        assert!(!raw.contains("cf-token"));
+        let opened = open_credentials(&provider, Some("correct horse battery")).unwrap();
+        assert_eq!(opened.unwrap().api_token.as_deref(), Some("cf-token"));
+        assert!(open_credentials(&provider, Some("wrong passphrase!!")).is_err());
+        assert!(open_credentials(&provider, None).unwrap().is_none());
+    }
+}
@@ -1,155 +1,451 @@
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use log::{info, warn};
+use sha2::{Digest, Sha256};
 use tauri::{async_runtime::spawn_blocking, State};
 use uuid::Uuid;
 
-use crate::core::types::{DnsProviderTestResult, TestDnsProviderRequest};
+use crate::core::errors::CommandError;
+use crate::core::metrics::CommandTimer;
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::types::{
+    DnsProviderTestResult, ProviderTestMode, TestDnsProviderRequest, TestRecordCleanup,
+    WriteAccessCheck,
+};
 use crate::issuance::dns::PropagationState;
-use crate::issuance::dns_providers::{adapter_for_provider, poll_dns_propagation};
+use crate::issuance::dns_providers::{
+    adapter_for_provider, adapter_with_record_ttl, poll_dns_propagation, DnsProviderAdapter,
+    DryRunAdapter,
+};
+use crate::issuance::flow::pending_issuance_domains;
+use crate::issuance::propagation_presets::{self, PropagationWaits, ProviderWait};
+use crate::issuance::propagation_resolvers::ResolverSet;
 use crate::secrets::manager::SecretManager;
-use crate::storage::dns::DnsConfigStore;
+use crate::storage::{
+    dns::{DnsConfigStore, DnsProvider},
+    preferences::PreferencesStore,
+};
 
+use super::dns_provider_helpers::{leftover_records, TestRecordSettings, TEST_RECORD_VALUE_PREFIX};
 use super::dns_validation::categorize_dns_error;
 
-/// Tests a DNS provider configuration by creating a temporary TXT record.
+/// Tests a DNS provider configuration by creating a temporary TXT record,
+/// or in `credentials_only` and `dry_run` modes without touching any records.
 #[tauri::command]
 pub async fn dns_provider_test(
+    read_only: State<'_, ReadOnlyMode>,
     store: State<'_, DnsConfigStore>,
     secrets: State<'_, SecretManager>,
+    prefs: State<'_, PreferencesStore>,
     test_req: TestDnsProviderRequest,
-) -> Result<DnsProviderTestResult, String> {
+) -> Result<DnsProviderTestResult, CommandError> {
+    if test_req.mode == ProviderTestMode::Full {
+        read_only.ensure_writable()?;
+    }
     let store = store.inner().clone();
     let secrets = secrets.inner().clone();
-    spawn_blocking(move || -> Result<DnsProviderTestResult, anyhow::Error> {
-        let started = Instant::now();
-        info!("[dns-test] Starting DNS provider test for provider_id: {}", test_req.provider_id);
-        let provider = store
-            .get_provider(&test_req.provider_id)?
-            .ok_or_else(|| anyhow::anyhow!("provider not found: {}", test_req.provider_id))?;
-        info!("[dns-test] Found provider: type={}, label={}", provider.provider_type, provider.label);
-
-        // Generate test record details
-        let suffix = provider
-            .domain_suffixes
-            .first()
-            .cloned()
-            .ok_or_else(|| anyhow::anyhow!("provider has no domain suffixes"))?;
-        let random = Uuid::new_v4().as_simple().to_string();
-        let record_name = format!("_sslboard-test-{}.{}", &random[..10], suffix);
-        let value = format!("sslboard-test-{}", &random[..10]);
-
-        info!("[dns-test] Creating test TXT record: {} = {}", record_name, value);
-        let provider_adapter = adapter_for_provider(&provider, &secrets);
-
-        let create_start = Instant::now();
-        if let Err(err) = provider_adapter.create_txt(&record_name, &value) {
-            warn!("[dns-test] Failed to create TXT record: {}", err);
-            let error_category = categorize_dns_error(&err);
-            return Ok(DnsProviderTestResult {
-                success: false,
-                record_name: Some(record_name),
-                value: Some(value),
-                propagation: None,
-                error: Some(err.to_string()),
-                error_category: Some(error_category),
-                error_stage: Some("create".to_string()),
-                elapsed_ms: started.elapsed().as_millis() as u64,
-                create_ms: Some(create_start.elapsed().as_millis() as u64),
-                propagation_ms: None,
-                cleanup_ms: None,
-            });
+    let waits = PropagationWaits::from_preferences(prefs.inner());
+    let test_defaults = TestRecordSettings::from_preferences(prefs.inner());
+    let resolvers = ResolverSet::from_preferences(prefs.inner());
+    let timer = CommandTimer::start("dns_provider_test");
+    let result = spawn_blocking(move || match test_req.mode {
+        ProviderTestMode::Full => run_provider_test(
+            &store,
+            &secrets,
+            &waits,
+            &test_defaults,
+            &resolvers,
+            &test_req.provider_id,
+        ),
+        ProviderTestMode::CredentialsOnly => {
+            run_credentials_check(&store, &secrets, &test_req.provider_id)
         }
-        let create_ms = create_start.elapsed().as_millis() as u64;
-        info!("[dns-test] TXT record created in {}ms", create_ms);
-
-        let propagation_start = Instant::now();
-        info!("[dns-test] Starting propagation polling for {}", record_name);
-        let timeout = Duration::from_secs(30);
-        let interval = Duration::from_secs(2);
-        let propagation = match poll_dns_propagation(&record_name, &value, timeout, interval) {
-            Ok(result) => result,
-            Err(err) => {
-                let propagation_ms = propagation_start.elapsed().as_millis() as u64;
-                let cleanup_start = Instant::now();
-                let cleanup_result = provider_adapter.cleanup_txt(&record_name);
-                let cleanup_ms = cleanup_start.elapsed().as_millis() as u64;
-                if let Err(cleanup_err) = cleanup_result {
-                    let error_category = categorize_dns_error(&cleanup_err);
-                    return Ok(DnsProviderTestResult {
-                        success: false,
-                        record_name: Some(record_name),
-                        value: Some(value),
-                        propagation: None,
-                        error: Some(cleanup_err.to_string()),
-                        error_category: Some(error_category),
-                        error_stage: Some("cleanup".to_string()),
-                        elapsed_ms: started.elapsed().as_millis() as u64,
-                        create_ms: Some(create_ms),
-                        propagation_ms: Some(propagation_ms),
-                        cleanup_ms: Some(cleanup_ms),
-                    });
-                }
-                let error_category = categorize_dns_error(&err);
+        ProviderTestMode::DryRun => {
+            run_dry_run_test(&store, &secrets, &test_defaults, &test_req.provider_id)
+        }
+    })
+    .await
+    .map_err(|err| format!("DNS provider test join error: {err}"))?
+    .map_err(CommandError::from);
+    timer.finish(result)
+}
+
+/// Creates a temporary TXT record with the provider, waits for it to
+/// propagate and removes it again. Also run by the provider health checks.
+pub(crate) fn run_provider_test(
+    store: &DnsConfigStore,
+    secrets: &SecretManager,
+    waits: &PropagationWaits,
+    test_defaults: &TestRecordSettings,
+    resolvers: &ResolverSet,
+    provider_id: &str,
+) -> Result<DnsProviderTestResult, anyhow::Error> {
+    let started = Instant::now();
+    info!("[dns-test] Starting DNS provider test for provider_id: {}", provider_id);
+    let provider = store
+        .get_provider(provider_id)?
+        .ok_or_else(|| anyhow::anyhow!("provider not found: {}", provider_id))?;
+    info!("[dns-test] Found provider: type={}, label={}", provider.provider_type, provider.label);
+
+    let config = provider
+        .config_json
+        .as_deref()
+        .map(serde_json::from_str::<serde_json::Value>)
+        .transpose()?;
+    let settings = TestRecordSettings::from_config_with(config.as_ref(), test_defaults)?;
+    let (record_name, value) = test_record(&provider, &settings)?;
+
+    info!("[dns-test] Creating test TXT record: {} = {}", record_name, value);
+    let provider_adapter = adapter_with_record_ttl(&provider, secrets, settings.ttl);
+
+    let create_start = Instant::now();
+    if let Err(err) = provider_adapter.create_txt(&record_name, &value) {
+        warn!("[dns-test] Failed to create TXT record: {}", err);
+        let error_category = categorize_dns_error(&err);
+        return Ok(DnsProviderTestResult {
+            success: false,
+            record_name: Some(record_name),
+            value: Some(value),
+            propagation: None,
+            error: Some(err.to_string()),
+            error_category: Some(error_category),
+            error_stage: Some("create".to_string()),
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            create_ms: Some(create_start.elapsed().as_millis() as u64),
+            propagation_ms: None,
+            cleanup_ms: None,
+            mode: ProviderTestMode::Full,
+            zones: None,
+            write_access: None,
+            planned_operations: None,
+        });
+    }
+    let create_ms = create_start.elapsed().as_millis() as u64;
+    info!("[dns-test] TXT record created in {}ms", create_ms);
+
+    let propagation_start = Instant::now();
+    info!("[dns-test] Starting propagation polling for {}", record_name);
+    let wait = waits.resolve(&provider.provider_type, ProviderWait::from_config(config.as_ref())?);
+    let timeout = propagation_presets::timeout(wait);
+    let interval = propagation_presets::interval(wait);
+    let polled = poll_dns_propagation(&record_name, &value, timeout, interval, resolvers);
+    let propagation = match polled {
+        Ok(result) => result,
+        Err(err) => {
+            let propagation_ms = propagation_start.elapsed().as_millis() as u64;
+            let cleanup_start = Instant::now();
+            let cleanup_result = provider_adapter.cleanup_txt(&record_name);
+            let cleanup_ms = cleanup_start.elapsed().as_millis() as u64;
+            if let Err(cleanup_err) = cleanup_result {
+                let error_category = categorize_dns_error(&cleanup_err);
                 return Ok(DnsProviderTestResult {
                     success: false,
                     record_name: Some(record_name),
                     value: Some(value),
                     propagation: None,
-                    error: Some(err.to_string()),
+                    error: Some(cleanup_err.to_string()),
                     error_category: Some(error_category),
-                    error_stage: Some("propagation".to_string()),
+                    error_stage: Some("cleanup".to_string()),
                     elapsed_ms: started.elapsed().as_millis() as u64,
                     create_ms: Some(create_ms),
                     propagation_ms: Some(propagation_ms),
                     cleanup_ms: Some(cleanup_ms),
+                    mode: ProviderTestMode::Full,
+                    zones: None,
+                    write_access: None,
+                    planned_operations: None,
                 });
             }
-        };
-        let propagation_ms = propagation_start.elapsed().as_millis() as u64;
-        info!(
-            "[dns-test] Propagation check completed in {}ms: state={:?}",
-            propagation_ms, propagation.state
-        );
-        
-        // Spawn cleanup in background thread - don't block on it
-        let record_name_clone = record_name.clone();
-        let provider_adapter_clone = adapter_for_provider(&provider, &secrets);
-        info!("[dns-test] Starting background cleanup for {}", record_name);
-        std::thread::spawn(move || {
-            let cleanup_start = Instant::now();
-            if let Err(err) = provider_adapter_clone.cleanup_txt(&record_name_clone) {
-                warn!("[dns-test] Background cleanup failed for {}: {}", record_name_clone, err);
-            } else {
-                info!(
-                    "[dns-test] Background cleanup completed for {} in {}ms",
-                    record_name_clone,
-                    cleanup_start.elapsed().as_millis()
-                );
-            }
-        });
+            let error_category = categorize_dns_error(&err);
+            return Ok(DnsProviderTestResult {
+                success: false,
+                record_name: Some(record_name),
+                value: Some(value),
+                propagation: None,
+                error: Some(err.to_string()),
+                error_category: Some(error_category),
+                error_stage: Some("propagation".to_string()),
+                elapsed_ms: started.elapsed().as_millis() as u64,
+                create_ms: Some(create_ms),
+                propagation_ms: Some(propagation_ms),
+                cleanup_ms: Some(cleanup_ms),
+                mode: ProviderTestMode::Full,
+                zones: None,
+                write_access: None,
+                planned_operations: None,
+            });
+        }
+    };
+    let propagation_ms = propagation_start.elapsed().as_millis() as u64;
+    info!(
+        "[dns-test] Propagation check completed in {}ms: state={:?}",
+        propagation_ms, propagation.state
+    );
+    
+    // Spawn cleanup in background thread - don't block on it
+    // The adapter moves to the cleanup thread with the zone it already found
+    let record_name_clone = record_name.clone();
+    info!("[dns-test] Starting background cleanup for {}", record_name);
+    std::thread::spawn(move || {
+        let cleanup_start = Instant::now();
+        if let Err(err) = provider_adapter.cleanup_txt(&record_name_clone) {
+            warn!("[dns-test] Background cleanup failed for {}: {}", record_name_clone, err);
+        } else {
+            info!(
+                "[dns-test] Background cleanup completed for {} in {}ms",
+                record_name_clone,
+                cleanup_start.elapsed().as_millis()
+            );
+        }
+    });
 
-        let success = matches!(propagation.state, PropagationState::Found);
-        info!(
-            "[dns-test] Test completed: success={}, total_elapsed={}ms, create={}ms, propagation={}ms, cleanup=async",
-            success, started.elapsed().as_millis(), create_ms, propagation_ms
-        );
+    let success = matches!(propagation.state, PropagationState::Found);
+    info!(
+        "[dns-test] Test completed: success={}, total_elapsed={}ms, create={}ms, propagation={}ms, cleanup=async",
+        success, started.elapsed().as_millis(), create_ms, propagation_ms
+    );
 
-        Ok(DnsProviderTestResult {
-            success,
-            record_name: Some(record_name),
-            value: Some(value),
-            propagation: Some(propagation),
-            error: None,
-            error_category: None,
-            error_stage: None,
+    Ok(DnsProviderTestResult {
+        success,
+        record_name: Some(record_name),
+        value: Some(value),
+        propagation: Some(propagation),
+        error: None,
+        error_category: None,
+        error_stage: None,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        create_ms: Some(create_ms),
+        propagation_ms: Some(propagation_ms),
+        cleanup_ms: None, // Cleanup is async, no timing available
+        mode: ProviderTestMode::Full,
+        zones: None,
+        write_access: None,
+        planned_operations: None,
+    })
+}
+
+/// Name and value of a temporary test record under the provider's first
+/// domain suffix.
+fn test_record(
+    provider: &DnsProvider,
+    settings: &TestRecordSettings,
+) -> Result<(String, String), anyhow::Error> {
+    let suffix = provider
+        .domain_suffixes
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("provider has no domain suffixes"))?;
+    let random = Uuid::new_v4().as_simple().to_string();
+    if provider.provider_type == "acme_dns" {
+        // acme-dns only answers the challenge name and only accepts values
+        // shaped like a challenge digest; the check also proves the CNAME
+        return Ok((
+            format!("_acme-challenge.{suffix}"),
+            URL_SAFE_NO_PAD.encode(Sha256::digest(random.as_bytes())),
+        ));
+    }
+    Ok((
+        settings.record_name(&random[..10], suffix),
+        format!("{TEST_RECORD_VALUE_PREFIX}{}", &random[..10]),
+    ))
+}
+
+/// Runs the test record's lifecycle through a [`DryRunAdapter`]: zone access
+/// is checked against the provider, while creating and removing the record
+/// are only logged and returned as planned operations.
+fn run_dry_run_test(
+    store: &DnsConfigStore,
+    secrets: &SecretManager,
+    test_defaults: &TestRecordSettings,
+    provider_id: &str,
+) -> Result<DnsProviderTestResult, anyhow::Error> {
+    let started = Instant::now();
+    let provider = store
+        .get_provider(provider_id)?
+        .ok_or_else(|| anyhow::anyhow!("provider not found: {}", provider_id))?;
+    info!("[dns-test] Dry run of {} without writing records", provider.label);
+    let config = provider
+        .config_json
+        .as_deref()
+        .map(serde_json::from_str::<serde_json::Value>)
+        .transpose()?;
+    let settings = TestRecordSettings::from_config_with(config.as_ref(), test_defaults)?;
+    let (record_name, value) = test_record(&provider, &settings)?;
+    let adapter = DryRunAdapter::new(
+        provider.label.clone(),
+        adapter_with_record_ttl(&provider, secrets, settings.ttl),
+    );
+
+    let (error, stage) = match adapter.verify_zone_access() {
+        Ok(()) => (None, None),
+        Err(err) => {
+            warn!("[dns-test] Zone access check failed: {}", err);
+            (Some(err), Some("credentials"))
+        }
+    };
+    if error.is_none() {
+        adapter.create_txt(&record_name, &value)?;
+        adapter.cleanup_txt(&record_name)?;
+    }
+    Ok(DnsProviderTestResult {
+        success: error.is_none(),
+        record_name: Some(record_name),
+        value: Some(value),
+        propagation: None,
+        error_category: error.as_ref().map(categorize_dns_error),
+        error: error.map(|err| err.to_string()),
+        error_stage: stage.map(str::to_string),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        create_ms: None,
+        propagation_ms: None,
+        cleanup_ms: None,
+        mode: ProviderTestMode::DryRun,
+        zones: None,
+        write_access: None,
+        planned_operations: Some(adapter.planned()),
+    })
+}
+
+/// Checks the provider's credentials without touching records: zone access,
+/// the zones they can see, and write permission where the API can tell.
+fn run_credentials_check(
+    store: &DnsConfigStore,
+    secrets: &SecretManager,
+    provider_id: &str,
+) -> Result<DnsProviderTestResult, anyhow::Error> {
+    let started = Instant::now();
+    let provider = store
+        .get_provider(provider_id)?
+        .ok_or_else(|| anyhow::anyhow!("provider not found: {}", provider_id))?;
+    info!("[dns-test] Checking credentials of {} without writing records", provider.label);
+    let adapter = adapter_for_provider(&provider, secrets);
+    let result = |error: Option<anyhow::Error>, stage: Option<&str>, zones, write_access| {
+        DnsProviderTestResult {
+            success: error.is_none(),
+            record_name: None,
+            value: None,
+            propagation: None,
+            error_category: error.as_ref().map(categorize_dns_error),
+            error: error.map(|err| err.to_string()),
+            error_stage: stage.map(str::to_string),
             elapsed_ms: started.elapsed().as_millis() as u64,
-            create_ms: Some(create_ms),
-            propagation_ms: Some(propagation_ms),
-            cleanup_ms: None, // Cleanup is async, no timing available
+            create_ms: None,
+            propagation_ms: None,
+            cleanup_ms: None,
+            mode: ProviderTestMode::CredentialsOnly,
+            zones,
+            write_access,
+            planned_operations: None,
+        }
+    };
+
+    if let Err(err) = adapter.verify_zone_access() {
+        warn!("[dns-test] Zone access check failed: {}", err);
+        return Ok(result(Some(err), Some("credentials"), None, None));
+    }
+    let zones = match adapter.list_zones() {
+        Ok(zones) => Some(zones),
+        Err(err) => {
+            warn!("[dns-test] Failed to list zones: {}", err);
+            None
+        }
+    };
+    let write_access = match adapter.verify_write_access() {
+        Some(Ok(())) => WriteAccessCheck::Verified,
+        Some(Err(err)) => {
+            warn!("[dns-test] Write permission check failed: {}", err);
+            return Ok(result(Some(err), Some("write_access"), zones, None));
+        }
+        None => WriteAccessCheck::NotSupported,
+    };
+    Ok(result(None, None, zones, Some(write_access)))
+}
+
+/// Finds provider-test records and `_acme-challenge` records no pending
+/// issuance uses in the provider's zones, and deletes them unless `dry_run`
+/// is set. Test runs whose cleanup failed leave such records behind.
+#[tauri::command]
+pub async fn cleanup_provider_test_records(
+    read_only: State<'_, ReadOnlyMode>,
+    store: State<'_, DnsConfigStore>,
+    secrets: State<'_, SecretManager>,
+    prefs: State<'_, PreferencesStore>,
+    provider_id: String,
+    dry_run: bool,
+) -> Result<TestRecordCleanup, CommandError> {
+    if !dry_run {
+        read_only.ensure_writable()?;
+    }
+    let store = store.inner().clone();
+    let secrets = secrets.inner().clone();
+    let test_defaults = TestRecordSettings::from_preferences(prefs.inner());
+    let timer = CommandTimer::start("cleanup_provider_test_records");
+    let result = spawn_blocking(move || -> Result<TestRecordCleanup, anyhow::Error> {
+        let provider = store
+            .get_provider(&provider_id)?
+            .ok_or_else(|| anyhow::anyhow!("provider not found: {provider_id}"))?;
+        let config = provider
+            .config_json
+            .as_deref()
+            .map(serde_json::from_str::<serde_json::Value>)
+            .transpose()?;
+        let settings = TestRecordSettings::from_config_with(config.as_ref(), &test_defaults)?;
+        let adapter = adapter_for_provider(&provider, &secrets);
+        let pending: Vec<String> = pending_issuance_domains().into_iter().flatten().collect();
+        let records = leftover_records(adapter.list_txt_records()?, &settings, &pending);
+
+        let mut deleted = Vec::new();
+        let mut failed = Vec::new();
+        if !dry_run {
+            for record in &records {
+                match adapter.cleanup_txt(&record.record_name) {
+                    Ok(()) => deleted.push(record.record_name.clone()),
+                    Err(err) => {
+                        warn!("[dns-test] Failed to delete {}: {err}", record.record_name);
+                        failed.push(format!("{}: {err}", record.record_name));
+                    }
+                }
+            }
+            info!(
+                "[dns-test] Cleaned up {} leftover record(s) for {}, {} failed",
+                deleted.len(),
+                provider.label,
+                failed.len()
+            );
+        }
+        Ok(TestRecordCleanup {
+            dry_run,
+            records,
+            deleted,
+            failed,
         })
     })
     .await
-    .map_err(|err| format!("DNS provider test join error: {err}"))?
-    .map_err(|err: anyhow::Error| err.to_string())
+    .map_err(|err| format!("Test record cleanup join error: {err}"))?
+    .map_err(CommandError::from);
+    timer.finish(result)
+}
+
+/// Asks the provider's API which account its credentials belong to, so
+/// providers of one type with different credentials can be told apart.
+/// `None` for provider types whose API does not say.
+#[tauri::command]
+pub async fn dns_provider_account(
+    store: State<'_, DnsConfigStore>,
+    secrets: State<'_, SecretManager>,
+    provider_id: String,
+) -> Result<Option<String>, String> {
+    let store = store.inner().clone();
+    let secrets = secrets.inner().clone();
+    spawn_blocking(move || -> Result<Option<String>, anyhow::Error> {
+        let provider = store
+            .get_provider(&provider_id)?
+            .ok_or_else(|| anyhow::anyhow!("provider not found: {provider_id}"))?;
+        adapter_for_provider(&provider, &secrets)
+            .account_identity()
+            .transpose()
+    })
+    .await
+    .map_err(|err| format!("DNS provider account join error: {err}"))?
+    .map_err(|err| err.to_string())
 }
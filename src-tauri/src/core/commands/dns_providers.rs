@@ -1,5 +1,18 @@
+pub use super::dns_propagation::{
+    cancel_dns_propagation_watch, check_authoritative_propagation, watch_dns_propagation,
+};
 pub use super::dns_provider_creation::dns_provider_create;
+pub use super::dns_provider_debug::{clear_provider_debug_log, get_provider_debug_log};
+pub use super::dns_provider_discovery::discover_provider_zones;
+pub use super::dns_provider_env_import::import_dns_providers_from_env;
+pub use super::dns_provider_health::dns_provider_health_history;
 pub use super::dns_provider_management::{
-    dns_provider_delete, dns_provider_list, dns_provider_update, dns_resolve_provider,
+    dns_propagation_presets, dns_provider_delete, dns_provider_list, dns_provider_set_priority,
+    dns_provider_update, dns_resolve_provider,
+};
+pub use super::dns_provider_portability::{
+    dns_provider_export, dns_provider_import, preview_dns_provider_import,
+};
+pub use super::dns_provider_testing::{
+    cleanup_provider_test_records, dns_provider_account, dns_provider_test,
 };
-pub use super::dns_provider_testing::dns_provider_test;
@@ -1,7 +1,27 @@
 use crate::core::types::DnsProviderErrorCategory;
+use crate::issuance::dns_providers::errors::{MissingScopes, ProviderError};
 
 /// Categorizes a DNS error for consistent error reporting.
+///
+/// Provider API failures carry their category in a [`ProviderError`] and
+/// transport failures are recognized from the reqwest error; only errors with
+/// neither (e.g. resolver failures) fall back to matching the message.
 pub fn categorize_dns_error(error: &anyhow::Error) -> DnsProviderErrorCategory {
+    if let Some(provider_error) = error.downcast_ref::<ProviderError>() {
+        return provider_error.category;
+    }
+    if error.downcast_ref::<MissingScopes>().is_some() {
+        return DnsProviderErrorCategory::AuthError;
+    }
+    let transport_failure = error.chain().any(|cause| {
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|err| err.is_timeout() || err.is_connect())
+    });
+    if transport_failure {
+        return DnsProviderErrorCategory::NetworkError;
+    }
+
     let error_msg = error.to_string().to_lowercase();
 
     if error_msg.contains("auth")
@@ -34,7 +54,8 @@ pub fn categorize_dns_error(error: &anyhow::Error) -> DnsProviderErrorCategory {
 
 #[cfg(test)]
 mod tests {
-    use super::{DnsProviderErrorCategory, categorize_dns_error};
+    use super::{DnsProviderErrorCategory, ProviderError, categorize_dns_error};
+    use reqwest::StatusCode;
 
     #[test]
     fn categorizes_auth_errors() {
@@ -80,4 +101,20 @@ mod tests {
             DnsProviderErrorCategory::Unknown
         ));
     }
+
+    #[test]
+    fn prefers_provider_error_category_over_message() {
+        // The message mentions "not found", but the payload says the token is invalid.
+        let body = r#"{"id":"unauthorized","message":"Domain not found for this token."}"#;
+        let err = anyhow::Error::from(ProviderError::from_status(
+            "DigitalOcean",
+            StatusCode::NOT_FOUND,
+            Some(body),
+        ))
+        .context("Failed to set 1 record(s)");
+        assert!(matches!(
+            categorize_dns_error(&err),
+            DnsProviderErrorCategory::AuthError
+        ));
+    }
 }
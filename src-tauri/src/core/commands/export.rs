@@ -1,62 +1,593 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Duration, Utc};
+use openssl::pkey::PKey;
 use tauri::{async_runtime::spawn_blocking, State};
+use zeroize::Zeroizing;
 
+use crate::core::errors::CommandError;
+use crate::core::metrics::CommandTimer;
+use crate::core::read_only::ReadOnlyMode;
 use crate::core::types::{
-    CertificateSource, ExportCertificateRequest, ExportCertificateResponse,
+    BulkExportFilter, BulkExportReport, BulkExportRequest, BulkExportSkipped,
+    BulkExportedCertificate, CertificateQrCode, CertificateRecord, CertificateSource,
+    CertificateStatus, ExportCertificateRequest, ExportCertificateResponse, ExportProfile,
+    ExportVerification, ExportedFile, JobKind, KeyEscrowRequest, KeyEscrowResponse,
+    KeyExportPolicy, RecoverEscrowRequest,
+};
+use crate::distribution::export::{
+    export_pem_bundle, validate_folder_name, write_secure_file, ExportFileNames, ExportOptions,
+};
+use crate::distribution::key_policy::{
+    encrypt_private_key, ensure_key_export_allowed, policy_from_preferences,
+};
+use crate::distribution::naming::{render_file_names, render_folder_name, NameContext};
+use crate::distribution::qr::{render_qr_png, verification_payload};
+use crate::distribution::verify::verify_export_path;
+use crate::issuance::flow::pending_issuance_domains;
+use crate::secrets::escrow::{
+    combine_shares, open_share, seal_share, split_secret, EscrowShareFile,
 };
-use crate::distribution::export::{export_pem_bundle, ExportOptions};
 use crate::secrets::manager::SecretManager;
 use crate::storage::inventory::InventoryStore;
+use crate::storage::jobs::{new_job_id, JobStore};
+use crate::storage::preferences::PreferencesStore;
 
 #[tauri::command]
 pub async fn export_certificate_pem(
+    read_only: State<'_, ReadOnlyMode>,
     inventory: State<'_, InventoryStore>,
     secrets: State<'_, SecretManager>,
+    jobs: State<'_, JobStore>,
+    prefs: State<'_, PreferencesStore>,
     export_req: ExportCertificateRequest,
-) -> Result<ExportCertificateResponse, String> {
+) -> Result<ExportCertificateResponse, CommandError> {
+    read_only.ensure_writable()?;
     let inventory = inventory.inner().clone();
     let secrets = secrets.inner().clone();
-    spawn_blocking(move || {
+    let jobs = jobs.inner().clone();
+    let prefs = prefs.inner().clone();
+    let timer = CommandTimer::start("export_certificate_pem");
+    let result = spawn_blocking(move || {
         let record = inventory
             .get_certificate(&export_req.certificate_id)
             .map_err(|err| err.to_string())?
             .ok_or_else(|| format!("Certificate not found: {}", export_req.certificate_id))?;
 
-        if !matches!(record.source, CertificateSource::Managed) {
-            return Err("Export is only available for Managed certificates".to_string());
-        }
-
-        let chain_pem = record
-            .chain_pem
-            .ok_or_else(|| "Certificate chain PEM is missing for export".to_string())?;
-
-        let key_pem = if export_req.include_private_key {
-            let key_ref = record.managed_key_ref.ok_or_else(|| {
-                "Certificate does not have a managed key reference".to_string()
-            })?;
-            let bytes = secrets
-                .resolve_secret(&key_ref)
-                .map_err(|err| err.to_string())?;
-            Some(
-                String::from_utf8(bytes)
-                    .map_err(|_| "Managed key material was not valid UTF-8".to_string())?,
-            )
-        } else {
-            None
-        };
-
-        export_pem_bundle(
-            &chain_pem,
-            key_pem.as_deref(),
-            ExportOptions {
-                destination_dir: &export_req.destination_dir,
-                folder_name: &export_req.folder_name,
-                include_private_key: export_req.include_private_key,
-                overwrite: export_req.overwrite,
-                bundle: export_req.bundle,
-            },
+        let profile = ExportProfile {
+            folder_name_template: export_req.folder_name,
+            file_name_template: export_req.file_name_template,
+            bundle: export_req.bundle,
+            include_private_key: export_req.include_private_key,
+            overwrite: export_req.overwrite,
+        };
+        export_record(
+            &record,
+            &secrets,
+            &jobs,
+            &export_req.destination_dir,
+            &profile,
+            policy_from_preferences(&prefs),
+            export_req.key_passphrase.as_deref(),
         )
-        .map_err(|err| err.to_string())
     })
     .await
     .map_err(|err| format!("Export join error: {err}"))?
+    .map_err(CommandError::from);
+    timer.finish(result)
+}
+
+/// Exports every certificate matching the filter into its own subfolder.
+/// Failures are reported per certificate instead of aborting the batch.
+#[tauri::command]
+pub async fn export_certificates_bulk(
+    read_only: State<'_, ReadOnlyMode>,
+    inventory: State<'_, InventoryStore>,
+    secrets: State<'_, SecretManager>,
+    jobs: State<'_, JobStore>,
+    prefs: State<'_, PreferencesStore>,
+    bulk_req: BulkExportRequest,
+) -> Result<BulkExportReport, CommandError> {
+    read_only.ensure_writable()?;
+    let inventory = inventory.inner().clone();
+    let secrets = secrets.inner().clone();
+    let jobs = jobs.inner().clone();
+    let prefs = prefs.inner().clone();
+    let timer = CommandTimer::start("export_certificates_bulk");
+    let result = spawn_blocking(move || -> Result<BulkExportReport, anyhow::Error> {
+        let key_policy = policy_from_preferences(&prefs);
+        let expires_before = expiry_cutoff(&bulk_req.filter, Utc::now())?;
+        let records = inventory.refresh_statuses(&pending_issuance_domains())?;
+        let (planned, skipped) = plan_bulk_export(
+            &records,
+            &bulk_req.filter,
+            expires_before,
+            &bulk_req.profile.folder_name_template,
+        );
+        let mut report = BulkExportReport {
+            exported: Vec::new(),
+            skipped,
+        };
+
+        for record in planned {
+            let domain = NameContext::from_record(record).domain.to_string();
+            let skip = |reason: String| BulkExportSkipped {
+                certificate_id: record.id.clone(),
+                domain: domain.clone(),
+                reason,
+            };
+
+            let exported = export_record(
+                record,
+                &secrets,
+                &jobs,
+                &bulk_req.destination_dir,
+                &bulk_req.profile,
+                key_policy,
+                bulk_req.key_passphrase.as_deref(),
+            );
+            match exported {
+                Ok(ExportCertificateResponse::Success { output_dir, files }) => {
+                    report.exported.push(BulkExportedCertificate {
+                        certificate_id: record.id.clone(),
+                        domain,
+                        output_dir,
+                        files,
+                    });
+                }
+                Ok(ExportCertificateResponse::OverwriteRequired { existing_files, .. }) => {
+                    report.skipped.push(skip(format!(
+                        "files already exist: {}",
+                        existing_files.join(", ")
+                    )));
+                }
+                Err(reason) => report.skipped.push(skip(reason)),
+            }
+        }
+        Ok(report)
+    })
+    .await
+    .map_err(|err| format!("Bulk export join error: {err}"))?
+    .map_err(CommandError::from);
+    timer.finish(result)
+}
+
+/// Checks previously exported files (a folder or a single PEM file) against the inventory.
+/// Statuses are derived in memory, so verifying never writes.
+#[tauri::command]
+pub async fn verify_export(
+    inventory: State<'_, InventoryStore>,
+    path: String,
+) -> Result<ExportVerification, String> {
+    let inventory = inventory.inner().clone();
+    spawn_blocking(move || {
+        let records = inventory.current_statuses(&pending_issuance_domains())?;
+        verify_export_path(Path::new(&path), &records)
+    })
+    .await
+    .map_err(|err| format!("Verify export join error: {err}"))?
+    .map_err(|err| err.to_string())
+}
+
+/// Renders a certificate's fingerprint and validity as a QR code for
+/// out-of-band verification.
+#[tauri::command]
+pub async fn certificate_verification_qr(
+    inventory: State<'_, InventoryStore>,
+    id: String,
+) -> Result<CertificateQrCode, String> {
+    let inventory = inventory.inner().clone();
+    spawn_blocking(move || {
+        let record = inventory
+            .get_certificate(&id)?
+            .ok_or_else(|| anyhow!("Certificate not found: {id}"))?;
+        let payload = verification_payload(&record);
+        let png = render_qr_png(&payload)?;
+        Ok::<_, anyhow::Error>(CertificateQrCode { payload, png })
+    })
+    .await
+    .map_err(|err| format!("QR code join error: {err}"))?
+    .map_err(|err| err.to_string())
+}
+
+/// End of the expiry window of `filter`, rejecting windows too large to
+/// represent.
+fn expiry_cutoff(
+    filter: &BulkExportFilter,
+    now: DateTime<Utc>,
+) -> anyhow::Result<Option<DateTime<Utc>>> {
+    filter
+        .expiring_within_days
+        .map(|days| {
+            Duration::try_days(days)
+                .and_then(|window| now.checked_add_signed(window))
+                .ok_or_else(|| anyhow!("Expiry window of {days} days is out of range"))
+        })
+        .transpose()
+}
+
+/// Records selected by `filter`, each with a folder of its own. Records whose
+/// folder cannot be rendered or is already taken are skipped.
+fn plan_bulk_export<'a>(
+    records: &'a [CertificateRecord],
+    filter: &BulkExportFilter,
+    expires_before: Option<DateTime<Utc>>,
+    folder_name_template: &str,
+) -> (Vec<&'a CertificateRecord>, Vec<BulkExportSkipped>) {
+    let mut planned = Vec::new();
+    let mut skipped = Vec::new();
+    let mut used_folders = HashSet::new();
+    for record in records
+        .iter()
+        .filter(|record| matches_filter(record, filter, expires_before))
+    {
+        let ctx = NameContext::from_record(record);
+        let skip = |reason: String| BulkExportSkipped {
+            certificate_id: record.id.clone(),
+            domain: ctx.domain.to_string(),
+            reason,
+        };
+        match render_folder_name(folder_name_template, &ctx) {
+            Ok(folder) if used_folders.insert(folder.clone()) => planned.push(record),
+            Ok(folder) => skipped.push(skip(format!(
+                "another certificate in this export already uses folder {folder}"
+            ))),
+            Err(err) => skipped.push(skip(err.to_string())),
+        }
+    }
+    (planned, skipped)
+}
+
+fn matches_filter(
+    record: &CertificateRecord,
+    filter: &BulkExportFilter,
+    expires_before: Option<DateTime<Utc>>,
+) -> bool {
+    if let Some(tag) = filter.tag.as_deref().map(str::trim).filter(|tag| !tag.is_empty())
+        && !record.tags.iter().any(|candidate| candidate.eq_ignore_ascii_case(tag))
+    {
+        return false;
+    }
+    if let Some(cutoff) = expires_before
+        && record.not_after > cutoff
+    {
+        return false;
+    }
+    if filter.statuses.is_empty() {
+        !matches!(
+            record.status,
+            CertificateStatus::Superseded | CertificateStatus::Revoked
+        )
+    } else {
+        filter.statuses.contains(&record.status)
+    }
+}
+
+/// Exports one certificate, journaling the files it writes so a crash
+/// mid-export can be cleaned up or resumed on the next start.
+pub(crate) fn export_record(
+    record: &CertificateRecord,
+    secrets: &SecretManager,
+    jobs: &JobStore,
+    destination_dir: &str,
+    profile: &ExportProfile,
+    key_policy: KeyExportPolicy,
+    key_passphrase: Option<&str>,
+) -> Result<ExportCertificateResponse, String> {
+    if !matches!(record.source, CertificateSource::Managed) {
+        return Err("Export is only available for Managed certificates".to_string());
+    }
+
+    let ctx = NameContext::from_record(record);
+    let folder_name =
+        render_folder_name(&profile.folder_name_template, &ctx).map_err(|err| err.to_string())?;
+    let file_names = match profile.file_name_template.as_deref() {
+        Some(template) if !template.trim().is_empty() => {
+            render_file_names(template.trim(), &ctx).map_err(|err| err.to_string())?
+        }
+        _ => ExportFileNames::default(),
+    };
+
+    let chain_pem = record
+        .chain_pem
+        .as_deref()
+        .ok_or_else(|| "Certificate chain PEM is missing for export".to_string())?;
+
+    let key_pem = if profile.include_private_key {
+        let key_ref = record.managed_key_ref.as_deref().ok_or_else(|| {
+            "Certificate does not have a managed key reference".to_string()
+        })?;
+        let bytes = secrets
+            .resolve_secret(key_ref)
+            .map_err(|err| err.to_string())?;
+        Some(Zeroizing::new(
+            String::from_utf8(bytes)
+                .map_err(|_| "Managed key material was not valid UTF-8".to_string())?,
+        ))
+    } else {
+        None
+    };
+
+    let output_dir = Path::new(destination_dir).join(&folder_name);
+    let mut files = vec![&file_names.cert, &file_names.chain, &file_names.fullchain];
+    if profile.include_private_key {
+        files.push(&file_names.privkey);
+    }
+    let job_id = new_job_id();
+    jobs.start(
+        &job_id,
+        JobKind::Export,
+        &serde_json::json!({
+            "certificate_id": record.id,
+            "destination_dir": destination_dir,
+            "profile": profile,
+            "files": files
+                .iter()
+                .map(|name| output_dir.join(name).display().to_string())
+                .collect::<Vec<_>>(),
+        }),
+    )
+    .map_err(|err| err.to_string())?;
+
+    let result = export_pem_bundle(
+        chain_pem,
+        key_pem.as_ref().map(|key| key.as_str()),
+        ExportOptions {
+            destination_dir,
+            folder_name: &folder_name,
+            file_names,
+            include_private_key: profile.include_private_key,
+            overwrite: profile.overwrite,
+            bundle: profile.bundle.clone(),
+            key_policy,
+            key_passphrase,
+        },
+    )
+    .map_err(|err| err.to_string());
+    jobs.finish_quietly(&job_id);
+    result
+}
+
+/// Writes a managed private key as N-of-M passphrase-encrypted Shamir shares.
+#[tauri::command]
+pub async fn export_key_escrow(
+    read_only: State<'_, ReadOnlyMode>,
+    inventory: State<'_, InventoryStore>,
+    secrets: State<'_, SecretManager>,
+    prefs: State<'_, PreferencesStore>,
+    escrow_req: KeyEscrowRequest,
+) -> Result<KeyEscrowResponse, CommandError> {
+    read_only.ensure_writable()?;
+    let inventory = inventory.inner().clone();
+    let secrets = secrets.inner().clone();
+    let prefs = prefs.inner().clone();
+    spawn_blocking(move || -> Result<KeyEscrowResponse, anyhow::Error> {
+        // Every share is sealed to a passphrase
+        ensure_key_export_allowed(policy_from_preferences(&prefs), true)?;
+        let record = inventory
+            .get_certificate(&escrow_req.certificate_id)?
+            .ok_or_else(|| anyhow!("Certificate not found: {}", escrow_req.certificate_id))?;
+        let key_ref = record
+            .managed_key_ref
+            .as_deref()
+            .ok_or_else(|| anyhow!("Certificate does not have a managed key reference"))?;
+
+        let total = u8::try_from(escrow_req.recipients.len())
+            .map_err(|_| anyhow!("too many escrow recipients"))?;
+        let mut labels = HashSet::new();
+        for recipient in &escrow_req.recipients {
+            let label = recipient.label.trim();
+            if label.is_empty() {
+                return Err(anyhow!("every escrow recipient needs a label"));
+            }
+            if !labels.insert(label.to_ascii_lowercase()) {
+                return Err(anyhow!("duplicate escrow recipient: {label}"));
+            }
+        }
+
+        let folder_name =
+            render_folder_name(&escrow_req.folder_name, &NameContext::from_record(&record))?;
+        let output_dir = Path::new(&escrow_req.destination_dir).join(&folder_name);
+        let key_pem = Zeroizing::new(secrets.resolve_secret(key_ref)?);
+        let shares = split_secret(&key_pem, escrow_req.threshold, total)?;
+
+        // Seal everything before touching the filesystem so a bad passphrase
+        // does not leave a partial set of shares behind.
+        let mut sealed = Vec::with_capacity(shares.len());
+        for ((index, share), recipient) in shares.iter().zip(&escrow_req.recipients) {
+            sealed.push(seal_share(
+                &record.id,
+                recipient.label.trim(),
+                *index,
+                escrow_req.threshold,
+                total,
+                share,
+                &recipient.passphrase,
+            )?);
+        }
+
+        fs::create_dir_all(&output_dir).with_context(|| {
+            format!("failed to create escrow directory at {}", output_dir.display())
+        })?;
+        let mut files = Vec::with_capacity(sealed.len());
+        for share in sealed {
+            let path = output_dir.join(format!(
+                "share-{}-{}.json",
+                share.index,
+                file_safe_label(&share.recipient)
+            ));
+            write_secure_file(&path, serde_json::to_string_pretty(&share)?.as_bytes(), false)?;
+            files.push(ExportedFile {
+                label: share.recipient,
+                path: path.display().to_string(),
+            });
+        }
+
+        Ok(KeyEscrowResponse {
+            output_dir: output_dir.display().to_string(),
+            threshold: escrow_req.threshold,
+            files,
+        })
+    })
+    .await
+    .map_err(|err| format!("Key escrow join error: {err}"))?
+    .map_err(CommandError::from)
+}
+
+/// Recovers an escrowed private key from enough share files and passphrases.
+/// The key is written as the export policy allows, encrypted when a key
+/// passphrase is given.
+#[tauri::command]
+pub async fn recover_escrowed_key(
+    read_only: State<'_, ReadOnlyMode>,
+    prefs: State<'_, PreferencesStore>,
+    recover_req: RecoverEscrowRequest,
+) -> Result<ExportedFile, CommandError> {
+    read_only.ensure_writable()?;
+    let prefs = prefs.inner().clone();
+    spawn_blocking(move || -> Result<ExportedFile, anyhow::Error> {
+        ensure_key_export_allowed(
+            policy_from_preferences(&prefs),
+            recover_req.key_passphrase.is_some(),
+        )?;
+        let mut certificate_id: Option<String> = None;
+        let mut threshold = 0;
+        let mut shares = Vec::with_capacity(recover_req.shares.len());
+        for input in &recover_req.shares {
+            let raw = fs::read_to_string(&input.path)
+                .with_context(|| format!("failed to read escrow share {}", input.path))?;
+            let file: EscrowShareFile = serde_json::from_str(&raw)
+                .with_context(|| format!("{} is not an escrow share", input.path))?;
+            match &certificate_id {
+                Some(id) if *id != file.certificate_id => {
+                    return Err(anyhow!("escrow shares belong to different certificates"));
+                }
+                _ => certificate_id = Some(file.certificate_id.clone()),
+            }
+            threshold = file.threshold;
+            shares.push(open_share(&file, &input.passphrase)?);
+        }
+        if shares.len() < usize::from(threshold) {
+            return Err(anyhow!("{threshold} shares are required to recover this key"));
+        }
+
+        let key_pem = combine_shares(&shares)?;
+        PKey::private_key_from_pem(&key_pem)
+            .map_err(|_| anyhow!("recovered data is not a valid private key"))?;
+        let key_pem = match &recover_req.key_passphrase {
+            Some(passphrase) => {
+                let key_pem = std::str::from_utf8(&key_pem)
+                    .map_err(|_| anyhow!("recovered data is not a valid private key"))?;
+                Zeroizing::new(encrypt_private_key(key_pem, passphrase)?.as_bytes().to_vec())
+            }
+            None => key_pem,
+        };
+
+        validate_folder_name(&recover_req.folder_name)?;
+        let output_dir = Path::new(&recover_req.destination_dir).join(&recover_req.folder_name);
+        fs::create_dir_all(&output_dir).with_context(|| {
+            format!("failed to create recovery directory at {}", output_dir.display())
+        })?;
+        let path = output_dir.join("privkey.pem");
+        write_secure_file(&path, &key_pem, false)?;
+        Ok(ExportedFile {
+            label: "privkey".to_string(),
+            path: path.display().to_string(),
+        })
+    })
+    .await
+    .map_err(|err| format!("Key recovery join error: {err}"))?
+    .map_err(CommandError::from)
+}
+
+fn file_safe_label(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(
+        id: &str,
+        domain: &str,
+        days_left: i64,
+        status: CertificateStatus,
+    ) -> CertificateRecord {
+        CertificateRecord {
+            not_before: Utc::now() - Duration::days(30),
+            not_after: Utc::now() + Duration::days(days_left),
+            tags: vec!["prod".into()],
+            status,
+            ..CertificateRecord::test_record(id, &[domain])
+        }
+    }
+
+    #[test]
+    fn filter_matches_tag_expiry_window_and_live_statuses() {
+        let now = Utc::now();
+        let soon = record("soon", "a.example.com", 10, CertificateStatus::ExpiringSoon);
+        let later = record("later", "b.example.com", 80, CertificateStatus::Active);
+        let superseded = record("old", "a.example.com", 5, CertificateStatus::Superseded);
+
+        let filter = BulkExportFilter::default();
+        assert!(matches_filter(&soon, &filter, None));
+        assert!(!matches_filter(&superseded, &filter, None));
+
+        let filter = BulkExportFilter {
+            tag: Some(" PROD ".into()),
+            ..BulkExportFilter::default()
+        };
+        assert!(matches_filter(&later, &filter, None));
+        let filter = BulkExportFilter {
+            tag: Some("staging".into()),
+            ..BulkExportFilter::default()
+        };
+        assert!(!matches_filter(&later, &filter, None));
+
+        let filter = BulkExportFilter {
+            expiring_within_days: Some(30),
+            ..BulkExportFilter::default()
+        };
+        let cutoff = expiry_cutoff(&filter, now).unwrap();
+        assert!(matches_filter(&soon, &filter, cutoff));
+        assert!(!matches_filter(&later, &filter, cutoff));
+
+        let filter = BulkExportFilter {
+            statuses: vec![CertificateStatus::Superseded],
+            ..BulkExportFilter::default()
+        };
+        assert!(matches_filter(&superseded, &filter, None));
+        assert!(!matches_filter(&soon, &filter, None));
+    }
+
+    #[test]
+    fn expiry_windows_out_of_range_are_rejected() {
+        let filter = BulkExportFilter {
+            expiring_within_days: Some(i64::MAX),
+            ..BulkExportFilter::default()
+        };
+        assert!(expiry_cutoff(&filter, Utc::now()).is_err());
+        assert_eq!(expiry_cutoff(&BulkExportFilter::default(), Utc::now()).unwrap(), None);
+    }
+
+    #[test]
+    fn records_sharing_a_folder_are_skipped() {
+        let records = vec![
+            record("first", "a.example.com", 10, CertificateStatus::Active),
+            record("second", "a.example.com", 20, CertificateStatus::Active),
+            record("other", "b.example.com", 30, CertificateStatus::Active),
+        ];
+        let (planned, skipped) =
+            plan_bulk_export(&records, &BulkExportFilter::default(), None, "{domain}");
+        let ids: Vec<&str> = planned.iter().map(|record| record.id.as_str()).collect();
+        assert_eq!(ids, vec!["first", "other"]);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].certificate_id, "second");
+        assert!(skipped[0].reason.contains("a.example.com"));
+    }
 }
@@ -1,21 +1,36 @@
 use tauri::{async_runtime::spawn_blocking, State};
 
-use crate::core::types::CertificateRecord;
-use crate::domain::normalize_domains_for_display;
+use crate::core::errors::CommandError;
+use crate::core::metrics::CommandTimer;
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::types::{CertificateImport, CertificateRecord};
+use crate::domain::{domain_forms_of, normalize_domains_for_display};
+use crate::issuance::certificate_import;
+use crate::issuance::certificate_text::render_chain_text;
+use crate::issuance::flow::pending_issuance_domains;
 use crate::storage::inventory::InventoryStore;
 
 /// Retrieves all certificate records from the inventory.
-/// This command fetches all stored certificate data and returns it as a vector.
+/// Statuses are re-derived before the records are returned, and persisted
+/// when they changed unless read-only mode is active.
 ///
 /// # Returns
 /// A Result containing either a vector of CertificateRecord or an error string
 #[tauri::command]
 pub async fn list_certificates(
+    read_only: State<'_, ReadOnlyMode>,
     store: State<'_, InventoryStore>,
 ) -> Result<Vec<CertificateRecord>, String> {
+    let read_only = read_only.inner().clone();
     let store = store.inner().clone();
     spawn_blocking(move || {
-        store.list_certificates().map(|records| {
+        let pending = pending_issuance_domains();
+        let records = if read_only.is_enabled() {
+            store.current_statuses(&pending)
+        } else {
+            store.refresh_statuses(&pending)
+        };
+        records.map(|records| {
             records
                 .into_iter()
                 .map(record_for_display)
@@ -51,7 +66,67 @@ pub async fn get_certificate(
         .ok_or_else(|| format!("Certificate not found: {missing_id}"))
 }
 
-fn record_for_display(mut record: CertificateRecord) -> CertificateRecord {
+/// Renders a certificate's stored chain as `openssl x509 -text` output,
+/// leaf first, for copying into tickets and audits.
+#[tauri::command]
+pub async fn get_certificate_text(
+    store: State<'_, InventoryStore>,
+    id: String,
+) -> Result<String, String> {
+    let store = store.inner().clone();
+    spawn_blocking(move || {
+        let record = store
+            .get_certificate(&id)?
+            .ok_or_else(|| anyhow::anyhow!("Certificate not found: {id}"))?;
+        let chain_pem = record
+            .chain_pem
+            .ok_or_else(|| anyhow::anyhow!("Certificate {id} has no stored chain"))?;
+        render_chain_text(&chain_pem)
+    })
+    .await
+    .map_err(|err| format!("Certificate text join error: {err}"))?
+    .map_err(|err| err.to_string())
+}
+
+/// Adds a certificate from a PEM bundle, DER or PKCS#7 file to the inventory
+/// as an external certificate. The chain is stored as clean PEM, leaf first.
+#[tauri::command]
+pub async fn import_certificate(
+    read_only: State<'_, ReadOnlyMode>,
+    store: State<'_, InventoryStore>,
+    contents: Vec<u8>,
+) -> Result<CertificateImport, CommandError> {
+    read_only.ensure_writable()?;
+    let store = store.inner().clone();
+    let timer = CommandTimer::start("import_certificate");
+    let result = spawn_blocking(move || -> Result<CertificateImport, anyhow::Error> {
+        let (record, format) = certificate_import::import_certificate(&contents)?;
+        if let Some(existing) = store
+            .list_certificates()?
+            .into_iter()
+            .find(|existing| existing.fingerprint == record.fingerprint)
+        {
+            return Err(anyhow::anyhow!(
+                "This certificate is already in the inventory ({})",
+                existing.subjects.join(", ")
+            ));
+        }
+        store.insert_certificate(&record)?;
+        Ok(CertificateImport {
+            record: record_for_display(record),
+            format,
+        })
+    })
+    .await
+    .map_err(|err| format!("Certificate import join error: {err}"))?
+    .map_err(CommandError::from);
+    timer.finish(result)
+}
+
+/// Converts a record's names to their Unicode form for the frontend,
+/// keeping both forms of each in `name_forms`.
+pub(crate) fn record_for_display(mut record: CertificateRecord) -> CertificateRecord {
+    record.name_forms = domain_forms_of(record.sans.iter().chain(&record.domain_roots));
     record.subjects = normalize_domains_for_display(&record.subjects);
     record.sans = normalize_domains_for_display(&record.sans);
     record.domain_roots = normalize_domains_for_display(&record.domain_roots);
@@ -1,68 +1,369 @@
-use tauri::{async_runtime::spawn_blocking, State};
+use std::collections::HashMap;
 
+use anyhow::anyhow;
+use tauri::{async_runtime::spawn_blocking, AppHandle, Emitter, State};
+
+use crate::core::errors::CommandError;
+use crate::core::metrics::CommandTimer;
+use crate::core::read_only::ReadOnlyMode;
 use crate::core::types::{
-    CertificateRecord, CompleteIssuanceRequest, StartIssuanceRequest, StartIssuanceResponse,
+    CertificateRecord, CertificateSource, ClockSkewCheck, CompleteIssuanceRequest,
+    DnsChallengeInput, DomainIssue, ParsedDomainList, PreflightReport, PreparedDnsChallenge,
+    PromoteCertificateRequest, StartIssuanceRequest, StartIssuanceResponse,
 };
-use crate::domain::normalize_domains_for_display;
+use crate::domain::{domain_forms, parse_domain_list as parse_pasted_domains};
+use crate::issuance::acme_workflow::check_domain_list;
+use crate::issuance::auto_complete;
+use crate::issuance::clock_skew::check_clock_skew;
+use crate::issuance::dns::DnsRecordInstruction;
+use crate::issuance::dns_providers::cached_adapter_for_provider;
 use crate::issuance::flow::{complete_managed_dns01, start_managed_dns01};
+use crate::issuance::issuer_selection::{
+    is_staging_certificate, promotion_issuer, suggest_issuer_for_domains,
+};
+use crate::issuance::order_polling::{PollSettings, ISSUANCE_PROGRESS_EVENT};
+use crate::issuance::preflight;
+use crate::issuance::propagation_presets::{PropagationWaits, MANUAL_PROVIDER_TYPE};
+use crate::issuance::propagation_resolvers::ResolverSet;
+use crate::issuance::solvers::{plan_challenge_record, PlannedChallenge};
 use crate::secrets::manager::SecretManager;
-use crate::storage::{dns::DnsConfigStore, inventory::InventoryStore, issuer::IssuerConfigStore};
+use crate::storage::{
+    dns::DnsConfigStore, inventory::InventoryStore, issuer::IssuerConfigStore, jobs::JobStore,
+    preferences::PreferencesStore,
+};
+
+use super::inventory::record_for_display;
+
+/// Compares the local clock with the issuer's ACME server so the UI can warn
+/// about skew before an order is placed.
+#[tauri::command]
+pub async fn check_issuer_clock_skew(
+    issuer_store: State<'_, IssuerConfigStore>,
+    issuer_id: String,
+) -> Result<ClockSkewCheck, String> {
+    let issuer_store = issuer_store.inner().clone();
+    spawn_blocking(move || {
+        let issuer = issuer_store
+            .get(&issuer_id)?
+            .ok_or_else(|| anyhow::anyhow!("Issuer not found: {issuer_id}"))?;
+        check_clock_skew(&issuer.directory_url)
+    })
+    .await
+    .map_err(|err| format!("Clock check join error: {err}"))?
+    .map_err(|err| err.to_string())
+}
+
+/// Checks provider resolution, CAA, NS delegation, the challenge records a
+/// dry run would write, existing certificates and rate-limit headroom for
+/// each domain so the UI can show a go/no-go summary before issuance starts.
+/// Without an issuer id, the issuer selection policy picks one as
+/// `start_managed_issuance` would.
+#[tauri::command]
+pub async fn preflight_domains(
+    issuer_store: State<'_, IssuerConfigStore>,
+    dns_store: State<'_, DnsConfigStore>,
+    inventory: State<'_, InventoryStore>,
+    preferences: State<'_, PreferencesStore>,
+    secrets: State<'_, SecretManager>,
+    domains: Vec<String>,
+    issuer_id: Option<String>,
+) -> Result<PreflightReport, String> {
+    let issuer_store = issuer_store.inner().clone();
+    let dns_store = dns_store.inner().clone();
+    let inventory = inventory.inner().clone();
+    let preferences = preferences.inner().clone();
+    let secrets = secrets.inner().clone();
+    spawn_blocking(move || {
+        let issuer_id = match issuer_id.filter(|id| !id.trim().is_empty()) {
+            Some(issuer_id) => issuer_id,
+            None => {
+                let suggestion = suggest_issuer_for_domains(
+                    domains.clone(),
+                    &issuer_store,
+                    &inventory,
+                    &preferences,
+                )?;
+                suggestion.issuer_id
+            }
+        };
+        preflight::preflight_domains(
+            domains,
+            &issuer_id,
+            &issuer_store,
+            &dns_store,
+            &inventory,
+            &secrets,
+        )
+    })
+    .await
+    .map_err(|err| format!("Preflight join error: {err}"))?
+    .map_err(|err| err.to_string())
+}
+
+/// Builds the challenge records for several names in one round trip,
+/// placed as issuance would place them and marked as written by a provider
+/// or left to the user. Nothing is written to DNS.
+#[tauri::command]
+pub async fn prepare_dns_challenges(
+    dns_store: State<'_, DnsConfigStore>,
+    secrets: State<'_, SecretManager>,
+    challenges: Vec<DnsChallengeInput>,
+) -> Result<Vec<PreparedDnsChallenge>, String> {
+    let dns_store = dns_store.inner().clone();
+    let secrets = secrets.inner().clone();
+    spawn_blocking(move || {
+        challenges
+            .into_iter()
+            .map(|challenge| {
+                let PlannedChallenge {
+                    mut record,
+                    resolution,
+                    ..
+                } = plan_challenge_record(
+                    &dns_store,
+                    &secrets,
+                    &challenge.domain,
+                    challenge.txt_value,
+                )
+                .map_err(|err| anyhow!("{}: {err}", challenge.domain))?;
+                let automated = resolution.ambiguous.len() <= 1
+                    && resolution
+                        .provider
+                        .as_ref()
+                        .is_some_and(|provider| provider.provider_type != MANUAL_PROVIDER_TYPE);
+                if automated && let Some(provider) = resolution.provider.as_ref() {
+                    let adapter = cached_adapter_for_provider(provider, &secrets, &dns_store);
+                    record.adapter = provider.provider_type.clone();
+                    record.cname_target = adapter.cname_target(&record.record_name);
+                }
+                record.record_name_forms = Some(domain_forms(&record.record_name));
+                Ok(PreparedDnsChallenge {
+                    domain: challenge.domain,
+                    record,
+                    automated,
+                    provider_id: resolution.provider.as_ref().map(|p| p.id.clone()),
+                    provider_label: resolution.provider.map(|p| p.label),
+                    ambiguous_provider_ids: resolution
+                        .ambiguous
+                        .into_iter()
+                        .map(|p| p.id)
+                        .collect(),
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+    })
+    .await
+    .map_err(|err| format!("Prepare challenges join error: {err}"))?
+    .map_err(|err| err.to_string())
+}
+
+/// Lists what is wrong with each malformed name of a pasted domain list,
+/// with suggested corrections; empty when every name is valid.
+#[tauri::command]
+pub async fn validate_domains(domains: Vec<String>) -> Result<Vec<DomainIssue>, String> {
+    Ok(check_domain_list(&domains).1)
+}
+
+/// Extracts certificate names from freeform pasted text (lists, URLs, nginx
+/// `server_name` lines) and lists the entries that could not be used.
+#[tauri::command]
+pub async fn parse_domain_list(text: String) -> Result<ParsedDomainList, String> {
+    Ok(parse_pasted_domains(&text))
+}
 
 /// Starts a managed-key ACME issuance and returns DNS-01 instructions plus a request id.
+/// Without an issuer id, the issuer selection policy picks one for the domains.
+/// With `auto_complete`, the issuance completes itself once the records added
+/// by hand propagate, reporting through `issuance-auto-complete` events.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn start_managed_issuance(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
     issuer_store: State<'_, IssuerConfigStore>,
     dns_store: State<'_, DnsConfigStore>,
     secrets: State<'_, SecretManager>,
+    jobs: State<'_, JobStore>,
+    inventory: State<'_, InventoryStore>,
+    preferences: State<'_, PreferencesStore>,
     start_req: StartIssuanceRequest,
-) -> Result<StartIssuanceResponse, String> {
+) -> Result<StartIssuanceResponse, CommandError> {
+    read_only.ensure_writable()?;
     let issuer_store = issuer_store.inner().clone();
     let dns_store = dns_store.inner().clone();
     let secrets = secrets.inner().clone();
-    spawn_blocking(move || {
+    let jobs = jobs.inner().clone();
+    let inventory = inventory.inner().clone();
+    let preferences = preferences.inner().clone();
+    let auto_complete = start_req.auto_complete;
+    let timer = CommandTimer::start("start_managed_issuance");
+    let result = spawn_blocking(move || {
+        let issuer_id = match start_req.issuer_id.filter(|id| !id.trim().is_empty()) {
+            Some(issuer_id) => issuer_id,
+            None => {
+                let suggestion = suggest_issuer_for_domains(
+                    start_req.domains.clone(),
+                    &issuer_store,
+                    &inventory,
+                    &preferences,
+                )?;
+                log::info!(
+                    "[issuance] issuer {} chosen by policy ({:?})",
+                    suggestion.issuer_id,
+                    suggestion.reason
+                );
+                suggestion.issuer_id
+            }
+        };
         start_managed_dns01(
             start_req.domains,
-            start_req.issuer_id,
+            issuer_id.clone(),
             start_req.key_algorithm,
             start_req.key_size,
             start_req.key_curve,
+            None,
+            start_req.solvers,
             &issuer_store,
             &dns_store,
             &secrets,
+            &jobs,
         )
         .map(|(request_id, dns_records)| StartIssuanceResponse {
             request_id,
-            dns_records,
+            issuer_id,
+            dns_records: instructions_for_display(dns_records),
         })
     })
     .await
     .map_err(|err| format!("Start issuance join error: {err}"))?
-    .map_err(|err: anyhow::Error| err.to_string())
+    .map_err(CommandError::from);
+    if auto_complete && let Ok(response) = &result {
+        auto_complete::spawn(app, response.request_id.clone(), &response.dns_records);
+    }
+    timer.finish(result)
+}
+
+/// Re-issues a certificate obtained from a staging issuer with a production
+/// issuer, using the same names and key parameters. Returns DNS-01
+/// instructions like `start_managed_issuance`; the record produced by
+/// `complete_managed_issuance` links back to the staging certificate.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn promote_to_production(
+    read_only: State<'_, ReadOnlyMode>,
+    issuer_store: State<'_, IssuerConfigStore>,
+    dns_store: State<'_, DnsConfigStore>,
+    secrets: State<'_, SecretManager>,
+    jobs: State<'_, JobStore>,
+    inventory: State<'_, InventoryStore>,
+    promote_req: PromoteCertificateRequest,
+) -> Result<StartIssuanceResponse, CommandError> {
+    read_only.ensure_writable()?;
+    let issuer_store = issuer_store.inner().clone();
+    let dns_store = dns_store.inner().clone();
+    let secrets = secrets.inner().clone();
+    let jobs = jobs.inner().clone();
+    let inventory = inventory.inner().clone();
+    let timer = CommandTimer::start("promote_to_production");
+    let result = spawn_blocking(move || -> Result<StartIssuanceResponse, anyhow::Error> {
+        let record = inventory
+            .get_certificate(&promote_req.certificate_id)?
+            .ok_or_else(|| anyhow!("Certificate not found: {}", promote_req.certificate_id))?;
+        if !matches!(record.source, CertificateSource::Managed) {
+            return Err(anyhow!("Only certificates issued by SSLBoard can be promoted"));
+        }
+        let issuers = issuer_store.list()?;
+        if !is_staging_certificate(&record, &issuers) {
+            return Err(anyhow!("Certificate was not issued by a staging issuer"));
+        }
+        let issuer_id = promotion_issuer(&issuers, promote_req.issuer_id.as_deref())?
+            .issuer_id
+            .clone();
+
+        let (request_id, dns_records) = start_managed_dns01(
+            record.sans.clone(),
+            issuer_id.clone(),
+            record.key_algorithm.clone(),
+            record.key_size,
+            record.key_curve.clone(),
+            Some(record.id.clone()),
+            HashMap::new(),
+            &issuer_store,
+            &dns_store,
+            &secrets,
+            &jobs,
+        )?;
+        Ok(StartIssuanceResponse {
+            request_id,
+            issuer_id,
+            dns_records: instructions_for_display(dns_records),
+        })
+    })
+    .await
+    .map_err(|err| format!("Promote certificate join error: {err}"))?
+    .map_err(CommandError::from);
+    timer.finish(result)
 }
 
 /// Completes a managed-key ACME issuance after DNS-01 is satisfied.
+/// Progress is emitted as `issuance-progress` events; a stage that runs out of
+/// time fails with a `timed_out` error and can be resumed by calling again.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn complete_managed_issuance(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
     inventory: State<'_, InventoryStore>,
+    issuer_store: State<'_, IssuerConfigStore>,
     secrets: State<'_, SecretManager>,
     dns_store: State<'_, DnsConfigStore>,
+    preferences: State<'_, PreferencesStore>,
+    jobs: State<'_, JobStore>,
     complete_req: CompleteIssuanceRequest,
-) -> Result<CertificateRecord, String> {
+) -> Result<CertificateRecord, CommandError> {
+    read_only.ensure_writable()?;
     let inventory = inventory.inner().clone();
+    let issuer_store = issuer_store.inner().clone();
     let secrets = secrets.inner().clone();
     let dns_store = dns_store.inner().clone();
-    spawn_blocking(move || {
-        complete_managed_dns01(&complete_req.request_id, &inventory, &secrets, &dns_store)
+    let jobs = jobs.inner().clone();
+    let settings = PollSettings::from_preferences(preferences.inner());
+    let waits = PropagationWaits::from_preferences(preferences.inner())
+        .with_request(complete_req.propagation_wait);
+    let resolvers = ResolverSet::from_preferences(preferences.inner());
+    let timer = CommandTimer::start("complete_managed_issuance");
+    let result = spawn_blocking(move || {
+        let report = |progress| {
+            if let Err(err) = app.emit(ISSUANCE_PROGRESS_EVENT, &progress) {
+                log::warn!("[issuance] failed to emit progress: {err}");
+            }
+        };
+        complete_managed_dns01(
+            &complete_req.request_id,
+            &inventory,
+            &issuer_store,
+            &secrets,
+            &dns_store,
+            &jobs,
+            settings,
+            &waits,
+            &resolvers,
+            &report,
+        )
     })
         .await
         .map_err(|err| format!("Complete issuance join error: {err}"))?
-        .map_err(|err: anyhow::Error| err.to_string())
-        .map(record_for_display)
+        .map_err(CommandError::from)
+        .map(record_for_display);
+    timer.finish(result)
 }
 
-fn record_for_display(mut record: CertificateRecord) -> CertificateRecord {
-    record.subjects = normalize_domains_for_display(&record.subjects);
-    record.sans = normalize_domains_for_display(&record.sans);
-    record.domain_roots = normalize_domains_for_display(&record.domain_roots);
-    record
+fn instructions_for_display(records: Vec<DnsRecordInstruction>) -> Vec<DnsRecordInstruction> {
+    records
+        .into_iter()
+        .map(|mut record| {
+            record.record_name_forms = Some(domain_forms(&record.record_name));
+            record
+        })
+        .collect()
 }
@@ -0,0 +1,75 @@
+use std::{collections::HashSet, fs};
+
+use anyhow::{anyhow, Context};
+use chrono::Utc;
+use tauri::{async_runtime::spawn_blocking, State};
+
+use crate::core::errors::CommandError;
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::types::{IssuanceArtifactExport, IssuanceArtifactSummary, ProviderDebugEntry};
+use crate::issuance::artifacts;
+use crate::issuance::dns_providers::captured_calls;
+use crate::storage::dns::DnsConfigStore;
+
+const EXPORT_FORMAT: &str = "sslboard-issuance-artifacts";
+const EXPORT_VERSION: u32 = 1;
+
+/// Lists the issuance attempts recorded since the app started that did not
+/// end with a certificate, newest first.
+#[tauri::command]
+pub async fn list_issuance_artifacts() -> Result<Vec<IssuanceArtifactSummary>, String> {
+    Ok(artifacts::summaries())
+}
+
+/// Writes everything recorded about an issuance attempt to a JSON file:
+/// the ACME order and authorizations, the challenge records, the errors
+/// raised and, for providers capturing API calls, the failed calls.
+/// Credentials are redacted. Returns the path written.
+#[tauri::command]
+pub async fn export_issuance_artifacts(
+    read_only: State<'_, ReadOnlyMode>,
+    dns_store: State<'_, DnsConfigStore>,
+    request_id: String,
+    path: String,
+) -> Result<String, CommandError> {
+    read_only.ensure_writable()?;
+    let dns_store = dns_store.inner().clone();
+    spawn_blocking(move || -> Result<String, anyhow::Error> {
+        let artifacts = artifacts::get(&request_id)
+            .ok_or_else(|| anyhow!("No issuance attempt {request_id} was recorded"))?;
+        let mut provider_ids = HashSet::new();
+        let mut provider_calls: Vec<ProviderDebugEntry> = Vec::new();
+        for domain in &artifacts.domains {
+            let Ok(resolution) = dns_store.resolve_provider_for_domain(domain) else {
+                continue;
+            };
+            let Some(provider) = resolution.provider else {
+                continue;
+            };
+            if !provider_ids.insert(provider.id.clone()) {
+                continue;
+            }
+            provider_calls.extend(captured_calls(&provider.id).into_iter().filter(|call| {
+                call.captured_at >= artifacts.started_at
+                    && (call.error.is_some() || call.status.is_some_and(|status| status >= 400))
+            }));
+        }
+        provider_calls.sort_by_key(|call| call.captured_at);
+
+        let file = IssuanceArtifactExport {
+            format: EXPORT_FORMAT.to_string(),
+            version: EXPORT_VERSION,
+            exported_at: Utc::now(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            artifacts,
+            provider_calls,
+        };
+        fs::write(&path, serde_json::to_string_pretty(&file)?)
+            .with_context(|| format!("failed to write {path}"))?;
+        log::info!("[issuance] exported artifacts of {request_id} to {path}");
+        Ok(path)
+    })
+    .await
+    .map_err(|err| format!("Issuance artifact export join error: {err}"))?
+    .map_err(CommandError::from)
+}
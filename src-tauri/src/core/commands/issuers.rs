@@ -1,18 +1,29 @@
 use tauri::{async_runtime::spawn_blocking, State};
 use log::debug;
 
+use crate::core::confirmation_pin::ConfirmationPin;
+use crate::core::errors::{CommandError, ConflictError};
+use crate::core::mappers::{environment_to_string, issuer_record_to_dto, issuer_type_to_string};
+use crate::core::messages::MessageCode;
+use crate::core::read_only::ReadOnlyMode;
 use crate::core::types::{
-    CreateIssuerRequest, DeleteIssuerRequest, IssuerConfigDto, IssuerEnvironment, IssuerType,
-    SelectIssuerRequest, UpdateIssuerRequest,
+    ConflictEntity, ConflictResolution, CreateIssuerRequest, DeleteIssuerRequest,
+    DuplicateConflict, IssuerConfigDto, IssuerSuggestion, IssuerType, SelectIssuerRequest,
+    UpdateIssuerRequest,
 };
-use crate::issuance::acme::generate_account_key_pem;
-use crate::secrets::{
-    manager::{SecretError, SecretManager},
-    types::SecretKind,
+use crate::issuance::{
+    acme::create_account_key, ca_pinning::normalize_pins,
+    issuer_selection::suggest_issuer_for_domains,
+};
+use crate::secrets::manager::{SecretError, SecretManager};
+use crate::storage::{
+    inventory::InventoryStore,
+    issuer::{IssuerConfigStore, IssuerParams},
+    preferences::PreferencesStore,
 };
-use crate::storage::issuer::IssuerConfigStore;
 
-/// Lists issuer configurations, including the selected issuer.
+/// Lists issuer configurations, including the selected issuer and each
+/// issuer's ACME statistics.
 #[tauri::command]
 pub async fn list_issuers(
     store: State<'_, IssuerConfigStore>,
@@ -22,7 +33,17 @@ pub async fn list_issuers(
     spawn_blocking(move || -> Result<Vec<IssuerConfigDto>, anyhow::Error> {
         debug!("[list_issuers] querying store");
         let records = store.list()?;
-        let result: Vec<IssuerConfigDto> = records.into_iter().map(issuer_record_to_dto).collect();
+        let mut stats = store.stats()?;
+        let result: Vec<IssuerConfigDto> = records
+            .into_iter()
+            .map(|record| {
+                let issuer_stats = stats.remove(&record.issuer_id).unwrap_or_default();
+                IssuerConfigDto {
+                    stats: issuer_stats,
+                    ..issuer_record_to_dto(record)
+                }
+            })
+            .collect();
         debug!("[list_issuers] returning {} issuers", result.len());
         Ok(result)
     })
@@ -34,31 +55,53 @@ pub async fn list_issuers(
 /// Sets the selected issuer id.
 #[tauri::command]
 pub async fn select_issuer(
+    read_only: State<'_, ReadOnlyMode>,
     store: State<'_, IssuerConfigStore>,
     select_req: SelectIssuerRequest,
-) -> Result<IssuerConfigDto, String> {
+) -> Result<IssuerConfigDto, CommandError> {
+    read_only.ensure_writable()?;
     let store = store.inner().clone();
-    spawn_blocking(move || {
+    spawn_blocking(move || -> Result<IssuerConfigDto, anyhow::Error> {
         let record = store.set_selected(&select_req.issuer_id)?;
         Ok(issuer_record_to_dto(record))
     })
     .await
     .map_err(|err| format!("Select issuer join error: {err}"))?
-    .map_err(|err: anyhow::Error| err.to_string())
+    .map_err(CommandError::from)
+}
+
+/// Issuer the selection policy would use for `domains`, so the UI can
+/// preselect it and explain why.
+#[tauri::command]
+pub async fn suggest_issuer(
+    store: State<'_, IssuerConfigStore>,
+    inventory: State<'_, InventoryStore>,
+    preferences: State<'_, PreferencesStore>,
+    domains: Vec<String>,
+) -> Result<IssuerSuggestion, String> {
+    let store = store.inner().clone();
+    let inventory = inventory.inner().clone();
+    let preferences = preferences.inner().clone();
+    spawn_blocking(move || suggest_issuer_for_domains(domains, &store, &inventory, &preferences))
+        .await
+        .map_err(|err| format!("Suggest issuer join error: {err}"))?
+        .map_err(|err| err.to_string())
 }
 
 /// Creates a new issuer entry.
 #[tauri::command]
 pub async fn create_issuer(
+    read_only: State<'_, ReadOnlyMode>,
     store: State<'_, IssuerConfigStore>,
     secrets: State<'_, SecretManager>,
     create_req: CreateIssuerRequest,
-) -> Result<IssuerConfigDto, String> {
+) -> Result<IssuerConfigDto, CommandError> {
+    read_only.ensure_writable()?;
     let store = store.inner().clone();
     let secrets = secrets.inner().clone();
-    spawn_blocking(move || {
+    spawn_blocking(move || -> Result<IssuerConfigDto, anyhow::Error> {
         if create_req.label.trim().is_empty() {
-            return Err(anyhow::anyhow!("issuer label is required"));
+            return Err(MessageCode::IssuerLabelRequired.into());
         }
         validate_acme_requirements(
             &create_req.issuer_type,
@@ -66,22 +109,40 @@ pub async fn create_issuer(
             create_req.tos_agreed,
         )?;
         if create_req.directory_url.trim().is_empty() {
-            return Err(anyhow::anyhow!("directory URL is required"));
+            return Err(MessageCode::DirectoryUrlRequired.into());
         }
+        let ca_pins = normalize_pins(create_req.ca_pins)?;
 
-        let account_key_ref = match create_req.issuer_type {
-            IssuerType::Acme => {
-                let pem = generate_account_key_pem()
-                    .map_err(|err| anyhow::anyhow!("failed to generate ACME account key: {err}"))?;
-                let record = secrets
-                    .create_secret(
-                        SecretKind::AcmeAccountKey,
-                        "ACME account key".into(),
-                        pem,
-                    )
-                    .map_err(|err| anyhow::anyhow!(err.to_string()))?;
-                Some(record.id)
+        let duplicates = store.find_by_directory_url(&create_req.directory_url)?;
+        if let Some(existing) = duplicates.first() {
+            match create_req.on_conflict {
+                None => {
+                    return Err(ConflictError {
+                        message: format!(
+                            "issuer \"{}\" already uses this directory URL; use it or create anyway",
+                            existing.label
+                        ),
+                        conflicts: duplicates
+                            .iter()
+                            .map(|record| DuplicateConflict {
+                                entity: ConflictEntity::Issuer,
+                                existing_id: record.issuer_id.clone(),
+                                existing_label: record.label.clone(),
+                                overlapping: vec![record.directory_url.clone()],
+                                can_merge: true,
+                            })
+                            .collect(),
+                    }
+                    .into());
+                }
+                Some(ConflictResolution::CreateAnyway) => {}
+                // Merging keeps the existing issuer and its ACME account as-is.
+                Some(ConflictResolution::Merge) => return Ok(issuer_record_to_dto(existing.clone())),
             }
+        }
+
+        let account_key_ref = match create_req.issuer_type {
+            IssuerType::Acme => Some(create_account_key(&secrets)?),
         };
 
         let record = store.create(
@@ -92,26 +153,34 @@ pub async fn create_issuer(
             create_req.contact_email,
             account_key_ref,
             create_req.tos_agreed,
+            &IssuerParams {
+                preferred_chain: create_req.preferred_chain,
+                ca_pins,
+                enforce_ca_pins: create_req.enforce_ca_pins,
+                disabled: false,
+            },
         )?;
         Ok(issuer_record_to_dto(record))
     })
     .await
     .map_err(|err| format!("Create issuer join error: {err}"))?
-    .map_err(|err: anyhow::Error| err.to_string())
+    .map_err(CommandError::from)
 }
 
 /// Updates an existing issuer entry.
 #[tauri::command]
 pub async fn update_issuer(
+    read_only: State<'_, ReadOnlyMode>,
     store: State<'_, IssuerConfigStore>,
     secrets: State<'_, SecretManager>,
     update_req: UpdateIssuerRequest,
-) -> Result<IssuerConfigDto, String> {
+) -> Result<IssuerConfigDto, CommandError> {
+    read_only.ensure_writable()?;
     let store = store.inner().clone();
     let secrets = secrets.inner().clone();
-    spawn_blocking(move || {
+    spawn_blocking(move || -> Result<IssuerConfigDto, anyhow::Error> {
         if update_req.label.trim().is_empty() {
-            return Err(anyhow::anyhow!("issuer label is required"));
+            return Err(MessageCode::IssuerLabelRequired.into());
         }
         validate_acme_requirements(
             &IssuerType::Acme,
@@ -119,7 +188,7 @@ pub async fn update_issuer(
             update_req.tos_agreed,
         )?;
         if update_req.directory_url.trim().is_empty() {
-            return Err(anyhow::anyhow!("directory URL is required"));
+            return Err(MessageCode::DirectoryUrlRequired.into());
         }
 
         let existing = store
@@ -132,18 +201,15 @@ pub async fn update_issuer(
             update_req.directory_url,
             update_req.contact_email,
             update_req.tos_agreed,
+            &IssuerParams {
+                preferred_chain: update_req.preferred_chain,
+                ca_pins: normalize_pins(update_req.ca_pins)?,
+                enforce_ca_pins: update_req.enforce_ca_pins,
+                disabled: update_req.disabled,
+            },
         )?;
         let record = if existing.account_key_ref.is_none() {
-            let pem = generate_account_key_pem()
-                .map_err(|err| anyhow::anyhow!("failed to generate ACME account key: {err}"))?;
-            let secret_record = secrets
-                .create_secret(
-                    SecretKind::AcmeAccountKey,
-                    "ACME account key".into(),
-                    pem,
-                )
-                .map_err(|err| anyhow::anyhow!(err.to_string()))?;
-            store.set_account_key_ref(&update_req.issuer_id, secret_record.id)?
+            store.set_account_key_ref(&update_req.issuer_id, create_account_key(&secrets)?)?
         } else {
             record
         };
@@ -151,19 +217,24 @@ pub async fn update_issuer(
     })
     .await
     .map_err(|err| format!("Update issuer join error: {err}"))?
-    .map_err(|err: anyhow::Error| err.to_string())
+    .map_err(CommandError::from)
 }
 
 /// Deletes an issuer entry and its associated account key if present.
+/// Requires the confirmation PIN when one is set.
 #[tauri::command]
 pub async fn delete_issuer(
+    read_only: State<'_, ReadOnlyMode>,
+    pin: State<'_, ConfirmationPin>,
     store: State<'_, IssuerConfigStore>,
     secrets: State<'_, SecretManager>,
     delete_req: DeleteIssuerRequest,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
+    read_only.ensure_writable()?;
+    pin.ensure_confirmed(delete_req.confirmation_pin.as_deref())?;
     let store = store.inner().clone();
     let secrets = secrets.inner().clone();
-    spawn_blocking(move || {
+    spawn_blocking(move || -> Result<String, anyhow::Error> {
         let record = store
             .get(&delete_req.issuer_id)?
             .ok_or_else(|| anyhow::anyhow!("issuer not found: {}", delete_req.issuer_id))?;
@@ -179,43 +250,7 @@ pub async fn delete_issuer(
     })
     .await
     .map_err(|err| format!("Delete issuer join error: {err}"))?
-    .map_err(|err: anyhow::Error| err.to_string())
-}
-
-fn issuer_record_to_dto(record: crate::storage::issuer::IssuerConfigRecord) -> IssuerConfigDto {
-    let environment = match record.environment.as_str() {
-        "production" => IssuerEnvironment::Production,
-        _ => IssuerEnvironment::Staging,
-    };
-    let issuer_type = match record.issuer_type.as_str() {
-        "acme" => IssuerType::Acme,
-        _ => IssuerType::Acme,
-    };
-
-    IssuerConfigDto {
-        issuer_id: record.issuer_id,
-        label: record.label,
-        directory_url: record.directory_url,
-        environment,
-        issuer_type,
-        contact_email: record.contact_email,
-        account_key_ref: record.account_key_ref,
-        tos_agreed: record.tos_agreed,
-        is_selected: record.is_selected,
-    }
-}
-
-fn environment_to_string(environment: &IssuerEnvironment) -> String {
-    match environment {
-        IssuerEnvironment::Production => "production".to_string(),
-        IssuerEnvironment::Staging => "staging".to_string(),
-    }
-}
-
-fn issuer_type_to_string(issuer_type: &IssuerType) -> String {
-    match issuer_type {
-        IssuerType::Acme => "acme".to_string(),
-    }
+    .map_err(CommandError::from)
 }
 
 fn validate_acme_requirements(
@@ -225,12 +260,10 @@ fn validate_acme_requirements(
 ) -> Result<(), anyhow::Error> {
     if matches!(issuer_type, IssuerType::Acme) {
         if contact_email.is_none_or(|email| email.trim().is_empty()) {
-            return Err(anyhow::anyhow!("contact email is required for ACME issuers"));
+            return Err(MessageCode::ContactEmailRequired.into());
         }
         if !tos_agreed {
-            return Err(anyhow::anyhow!(
-                "Terms of Service acceptance is required for ACME issuers"
-            ));
+            return Err(MessageCode::TosAcceptanceRequired.into());
         }
     }
     Ok(())
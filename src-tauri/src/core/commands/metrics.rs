@@ -0,0 +1,32 @@
+use tauri::{async_runtime::spawn_blocking, State};
+
+use crate::core::errors::CommandError;
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::types::MetricSummary;
+use crate::storage::metrics::MetricsStore;
+
+/// Returns the local call metrics of commands and DNS providers.
+#[tauri::command]
+pub async fn get_metrics(
+    metrics: State<'_, MetricsStore>,
+) -> Result<Vec<MetricSummary>, String> {
+    let metrics = metrics.inner().clone();
+    spawn_blocking(move || metrics.list())
+        .await
+        .map_err(|err| format!("Metrics join error: {err}"))?
+        .map_err(|err| err.to_string())
+}
+
+/// Resets every metric.
+#[tauri::command]
+pub async fn clear_metrics(
+    read_only: State<'_, ReadOnlyMode>,
+    metrics: State<'_, MetricsStore>,
+) -> Result<(), CommandError> {
+    read_only.ensure_writable()?;
+    let metrics = metrics.inner().clone();
+    spawn_blocking(move || metrics.clear())
+        .await
+        .map_err(|err| format!("Metrics join error: {err}"))?
+        .map_err(CommandError::from)
+}
@@ -1,23 +1,79 @@
+pub mod backups;
+pub mod config_issues;
+pub mod deep_links;
+pub mod demo;
+pub mod deploy;
+mod dns_propagation;
 mod dns_provider_creation;
-mod dns_provider_helpers;
+mod dns_provider_debug;
+mod dns_provider_discovery;
+mod dns_provider_env_import;
+mod dns_provider_health;
+pub(crate) mod dns_provider_helpers;
 mod dns_provider_management;
-mod dns_provider_testing;
+mod dns_provider_portability;
+pub(crate) mod dns_provider_testing;
 pub mod dns_providers;
-mod dns_validation;
+pub(crate) mod dns_validation;
 pub mod export;
 pub mod inventory;
 pub mod issuance;
+pub mod issuance_artifacts;
 pub mod issuers;
+pub mod metrics;
 pub mod preferences;
+pub mod recovery;
+pub mod reports;
+pub mod scheduler;
 pub mod secrets;
+pub mod state;
+pub mod updates;
+pub mod usages;
 
+pub use backups::{create_backup, verify_backup};
+pub use config_issues::{get_config_issues, repair_config_issue};
+pub use deep_links::take_pending_deep_link;
+pub use demo::{get_demo_mode, set_demo_mode};
+pub use deploy::{
+    deploy_certificate, deploy_target_create, deploy_target_delete, deploy_target_list,
+    deploy_target_update,
+};
 pub use dns_providers::{
-    dns_provider_create, dns_provider_delete, dns_provider_list, dns_provider_test,
-    dns_provider_update, dns_resolve_provider,
-};
-pub use export::export_certificate_pem;
-pub use inventory::{get_certificate, list_certificates};
-pub use issuance::{complete_managed_issuance, start_managed_issuance};
-pub use issuers::{create_issuer, delete_issuer, list_issuers, select_issuer, update_issuer};
-pub use preferences::{get_preference, set_preference};
-pub use secrets::{list_secret_refs, lock_vault};
+    cancel_dns_propagation_watch, check_authoritative_propagation,
+    cleanup_provider_test_records, clear_provider_debug_log, discover_provider_zones,
+    dns_propagation_presets, dns_provider_account, dns_provider_create, dns_provider_delete,
+    dns_provider_export, dns_provider_health_history, dns_provider_import, dns_provider_list,
+    dns_provider_set_priority, dns_provider_test, dns_provider_update, dns_resolve_provider,
+    get_provider_debug_log, import_dns_providers_from_env, preview_dns_provider_import,
+    watch_dns_propagation,
+};
+pub use export::{
+    certificate_verification_qr, export_certificate_pem, export_certificates_bulk,
+    export_key_escrow, recover_escrowed_key, verify_export,
+};
+pub use inventory::{get_certificate, get_certificate_text, import_certificate, list_certificates};
+pub use issuance::{
+    check_issuer_clock_skew, complete_managed_issuance, parse_domain_list, preflight_domains,
+    prepare_dns_challenges, promote_to_production, start_managed_issuance, validate_domains,
+};
+pub use issuance_artifacts::{export_issuance_artifacts, list_issuance_artifacts};
+pub use issuers::{
+    create_issuer, delete_issuer, list_issuers, select_issuer, suggest_issuer, update_issuer,
+};
+pub use metrics::{clear_metrics, get_metrics};
+pub use preferences::{
+    get_confirmation_pin_status, get_preference, get_read_only_status, set_confirmation_pin,
+    set_key_export_policy, set_preference,
+};
+pub use recovery::{apply_recovery_action, list_recovery_actions};
+pub use reports::{generate_certificate_report, write_status_feed};
+pub use scheduler::{get_background_status, run_background_tasks_now};
+pub use secrets::{
+    get_managed_key_directory, list_secret_refs, lock_vault, set_managed_key_directory,
+};
+pub use state::reload_app_state;
+pub use updates::check_for_updates;
+pub use usages::{
+    add_certificate_usage, check_certificate_usages, get_endpoint_timeline,
+    list_certificate_usages, remove_certificate_usage,
+};
@@ -1,6 +1,17 @@
 use tauri::{async_runtime::spawn_blocking, State};
 
-use crate::core::types::{GetPreferenceRequest, PreferenceEntry, SetPreferenceRequest};
+use crate::core::confirmation_pin::{CONFIRMATION_PIN_PREFERENCE, ConfirmationPin};
+use crate::core::errors::CommandError;
+use crate::core::messages::MessageCode;
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::types::{
+    GetPreferenceRequest, KeyExportPolicy, PreferenceEntry, ReadOnlyStatus,
+    SetConfirmationPinRequest, SetKeyExportPolicyRequest, SetPreferenceRequest,
+};
+use crate::distribution::key_policy::{
+    key_export_policy_value, policy_from_preferences, relaxes_policy,
+    KEY_EXPORT_POLICY_PREFERENCE,
+};
 use crate::storage::preferences::PreferencesStore;
 
 #[tauri::command]
@@ -8,6 +19,9 @@ pub async fn get_preference(
     store: State<'_, PreferencesStore>,
     get_req: GetPreferenceRequest,
 ) -> Result<Option<PreferenceEntry>, String> {
+    if get_req.name == CONFIRMATION_PIN_PREFERENCE {
+        return Err("The confirmation PIN cannot be read".to_string());
+    }
     let store = store.inner().clone();
     spawn_blocking(move || {
         let record = store.get(&get_req.name)?;
@@ -23,13 +37,25 @@ pub async fn get_preference(
 
 #[tauri::command]
 pub async fn set_preference(
+    read_only: State<'_, ReadOnlyMode>,
     store: State<'_, PreferencesStore>,
     set_req: SetPreferenceRequest,
-) -> Result<PreferenceEntry, String> {
+) -> Result<PreferenceEntry, CommandError> {
+    read_only.ensure_preference_writable(&set_req.name)?;
+    if set_req.name == CONFIRMATION_PIN_PREFERENCE {
+        return Err(CommandError::PermissionDenied(
+            "The confirmation PIN can only be changed with the current PIN".to_string(),
+        ));
+    }
+    if set_req.name == KEY_EXPORT_POLICY_PREFERENCE {
+        return Err(CommandError::PermissionDenied(
+            "The key export policy can only be changed from its own setting".to_string(),
+        ));
+    }
     let store = store.inner().clone();
-    spawn_blocking(move || {
+    spawn_blocking(move || -> Result<PreferenceEntry, anyhow::Error> {
         if set_req.name.trim().is_empty() {
-            return Err(anyhow::anyhow!("preference name is required"));
+            return Err(MessageCode::PreferenceNameRequired.into());
         }
 
         let record = store.set(&set_req.name, &set_req.value)?;
@@ -40,5 +66,72 @@ pub async fn set_preference(
     })
     .await
     .map_err(|err| format!("Set preference join error: {err}"))?
-    .map_err(|err: anyhow::Error| err.to_string())
+    .map_err(CommandError::from)
+}
+
+/// Changes the key export policy. Relaxing it requires the confirmation PIN
+/// when one is set; tightening it does not.
+#[tauri::command]
+pub async fn set_key_export_policy(
+    read_only: State<'_, ReadOnlyMode>,
+    pin: State<'_, ConfirmationPin>,
+    store: State<'_, PreferencesStore>,
+    set_req: SetKeyExportPolicyRequest,
+) -> Result<KeyExportPolicy, CommandError> {
+    read_only.ensure_writable()?;
+    let pin = pin.inner().clone();
+    let store = store.inner().clone();
+    spawn_blocking(move || -> Result<KeyExportPolicy, CommandError> {
+        let current = policy_from_preferences(&store);
+        if relaxes_policy(current, set_req.policy) {
+            pin.ensure_confirmed(set_req.confirmation_pin.as_deref())?;
+        }
+        store.set(KEY_EXPORT_POLICY_PREFERENCE, key_export_policy_value(set_req.policy))?;
+        log::info!(
+            "[export] key export policy set to {}",
+            key_export_policy_value(set_req.policy)
+        );
+        Ok(set_req.policy)
+    })
+    .await
+    .map_err(|err| format!("Set key export policy join error: {err}"))?
+}
+
+/// Reports whether read-only mode is active and whether it was forced from the CLI.
+#[tauri::command]
+pub async fn get_read_only_status(
+    read_only: State<'_, ReadOnlyMode>,
+) -> Result<ReadOnlyStatus, String> {
+    let read_only = read_only.inner().clone();
+    spawn_blocking(move || read_only.status())
+        .await
+        .map_err(|err| format!("Read-only status join error: {err}"))
+}
+
+/// Whether destructive commands ask for the confirmation PIN.
+#[tauri::command]
+pub async fn get_confirmation_pin_status(
+    pin: State<'_, ConfirmationPin>,
+) -> Result<bool, CommandError> {
+    let pin = pin.inner().clone();
+    spawn_blocking(move || pin.is_set())
+        .await
+        .map_err(|err| format!("Confirmation PIN status join error: {err}"))?
+}
+
+/// Sets, changes or removes the confirmation PIN; an existing PIN has to be
+/// entered first.
+#[tauri::command]
+pub async fn set_confirmation_pin(
+    read_only: State<'_, ReadOnlyMode>,
+    pin: State<'_, ConfirmationPin>,
+    set_req: SetConfirmationPinRequest,
+) -> Result<(), CommandError> {
+    read_only.ensure_writable()?;
+    let pin = pin.inner().clone();
+    spawn_blocking(move || {
+        pin.update(set_req.current_pin.as_deref(), set_req.new_pin.as_deref())
+    })
+    .await
+    .map_err(|err| format!("Set confirmation PIN join error: {err}"))?
 }
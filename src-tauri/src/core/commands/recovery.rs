@@ -0,0 +1,270 @@
+use std::{fs, io::ErrorKind};
+
+use anyhow::anyhow;
+use serde::Deserialize;
+use tauri::{async_runtime::spawn_blocking, State};
+
+use crate::core::errors::CommandError;
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::types::{
+    ApplyRecoveryRequest, ExportCertificateResponse, ExportProfile, InterruptedJob, JobKind,
+    RecoveryAction,
+};
+use crate::distribution::key_policy::policy_from_preferences;
+use crate::issuance::domain_locks;
+use crate::issuance::flow::cleanup_challenge_records;
+use crate::secrets::manager::SecretManager;
+use crate::storage::{
+    deploy::DeployTargetStore,
+    dns::DnsConfigStore,
+    inventory::InventoryStore,
+    jobs::{JobEntry, JobStore},
+    preferences::PreferencesStore,
+    usages::CertificateUsageStore,
+};
+
+use super::deploy::run_deployment;
+use super::export::export_record;
+
+#[derive(Deserialize)]
+struct IssuanceJobState {
+    domains: Vec<String>,
+    #[serde(default)]
+    dns_records: Vec<ChallengeRecord>,
+}
+
+#[derive(Deserialize)]
+struct ChallengeRecord {
+    domain: String,
+    record_name: String,
+}
+
+#[derive(Deserialize)]
+struct ExportJobState {
+    certificate_id: String,
+    destination_dir: String,
+    profile: ExportProfile,
+    files: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct DeploymentJobState {
+    target_id: String,
+    certificate_id: String,
+}
+
+/// Lists operations a previous run left unfinished, with what can be done about each.
+#[tauri::command]
+pub async fn list_recovery_actions(
+    jobs: State<'_, JobStore>,
+    inventory: State<'_, InventoryStore>,
+    deploy_store: State<'_, DeployTargetStore>,
+) -> Result<Vec<InterruptedJob>, String> {
+    let jobs = jobs.inner().clone();
+    let inventory = inventory.inner().clone();
+    let deploy_store = deploy_store.inner().clone();
+    spawn_blocking(move || -> Result<Vec<InterruptedJob>, anyhow::Error> {
+        Ok(jobs
+            .list_interrupted()?
+            .into_iter()
+            .map(|entry| describe_job(entry, &inventory, &deploy_store))
+            .collect())
+    })
+    .await
+    .map_err(|err| format!("Recovery list join error: {err}"))?
+    .map_err(|err| err.to_string())
+}
+
+/// Applies a recovery action to an interrupted operation. The journal entry
+/// is cleared only when the action succeeds, so a failed cleanup can be retried.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn apply_recovery_action(
+    read_only: State<'_, ReadOnlyMode>,
+    jobs: State<'_, JobStore>,
+    inventory: State<'_, InventoryStore>,
+    secrets: State<'_, SecretManager>,
+    dns_store: State<'_, DnsConfigStore>,
+    deploy_store: State<'_, DeployTargetStore>,
+    usages: State<'_, CertificateUsageStore>,
+    prefs: State<'_, PreferencesStore>,
+    recovery_req: ApplyRecoveryRequest,
+) -> Result<(), CommandError> {
+    read_only.ensure_writable()?;
+    let jobs = jobs.inner().clone();
+    let inventory = inventory.inner().clone();
+    let secrets = secrets.inner().clone();
+    let dns_store = dns_store.inner().clone();
+    let deploy_store = deploy_store.inner().clone();
+    let usages = usages.inner().clone();
+    let prefs = prefs.inner().clone();
+    spawn_blocking(move || -> Result<(), anyhow::Error> {
+        let entry = jobs
+            .get_interrupted(&recovery_req.job_id)?
+            .ok_or_else(|| anyhow!("interrupted job not found: {}", recovery_req.job_id))?;
+        match (entry.kind, recovery_req.action) {
+            (_, RecoveryAction::Discard) => {}
+            (JobKind::Issuance, RecoveryAction::Cleanup) => {
+                let state: IssuanceJobState = serde_json::from_value(entry.state.clone())?;
+                // A newer session may have written its own values at these names
+                domain_locks::ensure_unlocked(&state.domains)?;
+                let records: Vec<(String, String)> = state
+                    .dns_records
+                    .into_iter()
+                    .map(|record| (record.domain, record.record_name))
+                    .collect();
+                let failures = cleanup_challenge_records(&records, &dns_store, &secrets);
+                if !failures.is_empty() {
+                    return Err(anyhow!(
+                        "failed to remove challenge records: {}",
+                        failures.join("; ")
+                    ));
+                }
+            }
+            (JobKind::Export, RecoveryAction::Cleanup) => {
+                let state: ExportJobState = serde_json::from_value(entry.state.clone())?;
+                for path in &state.files {
+                    match fs::remove_file(path) {
+                        Ok(()) => {}
+                        Err(err) if err.kind() == ErrorKind::NotFound => {}
+                        Err(err) => return Err(anyhow!("failed to remove {path}: {err}")),
+                    }
+                }
+            }
+            (JobKind::Export, RecoveryAction::Resume) => {
+                let state: ExportJobState = serde_json::from_value(entry.state.clone())?;
+                let record = inventory
+                    .get_certificate(&state.certificate_id)?
+                    .ok_or_else(|| anyhow!("Certificate not found: {}", state.certificate_id))?;
+                // Files from the interrupted run are replaced
+                let profile = ExportProfile {
+                    overwrite: true,
+                    ..state.profile
+                };
+                // The passphrase of an encrypted key export is not journaled,
+                // so under an encrypted-only policy such exports cannot resume
+                match export_record(
+                    &record,
+                    &secrets,
+                    &jobs,
+                    &state.destination_dir,
+                    &profile,
+                    policy_from_preferences(&prefs),
+                    None,
+                )
+                .map_err(|err| anyhow!(err))?
+                {
+                    ExportCertificateResponse::Success { .. } => {}
+                    ExportCertificateResponse::OverwriteRequired { .. } => {
+                        return Err(anyhow!("export did not complete"));
+                    }
+                }
+            }
+            (JobKind::Deployment, RecoveryAction::Resume) => {
+                let state: DeploymentJobState = serde_json::from_value(entry.state.clone())?;
+                run_deployment(
+                    &deploy_store,
+                    &inventory,
+                    &secrets,
+                    &dns_store,
+                    &usages,
+                    &prefs,
+                    &state.target_id,
+                    &state.certificate_id,
+                )?;
+            }
+            (kind, action) => return Err(anyhow!("{action:?} is not supported for {kind:?} jobs")),
+        }
+
+        log::info!(
+            "[jobs] applied {:?} to interrupted {:?} job {}",
+            recovery_req.action,
+            entry.kind,
+            entry.id
+        );
+        jobs.finish(&entry.id)
+    })
+    .await
+    .map_err(|err| format!("Recovery join error: {err}"))?
+    .map_err(CommandError::from)
+}
+
+fn available_actions(entry: &JobEntry) -> Vec<RecoveryAction> {
+    match entry.kind {
+        // The ACME order lived in memory, so an issuance cannot be resumed
+        JobKind::Issuance => {
+            let has_records = serde_json::from_value::<IssuanceJobState>(entry.state.clone())
+                .is_ok_and(|state| !state.dns_records.is_empty());
+            if has_records {
+                vec![RecoveryAction::Cleanup, RecoveryAction::Discard]
+            } else {
+                vec![RecoveryAction::Discard]
+            }
+        }
+        JobKind::Export => vec![
+            RecoveryAction::Resume,
+            RecoveryAction::Cleanup,
+            RecoveryAction::Discard,
+        ],
+        JobKind::Deployment => vec![RecoveryAction::Resume, RecoveryAction::Discard],
+    }
+}
+
+fn describe_job(
+    entry: JobEntry,
+    inventory: &InventoryStore,
+    deploy_store: &DeployTargetStore,
+) -> InterruptedJob {
+    let certificate_name = |certificate_id: &str| {
+        inventory
+            .get_certificate(certificate_id)
+            .ok()
+            .flatten()
+            .and_then(|record| record.subjects.first().cloned())
+            .unwrap_or_else(|| certificate_id.to_string())
+    };
+    let summary = match entry.kind {
+        JobKind::Issuance => match serde_json::from_value::<IssuanceJobState>(entry.state.clone())
+        {
+            Ok(state) => format!(
+                "Issuance for {} ({} challenge record(s) created)",
+                state.domains.join(", "),
+                state.dns_records.len()
+            ),
+            Err(_) => "Issuance".to_string(),
+        },
+        JobKind::Export => match serde_json::from_value::<ExportJobState>(entry.state.clone()) {
+            Ok(state) => format!(
+                "Export of {} to {}",
+                certificate_name(&state.certificate_id),
+                state.destination_dir
+            ),
+            Err(_) => "Export".to_string(),
+        },
+        JobKind::Deployment => {
+            match serde_json::from_value::<DeploymentJobState>(entry.state.clone()) {
+                Ok(state) => {
+                    let target = deploy_store
+                        .get_target(&state.target_id)
+                        .ok()
+                        .flatten()
+                        .map(|target| target.label)
+                        .unwrap_or(state.target_id);
+                    format!(
+                        "Deployment of {} to {}",
+                        certificate_name(&state.certificate_id),
+                        target
+                    )
+                }
+                Err(_) => "Deployment".to_string(),
+            }
+        }
+    };
+    InterruptedJob {
+        actions: available_actions(&entry),
+        id: entry.id,
+        kind: entry.kind,
+        started_at: entry.started_at,
+        summary,
+    }
+}
@@ -0,0 +1,53 @@
+use tauri::{async_runtime::spawn_blocking, State};
+
+use crate::core::errors::CommandError;
+use crate::core::metrics::CommandTimer;
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::reports::generate_report;
+use crate::core::status_feed;
+use crate::core::types::{CertificateReportResult, GenerateReportRequest, StatusFeedResult};
+use crate::storage::{inventory::InventoryStore, preferences::PreferencesStore};
+
+/// Writes a certificate report for the requested period and returns where it was saved.
+#[tauri::command]
+pub async fn generate_certificate_report(
+    read_only: State<'_, ReadOnlyMode>,
+    inventory: State<'_, InventoryStore>,
+    prefs: State<'_, PreferencesStore>,
+    report_req: GenerateReportRequest,
+) -> Result<CertificateReportResult, CommandError> {
+    read_only.ensure_writable()?;
+    let inventory = inventory.inner().clone();
+    let prefs = prefs.inner().clone();
+    let timer = CommandTimer::start("generate_certificate_report");
+    let result = spawn_blocking(move || {
+        generate_report(
+            &inventory,
+            &prefs,
+            report_req.period,
+            report_req.format,
+            report_req.folder,
+        )
+    })
+    .await
+    .map_err(|err| format!("Report join error: {err}"))?
+    .map_err(CommandError::from);
+    timer.finish(result)
+}
+
+/// Writes the JSON status feed now, to `path` or the configured path.
+#[tauri::command]
+pub async fn write_status_feed(
+    read_only: State<'_, ReadOnlyMode>,
+    inventory: State<'_, InventoryStore>,
+    prefs: State<'_, PreferencesStore>,
+    path: Option<String>,
+) -> Result<StatusFeedResult, CommandError> {
+    read_only.ensure_writable()?;
+    let inventory = inventory.inner().clone();
+    let prefs = prefs.inner().clone();
+    spawn_blocking(move || status_feed::write_status_feed(&inventory, &prefs, path))
+        .await
+        .map_err(|err| format!("Status feed join error: {err}"))?
+        .map_err(CommandError::from)
+}
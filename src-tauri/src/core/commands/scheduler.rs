@@ -0,0 +1,24 @@
+use tauri::{async_runtime::spawn_blocking, State};
+
+use crate::core::scheduler;
+use crate::core::types::BackgroundStatus;
+use crate::storage::preferences::PreferencesStore;
+
+/// Reports whether background tasks are held back right now, and why.
+#[tauri::command]
+pub async fn get_background_status(
+    prefs: State<'_, PreferencesStore>,
+) -> Result<BackgroundStatus, String> {
+    let prefs = prefs.inner().clone();
+    spawn_blocking(move || scheduler::status(&prefs))
+        .await
+        .map_err(|err| format!("Background status join error: {err}"))
+}
+
+/// Runs every background task now, ignoring quiet hours, battery and
+/// metered connections for that run.
+#[tauri::command]
+pub async fn run_background_tasks_now() -> Result<(), String> {
+    scheduler::run_now();
+    Ok(())
+}
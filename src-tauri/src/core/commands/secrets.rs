@@ -1,8 +1,12 @@
 use tauri::{async_runtime::spawn_blocking, State};
 use log::debug;
 
+use crate::core::errors::CommandError;
+use crate::core::read_only::ReadOnlyMode;
 use crate::core::types::SecretRefRecord;
+use crate::secrets::directory_store::{MANAGED_KEY_DIRECTORY_PREFERENCE, validate_key_directory};
 use crate::secrets::manager::SecretManager;
+use crate::storage::preferences::PreferencesStore;
 
 /// Lists secret references (metadata only, no secret bytes).
 #[tauri::command]
@@ -32,3 +36,45 @@ pub async fn lock_vault(manager: State<'_, SecretManager>) -> Result<(), String>
     .await
     .map_err(|err| format!("Lock vault join error: {err}"))?
 }
+
+/// Returns the directory new managed keys are written to, or `None` when
+/// they are kept in the vault database.
+#[tauri::command]
+pub async fn get_managed_key_directory(
+    manager: State<'_, SecretManager>,
+) -> Result<Option<String>, String> {
+    let directory = manager.managed_key_directory();
+    Ok(directory.map(|dir| dir.to_string_lossy().into_owned()))
+}
+
+/// Chooses where new managed keys are stored. `None` or an empty path
+/// switches back to the vault; existing keys are not moved.
+#[tauri::command]
+pub async fn set_managed_key_directory(
+    read_only: State<'_, ReadOnlyMode>,
+    manager: State<'_, SecretManager>,
+    preferences: State<'_, PreferencesStore>,
+    directory: Option<String>,
+) -> Result<Option<String>, CommandError> {
+    read_only.ensure_writable()?;
+    let manager = manager.inner().clone();
+    let preferences = preferences.inner().clone();
+    spawn_blocking(move || {
+        let directory = directory
+            .filter(|dir| !dir.trim().is_empty())
+            .map(|dir| validate_key_directory(&dir))
+            .transpose()?;
+        let stored = directory
+            .as_ref()
+            .map(|dir| dir.to_string_lossy().into_owned());
+        preferences.set(
+            MANAGED_KEY_DIRECTORY_PREFERENCE,
+            stored.as_deref().unwrap_or_default(),
+        )?;
+        manager.set_managed_key_directory(directory);
+        Ok(stored)
+    })
+    .await
+    .map_err(|err| format!("Set key directory join error: {err}"))?
+    .map_err(|err: anyhow::Error| CommandError::from(err))
+}
@@ -0,0 +1,20 @@
+use tauri::{async_runtime::spawn_blocking, AppHandle, State};
+
+use crate::core::errors::CommandError;
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::state_reload::reload_state;
+use crate::core::types::{StateReloadReason, StateReloaded};
+
+/// Reloads the app state from the database and notifies every window.
+#[tauri::command]
+pub async fn reload_app_state(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    reason: Option<StateReloadReason>,
+) -> Result<StateReloaded, CommandError> {
+    read_only.ensure_writable()?;
+    spawn_blocking(move || reload_state(&app, reason.unwrap_or(StateReloadReason::Manual)))
+        .await
+        .map_err(|err| format!("State reload join error: {err}"))?
+        .map_err(CommandError::from)
+}
@@ -0,0 +1,21 @@
+use tauri::{async_runtime::spawn_blocking, AppHandle, State};
+
+use crate::core::metrics::CommandTimer;
+use crate::core::types::UpdateCheckResult;
+use crate::core::updates::check_for_app_update;
+use crate::storage::preferences::PreferencesStore;
+
+/// Checks the release feed for a newer version of the app.
+#[tauri::command]
+pub async fn check_for_updates(
+    app: AppHandle,
+    prefs: State<'_, PreferencesStore>,
+) -> Result<UpdateCheckResult, String> {
+    let prefs = prefs.inner().clone();
+    let timer = CommandTimer::start("check_for_updates");
+    let result = spawn_blocking(move || check_for_app_update(&app, &prefs))
+        .await
+        .map_err(|err| format!("Update check join error: {err}"))?
+        .map_err(|err: anyhow::Error| err.to_string());
+    timer.finish(result)
+}
@@ -0,0 +1,278 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::anyhow;
+use chrono::Utc;
+use tauri::{async_runtime::spawn_blocking, State};
+
+use crate::core::errors::CommandError;
+use crate::core::metrics::CommandTimer;
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::types::{
+    AddCertificateUsageRequest, CertificateRecord, CertificateUsage, EndpointCheck,
+    EndpointObservation, EndpointServingState,
+};
+use crate::distribution::endpoints::{normalize_endpoint, served_fingerprint};
+use crate::distribution::verify::{normalize_fingerprint, same_names};
+use crate::storage::{
+    deploy::DeployTargetStore,
+    inventory::InventoryStore,
+    usages::{CertificateUsageStore, UsageRow},
+};
+
+/// Lists where a certificate is used. Links made for earlier certificates
+/// with the same names are included, since a renewal replaces them in place.
+#[tauri::command]
+pub async fn list_certificate_usages(
+    inventory: State<'_, InventoryStore>,
+    usages: State<'_, CertificateUsageStore>,
+    deploy_store: State<'_, DeployTargetStore>,
+    certificate_id: String,
+) -> Result<Vec<CertificateUsage>, String> {
+    let inventory = inventory.inner().clone();
+    let usages = usages.inner().clone();
+    let deploy_store = deploy_store.inner().clone();
+    spawn_blocking(move || -> Result<Vec<CertificateUsage>, anyhow::Error> {
+        let (record, records) = load_certificates(&inventory, &certificate_id)?;
+        let rows = usages.list_for_certificates(&related_ids(&record, &records))?;
+        let labels: HashMap<String, String> = deploy_store
+            .list_targets()?
+            .into_iter()
+            .map(|target| (target.id, target.label))
+            .collect();
+        Ok(merge_usages(&record, rows, &labels))
+    })
+    .await
+    .map_err(|err| format!("Usage list join error: {err}"))?
+    .map_err(|err| err.to_string())
+}
+
+/// Links a certificate to an endpoint (`host[:port]`) or a deployment target.
+#[tauri::command]
+pub async fn add_certificate_usage(
+    read_only: State<'_, ReadOnlyMode>,
+    inventory: State<'_, InventoryStore>,
+    usages: State<'_, CertificateUsageStore>,
+    deploy_store: State<'_, DeployTargetStore>,
+    usage_req: AddCertificateUsageRequest,
+) -> Result<CertificateUsage, CommandError> {
+    read_only.ensure_writable()?;
+    let inventory = inventory.inner().clone();
+    let usages = usages.inner().clone();
+    let deploy_store = deploy_store.inner().clone();
+    spawn_blocking(move || -> Result<CertificateUsage, anyhow::Error> {
+        inventory
+            .get_certificate(&usage_req.certificate_id)?
+            .ok_or_else(|| anyhow!("Certificate not found: {}", usage_req.certificate_id))?;
+        let endpoint = usage_req
+            .endpoint
+            .as_deref()
+            .filter(|endpoint| !endpoint.trim().is_empty())
+            .map(normalize_endpoint)
+            .transpose()?;
+        let target = match usage_req.deploy_target_id.as_deref() {
+            Some(target_id) => Some(
+                deploy_store
+                    .get_target(target_id)?
+                    .ok_or_else(|| anyhow!("deploy target not found: {target_id}"))?,
+            ),
+            None => None,
+        };
+        if endpoint.is_none() == target.is_none() {
+            return Err(anyhow!("a usage links either an endpoint or a deploy target"));
+        }
+
+        let row = usages.add(
+            &usage_req.certificate_id,
+            endpoint.as_deref(),
+            target.as_ref().map(|target| target.id.as_str()),
+        )?;
+        Ok(usage_from_row(row, false, target.map(|target| target.label)))
+    })
+    .await
+    .map_err(|err| format!("Usage add join error: {err}"))?
+    .map_err(CommandError::from)
+}
+
+/// Removes a usage link.
+#[tauri::command]
+pub async fn remove_certificate_usage(
+    read_only: State<'_, ReadOnlyMode>,
+    usages: State<'_, CertificateUsageStore>,
+    usage_id: String,
+) -> Result<(), CommandError> {
+    read_only.ensure_writable()?;
+    let usages = usages.inner().clone();
+    spawn_blocking(move || usages.remove(&usage_id))
+        .await
+        .map_err(|err| format!("Usage remove join error: {err}"))?
+        .map_err(CommandError::from)
+}
+
+/// Connects to every endpoint linked to the certificate (or an earlier one
+/// with the same names) and reports which certificate each one serves. Each
+/// result is added to the endpoint's history, except in read-only mode.
+#[tauri::command]
+pub async fn check_certificate_usages(
+    read_only: State<'_, ReadOnlyMode>,
+    inventory: State<'_, InventoryStore>,
+    usages: State<'_, CertificateUsageStore>,
+    certificate_id: String,
+) -> Result<Vec<EndpointCheck>, String> {
+    let read_only = read_only.inner().clone();
+    let inventory = inventory.inner().clone();
+    let usages = usages.inner().clone();
+    let timer = CommandTimer::start("check_certificate_usages");
+    let result = spawn_blocking(move || -> Result<Vec<EndpointCheck>, anyhow::Error> {
+        let (record, records) = load_certificates(&inventory, &certificate_id)?;
+        let rows = usages.list_for_certificates(&related_ids(&record, &records))?;
+        let mut seen = HashSet::new();
+        let checks = rows
+            .into_iter()
+            .filter_map(|row| {
+                let endpoint = row.endpoint?;
+                seen.insert(endpoint.clone()).then_some((row.id, endpoint))
+            })
+            .map(|(usage_id, endpoint)| check_endpoint(usage_id, endpoint, &record, &records))
+            .collect::<Vec<_>>();
+        if read_only.is_enabled() {
+            return Ok(checks);
+        }
+        let checked_at = Utc::now();
+        for check in &checks {
+            let Some(fingerprint) = check.served_fingerprint.as_deref() else {
+                continue;
+            };
+            if let Err(err) = usages.record_observation(
+                &check.endpoint,
+                fingerprint,
+                check.served_certificate_id.as_deref(),
+                checked_at,
+            ) {
+                log::warn!("[usages] failed to record history for {}: {err}", check.endpoint);
+            }
+        }
+        Ok(checks)
+    })
+    .await
+    .map_err(|err| format!("Usage check join error: {err}"))?
+    .map_err(|err| err.to_string());
+    timer.finish(result)
+}
+
+/// Certificates an endpoint has served over time, most recent first.
+#[tauri::command]
+pub async fn get_endpoint_timeline(
+    usages: State<'_, CertificateUsageStore>,
+    endpoint: String,
+) -> Result<Vec<EndpointObservation>, String> {
+    let usages = usages.inner().clone();
+    spawn_blocking(move || usages.endpoint_timeline(&normalize_endpoint(&endpoint)?))
+        .await
+        .map_err(|err| format!("Endpoint timeline join error: {err}"))?
+        .map_err(|err| err.to_string())
+}
+
+fn load_certificates(
+    inventory: &InventoryStore,
+    certificate_id: &str,
+) -> Result<(CertificateRecord, Vec<CertificateRecord>), anyhow::Error> {
+    let records = inventory.list_certificates()?;
+    let record = records
+        .iter()
+        .find(|record| record.id == certificate_id)
+        .cloned()
+        .ok_or_else(|| anyhow!("Certificate not found: {certificate_id}"))?;
+    Ok((record, records))
+}
+
+/// The certificate itself plus every other certificate for the same names.
+fn related_ids(record: &CertificateRecord, records: &[CertificateRecord]) -> Vec<String> {
+    records
+        .iter()
+        .filter(|other| other.id == record.id || same_names(other, record))
+        .map(|other| other.id.clone())
+        .collect()
+}
+
+/// Own links first; inherited links are kept once per endpoint or target.
+fn merge_usages(
+    record: &CertificateRecord,
+    rows: Vec<UsageRow>,
+    labels: &HashMap<String, String>,
+) -> Vec<CertificateUsage> {
+    let (own, inherited): (Vec<UsageRow>, Vec<UsageRow>) = rows
+        .into_iter()
+        .partition(|row| row.certificate_id == record.id);
+    let mut seen = HashSet::new();
+    own.into_iter()
+        .map(|row| (row, false))
+        .chain(inherited.into_iter().map(|row| (row, true)))
+        .filter(|(row, _)| seen.insert((row.endpoint.clone(), row.deploy_target_id.clone())))
+        .map(|(row, inherited)| {
+            let label = row
+                .deploy_target_id
+                .as_ref()
+                .and_then(|target_id| labels.get(target_id).cloned());
+            usage_from_row(row, inherited, label)
+        })
+        .collect()
+}
+
+fn usage_from_row(
+    row: UsageRow,
+    inherited: bool,
+    deploy_target_label: Option<String>,
+) -> CertificateUsage {
+    CertificateUsage {
+        id: row.id,
+        certificate_id: row.certificate_id,
+        endpoint: row.endpoint,
+        deploy_target_id: row.deploy_target_id,
+        deploy_target_label,
+        inherited,
+        created_at: row.created_at,
+    }
+}
+
+fn check_endpoint(
+    usage_id: String,
+    endpoint: String,
+    record: &CertificateRecord,
+    records: &[CertificateRecord],
+) -> EndpointCheck {
+    let fingerprint = match served_fingerprint(&endpoint) {
+        Ok(fingerprint) => fingerprint,
+        Err(err) => {
+            return EndpointCheck {
+                usage_id,
+                endpoint,
+                state: EndpointServingState::Unreachable,
+                served_fingerprint: None,
+                served_certificate_id: None,
+                error: Some(err.to_string()),
+            };
+        }
+    };
+    let served = records
+        .iter()
+        .find(|other| normalize_fingerprint(&other.fingerprint) == fingerprint);
+    let state = match served {
+        Some(served) if served.id == record.id => EndpointServingState::Current,
+        Some(served) if same_names(served, record) && served.not_after < record.not_after => {
+            EndpointServingState::Outdated
+        }
+        Some(served) if same_names(served, record) => EndpointServingState::Newer,
+        _ => EndpointServingState::Other,
+    };
+    if state == EndpointServingState::Outdated {
+        log::warn!("[usages] {endpoint} still serves {} instead of {}", fingerprint, record.id);
+    }
+    EndpointCheck {
+        usage_id,
+        endpoint,
+        state,
+        served_certificate_id: served.map(|served| served.id.clone()),
+        served_fingerprint: Some(fingerprint),
+        error: None,
+    }
+}
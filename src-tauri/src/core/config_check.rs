@@ -0,0 +1,223 @@
+//! Consistency check of stored configuration.
+//!
+//! Providers, issuers and certificates refer to secrets by id. A secret
+//! deleted by hand, lost with a managed key directory or missing from a
+//! partial restore leaves the reference dangling, and the failure only
+//! shows up when the secret is next needed. The check runs once at startup
+//! and on demand from `get_config_issues`, and repairs the issues where no
+//! data can be lost.
+
+use std::collections::HashSet;
+
+use anyhow::{Result, anyhow};
+use tauri::{AppHandle, Manager};
+
+use crate::core::types::{CertificateRecord, ConfigIssue, ConfigIssueKind, ConfigRepair};
+use crate::issuance::acme::create_account_key;
+use crate::secrets::manager::SecretManager;
+use crate::storage::{
+    demo::is_demo_id,
+    dns::{DnsConfigStore, DnsProvider},
+    inventory::InventoryStore,
+    issuer::{IssuerConfigRecord, IssuerConfigStore},
+};
+
+/// Checks every stored reference to a secret against the secret store. A
+/// secret counts as present only when its payload is stored, not just its
+/// metadata row.
+pub fn find_config_issues(
+    dns_store: &DnsConfigStore,
+    issuer_store: &IssuerConfigStore,
+    inventory: &InventoryStore,
+    secrets: &SecretManager,
+) -> Result<Vec<ConfigIssue>> {
+    let mut known = HashSet::new();
+    for metadata in secrets.list().map_err(|err| anyhow!(err.to_string()))? {
+        match secrets.payload_exists(&metadata) {
+            Ok(true) => {
+                known.insert(metadata.id);
+            }
+            Ok(false) => log::warn!("[config] secret {} has no stored payload", metadata.id),
+            // An unreachable key directory is not proof the key is gone
+            Err(err) => {
+                log::warn!("[config] could not check secret {}: {err}", metadata.id);
+                known.insert(metadata.id);
+            }
+        }
+    }
+    Ok(collect_issues(
+        &dns_store.list_providers()?,
+        &issuer_store.list()?,
+        &inventory.list_certificates()?,
+        &known,
+    ))
+}
+
+/// Applies the repair of `issue`.
+pub fn repair_config_issue(
+    issue: &ConfigIssue,
+    issuer_store: &IssuerConfigStore,
+    inventory: &InventoryStore,
+    secrets: &SecretManager,
+) -> Result<()> {
+    match issue.repair {
+        Some(ConfigRepair::CreateAccountKey) => {
+            let key_ref = create_account_key(secrets)?;
+            issuer_store.set_account_key_ref(&issue.subject_id, key_ref)?;
+        }
+        Some(ConfigRepair::DetachManagedKey) => inventory.detach_managed_key(&issue.subject_id)?,
+        None => return Err(anyhow!("{} has to be fixed by hand", issue.subject_label)),
+    }
+    log::info!("[config] repaired {}", issue.id);
+    Ok(())
+}
+
+fn collect_issues(
+    providers: &[DnsProvider],
+    issuers: &[IssuerConfigRecord],
+    certificates: &[CertificateRecord],
+    known: &HashSet<String>,
+) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    for provider in providers {
+        for secret_ref in provider.secret_refs.iter().filter(|id| !known.contains(*id)) {
+            issues.push(ConfigIssue {
+                id: format!("provider_secret:{}:{secret_ref}", provider.id),
+                kind: ConfigIssueKind::ProviderSecret,
+                subject_id: provider.id.clone(),
+                subject_label: provider.label.clone(),
+                detail: format!(
+                    "Credential {secret_ref} no longer exists; enter the provider's \
+                     credentials again"
+                ),
+                repair: None,
+            });
+        }
+    }
+    // Demo issuers are seeded without an account
+    for issuer in issuers.iter().filter(|issuer| !is_demo_id(&issuer.issuer_id)) {
+        let detail = match &issuer.account_key_ref {
+            None => "No ACME account key".to_string(),
+            Some(key_ref) if !known.contains(key_ref) => {
+                format!("ACME account key {key_ref} no longer exists")
+            }
+            Some(_) => continue,
+        };
+        issues.push(ConfigIssue {
+            id: format!("issuer_account_key:{}", issuer.issuer_id),
+            kind: ConfigIssueKind::IssuerAccountKey,
+            subject_id: issuer.issuer_id.clone(),
+            subject_label: issuer.label.clone(),
+            detail,
+            repair: Some(ConfigRepair::CreateAccountKey),
+        });
+    }
+    for certificate in certificates {
+        let Some(key_ref) = certificate.managed_key_ref.as_ref() else {
+            continue;
+        };
+        if known.contains(key_ref) {
+            continue;
+        }
+        issues.push(ConfigIssue {
+            id: format!("certificate_key:{}", certificate.id),
+            kind: ConfigIssueKind::CertificateKey,
+            subject_id: certificate.id.clone(),
+            subject_label: certificate
+                .subjects
+                .first()
+                .cloned()
+                .unwrap_or_else(|| certificate.id.clone()),
+            detail: format!("Private key {key_ref} no longer exists"),
+            repair: Some(ConfigRepair::DetachManagedKey),
+        });
+    }
+    issues
+}
+
+/// Runs the check once in the background and logs what it finds.
+pub fn spawn_startup_check(app: AppHandle) {
+    tauri::async_runtime::spawn_blocking(move || {
+        let issues = find_config_issues(
+            app.state::<DnsConfigStore>().inner(),
+            app.state::<IssuerConfigStore>().inner(),
+            app.state::<InventoryStore>().inner(),
+            app.state::<SecretManager>().inner(),
+        );
+        match issues {
+            Ok(issues) => {
+                for issue in issues {
+                    log::warn!("[config] {}: {}", issue.subject_label, issue.detail);
+                }
+            }
+            Err(err) => log::warn!("[config] configuration check failed: {err:#}"),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn issuer(id: &str, account_key_ref: Option<&str>) -> IssuerConfigRecord {
+        IssuerConfigRecord {
+            issuer_id: id.to_string(),
+            label: id.to_string(),
+            directory_url: "https://acme.example/directory".to_string(),
+            environment: "production".to_string(),
+            issuer_type: "acme".to_string(),
+            params_json: "{}".to_string(),
+            contact_email: Some("ops@example.com".to_string()),
+            account_key_ref: account_key_ref.map(str::to_string),
+            tos_agreed: true,
+            is_selected: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn reports_references_to_missing_secrets() {
+        let known: HashSet<String> = ["sec_provider_ok", "sec_account_ok", "sec_key_ok"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        let provider = DnsProvider {
+            id: "dns_1".to_string(),
+            provider_type: "cloudflare".to_string(),
+            label: "Cloudflare".to_string(),
+            domain_suffixes: vec!["example.com".to_string()],
+            secret_refs: vec!["sec_provider_ok".to_string(), "sec_provider_gone".to_string()],
+            config_json: None,
+            priority: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let issuers = [
+            issuer("iss_ok", Some("sec_account_ok")),
+            issuer("iss_gone", Some("sec_account_gone")),
+            issuer("iss_none", None),
+            issuer("demo_issuer", None),
+        ];
+        let certificate = |id: &str, key_ref: &str| CertificateRecord {
+            managed_key_ref: Some(key_ref.to_string()),
+            ..CertificateRecord::test_record(id, &[&format!("{id}.example.com")])
+        };
+        let certificates = [certificate("ok", "sec_key_ok"), certificate("gone", "sec_key_gone")];
+
+        let issues = collect_issues(&[provider], &issuers, &certificates, &known);
+        let found: Vec<(&str, Option<ConfigRepair>)> =
+            issues.iter().map(|issue| (issue.id.as_str(), issue.repair)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("provider_secret:dns_1:sec_provider_gone", None),
+                ("issuer_account_key:iss_gone", Some(ConfigRepair::CreateAccountKey)),
+                ("issuer_account_key:iss_none", Some(ConfigRepair::CreateAccountKey)),
+                ("certificate_key:gone", Some(ConfigRepair::DetachManagedKey)),
+            ]
+        );
+    }
+}
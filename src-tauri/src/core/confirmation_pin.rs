@@ -0,0 +1,197 @@
+//! Optional PIN required by destructive commands.
+//!
+//! The PIN is checked here in the command layer, so a frontend that has been
+//! taken over cannot delete issuers, DNS providers or deployment targets and
+//! their stored credentials on its own. Only a
+//! salted hash is stored, under a preference the generic preference commands
+//! refuse to read or write. Repeated wrong PINs lock confirmation for a while.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use crate::core::errors::CommandError;
+use crate::storage::preferences::PreferencesStore;
+
+pub const CONFIRMATION_PIN_PREFERENCE: &str = "confirmation_pin";
+
+const HASH_VERSION: &str = "v1";
+// Kept low in tests, where unoptimized hashing is slow
+const HASH_ROUNDS: u32 = if cfg!(test) { 16 } else { 100_000 };
+const MIN_PIN_LEN: usize = 4;
+const MAX_ATTEMPTS: u32 = 5;
+const LOCKOUT: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Default)]
+struct Attempts {
+    failures: u32,
+    locked_until: Option<Instant>,
+}
+
+#[derive(Clone)]
+pub struct ConfirmationPin {
+    prefs: PreferencesStore,
+    attempts: Arc<Mutex<Attempts>>,
+}
+
+impl ConfirmationPin {
+    pub fn new(prefs: PreferencesStore) -> Self {
+        Self {
+            prefs,
+            attempts: Arc::default(),
+        }
+    }
+
+    pub fn is_set(&self) -> Result<bool, CommandError> {
+        Ok(self.stored_hash()?.is_some())
+    }
+
+    /// Returns `PinRequired` unless no PIN is configured or `pin` matches it.
+    pub fn ensure_confirmed(&self, pin: Option<&str>) -> Result<(), CommandError> {
+        let Some(stored) = self.stored_hash()? else {
+            return Ok(());
+        };
+        let mut attempts = self.attempts.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some(until) = attempts.locked_until {
+            if Instant::now() < until {
+                return Err(CommandError::PinRequired(
+                    "Too many incorrect PINs; try again in a few minutes".to_string(),
+                ));
+            }
+            *attempts = Attempts::default();
+        }
+        let Some(pin) = pin.filter(|pin| !pin.is_empty()) else {
+            return Err(CommandError::PinRequired(
+                "Enter the confirmation PIN to continue".to_string(),
+            ));
+        };
+        if verify_pin(pin, &stored) {
+            *attempts = Attempts::default();
+            return Ok(());
+        }
+        attempts.failures += 1;
+        if attempts.failures >= MAX_ATTEMPTS {
+            attempts.locked_until = Some(Instant::now() + LOCKOUT);
+            log::warn!("[confirmation-pin] locked after {MAX_ATTEMPTS} incorrect PINs");
+        }
+        Err(CommandError::PinRequired("Incorrect confirmation PIN".to_string()))
+    }
+
+    /// Sets, changes or (with `new_pin` of `None`) removes the PIN. Changing
+    /// an existing PIN requires the current one.
+    pub fn update(
+        &self,
+        current_pin: Option<&str>,
+        new_pin: Option<&str>,
+    ) -> Result<(), CommandError> {
+        self.ensure_confirmed(current_pin)?;
+        let value = match new_pin {
+            Some(pin) => {
+                if pin.chars().count() < MIN_PIN_LEN {
+                    return Err(CommandError::Failed(format!(
+                        "The PIN needs at least {MIN_PIN_LEN} characters"
+                    )));
+                }
+                hash_pin(pin)
+            }
+            None => String::new(),
+        };
+        self.prefs
+            .set(CONFIRMATION_PIN_PREFERENCE, &value)
+            .map_err(CommandError::from)?;
+        Ok(())
+    }
+
+    fn stored_hash(&self) -> Result<Option<String>, CommandError> {
+        let pref = self.prefs.get(CONFIRMATION_PIN_PREFERENCE)?;
+        Ok(pref
+            .map(|pref| pref.value)
+            .filter(|value| !value.is_empty()))
+    }
+}
+
+fn hash_pin(pin: &str) -> String {
+    let mut salt = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut salt);
+    format!(
+        "{HASH_VERSION}${}${}",
+        hex::encode(salt),
+        hex::encode(stretch(pin, &salt))
+    )
+}
+
+fn verify_pin(pin: &str, stored: &str) -> bool {
+    let mut parts = stored.split('$');
+    let (Some(HASH_VERSION), Some(salt), Some(expected), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return false;
+    };
+    let (Ok(salt), Ok(expected)) = (hex::decode(salt), hex::decode(expected)) else {
+        return false;
+    };
+    let actual = stretch(pin, &salt);
+    // Compare every byte so timing does not reveal the matching prefix
+    actual.len() == expected.len()
+        && actual
+            .iter()
+            .zip(&expected)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Iterated SHA-256 so a copied database does not give the PIN away at once.
+fn stretch(pin: &str, salt: &[u8]) -> Vec<u8> {
+    let mut digest = Sha256::new()
+        .chain_update(salt)
+        .chain_update(pin.as_bytes())
+        .finalize();
+    for _ in 1..HASH_ROUNDS {
+        digest = Sha256::new()
+            .chain_update(salt)
+            .chain_update(digest)
+            .finalize();
+    }
+    digest.to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::Db;
+    use std::fs;
+    use uuid::Uuid;
+
+    #[test]
+    fn pin_gates_confirmation_and_locks_out() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("sslboard_pin_test_{}", Uuid::new_v4().as_simple()));
+        fs::create_dir_all(&dir)?;
+        let prefs = PreferencesStore::initialize(Db::initialize_with_path(&dir)?)?;
+        let pin = ConfirmationPin::new(prefs.clone());
+
+        assert!(pin.ensure_confirmed(None).is_ok());
+        pin.update(None, Some("4821")).unwrap();
+        assert!(prefs.get(CONFIRMATION_PIN_PREFERENCE)?.unwrap().value.starts_with("v1$"));
+        assert!(matches!(pin.ensure_confirmed(None), Err(CommandError::PinRequired(_))));
+        assert!(pin.ensure_confirmed(Some("4821")).is_ok());
+        assert!(pin.update(None, Some("9999")).is_err());
+        assert!(pin.update(Some("4821"), Some("12")).is_err());
+
+        for _ in 0..MAX_ATTEMPTS {
+            assert!(pin.ensure_confirmed(Some("0000")).is_err());
+        }
+        assert!(pin.ensure_confirmed(Some("4821")).is_err());
+
+        let restarted = ConfirmationPin::new(prefs.clone());
+        restarted.update(Some("4821"), None).unwrap();
+        assert!(!restarted.is_set().unwrap());
+
+        drop(prefs);
+        drop(pin);
+        drop(restarted);
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+//! `sslboard://` quick actions.
+//!
+//! Launchers and scripts open links such as `sslboard://renew/<cert_id>` or
+//! `sslboard://check/<domain>`. A link is parsed and resolved against the
+//! inventory here, then handed to the UI as a `deep-link-action` event. The
+//! latest action is also kept until the UI takes it, so a link that launched
+//! the app is not lost while the window is still loading.
+
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{Result, anyhow};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::core::status::name_set;
+use crate::core::types::{CertificateStatus, DeepLinkAction};
+use crate::domain::{
+    normalize_domain_for_display, normalize_domain_for_storage, normalize_domains_for_display,
+};
+use crate::issuance::flow::pending_issuance_domains;
+use crate::storage::inventory::InventoryStore;
+
+pub const DEEP_LINK_SCHEME: &str = "sslboard";
+pub const DEEP_LINK_EVENT: &str = "deep-link-action";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+    /// Start a new issuance for the names of a certificate
+    Renew(String),
+    /// Show the certificates covering a domain (stored form)
+    Check(String),
+}
+
+static PENDING: OnceLock<Mutex<Option<DeepLinkAction>>> = OnceLock::new();
+
+fn pending() -> &'static Mutex<Option<DeepLinkAction>> {
+    PENDING.get_or_init(|| Mutex::new(None))
+}
+
+/// Parses `sslboard://<action>/<argument>`; query strings and fragments are ignored.
+pub fn parse_deep_link(raw: &str) -> Result<DeepLink> {
+    let raw = raw.trim();
+    let rest = raw
+        .split_once("://")
+        .filter(|(scheme, _)| scheme.eq_ignore_ascii_case(DEEP_LINK_SCHEME))
+        .map(|(_, rest)| rest)
+        .ok_or_else(|| anyhow!("not an {DEEP_LINK_SCHEME}:// link: {raw}"))?;
+    let rest = rest
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .trim_matches('/');
+    let (action, argument) = rest
+        .split_once('/')
+        .ok_or_else(|| anyhow!("link is missing an argument: {raw}"))?;
+    let argument = percent_decode(argument.trim_matches('/'))?;
+    if argument.is_empty() || argument.contains('/') {
+        return Err(anyhow!("link argument is invalid: {raw}"));
+    }
+
+    match action.to_ascii_lowercase().as_str() {
+        "renew" => Ok(DeepLink::Renew(argument)),
+        "check" => Ok(DeepLink::Check(normalize_domain_for_storage(&argument)?)),
+        other => Err(anyhow!("unsupported {DEEP_LINK_SCHEME}:// action: {other}")),
+    }
+}
+
+fn percent_decode(raw: &str) -> Result<String> {
+    let bytes = raw.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = raw
+                .get(index + 1..index + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| anyhow!("invalid percent-encoding in link: {raw}"))?;
+            decoded.push(hex);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| anyhow!("link is not valid UTF-8: {raw}"))
+}
+
+/// Looks the link up in the inventory and builds the action for the UI.
+pub fn resolve_deep_link(link: DeepLink, inventory: &InventoryStore) -> Result<DeepLinkAction> {
+    match link {
+        DeepLink::Renew(certificate_id) => {
+            let record = inventory
+                .get_certificate(&certificate_id)?
+                .ok_or_else(|| anyhow!("Certificate not found: {certificate_id}"))?;
+            Ok(DeepLinkAction::Renew {
+                certificate_id,
+                domains: normalize_domains_for_display(&record.sans),
+            })
+        }
+        DeepLink::Check(domain) => {
+            let records = inventory.current_statuses(&pending_issuance_domains())?;
+            let certificate_ids = records
+                .iter()
+                .filter(|record| record.status != CertificateStatus::Superseded)
+                .filter(|record| {
+                    name_set(&record.sans)
+                        .iter()
+                        .any(|name| name_covers(name, &domain))
+                })
+                .map(|record| record.id.clone())
+                .collect();
+            Ok(DeepLinkAction::Check {
+                domain: normalize_domain_for_display(&domain),
+                certificate_ids,
+            })
+        }
+    }
+}
+
+/// True when a certificate name matches the domain, including one-label wildcards.
+fn name_covers(name: &str, domain: &str) -> bool {
+    match name.strip_prefix("*.") {
+        Some(parent) => domain
+            .split_once('.')
+            .is_some_and(|(label, rest)| !label.is_empty() && rest == parent),
+        None => name == domain,
+    }
+}
+
+/// Handles one opened link: resolves it, remembers it, and notifies the UI.
+pub fn handle_deep_link(app: &AppHandle, raw: &str) {
+    log::info!("[deep-link] received {raw}");
+    let inventory = app.state::<InventoryStore>().inner().clone();
+    let action = match parse_deep_link(raw).and_then(|link| resolve_deep_link(link, &inventory)) {
+        Ok(action) => action,
+        Err(err) => {
+            log::warn!("[deep-link] ignoring {raw}: {err}");
+            DeepLinkAction::Invalid {
+                url: raw.to_string(),
+                message: err.to_string(),
+            }
+        }
+    };
+    match pending().lock() {
+        Ok(mut slot) => *slot = Some(action.clone()),
+        Err(err) => log::warn!("[deep-link] pending action unavailable: {err}"),
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    if let Err(err) = app.emit(DEEP_LINK_EVENT, &action) {
+        log::warn!("[deep-link] failed to emit action: {err}");
+    }
+}
+
+/// Returns and clears the latest action not yet handled by the UI.
+pub fn take_pending_action() -> Option<DeepLinkAction> {
+    match pending().lock() {
+        Ok(mut slot) => slot.take(),
+        Err(err) => {
+            log::warn!("[deep-link] pending action unavailable: {err}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_supported_actions() {
+        assert_eq!(
+            parse_deep_link("sslboard://renew/cert_123").unwrap(),
+            DeepLink::Renew("cert_123".to_string())
+        );
+        assert_eq!(
+            parse_deep_link("SSLBoard://check/WWW.Example.com/?from=raycast").unwrap(),
+            DeepLink::Check("www.example.com".to_string())
+        );
+        assert_eq!(
+            parse_deep_link("sslboard://check/test%C3%A9.fr").unwrap(),
+            DeepLink::Check("xn--test-epa.fr".to_string())
+        );
+
+        assert!(parse_deep_link("https://renew/cert_123").is_err());
+        assert!(parse_deep_link("sslboard://renew").is_err());
+        assert!(parse_deep_link("sslboard://renew/a/b").is_err());
+        assert!(parse_deep_link("sslboard://delete/cert_123").is_err());
+        assert!(parse_deep_link("sslboard://check/bad%zz").is_err());
+    }
+
+    #[test]
+    fn wildcards_cover_one_label() {
+        assert!(name_covers("example.com", "example.com"));
+        assert!(name_covers("*.example.com", "www.example.com"));
+        assert!(!name_covers("*.example.com", "example.com"));
+        assert!(!name_covers("*.example.com", "a.b.example.com"));
+    }
+}
@@ -0,0 +1,238 @@
+//! Errors returned to the frontend by commands that need a machine-readable code.
+
+use serde::{Serialize, Serializer, ser::SerializeStruct};
+
+use crate::core::messages::CatalogError;
+use crate::core::types::{DomainIssue, DuplicateConflict, FinalizationStage};
+use crate::domain::DomainError;
+
+/// Serialized as `{ "code": "...", "message": "..." }` so the UI can branch on `code`.
+/// Conflicts also carry a `conflicts` array describing the overlapping records,
+/// timeouts carry the `request_id` and `stage` to resume from, issuances
+/// blocked by another session carry its `request_id` and the held `domains`,
+/// malformed domain names carry their `issues`, and catalog messages carry a
+/// `message_code` the UI can localize.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum CommandError {
+    /// The app is in read-only mode and the command would change state.
+    #[error("{0}")]
+    PermissionDenied(String),
+    /// A destructive command needs the confirmation PIN, or got a wrong one.
+    #[error("{0}")]
+    PinRequired(String),
+    /// A create request overlaps existing records; retry with `on_conflict` set.
+    #[error("{0}")]
+    Conflict(ConflictError),
+    /// A long-running step hit its deadline; the operation can be resumed.
+    #[error("{0}")]
+    TimedOut(IssuanceTimeout),
+    /// Another pending issuance holds some of the requested names.
+    #[error("{0}")]
+    InProgress(IssuanceInProgress),
+    /// Submitted domain names are malformed.
+    #[error("{0}")]
+    InvalidDomains(InvalidDomainsError),
+    /// A failure with a message from the catalog in `core::messages`.
+    #[error("{0}")]
+    Catalog(CatalogError),
+    #[error("{0}")]
+    Failed(String),
+}
+
+/// Raised inside command bodies and surfaced as [`CommandError::Conflict`].
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{message}")]
+pub struct ConflictError {
+    pub message: String,
+    pub conflicts: Vec<DuplicateConflict>,
+}
+
+/// Raised when issuance finalization runs out of time and surfaced as
+/// [`CommandError::TimedOut`].
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{message}")]
+pub struct IssuanceTimeout {
+    pub message: String,
+    pub request_id: String,
+    /// Stage that was still running; completing again resumes from here
+    pub stage: FinalizationStage,
+    /// False when the order can't continue and issuance must be restarted
+    pub resumable: bool,
+}
+
+/// Raised when a pending issuance already holds some of the names and
+/// surfaced as [`CommandError::InProgress`].
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{message}")]
+pub struct IssuanceInProgress {
+    pub message: String,
+    pub domains: Vec<String>,
+    /// Session holding the names; completing it releases them
+    pub request_id: String,
+}
+
+impl IssuanceInProgress {
+    pub fn new(domains: Vec<String>, request_id: String) -> Self {
+        let message = format!("issuance already in progress for {}", domains.join(", "));
+        Self {
+            message,
+            domains,
+            request_id,
+        }
+    }
+}
+
+/// Raised when submitted domain names are malformed and surfaced as
+/// [`CommandError::InvalidDomains`].
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{message}")]
+pub struct InvalidDomainsError {
+    pub message: String,
+    pub issues: Vec<DomainIssue>,
+}
+
+impl InvalidDomainsError {
+    pub fn new(issues: Vec<DomainIssue>) -> Self {
+        let message = match issues.as_slice() {
+            [issue] => format!("Invalid domain name \"{}\": {}", issue.input, issue.message),
+            _ => format!("{} domain names are invalid", issues.len()),
+        };
+        Self { message, issues }
+    }
+}
+
+impl CommandError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::PermissionDenied(_) => "permission_denied",
+            Self::PinRequired(_) => "pin_required",
+            Self::Conflict(_) => "conflict",
+            Self::TimedOut(_) => "timed_out",
+            Self::InProgress(_) => "issuance_in_progress",
+            Self::InvalidDomains(_) => "invalid_domains",
+            Self::Catalog(_) | Self::Failed(_) => "failed",
+        }
+    }
+}
+
+impl Serialize for CommandError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let extra = match self {
+            Self::Conflict(_) | Self::InvalidDomains(_) | Self::Catalog(_) => 1,
+            Self::TimedOut(_) => 3,
+            Self::InProgress(_) => 2,
+            _ => 0,
+        };
+        let mut state = serializer.serialize_struct("CommandError", 2 + extra)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        match self {
+            Self::Conflict(err) => state.serialize_field("conflicts", &err.conflicts)?,
+            Self::InvalidDomains(err) => state.serialize_field("issues", &err.issues)?,
+            Self::TimedOut(err) => {
+                state.serialize_field("request_id", &err.request_id)?;
+                state.serialize_field("stage", &err.stage)?;
+                state.serialize_field("resumable", &err.resumable)?;
+            }
+            Self::InProgress(err) => {
+                state.serialize_field("domains", &err.domains)?;
+                state.serialize_field("request_id", &err.request_id)?;
+            }
+            Self::Catalog(err) => state.serialize_field("message_code", &err.0)?,
+            _ => {}
+        }
+        state.end()
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::Failed(message)
+    }
+}
+
+impl From<anyhow::Error> for CommandError {
+    fn from(err: anyhow::Error) -> Self {
+        let err = match err.downcast::<ConflictError>() {
+            Ok(conflict) => return Self::Conflict(conflict),
+            Err(err) => err,
+        };
+        let err = match err.downcast::<IssuanceTimeout>() {
+            Ok(timeout) => return Self::TimedOut(timeout),
+            Err(err) => err,
+        };
+        let err = match err.downcast::<IssuanceInProgress>() {
+            Ok(in_progress) => return Self::InProgress(in_progress),
+            Err(err) => err,
+        };
+        let err = match err.downcast::<InvalidDomainsError>() {
+            Ok(invalid) => return Self::InvalidDomains(invalid),
+            Err(err) => err,
+        };
+        let err = match err.downcast::<DomainError>() {
+            Ok(DomainError(issue)) => {
+                return Self::InvalidDomains(InvalidDomainsError::new(vec![issue]))
+            }
+            Err(err) => err,
+        };
+        match err.downcast::<CatalogError>() {
+            Ok(message) => Self::Catalog(message),
+            Err(err) => Self::Failed(err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::messages::MessageCode;
+    use crate::core::types::ConflictEntity;
+
+    #[test]
+    fn details_survive_anyhow_and_serialize() {
+        let err = anyhow::Error::new(ConflictError {
+            message: "overlaps Cloudflare".to_string(),
+            conflicts: vec![DuplicateConflict {
+                entity: ConflictEntity::DnsProvider,
+                existing_id: "dns_1".to_string(),
+                existing_label: "Cloudflare".to_string(),
+                overlapping: vec!["example.com".to_string()],
+                can_merge: true,
+            }],
+        });
+        let value = serde_json::to_value(CommandError::from(err)).unwrap();
+        assert_eq!(value["code"], "conflict");
+        assert_eq!(value["message"], "overlaps Cloudflare");
+        assert_eq!(value["conflicts"][0]["existing_id"], "dns_1");
+
+        let err = anyhow::Error::new(IssuanceTimeout {
+            message: "still waiting".to_string(),
+            request_id: "req_1".to_string(),
+            stage: FinalizationStage::AwaitingOrder,
+            resumable: true,
+        });
+        let value = serde_json::to_value(CommandError::from(err)).unwrap();
+        assert_eq!(value["code"], "timed_out");
+        assert_eq!(value["stage"], "awaiting_order");
+        assert_eq!(value["resumable"], true);
+
+        let err = anyhow::Error::new(IssuanceInProgress::new(
+            vec!["example.com".to_string()],
+            "req_2".to_string(),
+        ));
+        let value = serde_json::to_value(CommandError::from(err)).unwrap();
+        assert_eq!(value["code"], "issuance_in_progress");
+        assert_eq!(value["message"], "issuance already in progress for example.com");
+        assert_eq!(value["domains"][0], "example.com");
+        assert_eq!(value["request_id"], "req_2");
+
+        let err = anyhow::Error::from(MessageCode::IssuerLabelRequired);
+        let value = serde_json::to_value(CommandError::from(err)).unwrap();
+        assert_eq!(value["code"], "failed");
+        assert_eq!(value["message"], "issuer label is required");
+        assert_eq!(value["message_code"], "issuer_label_required");
+
+        let value = serde_json::to_value(CommandError::from("nope".to_string())).unwrap();
+        assert_eq!(value, serde_json::json!({ "code": "failed", "message": "nope" }));
+    }
+}
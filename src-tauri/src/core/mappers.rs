@@ -0,0 +1,449 @@
+//! Conversions between storage records and the DTOs returned to the UI.
+//!
+//! Commands and the issuance flow share these so a new record field only has
+//! to be mapped in one place.
+
+use log::warn;
+use serde_json::Value;
+
+use crate::core::types::{
+    DnsProviderDto, DnsProviderErrorCategory, DnsProviderType, IssuerConfigDto,
+    IssuerEnvironment, IssuerSelectionPolicy, IssuerStats, IssuerType, JobKind, MetricKind,
+    ProviderHealthCheckKind, SuffixShadowKind, SuffixShadowWarning,
+};
+use crate::domain::{domain_forms_of, normalize_domain_for_display};
+use crate::storage::dns::{DnsProvider, SuffixNesting, SuffixShadowing};
+use crate::storage::issuer::IssuerConfigRecord;
+
+pub fn issuer_record_to_dto(record: IssuerConfigRecord) -> IssuerConfigDto {
+    let params = record.params();
+    IssuerConfigDto {
+        environment: environment_from_str(&record.environment),
+        issuer_type: issuer_type_from_str(&record.issuer_type),
+        issuer_id: record.issuer_id,
+        label: record.label,
+        directory_url: record.directory_url,
+        contact_email: record.contact_email,
+        account_key_ref: record.account_key_ref,
+        tos_agreed: record.tos_agreed,
+        is_selected: record.is_selected,
+        preferred_chain: params.preferred_chain,
+        ca_pins: params.ca_pins,
+        enforce_ca_pins: params.enforce_ca_pins,
+        disabled: params.disabled,
+        stats: IssuerStats::default(),
+    }
+}
+
+/// Unknown values map to staging so a corrupt row never issues against production.
+pub fn environment_from_str(raw: &str) -> IssuerEnvironment {
+    match raw {
+        "production" => IssuerEnvironment::Production,
+        _ => IssuerEnvironment::Staging,
+    }
+}
+
+pub fn environment_to_string(environment: &IssuerEnvironment) -> String {
+    match environment {
+        IssuerEnvironment::Production => "production".to_string(),
+        IssuerEnvironment::Staging => "staging".to_string(),
+    }
+}
+
+pub fn issuer_type_from_str(raw: &str) -> IssuerType {
+    match raw {
+        "acme" => IssuerType::Acme,
+        _ => IssuerType::Acme,
+    }
+}
+
+pub fn issuer_type_to_string(issuer_type: &IssuerType) -> String {
+    match issuer_type {
+        IssuerType::Acme => "acme".to_string(),
+    }
+}
+
+pub fn provider_record_to_dto(record: DnsProvider) -> DnsProviderDto {
+    let config = provider_config(&record);
+    let suffix_forms = domain_forms_of(&record.domain_suffixes);
+    DnsProviderDto {
+        provider_type: provider_type_from_str(&record.provider_type),
+        id: record.id,
+        label: record.label,
+        domain_suffixes: record
+            .domain_suffixes
+            .into_iter()
+            .map(|suffix| normalize_domain_for_display(&suffix))
+            .collect(),
+        suffix_forms,
+        config,
+        priority: record.priority,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+        verification: None,
+        suffix_warnings: Vec::new(),
+    }
+}
+
+/// Warnings for suffixes that nest with another provider's suffix.
+pub fn suffix_shadow_warnings(shadowing: &[SuffixShadowing]) -> Vec<SuffixShadowWarning> {
+    shadowing
+        .iter()
+        .map(|entry| {
+            let suffix = normalize_domain_for_display(&entry.suffix);
+            let existing_suffix = normalize_domain_for_display(&entry.existing_suffix);
+            let label = &entry.provider.label;
+            let (kind, message) = match entry.nesting {
+                SuffixNesting::Shadows => (
+                    SuffixShadowKind::Shadows,
+                    format!(
+                        "{suffix} is inside {existing_suffix} ({label}); \
+                         names under {suffix} will use this provider instead"
+                    ),
+                ),
+                SuffixNesting::ShadowedBy => (
+                    SuffixShadowKind::ShadowedBy,
+                    format!(
+                        "{existing_suffix} ({label}) is inside {suffix}; \
+                         names under {existing_suffix} will keep using {label}"
+                    ),
+                ),
+            };
+            SuffixShadowWarning {
+                suffix,
+                kind,
+                existing_suffix,
+                provider_id: entry.provider.id.clone(),
+                provider_label: label.clone(),
+                message,
+            }
+        })
+        .collect()
+}
+
+/// Unknown provider types fall back to manual DNS, which never calls an API.
+pub fn provider_type_from_str(raw: &str) -> DnsProviderType {
+    match raw {
+        "acme_dns" => DnsProviderType::AcmeDns,
+        "cloudflare" => DnsProviderType::Cloudflare,
+        "digitalocean" => DnsProviderType::DigitalOcean,
+        "desec" => DnsProviderType::Desec,
+        "powerdns" => DnsProviderType::PowerDns,
+        "rfc2136" => DnsProviderType::Rfc2136,
+        "route53" => DnsProviderType::Route53,
+        "script" => DnsProviderType::Script,
+        _ => DnsProviderType::Manual,
+    }
+}
+
+pub fn provider_type_to_string(provider_type: &DnsProviderType) -> String {
+    match provider_type {
+        DnsProviderType::AcmeDns => "acme_dns".to_string(),
+        DnsProviderType::Cloudflare => "cloudflare".to_string(),
+        DnsProviderType::DigitalOcean => "digitalocean".to_string(),
+        DnsProviderType::Desec => "desec".to_string(),
+        DnsProviderType::PowerDns => "powerdns".to_string(),
+        DnsProviderType::Rfc2136 => "rfc2136".to_string(),
+        DnsProviderType::Route53 => "route53".to_string(),
+        DnsProviderType::Script => "script".to_string(),
+        DnsProviderType::Manual => "manual".to_string(),
+    }
+}
+
+/// Unknown values fall back to the smart policy.
+pub fn issuer_policy_from_str(raw: &str) -> IssuerSelectionPolicy {
+    match raw.trim() {
+        "staging" => IssuerSelectionPolicy::Staging,
+        "production" => IssuerSelectionPolicy::Production,
+        "selected" => IssuerSelectionPolicy::Selected,
+        _ => IssuerSelectionPolicy::Smart,
+    }
+}
+
+pub fn job_kind_from_str(raw: &str) -> Option<JobKind> {
+    match raw {
+        "issuance" => Some(JobKind::Issuance),
+        "export" => Some(JobKind::Export),
+        "deployment" => Some(JobKind::Deployment),
+        _ => None,
+    }
+}
+
+pub fn job_kind_to_str(kind: JobKind) -> &'static str {
+    match kind {
+        JobKind::Issuance => "issuance",
+        JobKind::Export => "export",
+        JobKind::Deployment => "deployment",
+    }
+}
+
+pub fn metric_kind_from_str(raw: &str) -> Option<MetricKind> {
+    match raw {
+        "command" => Some(MetricKind::Command),
+        "dns_provider" => Some(MetricKind::DnsProvider),
+        _ => None,
+    }
+}
+
+pub fn metric_kind_to_str(kind: MetricKind) -> &'static str {
+    match kind {
+        MetricKind::Command => "command",
+        MetricKind::DnsProvider => "dns_provider",
+    }
+}
+
+pub fn health_check_kind_from_str(raw: &str) -> Option<ProviderHealthCheckKind> {
+    match raw {
+        "credentials" => Some(ProviderHealthCheckKind::Credentials),
+        "full_test" => Some(ProviderHealthCheckKind::FullTest),
+        _ => None,
+    }
+}
+
+pub fn health_check_kind_to_str(kind: ProviderHealthCheckKind) -> &'static str {
+    match kind {
+        ProviderHealthCheckKind::Credentials => "credentials",
+        ProviderHealthCheckKind::FullTest => "full_test",
+    }
+}
+
+pub fn dns_error_category_from_str(raw: &str) -> Option<DnsProviderErrorCategory> {
+    match raw {
+        "auth_error" => Some(DnsProviderErrorCategory::AuthError),
+        "not_found" => Some(DnsProviderErrorCategory::NotFound),
+        "rate_limited" => Some(DnsProviderErrorCategory::RateLimited),
+        "network_error" => Some(DnsProviderErrorCategory::NetworkError),
+        "unknown" => Some(DnsProviderErrorCategory::Unknown),
+        _ => None,
+    }
+}
+
+pub fn dns_error_category_to_str(category: DnsProviderErrorCategory) -> &'static str {
+    match category {
+        DnsProviderErrorCategory::AuthError => "auth_error",
+        DnsProviderErrorCategory::NotFound => "not_found",
+        DnsProviderErrorCategory::RateLimited => "rate_limited",
+        DnsProviderErrorCategory::NetworkError => "network_error",
+        DnsProviderErrorCategory::Unknown => "unknown",
+    }
+}
+
+/// Zone configured on the provider, used instead of guessing it from the domain.
+pub fn provider_zone_override(provider: &DnsProvider) -> Option<String> {
+    provider_config(provider)?
+        .get("zone")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Whether `debug_capture` is turned on in the provider config.
+pub fn provider_debug_capture(provider: &DnsProvider) -> bool {
+    provider_config(provider)
+        .and_then(|config| config.get("debug_capture").and_then(Value::as_bool))
+        .unwrap_or(false)
+}
+
+/// API endpoint from `api_url` in the provider config, for self-hosted providers.
+pub fn provider_api_url(provider: &DnsProvider) -> Option<String> {
+    provider_config_str(provider, "api_url")
+}
+
+/// Server ID from `server_id` in the provider config.
+pub fn provider_server_id(provider: &DnsProvider) -> Option<String> {
+    provider_config_str(provider, "server_id")
+}
+
+/// Non-empty string setting from the provider config.
+pub fn provider_config_str(provider: &DnsProvider, key: &str) -> Option<String> {
+    provider_config(provider)?
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// Parsed `config_json`; invalid JSON is logged and treated as absent.
+fn provider_config(provider: &DnsProvider) -> Option<Value> {
+    let raw = provider.config_json.as_ref()?;
+    match serde_json::from_str::<Value>(raw) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            warn!(
+                "[dns] invalid provider config_json for {}: {}",
+                provider.id, err
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn issuer_record(params_json: &str) -> IssuerConfigRecord {
+        IssuerConfigRecord {
+            issuer_id: "acme_1".to_string(),
+            label: "LE".to_string(),
+            directory_url: "https://acme.test/directory".to_string(),
+            environment: "production".to_string(),
+            issuer_type: "acme".to_string(),
+            params_json: params_json.to_string(),
+            contact_email: Some("ops@example.com".to_string()),
+            account_key_ref: Some("sec_1".to_string()),
+            tos_agreed: true,
+            is_selected: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn provider_record(config_json: Option<&str>) -> DnsProvider {
+        DnsProvider {
+            id: "dns_1".to_string(),
+            provider_type: "cloudflare".to_string(),
+            label: "CF".to_string(),
+            domain_suffixes: vec!["xn--test-epa.fr".to_string()],
+            secret_refs: vec!["sec_2".to_string()],
+            config_json: config_json.map(str::to_string),
+            priority: 3,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn issuer_dto_maps_every_field() {
+        let dto = issuer_record_to_dto(issuer_record(
+            r#"{"preferred_chain":"X1","ca_pins":[{"kind":"subject","value":"R11"}],
+                "enforce_ca_pins":true,"disabled":true,"unknown":1}"#,
+        ));
+        // Destructured without `..` so a new DTO field fails to compile here.
+        let IssuerConfigDto {
+            issuer_id,
+            label,
+            directory_url,
+            environment,
+            issuer_type,
+            contact_email,
+            account_key_ref,
+            tos_agreed,
+            is_selected,
+            preferred_chain,
+            ca_pins,
+            enforce_ca_pins,
+            disabled,
+            stats,
+        } = dto;
+        assert_eq!(issuer_id, "acme_1");
+        assert_eq!(label, "LE");
+        assert_eq!(directory_url, "https://acme.test/directory");
+        assert!(matches!(environment, IssuerEnvironment::Production));
+        assert!(matches!(issuer_type, IssuerType::Acme));
+        assert_eq!(contact_email.as_deref(), Some("ops@example.com"));
+        assert_eq!(account_key_ref.as_deref(), Some("sec_1"));
+        assert!(tos_agreed);
+        assert_eq!(stats.orders, 0);
+        assert!(is_selected);
+        assert_eq!(preferred_chain.as_deref(), Some("X1"));
+        assert_eq!(ca_pins.len(), 1);
+        assert!(enforce_ca_pins);
+        assert!(disabled);
+
+        // Rows written before newer params existed map to defaults.
+        let legacy = issuer_record_to_dto(issuer_record("{}"));
+        assert!(!legacy.disabled);
+        assert!(legacy.ca_pins.is_empty());
+        assert!(legacy.preferred_chain.is_none());
+    }
+
+    #[test]
+    fn provider_dto_maps_every_field() {
+        let dto = provider_record_to_dto(provider_record(Some(r#"{"zone":"test.fr"}"#)));
+        let DnsProviderDto {
+            id,
+            provider_type,
+            label,
+            domain_suffixes,
+            suffix_forms,
+            config,
+            priority,
+            created_at: _,
+            updated_at: _,
+            verification,
+            suffix_warnings,
+        } = dto;
+        assert_eq!(id, "dns_1");
+        assert!(matches!(provider_type, DnsProviderType::Cloudflare));
+        assert_eq!(label, "CF");
+        assert_eq!(domain_suffixes, vec!["testé.fr".to_string()]);
+        assert_eq!(suffix_forms[0].ascii, "xn--test-epa.fr");
+        assert_eq!(config.unwrap()["zone"], "test.fr");
+        assert_eq!(priority, 3);
+        assert!(verification.is_none());
+        assert!(suffix_warnings.is_empty());
+
+        assert!(provider_record_to_dto(provider_record(Some("{"))).config.is_none());
+    }
+
+    #[test]
+    fn type_strings_round_trip() {
+        for environment in [IssuerEnvironment::Staging, IssuerEnvironment::Production] {
+            let raw = environment_to_string(&environment);
+            assert_eq!(environment_to_string(&environment_from_str(&raw)), raw);
+        }
+        assert!(matches!(environment_from_str("bogus"), IssuerEnvironment::Staging));
+        assert_eq!(issuer_type_to_string(&issuer_type_from_str("acme")), "acme");
+
+        for provider_type in [
+            DnsProviderType::AcmeDns,
+            DnsProviderType::Cloudflare,
+            DnsProviderType::DigitalOcean,
+            DnsProviderType::Desec,
+            DnsProviderType::PowerDns,
+            DnsProviderType::Rfc2136,
+            DnsProviderType::Route53,
+            DnsProviderType::Script,
+            DnsProviderType::Manual,
+        ] {
+            let raw = provider_type_to_string(&provider_type);
+            assert_eq!(provider_type_to_string(&provider_type_from_str(&raw)), raw);
+        }
+        assert!(matches!(provider_type_from_str("bogus"), DnsProviderType::Manual));
+    }
+
+    #[test]
+    fn zone_override_reads_config() {
+        assert_eq!(
+            provider_zone_override(&provider_record(Some(r#"{"zone":"example.com"}"#))).as_deref(),
+            Some("example.com")
+        );
+        assert!(provider_zone_override(&provider_record(Some(r#"{"zone":1}"#))).is_none());
+        assert!(provider_zone_override(&provider_record(Some("not json"))).is_none());
+        assert!(provider_zone_override(&provider_record(None)).is_none());
+    }
+
+    #[test]
+    fn shadow_warnings_name_the_other_provider() {
+        let warnings = suffix_shadow_warnings(&[SuffixShadowing {
+            provider: provider_record(None),
+            suffix: "dev.xn--test-epa.fr".to_string(),
+            existing_suffix: "xn--test-epa.fr".to_string(),
+            nesting: SuffixNesting::Shadows,
+        }]);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, SuffixShadowKind::Shadows);
+        assert_eq!(warnings[0].suffix, "dev.testé.fr");
+        assert_eq!(warnings[0].provider_id, "dns_1");
+        assert!(warnings[0].message.contains("(CF)"));
+    }
+
+    #[test]
+    fn debug_capture_reads_config() {
+        assert!(provider_debug_capture(&provider_record(Some(r#"{"debug_capture":true}"#))));
+        assert!(!provider_debug_capture(&provider_record(Some(r#"{"debug_capture":"yes"}"#))));
+        assert!(!provider_debug_capture(&provider_record(None)));
+    }
+}
@@ -0,0 +1,100 @@
+//! Catalog of user-facing messages keyed by stable codes.
+//!
+//! Commands still return English text, but catalog messages also carry a
+//! `message_code` so the frontend can render them in the user's locale,
+//! falling back to the English default shipped here.
+
+use serde::Serialize;
+
+/// Preference holding the UI locale, e.g. `fr` or `de-CH`; empty follows the system.
+pub const LOCALE_PREFERENCE: &str = "locale";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageCode {
+    IssuerLabelRequired,
+    DirectoryUrlRequired,
+    ContactEmailRequired,
+    TosAcceptanceRequired,
+    ProviderLabelRequired,
+    DomainSuffixRequired,
+    ApiTokenRequired,
+    DomainRequired,
+    PreferenceNameRequired,
+    PropagationWrongContent,
+    PropagationPending,
+    PropagationNxDomain,
+    PropagationNoResponses,
+}
+
+impl MessageCode {
+    pub const ALL: [MessageCode; 13] = [
+        MessageCode::IssuerLabelRequired,
+        MessageCode::DirectoryUrlRequired,
+        MessageCode::ContactEmailRequired,
+        MessageCode::TosAcceptanceRequired,
+        MessageCode::ProviderLabelRequired,
+        MessageCode::DomainSuffixRequired,
+        MessageCode::ApiTokenRequired,
+        MessageCode::DomainRequired,
+        MessageCode::PreferenceNameRequired,
+        MessageCode::PropagationWrongContent,
+        MessageCode::PropagationPending,
+        MessageCode::PropagationNxDomain,
+        MessageCode::PropagationNoResponses,
+    ];
+
+    /// English default text.
+    pub fn english(self) -> &'static str {
+        match self {
+            MessageCode::IssuerLabelRequired => "issuer label is required",
+            MessageCode::DirectoryUrlRequired => "directory URL is required",
+            MessageCode::ContactEmailRequired => "contact email is required for ACME issuers",
+            MessageCode::TosAcceptanceRequired => {
+                "Terms of Service acceptance is required for ACME issuers"
+            }
+            MessageCode::ProviderLabelRequired => "provider label is required",
+            MessageCode::DomainSuffixRequired => "at least one domain suffix is required",
+            MessageCode::ApiTokenRequired => "API token is required for this provider",
+            MessageCode::DomainRequired => "domain name is required",
+            MessageCode::PreferenceNameRequired => "preference name is required",
+            MessageCode::PropagationWrongContent => "TXT record present with different value",
+            MessageCode::PropagationPending => "record not found yet",
+            MessageCode::PropagationNxDomain => "record not found (NXDOMAIN)",
+            MessageCode::PropagationNoResponses => "no responses from DNS resolvers",
+        }
+    }
+}
+
+/// Error whose text comes from the catalog; commands surface its code as
+/// `message_code` next to the English message.
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("{}", .0.english())]
+pub struct CatalogError(pub MessageCode);
+
+impl From<MessageCode> for anyhow::Error {
+    fn from(code: MessageCode) -> Self {
+        CatalogError(code).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn codes_are_unique_with_english_defaults() {
+        let names: HashSet<String> = MessageCode::ALL
+            .iter()
+            .map(|code| serde_json::to_value(code).unwrap().as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(names.len(), MessageCode::ALL.len());
+        assert!(names.contains("issuer_label_required"));
+        assert!(MessageCode::ALL.iter().all(|code| !code.english().is_empty()));
+        assert_eq!(
+            CatalogError(MessageCode::IssuerLabelRequired).to_string(),
+            "issuer label is required"
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! Recording of command and DNS provider call metrics.
+//!
+//! Startup installs the [`MetricsStore`] here so DNS adapters, which have no
+//! access to managed state, can record their calls. Before installation (and
+//! in tests) recording is a no-op. Failing to record only logs a warning.
+
+use std::fmt::Display;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::core::types::MetricKind;
+use crate::storage::metrics::MetricsStore;
+
+static STORE: OnceLock<MetricsStore> = OnceLock::new();
+
+pub fn install(store: MetricsStore) {
+    if STORE.set(store).is_err() {
+        log::warn!("[metrics] store already installed");
+    }
+}
+
+pub fn record(kind: MetricKind, name: &str, label: &str, elapsed: Duration, error: Option<&str>) {
+    let Some(store) = STORE.get() else {
+        return;
+    };
+    let elapsed_ms = elapsed.as_millis().min(u64::MAX as u128) as u64;
+    if let Err(err) = store.record(kind, name, label, elapsed_ms, error) {
+        log::warn!("[metrics] failed to record {name}: {err}");
+    }
+}
+
+/// Times one command invocation from [`CommandTimer::start`] to
+/// [`CommandTimer::finish`].
+pub struct CommandTimer {
+    command: &'static str,
+    started: Instant,
+}
+
+impl CommandTimer {
+    pub fn start(command: &'static str) -> Self {
+        Self {
+            command,
+            started: Instant::now(),
+        }
+    }
+
+    /// Records the command's outcome and hands `result` back.
+    pub fn finish<T, E: Display>(self, result: Result<T, E>) -> Result<T, E> {
+        let error = result.as_ref().err().map(ToString::to_string);
+        record(
+            MetricKind::Command,
+            self.command,
+            self.command,
+            self.started.elapsed(),
+            error.as_deref(),
+        );
+        result
+    }
+}
@@ -1,2 +1,20 @@
+pub mod backups;
 pub mod commands;
+pub mod config_check;
+pub mod confirmation_pin;
+pub mod deep_links;
+pub mod errors;
+pub mod mappers;
+pub mod messages;
+pub mod metrics;
+pub mod provider_health;
+pub mod read_only;
+pub mod reports;
+pub mod runtime;
+pub mod scheduler;
+pub mod state_reload;
+pub mod status;
+pub mod status_feed;
 pub mod types;
+pub mod updates;
+pub mod user_agent;
@@ -0,0 +1,191 @@
+//! Scheduled DNS provider health checks.
+//!
+//! While the `provider_health_interval_hours` preference is set, every
+//! automated provider is checked that often and the outcome is stored in
+//! [`ProviderHealthStore`]. The check either confirms the credentials can
+//! see the configured zones (the default) or, with
+//! `provider_health_check_mode` set to `full_test`, runs the full provider
+//! test. A failed check emits `provider-health-failed`, so a revoked or
+//! expired token shows up before an issuance needs it.
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::Utc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::core::commands::dns_provider_helpers::TestRecordSettings;
+use crate::core::commands::dns_provider_testing::run_provider_test;
+use crate::core::commands::dns_validation::categorize_dns_error;
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::scheduler::{self, BackgroundTask};
+use crate::core::types::{DnsProviderHealthCheck, ProviderHealthCheckKind};
+use crate::issuance::dns_providers::adapter_for_provider;
+use crate::issuance::propagation_presets::PropagationWaits;
+use crate::issuance::propagation_resolvers::ResolverSet;
+use crate::secrets::manager::SecretManager;
+use crate::storage::dns::{DnsConfigStore, DnsProvider};
+use crate::storage::preferences::PreferencesStore;
+use crate::storage::provider_health::ProviderHealthStore;
+
+/// Preference holding the hours between checks of a provider; unset or 0
+/// turns the checks off.
+pub const HEALTH_INTERVAL_PREFERENCE: &str = "provider_health_interval_hours";
+/// Preference choosing the check: `credentials` (default) or `full_test`.
+pub const HEALTH_CHECK_MODE_PREFERENCE: &str = "provider_health_check_mode";
+/// Emitted with the [`DnsProviderHealthCheck`] of every failed check.
+pub const HEALTH_FAILED_EVENT: &str = "provider-health-failed";
+
+const HEALTH_TICK: Duration = Duration::from_secs(15 * 60);
+
+fn read_preference(prefs: &PreferencesStore, name: &str) -> Option<String> {
+    match prefs.get(name) {
+        Ok(Some(pref)) => Some(pref.value.trim().to_string()).filter(|value| !value.is_empty()),
+        Ok(None) => None,
+        Err(err) => {
+            log::warn!("[provider-health] failed to read preference {name}: {err}");
+            None
+        }
+    }
+}
+
+fn read_interval(prefs: &PreferencesStore) -> Option<chrono::Duration> {
+    let raw = read_preference(prefs, HEALTH_INTERVAL_PREFERENCE)?;
+    match raw.parse::<i64>() {
+        Ok(hours) if hours > 0 => Some(chrono::Duration::hours(hours)),
+        Ok(_) => None,
+        Err(_) => {
+            log::warn!("[provider-health] ignoring invalid interval {raw:?}");
+            None
+        }
+    }
+}
+
+fn read_mode(prefs: &PreferencesStore) -> ProviderHealthCheckKind {
+    match read_preference(prefs, HEALTH_CHECK_MODE_PREFERENCE).as_deref() {
+        Some("full_test") => ProviderHealthCheckKind::FullTest,
+        _ => ProviderHealthCheckKind::Credentials,
+    }
+}
+
+/// Runs one check of `provider`. Failures are part of the result.
+pub fn check_provider(
+    store: &DnsConfigStore,
+    secrets: &SecretManager,
+    waits: &PropagationWaits,
+    test_defaults: &TestRecordSettings,
+    resolvers: &ResolverSet,
+    provider: &DnsProvider,
+    kind: ProviderHealthCheckKind,
+) -> DnsProviderHealthCheck {
+    let started = Instant::now();
+    let outcome = match kind {
+        ProviderHealthCheckKind::Credentials => adapter_for_provider(provider, secrets)
+            .verify_zone_access()
+            .map_err(|err| (err.to_string(), Some(categorize_dns_error(&err)))),
+        ProviderHealthCheckKind::FullTest => {
+            match run_provider_test(store, secrets, waits, test_defaults, resolvers, &provider.id) {
+                Ok(result) if result.success => Ok(()),
+                Ok(result) => Err((
+                    result
+                        .error
+                        .unwrap_or_else(|| "The test record did not propagate".to_string()),
+                    result.error_category,
+                )),
+                Err(err) => Err((err.to_string(), Some(categorize_dns_error(&err)))),
+            }
+        }
+    };
+    let (error, error_category) = match outcome {
+        Ok(()) => (None, None),
+        Err((error, category)) => (Some(error), category),
+    };
+    DnsProviderHealthCheck {
+        provider_id: provider.id.clone(),
+        checked_at: Utc::now(),
+        kind,
+        success: error.is_none(),
+        error,
+        error_category,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+/// Checks every automated provider whose last check is older than the
+/// configured interval. Returns the checks that ran.
+fn run_due_checks(app: &AppHandle) -> Result<Vec<DnsProviderHealthCheck>> {
+    let prefs = app.state::<PreferencesStore>().inner().clone();
+    let Some(interval) = read_interval(&prefs) else {
+        return Ok(Vec::new());
+    };
+    let kind = read_mode(&prefs);
+    // The full test writes records; credential checks only read
+    if kind == ProviderHealthCheckKind::FullTest && app.state::<ReadOnlyMode>().is_enabled() {
+        return Ok(Vec::new());
+    }
+    if !scheduler::should_run(BackgroundTask::ProviderHealth, &prefs) {
+        return Ok(Vec::new());
+    }
+
+    let store = app.state::<DnsConfigStore>().inner().clone();
+    let secrets = app.state::<SecretManager>().inner().clone();
+    let health = app.state::<ProviderHealthStore>().inner().clone();
+    let waits = PropagationWaits::from_preferences(&prefs);
+    let test_defaults = TestRecordSettings::from_preferences(&prefs);
+    let resolvers = ResolverSet::from_preferences(&prefs);
+    let now = Utc::now();
+    let mut checks = Vec::new();
+    for provider in store.list_providers()? {
+        if provider.provider_type == "manual" {
+            continue;
+        }
+        let due = health
+            .last_checked_at(&provider.id)?
+            .is_none_or(|last| now - last >= interval);
+        if !due {
+            continue;
+        }
+        let check = check_provider(
+            &store,
+            &secrets,
+            &waits,
+            &test_defaults,
+            &resolvers,
+            &provider,
+            kind,
+        );
+        health.record(&check)?;
+        checks.push(check);
+    }
+    Ok(checks)
+}
+
+/// Starts the background checks; does nothing while no interval is set.
+pub fn spawn_scheduled_health_checks(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let handle = app.clone();
+            let outcome =
+                tauri::async_runtime::spawn_blocking(move || run_due_checks(&handle)).await;
+
+            match outcome {
+                Ok(Ok(checks)) => {
+                    for check in checks.iter().filter(|check| !check.success) {
+                        log::warn!(
+                            "[provider-health] provider {} failed its check: {}",
+                            check.provider_id,
+                            check.error.as_deref().unwrap_or("unknown error")
+                        );
+                        if let Err(err) = app.emit(HEALTH_FAILED_EVENT, check) {
+                            log::warn!("[provider-health] failed to emit event: {err}");
+                        }
+                    }
+                }
+                Ok(Err(err)) => log::warn!("[provider-health] scheduled check failed: {err}"),
+                Err(err) => log::warn!("[provider-health] scheduled check join error: {err}"),
+            }
+
+            scheduler::wait(BackgroundTask::ProviderHealth, HEALTH_TICK).await;
+        }
+    });
+}
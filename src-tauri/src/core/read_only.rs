@@ -0,0 +1,112 @@
+//! App-level read-only mode.
+//!
+//! Enabled either by the `read_only_mode` preference or by launching with
+//! `--read-only`. The CLI flag cannot be switched off from the UI, which makes
+//! it the option for kiosk dashboards on shared screens.
+
+use crate::core::errors::CommandError;
+use crate::core::messages::LOCALE_PREFERENCE;
+use crate::core::types::ReadOnlyStatus;
+use crate::storage::preferences::PreferencesStore;
+
+pub const READ_ONLY_PREFERENCE: &str = "read_only_mode";
+pub const READ_ONLY_FLAG: &str = "--read-only";
+
+#[derive(Clone)]
+pub struct ReadOnlyMode {
+    prefs: PreferencesStore,
+    forced: bool,
+}
+
+impl ReadOnlyMode {
+    pub fn new(prefs: PreferencesStore, forced: bool) -> Self {
+        Self { prefs, forced }
+    }
+
+    /// Builds the mode from the process arguments.
+    pub fn from_args(prefs: PreferencesStore) -> Self {
+        let forced = std::env::args().any(|arg| arg == READ_ONLY_FLAG);
+        if forced {
+            log::info!("[read-only] enabled via {READ_ONLY_FLAG}");
+        }
+        Self::new(prefs, forced)
+    }
+
+    pub fn status(&self) -> ReadOnlyStatus {
+        ReadOnlyStatus {
+            enabled: self.is_enabled(),
+            forced: self.forced,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        if self.forced {
+            return true;
+        }
+        match self.prefs.get(READ_ONLY_PREFERENCE) {
+            Ok(pref) => pref.is_some_and(|pref| pref.value == "true"),
+            Err(err) => {
+                // Fail closed: if we cannot tell, do not allow writes.
+                log::warn!("[read-only] failed to read preference: {err}");
+                true
+            }
+        }
+    }
+
+    /// Returns `PermissionDenied` when read-only mode is active.
+    pub fn ensure_writable(&self) -> Result<(), CommandError> {
+        if self.is_enabled() {
+            return Err(CommandError::PermissionDenied(
+                "SSLBoard is in read-only mode; changes are disabled".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Preference writes are blocked too, except turning the preference-based
+    /// mode off again when it was not forced from the command line, and the
+    /// display locale, which changes nothing but how messages read.
+    pub fn ensure_preference_writable(&self, name: &str) -> Result<(), CommandError> {
+        if (name == READ_ONLY_PREFERENCE && !self.forced) || name == LOCALE_PREFERENCE {
+            return Ok(());
+        }
+        self.ensure_writable()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::Db;
+    use std::fs;
+    use uuid::Uuid;
+
+    #[test]
+    fn preference_toggles_mode_unless_forced() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("sslboard_ro_test_{}", Uuid::new_v4().as_simple()));
+        fs::create_dir_all(&dir)?;
+        let prefs = PreferencesStore::initialize(Db::initialize_with_path(&dir)?)?;
+
+        let mode = ReadOnlyMode::new(prefs.clone(), false);
+        assert!(mode.ensure_writable().is_ok());
+
+        prefs.set(READ_ONLY_PREFERENCE, "true")?;
+        assert!(matches!(
+            mode.ensure_writable(),
+            Err(CommandError::PermissionDenied(_))
+        ));
+        assert!(mode.ensure_preference_writable(READ_ONLY_PREFERENCE).is_ok());
+        assert!(mode.ensure_preference_writable("export_destination_dir").is_err());
+
+        let forced = ReadOnlyMode::new(prefs.clone(), true);
+        prefs.set(READ_ONLY_PREFERENCE, "false")?;
+        assert!(forced.ensure_preference_writable(READ_ONLY_PREFERENCE).is_err());
+        assert!(forced.ensure_preference_writable(LOCALE_PREFERENCE).is_ok());
+
+        drop(prefs);
+        drop(mode);
+        drop(forced);
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
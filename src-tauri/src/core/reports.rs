@@ -0,0 +1,492 @@
+//! Periodic certificate reports.
+//!
+//! Summarizes the certificates issued, renewed, failed and expiring over the
+//! last week or month and writes the summary as Markdown or HTML into a
+//! folder. The background schedule is driven by the `report_schedule`
+//! preference (`weekly` or `monthly`); the HTML report is self-contained so it
+//! can be printed to PDF for archiving.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Months, Utc};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::scheduler::{self, BackgroundTask};
+use crate::core::status::name_set;
+use crate::core::types::{
+    CertificateRecord, CertificateReportResult, CertificateSource, CertificateStatus,
+    ReportFormat, ReportPeriod,
+};
+use crate::domain::normalize_domains_for_display;
+use crate::issuance::flow::pending_issuance_domains;
+use crate::storage::inventory::{InventoryStore, IssuanceFailure};
+use crate::storage::preferences::PreferencesStore;
+
+/// Preference holding the schedule: `weekly`, `monthly`, or anything else for off.
+pub const REPORT_SCHEDULE_PREFERENCE: &str = "report_schedule";
+/// Preference holding the default format: `markdown` or `html`.
+pub const REPORT_FORMAT_PREFERENCE: &str = "report_format";
+/// Preference holding the folder reports are written to.
+pub const REPORT_FOLDER_PREFERENCE: &str = "report_folder";
+/// Preference recording when the last report was written (RFC 3339).
+pub const REPORT_LAST_RUN_PREFERENCE: &str = "report_last_generated_at";
+pub const REPORT_GENERATED_EVENT: &str = "report-generated";
+
+const SCHEDULE_TICK: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone)]
+pub struct ReportEntry {
+    pub names: Vec<String>,
+    pub issuer: String,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CertificateReport {
+    pub period: ReportPeriod,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Managed certificates issued in the period for names not seen before
+    pub issued: Vec<ReportEntry>,
+    /// Managed certificates issued in the period that replace an older one
+    pub renewed: Vec<ReportEntry>,
+    pub failed: Vec<IssuanceFailure>,
+    /// Certificates currently expiring soon, soonest first
+    pub expiring: Vec<ReportEntry>,
+}
+
+/// First instant covered by a report that ends at `end`.
+pub fn period_start(period: ReportPeriod, end: DateTime<Utc>) -> DateTime<Utc> {
+    match period {
+        ReportPeriod::Weekly => end - chrono::Duration::days(7),
+        ReportPeriod::Monthly => end
+            .checked_sub_months(Months::new(1))
+            .unwrap_or(end - chrono::Duration::days(30)),
+    }
+}
+
+/// Builds the report from inventory records whose statuses are current.
+pub fn build_report(
+    records: &[CertificateRecord],
+    failures: Vec<IssuanceFailure>,
+    period: ReportPeriod,
+    now: DateTime<Utc>,
+) -> CertificateReport {
+    let start = period_start(period, now);
+    let names: Vec<Vec<String>> = records.iter().map(|record| name_set(&record.sans)).collect();
+    let mut issued = Vec::new();
+    let mut renewed = Vec::new();
+    let mut expiring = Vec::new();
+
+    for (index, record) in records.iter().enumerate() {
+        let in_period = record.not_before >= start && record.not_before <= now;
+        if matches!(record.source, CertificateSource::Managed) && in_period {
+            let replaces_older = records.iter().enumerate().any(|(other_index, other)| {
+                other_index != index
+                    && names[other_index] == names[index]
+                    && other.not_before < record.not_before
+            });
+            if replaces_older {
+                renewed.push(entry(record));
+            } else {
+                issued.push(entry(record));
+            }
+        }
+        if matches!(
+            record.status,
+            CertificateStatus::ExpiringSoon | CertificateStatus::PendingRenewal
+        ) {
+            expiring.push(entry(record));
+        }
+    }
+
+    issued.sort_by_key(|entry| entry.not_before);
+    renewed.sort_by_key(|entry| entry.not_before);
+    expiring.sort_by_key(|entry| entry.not_after);
+    let failed = failures
+        .into_iter()
+        .filter(|failure| failure.failed_at >= start && failure.failed_at <= now)
+        .collect();
+
+    CertificateReport {
+        period,
+        start,
+        end: now,
+        issued,
+        renewed,
+        failed,
+        expiring,
+    }
+}
+
+fn entry(record: &CertificateRecord) -> ReportEntry {
+    ReportEntry {
+        names: normalize_domains_for_display(&record.sans),
+        issuer: record.issuer.clone(),
+        not_before: record.not_before,
+        not_after: record.not_after,
+    }
+}
+
+struct Section {
+    title: &'static str,
+    headers: &'static [&'static str],
+    rows: Vec<Vec<String>>,
+}
+
+fn sections(report: &CertificateReport) -> Vec<Section> {
+    let certificate_rows = |entries: &[ReportEntry]| {
+        entries
+            .iter()
+            .map(|entry| {
+                vec![
+                    entry.names.join(", "),
+                    entry.issuer.clone(),
+                    format_date(entry.not_before),
+                    format_date(entry.not_after),
+                ]
+            })
+            .collect()
+    };
+    vec![
+        Section {
+            title: "Issued",
+            headers: &["Names", "Issuer", "Valid from", "Expires"],
+            rows: certificate_rows(&report.issued),
+        },
+        Section {
+            title: "Renewed",
+            headers: &["Names", "Issuer", "Valid from", "Expires"],
+            rows: certificate_rows(&report.renewed),
+        },
+        Section {
+            title: "Failed",
+            headers: &["Names", "Failed at", "Reason"],
+            rows: report
+                .failed
+                .iter()
+                .map(|failure| {
+                    vec![
+                        normalize_domains_for_display(&failure.domains).join(", "),
+                        failure.failed_at.format("%Y-%m-%d %H:%M UTC").to_string(),
+                        failure.reason.clone(),
+                    ]
+                })
+                .collect(),
+        },
+        Section {
+            title: "Expiring",
+            headers: &["Names", "Issuer", "Expires", "Days left"],
+            rows: report
+                .expiring
+                .iter()
+                .map(|entry| {
+                    vec![
+                        entry.names.join(", "),
+                        entry.issuer.clone(),
+                        format_date(entry.not_after),
+                        (entry.not_after - report.end).num_days().max(0).to_string(),
+                    ]
+                })
+                .collect(),
+        },
+    ]
+}
+
+fn format_date(value: DateTime<Utc>) -> String {
+    value.format("%Y-%m-%d").to_string()
+}
+
+fn title(report: &CertificateReport) -> String {
+    let period = match report.period {
+        ReportPeriod::Weekly => "Weekly",
+        ReportPeriod::Monthly => "Monthly",
+    };
+    format!("{period} certificate report")
+}
+
+fn period_line(report: &CertificateReport) -> String {
+    format!(
+        "{} to {} (UTC)",
+        format_date(report.start),
+        format_date(report.end)
+    )
+}
+
+pub fn render_markdown(report: &CertificateReport) -> String {
+    let cell = |value: &str| value.replace('|', "\\|").replace(['\r', '\n'], " ");
+    let sections = sections(report);
+    let mut out = format!("# {}\n\n{}\n\n", title(report), period_line(report));
+    out.push_str("| | Count |\n|---|---|\n");
+    for section in &sections {
+        out.push_str(&format!("| {} | {} |\n", section.title, section.rows.len()));
+    }
+    for section in &sections {
+        out.push_str(&format!("\n## {}\n\n", section.title));
+        if section.rows.is_empty() {
+            out.push_str("None.\n");
+            continue;
+        }
+        out.push_str(&format!("| {} |\n", section.headers.join(" | ")));
+        out.push_str(&format!("|{}\n", "---|".repeat(section.headers.len())));
+        for row in &section.rows {
+            let cells: Vec<String> = row.iter().map(|value| cell(value)).collect();
+            out.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+    }
+    out
+}
+
+pub fn render_html(report: &CertificateReport) -> String {
+    let sections = sections(report);
+    let title = escape_html(&title(report));
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>\n\
+         body {{ font-family: system-ui, sans-serif; margin: 2rem; color: #111; }}\n\
+         table {{ border-collapse: collapse; margin-bottom: 1.5rem; }}\n\
+         th, td {{ border: 1px solid #ccc; padding: 0.3rem 0.6rem; text-align: left; }}\n\
+         th {{ background: #f3f3f3; }}\n\
+         </style>\n</head>\n<body>\n<h1>{title}</h1>\n<p>{}</p>\n",
+        escape_html(&period_line(report))
+    );
+    out.push_str("<table>\n<tr><th></th><th>Count</th></tr>\n");
+    for section in &sections {
+        out.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td></tr>\n",
+            section.title,
+            section.rows.len()
+        ));
+    }
+    out.push_str("</table>\n");
+    for section in &sections {
+        out.push_str(&format!("<h2>{}</h2>\n", section.title));
+        if section.rows.is_empty() {
+            out.push_str("<p>None.</p>\n");
+            continue;
+        }
+        out.push_str("<table>\n<tr>");
+        for header in section.headers {
+            out.push_str(&format!("<th>{header}</th>"));
+        }
+        out.push_str("</tr>\n");
+        for row in &section.rows {
+            out.push_str("<tr>");
+            for value in row {
+                out.push_str(&format!("<td>{}</td>", escape_html(value)));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn read_preference(prefs: &PreferencesStore, name: &str) -> Option<String> {
+    match prefs.get(name) {
+        Ok(Some(pref)) => Some(pref.value.trim().to_string()).filter(|value| !value.is_empty()),
+        Ok(None) => None,
+        Err(err) => {
+            log::warn!("[reports] failed to read preference {name}: {err}");
+            None
+        }
+    }
+}
+
+fn scheduled_period(prefs: &PreferencesStore) -> Option<ReportPeriod> {
+    match read_preference(prefs, REPORT_SCHEDULE_PREFERENCE)?.as_str() {
+        "weekly" => Some(ReportPeriod::Weekly),
+        "monthly" => Some(ReportPeriod::Monthly),
+        _ => None,
+    }
+}
+
+/// A scheduled report is due once a full period has passed since the last one.
+fn is_due(period: ReportPeriod, last_run: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    last_run.is_none_or(|last| period_start(period, now) >= last)
+}
+
+/// Builds a report for the period ending now and writes it to the folder.
+pub fn generate_report(
+    inventory: &InventoryStore,
+    prefs: &PreferencesStore,
+    period: ReportPeriod,
+    format: Option<ReportFormat>,
+    folder: Option<String>,
+) -> Result<CertificateReportResult> {
+    let format = format.unwrap_or_else(|| {
+        match read_preference(prefs, REPORT_FORMAT_PREFERENCE).as_deref() {
+            Some("html") => ReportFormat::Html,
+            _ => ReportFormat::Markdown,
+        }
+    });
+    let folder = folder
+        .map(|folder| folder.trim().to_string())
+        .filter(|folder| !folder.is_empty())
+        .or_else(|| read_preference(prefs, REPORT_FOLDER_PREFERENCE))
+        .ok_or_else(|| anyhow!("No report folder configured"))?;
+
+    let now = Utc::now();
+    let records = inventory.refresh_statuses(&pending_issuance_domains())?;
+    let failures = inventory.issuance_failures_since(period_start(period, now))?;
+    let report = build_report(&records, failures, period, now);
+
+    let (contents, extension) = match format {
+        ReportFormat::Markdown => (render_markdown(&report), "md"),
+        ReportFormat::Html => (render_html(&report), "html"),
+    };
+    let period_name = match period {
+        ReportPeriod::Weekly => "weekly",
+        ReportPeriod::Monthly => "monthly",
+    };
+    let folder = PathBuf::from(folder);
+    fs::create_dir_all(&folder)
+        .with_context(|| format!("failed to create report folder {}", folder.display()))?;
+    let path = folder.join(format!(
+        "sslboard-{period_name}-report-{}.{extension}",
+        now.format("%Y-%m-%d")
+    ));
+    fs::write(&path, contents)
+        .with_context(|| format!("failed to write report {}", path.display()))?;
+    prefs.set(REPORT_LAST_RUN_PREFERENCE, &now.to_rfc3339())?;
+
+    Ok(CertificateReportResult {
+        path: path.display().to_string(),
+        period,
+        period_start: report.start,
+        period_end: report.end,
+        issued: report.issued.len(),
+        renewed: report.renewed.len(),
+        failed: report.failed.len(),
+        expiring: report.expiring.len(),
+    })
+}
+
+/// Starts the background report schedule. Checks hourly and writes a report
+/// once the configured period has elapsed; skipped in read-only mode.
+pub fn spawn_scheduled_reports(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let handle = app.clone();
+            let outcome = tauri::async_runtime::spawn_blocking(move || {
+                if handle.state::<ReadOnlyMode>().is_enabled() {
+                    return Ok(None);
+                }
+                let prefs = handle.state::<PreferencesStore>().inner().clone();
+                let Some(period) = scheduled_period(&prefs) else {
+                    return Ok(None);
+                };
+                let last_run = read_preference(&prefs, REPORT_LAST_RUN_PREFERENCE)
+                    .and_then(|raw| DateTime::parse_from_rfc3339(&raw).ok())
+                    .map(|dt| dt.with_timezone(&Utc));
+                if !is_due(period, last_run, Utc::now())
+                    || !scheduler::should_run(BackgroundTask::Reports, &prefs)
+                {
+                    return Ok(None);
+                }
+                let inventory = handle.state::<InventoryStore>().inner().clone();
+                generate_report(&inventory, &prefs, period, None, None).map(Some)
+            })
+            .await;
+
+            match outcome {
+                Ok(Ok(Some(result))) => {
+                    log::info!("[reports] wrote scheduled report to {}", result.path);
+                    if let Err(err) = app.emit(REPORT_GENERATED_EVENT, &result) {
+                        log::warn!("[reports] failed to emit report event: {err}");
+                    }
+                }
+                Ok(Ok(None)) => {}
+                Ok(Err(err)) => log::warn!("[reports] scheduled report failed: {err}"),
+                Err(err) => log::warn!("[reports] scheduled report join error: {err}"),
+            }
+
+            scheduler::wait(BackgroundTask::Reports, SCHEDULE_TICK).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(
+        id: &str,
+        names: &[&str],
+        issued_days_ago: i64,
+        status: CertificateStatus,
+    ) -> CertificateRecord {
+        let issued = Utc::now() - chrono::Duration::days(issued_days_ago);
+        CertificateRecord {
+            issuer: "R11".to_string(),
+            not_before: issued,
+            not_after: issued + chrono::Duration::days(90),
+            domain_roots: names.iter().map(|name| name.to_string()).collect(),
+            status,
+            ..CertificateRecord::test_record(id, names)
+        }
+    }
+
+    #[test]
+    fn classifies_issued_renewed_failed_and_expiring() {
+        let now = Utc::now();
+        let records = vec![
+            record("old", &["a.example.com"], 80, CertificateStatus::Superseded),
+            record("renewal", &["A.example.com"], 2, CertificateStatus::Active),
+            record("new", &["b.example.com"], 3, CertificateStatus::Active),
+            record("soon", &["c.example.com"], 75, CertificateStatus::ExpiringSoon),
+        ];
+        let failures = vec![
+            IssuanceFailure {
+                failed_at: now - chrono::Duration::days(1),
+                domains: vec!["d.example.com".to_string()],
+                reason: "order invalid | rejected".to_string(),
+            },
+            IssuanceFailure {
+                failed_at: now - chrono::Duration::days(20),
+                domains: vec!["e.example.com".to_string()],
+                reason: "too old".to_string(),
+            },
+        ];
+
+        let report = build_report(&records, failures, ReportPeriod::Weekly, now);
+        assert_eq!(report.issued.len(), 1);
+        assert_eq!(report.issued[0].names, vec!["b.example.com".to_string()]);
+        assert_eq!(report.renewed.len(), 1);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.expiring.len(), 1);
+
+        let markdown = render_markdown(&report);
+        assert!(markdown.starts_with("# Weekly certificate report"));
+        assert!(markdown.contains("| Failed | 1 |"));
+        assert!(markdown.contains("order invalid \\| rejected"));
+
+        let html = render_html(&report);
+        assert!(html.contains("<h2>Renewed</h2>"));
+        assert_eq!(escape_html("<a href=\"x\">"), "&lt;a href=&quot;x&quot;&gt;");
+    }
+
+    #[test]
+    fn schedule_is_due_after_a_full_period() {
+        let now = Utc::now();
+        assert!(is_due(ReportPeriod::Weekly, None, now));
+        assert!(!is_due(ReportPeriod::Weekly, Some(now - chrono::Duration::days(6)), now));
+        assert!(is_due(ReportPeriod::Weekly, Some(now - chrono::Duration::days(7)), now));
+        assert!(!is_due(ReportPeriod::Monthly, Some(now - chrono::Duration::days(20)), now));
+    }
+}
@@ -0,0 +1,48 @@
+//! Shared Tokio runtime for blocking code that drives async SDK calls.
+//!
+//! The AWS SDKs are async-only while the DNS adapters and deployers run on
+//! blocking threads. Building a runtime per call is slow and leaves the SDK's
+//! pooled connections tied to a runtime that is about to be dropped, so every
+//! such call goes through one lazily built runtime instead.
+
+use std::future::Future;
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Result};
+use tokio::runtime::{Builder, Runtime};
+
+const WORKER_THREADS: usize = 2;
+
+fn shared() -> Result<&'static Runtime> {
+    static RUNTIME: OnceLock<Result<Runtime, String>> = OnceLock::new();
+    RUNTIME
+        .get_or_init(|| {
+            Builder::new_multi_thread()
+                .worker_threads(WORKER_THREADS)
+                .thread_name("sslboard-sdk")
+                .enable_all()
+                .build()
+                .map_err(|err| err.to_string())
+        })
+        .as_ref()
+        .map_err(|err| anyhow!("Failed to create tokio runtime: {err}"))
+}
+
+/// Runs `future` to completion on the shared runtime. Must be called from a
+/// blocking thread (e.g. inside `spawn_blocking`), never from async code.
+pub fn block_on<F: Future>(future: F) -> Result<F::Output> {
+    Ok(shared()?.block_on(future))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuses_one_runtime_across_threads() {
+        let first = std::thread::spawn(|| block_on(async { 1 }).unwrap());
+        let second = std::thread::spawn(|| block_on(async { 2 }).unwrap());
+        assert_eq!(first.join().unwrap() + second.join().unwrap(), 3);
+        assert!(std::ptr::eq(shared().unwrap(), shared().unwrap()));
+    }
+}
@@ -0,0 +1,272 @@
+//! Throttling shared by the background tasks: update checks, reports,
+//! backups, the status feed and provider health checks.
+//!
+//! Each task asks [`should_run`] before doing its work. Runs are held back
+//! during quiet hours (`scheduler_quiet_hours`, local `HH:MM-HH:MM`), while
+//! on battery (`scheduler_pause_on_battery`) and on metered connections
+//! (`scheduler_pause_on_metered`). A held-back task tries again after
+//! [`DEFERRED_RETRY`] instead of its full interval. [`run_now`] wakes every
+//! task and lets its next run ignore those limits; tasks still only do work
+//! that is due.
+
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use chrono::{Local, NaiveTime};
+use tokio::sync::Notify;
+
+use crate::core::types::{BackgroundDeferral, BackgroundStatus};
+use crate::storage::preferences::PreferencesStore;
+
+/// Preference holding the quiet hours as `HH:MM-HH:MM` in local time; the
+/// range may wrap past midnight.
+pub const QUIET_HOURS_PREFERENCE: &str = "scheduler_quiet_hours";
+/// Preference (`true`/`false`) holding tasks back while on battery.
+pub const PAUSE_ON_BATTERY_PREFERENCE: &str = "scheduler_pause_on_battery";
+/// Preference (`true`/`false`) holding tasks back on metered connections.
+pub const PAUSE_ON_METERED_PREFERENCE: &str = "scheduler_pause_on_metered";
+
+const DEFERRED_RETRY: Duration = Duration::from_secs(15 * 60);
+
+const TASK_COUNT: usize = 5;
+
+#[derive(Debug, Clone, Copy)]
+pub enum BackgroundTask {
+    UpdateCheck,
+    Reports,
+    Backups,
+    StatusFeed,
+    ProviderHealth,
+}
+
+impl BackgroundTask {
+    const ALL: [Self; TASK_COUNT] = [
+        Self::UpdateCheck,
+        Self::Reports,
+        Self::Backups,
+        Self::StatusFeed,
+        Self::ProviderHealth,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+static WAKE: [Notify; TASK_COUNT] = [const { Notify::const_new() }; TASK_COUNT];
+/// Set by [`run_now`]; the task's next run skips the throttling checks
+static FORCED: [AtomicBool; TASK_COUNT] = [const { AtomicBool::new(false) }; TASK_COUNT];
+/// Set while the task's last run was held back
+static DEFERRED: [AtomicBool; TASK_COUNT] = [const { AtomicBool::new(false) }; TASK_COUNT];
+
+fn read_preference(prefs: &PreferencesStore, name: &str) -> Option<String> {
+    match prefs.get(name) {
+        Ok(Some(pref)) => Some(pref.value.trim().to_string()).filter(|value| !value.is_empty()),
+        Ok(None) => None,
+        Err(err) => {
+            log::warn!("[scheduler] failed to read preference {name}: {err}");
+            None
+        }
+    }
+}
+
+fn preference_enabled(prefs: &PreferencesStore, name: &str) -> bool {
+    read_preference(prefs, name).as_deref() == Some("true")
+}
+
+/// Parses `HH:MM-HH:MM`. Equal start and end times mean no quiet hours.
+fn parse_quiet_hours(raw: &str) -> Option<(NaiveTime, NaiveTime)> {
+    let (start, end) = raw.split_once('-')?;
+    let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").ok()?;
+    let end = NaiveTime::parse_from_str(end.trim(), "%H:%M").ok()?;
+    (start != end).then_some((start, end))
+}
+
+fn within(time: NaiveTime, (start, end): (NaiveTime, NaiveTime)) -> bool {
+    if start < end {
+        start <= time && time < end
+    } else {
+        time >= start || time < end
+    }
+}
+
+fn in_quiet_hours(prefs: &PreferencesStore) -> bool {
+    let Some(raw) = read_preference(prefs, QUIET_HOURS_PREFERENCE) else {
+        return false;
+    };
+    match parse_quiet_hours(&raw) {
+        Some(range) => within(Local::now().time(), range),
+        None => {
+            log::warn!("[scheduler] ignoring invalid quiet hours {raw:?}");
+            false
+        }
+    }
+}
+
+/// Whether the machine runs on battery.
+#[cfg(target_os = "linux")]
+fn on_battery() -> Option<bool> {
+    let supplies = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    let mut has_battery = false;
+    for supply in supplies.flatten() {
+        let read = |file: &str| std::fs::read_to_string(supply.path().join(file)).ok();
+        match read("type").as_deref().map(str::trim) {
+            Some("Mains") if read("online").as_deref().map(str::trim) == Some("1") => {
+                return Some(false);
+            }
+            Some("Battery") => has_battery = true,
+            _ => {}
+        }
+    }
+    Some(has_battery)
+}
+
+#[cfg(target_os = "macos")]
+fn on_battery() -> Option<bool> {
+    let output = Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let first = text.lines().next()?;
+    if first.contains("'Battery Power'") {
+        Some(true)
+    } else if first.contains("'AC Power'") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(windows)]
+fn on_battery() -> Option<bool> {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    // SAFETY: the struct is plain data and the call only writes into it.
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return None;
+    }
+    match status.ACLineStatus {
+        0 => Some(true),
+        1 => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn on_battery() -> Option<bool> {
+    None
+}
+
+/// Whether the active connection is metered, as NetworkManager reports it.
+/// Other platforms offer no command-line way to tell.
+fn metered_connection() -> Option<bool> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let output = Command::new("busctl")
+        .args([
+            "get-property",
+            "org.freedesktop.NetworkManager",
+            "/org/freedesktop/NetworkManager",
+            "org.freedesktop.NetworkManager",
+            "Metered",
+        ])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    // NMMetered: 1 yes, 2 no, 3 guessed yes, 4 guessed no
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "u 1" | "u 3" => Some(true),
+        "u 2" | "u 4" => Some(false),
+        _ => None,
+    }
+}
+
+/// Current quiet hours, power and connection state, and what of it holds
+/// tasks back under the saved preferences.
+pub fn status(prefs: &PreferencesStore) -> BackgroundStatus {
+    let in_quiet_hours = in_quiet_hours(prefs);
+    let on_battery = on_battery();
+    let metered = metered_connection();
+    let deferred_by = if in_quiet_hours {
+        Some(BackgroundDeferral::QuietHours)
+    } else if on_battery == Some(true) && preference_enabled(prefs, PAUSE_ON_BATTERY_PREFERENCE) {
+        Some(BackgroundDeferral::OnBattery)
+    } else if metered == Some(true) && preference_enabled(prefs, PAUSE_ON_METERED_PREFERENCE) {
+        Some(BackgroundDeferral::MeteredConnection)
+    } else {
+        None
+    };
+    BackgroundStatus {
+        deferred_by,
+        in_quiet_hours,
+        on_battery,
+        metered,
+    }
+}
+
+/// Whether `task` may do its work now: always after [`run_now`], otherwise
+/// only when nothing holds it back.
+pub fn should_run(task: BackgroundTask, prefs: &PreferencesStore) -> bool {
+    let index = task.index();
+    if FORCED[index].swap(false, Ordering::Relaxed) {
+        DEFERRED[index].store(false, Ordering::Relaxed);
+        return true;
+    }
+    let deferred_by = status(prefs).deferred_by;
+    if let Some(reason) = deferred_by {
+        log::debug!("[scheduler] {task:?} held back: {reason:?}");
+    }
+    DEFERRED[index].store(deferred_by.is_some(), Ordering::Relaxed);
+    deferred_by.is_none()
+}
+
+/// Sleeps until the task's next run: `interval`, or [`DEFERRED_RETRY`]
+/// after a held-back run, or until [`run_now`] is called.
+pub async fn wait(task: BackgroundTask, interval: Duration) {
+    let index = task.index();
+    let interval = if DEFERRED[index].load(Ordering::Relaxed) {
+        interval.min(DEFERRED_RETRY)
+    } else {
+        interval
+    };
+    if tokio::time::timeout(interval, WAKE[index].notified()).await.is_err() {
+        // A run-now request the last run had no due work for expires here
+        FORCED[index].store(false, Ordering::Relaxed);
+    }
+}
+
+/// Runs every background task now, ignoring quiet hours, battery and
+/// metered connections for that run.
+pub fn run_now() {
+    for task in BackgroundTask::ALL {
+        FORCED[task.index()].store(true, Ordering::Relaxed);
+        WAKE[task.index()].notify_one();
+    }
+    log::info!("[scheduler] running background tasks now");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(raw: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(raw, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn quiet_hours_may_wrap_past_midnight() {
+        let night = parse_quiet_hours("22:00-07:00").unwrap();
+        assert!(within(at("23:30"), night));
+        assert!(within(at("06:59"), night));
+        assert!(!within(at("07:00"), night));
+        assert!(!within(at("12:00"), night));
+
+        let lunch = parse_quiet_hours(" 12:00 - 13:30 ").unwrap();
+        assert!(within(at("12:00"), lunch));
+        assert!(!within(at("13:30"), lunch));
+
+        assert!(parse_quiet_hours("09:00-09:00").is_none());
+        assert!(parse_quiet_hours("9pm-7am").is_none());
+    }
+}
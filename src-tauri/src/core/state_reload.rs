@@ -0,0 +1,59 @@
+use anyhow::Result;
+use chrono::Utc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::core::types::{StateReloadReason, StateReloaded};
+use crate::issuance::flow::abandon_pending_issuances;
+use crate::secrets::directory_store::MANAGED_KEY_DIRECTORY_PREFERENCE;
+use crate::secrets::manager::SecretManager;
+use crate::storage::{db::Db, dns::DnsConfigStore, jobs::JobStore, preferences::PreferencesStore};
+
+/// Emitted to every window once the state has been reloaded, so each one
+/// fetches its data again.
+pub const STATE_RELOADED_EVENT: &str = "state://reloaded";
+
+/// Points new managed keys at the directory saved in preferences, or back
+/// into the vault when none is set.
+pub fn apply_managed_key_directory(
+    secrets: &SecretManager,
+    prefs: &PreferencesStore,
+) -> Result<()> {
+    let directory = prefs
+        .get(MANAGED_KEY_DIRECTORY_PREFERENCE)?
+        .map(|pref| pref.value)
+        .filter(|value| !value.trim().is_empty());
+    secrets.set_managed_key_directory(directory.map(Into::into));
+    Ok(())
+}
+
+/// Re-runs the startup work of the managed stores against the current
+/// database contents and tells every window to refresh.
+///
+/// Called after the database was replaced in place (restore, profile switch,
+/// legacy import). Stores read through the shared pool, so they see the new
+/// data without being rebuilt; what is redone here is everything setup
+/// derived from the old contents.
+pub fn reload_state(app: &AppHandle, reason: StateReloadReason) -> Result<StateReloaded> {
+    log::info!("[state] reloading after {reason:?}");
+    let db = app.state::<Db>();
+    let secrets = app.state::<SecretManager>();
+    let dns_store = app.state::<DnsConfigStore>();
+
+    // Sessions reference keys and journal entries of the old database.
+    let abandoned_issuances = abandon_pending_issuances(&dns_store, &secrets);
+    db.reload()?;
+    DnsConfigStore::initialize(db.inner().clone())?;
+    let interrupted_jobs = app.state::<JobStore>().mark_interrupted()?;
+    apply_managed_key_directory(&secrets, &app.state::<PreferencesStore>())?;
+
+    let reloaded = StateReloaded {
+        reason,
+        abandoned_issuances,
+        interrupted_jobs,
+        reloaded_at: Utc::now(),
+    };
+    if let Err(err) = app.emit(STATE_RELOADED_EVENT, &reloaded) {
+        log::warn!("[state] failed to emit reload event: {err}");
+    }
+    Ok(reloaded)
+}
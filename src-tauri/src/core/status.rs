@@ -0,0 +1,112 @@
+//! Certificate status derivation.
+//!
+//! One place decides whether a certificate is active, expiring, expired,
+//! superseded by a renewal, waiting on an in-flight renewal, or revoked.
+//! Revocation is not observable from the inventory itself, so a stored
+//! `revoked` status is kept as-is.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::core::types::{CertificateRecord, CertificateStatus};
+
+/// Certificates expiring within this many days are reported as expiring soon.
+pub const EXPIRING_SOON_DAYS: i64 = 30;
+
+/// Computes the status for every record.
+///
+/// `pending_renewals` lists the domain sets of managed issuances that are
+/// currently in progress. Statuses are returned in the same order as `records`.
+pub fn derive_statuses(
+    records: &[CertificateRecord],
+    pending_renewals: &[Vec<String>],
+    now: DateTime<Utc>,
+) -> Vec<CertificateStatus> {
+    let pending: HashSet<Vec<String>> = pending_renewals
+        .iter()
+        .map(|domains| name_set(domains))
+        .collect();
+    let names: Vec<Vec<String>> = records.iter().map(|record| name_set(&record.sans)).collect();
+
+    records
+        .iter()
+        .enumerate()
+        .map(|(index, record)| {
+            if record.status == CertificateStatus::Revoked {
+                return CertificateStatus::Revoked;
+            }
+            let superseded = records.iter().enumerate().any(|(other_index, other)| {
+                other_index != index
+                    && other.status != CertificateStatus::Revoked
+                    && names[other_index] == names[index]
+                    && other.not_before > record.not_before
+            });
+            if superseded {
+                CertificateStatus::Superseded
+            } else if record.not_after <= now {
+                CertificateStatus::Expired
+            } else if pending.contains(&names[index]) {
+                CertificateStatus::PendingRenewal
+            } else if record.not_after - now < Duration::days(EXPIRING_SOON_DAYS) {
+                CertificateStatus::ExpiringSoon
+            } else {
+                CertificateStatus::Active
+            }
+        })
+        .collect()
+}
+
+/// Lowercased, deduplicated and sorted names, used to match renewals.
+pub(crate) fn name_set(domains: &[String]) -> Vec<String> {
+    let mut names: Vec<String> = domains
+        .iter()
+        .map(|domain| domain.trim().trim_end_matches('.').to_ascii_lowercase())
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, domains: &[&str], start: i64, end: i64, now: DateTime<Utc>) -> CertificateRecord {
+        CertificateRecord {
+            not_before: now + Duration::days(start),
+            not_after: now + Duration::days(end),
+            ..CertificateRecord::test_record(id, domains)
+        }
+    }
+
+    #[test]
+    fn derives_each_status() {
+        let now = Utc::now();
+        let mut revoked = record("revoked", &["r.example.com"], -10, 80, now);
+        revoked.status = CertificateStatus::Revoked;
+        let records = vec![
+            record("active", &["a.example.com"], -10, 80, now),
+            record("expiring", &["b.example.com"], -80, 10, now),
+            record("expired", &["c.example.com"], -100, -1, now),
+            record("old", &["d.example.com", "www.d.example.com"], -100, -10, now),
+            record("new", &["WWW.d.example.com", "d.example.com"], -5, 85, now),
+            record("renewing", &["e.example.com"], -80, 5, now),
+            revoked,
+        ];
+        let pending = vec![vec!["e.example.com".to_string()]];
+
+        assert_eq!(
+            derive_statuses(&records, &pending, now),
+            vec![
+                CertificateStatus::Active,
+                CertificateStatus::ExpiringSoon,
+                CertificateStatus::Expired,
+                CertificateStatus::Superseded,
+                CertificateStatus::Active,
+                CertificateStatus::PendingRenewal,
+                CertificateStatus::Revoked,
+            ]
+        );
+    }
+}
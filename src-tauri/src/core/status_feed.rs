@@ -0,0 +1,217 @@
+//! Machine-readable certificate status file.
+//!
+//! Writes a small JSON summary of inventory health (counts by status and the
+//! soonest expiry) to the path in the `status_feed_path` preference and keeps
+//! it fresh in the background, so dashboards such as Home Assistant or
+//! Grafana can read certificate health from a file without an API.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use tauri::{AppHandle, Manager};
+
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::scheduler::{self, BackgroundTask};
+use crate::core::types::{
+    CertificateRecord, CertificateStatus, StatusFeed, StatusFeedExpiry, StatusFeedHealth,
+    StatusFeedResult,
+};
+use crate::domain::normalize_domains_for_display;
+use crate::issuance::flow::pending_issuance_domains;
+use crate::storage::inventory::InventoryStore;
+use crate::storage::preferences::PreferencesStore;
+
+/// Preference holding the file the feed is written to; unset turns it off.
+pub const STATUS_FEED_PATH_PREFERENCE: &str = "status_feed_path";
+
+const FEED_TICK: Duration = Duration::from_secs(15 * 60);
+
+/// Summarizes inventory records whose statuses are current.
+pub fn build_feed(records: &[CertificateRecord], now: DateTime<Utc>) -> StatusFeed {
+    let current: Vec<&CertificateRecord> = records
+        .iter()
+        .filter(|record| {
+            !matches!(
+                record.status,
+                CertificateStatus::Superseded | CertificateStatus::Revoked
+            )
+        })
+        .collect();
+    let count = |status: CertificateStatus| {
+        current
+            .iter()
+            .filter(|record| record.status == status)
+            .count()
+    };
+    let active = count(CertificateStatus::Active);
+    let expiring_soon = count(CertificateStatus::ExpiringSoon);
+    let pending_renewal = count(CertificateStatus::PendingRenewal);
+    let expired = count(CertificateStatus::Expired);
+
+    let health = if expired > 0 {
+        StatusFeedHealth::Critical
+    } else if expiring_soon > 0 || pending_renewal > 0 {
+        StatusFeedHealth::Warning
+    } else {
+        StatusFeedHealth::Ok
+    };
+    let worst_expiry = current
+        .iter()
+        .min_by_key(|record| record.not_after)
+        .map(|record| StatusFeedExpiry {
+            names: normalize_domains_for_display(&record.sans),
+            not_after: record.not_after,
+            days_left: (record.not_after - now).num_days(),
+        });
+
+    StatusFeed {
+        generated_at: now,
+        health,
+        total: current.len(),
+        active,
+        expiring_soon,
+        pending_renewal,
+        expired,
+        worst_expiry,
+    }
+}
+
+fn read_path(prefs: &PreferencesStore) -> Option<String> {
+    match prefs.get(STATUS_FEED_PATH_PREFERENCE) {
+        Ok(Some(pref)) => Some(pref.value.trim().to_string()).filter(|value| !value.is_empty()),
+        Ok(None) => None,
+        Err(err) => {
+            log::warn!("[status-feed] failed to read preference: {err}");
+            None
+        }
+    }
+}
+
+/// Writes through a temporary file so readers never see a partial feed.
+fn write_atomically(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create folder {}", parent.display()))?;
+    }
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+    fs::write(&temp, contents)
+        .with_context(|| format!("failed to write status feed {}", temp.display()))?;
+    fs::rename(&temp, path)
+        .with_context(|| format!("failed to write status feed {}", path.display()))?;
+    Ok(())
+}
+
+/// Builds the feed from current inventory and writes it to `path`, or to the
+/// configured path when none is given.
+pub fn write_status_feed(
+    inventory: &InventoryStore,
+    prefs: &PreferencesStore,
+    path: Option<String>,
+) -> Result<StatusFeedResult> {
+    let path = path
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .or_else(|| read_path(prefs))
+        .ok_or_else(|| anyhow!("No status feed path configured"))?;
+
+    let records = inventory.refresh_statuses(&pending_issuance_domains())?;
+    let feed = build_feed(&records, Utc::now());
+    let path = PathBuf::from(path);
+    write_atomically(&path, &serde_json::to_string_pretty(&feed)?)?;
+
+    Ok(StatusFeedResult {
+        path: path.display().to_string(),
+        feed,
+    })
+}
+
+/// Starts the background refresh. Rewrites the feed every 15 minutes while a
+/// path is configured; skipped in read-only mode.
+pub fn spawn_scheduled_status_feed(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let handle = app.clone();
+            let outcome = tauri::async_runtime::spawn_blocking(move || {
+                if handle.state::<ReadOnlyMode>().is_enabled() {
+                    return Ok(None);
+                }
+                let prefs = handle.state::<PreferencesStore>().inner().clone();
+                if read_path(&prefs).is_none()
+                    || !scheduler::should_run(BackgroundTask::StatusFeed, &prefs)
+                {
+                    return Ok(None);
+                }
+                let inventory = handle.state::<InventoryStore>().inner().clone();
+                write_status_feed(&inventory, &prefs, None).map(Some)
+            })
+            .await;
+
+            match outcome {
+                Ok(Ok(Some(result))) => {
+                    log::debug!("[status-feed] wrote status feed to {}", result.path);
+                }
+                Ok(Ok(None)) => {}
+                Ok(Err(err)) => log::warn!("[status-feed] scheduled write failed: {err}"),
+                Err(err) => log::warn!("[status-feed] scheduled write join error: {err}"),
+            }
+
+            scheduler::wait(BackgroundTask::StatusFeed, FEED_TICK).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, expires_in_days: i64, status: CertificateStatus) -> CertificateRecord {
+        let now = Utc::now();
+        let name = format!("{id}.example.com");
+        CertificateRecord {
+            issuer: "R11".to_string(),
+            not_before: now - chrono::Duration::days(90 - expires_in_days),
+            not_after: now + chrono::Duration::days(expires_in_days),
+            domain_roots: vec![name.clone()],
+            status,
+            ..CertificateRecord::test_record(id, &[&name])
+        }
+    }
+
+    #[test]
+    fn feed_counts_current_certificates_and_soonest_expiry() {
+        let now = Utc::now();
+        let mut records = vec![
+            record("old", -30, CertificateStatus::Superseded),
+            record("fine", 60, CertificateStatus::Active),
+            record("soon", 10, CertificateStatus::ExpiringSoon),
+        ];
+        let feed = build_feed(&records, now);
+        assert_eq!(feed.health, StatusFeedHealth::Warning);
+        assert_eq!((feed.total, feed.active, feed.expiring_soon), (2, 1, 1));
+        let worst = feed.worst_expiry.unwrap();
+        assert_eq!(worst.names, vec!["soon.example.com".to_string()]);
+        assert_eq!(worst.days_left, 10);
+
+        records.push(record("gone", -2, CertificateStatus::Expired));
+        let feed = build_feed(&records, now);
+        assert_eq!(feed.health, StatusFeedHealth::Critical);
+        assert!(feed.worst_expiry.as_ref().unwrap().days_left < 0);
+
+        assert_eq!(build_feed(&[], now).health, StatusFeedHealth::Ok);
+
+        let dir = std::env::temp_dir()
+            .join(format!("sslboard_status_feed_test_{}", uuid::Uuid::new_v4()));
+        let path = dir.join("status.json");
+        write_atomically(&path, &serde_json::to_string(&feed).unwrap()).unwrap();
+        let written: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["health"], "critical");
+        assert_eq!(written["expired"], 1);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -1,8 +1,12 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::issuance::dns::{DnsPropagationResult, DnsRecordInstruction};
+use crate::issuance::dns_wire::DnsTransport;
+use crate::secrets::sealed::SealedSecret;
 use crate::secrets::types::SecretMetadata;
 
 /// Represents the source of a certificate record, indicating whether it was
@@ -29,6 +33,22 @@ pub enum KeyCurve {
     P384,
 }
 
+/// Lifecycle state derived from validity dates, renewals, and issuance progress.
+/// Computed in the backend so listings, filters, and alerts agree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CertificateStatus {
+    #[default]
+    Active,
+    ExpiringSoon,
+    Expired,
+    Revoked,
+    /// A newer certificate covers the same names
+    Superseded,
+    /// A managed issuance for the same names is in progress
+    PendingRenewal,
+}
+
 /// Represents a complete certificate record with all metadata and validation information.
 /// This structure is used for storing, retrieving, and displaying SSL/TLS certificate data.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,12 +79,97 @@ pub struct CertificateRecord {
     pub managed_key_ref: Option<String>,
     /// PEM-encoded certificate chain for export
     pub chain_pem: Option<String>,
+    /// Root name of the chain selected at download (e.g. "ISRG Root X1")
+    pub chain_name: Option<String>,
     /// Key algorithm metadata for managed issuance (rsa/ecdsa)
     pub key_algorithm: Option<KeyAlgorithm>,
     /// RSA key size when applicable
     pub key_size: Option<u16>,
     /// ECDSA curve when applicable
     pub key_curve: Option<KeyCurve>,
+    /// Derived lifecycle status, refreshed whenever the inventory is listed
+    #[serde(default)]
+    pub status: CertificateStatus,
+    /// Issuer configuration that issued a managed certificate
+    #[serde(default)]
+    pub issuer_id: Option<String>,
+    /// Staging certificate this one was promoted from
+    #[serde(default)]
+    pub promoted_from: Option<String>,
+    /// Fixes applied to `chain_pem` when it was stored
+    #[serde(default)]
+    pub chain_repairs: Vec<ChainRepair>,
+    /// ASCII and Unicode forms of every name in `sans` and `domain_roots`;
+    /// filled when the record is returned to the frontend
+    #[serde(default)]
+    pub name_forms: Vec<DomainForms>,
+}
+
+#[cfg(test)]
+impl CertificateRecord {
+    /// Active managed certificate for `names`, valid for 90 days from now.
+    /// Tests override the fields they care about with struct update syntax.
+    pub(crate) fn test_record(id: &str, names: &[&str]) -> Self {
+        let names: Vec<String> = names.iter().map(|name| name.to_string()).collect();
+        let now = Utc::now();
+        Self {
+            id: id.to_string(),
+            subjects: names.clone(),
+            sans: names,
+            issuer: "Test CA".to_string(),
+            serial: id.to_string(),
+            not_before: now,
+            not_after: now + chrono::Duration::days(90),
+            fingerprint: id.to_string(),
+            source: CertificateSource::Managed,
+            domain_roots: Vec::new(),
+            tags: Vec::new(),
+            managed_key_ref: None,
+            chain_pem: None,
+            chain_name: None,
+            key_algorithm: None,
+            key_size: None,
+            key_curve: None,
+            status: CertificateStatus::Active,
+            issuer_id: None,
+            promoted_from: None,
+            chain_repairs: Vec::new(),
+            name_forms: Vec::new(),
+        }
+    }
+}
+
+/// Encoding an imported certificate file was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CertificateFormat {
+    Pem,
+    Der,
+    Pkcs7,
+}
+
+/// A certificate added to the inventory from a file.
+#[derive(Debug, Clone, Serialize)]
+pub struct CertificateImport {
+    pub record: CertificateRecord,
+    pub format: CertificateFormat,
+}
+
+/// A change made to a certificate chain so it runs from the leaf up to,
+/// but not including, the root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainRepair {
+    /// Certificates were not in leaf-to-root order
+    Reordered,
+    /// The same certificate appeared more than once
+    DuplicateRemoved,
+    /// The self-signed root was included
+    RootRemoved,
+    /// Certificates that do not chain to the leaf were included
+    UnrelatedRemoved,
+    /// A missing intermediate was downloaded from the issuer's AIA URL
+    IntermediateFetched,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -81,12 +186,209 @@ impl ExportBundle {}
 pub struct ExportCertificateRequest {
     pub certificate_id: String,
     pub destination_dir: String,
+    /// Folder name; may contain placeholders such as `{domain}` or `{not_after:%Y%m%d}`
     pub folder_name: String,
+    /// Optional file name template; must contain `{file}` (e.g. `{domain}-{file}.pem`)
+    #[serde(default)]
+    pub file_name_template: Option<String>,
     pub include_private_key: bool,
+    /// Encrypts the exported private key with this passphrase
+    #[serde(default)]
+    pub key_passphrase: Option<String>,
     pub bundle: ExportBundle,
     pub overwrite: bool,
 }
 
+fn default_profile_folder_template() -> String {
+    "{domain}".to_string()
+}
+
+/// Reusable export settings applied to each certificate in a bulk export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportProfile {
+    /// Folder name template; defaults to one subfolder per domain
+    #[serde(default = "default_profile_folder_template")]
+    pub folder_name_template: String,
+    #[serde(default)]
+    pub file_name_template: Option<String>,
+    pub bundle: ExportBundle,
+    #[serde(default)]
+    pub include_private_key: bool,
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+/// Selects certificates for a bulk export. Empty criteria match everything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkExportFilter {
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// Only certificates expiring within this many days (expired ones included)
+    #[serde(default)]
+    pub expiring_within_days: Option<i64>,
+    /// Statuses to include; when empty, superseded and revoked certificates are left out
+    #[serde(default)]
+    pub statuses: Vec<CertificateStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkExportRequest {
+    pub destination_dir: String,
+    #[serde(default)]
+    pub filter: BulkExportFilter,
+    pub profile: ExportProfile,
+    /// Kept out of the profile so it is never written to the job journal
+    #[serde(default)]
+    pub key_passphrase: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkExportedCertificate {
+    pub certificate_id: String,
+    pub domain: String,
+    pub output_dir: String,
+    pub files: Vec<ExportedFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkExportSkipped {
+    pub certificate_id: String,
+    pub domain: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkExportReport {
+    pub exported: Vec<BulkExportedCertificate>,
+    pub skipped: Vec<BulkExportSkipped>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyEscrowRecipient {
+    pub label: String,
+    pub passphrase: String,
+}
+
+/// Splits a managed private key into passphrase-protected Shamir shares.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyEscrowRequest {
+    pub certificate_id: String,
+    pub destination_dir: String,
+    pub folder_name: String,
+    /// Number of shares required to recover the key
+    pub threshold: u8,
+    /// One share is written per recipient
+    pub recipients: Vec<KeyEscrowRecipient>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyEscrowResponse {
+    pub output_dir: String,
+    pub threshold: u8,
+    pub files: Vec<ExportedFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscrowShareInput {
+    pub path: String,
+    pub passphrase: String,
+}
+
+/// Recombines escrow shares and writes the recovered key as `privkey.pem`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoverEscrowRequest {
+    pub shares: Vec<EscrowShareInput>,
+    pub destination_dir: String,
+    pub folder_name: String,
+    /// Encrypts the recovered private key with this passphrase
+    #[serde(default)]
+    pub key_passphrase: Option<String>,
+}
+
+/// QR code carrying a certificate's public verification details.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CertificateQrCode {
+    /// Text encoded in the QR code
+    pub payload: String,
+    /// PNG image bytes
+    pub png: Vec<u8>,
+}
+
+/// Result of comparing exported files with the current inventory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportVerification {
+    pub path: String,
+    /// SHA-256 fingerprint of the exported leaf certificate
+    pub fingerprint: String,
+    pub certificate_id: Option<String>,
+    pub status: Option<CertificateStatus>,
+    pub matches_inventory: bool,
+    pub chain_complete: bool,
+    /// None when no private key was found next to the certificate
+    pub key_matches: Option<bool>,
+    /// Human-readable problems; empty when the export is current
+    pub issues: Vec<String>,
+}
+
+/// Where a certificate is used: a monitored endpoint or a deployment target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CertificateUsage {
+    pub id: String,
+    pub certificate_id: String,
+    /// `host[:port]` expected to serve the certificate
+    pub endpoint: Option<String>,
+    pub deploy_target_id: Option<String>,
+    pub deploy_target_label: Option<String>,
+    /// True when the link was made for an earlier certificate with the same names
+    pub inherited: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddCertificateUsageRequest {
+    pub certificate_id: String,
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub deploy_target_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndpointServingState {
+    /// The endpoint serves this certificate
+    Current,
+    /// The endpoint still serves an older certificate for the same names
+    Outdated,
+    /// The endpoint already serves a newer certificate for the same names
+    Newer,
+    /// The endpoint serves a certificate for other names or one not in the inventory
+    Other,
+    Unreachable,
+}
+
+/// A stretch of time during which an endpoint served the same certificate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointObservation {
+    pub endpoint: String,
+    pub fingerprint: String,
+    /// Inventory certificate with this fingerprint; None for unknown certificates
+    pub certificate_id: Option<String>,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Result of connecting to a linked endpoint and reading its certificate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointCheck {
+    pub usage_id: String,
+    pub endpoint: String,
+    pub state: EndpointServingState,
+    pub served_fingerprint: Option<String>,
+    pub served_certificate_id: Option<String>,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExportedFile {
     pub label: String,
@@ -123,6 +425,210 @@ pub struct SetPreferenceRequest {
     pub value: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct DemoModeStatus {
+    pub enabled: bool,
+    pub certificates: usize,
+    pub issuers: usize,
+    pub providers: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetDemoModeRequest {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetConfirmationPinRequest {
+    /// Required when a PIN is already set
+    #[serde(default)]
+    pub current_pin: Option<String>,
+    /// `None` removes the PIN
+    #[serde(default)]
+    pub new_pin: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadOnlyStatus {
+    pub enabled: bool,
+    /// True when enabled via `--read-only`, which the UI cannot switch off.
+    pub forced: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateCheckResult {
+    pub current_version: String,
+    pub latest_version: String,
+    pub update_available: bool,
+    pub release_notes: Option<String>,
+    pub download_url: Option<String>,
+    pub published_at: Option<String>,
+    /// True when the updater plugin is compiled in and enabled in preferences.
+    pub updater_enabled: bool,
+}
+
+/// Window a certificate report covers, ending when it is generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportPeriod {
+    Weekly,
+    Monthly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GenerateReportRequest {
+    pub period: ReportPeriod,
+    /// Falls back to the `report_format` preference, then Markdown
+    pub format: Option<ReportFormat>,
+    /// Falls back to the `report_folder` preference
+    pub folder: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CertificateReportResult {
+    pub path: String,
+    pub period: ReportPeriod,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub issued: usize,
+    pub renewed: usize,
+    pub failed: usize,
+    pub expiring: usize,
+}
+
+/// Overall inventory health in the status feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusFeedHealth {
+    Ok,
+    /// Something is expiring soon or awaiting renewal
+    Warning,
+    /// Something has expired
+    Critical,
+}
+
+/// The certificate that expires first.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusFeedExpiry {
+    pub names: Vec<String>,
+    pub not_after: DateTime<Utc>,
+    /// Negative once expired
+    pub days_left: i64,
+}
+
+/// Contents of the machine-readable status file. Superseded and revoked
+/// certificates are not counted.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusFeed {
+    pub generated_at: DateTime<Utc>,
+    pub health: StatusFeedHealth,
+    pub total: usize,
+    pub active: usize,
+    pub expiring_soon: usize,
+    pub pending_renewal: usize,
+    pub expired: usize,
+    pub worst_expiry: Option<StatusFeedExpiry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusFeedResult {
+    pub path: String,
+    pub feed: StatusFeed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupResult {
+    pub path: String,
+    pub created_at: DateTime<Utc>,
+    /// Older automatic backups deleted to stay within the retention count
+    pub removed: Vec<String>,
+}
+
+/// Outcome of opening a backup without restoring it.
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupVerification {
+    pub path: String,
+    /// True when no problem was found
+    pub valid: bool,
+    pub schema_version: i64,
+    pub supported_schema_version: i64,
+    pub integrity_ok: bool,
+    pub certificates: usize,
+    /// Vault secrets whose ciphertext is inside the backup
+    pub secrets_in_backup: usize,
+    pub secrets_decryptable: usize,
+    /// Secrets kept in a key directory, which the backup does not contain
+    pub secrets_external: usize,
+    pub problems: Vec<String>,
+}
+
+/// Why the background scheduler is holding its tasks back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackgroundDeferral {
+    QuietHours,
+    OnBattery,
+    MeteredConnection,
+}
+
+/// What the background scheduler sees right now.
+#[derive(Debug, Clone, Serialize)]
+pub struct BackgroundStatus {
+    /// Set while scheduled tasks are held back
+    pub deferred_by: Option<BackgroundDeferral>,
+    pub in_quiet_hours: bool,
+    /// `None` where the power source cannot be read
+    pub on_battery: Option<bool>,
+    /// `None` where the connection cost cannot be read
+    pub metered: Option<bool>,
+}
+
+/// Why the app state was reloaded from the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StateReloadReason {
+    BackupRestore,
+    ProfileSwitch,
+    LegacyImport,
+    Manual,
+}
+
+/// Payload of the state reload event, also returned to the caller.
+#[derive(Debug, Clone, Serialize)]
+pub struct StateReloaded {
+    pub reason: StateReloadReason,
+    /// Issuances that were in progress and had to be abandoned
+    pub abandoned_issuances: usize,
+    /// Journal entries from before the reload, now offered for recovery
+    pub interrupted_jobs: usize,
+    pub reloaded_at: DateTime<Utc>,
+}
+
+/// Action requested through an `sslboard://` link, resolved against the inventory.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DeepLinkAction {
+    Renew {
+        certificate_id: String,
+        /// Names to prefill in a new issuance
+        domains: Vec<String>,
+    },
+    Check {
+        domain: String,
+        /// Current certificates covering the domain
+        certificate_ids: Vec<String>,
+    },
+    /// The link could not be parsed or resolved
+    Invalid { url: String, message: String },
+}
+
 pub type SecretRefRecord = SecretMetadata;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -132,6 +638,60 @@ pub enum IssuerEnvironment {
     Production,
 }
 
+/// Whether managed private keys may leave the vault.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyExportPolicy {
+    #[default]
+    Allowed,
+    /// Only passphrase-encrypted exports and escrow shares
+    EncryptedOnly,
+    Forbidden,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetKeyExportPolicyRequest {
+    pub policy: KeyExportPolicy,
+    /// Required to relax the policy when a PIN is set
+    #[serde(default)]
+    pub confirmation_pin: Option<String>,
+}
+
+/// How an issuer is picked when issuance starts without one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssuerSelectionPolicy {
+    /// Staging for test TLDs and new names, production once a name has issued
+    #[default]
+    Smart,
+    Staging,
+    Production,
+    /// The issuer marked as selected, whatever the names
+    Selected,
+}
+
+/// Why [`IssuerSuggestion::issuer_id`] was picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssuerSelectionReason {
+    TestDomain,
+    NewDomain,
+    PreviouslyIssued,
+    /// The policy names a fixed environment
+    Policy,
+    SelectedIssuer,
+    /// No enabled issuer exists for the environment the policy asked for
+    Fallback,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuerSuggestion {
+    pub issuer_id: String,
+    pub environment: IssuerEnvironment,
+    pub policy: IssuerSelectionPolicy,
+    pub reason: IssuerSelectionReason,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum IssuerType {
@@ -149,6 +709,42 @@ pub struct IssuerConfigDto {
     pub account_key_ref: Option<String>,
     pub tos_agreed: bool,
     pub is_selected: bool,
+    pub preferred_chain: Option<String>,
+    pub ca_pins: Vec<CaPin>,
+    pub enforce_ca_pins: bool,
+    /// Disabled issuers are kept but cannot be selected or used to issue
+    pub disabled: bool,
+    #[serde(default)]
+    pub stats: IssuerStats,
+}
+
+/// ACME activity counted per issuer, so an account that keeps failing
+/// (revoked key, changed terms of service) stands out.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IssuerStats {
+    /// Orders created with the ACME server
+    pub orders: u64,
+    pub successes: u64,
+    pub failures: u64,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+    pub last_success_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaPinKind {
+    /// Full subject DN or common name of the issuing CA
+    Subject,
+    /// Base64 SHA-256 of the CA's SubjectPublicKeyInfo
+    SpkiSha256,
+}
+
+/// Expected issuing CA for certificates from an issuer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaPin {
+    pub kind: CaPinKind,
+    pub value: String,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -164,6 +760,15 @@ pub struct CreateIssuerRequest {
     pub directory_url: String,
     pub contact_email: Option<String>,
     pub tos_agreed: bool,
+    pub preferred_chain: Option<String>,
+    #[serde(default)]
+    pub ca_pins: Vec<CaPin>,
+    /// Reject certificates whose chain does not match a pin instead of tagging them
+    #[serde(default)]
+    pub enforce_ca_pins: bool,
+    /// Required to proceed when another issuer uses the same directory URL
+    #[serde(default)]
+    pub on_conflict: Option<ConflictResolution>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -174,32 +779,127 @@ pub struct UpdateIssuerRequest {
     pub directory_url: String,
     pub contact_email: Option<String>,
     pub tos_agreed: bool,
+    pub preferred_chain: Option<String>,
+    #[serde(default)]
+    pub ca_pins: Vec<CaPin>,
+    #[serde(default)]
+    pub enforce_ca_pins: bool,
+    #[serde(default)]
+    pub disabled: bool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct DeleteIssuerRequest {
     pub issuer_id: String,
+    #[serde(default)]
+    pub confirmation_pin: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum DnsProviderType {
+    AcmeDns,
     Cloudflare,
     #[serde(rename = "digitalocean", alias = "digital_ocean")]
     DigitalOcean,
+    Desec,
+    #[serde(rename = "powerdns")]
+    PowerDns,
+    Rfc2136,
     Route53,
+    Script,
     Manual,
 }
 
+/// How a create request proceeds when it overlaps an existing record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    /// Create the new record alongside the existing one
+    CreateAnyway,
+    /// Fold the request into the existing record instead
+    Merge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictEntity {
+    DnsProvider,
+    Issuer,
+}
+
+/// An existing record that a create request would nearly duplicate.
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateConflict {
+    pub entity: ConflictEntity,
+    pub existing_id: String,
+    pub existing_label: String,
+    /// Shared domain suffixes, or the shared directory URL for issuers
+    pub overlapping: Vec<String>,
+    /// Whether `on_conflict: merge` can fold the request into this record
+    pub can_merge: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DnsProviderDto {
     pub id: String,
     pub provider_type: DnsProviderType,
     pub label: String,
+    /// Suffixes in their Unicode form, for display
     pub domain_suffixes: Vec<String>,
+    /// ASCII and Unicode forms of each suffix
+    #[serde(default)]
+    pub suffix_forms: Vec<DomainForms>,
     pub config: Option<Value>,
+    /// Higher wins when providers match a hostname with equally long suffixes
+    #[serde(default)]
+    pub priority: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Per-suffix results, only present when verification was requested on create
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<Vec<DomainSuffixVerification>>,
+    /// Nesting with other providers' suffixes, reported on create and update
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suffix_warnings: Vec<SuffixShadowWarning>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuffixShadowKind {
+    /// The saved suffix sits under another provider's suffix and takes its names over
+    Shadows,
+    /// Another provider's suffix sits under the saved one and keeps its names
+    ShadowedBy,
+}
+
+/// A saved suffix that nests with another provider's suffix.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuffixShadowWarning {
+    pub suffix: String,
+    pub kind: SuffixShadowKind,
+    pub existing_suffix: String,
+    pub provider_id: String,
+    pub provider_label: String,
+    pub message: String,
+}
+
+/// NS delegation and zone access check for one domain suffix.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainSuffixVerification {
+    pub suffix: String,
+    /// Closest enclosing zone with NS records
+    pub zone: Option<String>,
+    pub nameservers: Vec<String>,
+    /// Whether the nameservers belong to the provider; None when not checkable
+    pub delegated: Option<bool>,
+    /// Whether the credentials can see the zone; None for manual providers
+    pub zone_accessible: Option<bool>,
+    /// Token permissions the credentials lack on the zone, where the provider
+    /// can tell
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing_scopes: Vec<String>,
+    pub issues: Vec<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -213,6 +913,12 @@ pub struct CreateDnsProviderRequest {
     #[serde(rename = "route53_secret_key")]
     pub route53_secret_key: Option<String>,
     pub config: Option<Value>,
+    /// Required to proceed when another provider already claims one of the suffixes
+    #[serde(default)]
+    pub on_conflict: Option<ConflictResolution>,
+    /// Check NS delegation and zone access for each suffix after creating
+    #[serde(default)]
+    pub verify_suffixes: bool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -228,9 +934,138 @@ pub struct UpdateDnsProviderRequest {
     pub config: Option<Value>,
 }
 
+/// Credential a DNS provider export leaves out and an import asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DnsCredentialField {
+    ApiToken,
+    Route53AccessKey,
+    Route53SecretKey,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsCredentialPlaceholder {
+    pub field: DnsCredentialField,
+    /// Optional credentials may be left empty, e.g. acme-dns registers new accounts
+    pub required: bool,
+}
+
+/// A DNS provider as written to an export file. Secrets are only present
+/// when the export was sealed with a passphrase.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortableDnsProvider {
+    pub provider_type: DnsProviderType,
+    pub label: String,
+    pub domain_suffixes: Vec<String>,
+    #[serde(default)]
+    pub config: Option<Value>,
+    #[serde(default)]
+    pub credentials: Vec<DnsCredentialPlaceholder>,
+    /// The provider's credentials, encrypted to the export passphrase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sealed_credentials: Option<SealedSecret>,
+}
+
+/// File written by `dns_provider_export`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsProviderExportFile {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub providers: Vec<PortableDnsProvider>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportDnsProvidersRequest {
+    pub path: String,
+    /// Every provider when empty
+    #[serde(default)]
+    pub provider_ids: Vec<String>,
+    /// Include credentials, encrypted to this passphrase; placeholders only when unset
+    #[serde(default)]
+    pub passphrase: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsProviderExportResult {
+    pub path: String,
+    pub exported: usize,
+    pub credentials_included: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportDnsProvidersRequest {
+    pub path: String,
+    /// Opens credentials sealed in the file
+    #[serde(default)]
+    pub passphrase: Option<String>,
+}
+
+/// One provider of an export file and what `dns_provider_import` did with it.
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsProviderFileImport {
+    pub provider_type: DnsProviderType,
+    pub label: String,
+    pub provider_id: Option<String>,
+    pub skipped_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsProviderFileImportResult {
+    pub imported: Vec<DnsProviderFileImport>,
+    pub skipped: Vec<DnsProviderFileImport>,
+}
+
+/// A provider read from an export file, ready to be created with credentials.
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsProviderImportCandidate {
+    #[serde(flatten)]
+    pub provider: PortableDnsProvider,
+    /// Label of a configured provider of the same type that already has these suffixes
+    pub existing_label: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportEnvProvidersRequest {
+    /// lego `.env` file or acme.sh `account.conf`
+    pub path: String,
+    /// Suffixes for every imported provider; when empty, each provider gets
+    /// the zones its credentials can see that no other provider claims
+    #[serde(default)]
+    pub domain_suffixes: Option<String>,
+}
+
+/// One provider found in an environment file.
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvProviderImport {
+    pub provider_type: DnsProviderType,
+    pub label: String,
+    /// Variables the provider was read from
+    pub source_keys: Vec<String>,
+    /// Set when the provider was created
+    pub provider_id: Option<String>,
+    pub domain_suffixes: Vec<String>,
+    /// Why the provider was not created
+    pub skipped_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvProviderImportResult {
+    pub imported: Vec<EnvProviderImport>,
+    pub skipped: Vec<EnvProviderImport>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct DeleteDnsProviderRequest {
     pub provider_id: String,
+    #[serde(default)]
+    pub confirmation_pin: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetDnsProviderPriorityRequest {
+    pub provider_id: String,
+    pub priority: i64,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -245,12 +1080,86 @@ pub struct DnsProviderResolutionDto {
     pub ambiguous: Vec<DnsProviderDto>,
 }
 
+/// How long to wait for a challenge TXT record to show up in public DNS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PropagationWait {
+    pub timeout_secs: u64,
+    pub interval_secs: u64,
+}
+
+/// Propagation wait for one provider type: the built-in preset and the
+/// wait actually used once the user's override is applied.
+#[derive(Debug, Clone, Serialize)]
+pub struct PropagationPreset {
+    pub provider_type: String,
+    pub preset: PropagationWait,
+    pub effective: PropagationWait,
+    pub overridden: bool,
+}
+
+/// How `dns_provider_test` exercises a provider.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderTestMode {
+    /// Creates a temporary TXT record, waits for it to propagate and removes it
+    #[default]
+    Full,
+    /// Checks the credentials, zones and write permission without touching records
+    CredentialsOnly,
+    /// Checks zone access and logs the test record's creation and removal
+    /// without sending them
+    DryRun,
+}
+
+/// Kind of TXT record change a dry run skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordOperationKind {
+    Create,
+    Delete,
+}
+
+/// A TXT record change logged by a dry run instead of being sent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlannedRecordOperation {
+    pub operation: RecordOperationKind,
+    pub record_name: String,
+    /// Value to create; deletions remove every value at the name
+    pub value: Option<String>,
+}
+
+/// Whether a credentials-only test could confirm write permission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteAccessCheck {
+    Verified,
+    /// The provider's API cannot tell without a real change
+    NotSupported,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct TestDnsProviderRequest {
     pub provider_id: String,
+    #[serde(default)]
+    pub mode: ProviderTestMode,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiscoverProviderZonesRequest {
+    pub provider_id: String,
+}
+
+/// A zone visible to a provider's credentials.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveredZoneDto {
+    pub name: String,
+    /// Already one of this provider's domain suffixes
+    pub configured: bool,
+    /// Label of another provider that already claims this zone
+    pub claimed_by: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum DnsProviderErrorCategory {
     AuthError,
@@ -273,24 +1182,602 @@ pub struct DnsProviderTestResult {
     pub create_ms: Option<u64>,
     pub propagation_ms: Option<u64>,
     pub cleanup_ms: Option<u64>,
+    pub mode: ProviderTestMode,
+    /// Zones the credentials can see; credentials-only tests
+    pub zones: Option<Vec<String>>,
+    pub write_access: Option<WriteAccessCheck>,
+    /// Record changes a dry-run test would have made
+    pub planned_operations: Option<Vec<PlannedRecordOperation>>,
+}
+
+/// How a provider health check exercised the provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderHealthCheckKind {
+    /// Confirmed the credentials can see the configured zones
+    Credentials,
+    /// Created a test TXT record and waited for it to propagate
+    FullTest,
+}
+
+/// Outcome of one scheduled provider health check.
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsProviderHealthCheck {
+    pub provider_id: String,
+    pub checked_at: DateTime<Utc>,
+    pub kind: ProviderHealthCheckKind,
+    pub success: bool,
+    pub error: Option<String>,
+    pub error_category: Option<DnsProviderErrorCategory>,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WatchDnsPropagationRequest {
+    pub record_name: String,
+    pub value: String,
+    /// Picks the propagation wait preset; manual records when unset
+    #[serde(default)]
+    pub provider_type: Option<String>,
+    #[serde(default)]
+    pub mode: PropagationCheckMode,
+}
+
+/// Where propagation checks look for a TXT record.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PropagationCheckMode {
+    /// Public resolvers over DNS-over-HTTPS, as the CA's resolvers see it
+    #[default]
+    Public,
+    /// The zone's authoritative nameservers, queried directly; unaffected by
+    /// cached NXDOMAIN answers
+    Authoritative,
+    /// Authoritative nameservers, also waiting until every secondary has
+    /// loaded the zone serial that contains the record
+    SerialSync,
+}
+
+/// One authoritative nameserver's view of a TXT record.
+#[derive(Debug, Clone, Serialize)]
+pub struct NameserverCheck {
+    pub nameserver: String,
+    /// Address the query went to; unset when the host did not resolve
+    pub address: Option<String>,
+    pub transport: Option<DnsTransport>,
+    pub result: DnsPropagationResult,
+    /// Zone SOA serial the nameserver serves, when serials are compared
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub soa_serial: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthoritativePropagationResult {
+    /// Name actually queried, after following a CNAME
+    pub record_name: String,
+    pub zone: String,
+    pub nameservers: Vec<NameserverCheck>,
+    /// Every nameserver serves the expected value
+    pub all_found: bool,
+    /// Set when SOA serials were compared
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial_sync: Option<SerialSync>,
+}
+
+/// Whether every nameserver has loaded the zone version holding the record.
+#[derive(Debug, Clone, Serialize)]
+pub struct SerialSync {
+    /// Oldest serial among nameservers that serve the record; unset until one does
+    pub target_serial: Option<u32>,
+    /// Nameservers on an older serial, or whose serial could not be read
+    pub lagging: Vec<String>,
+    pub in_sync: bool,
+}
+
+/// How a propagation watch ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PropagationWatchOutcome {
+    Found,
+    TimedOut,
+    Cancelled,
+}
+
+/// Emitted by a propagation watch when the observed state changes and once
+/// more when the watch ends.
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsPropagationUpdate {
+    pub watch_id: String,
+    pub record_name: String,
+    pub result: DnsPropagationResult,
+    pub attempt: u32,
+    pub elapsed_secs: u64,
+    /// Set on the watch's last update
+    pub outcome: Option<PropagationWatchOutcome>,
+    /// Per-nameserver results of an authoritative watch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nameservers: Option<Vec<NameserverCheck>>,
+}
+
+/// Why a TXT record left in a provider's zone is offered for cleanup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeftoverRecordKind {
+    /// Written by a provider test
+    ProviderTest,
+    /// `_acme-challenge` record no pending issuance is using
+    StaleChallenge,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LeftoverTxtRecord {
+    pub record_name: String,
+    pub values: Vec<String>,
+    pub kind: LeftoverRecordKind,
+}
+
+/// Leftover test and challenge records of a provider, and on a real run
+/// what became of them.
+#[derive(Debug, Clone, Serialize)]
+pub struct TestRecordCleanup {
+    pub dry_run: bool,
+    pub records: Vec<LeftoverTxtRecord>,
+    /// Record names deleted; empty on a dry run
+    pub deleted: Vec<String>,
+    /// `record name: error` for each record that could not be deleted
+    pub failed: Vec<String>,
+}
+
+/// One captured provider API call; credentials are redacted before storage.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderDebugEntry {
+    pub captured_at: DateTime<Utc>,
+    pub method: String,
+    pub url: String,
+    pub request_body: Option<String>,
+    /// None when no response was received
+    pub status: Option<u16>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+    pub elapsed_ms: u64,
+}
+
+/// What a metrics entry counts calls of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricKind {
+    Command,
+    DnsProvider,
+}
+
+/// Local call statistics for one command or DNS provider.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricSummary {
+    pub kind: MetricKind,
+    /// Command name or provider id
+    pub name: String,
+    pub label: String,
+    pub calls: u64,
+    pub failures: u64,
+    pub avg_ms: u64,
+    pub max_ms: u64,
+    pub last_error: Option<String>,
+    pub last_called_at: DateTime<Utc>,
+}
+
+/// What is wrong with a domain name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DomainIssueKind {
+    Empty,
+    InvalidCharacter,
+    EmptyLabel,
+    LabelTooLong,
+    NameTooLong,
+    Punycode,
+    Wildcard,
+}
+
+/// A malformed domain name, precise enough for the UI to highlight it.
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainIssue {
+    /// Position of the name in the submitted list
+    pub index: Option<usize>,
+    /// The name as submitted, trimmed
+    pub input: String,
+    pub kind: DomainIssueKind,
+    pub message: String,
+    /// Character offset in `input` where the problem starts
+    pub position: Option<usize>,
+    /// A likely correction, e.g. the host of a pasted URL
+    pub suggestion: Option<String>,
+}
+
+/// An entry of pasted text that is not a usable certificate name.
+#[derive(Debug, Clone, Serialize)]
+pub struct RejectedDomainEntry {
+    pub entry: String,
+    pub reason: String,
+}
+
+/// A domain name as stored (`ascii`, with punycode labels) and as shown to
+/// users (`unicode`). The two are equal for names without IDN labels.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DomainForms {
+    pub ascii: String,
+    pub unicode: String,
+}
+
+/// One name of a `prepare_dns_challenges` batch with the TXT value to publish.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DnsChallengeInput {
+    pub domain: String,
+    pub txt_value: String,
+}
+
+/// Challenge record for one name of a `prepare_dns_challenges` batch.
+#[derive(Debug, Clone, Serialize)]
+pub struct PreparedDnsChallenge {
+    pub domain: String,
+    pub record: DnsRecordInstruction,
+    /// Whether a configured provider writes the record, rather than the user
+    pub automated: bool,
+    /// Provider that owns the record's name, when one matches
+    pub provider_id: Option<String>,
+    pub provider_label: Option<String>,
+    /// Providers that match the name equally well; none is automated then
+    pub ambiguous_provider_ids: Vec<String>,
+}
+
+/// Result of `parse_domain_list`: normalized names in the order pasted.
+#[derive(Debug, Clone, Serialize)]
+pub struct ParsedDomainList {
+    pub domains: Vec<String>,
+    pub rejected: Vec<RejectedDomainEntry>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct StartIssuanceRequest {
     pub domains: Vec<String>,
-    pub issuer_id: String,
+    /// Chosen by the issuer selection policy when omitted
+    #[serde(default)]
+    pub issuer_id: Option<String>,
     pub key_algorithm: Option<KeyAlgorithm>,
     pub key_size: Option<u16>,
     pub key_curve: Option<KeyCurve>,
+    /// Challenge solver per name; names left out use dns-01 through their
+    /// provider
+    #[serde(default)]
+    pub solvers: HashMap<String, ChallengeSolverConfig>,
+    /// Complete the issuance in the background once the TXT records added
+    /// by hand have propagated
+    #[serde(default)]
+    pub auto_complete: bool,
+}
+
+/// Result of an issuance completed in the background, sent as an
+/// `issuance-auto-complete` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoCompleteOutcome {
+    pub request_id: String,
+    pub certificate: Option<CertificateRecord>,
+    pub error: Option<String>,
+}
+
+/// Way an ACME challenge is answered for a name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChallengeSolverKind {
+    /// TXT record through the name's DNS provider, or by hand when none matches
+    Dns01Provider,
+    /// TXT record added by hand even when a provider matches
+    Dns01Manual,
+    /// Token file written under a folder the name's web server serves
+    Http01Webroot,
+    /// Token served by a built-in HTTP listener
+    Http01Standalone,
+    /// Validation certificate served by a built-in TLS listener
+    TlsAlpn01,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeSolverConfig {
+    pub kind: ChallengeSolverKind,
+    /// Web root folder, for http-01 webroot
+    #[serde(default)]
+    pub webroot: Option<String>,
+    /// Address the built-in listener binds; port 80 or 443 on all interfaces
+    /// when omitted
+    #[serde(default)]
+    pub listen: Option<String>,
+}
+
+/// Re-issues a staging certificate from a production issuer.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PromoteCertificateRequest {
+    pub certificate_id: String,
+    /// Production issuer to use; the preferred one when omitted
+    #[serde(default)]
+    pub issuer_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct StartIssuanceResponse {
     pub request_id: String,
+    pub issuer_id: String,
     pub dns_records: Vec<DnsRecordInstruction>,
 }
 
+/// Local clock compared with the `Date` header of an issuer's ACME directory.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClockSkewCheck {
+    pub local_time: DateTime<Utc>,
+    pub server_time: DateTime<Utc>,
+    /// Positive when the local clock is ahead of the server
+    pub skew_secs: i64,
+    pub threshold_secs: i64,
+    pub exceeds_threshold: bool,
+}
+
+/// Outcome of one preflight check, from best to worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreflightStatus {
+    Pass,
+    Warn,
+    /// Issuance is expected to fail
+    Fail,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreflightCheckKind {
+    /// DNS provider that will publish the challenge, or manual records
+    Provider,
+    /// Whether CAA records allow the issuer's CA
+    Caa,
+    /// NS delegation of the enclosing zone
+    Delegation,
+    /// Current certificates already covering the name
+    Inventory,
+    /// Issuer rate limits, from certificates issued recently
+    RateLimit,
+    /// Challenge records the provider would be asked to create and remove
+    RecordPlan,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreflightCheck {
+    pub kind: PreflightCheckKind,
+    pub status: PreflightStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainPreflight {
+    pub domain: String,
+    /// Worst status of the domain's checks
+    pub status: PreflightStatus,
+    pub checks: Vec<PreflightCheck>,
+}
+
+/// Go/no-go summary shown before issuance starts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreflightReport {
+    pub issuer_id: String,
+    /// False when any check failed
+    pub go: bool,
+    pub domains: Vec<DomainPreflight>,
+    /// Checks that apply to the order as a whole
+    pub order_checks: Vec<PreflightCheck>,
+}
+
+/// Kind of operation tracked in the job journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    Issuance,
+    Export,
+    Deployment,
+}
+
+/// What can be done about an operation interrupted by a previous run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryAction {
+    /// Removes what the operation left behind (challenge records, partial files)
+    Cleanup,
+    /// Runs the operation again from its recorded inputs
+    Resume,
+    /// Forgets the operation without touching anything
+    Discard,
+}
+
+/// Operation found unfinished at startup, with the actions it supports.
+#[derive(Debug, Clone, Serialize)]
+pub struct InterruptedJob {
+    pub id: String,
+    pub kind: JobKind,
+    pub started_at: DateTime<Utc>,
+    pub summary: String,
+    pub actions: Vec<RecoveryAction>,
+}
+
+/// Missing secret a piece of stored configuration refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigIssueKind {
+    /// A DNS provider's credentials were deleted from the secret store
+    ProviderSecret,
+    /// An issuer has no ACME account key, or its key was deleted
+    IssuerAccountKey,
+    /// A managed certificate's private key was deleted
+    CertificateKey,
+}
+
+/// Fix applied by `repair_config_issue`, offered only where nothing can be lost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigRepair {
+    /// Generates a new account key; the issuer registers a new ACME account with it
+    CreateAccountKey,
+    /// Forgets the deleted key; renewals generate a new one
+    DetachManagedKey,
+}
+
+/// Finding of the configuration check run at startup.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigIssue {
+    /// Stable across checks, so a repair can name the issue it fixes
+    pub id: String,
+    pub kind: ConfigIssueKind,
+    pub subject_id: String,
+    pub subject_label: String,
+    pub detail: String,
+    pub repair: Option<ConfigRepair>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApplyRecoveryRequest {
+    pub job_id: String,
+    pub action: RecoveryAction,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct CompleteIssuanceRequest {
     pub request_id: String,
+    /// Propagation wait for every challenge record of this issuance,
+    /// replacing the provider and preset waits
+    #[serde(default)]
+    pub propagation_wait: Option<PropagationWait>,
+}
+
+/// Steps of finalizing an order, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinalizationStage {
+    CheckingDns,
+    ValidatingChallenges,
+    AwaitingOrder,
+    Finalizing,
+    Completed,
+}
+
+/// Payload of the `issuance-progress` event emitted while an order is finalized.
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuanceProgress {
+    pub request_id: String,
+    pub stage: FinalizationStage,
+    /// Polling attempt within the stage, starting at 1
+    pub attempt: u32,
+    pub elapsed_secs: u64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeployTargetType {
+    /// S3 or any S3-compatible object store (MinIO, R2, B2, ...)
+    S3,
+    /// DigitalOcean load balancer or App Platform app
+    #[serde(rename = "digitalocean")]
+    DigitalOcean,
+    /// Nginx Proxy Manager custom certificate files
+    NginxProxyManager,
+    /// Traefik file provider (certificate files plus a dynamic config fragment)
+    Traefik,
+    /// Windows IIS / HTTP.sys SSL binding
+    Iis,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployTargetDto {
+    pub id: String,
+    pub target_type: DeployTargetType,
+    pub label: String,
+    pub config: Value,
+    pub has_credentials: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateDeployTargetRequest {
+    pub target_type: DeployTargetType,
+    pub label: String,
+    pub config: Value,
+    /// Target-specific credential fields, stored encrypted as one secret
+    pub credentials: Option<Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateDeployTargetRequest {
+    pub target_id: String,
+    pub label: String,
+    pub config: Value,
+    /// Replaces the stored credentials when present
+    pub credentials: Option<Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeployCertificateRequest {
+    pub target_id: String,
+    pub certificate_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployResult {
+    pub target_id: String,
+    pub certificate_id: String,
+    /// Where the certificate ended up (object URLs, resource ids, file paths)
+    pub locations: Vec<String>,
+    pub deployed_at: DateTime<Utc>,
+}
+
+/// An error raised during an issuance attempt, with credentials redacted.
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuanceArtifactError {
+    pub at: DateTime<Utc>,
+    /// What the issuance was doing, e.g. "creating the order"
+    pub step: String,
+    pub message: String,
+}
+
+/// What was recorded about one issuance attempt.
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuanceArtifacts {
+    pub request_id: String,
+    pub domains: Vec<String>,
+    pub issuer_id: String,
+    pub directory_url: String,
+    pub started_at: DateTime<Utc>,
+    /// Last ACME order object received
+    pub order: Option<Value>,
+    /// Last ACME authorization objects received, with challenge statuses
+    /// and the CA's validation errors
+    pub authorizations: Vec<Value>,
+    pub challenge_records: Vec<DnsRecordInstruction>,
+    pub errors: Vec<IssuanceArtifactError>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuanceArtifactSummary {
+    pub request_id: String,
+    pub domains: Vec<String>,
+    pub started_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+}
+
+/// File written by `export_issuance_artifacts`.
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuanceArtifactExport {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub app_version: String,
+    #[serde(flatten)]
+    pub artifacts: IssuanceArtifacts,
+    /// Failed provider API calls captured during the attempt; only present
+    /// for providers with `debug_capture` set
+    pub provider_calls: Vec<ProviderDebugEntry>,
 }
@@ -0,0 +1,232 @@
+//! Release feed checks.
+//!
+//! Queries the GitHub releases feed for the latest published version and
+//! compares it against the running build. The optional background check is
+//! driven by the `update_check_enabled` preference; installing updates in place
+//! is left to the Tauri updater plugin (`updater` feature).
+
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::core::scheduler::{self, BackgroundTask};
+use crate::core::types::UpdateCheckResult;
+use crate::storage::preferences::PreferencesStore;
+
+pub const RELEASE_FEED_URL: &str =
+    "https://api.github.com/repos/sslboard/SSLBoard-desktop/releases/latest";
+
+/// Preference that enables the periodic background check.
+pub const UPDATE_CHECK_PREFERENCE: &str = "update_check_enabled";
+/// Preference that lets the UI install updates through the updater plugin.
+pub const UPDATER_PREFERENCE: &str = "updater_enabled";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Deserialize)]
+struct ReleaseFeedEntry {
+    tag_name: String,
+    body: Option<String>,
+    html_url: Option<String>,
+    published_at: Option<String>,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Fetches the latest release and compares it with `current_version`.
+pub fn check_release_feed(feed_url: &str, current_version: &str) -> Result<UpdateCheckResult> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(10))
+        .build();
+    let body = agent
+        .get(feed_url)
+        .set("Accept", "application/vnd.github+json")
+        .call()
+        .map_err(|err| anyhow!("failed to query release feed: {err}"))?
+        .into_string()
+        .context("failed to read release feed response")?;
+    let release: ReleaseFeedEntry =
+        serde_json::from_str(&body).context("failed to parse release feed")?;
+    if release.draft {
+        return Err(anyhow!("release feed returned a draft release"));
+    }
+
+    let latest_version = release.tag_name.trim_start_matches('v').to_string();
+    let update_available = is_newer_version(current_version, &latest_version)?;
+    let download_url = platform_asset(&release.assets)
+        .map(|asset| asset.browser_download_url.clone())
+        .or(release.html_url);
+
+    Ok(UpdateCheckResult {
+        current_version: current_version.to_string(),
+        latest_version,
+        update_available,
+        release_notes: release.body,
+        download_url,
+        published_at: release.published_at,
+        updater_enabled: false,
+    })
+}
+
+/// Returns true when `candidate` is a strictly newer semantic version.
+/// Pre-release versions sort before the matching release and by their
+/// identifiers among themselves, so `1.0.0-beta.2` is newer than `1.0.0-beta.1`.
+pub fn is_newer_version(current: &str, candidate: &str) -> Result<bool> {
+    Ok(parse_version(candidate)? > parse_version(current)?)
+}
+
+/// Pre-release identifier. Numeric identifiers compare numerically and sort
+/// before alphanumeric ones, which compare as ASCII.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum PreReleaseIdentifier {
+    Numeric(u64),
+    Alphanumeric(String),
+}
+
+/// Sort key of a version: the release fields, whether it is a release, then
+/// its pre-release identifiers. Build metadata is ignored.
+type VersionKey = (u64, u64, u64, bool, Vec<PreReleaseIdentifier>);
+
+fn parse_version(raw: &str) -> Result<VersionKey> {
+    let trimmed = raw.trim().trim_start_matches('v');
+    let without_build = trimmed.split('+').next().unwrap_or(trimmed);
+    let (core, pre) = match without_build.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (without_build, None),
+    };
+    let mut parts = core.split('.').map(|part| {
+        part.parse::<u64>()
+            .map_err(|_| anyhow!("invalid version: {raw}"))
+    });
+    let major = parts.next().ok_or_else(|| anyhow!("invalid version: {raw}"))??;
+    let minor = parts.next().transpose()?.unwrap_or(0);
+    let patch = parts.next().transpose()?.unwrap_or(0);
+    let pre_release = match pre {
+        Some(pre) => pre
+            .split('.')
+            .map(|identifier| {
+                if identifier.is_empty() {
+                    Err(anyhow!("invalid version: {raw}"))
+                } else if identifier.bytes().all(|b| b.is_ascii_digit()) {
+                    identifier
+                        .parse()
+                        .map(PreReleaseIdentifier::Numeric)
+                        .map_err(|_| anyhow!("invalid version: {raw}"))
+                } else {
+                    Ok(PreReleaseIdentifier::Alphanumeric(identifier.to_string()))
+                }
+            })
+            .collect::<Result<Vec<_>>>()?,
+        None => Vec::new(),
+    };
+    Ok((major, minor, patch, pre.is_none(), pre_release))
+}
+
+fn platform_asset(assets: &[ReleaseAsset]) -> Option<&ReleaseAsset> {
+    let extensions: &[&str] = if cfg!(target_os = "macos") {
+        &[".dmg"]
+    } else if cfg!(target_os = "windows") {
+        &[".msi", "-setup.exe"]
+    } else {
+        &[".AppImage", ".deb"]
+    };
+    extensions.iter().find_map(|ext| {
+        assets
+            .iter()
+            .find(|asset| asset.name.ends_with(ext) && !asset.name.ends_with(".sig"))
+    })
+}
+
+fn preference_enabled(prefs: &PreferencesStore, name: &str) -> bool {
+    match prefs.get(name) {
+        Ok(Some(pref)) => pref.value == "true",
+        Ok(None) => false,
+        Err(err) => {
+            log::warn!("[updates] failed to read preference {name}: {err}");
+            false
+        }
+    }
+}
+
+/// Runs the release check for the current build, honouring the updater preference.
+pub fn check_for_app_update(
+    app: &AppHandle,
+    prefs: &PreferencesStore,
+) -> Result<UpdateCheckResult> {
+    let current_version = app.package_info().version.to_string();
+    let mut result = check_release_feed(RELEASE_FEED_URL, &current_version)?;
+    result.updater_enabled =
+        cfg!(feature = "updater") && preference_enabled(prefs, UPDATER_PREFERENCE);
+    Ok(result)
+}
+
+/// Starts the daily background check. Emits `update-available` when a newer
+/// release is found; does nothing while the preference is off.
+pub fn spawn_scheduled_check(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let handle = app.clone();
+            let outcome = tauri::async_runtime::spawn_blocking(move || {
+                let prefs = handle.state::<PreferencesStore>().inner().clone();
+                if !preference_enabled(&prefs, UPDATE_CHECK_PREFERENCE)
+                    || !scheduler::should_run(BackgroundTask::UpdateCheck, &prefs)
+                {
+                    return Ok(None);
+                }
+                check_for_app_update(&handle, &prefs).map(Some)
+            })
+            .await;
+
+            match outcome {
+                Ok(Ok(Some(result))) if result.update_available => {
+                    log::info!("[updates] version {} is available", result.latest_version);
+                    if let Err(err) = app.emit("update-available", &result) {
+                        log::warn!("[updates] failed to emit update event: {err}");
+                    }
+                }
+                Ok(Ok(_)) => {}
+                Ok(Err(err)) => log::warn!("[updates] scheduled check failed: {err}"),
+                Err(err) => log::warn!("[updates] scheduled check join error: {err}"),
+            }
+
+            scheduler::wait(BackgroundTask::UpdateCheck, CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_versions() {
+        assert!(is_newer_version("0.1.0", "0.2.0").unwrap());
+        assert!(is_newer_version("0.1.9", "v0.1.10").unwrap());
+        assert!(is_newer_version("1.0.0-beta.1", "1.0.0").unwrap());
+        assert!(!is_newer_version("1.0.0", "1.0.0-beta.1").unwrap());
+        assert!(!is_newer_version("1.2.0", "1.2.0").unwrap());
+        assert!(is_newer_version("1.2", "not-a-version").is_err());
+    }
+
+    #[test]
+    fn compares_pre_release_identifiers() {
+        assert!(is_newer_version("1.0.0-beta.1", "1.0.0-beta.2").unwrap());
+        assert!(is_newer_version("1.0.0-beta.2", "1.0.0-beta.11").unwrap());
+        assert!(is_newer_version("1.0.0-alpha.9", "1.0.0-beta.1").unwrap());
+        assert!(is_newer_version("1.0.0-alpha", "1.0.0-alpha.1").unwrap());
+        assert!(is_newer_version("1.0.0-alpha.1", "1.0.0-alpha.beta").unwrap());
+        assert!(!is_newer_version("1.0.0-beta.2", "1.0.0-beta.2+build.5").unwrap());
+        assert!(!is_newer_version("1.0.0-rc.1", "1.0.0-beta.3").unwrap());
+        assert!(is_newer_version("1.0.0", "1.0.0-beta..1").is_err());
+    }
+}
@@ -0,0 +1,141 @@
+//! Identity sent with outbound ACME and DNS provider requests.
+//!
+//! Requests carry a `User-Agent` naming SSLBoard and its version so CAs and
+//! provider APIs can tell its traffic apart. The `outbound_contact`
+//! preference adds an operator contact, which some of them ask for before
+//! granting rate-limit exemptions or to report abuse: it is appended to the
+//! User-Agent and, when it is an email address, also sent as `From`. The
+//! `outbound_user_agent` preference replaces the product and version.
+//!
+//! Order issuance through acme-lib and Route 53 calls through the AWS SDK
+//! keep those libraries' own User-Agent.
+//!
+//! Startup installs the preferences here because adapters have no access to
+//! managed state; until then the default identity is sent.
+
+use std::sync::OnceLock;
+
+use reqwest::header::{FROM, HeaderMap, HeaderValue, USER_AGENT};
+
+use crate::storage::preferences::PreferencesStore;
+
+pub const USER_AGENT_PREFERENCE: &str = "outbound_user_agent";
+pub const CONTACT_PREFERENCE: &str = "outbound_contact";
+
+static PREFERENCES: OnceLock<PreferencesStore> = OnceLock::new();
+
+pub fn install(prefs: PreferencesStore) {
+    if PREFERENCES.set(prefs).is_err() {
+        log::warn!("[user-agent] preferences already installed");
+    }
+}
+
+pub fn default_user_agent() -> String {
+    format!("SSLBoard/{}", env!("CARGO_PKG_VERSION"))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboundIdentity {
+    pub user_agent: String,
+    /// Operator email sent as `From`
+    pub from: Option<String>,
+}
+
+impl OutboundIdentity {
+    /// Identity from the installed preferences.
+    pub fn current() -> Self {
+        match PREFERENCES.get() {
+            Some(prefs) => Self::from_preferences(prefs),
+            None => Self::new(None, None),
+        }
+    }
+
+    pub fn from_preferences(prefs: &PreferencesStore) -> Self {
+        let read = |name: &str| match prefs.get(name) {
+            Ok(pref) => pref.map(|pref| pref.value),
+            Err(err) => {
+                log::warn!("[user-agent] failed to read {name} preference: {err}");
+                None
+            }
+        };
+        Self::new(
+            read(USER_AGENT_PREFERENCE).as_deref(),
+            read(CONTACT_PREFERENCE).as_deref(),
+        )
+    }
+
+    /// Header values may only hold visible ASCII, so anything else is dropped;
+    /// parentheses would end the User-Agent comment early.
+    fn new(user_agent: Option<&str>, contact: Option<&str>) -> Self {
+        let clean = |value: &str, extra: &[char]| {
+            value
+                .chars()
+                .filter(|c| (c.is_ascii_graphic() || *c == ' ') && !extra.contains(c))
+                .collect::<String>()
+                .trim()
+                .to_string()
+        };
+        let product = user_agent
+            .map(|value| clean(value, &[]))
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(default_user_agent);
+        let contact = contact
+            .map(|value| clean(value, &['(', ')']))
+            .filter(|value| !value.is_empty());
+        let user_agent = match &contact {
+            Some(contact) => format!("{product} (contact: {contact})"),
+            None => product,
+        };
+        let from = contact
+            .map(|contact| contact.trim_start_matches("mailto:").to_string())
+            .filter(|contact| contact.contains('@') && !contact.contains(' '));
+        Self { user_agent, from }
+    }
+
+    pub fn apply_headers(&self, headers: &mut HeaderMap) {
+        if let Ok(value) = HeaderValue::from_str(&self.user_agent) {
+            headers.insert(USER_AGENT, value);
+        }
+        if let Some(from) = self.from.as_deref().and_then(|from| HeaderValue::from_str(from).ok()) {
+            headers.insert(FROM, from);
+        }
+    }
+
+    pub fn apply_ureq(&self, request: ureq::Request) -> ureq::Request {
+        let request = request.set("User-Agent", &self.user_agent);
+        match &self.from {
+            Some(from) => request.set("From", from),
+            None => request,
+        }
+    }
+}
+
+/// Sets the current identity on a ureq request.
+pub fn identify(request: ureq::Request) -> ureq::Request {
+    OutboundIdentity::current().apply_ureq(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity_adds_contact_and_sanitizes_values() {
+        let default = OutboundIdentity::new(None, Some("  "));
+        assert_eq!(default.user_agent, default_user_agent());
+        assert_eq!(default.from, None);
+
+        let identity =
+            OutboundIdentity::new(Some("Acme Ops/2.0\n"), Some("mailto:ops@example.com"));
+        assert_eq!(identity.user_agent, "Acme Ops/2.0 (contact: mailto:ops@example.com)");
+        assert_eq!(identity.from.as_deref(), Some("ops@example.com"));
+
+        let url_contact = OutboundIdentity::new(None, Some("https://example.com/(abuse)"));
+        assert!(url_contact.user_agent.ends_with("(contact: https://example.com/abuse)"));
+        assert_eq!(url_contact.from, None);
+
+        let mut headers = HeaderMap::new();
+        identity.apply_headers(&mut headers);
+        assert_eq!(headers[FROM], "ops@example.com");
+    }
+}
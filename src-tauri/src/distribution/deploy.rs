@@ -0,0 +1,211 @@
+//! Certificate deployment to configured targets.
+//!
+//! Each target type lives in its own module; this module builds the PEM
+//! bundle once, loads the target's credentials from the secret store, and
+//! dispatches to the matching implementation.
+
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use zeroize::Zeroizing;
+
+use crate::core::types::{CertificateRecord, CertificateSource, DeployTargetType, KeyExportPolicy};
+use crate::secrets::manager::SecretManager;
+use crate::storage::{deploy::DeployTarget, dns::DnsConfigStore};
+
+use super::{
+    digitalocean,
+    export::{split_certificate_chain, write_secure_file},
+    iis,
+    key_policy::ensure_key_export_allowed,
+    npm, s3, traefik,
+};
+
+/// PEM material for one certificate, split the way deployment targets expect it.
+pub struct CertificateBundle {
+    pub cert_pem: String,
+    pub chain_pem: String,
+    pub fullchain_pem: String,
+    pub key_pem: Option<Zeroizing<String>>,
+}
+
+impl CertificateBundle {
+    /// Loads the private key only when `include_private_key` is set and
+    /// `key_policy` allows sending it unencrypted.
+    pub fn from_record(
+        record: &CertificateRecord,
+        secrets: &SecretManager,
+        include_private_key: bool,
+        key_policy: KeyExportPolicy,
+    ) -> Result<Self> {
+        if !matches!(record.source, CertificateSource::Managed) {
+            return Err(anyhow!("Deployment is only available for Managed certificates"));
+        }
+        let chain = record
+            .chain_pem
+            .as_deref()
+            .ok_or_else(|| anyhow!("Certificate chain PEM is missing for deployment"))?;
+        let (cert_pem, chain_pem, fullchain_pem) = split_certificate_chain(chain)?;
+
+        let key_pem = if include_private_key {
+            ensure_key_export_allowed(key_policy, false)
+                .context("this target needs the private key")?;
+            let key_ref = record
+                .managed_key_ref
+                .as_deref()
+                .ok_or_else(|| anyhow!("Certificate does not have a managed key reference"))?;
+            let bytes = secrets.resolve_secret(key_ref)?;
+            Some(Zeroizing::new(
+                String::from_utf8(bytes)
+                    .map_err(|_| anyhow!("Managed key material was not valid UTF-8"))?,
+            ))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            cert_pem,
+            chain_pem,
+            fullchain_pem,
+            key_pem,
+        })
+    }
+
+    pub fn key(&self) -> Result<&str> {
+        self.key_pem
+            .as_deref()
+            .map(String::as_str)
+            .ok_or_else(|| anyhow!("private key was not loaded for this deployment"))
+    }
+}
+
+pub fn target_type_to_str(target_type: DeployTargetType) -> &'static str {
+    match target_type {
+        DeployTargetType::S3 => "s3",
+        DeployTargetType::DigitalOcean => "digitalocean",
+        DeployTargetType::NginxProxyManager => "nginx_proxy_manager",
+        DeployTargetType::Traefik => "traefik",
+        DeployTargetType::Iis => "iis",
+    }
+}
+
+pub fn target_type_from_str(raw: &str) -> Result<DeployTargetType> {
+    match raw {
+        "s3" => Ok(DeployTargetType::S3),
+        "digitalocean" => Ok(DeployTargetType::DigitalOcean),
+        "nginx_proxy_manager" => Ok(DeployTargetType::NginxProxyManager),
+        "traefik" => Ok(DeployTargetType::Traefik),
+        "iis" => Ok(DeployTargetType::Iis),
+        other => Err(anyhow!("unknown deploy target type: {other}")),
+    }
+}
+
+/// Checks a target configuration before it is stored.
+pub fn validate_target_config(target_type: DeployTargetType, config: &Value) -> Result<()> {
+    match target_type {
+        DeployTargetType::S3 => s3::parse_config(config).map(|_| ()),
+        DeployTargetType::DigitalOcean => digitalocean::parse_config(config).map(|_| ()),
+        DeployTargetType::NginxProxyManager => npm::parse_config(config).map(|_| ()),
+        DeployTargetType::Traefik => traefik::parse_config(config).map(|_| ()),
+        DeployTargetType::Iis => iis::parse_config(config).map(|_| ()),
+    }
+}
+
+/// Pushes `record` to `target` and returns the resulting locations.
+pub fn deploy_certificate(
+    target: &DeployTarget,
+    record: &CertificateRecord,
+    secrets: &SecretManager,
+    dns: &DnsConfigStore,
+    key_policy: KeyExportPolicy,
+) -> Result<Vec<String>> {
+    match target_type_from_str(&target.target_type)? {
+        DeployTargetType::S3 => {
+            let config = s3::parse_config(&target.config)?;
+            let credentials = load_credentials(target, secrets)?;
+            let bundle = CertificateBundle::from_record(
+                record,
+                secrets,
+                config.include_private_key,
+                key_policy,
+            )?;
+            s3::publish(&config, &credentials, record, &bundle)
+        }
+        DeployTargetType::DigitalOcean => {
+            let config = digitalocean::parse_config(&target.config)?;
+            let credentials = match config.dns_provider_id.as_deref() {
+                Some(provider_id) => digitalocean_dns_credentials(provider_id, secrets, dns)?,
+                None => load_credentials(target, secrets)?,
+            };
+            // Custom certificates on DO always include the key; apps never need it.
+            let bundle = CertificateBundle::from_record(
+                record,
+                secrets,
+                config.load_balancer_id.is_some(),
+                key_policy,
+            )?;
+            digitalocean::deploy(&config, &credentials, record, &bundle)
+        }
+        DeployTargetType::NginxProxyManager => {
+            let config = npm::parse_config(&target.config)?;
+            let bundle = CertificateBundle::from_record(record, secrets, true, key_policy)?;
+            npm::deploy(&config, &bundle)
+        }
+        DeployTargetType::Traefik => {
+            let config = traefik::parse_config(&target.config)?;
+            let bundle = CertificateBundle::from_record(record, secrets, true, key_policy)?;
+            traefik::deploy(&config, record, &bundle)
+        }
+        DeployTargetType::Iis => {
+            let config = iis::parse_config(&target.config)?;
+            let bundle = CertificateBundle::from_record(record, secrets, true, key_policy)?;
+            iis::deploy(&config, &bundle)
+        }
+    }
+}
+
+/// Replaces `path` via a temporary sibling so watchers never see a partial file.
+pub(crate) fn write_file_atomically(path: &Path, content: &[u8]) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("invalid deployment path {}", path.display()))?;
+    let mut temp_name = file_name.to_os_string();
+    temp_name.push(".sslboard-tmp");
+    let temp_path = path.with_file_name(temp_name);
+    write_secure_file(&temp_path, content, true)?;
+    fs::rename(&temp_path, path).with_context(|| format!("failed to replace {}", path.display()))
+}
+
+/// Reuses the API token of a DigitalOcean DNS provider.
+fn digitalocean_dns_credentials(
+    provider_id: &str,
+    secrets: &SecretManager,
+    dns: &DnsConfigStore,
+) -> Result<digitalocean::DigitalOceanCredentials> {
+    let provider = dns
+        .get_provider(provider_id)?
+        .ok_or_else(|| anyhow!("DNS provider not found: {provider_id}"))?;
+    if provider.provider_type != "digitalocean" {
+        return Err(anyhow!("DNS provider {} is not a DigitalOcean provider", provider.label));
+    }
+    let token_ref = provider
+        .secret_refs
+        .first()
+        .ok_or_else(|| anyhow!("DigitalOcean provider missing API token"))?;
+    let token = Zeroizing::new(secrets.resolve_secret(token_ref)?);
+    let api_token = String::from_utf8(token.to_vec())
+        .map_err(|_| anyhow!("Failed to decode DigitalOcean API token"))?;
+    Ok(digitalocean::DigitalOceanCredentials { api_token })
+}
+
+fn load_credentials<T: DeserializeOwned>(target: &DeployTarget, secrets: &SecretManager) -> Result<T> {
+    let secret_ref = target
+        .secret_ref
+        .as_deref()
+        .ok_or_else(|| anyhow!("deploy target {} has no stored credentials", target.label))?;
+    let raw = Zeroizing::new(secrets.resolve_secret(secret_ref)?);
+    serde_json::from_slice(&raw)
+        .with_context(|| format!("stored credentials for {} are malformed", target.label))
+}
@@ -0,0 +1,259 @@
+//! DigitalOcean certificate deployment.
+//!
+//! Load balancers: the bundle is uploaded as a DO custom certificate and every
+//! HTTPS forwarding rule on the balancer is switched to it. App Platform only
+//! terminates TLS with certificates DigitalOcean manages itself, so for apps
+//! the certificate's domains are attached to the app spec instead.
+//!
+//! The API token can come from an existing DigitalOcean DNS provider, so users
+//! of the DNS adapter do not have to enter it twice.
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::core::types::CertificateRecord;
+use crate::issuance::dns_providers::http::{status_error, HttpClient};
+
+use super::{
+    deploy::CertificateBundle,
+    naming::{render_folder_name, NameContext},
+};
+
+const API_BASE: &str = "https://api.digitalocean.com/v2";
+const PROVIDER: &str = "DigitalOcean";
+const HTTPS_PROTOCOLS: &[&str] = &["https", "http2", "http3"];
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DigitalOceanTargetConfig {
+    /// DigitalOcean DNS provider whose API token is reused
+    #[serde(default)]
+    pub dns_provider_id: Option<String>,
+    #[serde(default)]
+    pub load_balancer_id: Option<String>,
+    #[serde(default)]
+    pub app_id: Option<String>,
+    /// Name given to uploaded certificates; supports name placeholders
+    #[serde(default = "default_certificate_name")]
+    pub certificate_name: String,
+}
+
+#[derive(Deserialize)]
+pub struct DigitalOceanCredentials {
+    pub api_token: String,
+}
+
+fn default_certificate_name() -> String {
+    "sslboard-{domain}-{not_after:%Y%m%d}".to_string()
+}
+
+pub fn parse_config(config: &Value) -> Result<DigitalOceanTargetConfig> {
+    let config: DigitalOceanTargetConfig = serde_json::from_value(config.clone())
+        .context("invalid DigitalOcean target configuration")?;
+    let non_empty = |value: &Option<String>| value.as_deref().is_some_and(|v| !v.trim().is_empty());
+    match (non_empty(&config.load_balancer_id), non_empty(&config.app_id)) {
+        (true, false) | (false, true) => Ok(config),
+        (true, true) => Err(anyhow!(
+            "choose either a load balancer or an App Platform app, not both"
+        )),
+        (false, false) => Err(anyhow!("a load balancer id or App Platform app id is required")),
+    }
+}
+
+/// Deploys to the configured load balancer or app and returns the touched resources.
+pub fn deploy(
+    config: &DigitalOceanTargetConfig,
+    credentials: &DigitalOceanCredentials,
+    record: &CertificateRecord,
+    bundle: &CertificateBundle,
+) -> Result<Vec<String>> {
+    let api = Api {
+        token: &credentials.api_token,
+    };
+    if let Some(load_balancer_id) = config.load_balancer_id.as_deref() {
+        let name = render_folder_name(&config.certificate_name, &NameContext::from_record(record))?;
+        let certificate_id = api.upload_certificate(&name, bundle)?;
+        let rules = api.attach_to_load_balancer(load_balancer_id, &certificate_id)?;
+        log::info!(
+            "[deploy] DigitalOcean certificate {certificate_id} attached to {rules} rule(s) on {load_balancer_id}"
+        );
+        return Ok(vec![
+            format!("digitalocean:certificate/{certificate_id}"),
+            format!("digitalocean:load_balancer/{load_balancer_id}"),
+        ]);
+    }
+    let app_id = config
+        .app_id
+        .as_deref()
+        .ok_or_else(|| anyhow!("DigitalOcean target has no load balancer or app configured"))?;
+    let added = api.attach_app_domains(app_id, &record.sans)?;
+    log::info!("[deploy] DigitalOcean app {app_id}: {added} domain(s) added");
+    Ok(vec![format!("digitalocean:app/{app_id}")])
+}
+
+struct Api<'a> {
+    token: &'a str,
+}
+
+impl Api<'_> {
+    fn send(&self, request: reqwest::blocking::RequestBuilder, action: &str) -> Result<Value> {
+        let response = request
+            .header("Authorization", format!("Bearer {}", self.token))
+            .send()
+            .with_context(|| format!("Failed to {action}"))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(status_error(PROVIDER, status, Some(body)));
+        }
+        response
+            .json()
+            .with_context(|| format!("Failed to parse response to {action}"))
+    }
+
+    fn upload_certificate(&self, name: &str, bundle: &CertificateBundle) -> Result<String> {
+        let body = json!({
+            "name": name,
+            "type": "custom",
+            "private_key": bundle.key()?,
+            "leaf_certificate": bundle.cert_pem,
+            "certificate_chain": bundle.chain_pem,
+        });
+        let response = self.send(
+            HttpClient::shared()
+                .post(format!("{API_BASE}/certificates"))
+                .json(&body),
+            "upload DigitalOcean certificate",
+        )?;
+        response["certificate"]["id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("DigitalOcean certificate response did not include an id"))
+    }
+
+    /// Points every HTTPS forwarding rule at `certificate_id`; returns how many changed.
+    fn attach_to_load_balancer(&self, load_balancer_id: &str, certificate_id: &str) -> Result<usize> {
+        let url = format!("{API_BASE}/load_balancers/{load_balancer_id}");
+        let current = self.send(HttpClient::shared().get(&url), "fetch DigitalOcean load balancer")?;
+        let (body, updated) = retarget_load_balancer(&current["load_balancer"], certificate_id)?;
+        self.send(
+            HttpClient::shared().put(&url).json(&body),
+            "update DigitalOcean load balancer",
+        )?;
+        Ok(updated)
+    }
+
+    /// Adds any of `domains` missing from the app spec; returns how many were added.
+    fn attach_app_domains(&self, app_id: &str, domains: &[String]) -> Result<usize> {
+        let url = format!("{API_BASE}/apps/{app_id}");
+        let current = self.send(HttpClient::shared().get(&url), "fetch DigitalOcean app")?;
+        let mut spec = current["app"]["spec"].clone();
+        if !spec.is_object() {
+            return Err(anyhow!("DigitalOcean app {app_id} returned no spec"));
+        }
+        let added = add_app_domains(&mut spec, domains);
+        if added > 0 {
+            self.send(
+                HttpClient::shared().put(&url).json(&json!({ "spec": spec })),
+                "update DigitalOcean app",
+            )?;
+        }
+        Ok(added)
+    }
+}
+
+/// Builds the PUT body for a load balancer with its HTTPS rules using `certificate_id`.
+fn retarget_load_balancer(load_balancer: &Value, certificate_id: &str) -> Result<(Value, usize)> {
+    let mut body = load_balancer.clone();
+    let object = body
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("DigitalOcean load balancer response was malformed"))?;
+    for read_only in ["id", "ip", "ipv6", "status", "created_at"] {
+        object.remove(read_only);
+    }
+    // The API returns the region as an object but expects its slug on update.
+    if let Some(slug) = object
+        .get("region")
+        .and_then(|region| region.get("slug"))
+        .cloned()
+    {
+        object.insert("region".to_string(), slug);
+    }
+
+    let mut updated = 0;
+    if let Some(rules) = object
+        .get_mut("forwarding_rules")
+        .and_then(Value::as_array_mut)
+    {
+        for rule in rules {
+            let protocol = rule["entry_protocol"].as_str().unwrap_or_default();
+            if HTTPS_PROTOCOLS.contains(&protocol) {
+                rule["certificate_id"] = json!(certificate_id);
+                rule["tls_passthrough"] = json!(false);
+                updated += 1;
+            }
+        }
+    }
+    if updated == 0 {
+        return Err(anyhow!("load balancer has no HTTPS forwarding rule to attach the certificate to"));
+    }
+    Ok((body, updated))
+}
+
+fn add_app_domains(spec: &mut Value, domains: &[String]) -> usize {
+    if !spec["domains"].is_array() {
+        spec["domains"] = json!([]);
+    }
+    let Some(existing) = spec["domains"].as_array_mut() else {
+        return 0;
+    };
+    let mut added = 0;
+    for domain in domains {
+        let (name, wildcard) = match domain.strip_prefix("*.") {
+            Some(base) => (base, true),
+            None => (domain.as_str(), false),
+        };
+        let present = existing.iter().any(|entry| {
+            entry["domain"].as_str() == Some(name) && entry["wildcard"].as_bool().unwrap_or(false) == wildcard
+        });
+        if !present {
+            existing.push(json!({ "domain": name, "type": "ALIAS", "wildcard": wildcard }));
+            added += 1;
+        }
+    }
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retargets_https_rules_and_adds_app_domains() {
+        assert!(parse_config(&json!({})).is_err());
+        assert!(parse_config(&json!({ "load_balancer_id": "lb", "app_id": "app" })).is_err());
+        assert!(parse_config(&json!({ "app_id": "app" })).is_ok());
+
+        let load_balancer = json!({
+            "id": "lb-1",
+            "name": "edge",
+            "region": { "slug": "ams3", "name": "Amsterdam 3" },
+            "forwarding_rules": [
+                { "entry_protocol": "http", "entry_port": 80 },
+                { "entry_protocol": "https", "entry_port": 443, "certificate_id": "old" }
+            ]
+        });
+        let (body, updated) = retarget_load_balancer(&load_balancer, "new").unwrap();
+        assert_eq!(updated, 1);
+        assert_eq!(body["region"], "ams3");
+        assert!(body.get("id").is_none());
+        assert_eq!(body["forwarding_rules"][1]["certificate_id"], "new");
+        assert!(body["forwarding_rules"][0].get("certificate_id").is_none());
+
+        let mut spec = json!({ "name": "site", "domains": [{ "domain": "example.com" }] });
+        let domains = vec!["example.com".to_string(), "*.example.com".to_string()];
+        assert_eq!(add_app_domains(&mut spec, &domains), 1);
+        assert_eq!(spec["domains"][1]["wildcard"], true);
+        assert_eq!(add_app_domains(&mut spec, &domains), 0);
+    }
+}
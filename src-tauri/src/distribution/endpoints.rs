@@ -0,0 +1,106 @@
+//! Reads the certificate an endpoint actually serves.
+//!
+//! Endpoints are stored as `host[:port]`; the TLS handshake sends the host as
+//! SNI and skips chain validation, since an expired or superseded certificate
+//! is exactly what the check is looking for.
+
+use std::{
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context, Result};
+use openssl::{
+    hash::MessageDigest,
+    ssl::{SslConnector, SslMethod, SslVerifyMode},
+};
+
+const DEFAULT_PORT: u16 = 443;
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Splits `host[:port]` (IPv6 hosts in brackets) and applies the HTTPS port.
+/// A leading `https://` and any path are tolerated so URLs can be pasted.
+pub fn parse_endpoint(raw: &str) -> Result<(String, u16)> {
+    let trimmed = raw.trim();
+    let trimmed = trimmed.strip_prefix("https://").unwrap_or(trimmed);
+    let authority = trimmed.split('/').next().unwrap_or_default();
+    let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+        let (host, rest) = rest
+            .split_once(']')
+            .ok_or_else(|| anyhow!("invalid endpoint: {raw}"))?;
+        (host, rest.strip_prefix(':'))
+    } else {
+        match authority.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    if host.is_empty() || host.contains(char::is_whitespace) {
+        return Err(anyhow!("invalid endpoint: {raw}"));
+    }
+    let port = match port {
+        Some(port) => port
+            .parse::<u16>()
+            .ok()
+            .filter(|port| *port != 0)
+            .ok_or_else(|| anyhow!("invalid port in endpoint: {raw}"))?,
+        None => DEFAULT_PORT,
+    };
+    Ok((host.to_ascii_lowercase(), port))
+}
+
+/// Canonical `host:port` form stored for an endpoint.
+pub fn normalize_endpoint(raw: &str) -> Result<String> {
+    let (host, port) = parse_endpoint(raw)?;
+    Ok(if host.contains(':') {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    })
+}
+
+/// Connects to `endpoint` and returns the SHA-256 fingerprint (lowercase hex)
+/// of the leaf certificate it presents.
+pub fn served_fingerprint(endpoint: &str) -> Result<String> {
+    let (host, port) = parse_endpoint(endpoint)?;
+    let address = (host.as_str(), port)
+        .to_socket_addrs()
+        .with_context(|| format!("failed to resolve {host}"))?
+        .next()
+        .ok_or_else(|| anyhow!("{host} has no address"))?;
+    let stream = TcpStream::connect_timeout(&address, IO_TIMEOUT)
+        .with_context(|| format!("failed to connect to {host}:{port}"))?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let mut builder = SslConnector::builder(SslMethod::tls_client())?;
+    builder.set_verify(SslVerifyMode::NONE);
+    let mut config = builder.build().configure()?;
+    config.set_verify_hostname(false);
+    let tls = config
+        .connect(&host, stream)
+        .map_err(|err| anyhow!("TLS handshake with {host}:{port} failed: {err}"))?;
+    let certificate = tls
+        .ssl()
+        .peer_certificate()
+        .ok_or_else(|| anyhow!("{host}:{port} did not present a certificate"))?;
+    Ok(hex::encode(certificate.digest(MessageDigest::sha256())?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_normalizes_endpoints() {
+        assert_eq!(normalize_endpoint("Example.com").unwrap(), "example.com:443");
+        assert_eq!(
+            normalize_endpoint("https://example.com:8443/health").unwrap(),
+            "example.com:8443"
+        );
+        assert_eq!(normalize_endpoint("[2001:db8::1]").unwrap(), "[2001:db8::1]:443");
+        assert!(parse_endpoint("example.com:0").is_err());
+        assert!(parse_endpoint("example.com:https").is_err());
+        assert!(parse_endpoint(" ").is_err());
+    }
+}
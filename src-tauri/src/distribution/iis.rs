@@ -0,0 +1,276 @@
+//! Windows IIS / HTTP.sys deployment.
+//!
+//! The bundle is packed into a PFX, imported into `LocalMachine\My` with
+//! PowerShell, and the HTTP.sys SSL binding for the configured address and port
+//! is pointed at the new thumbprint with `netsh http`. When a site name is set,
+//! a matching https binding is added to the IIS site if it does not exist yet.
+//! All of this requires an elevated process.
+// Off Windows only configuration parsing is reachable.
+#![cfg_attr(not(windows), allow(dead_code))]
+
+use anyhow::{anyhow, Context, Result};
+use openssl::{
+    hash::MessageDigest,
+    pkcs12::Pkcs12,
+    pkey::PKey,
+    stack::Stack,
+    x509::X509,
+};
+use serde::Deserialize;
+use serde_json::Value;
+#[cfg(windows)]
+use zeroize::Zeroizing;
+
+use super::deploy::CertificateBundle;
+
+/// Application id IIS registers its HTTP.sys bindings under.
+const IIS_APP_ID: &str = "{4dc3e181-e14b-4a21-b022-59fc669b0914}";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IisTargetConfig {
+    /// IIS site that should carry the https binding
+    #[serde(default)]
+    pub site_name: Option<String>,
+    #[serde(default = "default_ip")]
+    pub ip: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Host name for an SNI binding; binds by IP and port when unset
+    #[serde(default)]
+    pub hostname: Option<String>,
+}
+
+fn default_ip() -> String {
+    "0.0.0.0".to_string()
+}
+
+fn default_port() -> u16 {
+    443
+}
+
+impl IisTargetConfig {
+    fn hostname(&self) -> Option<&str> {
+        self.hostname
+            .as_deref()
+            .map(str::trim)
+            .filter(|host| !host.is_empty())
+    }
+
+    fn site_name(&self) -> Option<&str> {
+        self.site_name
+            .as_deref()
+            .map(str::trim)
+            .filter(|site| !site.is_empty())
+    }
+
+    /// The `netsh http` binding selector, e.g. `ipport=0.0.0.0:443`.
+    fn binding_selector(&self) -> String {
+        match self.hostname() {
+            Some(host) => format!("hostnameport={host}:{}", self.port),
+            None => format!("ipport={}:{}", self.ip.trim(), self.port),
+        }
+    }
+}
+
+pub fn parse_config(config: &Value) -> Result<IisTargetConfig> {
+    if !cfg!(windows) {
+        return Err(anyhow!("IIS deployment is only available on Windows"));
+    }
+    let config: IisTargetConfig =
+        serde_json::from_value(config.clone()).context("invalid IIS target configuration")?;
+    if config.port == 0 {
+        return Err(anyhow!("IIS binding port must be between 1 and 65535"));
+    }
+    config
+        .ip
+        .trim()
+        .parse::<std::net::IpAddr>()
+        .map_err(|_| anyhow!("IIS binding address is not a valid IP address"))?;
+    if let Some(host) = config.hostname()
+        && !host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '*'))
+    {
+        return Err(anyhow!("IIS binding host name is invalid"));
+    }
+    Ok(config)
+}
+
+/// Builds a password-protected PFX and returns it with the certificate's SHA-1 thumbprint.
+fn build_pfx(bundle: &CertificateBundle, password: &str) -> Result<(Vec<u8>, String)> {
+    let mut certs = X509::stack_from_pem(bundle.fullchain_pem.as_bytes())
+        .context("failed to parse certificate chain for PFX")?
+        .into_iter();
+    let leaf = certs
+        .next()
+        .ok_or_else(|| anyhow!("certificate chain is empty"))?;
+    let mut ca = Stack::new()?;
+    for cert in certs {
+        ca.push(cert)?;
+    }
+    let key = PKey::private_key_from_pem(bundle.key()?.as_bytes())
+        .context("failed to parse private key for PFX")?;
+    let pfx = Pkcs12::builder()
+        .name("SSLBoard")
+        .pkey(&key)
+        .cert(&leaf)
+        .ca(ca)
+        .build2(password)
+        .context("failed to build PFX")?
+        .to_der()?;
+    let thumbprint = hex::encode_upper(leaf.digest(MessageDigest::sha1())?);
+    Ok((pfx, thumbprint))
+}
+
+fn sslcert_args(action: &str, config: &IisTargetConfig, thumbprint: &str) -> Vec<String> {
+    vec![
+        "http".to_string(),
+        action.to_string(),
+        "sslcert".to_string(),
+        config.binding_selector(),
+        format!("certhash={thumbprint}"),
+        format!("appid={IIS_APP_ID}"),
+        "certstorename=MY".to_string(),
+    ]
+}
+
+#[cfg(windows)]
+pub fn deploy(config: &IisTargetConfig, bundle: &CertificateBundle) -> Result<Vec<String>> {
+    use std::process::Command;
+
+    let mut random = Zeroizing::new([0u8; 24]);
+    openssl::rand::rand_bytes(&mut random[..])?;
+    let password = Zeroizing::new(hex::encode(&random[..]));
+    let (pfx, thumbprint) = build_pfx(bundle, &password)?;
+    let pfx_path = std::env::temp_dir().join(format!("sslboard-{}.pfx", uuid::Uuid::new_v4()));
+    super::export::write_secure_file(&pfx_path, &pfx, false)?;
+
+    let import = Command::new("powershell.exe")
+        .args(["-NoProfile", "-NonInteractive", "-Command"])
+        .arg(
+            "Import-PfxCertificate -FilePath $env:SSLBOARD_PFX_PATH \
+             -CertStoreLocation Cert:\\LocalMachine\\My \
+             -Password (ConvertTo-SecureString $env:SSLBOARD_PFX_PASSWORD -AsPlainText -Force) \
+             | Out-Null",
+        )
+        .env("SSLBOARD_PFX_PATH", &pfx_path)
+        .env("SSLBOARD_PFX_PASSWORD", password.as_str())
+        .output();
+    let _ = std::fs::remove_file(&pfx_path);
+    check_output(import.context("failed to run PowerShell")?, "import the PFX")?;
+
+    if let Some(site) = config.site_name() {
+        let host = config.hostname().unwrap_or_default();
+        let ssl_flags = if config.hostname().is_some() { 1 } else { 0 };
+        // Values reach the script through the environment, never its text,
+        // so no site name can break out of a quoted string. The host header is
+        // expanded into a string so an empty one stays empty rather than null.
+        let script = format!(
+            "Import-Module WebAdministration; \
+             if (-not (Get-WebBinding -Name $env:SSLBOARD_SITE -Protocol https -Port {port} \
+             -HostHeader \"$env:SSLBOARD_HOST\")) {{ \
+             New-WebBinding -Name $env:SSLBOARD_SITE -Protocol https \
+             -IPAddress $env:SSLBOARD_IP -Port {port} -HostHeader \"$env:SSLBOARD_HOST\" \
+             -SslFlags {ssl_flags} }}",
+            port = config.port,
+        );
+        let ip = if config.ip.trim() == "0.0.0.0" { "*" } else { config.ip.trim() };
+        let output = Command::new("powershell.exe")
+            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+            .env("SSLBOARD_SITE", site)
+            .env("SSLBOARD_HOST", host)
+            .env("SSLBOARD_IP", ip)
+            .output()
+            .context("failed to run PowerShell")?;
+        check_output(output, "add the IIS site binding")?;
+    }
+
+    let update = Command::new("netsh")
+        .args(sslcert_args("update", config, &thumbprint))
+        .output()
+        .context("failed to run netsh")?;
+    if !update.status.success() {
+        // No binding yet for this address and port.
+        let add = Command::new("netsh")
+            .args(sslcert_args("add", config, &thumbprint))
+            .output()
+            .context("failed to run netsh")?;
+        check_output(add, "bind the certificate in HTTP.sys")?;
+    }
+
+    log::info!(
+        "[deploy] IIS binding {} now uses certificate {thumbprint}",
+        config.binding_selector()
+    );
+    Ok(vec![
+        format!("Cert:\\LocalMachine\\My\\{thumbprint}"),
+        format!("http.sys:{}", config.binding_selector()),
+    ])
+}
+
+#[cfg(not(windows))]
+pub fn deploy(_config: &IisTargetConfig, _bundle: &CertificateBundle) -> Result<Vec<String>> {
+    Err(anyhow!("IIS deployment is only available on Windows"))
+}
+
+#[cfg(windows)]
+fn check_output(output: std::process::Output, action: &str) -> Result<()> {
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let detail = if stderr.trim().is_empty() { stdout } else { stderr };
+    Err(anyhow!("failed to {action}: {}", detail.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::{
+        asn1::Asn1Time,
+        ec::{EcGroup, EcKey},
+        nid::Nid,
+        x509::X509NameBuilder,
+    };
+    use zeroize::Zeroizing;
+
+    fn self_signed() -> (String, String) {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", "example.com").unwrap();
+        let name = name.build();
+        let mut builder = X509::builder().unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+        builder.set_not_after(&Asn1Time::days_from_now(30).unwrap()).unwrap();
+        builder.sign(&key, MessageDigest::sha256()).unwrap();
+        let cert = String::from_utf8(builder.build().to_pem().unwrap()).unwrap();
+        let key = String::from_utf8(key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+        (cert, key)
+    }
+
+    #[test]
+    fn builds_pfx_and_netsh_arguments() {
+        let (cert, key) = self_signed();
+        let bundle = CertificateBundle {
+            cert_pem: cert.clone(),
+            chain_pem: String::new(),
+            fullchain_pem: cert,
+            key_pem: Some(Zeroizing::new(key)),
+        };
+        let (pfx, thumbprint) = build_pfx(&bundle, "secret").unwrap();
+        assert_eq!(thumbprint.len(), 40);
+        let parsed = Pkcs12::from_der(&pfx).unwrap().parse2("secret").unwrap();
+        assert!(parsed.pkey.is_some());
+
+        let config: IisTargetConfig =
+            serde_json::from_value(serde_json::json!({ "hostname": "example.com" })).unwrap();
+        let args = sslcert_args("update", &config, &thumbprint);
+        assert_eq!(args[3], "hostnameport=example.com:443");
+        assert_eq!(args[5], format!("appid={IIS_APP_ID}"));
+    }
+}
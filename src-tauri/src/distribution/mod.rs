@@ -1 +1,12 @@
+pub mod deploy;
+pub mod digitalocean;
+pub mod endpoints;
 pub mod export;
+pub mod iis;
+pub mod key_policy;
+pub mod naming;
+pub mod npm;
+pub mod qr;
+pub mod s3;
+pub mod traefik;
+pub mod verify;
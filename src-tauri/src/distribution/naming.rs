@@ -0,0 +1,179 @@
+//! Export name templates.
+//!
+//! Folder and file names may contain placeholders such as `{domain}`,
+//! `{serial}`, or `{not_after:%Y%m%d}` so repeated exports land in distinct
+//! locations. Substituted values are sanitized and the rendered name must
+//! still be a single path segment.
+
+use anyhow::{anyhow, Result};
+use chrono::{
+    format::{Item, StrftimeItems},
+    DateTime, Utc,
+};
+
+use crate::core::types::CertificateRecord;
+
+use super::export::{validate_folder_name, ExportFileNames};
+
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Values available to name templates for one certificate.
+pub struct NameContext<'a> {
+    pub domain: &'a str,
+    pub serial: &'a str,
+    pub id: &'a str,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+}
+
+impl<'a> NameContext<'a> {
+    pub fn from_record(record: &'a CertificateRecord) -> Self {
+        let domain = record
+            .sans
+            .first()
+            .or_else(|| record.subjects.first())
+            .map(String::as_str)
+            .unwrap_or(record.id.as_str());
+        Self {
+            domain,
+            serial: &record.serial,
+            id: &record.id,
+            not_before: record.not_before,
+            not_after: record.not_after,
+        }
+    }
+}
+
+/// Renders a folder name template. Names without placeholders pass through unchanged.
+pub fn render_folder_name(template: &str, ctx: &NameContext<'_>) -> Result<String> {
+    let rendered = render(template, ctx, None)?;
+    validate_folder_name(&rendered)?;
+    Ok(rendered)
+}
+
+/// Renders a file name template; `{file}` expands to the bundle part (e.g. `fullchain`).
+pub fn render_file_name(template: &str, ctx: &NameContext<'_>, file: &str) -> Result<String> {
+    let rendered = render(template, ctx, Some(file))?;
+    validate_folder_name(&rendered).map_err(|_| anyhow!("file name must be a single path segment"))?;
+    Ok(rendered)
+}
+
+/// Renders the file names for every bundle part from one template.
+pub fn render_file_names(template: &str, ctx: &NameContext<'_>) -> Result<ExportFileNames> {
+    let names = ExportFileNames {
+        cert: render_file_name(template, ctx, "cert")?,
+        chain: render_file_name(template, ctx, "chain")?,
+        fullchain: render_file_name(template, ctx, "fullchain")?,
+        privkey: render_file_name(template, ctx, "privkey")?,
+    };
+    if names.cert == names.chain || names.cert == names.fullchain || names.chain == names.fullchain {
+        return Err(anyhow!("file name template must include {{file}} so bundle parts do not collide"));
+    }
+    Ok(names)
+}
+
+fn render(template: &str, ctx: &NameContext<'_>, file: Option<&str>) -> Result<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        if rest[start..].starts_with('}') {
+            return Err(anyhow!("unmatched '}}' in name template: {template}"));
+        }
+        out.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| anyhow!("unclosed '{{' in name template: {template}"))?;
+        let placeholder = &rest[start + 1..start + end];
+        out.push_str(&sanitize(&expand(placeholder, ctx, file)?));
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn expand(placeholder: &str, ctx: &NameContext<'_>, file: Option<&str>) -> Result<String> {
+    let (name, format) = match placeholder.split_once(':') {
+        Some((name, format)) => (name.trim(), Some(format)),
+        None => (placeholder.trim(), None),
+    };
+    let value = match name {
+        "domain" => ctx.domain.replace("*.", "wildcard."),
+        "serial" => ctx.serial.to_string(),
+        "id" => ctx.id.to_string(),
+        "not_before" => return format_date(ctx.not_before, format),
+        "not_after" => return format_date(ctx.not_after, format),
+        "file" => file
+            .ok_or_else(|| anyhow!("{{file}} is only available in file name templates"))?
+            .to_string(),
+        other => return Err(anyhow!("unknown name template placeholder: {{{other}}}")),
+    };
+    if format.is_some() {
+        return Err(anyhow!("placeholder {{{name}}} does not take a format"));
+    }
+    Ok(value)
+}
+
+fn format_date(value: DateTime<Utc>, format: Option<&str>) -> Result<String> {
+    let format = format.unwrap_or(DEFAULT_DATE_FORMAT);
+    let items: Vec<Item<'_>> = StrftimeItems::new(format).collect();
+    if items.iter().any(|item| matches!(item, Item::Error)) {
+        return Err(anyhow!("invalid date format in name template: {format}"));
+    }
+    Ok(value.format_with_items(items.into_iter()).to_string())
+}
+
+/// Replaces characters that are path separators or invalid on Windows.
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn ctx() -> NameContext<'static> {
+        NameContext {
+            domain: "*.example.com",
+            serial: "0A1B",
+            id: "cert_1",
+            not_before: Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap(),
+            not_after: Utc.with_ymd_and_hms(2025, 4, 2, 0, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        assert_eq!(
+            render_folder_name("{domain}-{not_after:%Y%m%d}-{serial}", &ctx()).unwrap(),
+            "wildcard.example.com-20250402-0A1B"
+        );
+        assert_eq!(render_folder_name("plain.example", &ctx()).unwrap(), "plain.example");
+        assert_eq!(
+            render_file_name("{domain}.{file}.pem", &ctx(), "fullchain").unwrap(),
+            "wildcard.example.com.fullchain.pem"
+        );
+    }
+
+    #[test]
+    fn rejects_bad_templates_and_traversal() {
+        assert!(render_folder_name("{unknown}", &ctx()).is_err());
+        assert!(render_folder_name("{domain", &ctx()).is_err());
+        assert!(render_folder_name("{not_after:%Q}", &ctx()).is_err());
+        assert!(render_folder_name("{file}", &ctx()).is_err());
+        assert!(render_file_names("{domain}.pem", &ctx()).is_err());
+        assert!(render_folder_name("../{domain}", &ctx()).is_err());
+        // Separators produced by a date format are sanitized rather than nesting folders.
+        assert_eq!(
+            render_folder_name("{not_after:%m/%d}", &ctx()).unwrap(),
+            "04_02"
+        );
+    }
+}
@@ -0,0 +1,63 @@
+//! Nginx Proxy Manager deployment.
+//!
+//! NPM keeps uploaded "custom" certificates under
+//! `<data>/custom_ssl/npm-<id>/{fullchain,privkey}.pem`. The certificate entry
+//! has to exist in NPM once (upload any placeholder through its UI); after that
+//! each deployment overwrites the files in place. NPM only reads them when
+//! nginx reloads, which it does on proxy host changes or container restart.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::deploy::{write_file_atomically, CertificateBundle};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NpmTargetConfig {
+    /// NPM's `/data` directory as seen from this machine
+    pub data_dir: String,
+    /// Numeric id of the custom certificate in NPM
+    pub certificate_id: u32,
+}
+
+pub fn parse_config(config: &Value) -> Result<NpmTargetConfig> {
+    let config: NpmTargetConfig = serde_json::from_value(config.clone())
+        .context("invalid Nginx Proxy Manager target configuration")?;
+    if !Path::new(config.data_dir.trim()).is_absolute() {
+        return Err(anyhow!("Nginx Proxy Manager data directory must be an absolute path"));
+    }
+    if config.certificate_id == 0 {
+        return Err(anyhow!("Nginx Proxy Manager certificate id must be positive"));
+    }
+    Ok(config)
+}
+
+fn certificate_dir(config: &NpmTargetConfig) -> PathBuf {
+    Path::new(config.data_dir.trim())
+        .join("custom_ssl")
+        .join(format!("npm-{}", config.certificate_id))
+}
+
+pub fn deploy(config: &NpmTargetConfig, bundle: &CertificateBundle) -> Result<Vec<String>> {
+    let data_dir = Path::new(config.data_dir.trim());
+    if !data_dir.is_dir() {
+        return Err(anyhow!(
+            "Nginx Proxy Manager data directory not found: {}",
+            data_dir.display()
+        ));
+    }
+    let dir = certificate_dir(config);
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("failed to create {}", dir.display()))?;
+
+    let fullchain = dir.join("fullchain.pem");
+    let privkey = dir.join("privkey.pem");
+    write_file_atomically(&fullchain, bundle.fullchain_pem.as_bytes())?;
+    write_file_atomically(&privkey, bundle.key()?.as_bytes())?;
+    Ok(vec![
+        fullchain.display().to_string(),
+        privkey.display().to_string(),
+    ])
+}
@@ -0,0 +1,97 @@
+//! QR codes for out-of-band certificate verification.
+//!
+//! The payload only carries public details (names, fingerprint, validity) so
+//! another team can compare what they received against the inventory. It is
+//! rendered to PNG here so the UI never assembles the data itself.
+
+use anyhow::{Context, Result};
+use qrcode::{Color, EcLevel, QrCode};
+
+use crate::core::types::CertificateRecord;
+use crate::domain::normalize_domains_for_display;
+
+/// Names listed in the payload before the rest are summarized as a count.
+const MAX_PAYLOAD_NAMES: usize = 10;
+/// Pixels per QR module.
+const MODULE_SCALE: usize = 8;
+/// Light modules around the code, as recommended by the QR spec.
+const QUIET_ZONE: usize = 4;
+
+/// Builds the plain-text verification payload for a certificate.
+pub fn verification_payload(record: &CertificateRecord) -> String {
+    let names = normalize_domains_for_display(&record.sans);
+    let mut listed = names
+        .iter()
+        .take(MAX_PAYLOAD_NAMES)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(",");
+    if names.len() > MAX_PAYLOAD_NAMES {
+        listed.push_str(&format!(" (+{} more)", names.len() - MAX_PAYLOAD_NAMES));
+    }
+
+    [
+        "SSLBoard certificate".to_string(),
+        format!("Names: {listed}"),
+        format!("SHA-256: {}", record.fingerprint),
+        format!("Serial: {}", record.serial),
+        format!("Issuer: {}", record.issuer),
+        format!("Not before: {}", record.not_before.to_rfc3339()),
+        format!("Not after: {}", record.not_after.to_rfc3339()),
+    ]
+    .join("\n")
+}
+
+/// Renders the payload as a grayscale PNG QR code.
+pub fn render_qr_png(payload: &str) -> Result<Vec<u8>> {
+    let code = QrCode::with_error_correction_level(payload.as_bytes(), EcLevel::M)
+        .context("Verification details do not fit in a QR code")?;
+    let modules = code.width();
+    let size = (modules + QUIET_ZONE * 2) * MODULE_SCALE;
+    let colors = code.to_colors();
+
+    let mut pixels = vec![u8::MAX; size * size];
+    for (index, color) in colors.iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+        let left = (index % modules + QUIET_ZONE) * MODULE_SCALE;
+        let top = (index / modules + QUIET_ZONE) * MODULE_SCALE;
+        for row in top..top + MODULE_SCALE {
+            pixels[row * size + left..row * size + left + MODULE_SCALE].fill(0);
+        }
+    }
+
+    let mut png_bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png_bytes, size as u32, size as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().context("Failed to encode QR code")?;
+    writer
+        .write_image_data(&pixels)
+        .context("Failed to encode QR code")?;
+    writer.finish().context("Failed to encode QR code")?;
+    Ok(png_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_png_with_quiet_zone() {
+        let png_bytes = render_qr_png("SSLBoard certificate\nSHA-256: AB:CD").unwrap();
+        assert_eq!(&png_bytes[..8], b"\x89PNG\r\n\x1a\n");
+
+        let decoder = png::Decoder::new(png_bytes.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!(info.width, info.height);
+        assert_eq!(info.width as usize % MODULE_SCALE, 0);
+        // The corner is quiet zone; the finder pattern starts right after it.
+        assert_eq!(pixels[0], u8::MAX);
+        let finder = QUIET_ZONE * MODULE_SCALE;
+        assert_eq!(pixels[finder * info.width as usize + finder], 0);
+    }
+}
@@ -0,0 +1,189 @@
+//! S3-compatible object storage deployment.
+//!
+//! Uploads `cert.pem`, `chain.pem`, `fullchain.pem` (and optionally
+//! `privkey.pem`) under a templated prefix. A custom endpoint with path-style
+//! addressing covers MinIO, Cloudflare R2, Backblaze B2, and similar stores.
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::core::runtime;
+use crate::core::types::CertificateRecord;
+
+use super::{
+    deploy::CertificateBundle,
+    naming::{render_folder_name, NameContext},
+};
+
+const DEFAULT_REGION: &str = "us-east-1";
+const PEM_CONTENT_TYPE: &str = "application/x-pem-file";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum S3Encryption {
+    #[default]
+    None,
+    Aes256,
+    AwsKms,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct S3TargetConfig {
+    /// Custom endpoint for S3-compatible stores; AWS is used when unset
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub region: Option<String>,
+    pub bucket: String,
+    /// Key prefix; segments may use name placeholders such as `{domain}`
+    #[serde(default = "default_prefix")]
+    pub prefix: String,
+    #[serde(default)]
+    pub path_style: bool,
+    #[serde(default)]
+    pub encryption: S3Encryption,
+    #[serde(default)]
+    pub kms_key_id: Option<String>,
+    #[serde(default)]
+    pub include_private_key: bool,
+}
+
+#[derive(Deserialize)]
+pub struct S3Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+fn default_prefix() -> String {
+    "{domain}".to_string()
+}
+
+pub fn parse_config(config: &Value) -> Result<S3TargetConfig> {
+    let config: S3TargetConfig =
+        serde_json::from_value(config.clone()).context("invalid S3 target configuration")?;
+    if config.bucket.trim().is_empty() {
+        return Err(anyhow!("S3 bucket is required"));
+    }
+    if let Some(endpoint) = config.endpoint.as_deref()
+        && !endpoint.starts_with("https://")
+        && !endpoint.starts_with("http://")
+    {
+        return Err(anyhow!("S3 endpoint must be an http(s) URL"));
+    }
+    if config.encryption == S3Encryption::AwsKms
+        && config.kms_key_id.as_deref().is_none_or(|id| id.trim().is_empty())
+    {
+        return Err(anyhow!("a KMS key id is required for aws_kms encryption"));
+    }
+    Ok(config)
+}
+
+/// Renders the object key prefix for a certificate, one path segment at a time.
+pub fn render_prefix(template: &str, ctx: &NameContext<'_>) -> Result<String> {
+    template
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| render_folder_name(segment, ctx))
+        .collect::<Result<Vec<_>>>()
+        .map(|segments| segments.join("/"))
+}
+
+fn objects(bundle: &CertificateBundle) -> Result<Vec<(&'static str, &str)>> {
+    let mut objects = vec![
+        ("cert.pem", bundle.cert_pem.as_str()),
+        ("chain.pem", bundle.chain_pem.as_str()),
+        ("fullchain.pem", bundle.fullchain_pem.as_str()),
+    ];
+    if bundle.key_pem.is_some() {
+        objects.push(("privkey.pem", bundle.key()?));
+    }
+    Ok(objects)
+}
+
+pub fn publish(
+    config: &S3TargetConfig,
+    credentials: &S3Credentials,
+    record: &CertificateRecord,
+    bundle: &CertificateBundle,
+) -> Result<Vec<String>> {
+    use aws_sdk_s3::config::{BehaviorVersion, Builder, Credentials, Region};
+    use aws_sdk_s3::primitives::ByteStream;
+    use aws_sdk_s3::types::ServerSideEncryption;
+    use aws_sdk_s3::Client;
+
+    let prefix = render_prefix(&config.prefix, &NameContext::from_record(record))?;
+    let region = config
+        .region
+        .clone()
+        .filter(|region| !region.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_REGION.to_string());
+    let mut builder = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new(region))
+        .credentials_provider(Credentials::new(
+            &credentials.access_key_id,
+            &credentials.secret_access_key,
+            None,
+            None,
+            "sslboard",
+        ))
+        .force_path_style(config.path_style);
+    if let Some(endpoint) = config.endpoint.as_deref() {
+        builder = builder.endpoint_url(endpoint.trim_end_matches('/'));
+    }
+    let client = Client::from_conf(builder.build());
+
+    let mut locations = Vec::new();
+    for (name, body) in objects(bundle)? {
+        let key = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix}/{name}")
+        };
+        let mut request = client
+            .put_object()
+            .bucket(&config.bucket)
+            .key(&key)
+            .content_type(PEM_CONTENT_TYPE)
+            .body(ByteStream::from(body.as_bytes().to_vec()));
+        request = match config.encryption {
+            S3Encryption::None => request,
+            S3Encryption::Aes256 => request.server_side_encryption(ServerSideEncryption::Aes256),
+            S3Encryption::AwsKms => request
+                .server_side_encryption(ServerSideEncryption::AwsKms)
+                .ssekms_key_id(config.kms_key_id.clone().unwrap_or_default()),
+        };
+        runtime::block_on(request.send())?
+            .map_err(|err| anyhow!("failed to upload s3://{}/{key}: {err}", config.bucket))?;
+        locations.push(format!("s3://{}/{key}", config.bucket));
+    }
+    Ok(locations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    #[test]
+    fn validates_config_and_renders_prefix() {
+        assert!(parse_config(&json!({ "bucket": "" })).is_err());
+        assert!(parse_config(&json!({ "bucket": "certs", "endpoint": "minio:9000" })).is_err());
+        assert!(parse_config(&json!({ "bucket": "certs", "encryption": "aws_kms" })).is_err());
+        let config = parse_config(&json!({ "bucket": "certs", "encryption": "aes256" })).unwrap();
+        assert_eq!(config.prefix, "{domain}");
+        assert_eq!(config.encryption, S3Encryption::Aes256);
+
+        let ctx = NameContext {
+            domain: "example.com",
+            serial: "01",
+            id: "cert_1",
+            not_before: Utc::now(),
+            not_after: Utc::now(),
+        };
+        assert_eq!(render_prefix("/tls/{domain}/", &ctx).unwrap(), "tls/example.com");
+        assert!(render_prefix("tls/../{domain}", &ctx).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+//! Traefik file-provider deployment.
+//!
+//! Writes `<name>.crt` (full chain) and `<name>.key` into the certificate
+//! directory and a dynamic configuration fragment `<name>.yml` listing them
+//! under `tls.certificates`. Traefik picks the fragment up when the file
+//! provider watches that directory. Paths in the fragment use
+//! `container_cert_dir` when Traefik sees the certificates at a different path
+//! (for example inside a container).
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::core::types::CertificateRecord;
+
+use super::{
+    deploy::{write_file_atomically, CertificateBundle},
+    naming::{render_folder_name, NameContext},
+};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TraefikTargetConfig {
+    /// Directory the certificate and key are written to
+    pub cert_dir: String,
+    /// Directory watched by Traefik's file provider
+    pub dynamic_config_dir: String,
+    /// Certificate directory as Traefik sees it; defaults to `cert_dir`
+    #[serde(default)]
+    pub container_cert_dir: Option<String>,
+    /// Base name for the written files; supports name placeholders
+    #[serde(default = "default_file_name")]
+    pub file_name: String,
+}
+
+fn default_file_name() -> String {
+    "{domain}".to_string()
+}
+
+pub fn parse_config(config: &Value) -> Result<TraefikTargetConfig> {
+    let config: TraefikTargetConfig =
+        serde_json::from_value(config.clone()).context("invalid Traefik target configuration")?;
+    for (name, dir) in [
+        ("certificate directory", &config.cert_dir),
+        ("dynamic configuration directory", &config.dynamic_config_dir),
+    ] {
+        if !Path::new(dir.trim()).is_absolute() {
+            return Err(anyhow!("Traefik {name} must be an absolute path"));
+        }
+    }
+    if config.file_name.trim().is_empty() {
+        return Err(anyhow!("Traefik file name is required"));
+    }
+    Ok(config)
+}
+
+pub fn deploy(
+    config: &TraefikTargetConfig,
+    record: &CertificateRecord,
+    bundle: &CertificateBundle,
+) -> Result<Vec<String>> {
+    let name = render_folder_name(&config.file_name, &NameContext::from_record(record))?;
+    let cert_dir = PathBuf::from(config.cert_dir.trim());
+    let dynamic_dir = PathBuf::from(config.dynamic_config_dir.trim());
+    for dir in [&cert_dir, &dynamic_dir] {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+
+    let cert_path = cert_dir.join(format!("{name}.crt"));
+    let key_path = cert_dir.join(format!("{name}.key"));
+    let config_path = dynamic_dir.join(format!("{name}.yml"));
+    write_file_atomically(&cert_path, bundle.fullchain_pem.as_bytes())?;
+    write_file_atomically(&key_path, bundle.key()?.as_bytes())?;
+
+    let referenced_dir = config
+        .container_cert_dir
+        .as_deref()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+        .unwrap_or(config.cert_dir.trim());
+    // Written last so Traefik never loads a fragment pointing at missing files.
+    write_file_atomically(
+        &config_path,
+        dynamic_config(referenced_dir, &name, &record.sans).as_bytes(),
+    )?;
+
+    Ok(vec![
+        cert_path.display().to_string(),
+        key_path.display().to_string(),
+        config_path.display().to_string(),
+    ])
+}
+
+fn dynamic_config(cert_dir: &str, name: &str, domains: &[String]) -> String {
+    let dir = cert_dir.trim_end_matches(['/', '\\']);
+    format!(
+        "# Managed by SSLBoard for {domains}; overwritten on each deployment.\n\
+         tls:\n  certificates:\n    - certFile: {cert}\n      keyFile: {key}\n",
+        domains = domains.join(", "),
+        cert = yaml_string(&format!("{dir}/{name}.crt")),
+        key = yaml_string(&format!("{dir}/{name}.key")),
+    )
+}
+
+fn yaml_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renders_dynamic_config_with_container_paths() {
+        assert!(parse_config(&json!({ "cert_dir": "certs", "dynamic_config_dir": "/dyn" })).is_err());
+        let config = parse_config(&json!({
+            "cert_dir": "/srv/traefik/certs",
+            "dynamic_config_dir": "/srv/traefik/dynamic",
+        }))
+        .unwrap();
+        assert_eq!(config.file_name, "{domain}");
+
+        let domains = vec!["example.com".to_string(), "www.example.com".to_string()];
+        let fragment = dynamic_config("/certs/", "example.com", &domains);
+        assert!(fragment.contains("certFile: \"/certs/example.com.crt\""));
+        assert!(fragment.contains("keyFile: \"/certs/example.com.key\""));
+        assert!(fragment.contains("for example.com, www.example.com"));
+    }
+}
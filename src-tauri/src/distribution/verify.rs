@@ -0,0 +1,277 @@
+//! Verification of previously exported PEM files.
+//!
+//! Reads an export folder (or a single PEM file), identifies the leaf
+//! certificate, and compares it with the inventory so stale deployments from
+//! older exports can be spotted: a different fingerprint, a superseded
+//! certificate, a truncated chain, or a private key that does not belong to
+//! the certificate. Passphrase-encrypted keys are not opened, so their match
+//! is left unknown.
+
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use openssl::{
+    hash::MessageDigest,
+    pkey::PKey,
+    x509::{X509, X509VerifyResult},
+};
+
+use crate::core::types::{CertificateRecord, CertificateStatus, ExportVerification};
+
+/// How much of each file is read to tell PEM files from others.
+const PEM_SNIFF_BYTES: usize = 4096;
+
+/// Verifies the PEM files at `path` against the inventory `records`.
+pub fn verify_export_path(path: &Path, records: &[CertificateRecord]) -> Result<ExportVerification> {
+    let files = pem_files(path)?;
+    let mut certificates: Vec<X509> = Vec::new();
+    let mut private_keys = Vec::new();
+    for file in &files {
+        let contents =
+            fs::read(file).with_context(|| format!("failed to read {}", file.display()))?;
+        let text = String::from_utf8_lossy(&contents);
+        if is_encrypted_key(&text) {
+            log::info!("[export] skipping encrypted private key in {}", file.display());
+        } else if text.contains("PRIVATE KEY-----") {
+            private_keys.push(
+                PKey::private_key_from_pem(&contents)
+                    .with_context(|| format!("failed to parse private key in {}", file.display()))?,
+            );
+        }
+        if text.contains("BEGIN CERTIFICATE") {
+            let stack = X509::stack_from_pem(&contents)
+                .with_context(|| format!("failed to parse certificates in {}", file.display()))?;
+            // Keep the longest file first so a fullchain wins over cert/chain parts.
+            if stack.len() > certificates.len() {
+                certificates = stack;
+            }
+        }
+    }
+
+    let leaf = certificates
+        .first()
+        .ok_or_else(|| anyhow!("no certificates found at {}", path.display()))?;
+    let fingerprint = hex::encode(leaf.digest(MessageDigest::sha256())?);
+    let mut issues = Vec::new();
+
+    let record = records
+        .iter()
+        .find(|record| normalize_fingerprint(&record.fingerprint) == fingerprint);
+    match record {
+        None => issues.push("certificate does not match any inventory record".to_string()),
+        Some(record) if record.status == CertificateStatus::Superseded => {
+            let newer = records.iter().find(|other| {
+                other.id != record.id
+                    && other.status != CertificateStatus::Superseded
+                    && same_names(other, record)
+            });
+            issues.push(match newer {
+                Some(newer) => format!("certificate has been superseded by {}", newer.id),
+                None => "certificate has been superseded".to_string(),
+            });
+        }
+        Some(record) if record.status == CertificateStatus::Expired => {
+            issues.push("certificate has expired".to_string());
+        }
+        Some(_) => {}
+    }
+
+    let chain_complete = chain_is_complete(&certificates, record);
+    if !chain_complete {
+        issues.push("exported chain is incomplete".to_string());
+    }
+
+    let key_matches = match private_keys.first() {
+        Some(key) => {
+            let cert_key = leaf.public_key()?.public_key_to_der()?;
+            let matches = key.public_key_to_der()? == cert_key;
+            if !matches {
+                issues.push("private key does not match the certificate".to_string());
+            }
+            Some(matches)
+        }
+        None => None,
+    };
+
+    Ok(ExportVerification {
+        path: path.display().to_string(),
+        fingerprint,
+        certificate_id: record.map(|record| record.id.clone()),
+        status: record.map(|record| record.status),
+        matches_inventory: record.is_some(),
+        chain_complete,
+        key_matches,
+        issues,
+    })
+}
+
+/// The file at `path`, or every regular file in the folder whose content
+/// starts like PEM, whatever its extension: templated exports may name
+/// files `.crt` or `.key`.
+fn pem_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(path)
+        .with_context(|| format!("failed to read export folder {}", path.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|file| file.is_file() && looks_like_pem(file))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Whether the file opens with a PEM header, allowing leading whitespace or
+/// explanatory text before it.
+fn looks_like_pem(file: &Path) -> bool {
+    let mut head = Vec::with_capacity(PEM_SNIFF_BYTES);
+    let read = fs::File::open(file)
+        .and_then(|handle| handle.take(PEM_SNIFF_BYTES as u64).read_to_end(&mut head));
+    read.is_ok() && String::from_utf8_lossy(&head).contains("-----BEGIN ")
+}
+
+/// PKCS#8 `ENCRYPTED PRIVATE KEY` blocks and legacy PEM encryption headers.
+fn is_encrypted_key(text: &str) -> bool {
+    text.contains("BEGIN ENCRYPTED PRIVATE KEY") || text.contains("Proc-Type: 4,ENCRYPTED")
+}
+
+/// Each certificate must be issued by the next one, and the chain must be at
+/// least as long as the one stored in the inventory.
+fn chain_is_complete(certificates: &[X509], record: Option<&CertificateRecord>) -> bool {
+    if certificates.len() < 2 {
+        return false;
+    }
+    let linked = certificates
+        .windows(2)
+        .all(|pair| pair[1].issued(&pair[0]) == X509VerifyResult::OK);
+    let expected_len = record
+        .and_then(|record| record.chain_pem.as_deref())
+        .and_then(|pem| X509::stack_from_pem(pem.as_bytes()).ok())
+        .map(|stack| stack.len())
+        .unwrap_or(0);
+    linked && certificates.len() >= expected_len
+}
+
+pub(crate) fn normalize_fingerprint(value: &str) -> String {
+    value.replace(':', "").to_ascii_lowercase()
+}
+
+/// Whether both certificates cover the same SANs, ignoring order and case.
+pub(crate) fn same_names(a: &CertificateRecord, b: &CertificateRecord) -> bool {
+    let mut left: Vec<String> = a.sans.iter().map(|name| name.to_ascii_lowercase()).collect();
+    let mut right: Vec<String> = b.sans.iter().map(|name| name.to_ascii_lowercase()).collect();
+    left.sort();
+    right.sort();
+    left == right
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distribution::key_policy::encrypt_private_key;
+    use rcgen::{BasicConstraints, CertificateParams, IsCa, KeyPair};
+    use uuid::Uuid;
+
+    struct Issued {
+        chain_pem: String,
+        key_pem: String,
+        fingerprint: String,
+    }
+
+    fn issue() -> Issued {
+        let mut ca_params = CertificateParams::new(vec!["ca.example.com".to_string()]).expect("ca params");
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let ca_key = KeyPair::generate().expect("ca key");
+        let ca = ca_params.self_signed(&ca_key).expect("ca cert");
+        let leaf_params = CertificateParams::new(vec!["example.com".to_string()]).expect("leaf params");
+        let leaf_key = KeyPair::generate().expect("leaf key");
+        let leaf = leaf_params.signed_by(&leaf_key, &ca, &ca_key).expect("leaf cert");
+        let fingerprint = hex::encode(
+            X509::from_der(leaf.der())
+                .expect("der")
+                .digest(MessageDigest::sha256())
+                .expect("digest"),
+        );
+        Issued {
+            chain_pem: format!("{}{}", leaf.pem(), ca.pem()),
+            key_pem: leaf_key.serialize_pem(),
+            fingerprint,
+        }
+    }
+
+    fn record(id: &str, issued: &Issued, status: CertificateStatus) -> CertificateRecord {
+        CertificateRecord {
+            fingerprint: issued.fingerprint.clone(),
+            chain_pem: Some(issued.chain_pem.clone()),
+            status,
+            ..CertificateRecord::test_record(id, &["example.com"])
+        }
+    }
+
+    #[test]
+    fn flags_superseded_exports_and_foreign_keys() {
+        let old = issue();
+        let new = issue();
+        let dir = std::env::temp_dir().join(format!("sslboard-verify-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("dir");
+        fs::write(dir.join("fullchain.pem"), &old.chain_pem).expect("write chain");
+        fs::write(dir.join("privkey.pem"), &new.key_pem).expect("write key");
+
+        let records = vec![
+            record("cert_old", &old, CertificateStatus::Superseded),
+            record("cert_new", &new, CertificateStatus::Active),
+        ];
+        let result = verify_export_path(&dir, &records).expect("verify");
+        assert_eq!(result.certificate_id.as_deref(), Some("cert_old"));
+        assert!(result.chain_complete);
+        assert_eq!(result.key_matches, Some(false));
+        assert!(result.issues.iter().any(|issue| issue.contains("cert_new")));
+
+        fs::write(dir.join("fullchain.pem"), &new.chain_pem).expect("write chain");
+        let result = verify_export_path(&dir, &records).expect("verify");
+        assert_eq!(result.key_matches, Some(true));
+        assert!(result.issues.is_empty());
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn finds_pem_files_whatever_their_extension() {
+        let issued = issue();
+        let dir = std::env::temp_dir().join(format!("sslboard-verify-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("dir");
+        fs::write(dir.join("example.com-fullchain.crt"), &issued.chain_pem).expect("write chain");
+        fs::write(dir.join("example.com-privkey.key"), &issued.key_pem).expect("write key");
+        fs::write(dir.join("README.txt"), "Exported by SSLBoard").expect("write readme");
+
+        let records = vec![record("cert", &issued, CertificateStatus::Active)];
+        let result = verify_export_path(&dir, &records).expect("verify");
+        assert_eq!(result.certificate_id.as_deref(), Some("cert"));
+        assert!(result.chain_complete);
+        assert_eq!(result.key_matches, Some(true));
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn leaves_encrypted_key_match_unknown() {
+        let issued = issue();
+        let dir = std::env::temp_dir().join(format!("sslboard-verify-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("dir");
+        fs::write(dir.join("fullchain.pem"), &issued.chain_pem).expect("write chain");
+        let encrypted = encrypt_private_key(&issued.key_pem, "correct horse").expect("encrypt");
+        fs::write(dir.join("privkey.pem"), encrypted.as_bytes()).expect("write key");
+
+        let records = vec![record("cert", &issued, CertificateStatus::Active)];
+        let result = verify_export_path(&dir, &records).expect("verify");
+        assert_eq!(result.certificate_id.as_deref(), Some("cert"));
+        assert_eq!(result.key_matches, None);
+        assert!(result.issues.is_empty());
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+    }
+}
@@ -1,15 +1,259 @@
-use anyhow::{Result, anyhow};
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+use anyhow::Result;
+
+use crate::core::messages::MessageCode;
+use crate::core::types::{
+    DomainForms, DomainIssue, DomainIssueKind, ParsedDomainList, RejectedDomainEntry,
+};
+
+const MAX_LABEL_LEN: usize = 63;
+const MAX_NAME_LEN: usize = 253;
+
+/// A malformed domain name: what is wrong, where, and a likely correction.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{}", .0.message)]
+pub struct DomainError(pub DomainIssue);
 
 pub fn normalize_domain_for_storage(input: &str) -> Result<String> {
     let trimmed = input.trim().trim_end_matches('.');
     if trimmed.is_empty() {
-        return Err(anyhow!("domain name is required"));
+        return Err(MessageCode::DomainRequired.into());
+    }
+    if let Some(issue) = syntax_issue(trimmed) {
+        return Err(DomainError(issue).into());
+    }
+    let ascii =
+        idna::domain_to_ascii(trimmed).map_err(|_| DomainError(punycode_issue(trimmed)))?;
+    if let Some(issue) = length_issue(trimmed, &ascii) {
+        return Err(DomainError(issue).into());
     }
-    let ascii = idna::domain_to_ascii(trimmed)
-        .map_err(|err| anyhow!("invalid domain name: {err}"))?;
     Ok(ascii.to_lowercase())
 }
 
+/// Validates one certificate name: [`normalize_domain_for_storage`] plus the
+/// wildcard rules. Returns the normalized name or what is wrong with it.
+pub fn check_certificate_name(input: &str) -> std::result::Result<String, DomainIssue> {
+    if let Some(issue) = wildcard_issue(input) {
+        return Err(issue);
+    }
+    normalize_domain_for_storage(input).map_err(|err| match err.downcast::<DomainError>() {
+        Ok(DomainError(issue)) => issue,
+        Err(err) => new_issue(input.trim(), DomainIssueKind::Empty, err.to_string(), None, None),
+    })
+}
+
+/// A wildcard may only be the whole leftmost label and needs at least two
+/// labels after it.
+pub fn wildcard_issue(input: &str) -> Option<DomainIssue> {
+    let name = input.trim().trim_end_matches('.');
+    let stars: Vec<usize> = name
+        .chars()
+        .enumerate()
+        .filter(|(_, c)| *c == '*')
+        .map(|(position, _)| position)
+        .collect();
+    let first = *stars.first()?;
+    let misplaced = stars
+        .iter()
+        .copied()
+        .find(|&position| position != 0)
+        .or_else(|| (!name.starts_with("*.")).then_some(first));
+    if let Some(position) = misplaced {
+        // Keep what follows the last wildcard, e.g. a.*.example.com -> *.example.com
+        let base = name
+            .rsplit_once('*')
+            .map(|(_, rest)| rest.trim_start_matches('.'))
+            .unwrap_or_default();
+        let suggestion = base.contains('.').then(|| format!("*.{base}"));
+        let message = "a wildcard must be the whole leftmost label, as in *.example.com";
+        return Some(new_issue(
+            name,
+            DomainIssueKind::Wildcard,
+            message.to_string(),
+            Some(position),
+            suggestion,
+        ));
+    }
+    (name.split('.').count() < 3).then(|| {
+        let message = "a wildcard needs at least two labels after it";
+        new_issue(name, DomainIssueKind::Wildcard, message.to_string(), Some(0), None)
+    })
+}
+
+/// nginx words that show up when a `server_name` or `listen` line is pasted.
+const CONFIG_WORDS: [&str; 4] = ["server_name", "listen", "server", "_"];
+
+/// Pulls certificate names out of freeform pasted text: lists separated by
+/// newlines, commas, semicolons or tabs, URLs with schemes, paths and ports,
+/// and nginx `server_name` lines. Names are normalized and deduplicated in
+/// the order pasted; anything unusable is returned with a reason.
+pub fn parse_domain_list(text: &str) -> ParsedDomainList {
+    let mut parsed = ParsedDomainList {
+        domains: Vec::new(),
+        rejected: Vec::new(),
+    };
+    let mut seen = HashSet::new();
+    let mut reject = |entry: &str, reason: String| {
+        parsed.rejected.push(RejectedDomainEntry {
+            entry: entry.to_string(),
+            reason,
+        })
+    };
+    let entries = text
+        .split(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '|'))
+        .map(|entry| entry.trim_matches(|c: char| matches!(c, '"' | '\'' | '`' | '{' | '}')))
+        .filter(|entry| !entry.is_empty() && !CONFIG_WORDS.contains(entry));
+    for entry in entries {
+        if entry.starts_with('~') {
+            reject(entry, "regular expression server names are not supported".to_string());
+            continue;
+        }
+        let host = host_of(entry);
+        let bare = host.trim_start_matches(['[']).trim_end_matches([']', '.']);
+        if bare.parse::<IpAddr>().is_ok() {
+            reject(entry, "IP addresses cannot be used in these certificates".to_string());
+            continue;
+        }
+        // nginx's .example.com matches the name and all its subdomains
+        let names = match host.strip_prefix('.') {
+            Some(base) => vec![base.to_string(), format!("*.{base}")],
+            None => vec![host.to_string()],
+        };
+        for name in names {
+            if !name.trim_start_matches("*.").contains('.') {
+                reject(entry, "not a fully qualified domain name".to_string());
+                break;
+            }
+            match check_certificate_name(&name) {
+                Ok(normalized) => {
+                    if seen.insert(normalized.clone()) {
+                        parsed.domains.push(normalized);
+                    }
+                }
+                Err(issue) => {
+                    reject(entry, issue.message);
+                    break;
+                }
+            }
+        }
+    }
+    parsed
+}
+
+/// The host part of a URL-ish entry: without scheme, user info, path and port.
+fn host_of(entry: &str) -> &str {
+    let mut host = entry.split_once("://").map_or(entry, |(_, rest)| rest);
+    host = host.split(['/', '?', '#']).next().unwrap_or_default();
+    host = host.rsplit_once('@').map_or(host, |(_, rest)| rest);
+    if let Some((name, port)) = host.rsplit_once(':')
+        && !port.is_empty()
+        && port.chars().all(|c| c.is_ascii_digit())
+        && !name.ends_with(':')
+    {
+        host = name;
+    }
+    host
+}
+
+fn new_issue(
+    input: &str,
+    kind: DomainIssueKind,
+    message: String,
+    position: Option<usize>,
+    suggestion: Option<String>,
+) -> DomainIssue {
+    DomainIssue {
+        index: None,
+        input: input.to_string(),
+        kind,
+        message,
+        position,
+        suggestion,
+    }
+}
+
+/// Characters that can never appear in a name. Other non-ASCII characters
+/// are left to IDNA.
+fn is_name_char(c: char) -> bool {
+    if c.is_ascii() {
+        c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '*')
+    } else {
+        !c.is_whitespace() && !c.is_control()
+    }
+}
+
+fn syntax_issue(name: &str) -> Option<DomainIssue> {
+    let mut previous = None;
+    for (position, c) in name.chars().enumerate() {
+        if !is_name_char(c) {
+            let message = format!("invalid character {c:?} at position {}", position + 1);
+            let kind = DomainIssueKind::InvalidCharacter;
+            return Some(new_issue(name, kind, message, Some(position), suggest(name)));
+        }
+        if c == '.' && matches!(previous, None | Some('.')) {
+            let message = format!("empty label at position {}", position + 1);
+            let kind = DomainIssueKind::EmptyLabel;
+            return Some(new_issue(name, kind, message, Some(position), suggest(name)));
+        }
+        previous = Some(c);
+    }
+    None
+}
+
+/// Points at the first label IDNA rejects.
+fn punycode_issue(name: &str) -> DomainIssue {
+    let mut position = 0;
+    for label in name.split('.') {
+        if idna::domain_to_ascii(label).is_err() {
+            let message = if label.to_ascii_lowercase().starts_with("xn--") {
+                format!("\"{label}\" is not valid punycode")
+            } else {
+                format!("\"{label}\" cannot be converted to punycode")
+            };
+            let kind = DomainIssueKind::Punycode;
+            return new_issue(name, kind, message, Some(position), suggest(name));
+        }
+        position += label.chars().count() + 1;
+    }
+    let message = "cannot be converted to punycode".to_string();
+    new_issue(name, DomainIssueKind::Punycode, message, None, None)
+}
+
+fn length_issue(name: &str, ascii: &str) -> Option<DomainIssue> {
+    let mut position = 0;
+    for (label, ascii_label) in name.split('.').zip(ascii.split('.')) {
+        if ascii_label.len() > MAX_LABEL_LEN {
+            let message = format!("label \"{label}\" is longer than {MAX_LABEL_LEN} characters");
+            let kind = DomainIssueKind::LabelTooLong;
+            return Some(new_issue(name, kind, message, Some(position), None));
+        }
+        position += label.chars().count() + 1;
+    }
+    (ascii.len() > MAX_NAME_LEN).then(|| {
+        let message = format!("name is longer than {MAX_NAME_LEN} characters");
+        new_issue(name, DomainIssueKind::NameTooLong, message, None, None)
+    })
+}
+
+/// The host in a pasted URL or address, without invalid characters and
+/// repeated dots, when that is a valid name different from `input`.
+fn suggest(input: &str) -> Option<String> {
+    let cleaned: String = host_of(input)
+        .chars().filter(|&c| is_name_char(c)).collect();
+    let cleaned = cleaned
+        .split('.')
+        .filter(|label| !label.is_empty())
+        .collect::<Vec<_>>()
+        .join(".")
+        .to_lowercase();
+    if cleaned.is_empty() || cleaned == input {
+        return None;
+    }
+    normalize_domain_for_storage(&cleaned).ok().map(|_| cleaned)
+}
+
 pub fn normalize_domain_suffix_for_storage(raw: &str) -> Result<String> {
     let stripped = raw
         .trim()
@@ -33,8 +277,114 @@ pub fn normalize_domains_for_display(domains: &[String]) -> Vec<String> {
         .collect()
 }
 
+/// Both forms of a name in either form. Labels that cannot be converted
+/// are kept as given.
+pub fn domain_forms(input: &str) -> DomainForms {
+    let trimmed = input.trim().trim_end_matches('.');
+    let ascii = idna::domain_to_ascii(trimmed).unwrap_or_else(|_| trimmed.to_string());
+    DomainForms {
+        ascii: ascii.to_lowercase(),
+        unicode: normalize_unicode_domain(trimmed),
+    }
+}
+
+/// [`domain_forms`] of each name, without duplicates, in order.
+pub fn domain_forms_of<'a>(domains: impl IntoIterator<Item = &'a String>) -> Vec<DomainForms> {
+    let mut forms: Vec<DomainForms> = Vec::new();
+    for domain in domains {
+        let form = domain_forms(domain);
+        if !forms.contains(&form) {
+            forms.push(form);
+        }
+    }
+    forms
+}
+
 fn normalize_unicode_domain(input: &str) -> String {
     let trimmed = input.trim().trim_end_matches('.');
     let (unicode, _) = idna::domain_to_unicode(trimmed);
     unicode.to_lowercase()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(input: &str) -> DomainIssue {
+        check_certificate_name(input).unwrap_err()
+    }
+
+    #[test]
+    fn malformed_names_explain_what_is_wrong() {
+        assert_eq!(check_certificate_name("Bücher.Example").unwrap(), "xn--bcher-kva.example");
+        assert_eq!(check_certificate_name("*.example.com").unwrap(), "*.example.com");
+
+        let pasted = issue("https://www.example.com/path");
+        assert_eq!(pasted.kind, DomainIssueKind::InvalidCharacter);
+        assert_eq!(pasted.position, Some(5));
+        assert_eq!(pasted.suggestion.as_deref(), Some("www.example.com"));
+        assert_eq!(issue("exa mple.com").suggestion.as_deref(), Some("example.com"));
+        assert_eq!(issue("example.com:8443").suggestion.as_deref(), Some("example.com"));
+
+        let empty = issue("www..example.com");
+        assert_eq!((empty.kind, empty.position), (DomainIssueKind::EmptyLabel, Some(4)));
+        assert_eq!(empty.suggestion.as_deref(), Some("www.example.com"));
+
+        let punycode = issue("www.xn--a.example");
+        assert_eq!((punycode.kind, punycode.position), (DomainIssueKind::Punycode, Some(4)));
+        let long_label = format!("{}.example.com", "a".repeat(64));
+        assert_eq!(issue(&long_label).kind, DomainIssueKind::LabelTooLong);
+
+        let nested = issue("a.*.example.com");
+        assert_eq!((nested.kind, nested.position), (DomainIssueKind::Wildcard, Some(2)));
+        assert_eq!(nested.suggestion.as_deref(), Some("*.example.com"));
+        assert_eq!(issue("*example.com").suggestion.as_deref(), Some("*.example.com"));
+        assert_eq!(issue("*.com").kind, DomainIssueKind::Wildcard);
+    }
+
+    #[test]
+    fn pasted_text_yields_unique_names_and_reasons() {
+        let text = "Name\tNotes\nhttps://Shop.Example.com:8443/cart?id=1\twww\n\
+                    server_name example.com www.example.com .example.org;\n\
+                    listen 443 ssl;\n\
+                    \"shop.example.com\", 10.0.0.1, ~^api\\d+\\.example\\.com$, a..b.com";
+        let parsed = parse_domain_list(text);
+        assert_eq!(
+            parsed.domains,
+            vec![
+                "shop.example.com",
+                "example.com",
+                "www.example.com",
+                "example.org",
+                "*.example.org"
+            ]
+        );
+        let rejected: Vec<&str> = parsed.rejected.iter().map(|r| r.entry.as_str()).collect();
+        let regex = "~^api\\d+\\.example\\.com$";
+        assert_eq!(
+            rejected,
+            vec!["Name", "Notes", "www", "443", "ssl", "10.0.0.1", regex, "a..b.com"]
+        );
+        assert!(parsed.rejected[5].reason.contains("IP addresses"));
+        assert!(parsed.rejected[7].reason.contains("empty label"));
+    }
+
+    #[test]
+    fn domain_forms_pair_ascii_and_unicode() {
+        let expected = DomainForms {
+            ascii: "*.xn--bcher-kva.example".to_string(),
+            unicode: "*.bücher.example".to_string(),
+        };
+        assert_eq!(domain_forms("*.Bücher.example."), expected);
+        assert_eq!(domain_forms("*.xn--bcher-kva.example"), expected);
+
+        let names = vec![
+            "xn--bcher-kva.example".to_string(),
+            "bücher.example".to_string(),
+            "example.com".to_string(),
+        ];
+        let forms = domain_forms_of(&names);
+        assert_eq!(forms.len(), 2);
+        assert_eq!(forms[1].ascii, forms[1].unicode);
+    }
+}
@@ -1,6 +1,18 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow};
+
+use crate::secrets::{manager::SecretManager, types::SecretKind};
 
 pub fn generate_account_key_pem() -> Result<String> {
     let key = rcgen::KeyPair::generate()?;
     Ok(key.serialize_pem())
 }
+
+/// Generates an ACME account key and stores it as a secret, returning its reference.
+pub fn create_account_key(secrets: &SecretManager) -> Result<String> {
+    let pem = generate_account_key_pem()
+        .map_err(|err| anyhow!("failed to generate ACME account key: {err}"))?;
+    let record = secrets
+        .create_secret(SecretKind::AcmeAccountKey, "ACME account key".into(), pem)
+        .map_err(|err| anyhow!(err.to_string()))?;
+    Ok(record.id)
+}
@@ -1,41 +1,33 @@
-use std::time::Duration;
-
 use acme_lib::{
-    Certificate, Directory, DirectoryUrl, create_p256_key, create_p384_key,
-    create_rsa_key,
-    order::{Auth, NewOrder},
+    Certificate, Directory, DirectoryUrl,
+    order::{Auth, Challenge, CsrOrder, NewOrder},
 };
 use anyhow::{Result, anyhow};
+use zeroize::Zeroizing;
 
 use crate::{
-    core::types::{KeyAlgorithm, KeyCurve},
-    domain::normalize_domain_for_storage,
-    issuance::dns::{record_name, DnsAdapter, DnsChallengeRequest, DnsRecordInstruction, ManualDnsAdapter, PropagationState},
-    issuance::dns_providers::adapter_for_provider,
-    secrets::manager::SecretManager,
-    storage::dns::DnsConfigStore,
+    core::errors::InvalidDomainsError,
+    core::types::{DomainIssue, FinalizationStage, KeyAlgorithm, KeyCurve, PropagationWait},
+    domain::check_certificate_name,
+    issuance::dns::{DnsRecordInstruction, PropagationState, derive_zone, record_name},
+    issuance::propagation_presets,
+    issuance::propagation_resolvers::ResolverSet,
 };
 
 use super::flow::EphemeralPersist;
+use super::order_polling::OrderPoller;
 
 /// Validates and normalizes domain names for certificate issuance.
-/// Returns normalized domains or an error if validation fails.
+/// Returns normalized domains, or an [`InvalidDomainsError`] listing every
+/// malformed name.
 pub fn validate_and_normalize_domains(domains: Vec<String>) -> Result<Vec<String>> {
     if domains.is_empty() {
         return Err(anyhow!("At least one domain is required"));
     }
 
-    let mut normalized: Vec<String> = Vec::new();
-    for domain in domains {
-        let trimmed = domain.trim();
-        if trimmed.is_empty() {
-            continue;
-        }
-        let ascii = normalize_domain_for_storage(trimmed)
-            .map_err(|err| anyhow!("Invalid domain name \"{trimmed}\": {err}"))?;
-        if !ascii.is_empty() {
-            normalized.push(ascii);
-        }
+    let (mut normalized, issues) = check_domain_list(&domains);
+    if !issues.is_empty() {
+        return Err(InvalidDomainsError::new(issues).into());
     }
 
     normalized.sort();
@@ -48,6 +40,27 @@ pub fn validate_and_normalize_domains(domains: Vec<String>) -> Result<Vec<String
     Ok(normalized)
 }
 
+/// Normalizes every non-empty name of a submitted list, collecting the
+/// issues of the malformed ones with their list positions.
+pub fn check_domain_list(domains: &[String]) -> (Vec<String>, Vec<DomainIssue>) {
+    let mut normalized = Vec::new();
+    let mut issues = Vec::new();
+    for (index, domain) in domains.iter().enumerate() {
+        let trimmed = domain.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        match check_certificate_name(trimmed) {
+            Ok(ascii) => normalized.push(ascii),
+            Err(mut issue) => {
+                issue.index = Some(index);
+                issues.push(issue);
+            }
+        }
+    }
+    (normalized, issues)
+}
+
 /// Validates key algorithm and size/curve parameters.
 /// Returns the resolved parameters or an error if invalid.
 pub fn resolve_key_params(
@@ -88,32 +101,6 @@ pub fn resolve_key_params(
     }
 }
 
-/// Generates a private key based on the specified algorithm and parameters.
-pub fn generate_private_key(
-    key_algorithm: &KeyAlgorithm,
-    key_size: Option<u16>,
-    key_curve: Option<&KeyCurve>,
-) -> Result<String> {
-    let key = match key_algorithm {
-        KeyAlgorithm::Rsa => {
-            let size = key_size.unwrap_or(2048);
-            create_rsa_key(u32::from(size))
-        }
-        KeyAlgorithm::Ecdsa => match key_curve {
-            Some(KeyCurve::P256) => create_p256_key(),
-            Some(KeyCurve::P384) => create_p384_key(),
-            None => return Err(anyhow!("ECDSA key_curve is required")),
-        },
-    };
-
-    let key_pem = key
-        .private_key_to_pem_pkcs8()
-        .map_err(|e| anyhow!("failed to serialize private key: {e}"))?;
-
-    String::from_utf8(key_pem)
-        .map_err(|_| anyhow!("managed key PEM contained invalid UTF-8"))
-}
-
 /// Creates an ACME directory connection and account.
 /// Returns the directory and account objects.
 pub fn setup_acme_account(
@@ -155,164 +142,133 @@ pub fn create_acme_order(
         .map_err(|e: acme_lib::Error| anyhow!(e.to_string()))
 }
 
-/// Prepares DNS challenge records for the ACME order.
-/// Returns DNS record instructions, authorizations, and records to cleanup.
-#[allow(clippy::type_complexity)]
-pub fn prepare_dns_challenges(
-    order: &NewOrder<EphemeralPersist>,
-    dns_store: &DnsConfigStore,
-    secrets: &SecretManager,
-) -> Result<(Vec<DnsRecordInstruction>, Vec<Auth<EphemeralPersist>>, Vec<(String, String)>)> {
-    let auths: Vec<Auth<EphemeralPersist>> = order
-        .authorizations()
-        .map_err(|e: acme_lib::Error| anyhow!(e.to_string()))?;
-
-    let mut dns_records = Vec::new();
-    let mut dns_records_to_cleanup = Vec::new();
-    let adapter = ManualDnsAdapter::new();
-
-    for auth in &auths {
-        let dns = auth.dns_challenge();
-        let proof = dns.dns_proof();
-        let domain = auth.domain_name().to_string();
-
-        let resolution = dns_store.resolve_provider_for_domain(&domain)?;
-        let zone_override = resolution
-            .provider
-            .as_ref()
-            .and_then(provider_zone_override);
-
-        let request = DnsChallengeRequest {
-            domain: domain.clone(),
-            value: proof.clone(),
-            zone: zone_override,
-        };
-
-        let mut record = adapter.present_txt(&request)?;
-
-        if let Some(provider) = resolution.provider.as_ref()
-            && resolution.ambiguous.len() <= 1 {
-            let provider_adapter = adapter_for_provider(provider, secrets);
-            provider_adapter.create_txt(&record.record_name, &record.value)?;
-            record.adapter = provider.provider_type.clone();
-            // Store for cleanup after successful issuance
-            dns_records_to_cleanup.push((domain.clone(), record.record_name.clone()));
-        }
-
-        dns_records.push(record);
+/// Asks the CA to validate `challenge`, unless an earlier attempt already did.
+pub fn validate_challenge<A: Send + 'static>(
+    challenge: Challenge<EphemeralPersist, A>,
+    poller: &OrderPoller,
+) -> Result<()> {
+    if !challenge.need_validate() {
+        return Ok(());
     }
-
-    Ok((dns_records, auths, dns_records_to_cleanup))
+    let delay_millis = poller.settings().interval.as_millis() as u64;
+    poller.run_blocking(FinalizationStage::ValidatingChallenges, true, move || {
+        challenge.validate(delay_millis).map_err(|e| anyhow!(e.to_string()))
+    })
 }
 
-/// Validates DNS propagation for all ACME challenges.
-/// Returns successfully if all challenges are validated.
-pub fn validate_acme_challenges(
-    auths: &[Auth<EphemeralPersist>],
-) -> Result<()> {
-    for auth in auths {
-        let dns = auth.dns_challenge();
-        dns.validate(2000)
-            .map_err(|e| anyhow!(e.to_string()))?;
-    }
-    Ok(())
+/// Waits, backing off between refreshes, until the CA accepts a CSR for the order.
+pub fn await_order_ready(
+    order: &mut NewOrder<EphemeralPersist>,
+    poller: &OrderPoller,
+) -> Result<CsrOrder<EphemeralPersist>> {
+    poller.poll(FinalizationStage::AwaitingOrder, || {
+        if let Some(csr_order) = order.confirm_validations() {
+            return Ok(Some(csr_order));
+        }
+        order.refresh().map_err(|e| anyhow!(e.to_string()))?;
+        if order.api_order().is_status_invalid() {
+            let detail = order
+                .api_order()
+                .error
+                .as_ref()
+                .and_then(|problem| problem.detail.clone())
+                .unwrap_or_else(|| "no details given".to_string());
+            return Err(anyhow!("The CA marked the order invalid: {detail}"));
+        }
+        Ok(None)
+    })
 }
 
-/// Finalizes the ACME certificate order.
-/// Returns the certificate object.
+/// Submits the CSR and downloads the certificate.
+/// Returns the certificate and the URL it was downloaded from.
 pub fn finalize_acme_certificate(
-    mut order: NewOrder<EphemeralPersist>,
-    private_key_pem: &str,
-) -> Result<Certificate> {
-    let csr_order = loop {
-        if let Some(csr) = order.confirm_validations() {
-            break csr;
-        }
-        order.refresh()
+    csr_order: CsrOrder<EphemeralPersist>,
+    private_key_pem: Zeroizing<String>,
+    poller: &OrderPoller,
+) -> Result<(Certificate, Option<String>)> {
+    let delay_millis = poller.settings().interval.as_millis() as u64;
+    // The CSR order is consumed by finalize, so a timeout here can't be resumed.
+    poller.run_blocking(FinalizationStage::Finalizing, false, move || {
+        let cert_order = csr_order
+            .finalize(&private_key_pem, delay_millis)
             .map_err(|e| anyhow!(e.to_string()))?;
-    };
-
-    let cert_order = csr_order
-        .finalize(private_key_pem, 5000)
-        .map_err(|e| anyhow!(e.to_string()))?;
-
-    cert_order
-        .download_and_save_cert()
-        .map_err(|e| anyhow!(e.to_string()))
+        let certificate_url = cert_order.api_order().certificate.clone();
+        let certificate = cert_order
+            .download_and_save_cert()
+            .map_err(|e| anyhow!(e.to_string()))?;
+        Ok((certificate, certificate_url))
+    })
 }
 
-/// Checks DNS propagation for all challenge records.
-/// Returns successfully if all records are propagated.
+/// Checks DNS propagation for the challenge record of `auth` through
+/// `resolvers`, waiting as long as `wait` says. `presented` is the record
+/// the solver created or showed, whose name and zone account for provider
+/// zone overrides and CNAME delegation; without it the default challenge
+/// name is checked.
+/// Returns successfully once the record is propagated.
 pub fn check_dns_propagation(
-    auths: &[Auth<EphemeralPersist>],
+    auth: &Auth<EphemeralPersist>,
+    presented: Option<&DnsRecordInstruction>,
+    wait: PropagationWait,
+    resolvers: &ResolverSet,
 ) -> Result<()> {
-    for auth in auths {
-        let dns = auth.dns_challenge();
-        let proof = dns.dns_proof();
-        let domain = auth.domain_name().to_string();
-
-        // Poll for DNS propagation with retries
-        let timeout = Duration::from_secs(30);
-        let interval = Duration::from_secs(2);
-        let record_name = record_name(&domain);
-
-        let propagation_result = super::dns_providers::poll_dns_propagation(&record_name, &proof, timeout, interval)?;
+    let dns = auth.dns_challenge();
+    let proof = dns.dns_proof();
+    let domain = auth.domain_name().to_string();
+    let (record_name, zone) = match presented {
+        Some(record) => (record.record_name.clone(), record.zone.clone()),
+        None => (record_name(&domain), derive_zone(&domain)),
+    };
 
-        // Check final state after polling
-        match propagation_result.state {
-            PropagationState::Found => {
-                // Already handled in loop, continue to next domain
-            }
-            PropagationState::NxDomain => {
-                return Err(anyhow!(
-                    "No TXT record found at {} after {}s. Please ensure the DNS record is created and propagated.",
-                    record_name,
-                    timeout.as_secs()
-                ));
-            }
-            super::dns::PropagationState::Pending => {
-                return Err(anyhow!(
-                    "TXT record not found at {} after {}s. Please wait for DNS propagation and try again.",
-                    record_name,
-                    timeout.as_secs()
-                ));
-            }
-            super::dns::PropagationState::WrongContent => {
-                return Err(anyhow!(
-                    "TXT record at {} has wrong value. Expected: {}. Observed: {:?}",
-                    record_name,
-                    proof,
-                    propagation_result.observed_values
-                ));
-            }
-            PropagationState::Error => {
-                return Err(anyhow!(
-                    "Failed to check DNS propagation for {}: {}",
-                    record_name,
-                    propagation_result
-                        .reason
-                        .unwrap_or_else(|| "Unknown error".to_string())
-                ));
-            }
+    // Poll for DNS propagation with retries
+    let timeout = propagation_presets::timeout(wait);
+    let interval = propagation_presets::interval(wait);
+
+    let propagation_result = super::dns_providers::poll_dns_propagation(
+        &record_name,
+        &proof,
+        timeout,
+        interval,
+        resolvers,
+    )?;
+
+    // Check final state after polling
+    match propagation_result.state {
+        PropagationState::Found => {}
+        PropagationState::NxDomain => {
+            return Err(anyhow!(
+                "No TXT record found at {} (zone {}) after {}s. Please ensure the DNS record is created and propagated.",
+                record_name,
+                zone,
+                timeout.as_secs()
+            ));
+        }
+        super::dns::PropagationState::Pending => {
+            return Err(anyhow!(
+                "TXT record not found at {} (zone {}) after {}s. Please wait for DNS propagation and try again.",
+                record_name,
+                zone,
+                timeout.as_secs()
+            ));
+        }
+        super::dns::PropagationState::WrongContent => {
+            return Err(anyhow!(
+                "TXT record at {} has wrong value. Expected: {}. Observed: {:?}",
+                record_name,
+                proof,
+                propagation_result.observed_values
+            ));
+        }
+        PropagationState::Error => {
+            return Err(anyhow!(
+                "Failed to check DNS propagation for {}: {}",
+                record_name,
+                propagation_result
+                    .reason
+                    .unwrap_or_else(|| "Unknown error".to_string())
+            ));
         }
     }
 
     Ok(())
 }
-
-fn provider_zone_override(provider: &crate::storage::dns::DnsProvider) -> Option<String> {
-    let raw = provider.config_json.as_ref()?;
-    match serde_json::from_str::<serde_json::Value>(raw) {
-        Ok(value) => value
-            .get("zone")
-            .and_then(|zone| zone.as_str().map(|s| s.to_string())),
-        Err(err) => {
-            log::warn!(
-                "[dns] invalid provider config_json for {}: {}",
-                provider.id,
-                err
-            );
-            None
-        }
-    }
-}
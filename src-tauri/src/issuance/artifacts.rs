@@ -0,0 +1,166 @@
+//! Record of issuance attempts for troubleshooting.
+//!
+//! The ACME order and authorization objects, challenge records and errors of
+//! each attempt are kept in memory so a failed issuance can be exported as a
+//! single file by `export_issuance_artifacts` and shared when asking for
+//! help. Attempts that end with a certificate are dropped; the last
+//! [`MAX_ATTEMPTS`] others are kept until the app exits. Error messages are
+//! redacted before they are stored.
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+
+use acme_lib::api::ApiOrder;
+use acme_lib::order::Auth;
+use acme_lib::persist::Persist;
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::core::types::{
+    FinalizationStage, IssuanceArtifactError, IssuanceArtifactSummary, IssuanceArtifacts,
+};
+use crate::issuance::dns::DnsRecordInstruction;
+use crate::issuance::dns_providers::redact_text;
+
+const MAX_ATTEMPTS: usize = 20;
+
+static ATTEMPTS: OnceLock<Mutex<VecDeque<IssuanceArtifacts>>> = OnceLock::new();
+
+fn attempts() -> &'static Mutex<VecDeque<IssuanceArtifacts>> {
+    ATTEMPTS.get_or_init(|| Mutex::new(VecDeque::new()))
+}
+
+fn update(request_id: &str, apply: impl FnOnce(&mut IssuanceArtifacts)) {
+    match attempts().lock() {
+        Ok(mut attempts) => {
+            if let Some(attempt) = attempts.iter_mut().find(|a| a.request_id == request_id) {
+                apply(attempt);
+            }
+        }
+        Err(err) => log::warn!("[issuance] attempt record unavailable: {err}"),
+    }
+}
+
+fn to_value(object: &impl Serialize) -> Option<serde_json::Value> {
+    serde_json::to_value(object)
+        .inspect_err(|err| log::warn!("[issuance] failed to record ACME object: {err}"))
+        .ok()
+}
+
+/// Starts the record of an attempt, dropping the oldest one when full.
+pub fn begin(request_id: &str, domains: &[String], issuer_id: &str, directory_url: &str) {
+    let attempt = IssuanceArtifacts {
+        request_id: request_id.to_string(),
+        domains: domains.to_vec(),
+        issuer_id: issuer_id.to_string(),
+        directory_url: directory_url.to_string(),
+        started_at: Utc::now(),
+        order: None,
+        authorizations: Vec::new(),
+        challenge_records: Vec::new(),
+        errors: Vec::new(),
+    };
+    match attempts().lock() {
+        Ok(mut attempts) => {
+            if attempts.len() == MAX_ATTEMPTS {
+                attempts.pop_front();
+            }
+            attempts.push_back(attempt);
+        }
+        Err(err) => log::warn!("[issuance] attempt record unavailable: {err}"),
+    }
+}
+
+pub fn record_order(request_id: &str, order: &ApiOrder) {
+    let order = to_value(order);
+    update(request_id, |attempt| attempt.order = order);
+}
+
+pub fn record_authorizations<P: Persist>(request_id: &str, auths: &[Auth<P>]) {
+    let authorizations = auths.iter().filter_map(|auth| to_value(auth.api_auth())).collect();
+    update(request_id, |attempt| attempt.authorizations = authorizations);
+}
+
+pub fn record_challenge_records(request_id: &str, records: &[DnsRecordInstruction]) {
+    update(request_id, |attempt| attempt.challenge_records = records.to_vec());
+}
+
+pub fn record_error(request_id: &str, step: &str, err: &anyhow::Error) {
+    let error = IssuanceArtifactError {
+        at: Utc::now(),
+        step: step.to_string(),
+        message: redact_text(&format!("{err:#}")),
+    };
+    update(request_id, |attempt| attempt.errors.push(error));
+}
+
+/// Records an error raised while finalizing, named after its stage.
+pub fn record_stage_error(request_id: &str, stage: FinalizationStage, err: &anyhow::Error) {
+    let step = to_value(&stage)
+        .and_then(|value| value.as_str().map(|name| name.replace('_', " ")))
+        .unwrap_or_else(|| format!("{stage:?}"));
+    record_error(request_id, &step, err);
+}
+
+/// Forgets an attempt that ended with a certificate.
+pub fn discard(request_id: &str) {
+    if let Ok(mut attempts) = attempts().lock() {
+        attempts.retain(|attempt| attempt.request_id != request_id);
+    }
+}
+
+pub fn get(request_id: &str) -> Option<IssuanceArtifacts> {
+    attempts()
+        .lock()
+        .ok()?
+        .iter()
+        .find(|attempt| attempt.request_id == request_id)
+        .cloned()
+}
+
+/// Recorded attempts, newest first.
+pub fn summaries() -> Vec<IssuanceArtifactSummary> {
+    match attempts().lock() {
+        Ok(attempts) => attempts
+            .iter()
+            .rev()
+            .map(|attempt| IssuanceArtifactSummary {
+                request_id: attempt.request_id.clone(),
+                domains: attempt.domains.clone(),
+                started_at: attempt.started_at,
+                last_error: attempt.errors.last().map(|error| error.message.clone()),
+            })
+            .collect(),
+        Err(err) => {
+            log::warn!("[issuance] attempt record unavailable: {err}");
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[test]
+    fn keeps_failed_attempts_with_redacted_errors() {
+        let domains = vec!["artifacts.example.com".to_string()];
+        begin("artifacts-failed", &domains, "iss_1", "https://acme.example/directory");
+        begin("artifacts-issued", &domains, "iss_1", "https://acme.example/directory");
+        let err = anyhow!("HTTP 403 for /zones?api_token=abc123").context("writing records");
+        record_stage_error("artifacts-failed", FinalizationStage::CheckingDns, &err);
+        discard("artifacts-issued");
+
+        let failed = get("artifacts-failed").unwrap();
+        assert_eq!(failed.errors.len(), 1);
+        assert_eq!(failed.errors[0].step, "checking dns");
+        assert_eq!(
+            failed.errors[0].message,
+            "writing records: HTTP 403 for /zones?api_token=[redacted]"
+        );
+        assert!(get("artifacts-issued").is_none());
+        assert!(summaries().iter().any(|summary| summary.request_id == "artifacts-failed"));
+    }
+}
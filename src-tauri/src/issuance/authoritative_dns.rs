@@ -0,0 +1,357 @@
+//! Propagation checks against a zone's authoritative nameservers.
+//!
+//! Public resolvers cache NXDOMAIN for the SOA's negative TTL, so a record
+//! queried before it was written can look missing for minutes after the
+//! provider published it. Asking every authoritative server directly shows
+//! when the zone itself serves the value, which is what the CA's own
+//! lookups end up seeing once caches expire.
+//!
+//! Zones with secondaries (PowerDNS, RFC 2136 setups) can serve the record
+//! from the primary while a secondary still has an older copy. Comparing
+//! SOA serials shows when every nameserver has loaded the zone version
+//! holding the change.
+
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use log::info;
+
+use crate::core::messages::MessageCode;
+use crate::core::types::{AuthoritativePropagationResult, NameserverCheck, SerialSync};
+use crate::issuance::dns::{DnsPropagationResult, PropagationState, follow_cname};
+use crate::issuance::dns_providers::lookup_nameservers;
+use crate::issuance::dns_wire::{self, DEFAULT_PORT, RCODE_NXDOMAIN, TYPE_SOA, TYPE_TXT};
+
+/// Per-query timeout for each transport.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Queries every nameserver of the zone holding `record_name` for its TXT
+/// values, and with `compare_serials` for the zone's SOA serial. A CNAME at
+/// the name is followed first, so delegated challenge records are checked
+/// in the zone that actually serves them.
+pub fn check_authoritative(
+    record_name: &str,
+    expected_value: &str,
+    compare_serials: bool,
+) -> Result<AuthoritativePropagationResult> {
+    let record_name = record_name.trim().trim_end_matches('.').to_ascii_lowercase();
+    let target = follow_cname(&record_name)?.unwrap_or(record_name);
+    let (zone, hosts) = lookup_nameservers(&target)?
+        .ok_or_else(|| anyhow!("No nameservers found for {target}"))?;
+    info!(
+        "[dns] Checking TXT {target} on {} authoritative nameserver(s) of {zone}",
+        hosts.len()
+    );
+
+    let nameservers = thread::scope(|scope| {
+        let handles: Vec<_> = hosts
+            .iter()
+            .map(|host| {
+                let (target, zone) = (&target, &zone);
+                scope.spawn(move || {
+                    let mut check = check_nameserver(host, target, expected_value);
+                    if compare_serials && let Some(address) = check.address.as_deref() {
+                        check.soa_serial = query_serial(address, zone)
+                            .inspect_err(|err| info!("[dns] SOA of {zone} on {host}: {err}"))
+                            .ok();
+                    }
+                    check
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .zip(&hosts)
+            .map(|(handle, host)| {
+                handle.join().unwrap_or_else(|_| NameserverCheck {
+                    nameserver: host.clone(),
+                    address: None,
+                    transport: None,
+                    result: error_result("nameserver check panicked".to_string()),
+                    soa_serial: None,
+                })
+            })
+            .collect::<Vec<_>>()
+    });
+    let all_found = !nameservers.is_empty()
+        && nameservers
+            .iter()
+            .all(|check| check.result.state == PropagationState::Found);
+
+    let serial_sync = compare_serials.then(|| serial_sync(&nameservers));
+
+    Ok(AuthoritativePropagationResult {
+        record_name: target,
+        zone,
+        nameservers,
+        all_found,
+        serial_sync,
+    })
+}
+
+/// Compares serials against the oldest one known to hold the record, so a
+/// primary that has moved on to a later change does not hold the check up.
+fn serial_sync(nameservers: &[NameserverCheck]) -> SerialSync {
+    let target_serial = nameservers
+        .iter()
+        .filter(|check| check.result.state == PropagationState::Found)
+        .filter_map(|check| check.soa_serial)
+        .reduce(|oldest, serial| if serial_at_least(serial, oldest) { oldest } else { serial });
+    let lagging: Vec<String> = nameservers
+        .iter()
+        .filter(|check| match (check.soa_serial, target_serial) {
+            (Some(serial), Some(target)) => !serial_at_least(serial, target),
+            _ => true,
+        })
+        .map(|check| check.nameserver.clone())
+        .collect();
+    SerialSync {
+        target_serial,
+        in_sync: !nameservers.is_empty() && lagging.is_empty(),
+        lagging,
+    }
+}
+
+/// `serial >= other` in RFC 1982 serial number arithmetic, which wraps.
+fn serial_at_least(serial: u32, other: u32) -> bool {
+    serial.wrapping_sub(other) < 1 << 31
+}
+
+fn query_serial(address: &str, zone: &str) -> Result<u32> {
+    let address = SocketAddr::new(address.parse()?, DEFAULT_PORT);
+    let message = dns_wire::query(zone, TYPE_SOA)?;
+    let (response, _) = dns_wire::resolve(address, message, QUERY_TIMEOUT)?;
+    let soa = response
+        .answers
+        .iter()
+        .find(|record| record.rtype == TYPE_SOA)
+        .ok_or_else(|| anyhow!("no SOA record in the answer"))?;
+    dns_wire::soa_serial(&soa.rdata)
+}
+
+impl AuthoritativePropagationResult {
+    /// Folds the per-nameserver results into one: found only when every
+    /// nameserver serves the value, otherwise the first one that does not.
+    pub fn summary(&self) -> DnsPropagationResult {
+        if self.all_found {
+            let observed = self.nameservers[0].result.observed_values.clone();
+            if let Some(sync) = self.serial_sync.as_ref().filter(|sync| !sync.in_sync) {
+                let mut result =
+                    DnsPropagationResult::new(PropagationState::Pending, None, observed);
+                result.reason = Some(format!(
+                    "Waiting for {} to load the zone serial with the record",
+                    sync.lagging.join(", ")
+                ));
+                return result;
+            }
+            return DnsPropagationResult::new(PropagationState::Found, None, observed);
+        }
+        self.nameservers
+            .iter()
+            .find(|check| check.result.state != PropagationState::Found)
+            .map(|check| check.result.clone())
+            .unwrap_or_else(|| {
+                DnsPropagationResult::new(
+                    PropagationState::Error,
+                    Some(MessageCode::PropagationNoResponses),
+                    Vec::new(),
+                )
+            })
+    }
+}
+
+fn check_nameserver(host: &str, record_name: &str, expected_value: &str) -> NameserverCheck {
+    let mut check = NameserverCheck {
+        nameserver: host.to_string(),
+        address: None,
+        transport: None,
+        result: error_result(String::new()),
+        soa_serial: None,
+    };
+    let address = match resolve_host(host) {
+        Ok(address) => address,
+        Err(err) => {
+            check.result = error_result(err.to_string());
+            return check;
+        }
+    };
+    check.address = Some(address.ip().to_string());
+    check.result = match dns_wire::query(record_name, TYPE_TXT)
+        .and_then(|message| dns_wire::resolve(address, message, QUERY_TIMEOUT))
+    {
+        Ok((response, transport)) => {
+            check.transport = Some(transport);
+            interpret_response(host, &response, expected_value)
+        }
+        Err(err) => error_result(format!("{host}: {err}")),
+    };
+    check
+}
+
+/// Prefers an IPv4 address; many networks still lack IPv6 reachability.
+fn resolve_host(host: &str) -> Result<SocketAddr> {
+    let addresses: Vec<SocketAddr> = (host, DEFAULT_PORT)
+        .to_socket_addrs()
+        .map_err(|err| anyhow!("Failed to resolve nameserver {host}: {err}"))?
+        .collect();
+    addresses
+        .iter()
+        .find(|address| address.is_ipv4())
+        .or_else(|| addresses.first())
+        .copied()
+        .ok_or_else(|| anyhow!("Nameserver {host} has no address"))
+}
+
+fn interpret_response(
+    host: &str,
+    response: &dns_wire::Response,
+    expected_value: &str,
+) -> DnsPropagationResult {
+    if !response.authoritative {
+        return error_result(format!("{host} is not authoritative for this zone"));
+    }
+    let observed: Vec<String> = response
+        .answers
+        .iter()
+        .filter(|record| record.rtype == TYPE_TXT)
+        .map(|record| dns_wire::parse_txt_rdata(&record.rdata))
+        .collect();
+    if observed.iter().any(|value| value == expected_value) {
+        return DnsPropagationResult::new(PropagationState::Found, None, observed);
+    }
+    if !observed.is_empty() {
+        return DnsPropagationResult::new(
+            PropagationState::WrongContent,
+            Some(MessageCode::PropagationWrongContent),
+            observed,
+        );
+    }
+    match response.rcode {
+        0 => DnsPropagationResult::new(
+            PropagationState::Pending,
+            Some(MessageCode::PropagationPending),
+            observed,
+        ),
+        RCODE_NXDOMAIN => DnsPropagationResult::new(
+            PropagationState::NxDomain,
+            Some(MessageCode::PropagationNxDomain),
+            observed,
+        ),
+        rcode => error_result(format!("{host} answered with RCODE {rcode}")),
+    }
+}
+
+fn error_result(reason: String) -> DnsPropagationResult {
+    DnsPropagationResult {
+        state: PropagationState::Error,
+        reason: Some(reason),
+        reason_code: None,
+        observed_values: Vec::new(),
+        resolvers: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::issuance::dns_wire::{ResourceRecord, Response};
+
+    fn response(rcode: u8, authoritative: bool, values: &[&str]) -> Response {
+        let answers = values
+            .iter()
+            .map(|value| {
+                let mut rdata = vec![value.len() as u8];
+                rdata.extend_from_slice(value.as_bytes());
+                ResourceRecord {
+                    name: "_acme-challenge.example.com".to_string(),
+                    rtype: TYPE_TXT,
+                    rdata,
+                }
+            })
+            .collect();
+        Response {
+            rcode,
+            authoritative,
+            truncated: false,
+            answers,
+            authority: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn interprets_authoritative_answers() {
+        let found = interpret_response("ns1", &response(0, true, &["old", "token"]), "token");
+        assert_eq!(found.state, PropagationState::Found);
+        assert_eq!(found.observed_values, vec!["old", "token"]);
+
+        let wrong = interpret_response("ns1", &response(0, true, &["old"]), "token");
+        assert_eq!(wrong.state, PropagationState::WrongContent);
+        let pending = interpret_response("ns1", &response(0, true, &[]), "token");
+        assert_eq!(pending.state, PropagationState::Pending);
+        let missing = interpret_response("ns1", &response(RCODE_NXDOMAIN, true, &[]), "token");
+        assert_eq!(missing.state, PropagationState::NxDomain);
+
+        let lame = interpret_response("ns1", &response(0, false, &["token"]), "token");
+        assert_eq!(lame.state, PropagationState::Error);
+        let refused = interpret_response("ns1", &response(5, true, &[]), "token");
+        assert_eq!(refused.state, PropagationState::Error);
+    }
+
+    #[test]
+    fn summary_requires_every_nameserver() {
+        let check = |state: PropagationState| NameserverCheck {
+            nameserver: "ns".to_string(),
+            address: None,
+            transport: None,
+            result: DnsPropagationResult::new(state, None, Vec::new()),
+            soa_serial: None,
+        };
+        let mut result = AuthoritativePropagationResult {
+            record_name: "_acme-challenge.example.com".to_string(),
+            zone: "example.com".to_string(),
+            nameservers: vec![check(PropagationState::Found), check(PropagationState::Pending)],
+            all_found: false,
+            serial_sync: None,
+        };
+        assert_eq!(result.summary().state, PropagationState::Pending);
+
+        result.nameservers.pop();
+        result.all_found = true;
+        assert_eq!(result.summary().state, PropagationState::Found);
+    }
+
+    #[test]
+    fn secondaries_catch_up_to_the_oldest_serial_with_the_record() {
+        let check = |name: &str, state: PropagationState, soa_serial: Option<u32>| {
+            NameserverCheck {
+                nameserver: name.to_string(),
+                address: None,
+                transport: None,
+                result: DnsPropagationResult::new(state, None, Vec::new()),
+                soa_serial,
+            }
+        };
+        // The primary moved on past the change; ns2 already has it, ns3 does not
+        let sync = serial_sync(&[
+            check("ns1", PropagationState::Found, Some(12)),
+            check("ns2", PropagationState::Found, Some(11)),
+            check("ns3", PropagationState::Pending, Some(10)),
+        ]);
+        assert_eq!(sync.target_serial, Some(11));
+        assert_eq!(sync.lagging, vec!["ns3"]);
+        assert!(!sync.in_sync);
+
+        // Serials wrap around in RFC 1982 arithmetic
+        let sync = serial_sync(&[
+            check("ns1", PropagationState::Found, Some(3)),
+            check("ns2", PropagationState::Found, Some(u32::MAX)),
+        ]);
+        assert_eq!(sync.target_serial, Some(u32::MAX));
+        assert!(sync.in_sync);
+
+        let sync = serial_sync(&[check("ns1", PropagationState::Found, None)]);
+        assert_eq!(sync.lagging, vec!["ns1"]);
+    }
+}
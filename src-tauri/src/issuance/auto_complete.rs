@@ -0,0 +1,128 @@
+//! Background completion of issuances whose TXT records are added by hand.
+//!
+//! When `start_managed_issuance` is asked to auto-complete, the records the
+//! user has to add are polled on the manual propagation interval. Once every
+//! one of them is visible, the issuance is completed as
+//! `complete_managed_issuance` would complete it and the outcome is sent as
+//! an [`AUTO_COMPLETE_EVENT`]. Polling stops without completing when the
+//! session ends some other way: completed by hand, abandoned or collected.
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::core::commands::inventory::record_for_display;
+use crate::core::read_only::ReadOnlyMode;
+use crate::core::types::AutoCompleteOutcome;
+use crate::issuance::dns::{DnsRecordInstruction, PropagationState, check_txt_record};
+use crate::issuance::flow::{complete_managed_dns01, is_issuance_pending};
+use crate::issuance::order_polling::{ISSUANCE_PROGRESS_EVENT, PollSettings};
+use crate::issuance::propagation_presets::{self, MANUAL_PROVIDER_TYPE, PropagationWaits};
+use crate::issuance::propagation_resolvers::ResolverSet;
+use crate::secrets::manager::SecretManager;
+use crate::storage::{
+    dns::DnsConfigStore, inventory::InventoryStore, issuer::IssuerConfigStore, jobs::JobStore,
+    preferences::PreferencesStore,
+};
+
+pub const AUTO_COMPLETE_EVENT: &str = "issuance-auto-complete";
+
+/// Lower bound on the poll interval, whatever the manual preset says.
+const MIN_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Starts watching the manual records of `request_id`. Does nothing when
+/// every record is written by a provider.
+pub fn spawn(app: AppHandle, request_id: String, records: &[DnsRecordInstruction]) {
+    let manual: Vec<(String, String)> = records
+        .iter()
+        .filter(|record| record.adapter == MANUAL_PROVIDER_TYPE)
+        .map(|record| (record.record_name.clone(), record.value.clone()))
+        .collect();
+    if manual.is_empty() {
+        log::info!("[issuance] {request_id} has no records added by hand; not auto-completing");
+        return;
+    }
+    let prefs = app.state::<PreferencesStore>();
+    let wait = PropagationWaits::from_preferences(prefs.inner()).for_type(MANUAL_PROVIDER_TYPE);
+    let interval = propagation_presets::interval(wait).max(MIN_POLL_INTERVAL);
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            if !is_issuance_pending(&request_id) {
+                log::info!("[issuance] {request_id} ended; stopping auto-complete");
+                return;
+            }
+            let handle = app.clone();
+            let records = manual.clone();
+            let propagated = tauri::async_runtime::spawn_blocking(move || {
+                all_propagated(&handle, &records)
+            })
+            .await
+            .unwrap_or(false);
+            if !propagated {
+                continue;
+            }
+            log::info!("[issuance] records of {request_id} propagated; completing");
+            let handle = app.clone();
+            let id = request_id.clone();
+            let outcome = tauri::async_runtime::spawn_blocking(move || complete(&handle, &id))
+                .await
+                .unwrap_or_else(|err| AutoCompleteOutcome {
+                    request_id: request_id.clone(),
+                    certificate: None,
+                    error: Some(format!("Auto-complete join error: {err}")),
+                });
+            if let Err(err) = app.emit(AUTO_COMPLETE_EVENT, &outcome) {
+                log::warn!("[issuance] failed to emit auto-complete outcome: {err}");
+            }
+            return;
+        }
+    });
+}
+
+fn all_propagated(app: &AppHandle, records: &[(String, String)]) -> bool {
+    let resolvers = ResolverSet::from_preferences(app.state::<PreferencesStore>().inner());
+    records.iter().all(|(record_name, value)| {
+        check_txt_record(record_name, value, &resolvers)
+            .is_ok_and(|result| result.state == PropagationState::Found)
+    })
+}
+
+fn complete(app: &AppHandle, request_id: &str) -> AutoCompleteOutcome {
+    let outcome = |result: anyhow::Result<_>| match result {
+        Ok(record) => AutoCompleteOutcome {
+            request_id: request_id.to_string(),
+            certificate: Some(record_for_display(record)),
+            error: None,
+        },
+        Err(err) => {
+            log::warn!("[issuance] auto-complete of {request_id} failed: {err}");
+            AutoCompleteOutcome {
+                request_id: request_id.to_string(),
+                certificate: None,
+                error: Some(err.to_string()),
+            }
+        }
+    };
+    if let Err(err) = app.state::<ReadOnlyMode>().ensure_writable() {
+        return outcome(Err(anyhow::anyhow!(err.to_string())));
+    }
+    let prefs = app.state::<PreferencesStore>();
+    let report = |progress| {
+        if let Err(err) = app.emit(ISSUANCE_PROGRESS_EVENT, &progress) {
+            log::warn!("[issuance] failed to emit progress: {err}");
+        }
+    };
+    outcome(complete_managed_dns01(
+        request_id,
+        app.state::<InventoryStore>().inner(),
+        app.state::<IssuerConfigStore>().inner(),
+        app.state::<SecretManager>().inner(),
+        app.state::<DnsConfigStore>().inner(),
+        app.state::<JobStore>().inner(),
+        PollSettings::from_preferences(prefs.inner()),
+        &PropagationWaits::from_preferences(prefs.inner()),
+        &ResolverSet::from_preferences(prefs.inner()),
+        &report,
+    ))
+}
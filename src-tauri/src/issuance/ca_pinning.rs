@@ -0,0 +1,169 @@
+//! Issuing CA pinning.
+//!
+//! An issuer can pin the CA it expects to sign its certificates, either by
+//! subject (full DN or common name) or by the SHA-256 hash of the CA's
+//! SubjectPublicKeyInfo, base64 encoded as in HPKP `pin-sha256`. After an
+//! order is finalized the downloaded chain is checked against the pins, so a
+//! certificate issued from an unexpected intermediate is noticed.
+
+use anyhow::{Result, anyhow};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use sha2::{Digest, Sha256};
+use x509_parser::{parse_x509_certificate, pem::Pem};
+
+use crate::core::types::{CaPin, CaPinKind};
+
+/// Tag added to certificates whose chain did not match the issuer's pins.
+pub const CA_PIN_MISMATCH_TAG: &str = "ca-pin-mismatch";
+
+/// Outcome of checking a chain against an issuer's pins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PinCheck {
+    NotPinned,
+    Matched,
+    /// None of the CA certificates in the chain matched; lists what was seen.
+    Mismatch { observed: Vec<String> },
+}
+
+/// Trims pins, rejects empty values, and canonicalizes SPKI hashes given in
+/// hex or with a `sha256/` prefix to plain base64.
+pub fn normalize_pins(pins: Vec<CaPin>) -> Result<Vec<CaPin>> {
+    pins.into_iter()
+        .map(|pin| {
+            let value = pin.value.trim();
+            if value.is_empty() {
+                return Err(anyhow!("CA pin value is required"));
+            }
+            let value = match pin.kind {
+                CaPinKind::Subject => value.to_string(),
+                CaPinKind::SpkiSha256 => normalize_spki_hash(value)?,
+            };
+            Ok(CaPin {
+                kind: pin.kind,
+                value,
+            })
+        })
+        .collect()
+}
+
+fn normalize_spki_hash(value: &str) -> Result<String> {
+    let value = value.strip_prefix("sha256/").unwrap_or(value);
+    let bytes = if value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit()) {
+        hex::decode(value)?
+    } else {
+        STANDARD
+            .decode(value)
+            .map_err(|_| anyhow!("SPKI pin must be a base64 or hex SHA-256 hash"))?
+    };
+    if bytes.len() != 32 {
+        return Err(anyhow!("SPKI pin must be a SHA-256 hash (32 bytes)"));
+    }
+    Ok(STANDARD.encode(bytes))
+}
+
+/// Checks the CA certificates of `chain_pem` (everything after the leaf)
+/// against `pins`; any single match is enough.
+pub fn check_chain(chain_pem: &str, pins: &[CaPin]) -> Result<PinCheck> {
+    if pins.is_empty() {
+        return Ok(PinCheck::NotPinned);
+    }
+    let blocks: Vec<Pem> = Pem::iter_from_buffer(chain_pem.as_bytes())
+        .collect::<Result<_, _>>()
+        .map_err(|err| anyhow!("failed to read certificate chain: {err}"))?;
+    if blocks.len() < 2 {
+        return Err(anyhow!("certificate chain has no CA certificates to check"));
+    }
+
+    let mut observed = Vec::new();
+    for block in &blocks[1..] {
+        let (_, cert) = parse_x509_certificate(&block.contents)
+            .map_err(|err| anyhow!("failed to parse CA certificate: {err}"))?;
+        let subject = cert.subject().to_string();
+        let common_name = cert
+            .subject()
+            .iter_common_name()
+            .next()
+            .and_then(|cn| cn.as_str().ok())
+            .map(str::to_string);
+        let spki = STANDARD.encode(Sha256::digest(cert.public_key().raw));
+
+        let matched = pins.iter().any(|pin| match pin.kind {
+            CaPinKind::Subject => {
+                pin.value.eq_ignore_ascii_case(&subject)
+                    || common_name
+                        .as_deref()
+                        .is_some_and(|cn| pin.value.eq_ignore_ascii_case(cn))
+            }
+            CaPinKind::SpkiSha256 => pin.value == spki,
+        });
+        if matched {
+            return Ok(PinCheck::Matched);
+        }
+        observed.push(format!("{subject} (sha256/{spki})"));
+    }
+    Ok(PinCheck::Mismatch { observed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::{
+        asn1::Asn1Time,
+        ec::{EcGroup, EcKey},
+        hash::MessageDigest,
+        nid::Nid,
+        pkey::{PKey, Private},
+        x509::{X509, X509NameBuilder},
+    };
+
+    fn cert(cn: &str, key: &PKey<Private>, issuer: Option<(&X509, &PKey<Private>)>) -> X509 {
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("O", "Test CA Org").unwrap();
+        name.append_entry_by_text("CN", cn).unwrap();
+        let name = name.build();
+        let mut builder = X509::builder().unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder
+            .set_issuer_name(issuer.map_or(&name, |(ca, _)| ca.subject_name()))
+            .unwrap();
+        builder.set_pubkey(key).unwrap();
+        builder.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+        builder.set_not_after(&Asn1Time::days_from_now(30).unwrap()).unwrap();
+        builder
+            .sign(issuer.map_or(key, |(_, ca_key)| ca_key), MessageDigest::sha256())
+            .unwrap();
+        builder.build()
+    }
+
+    #[test]
+    fn checks_chain_against_subject_and_spki_pins() {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let ca_key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let leaf_key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let ca = cert("Pinned Intermediate", &ca_key, None);
+        let leaf = cert("example.com", &leaf_key, Some((&ca, &ca_key)));
+        let chain = format!(
+            "{}{}",
+            String::from_utf8(leaf.to_pem().unwrap()).unwrap(),
+            String::from_utf8(ca.to_pem().unwrap()).unwrap()
+        );
+        let spki_hex = hex::encode(Sha256::digest(ca_key.public_key_to_der().unwrap()));
+
+        let pin = |kind, value: &str| CaPin {
+            kind,
+            value: value.to_string(),
+        };
+        assert_eq!(check_chain(&chain, &[]).unwrap(), PinCheck::NotPinned);
+        assert_eq!(
+            check_chain(&chain, &[pin(CaPinKind::Subject, "pinned intermediate")]).unwrap(),
+            PinCheck::Matched
+        );
+        let spki = normalize_pins(vec![pin(CaPinKind::SpkiSha256, &spki_hex)]).unwrap();
+        assert_eq!(check_chain(&chain, &spki).unwrap(), PinCheck::Matched);
+        assert!(matches!(
+            check_chain(&chain, &[pin(CaPinKind::Subject, "Other CA")]).unwrap(),
+            PinCheck::Mismatch { observed } if observed.len() == 1
+        ));
+        assert!(normalize_pins(vec![pin(CaPinKind::SpkiSha256, "abc")]).is_err());
+    }
+}
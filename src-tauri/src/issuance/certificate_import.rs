@@ -0,0 +1,383 @@
+//! Certificate import from the encodings other tools export.
+//!
+//! Windows and appliance exports rarely hand over a tidy PEM file: they use
+//! DER, PKCS#7 (`.p7b`) containers, UTF-16 text with a byte-order mark, CRLF
+//! line endings, or bundles mixing certificates with keys and attribute
+//! lines. Each supported encoding is a [`CertificateParser`]; the first one
+//! that recognizes the input decodes it, and the certificates are stored as
+//! clean PEM, leaf first. Parse errors name the PEM block that failed.
+
+use anyhow::{Context, Result, anyhow};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use chrono::{TimeZone, Utc};
+use openssl::{pkcs7::Pkcs7, x509::X509};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use x509_parser::{extensions::GeneralName, parse_x509_certificate};
+
+use crate::core::types::{
+    CertificateFormat, CertificateRecord, CertificateSource, CertificateStatus,
+};
+use crate::issuance::chain_repair::repair_chain;
+use crate::issuance::flow::root_from_hostname;
+
+/// PEM labels holding a single X.509 certificate.
+const CERTIFICATE_LABELS: &[&str] = &["CERTIFICATE", "X509 CERTIFICATE"];
+/// PEM labels holding a PKCS#7 / CMS certificate container.
+const PKCS7_LABELS: &[&str] = &["PKCS7", "CMS"];
+
+/// One encoding certificates can be imported from.
+pub trait CertificateParser: Sync {
+    fn format(&self) -> CertificateFormat;
+    /// Whether `input` is in this encoding.
+    fn detects(&self, input: &[u8]) -> bool;
+    fn parse(&self, input: &[u8]) -> Result<Vec<X509>>;
+}
+
+/// Tried in order; text is checked first since DER detection is loose.
+static PARSERS: &[&dyn CertificateParser] = &[&PemParser, &Pkcs7DerParser, &DerParser];
+
+/// Text bundles of PEM blocks, in UTF-8 or UTF-16 with any line endings.
+struct PemParser;
+
+/// A binary PKCS#7 container (`.p7b`, `.p7c`).
+struct Pkcs7DerParser;
+
+/// A single binary X.509 certificate (`.der`, `.cer`).
+struct DerParser;
+
+impl CertificateParser for PemParser {
+    fn format(&self) -> CertificateFormat {
+        CertificateFormat::Pem
+    }
+
+    fn detects(&self, input: &[u8]) -> bool {
+        decode_text(input).is_some_and(|text| text.contains("-----BEGIN "))
+    }
+
+    fn parse(&self, input: &[u8]) -> Result<Vec<X509>> {
+        let text = decode_text(input).ok_or_else(|| anyhow!("File is not text"))?;
+        let mut certificates = Vec::new();
+        for (index, block) in pem_blocks(&text)?.into_iter().enumerate() {
+            let number = index + 1;
+            let label = block.label;
+            let der = STANDARD
+                .decode(&block.body)
+                .with_context(|| format!("Block {number} ({label}) is not valid base64"))?;
+            if CERTIFICATE_LABELS.contains(&label) {
+                let certificate = X509::from_der(&der).map_err(|err| {
+                    anyhow!("Block {number} ({label}) is not a valid X.509 certificate: {err}")
+                })?;
+                certificates.push(certificate);
+            } else if PKCS7_LABELS.contains(&label) {
+                let contained = pkcs7_certificates(&der)
+                    .with_context(|| format!("Block {number} ({label})"))?;
+                certificates.extend(contained);
+            } else {
+                log::info!("[import] skipping block {number} ({label}); not a certificate");
+            }
+        }
+        Ok(certificates)
+    }
+}
+
+impl CertificateParser for Pkcs7DerParser {
+    fn format(&self) -> CertificateFormat {
+        CertificateFormat::Pkcs7
+    }
+
+    fn detects(&self, input: &[u8]) -> bool {
+        Pkcs7::from_der(input).is_ok()
+    }
+
+    fn parse(&self, input: &[u8]) -> Result<Vec<X509>> {
+        pkcs7_certificates(input)
+    }
+}
+
+impl CertificateParser for DerParser {
+    fn format(&self) -> CertificateFormat {
+        CertificateFormat::Der
+    }
+
+    fn detects(&self, input: &[u8]) -> bool {
+        // Every DER certificate is an ASN.1 SEQUENCE
+        input.first() == Some(&0x30)
+    }
+
+    fn parse(&self, input: &[u8]) -> Result<Vec<X509>> {
+        X509::from_der(input)
+            .map(|certificate| vec![certificate])
+            .map_err(|err| anyhow!("File is not a valid DER certificate: {err}"))
+    }
+}
+
+/// Certificates decoded from an import, re-encoded as clean PEM.
+#[derive(Debug, Clone)]
+pub struct NormalizedCertificates {
+    pub format: CertificateFormat,
+    /// LF-terminated PEM blocks in the order they were found
+    pub pem: String,
+    pub count: usize,
+}
+
+/// Decodes `input` with the first parser that recognizes it.
+pub fn normalize_certificates(input: &[u8]) -> Result<NormalizedCertificates> {
+    let parser = PARSERS
+        .iter()
+        .find(|parser| parser.detects(input))
+        .ok_or_else(|| anyhow!("Not a PEM, DER or PKCS#7 certificate file"))?;
+    let certificates = parser.parse(input)?;
+    if certificates.is_empty() {
+        return Err(anyhow!("The file contains no certificates"));
+    }
+    let mut pem = String::new();
+    for certificate in &certificates {
+        pem.push_str(std::str::from_utf8(&certificate.to_pem()?)?);
+    }
+    Ok(NormalizedCertificates {
+        format: parser.format(),
+        pem,
+        count: certificates.len(),
+    })
+}
+
+/// Builds an external inventory record from imported certificate bytes. The
+/// chain is ordered leaf first; the first certificate after ordering is the
+/// one the record describes.
+pub fn import_certificate(input: &[u8]) -> Result<(CertificateRecord, CertificateFormat)> {
+    let normalized = normalize_certificates(input)?;
+    log::info!(
+        "[import] decoded {} certificate(s) from {:?}",
+        normalized.count,
+        normalized.format
+    );
+    let (pem, chain_repairs) = match repair_chain(&normalized.pem) {
+        Ok(repaired) => (repaired.pem, repaired.repairs),
+        Err(err) => {
+            log::warn!("[import] keeping certificates in file order: {err}");
+            (normalized.pem, Vec::new())
+        }
+    };
+
+    let leaf = X509::stack_from_pem(pem.as_bytes())?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("The file contains no certificates"))?
+        .to_der()?;
+    let (_, cert) = parse_x509_certificate(&leaf)
+        .map_err(|err| anyhow!("Failed to read the certificate: {err}"))?;
+    let mut names: Vec<String> = cert
+        .subject_alternative_name()
+        .ok()
+        .flatten()
+        .map(|san| {
+            san.value
+                .general_names
+                .iter()
+                .filter_map(|name| match name {
+                    GeneralName::DNSName(dns) => Some(dns.to_ascii_lowercase()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if names.is_empty() {
+        names.extend(
+            cert.subject()
+                .iter_common_name()
+                .filter_map(|cn| cn.as_str().ok())
+                .map(str::to_ascii_lowercase),
+        );
+    }
+    let mut domain_roots: Vec<String> =
+        names.iter().map(|name| root_from_hostname(name)).collect();
+    domain_roots.sort();
+    domain_roots.dedup();
+
+    let record = CertificateRecord {
+        id: format!("cert_{}", Uuid::new_v4().as_simple()),
+        subjects: names.clone(),
+        sans: names,
+        issuer: cert.issuer().to_string(),
+        serial: cert.raw_serial_as_string(),
+        not_before: Utc
+            .timestamp_opt(cert.validity().not_before.timestamp(), 0)
+            .single()
+            .unwrap_or_else(Utc::now),
+        not_after: Utc
+            .timestamp_opt(cert.validity().not_after.timestamp(), 0)
+            .single()
+            .unwrap_or_else(Utc::now),
+        fingerprint: hex::encode(Sha256::digest(&leaf)),
+        source: CertificateSource::External,
+        domain_roots,
+        tags: vec![],
+        managed_key_ref: None,
+        chain_pem: Some(pem),
+        chain_name: None,
+        key_algorithm: None,
+        key_size: None,
+        key_curve: None,
+        status: CertificateStatus::Active,
+        issuer_id: None,
+        promoted_from: None,
+        chain_repairs,
+        name_forms: Vec::new(),
+    };
+    Ok((record, normalized.format))
+}
+
+/// Reads UTF-8 or BOM-marked UTF-16 text with LF line endings; None for
+/// binary input.
+fn decode_text(input: &[u8]) -> Option<String> {
+    let text = match input {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8(rest.to_vec()).ok()?,
+        [0xFF, 0xFE, rest @ ..] => decode_utf16(rest, u16::from_le_bytes)?,
+        [0xFE, 0xFF, rest @ ..] => decode_utf16(rest, u16::from_be_bytes)?,
+        _ => String::from_utf8(input.to_vec()).ok()?,
+    };
+    Some(text.replace("\r\n", "\n").replace('\r', "\n"))
+}
+
+fn decode_utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> Option<String> {
+    if !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| unit([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16(&units).ok()
+}
+
+struct PemBlock<'a> {
+    label: &'a str,
+    /// Base64 body with whitespace removed
+    body: String,
+}
+
+/// Splits `text` into PEM blocks. Lines outside blocks, such as the "Bag
+/// Attributes" and "subject=" lines `openssl pkcs12` prints, are ignored.
+fn pem_blocks(text: &str) -> Result<Vec<PemBlock<'_>>> {
+    let mut blocks = Vec::new();
+    let mut current: Option<PemBlock> = None;
+    for line in text.lines().map(str::trim) {
+        if let Some(label) = pem_marker(line, "BEGIN") {
+            if let Some(open) = &current {
+                return Err(anyhow!(
+                    "Block {} ({}) has no END line before the next block",
+                    blocks.len() + 1,
+                    open.label
+                ));
+            }
+            current = Some(PemBlock {
+                label,
+                body: String::new(),
+            });
+        } else if let Some(label) = pem_marker(line, "END") {
+            let block = current.take().ok_or_else(|| {
+                anyhow!("END {label} line without a matching BEGIN line")
+            })?;
+            if block.label != label {
+                return Err(anyhow!(
+                    "Block {} ({}) ends with END {label}",
+                    blocks.len() + 1,
+                    block.label
+                ));
+            }
+            blocks.push(block);
+        } else if let Some(block) = current.as_mut() {
+            // Encapsulated headers (RFC 1421) carry no base64
+            if !line.contains(':') {
+                block.body.extend(line.chars().filter(|c| !c.is_whitespace()));
+            }
+        }
+    }
+    if let Some(open) = current {
+        return Err(anyhow!("Block {} ({}) has no END line", blocks.len() + 1, open.label));
+    }
+    Ok(blocks)
+}
+
+/// Label of a `-----BEGIN <label>-----` or `-----END <label>-----` line.
+fn pem_marker<'a>(line: &'a str, kind: &str) -> Option<&'a str> {
+    line.strip_prefix("-----")?
+        .strip_prefix(kind)?
+        .strip_prefix(' ')?
+        .strip_suffix("-----")
+}
+
+fn pkcs7_certificates(der: &[u8]) -> Result<Vec<X509>> {
+    let pkcs7 = Pkcs7::from_der(der).map_err(|err| anyhow!("Not a valid PKCS#7 file: {err}"))?;
+    let certificates: Vec<X509> = pkcs7
+        .signed()
+        .and_then(|signed| signed.certificates())
+        .map(|stack| stack.iter().map(|cert| cert.to_owned()).collect())
+        .unwrap_or_default();
+    if certificates.is_empty() {
+        return Err(anyhow!("The PKCS#7 file contains no certificates"));
+    }
+    Ok(certificates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::{pkcs7::Pkcs7Flags, pkey::PKey, stack::Stack};
+    use rcgen::{CertificateParams, KeyPair};
+
+    fn test_certificate() -> (rcgen::Certificate, KeyPair) {
+        let key = KeyPair::generate().expect("key");
+        let cert = CertificateParams::new(vec!["import.example.com".to_string()])
+            .expect("params")
+            .self_signed(&key)
+            .expect("cert");
+        (cert, key)
+    }
+
+    #[test]
+    fn normalizes_windows_text_der_and_pkcs7() {
+        let (cert, key) = test_certificate();
+        let clean = cert.pem().replace("\r\n", "\n");
+
+        // UTF-16 LE with a BOM, CRLF line endings and openssl attribute lines
+        let exported = format!("Bag Attributes\r\nsubject=CN=import\r\n{}", clean)
+            .replace('\n', "\r\n");
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend(exported.encode_utf16().flat_map(u16::to_le_bytes));
+        let normalized = normalize_certificates(&utf16).unwrap();
+        assert_eq!(normalized.format, CertificateFormat::Pem);
+        assert_eq!(normalized.pem, clean);
+
+        let der = normalize_certificates(cert.der()).unwrap();
+        assert_eq!((der.format, der.pem.as_str()), (CertificateFormat::Der, clean.as_str()));
+
+        let signer = X509::from_der(cert.der()).unwrap();
+        let pkey = PKey::private_key_from_pem(key.serialize_pem().as_bytes()).unwrap();
+        let p7 = Pkcs7::sign(&signer, &pkey, &Stack::new().unwrap(), b"", Pkcs7Flags::BINARY)
+            .unwrap();
+        let pkcs7 = normalize_certificates(&p7.to_der().unwrap()).unwrap();
+        assert_eq!((pkcs7.format, pkcs7.pem.as_str()), (CertificateFormat::Pkcs7, clean.as_str()));
+
+        let (record, _) = import_certificate(cert.der()).unwrap();
+        assert_eq!(record.sans, ["import.example.com"]);
+        assert_eq!(record.domain_roots, ["example.com"]);
+        assert!(matches!(record.source, CertificateSource::External));
+    }
+
+    #[test]
+    fn names_the_block_that_failed() {
+        let (cert, _) = test_certificate();
+        let broken = "-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----\n";
+        let bundle = format!("{}{broken}", cert.pem());
+        let err = normalize_certificates(bundle.as_bytes()).unwrap_err().to_string();
+        assert!(err.starts_with("Block 2 (CERTIFICATE)"), "{err}");
+
+        let unterminated = format!("{}-----BEGIN CERTIFICATE-----\nAAAA\n", cert.pem());
+        let err = normalize_certificates(unterminated.as_bytes()).unwrap_err().to_string();
+        assert_eq!(err, "Block 2 (CERTIFICATE) has no END line");
+
+        assert!(normalize_certificates(b"not a certificate").is_err());
+    }
+}
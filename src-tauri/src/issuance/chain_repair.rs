@@ -0,0 +1,274 @@
+//! Certificate chain normalization.
+//!
+//! A stored `chain_pem` should start with the leaf, list each issuer in
+//! order, and leave out the self-signed root that clients already trust.
+//! Servers do not always send that: blocks can arrive out of order or twice,
+//! include the root, or miss an intermediate. This module rebuilds the chain
+//! by signature, fetching a missing intermediate from the certificate's
+//! Authority Information Access (caIssuers) URL, and reports each repair.
+
+use std::io::Read;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use openssl::{
+    nid::Nid,
+    x509::{X509, X509Ref, X509VerifyResult},
+};
+
+use crate::core::types::ChainRepair;
+use crate::core::user_agent::identify;
+
+/// Limits how many missing intermediates are fetched for one chain.
+const MAX_FETCHED: usize = 3;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_FETCH_BYTES: u64 = 64 * 1024;
+
+/// A normalized chain and what was changed to get there.
+#[derive(Debug, Clone)]
+pub struct RepairedChain {
+    pub pem: String,
+    pub repairs: Vec<ChainRepair>,
+}
+
+/// Orders `chain_pem` from the leaf up, fetching missing intermediates over
+/// AIA. Certificates that do not chain to the leaf are dropped.
+pub fn repair_chain(chain_pem: &str) -> Result<RepairedChain> {
+    repair_chain_with(chain_pem, fetch_issuer)
+}
+
+fn repair_chain_with(
+    chain_pem: &str,
+    fetch: impl Fn(&str) -> Result<X509>,
+) -> Result<RepairedChain> {
+    let parsed = X509::stack_from_pem(chain_pem.as_bytes())
+        .context("failed to parse certificate chain PEM")?;
+    if parsed.is_empty() {
+        return Err(anyhow!("certificate chain PEM contains no certificates"));
+    }
+
+    let mut repairs = Vec::new();
+    let mut certs: Vec<X509> = Vec::with_capacity(parsed.len());
+    let mut ders = Vec::with_capacity(parsed.len());
+    for cert in parsed {
+        let der = cert.to_der()?;
+        if ders.contains(&der) {
+            push_once(&mut repairs, ChainRepair::DuplicateRemoved);
+            continue;
+        }
+        ders.push(der);
+        certs.push(cert);
+    }
+
+    // The leaf is the one certificate that issued none of the others
+    let leaf_index = (0..certs.len())
+        .find(|&index| {
+            certs.iter().enumerate().all(|(other, cert)| {
+                other == index || is_self_signed(cert) || !issued_by(cert, &certs[index])
+            })
+        })
+        .unwrap_or(0);
+
+    let mut remaining: Vec<(usize, X509)> = certs.into_iter().enumerate().collect();
+    let (leaf_position, leaf) = remaining.remove(leaf_index);
+    let mut ordered = vec![(Some(leaf_position), leaf)];
+    let mut fetched = 0;
+    loop {
+        let current = &ordered[ordered.len() - 1].1;
+        if is_self_signed(current) {
+            break;
+        }
+        if let Some(found) = remaining
+            .iter()
+            .position(|(_, candidate)| issued_by(current, candidate))
+        {
+            let (position, cert) = remaining.remove(found);
+            ordered.push((Some(position), cert));
+            continue;
+        }
+        if fetched >= MAX_FETCHED {
+            break;
+        }
+        let Some(url) = ca_issuers_url(current) else {
+            break;
+        };
+        match fetch(&url) {
+            Ok(issuer) if issued_by(current, &issuer) => {
+                log::info!("[issuance] fetched missing intermediate from {url}");
+                fetched += 1;
+                ordered.push((None, issuer));
+                push_once(&mut repairs, ChainRepair::IntermediateFetched);
+            }
+            Ok(_) => {
+                log::warn!("[issuance] certificate from {url} did not sign the chain");
+                break;
+            }
+            Err(err) => {
+                log::warn!("[issuance] failed to fetch intermediate from {url}: {err}");
+                break;
+            }
+        }
+    }
+
+    let positions: Vec<usize> = ordered.iter().filter_map(|(position, _)| *position).collect();
+    if positions.windows(2).any(|pair| pair[0] > pair[1]) {
+        repairs.push(ChainRepair::Reordered);
+    }
+    if ordered.len() > 1 && is_self_signed(&ordered[ordered.len() - 1].1) {
+        ordered.pop();
+        repairs.push(ChainRepair::RootRemoved);
+    }
+    if !remaining.is_empty() {
+        repairs.push(ChainRepair::UnrelatedRemoved);
+    }
+
+    let mut pem = String::new();
+    for (_, cert) in &ordered {
+        pem.push_str(std::str::from_utf8(&cert.to_pem()?)?);
+    }
+    Ok(RepairedChain { pem, repairs })
+}
+
+fn push_once(repairs: &mut Vec<ChainRepair>, repair: ChainRepair) {
+    if !repairs.contains(&repair) {
+        repairs.push(repair);
+    }
+}
+
+/// Whether `issuer` issued `cert`, checked by name and signature.
+fn issued_by(cert: &X509Ref, issuer: &X509Ref) -> bool {
+    issuer.issued(cert) == X509VerifyResult::OK
+        && issuer
+            .public_key()
+            .and_then(|key| cert.verify(&key))
+            .unwrap_or(false)
+}
+
+fn is_self_signed(cert: &X509Ref) -> bool {
+    issued_by(cert, cert)
+}
+
+fn ca_issuers_url(cert: &X509Ref) -> Option<String> {
+    cert.authority_info()?
+        .iter()
+        .filter(|access| access.method().nid() == Nid::AD_CA_ISSUERS)
+        .filter_map(|access| access.location().uri())
+        .find(|uri| uri.starts_with("http://") || uri.starts_with("https://"))
+        .map(|uri| uri.to_string())
+}
+
+/// Downloads an issuer certificate; caIssuers URLs usually serve DER.
+fn fetch_issuer(url: &str) -> Result<X509> {
+    let response = identify(ureq::get(url))
+        .timeout(FETCH_TIMEOUT)
+        .call()
+        .map_err(|err| anyhow!("request failed: {err}"))?;
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_FETCH_BYTES)
+        .read_to_end(&mut body)
+        .context("failed to read response body")?;
+    X509::from_der(&body)
+        .or_else(|_| X509::from_pem(&body))
+        .context("response is not a certificate")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{
+        BasicConstraints, Certificate, CertificateParams, DistinguishedName, DnType, IsCa, KeyPair,
+    };
+
+    struct TestChain {
+        leaf: String,
+        intermediate: String,
+        root: String,
+    }
+
+    fn ca_params(cn: &str) -> CertificateParams {
+        let mut params = CertificateParams::default();
+        let mut dn = DistinguishedName::new();
+        dn.push(DnType::CommonName, cn);
+        params.distinguished_name = dn;
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params
+    }
+
+    fn test_chain() -> TestChain {
+        let root_key = KeyPair::generate().expect("root key");
+        let root: Certificate = ca_params("Test Root").self_signed(&root_key).expect("root");
+        let int_key = KeyPair::generate().expect("intermediate key");
+        let intermediate = ca_params("Test Intermediate")
+            .signed_by(&int_key, &root, &root_key)
+            .expect("intermediate");
+        let leaf_key = KeyPair::generate().expect("leaf key");
+        let leaf = CertificateParams::new(vec!["example.com".to_string()])
+            .expect("leaf params")
+            .signed_by(&leaf_key, &intermediate, &int_key)
+            .expect("leaf");
+        TestChain {
+            leaf: leaf.pem(),
+            intermediate: intermediate.pem(),
+            root: root.pem(),
+        }
+    }
+
+    fn no_fetch(_: &str) -> Result<X509> {
+        Err(anyhow!("no network in tests"))
+    }
+
+    fn subjects(pem: &str) -> Vec<String> {
+        X509::stack_from_pem(pem.as_bytes())
+            .unwrap()
+            .iter()
+            .map(|cert| {
+                let entry = cert.subject_name().entries_by_nid(Nid::COMMONNAME).next();
+                entry.map(|e| e.data().as_utf8().unwrap().to_string()).unwrap_or_default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn well_formed_chain_is_left_alone() {
+        let chain = test_chain();
+        let pem = format!("{}{}", chain.leaf, chain.intermediate);
+        let repaired = repair_chain_with(&pem, no_fetch).unwrap();
+        assert!(repaired.repairs.is_empty());
+        assert_eq!(subjects(&repaired.pem), subjects(&pem));
+    }
+
+    #[test]
+    fn reorders_deduplicates_and_strips_root() {
+        let chain = test_chain();
+        let other = test_chain();
+        let pem = format!(
+            "{}{}{}{}{}",
+            chain.root, chain.intermediate, chain.leaf, chain.intermediate, other.intermediate
+        );
+        let repaired = repair_chain_with(&pem, no_fetch).unwrap();
+        assert_eq!(
+            repaired.repairs,
+            vec![
+                ChainRepair::DuplicateRemoved,
+                ChainRepair::Reordered,
+                ChainRepair::RootRemoved,
+                ChainRepair::UnrelatedRemoved,
+            ]
+        );
+        let expected = format!("{}{}", chain.leaf, chain.intermediate);
+        assert_eq!(subjects(&repaired.pem), subjects(&expected));
+        let first = X509::stack_from_pem(repaired.pem.as_bytes()).unwrap();
+        let leaf = X509::from_pem(chain.leaf.as_bytes()).unwrap();
+        assert_eq!(first[0].to_der().unwrap(), leaf.to_der().unwrap());
+    }
+
+    #[test]
+    fn leaves_missing_intermediate_without_aia() {
+        let chain = test_chain();
+        let repaired = repair_chain_with(&chain.leaf, |_| panic!("nothing to fetch")).unwrap();
+        assert!(repaired.repairs.is_empty());
+        assert!(repair_chain_with("not a certificate", no_fetch).is_err());
+    }
+}
@@ -0,0 +1,330 @@
+//! Alternate certificate chain handling.
+//!
+//! ACME servers such as Let's Encrypt can offer the same leaf certificate with
+//! different intermediate chains (for example ISRG Root X1 vs. the shorter
+//! ISRG Root X2 chain). acme-lib only downloads the default chain, so this
+//! module fetches the `Link: rel="alternate"` chains with signed POST-as-GET
+//! requests and picks the one whose topmost issuer matches the preference.
+
+use anyhow::{Context, Result, anyhow};
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use openssl::{
+    bn::{BigNum, BigNumContext},
+    ecdsa::EcdsaSig,
+    hash::{MessageDigest, hash},
+    pkey::{PKey, Private},
+};
+use serde_json::{Value, json};
+use x509_parser::{parse_x509_certificate, pem::Pem};
+
+use crate::core::user_agent::identify;
+
+/// A certificate chain offered by the ACME server.
+#[derive(Debug, Clone)]
+pub struct CertificateChain {
+    /// Common name of the issuer of the topmost certificate in the chain.
+    pub name: Option<String>,
+    pub pem: String,
+}
+
+impl CertificateChain {
+    pub fn from_pem(pem: String) -> Self {
+        let name = chain_root_name(&pem);
+        Self { name, pem }
+    }
+}
+
+/// Returns the issuer common name of the last certificate in a PEM chain.
+pub fn chain_root_name(chain_pem: &str) -> Option<String> {
+    let last = Pem::iter_from_buffer(chain_pem.as_bytes())
+        .filter_map(|block| block.ok())
+        .last()?;
+    let (_, cert) = parse_x509_certificate(&last.contents).ok()?;
+    cert.issuer()
+        .iter_common_name()
+        .next()
+        .and_then(|cn| cn.as_str().ok())
+        .map(|cn| cn.to_string())
+}
+
+/// Picks the chain whose root name matches `preferred` (case-insensitive),
+/// falling back to the first chain which is the server default.
+pub fn select_chain(chains: Vec<CertificateChain>, preferred: Option<&str>) -> Option<CertificateChain> {
+    let preferred = preferred.map(str::trim).filter(|value| !value.is_empty());
+    if let Some(preferred) = preferred
+        && let Some(found) = chains.iter().find(|chain| {
+            chain
+                .name
+                .as_deref()
+                .is_some_and(|name| name.eq_ignore_ascii_case(preferred))
+        })
+    {
+        return Some(found.clone());
+    }
+    chains.into_iter().next()
+}
+
+/// Downloads the alternate chains advertised for an issued certificate.
+///
+/// The default chain is not included; callers already have it from acme-lib.
+pub fn fetch_alternate_chains(
+    directory_url: &str,
+    account_key_pem: &str,
+    certificate_url: &str,
+) -> Result<Vec<CertificateChain>> {
+    let mut client = AcmeClient::connect(directory_url, account_key_pem)?;
+    let response = client.post_as_get(certificate_url)?;
+    let alternates = alternate_links(&response.links);
+
+    let mut chains = Vec::new();
+    for url in alternates {
+        match client.post_as_get(&url) {
+            Ok(alternate) => chains.push(CertificateChain::from_pem(alternate.body)),
+            Err(err) => log::warn!("[issuance] failed to download alternate chain {url}: {err}"),
+        }
+    }
+    Ok(chains)
+}
+
+struct AcmeResponse {
+    body: String,
+    links: Vec<String>,
+}
+
+/// Minimal JWS client for POST-as-GET requests against an existing account.
+struct AcmeClient {
+    key: PKey<Private>,
+    jwk: Value,
+    kid: String,
+    nonce: String,
+    new_nonce_url: String,
+}
+
+impl AcmeClient {
+    fn connect(directory_url: &str, account_key_pem: &str) -> Result<Self> {
+        let key = PKey::private_key_from_pem(account_key_pem.as_bytes())
+            .context("failed to parse ACME account key")?;
+        let jwk = es256_jwk(&key)?;
+
+        let directory = identify(ureq::get(directory_url))
+            .call()
+            .map_err(|e| anyhow!("failed to fetch ACME directory: {e}"))?
+            .into_string()
+            .context("failed to read ACME directory")?;
+        let directory: Value =
+            serde_json::from_str(&directory).context("failed to parse ACME directory")?;
+        let new_nonce_url = directory_entry(&directory, "newNonce")?;
+        let new_account_url = directory_entry(&directory, "newAccount")?;
+
+        let mut client = Self {
+            key,
+            jwk,
+            kid: String::new(),
+            nonce: String::new(),
+            new_nonce_url,
+        };
+        client.nonce = client.fresh_nonce()?;
+
+        let payload = json!({ "onlyReturnExisting": true }).to_string();
+        let response = client.post_raw(&new_account_url, &payload, true)?;
+        client.kid = response
+            .header("Location")
+            .map(|value| value.to_string())
+            .ok_or_else(|| anyhow!("ACME account lookup did not return an account URL"))?;
+        client.remember_nonce(&response);
+        Ok(client)
+    }
+
+    fn post_as_get(&mut self, url: &str) -> Result<AcmeResponse> {
+        let response = self.post_raw(url, "", false)?;
+        self.remember_nonce(&response);
+        let links = response
+            .all("Link")
+            .into_iter()
+            .map(|value| value.to_string())
+            .collect();
+        let body = response
+            .into_string()
+            .context("failed to read ACME response body")?;
+        Ok(AcmeResponse { body, links })
+    }
+
+    fn post_raw(&mut self, url: &str, payload: &str, use_jwk: bool) -> Result<ureq::Response> {
+        let mut protected = json!({
+            "alg": "ES256",
+            "nonce": self.nonce,
+            "url": url,
+        });
+        if use_jwk {
+            protected["jwk"] = self.jwk.clone();
+        } else {
+            protected["kid"] = Value::String(self.kid.clone());
+        }
+
+        let protected = URL_SAFE_NO_PAD.encode(protected.to_string());
+        let payload = URL_SAFE_NO_PAD.encode(payload);
+        let signature = es256_sign(&self.key, format!("{protected}.{payload}").as_bytes())?;
+        let body = json!({
+            "protected": protected,
+            "payload": payload,
+            "signature": URL_SAFE_NO_PAD.encode(signature),
+        });
+
+        match identify(ureq::post(url))
+            .set("Content-Type", "application/jose+json")
+            .send_string(&body.to_string())
+        {
+            Ok(response) => Ok(response),
+            Err(ureq::Error::Status(code, response)) => {
+                self.remember_nonce(&response);
+                let detail = response.into_string().unwrap_or_default();
+                Err(anyhow!("ACME request to {url} failed with HTTP {code}: {detail}"))
+            }
+            Err(err) => Err(anyhow!("ACME request to {url} failed: {err}")),
+        }
+    }
+
+    fn fresh_nonce(&self) -> Result<String> {
+        let response = identify(ureq::head(&self.new_nonce_url))
+            .call()
+            .map_err(|e| anyhow!("failed to fetch ACME nonce: {e}"))?;
+        response
+            .header("Replay-Nonce")
+            .map(|value| value.to_string())
+            .ok_or_else(|| anyhow!("ACME server did not return a nonce"))
+    }
+
+    fn remember_nonce(&mut self, response: &ureq::Response) {
+        if let Some(nonce) = response.header("Replay-Nonce") {
+            self.nonce = nonce.to_string();
+        }
+    }
+}
+
+fn directory_entry(directory: &Value, name: &str) -> Result<String> {
+    directory
+        .get(name)
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+        .ok_or_else(|| anyhow!("ACME directory is missing {name}"))
+}
+
+/// Extracts `rel="alternate"` targets from Link headers.
+fn alternate_links(links: &[String]) -> Vec<String> {
+    links
+        .iter()
+        .flat_map(|header| header.split(','))
+        .filter_map(|link| {
+            let mut parts = link.split(';');
+            let target = parts.next()?.trim();
+            let is_alternate = parts.any(|param| {
+                let param = param.trim().replace(' ', "");
+                param.eq_ignore_ascii_case("rel=\"alternate\"") || param.eq_ignore_ascii_case("rel=alternate")
+            });
+            if !is_alternate {
+                return None;
+            }
+            target
+                .strip_prefix('<')
+                .and_then(|value| value.strip_suffix('>'))
+                .map(|value| value.to_string())
+        })
+        .collect()
+}
+
+fn es256_jwk(key: &PKey<Private>) -> Result<Value> {
+    let ec = key
+        .ec_key()
+        .context("ACME account key must be an ECDSA P-256 key")?;
+    let mut ctx = BigNumContext::new()?;
+    let mut x = BigNum::new()?;
+    let mut y = BigNum::new()?;
+    ec.public_key()
+        .affine_coordinates(ec.group(), &mut x, &mut y, &mut ctx)?;
+    Ok(json!({
+        "crv": "P-256",
+        "kty": "EC",
+        "x": URL_SAFE_NO_PAD.encode(x.to_vec_padded(32)?),
+        "y": URL_SAFE_NO_PAD.encode(y.to_vec_padded(32)?),
+    }))
+}
+
+fn es256_sign(key: &PKey<Private>, input: &[u8]) -> Result<Vec<u8>> {
+    let ec = key.ec_key()?;
+    let digest = hash(MessageDigest::sha256(), input)?;
+    let sig = EcdsaSig::sign(&digest, &ec)?;
+    let mut out = sig.r().to_vec_padded(32)?;
+    out.extend(sig.s().to_vec_padded(32)?);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{BasicConstraints, CertificateParams, DistinguishedName, DnType, IsCa, KeyPair};
+
+    fn chain_with_root(root_cn: &str) -> String {
+        let mut root_params = CertificateParams::default();
+        let mut root_dn = DistinguishedName::new();
+        root_dn.push(DnType::CommonName, root_cn);
+        root_params.distinguished_name = root_dn;
+        root_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let root_key = KeyPair::generate().expect("root key");
+        let root = root_params.self_signed(&root_key).expect("root cert");
+
+        let mut int_params = CertificateParams::default();
+        let mut int_dn = DistinguishedName::new();
+        int_dn.push(DnType::CommonName, "Test Intermediate");
+        int_params.distinguished_name = int_dn;
+        int_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let int_key = KeyPair::generate().expect("intermediate key");
+        let intermediate = int_params
+            .signed_by(&int_key, &root, &root_key)
+            .expect("intermediate cert");
+
+        let leaf_params =
+            CertificateParams::new(vec!["example.com".to_string()]).expect("leaf params");
+        let leaf_key = KeyPair::generate().expect("leaf key");
+        let leaf = leaf_params
+            .signed_by(&leaf_key, &intermediate, &int_key)
+            .expect("leaf cert");
+
+        format!("{}{}", leaf.pem(), intermediate.pem())
+    }
+
+    #[test]
+    fn reads_root_name_from_topmost_certificate() {
+        let pem = chain_with_root("Test Root X2");
+        assert_eq!(chain_root_name(&pem).as_deref(), Some("Test Root X2"));
+    }
+
+    #[test]
+    fn selects_preferred_chain_or_default() {
+        let chains = vec![
+            CertificateChain::from_pem(chain_with_root("Test Root X1")),
+            CertificateChain::from_pem(chain_with_root("Test Root X2")),
+        ];
+
+        let picked = select_chain(chains.clone(), Some("test root x2")).expect("chain");
+        assert_eq!(picked.name.as_deref(), Some("Test Root X2"));
+
+        let fallback = select_chain(chains, Some("Unknown Root")).expect("chain");
+        assert_eq!(fallback.name.as_deref(), Some("Test Root X1"));
+    }
+
+    #[test]
+    fn parses_alternate_link_headers() {
+        let links = vec![
+            "<https://acme.test/directory>;rel=\"index\"".to_string(),
+            "<https://acme.test/cert/1/1>;rel=\"alternate\", <https://acme.test/cert/1/2>; rel=\"alternate\""
+                .to_string(),
+        ];
+        assert_eq!(
+            alternate_links(&links),
+            vec![
+                "https://acme.test/cert/1/1".to_string(),
+                "https://acme.test/cert/1/2".to_string()
+            ]
+        );
+    }
+}
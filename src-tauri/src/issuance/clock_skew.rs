@@ -0,0 +1,71 @@
+//! System clock sanity check.
+//!
+//! A local clock that is off by minutes produces confusing failures: freshly
+//! issued certificates look "not yet valid" and some CAs reject requests. The
+//! ACME directory response carries an HTTP `Date` header, so comparing it with
+//! the local time before issuance lets the UI warn about a bad clock up front.
+
+use std::time::Instant;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Duration, Utc};
+
+use crate::core::types::ClockSkewCheck;
+use crate::core::user_agent::identify;
+
+/// Skew beyond which issuance is flagged. HTTP dates have one-second
+/// resolution, so small differences are expected.
+pub const CLOCK_SKEW_THRESHOLD_SECS: i64 = 120;
+
+/// Fetches the ACME directory and compares its `Date` header with local time.
+pub fn check_clock_skew(directory_url: &str) -> Result<ClockSkewCheck> {
+    let sent_at = Utc::now();
+    let started = Instant::now();
+    let response = identify(ureq::get(directory_url))
+        .call()
+        .map_err(|err| anyhow!("failed to fetch ACME directory: {err}"))?;
+    let elapsed = Duration::from_std(started.elapsed()).unwrap_or_default();
+    let header = response
+        .header("Date")
+        .ok_or_else(|| anyhow!("ACME directory response has no Date header"))?;
+    let server_time = parse_http_date(header)?;
+    // The server stamped the response somewhere within the round trip.
+    Ok(compare_clocks(sent_at + elapsed / 2, server_time))
+}
+
+fn parse_http_date(raw: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(raw.trim())
+        .map(|date| date.with_timezone(&Utc))
+        .with_context(|| format!("invalid HTTP Date header: {raw}"))
+}
+
+fn compare_clocks(local_time: DateTime<Utc>, server_time: DateTime<Utc>) -> ClockSkewCheck {
+    let skew_secs = (local_time - server_time).num_seconds();
+    ClockSkewCheck {
+        local_time,
+        server_time,
+        skew_secs,
+        threshold_secs: CLOCK_SKEW_THRESHOLD_SECS,
+        exceeds_threshold: skew_secs.abs() > CLOCK_SKEW_THRESHOLD_SECS,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_skew_beyond_threshold() {
+        let server = parse_http_date("Tue, 15 Nov 2022 08:12:31 GMT").unwrap();
+        assert_eq!(server.to_rfc3339(), "2022-11-15T08:12:31+00:00");
+
+        let close = compare_clocks(server + Duration::seconds(3), server);
+        assert_eq!(close.skew_secs, 3);
+        assert!(!close.exceeds_threshold);
+
+        let behind = compare_clocks(server - Duration::minutes(10), server);
+        assert_eq!(behind.skew_secs, -600);
+        assert!(behind.exceeds_threshold);
+        assert!(parse_http_date("yesterday").is_err());
+    }
+}
@@ -3,7 +3,17 @@ use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
 use std::sync::mpsc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+use crate::core::messages::MessageCode;
+use crate::core::types::DomainForms;
+use crate::issuance::dns_wire::TYPE_TXT;
+use crate::issuance::propagation_cache::propagation_cache;
+use crate::issuance::propagation_resolvers::{ResolverResponse, ResolverSet};
+
+pub(crate) const CNAME_RECORD_TYPE: u16 = 5;
+/// Longest CNAME chain followed from a challenge name.
+const MAX_CNAME_HOPS: usize = 8;
 
 /// Represents a DNS-01 challenge request.
 #[derive(Debug, Clone)]
@@ -20,9 +30,19 @@ pub struct DnsRecordInstruction {
     pub record_name: String,
     pub value: String,
     pub zone: String,
+    /// One-time CNAME the user must create when the provider answers the
+    /// challenge outside the zone (acme-dns)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cname_target: Option<String>,
+    /// Challenge name whose CNAME points at `record_name`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delegated_from: Option<String>,
+    /// ASCII and Unicode forms of `record_name`, filled for responses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub record_name_forms: Option<DomainForms>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum PropagationState {
     Pending,
@@ -35,8 +55,66 @@ pub enum PropagationState {
 #[derive(Debug, Clone, Serialize)]
 pub struct DnsPropagationResult {
     pub state: PropagationState,
+    /// English reason; `reason_code` identifies it in the message catalog
     pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason_code: Option<MessageCode>,
+    pub observed_values: Vec<String>,
+    /// Each resolver's own outcome, in the configured order; empty when the
+    /// result does not come from public resolvers
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub resolvers: Vec<ResolverOutcome>,
+}
+
+impl DnsPropagationResult {
+    pub fn new(
+        state: PropagationState,
+        reason_code: Option<MessageCode>,
+        observed_values: Vec<String>,
+    ) -> Self {
+        Self {
+            state,
+            reason: reason_code.map(|code| code.english().to_string()),
+            reason_code,
+            observed_values,
+            resolvers: Vec::new(),
+        }
+    }
+}
+
+/// What one propagation resolver saw, so split-horizon and lagging
+/// resolvers show up instead of being folded into one state.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolverOutcome {
+    pub resolver: String,
+    pub state: PropagationState,
     pub observed_values: Vec<String>,
+    /// Round-trip time of the query, including failed ones
+    pub rtt_ms: u64,
+    /// Why the query failed; `state` is then `error`
+    pub error: Option<String>,
+}
+
+impl ResolverOutcome {
+    pub(crate) fn answered(resolver: &str, result: DnsPropagationResult, rtt_ms: u64) -> Self {
+        Self {
+            resolver: resolver.to_string(),
+            state: result.state,
+            observed_values: result.observed_values,
+            rtt_ms,
+            error: None,
+        }
+    }
+
+    pub(crate) fn failed(resolver: &str, error: String, rtt_ms: u64) -> Self {
+        Self {
+            resolver: resolver.to_string(),
+            state: PropagationState::Error,
+            observed_values: Vec::new(),
+            rtt_ms,
+            error: Some(error),
+        }
+    }
 }
 
 pub trait DnsAdapter: Send + Sync {
@@ -45,10 +123,10 @@ pub trait DnsAdapter: Send + Sync {
     fn cleanup_txt(&self, _req: &DnsChallengeRequest) -> Result<()> {
         Ok(())
     }
-    fn check_propagation(&self, req: &DnsChallengeRequest) -> Result<DnsPropagationResult>;
 }
 
-/// Manual DNS adapter that emits instructions and checks propagation via DNS over HTTPS.
+/// Manual DNS adapter that emits instructions. Propagation is checked with
+/// [`check_txt_record`] against the configured resolvers.
 pub struct ManualDnsAdapter;
 
 impl ManualDnsAdapter {
@@ -72,148 +150,120 @@ pub fn record_name(domain: &str) -> String {
     }
 }
 
-fn query_txt(record_name: &str, expected_value: Option<&str>) -> Result<Vec<GoogleDnsResponse>> {
+/// Follows the CNAME chain at `name` and returns the final target, or None
+/// when `name` is not an alias. `_acme-challenge` names are often delegated
+/// this way to a zone that only holds validation records.
+pub fn follow_cname(name: &str) -> Result<Option<String>> {
+    let mut current = normalize_dns_name(name);
+    let mut seen = vec![current.clone()];
+    while let Some(target) = query_cname(&current)? {
+        if seen.contains(&target) || seen.len() > MAX_CNAME_HOPS {
+            return Err(anyhow!("CNAME chain at {name} loops or is too long"));
+        }
+        debug!("[dns] {current} is an alias for {target}");
+        seen.push(target.clone());
+        current = target;
+    }
+    Ok((seen.len() > 1).then_some(current))
+}
+
+fn query_cname(name: &str) -> Result<Option<String>> {
+    let url = format!("https://dns.google/resolve?name={name}&type=CNAME");
+    let agent = ureq::AgentBuilder::new().timeout(resolve_dns_timeout()).build();
+    let body = agent
+        .get(&url)
+        .set("Accept", "application/dns-json")
+        .call()
+        .map_err(|err| anyhow!("CNAME lookup for {name} failed: {err}"))?
+        .into_string()
+        .map_err(|err| anyhow!("CNAME lookup for {name} failed: {err}"))?;
+    let response: GoogleDnsResponse = serde_json::from_str(&body)
+        .map_err(|err| anyhow!("CNAME lookup for {name} returned invalid JSON: {err}"))?;
+    Ok(cname_answer(&response))
+}
+
+fn cname_answer(response: &GoogleDnsResponse) -> Option<String> {
+    response
+        .answer
+        .as_ref()?
+        .iter()
+        .filter(|ans| ans.is_cname())
+        .find_map(|ans| ans.data.as_deref())
+        .map(normalize_dns_name)
+}
+
+fn normalize_dns_name(name: &str) -> String {
+    name.trim().trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// One resolver's reply to a TXT query, or why the query failed.
+struct ResolverReply {
+    /// Position in the resolver set, so replies are listed in its order
+    index: usize,
+    resolver: String,
+    rtt: Duration,
+    response: Result<GoogleDnsResponse, String>,
+}
+
+/// Queries every resolver in parallel and waits for all of them, so the
+/// result can say how many see the record. Fails only when none answered.
+fn query_txt(record_name: &str, resolvers: &ResolverSet) -> Result<Vec<ResolverReply>> {
     info!(
         "[dns-test] Starting parallel DNS queries for {}",
         record_name
     );
-    let urls = [
-        (
-            "Google DNS",
-            format!("https://dns.google/resolve?name={record_name}&type=TXT&random_padding=x"),
-            Some("application/dns-json"),
-        ),
-        (
-            "Cloudflare DNS",
-            format!("https://cloudflare-dns.com/dns-query?name={record_name}&type=TXT"),
-            Some("application/dns-json"),
-        ),
-    ];
-
     let timeout = resolve_dns_timeout();
     let (tx, rx) = mpsc::channel();
 
     // Spawn parallel queries
-    for (resolver_name, url, accept) in urls {
+    for (index, resolver) in resolvers.resolvers().iter().enumerate() {
         let tx = tx.clone();
-        let url_clone = url.clone();
+        let resolver = resolver.clone();
         let record_name_clone = record_name.to_string();
-        let resolver_name_clone = resolver_name.to_string();
-        let expected_value_clone = expected_value.map(|s| s.to_string());
 
         thread::spawn(move || {
+            let resolver_name = resolver.name();
             info!(
                 "[dns-test] Querying {} for {}",
-                resolver_name_clone, record_name_clone
+                resolver_name, record_name_clone
             );
-            let agent = ureq::AgentBuilder::new().timeout(timeout).build();
-
-            let mut req = agent.get(&url_clone);
-            if let Some(accept) = accept {
-                req = req.set("Accept", accept);
-            }
-
-            let result = match req.call() {
-                Ok(resp) => {
-                    match resp.into_string() {
-                        Ok(body) => {
-                            match serde_json::from_str::<GoogleDnsResponse>(&body) {
-                                Ok(parsed) => {
-                                    info!(
-                                        "[dns-test] {} responded: status={}, has_answer={}",
-                                        resolver_name_clone,
-                                        parsed.status,
-                                        parsed.answer.is_some()
-                                    );
-
-                                    // Check if this response contains the expected value
-                                    if let Some(expected) = &expected_value_clone
-                                        && let Some(answers) = &parsed.answer
-                                    {
-                                        for ans in answers {
-                                            if let Some(data) = &ans.data {
-                                                let trimmed = trim_txt_quotes(data);
-                                                if trimmed == *expected {
-                                                    info!(
-                                                        "[dns-test] {} found expected value!",
-                                                        resolver_name_clone
-                                                    );
-                                                    // Send success immediately
-                                                    let _ =
-                                                        tx.send(Ok((resolver_name_clone, parsed)));
-                                                    return;
-                                                }
-                                            }
-                                        }
-                                    }
-
-                                    Ok((resolver_name_clone, parsed))
-                                }
-                                Err(err) => {
-                                    warn!(
-                                        "[dns-test] {} parse failed for {} via {}: {}",
-                                        resolver_name_clone, record_name_clone, url_clone, err
-                                    );
-                                    Err(anyhow!("parse failed: {}", err))
-                                }
-                            }
-                        }
-                        Err(err) => {
-                            warn!(
-                                "[dns-test] {} body read failed for {}: {}",
-                                resolver_name_clone, record_name_clone, err
-                            );
-                            Err(anyhow!("body read failed: {}", err))
-                        }
-                    }
+            let started = Instant::now();
+            let response = match resolver.query_txt(&record_name_clone, timeout) {
+                Ok(response) => {
+                    let parsed = GoogleDnsResponse::from(response);
+                    info!(
+                        "[dns-test] {} responded: status={}, has_answer={}",
+                        resolver_name,
+                        parsed.status,
+                        parsed.answer.is_some()
+                    );
+                    Ok(parsed)
                 }
                 Err(err) => {
                     warn!(
-                        "[dns-test] {} query failed for {} via {}: {}",
-                        resolver_name_clone, record_name_clone, url_clone, err
+                        "[dns-test] {} query failed for {}: {}",
+                        resolver_name, record_name_clone, err
                     );
-                    Err(anyhow!("query failed: {}", err))
+                    Err(format!("query failed: {err}"))
                 }
             };
 
-            let _ = tx.send(result);
+            let _ = tx.send(ResolverReply {
+                index,
+                resolver: resolver_name,
+                rtt: started.elapsed(),
+                response,
+            });
         });
     }
 
     drop(tx); // Close sender so receiver knows when all threads are done
 
-    let mut results = Vec::new();
-
-    // Collect results, returning early if we find the expected value
-    for received in rx {
-        match received {
-            Ok((resolver_name, response)) => {
-                // Check if this response has the expected value
-                if let Some(expected) = expected_value
-                    && let Some(answers) = &response.answer
-                {
-                    for ans in answers {
-                        if let Some(data) = &ans.data {
-                            let trimmed = trim_txt_quotes(data);
-                            if trimmed == expected {
-                                info!(
-                                    "[dns-test] Found expected value via {}, returning immediately",
-                                    resolver_name
-                                );
-                                return Ok(vec![response]);
-                            }
-                        }
-                    }
-                }
-                results.push(response);
-            }
-            Err(err) => {
-                debug!("[dns-test] One resolver failed: {}", err);
-            }
-        }
-    }
+    let mut replies: Vec<ResolverReply> = rx.into_iter().collect();
+    replies.sort_by_key(|reply| reply.index);
+    let answered = replies.iter().filter(|reply| reply.response.is_ok()).count();
 
-    if results.is_empty() {
+    if answered == 0 {
         warn!("[dns-test] All DNS queries failed for {}", record_name);
         Err(anyhow!(
             "dns query failed for {record_name} across resolvers"
@@ -221,25 +271,31 @@ fn query_txt(record_name: &str, expected_value: Option<&str>) -> Result<Vec<Goog
     } else {
         info!(
             "[dns-test] Collected {} response(s) for {}",
-            results.len(),
+            answered,
             record_name
         );
-        Ok(results)
+        Ok(replies)
     }
 }
 
-pub fn check_txt_record(record_name: &str, expected_value: &str) -> Result<DnsPropagationResult> {
+pub fn check_txt_record(
+    record_name: &str,
+    expected_value: &str,
+    resolvers: &ResolverSet,
+) -> Result<DnsPropagationResult> {
     info!(
         "[dns-test] Checking TXT record {} for value {}",
         record_name, expected_value
     );
-    let responses = query_txt(record_name, Some(expected_value))?;
-    let req = DnsChallengeRequest {
-        domain: record_name.to_string(),
-        value: expected_value.to_string(),
-        zone: None,
-    };
-    let result = interpret_dns_results(&responses, &req);
+    let result = propagation_cache().get_or_check(record_name, expected_value, resolvers, || {
+        let replies = query_txt(record_name, resolvers)?;
+        let req = DnsChallengeRequest {
+            domain: record_name.to_string(),
+            value: expected_value.to_string(),
+            zone: None,
+        };
+        Ok(interpret_replies(&replies, &req))
+    })?;
     info!(
         "[dns-test] DNS check result for {}: state={:?}, observed={:?}",
         record_name, result.state, result.observed_values
@@ -260,24 +316,11 @@ impl DnsAdapter for ManualDnsAdapter {
             record_name,
             value: req.value.clone(),
             zone,
+            cname_target: None,
+            delegated_from: None,
+            record_name_forms: None,
         })
     }
-
-    fn check_propagation(&self, req: &DnsChallengeRequest) -> Result<DnsPropagationResult> {
-        let record_name = record_name(&req.domain);
-        let responses = query_txt(&record_name, Some(&req.value))?;
-        debug!(
-            "[dns] checked {record_name}: statuses={:?} answers={:?}",
-            responses.iter().map(|r| r.status).collect::<Vec<_>>(),
-            responses
-                .iter()
-                .filter_map(|r| r.answer.as_ref())
-                .flatten()
-                .filter_map(|a| a.data.clone())
-                .collect::<Vec<_>>()
-        );
-        Ok(interpret_dns_results(&responses, req))
-    }
 }
 
 fn trim_txt_quotes(value: &str) -> String {
@@ -297,7 +340,7 @@ fn resolve_dns_timeout() -> Duration {
     Duration::from_secs(timeout)
 }
 
-fn derive_zone(hostname: &str) -> String {
+pub(crate) fn derive_zone(hostname: &str) -> String {
     let parts: Vec<&str> = hostname.trim_end_matches('.').split('.').collect();
     if parts.len() >= 2 {
         format!("{}.{}", parts[parts.len() - 2], parts[parts.len() - 1])
@@ -310,6 +353,16 @@ fn derive_zone(hostname: &str) -> String {
 struct GoogleDnsAnswer {
     #[serde(rename = "data")]
     data: Option<String>,
+    #[serde(rename = "type", default)]
+    record_type: Option<u16>,
+}
+
+impl GoogleDnsAnswer {
+    /// Resolvers answer a TXT query at an alias with the CNAME followed by
+    /// the target's TXT records; the CNAME is not a value.
+    fn is_cname(&self) -> bool {
+        self.record_type == Some(CNAME_RECORD_TYPE)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -320,6 +373,50 @@ struct GoogleDnsResponse {
     answer: Option<Vec<GoogleDnsAnswer>>,
 }
 
+impl From<ResolverResponse> for GoogleDnsResponse {
+    fn from(response: ResolverResponse) -> Self {
+        let answers: Vec<GoogleDnsAnswer> = response
+            .values
+            .into_iter()
+            .map(|value| GoogleDnsAnswer {
+                data: Some(value),
+                record_type: Some(TYPE_TXT),
+            })
+            .collect();
+        Self {
+            status: response.status,
+            answer: (!answers.is_empty()).then_some(answers),
+        }
+    }
+}
+
+/// Folds the replies into one result, found when any resolver sees the
+/// value, and attaches each resolver's own outcome.
+fn interpret_replies(
+    replies: &[ResolverReply],
+    req: &DnsChallengeRequest,
+) -> DnsPropagationResult {
+    let responses: Vec<GoogleDnsResponse> = replies
+        .iter()
+        .filter_map(|reply| reply.response.as_ref().ok().cloned())
+        .collect();
+    let mut result = interpret_dns_results(&responses, req);
+    result.resolvers = replies
+        .iter()
+        .map(|reply| {
+            let rtt_ms = reply.rtt.as_millis() as u64;
+            match &reply.response {
+                Ok(response) => {
+                    let own = interpret_dns_results(std::slice::from_ref(response), req);
+                    ResolverOutcome::answered(&reply.resolver, own, rtt_ms)
+                }
+                Err(err) => ResolverOutcome::failed(&reply.resolver, err.clone(), rtt_ms),
+            }
+        })
+        .collect();
+    result
+}
+
 fn interpret_dns_results(
     responses: &[GoogleDnsResponse],
     req: &DnsChallengeRequest,
@@ -330,7 +427,7 @@ fn interpret_dns_results(
 
     for response in responses {
         if let Some(answers) = &response.answer {
-            for ans in answers {
+            for ans in answers.iter().filter(|ans| !ans.is_cname()) {
                 if let Some(data) = &ans.data {
                     observed.push(trim_txt_quotes(data));
                 }
@@ -351,42 +448,38 @@ fn interpret_dns_results(
     }
 
     if observed.iter().any(|val| val == &req.value) {
-        return DnsPropagationResult {
-            state: PropagationState::Found,
-            reason: None,
-            observed_values: observed,
-        };
+        return DnsPropagationResult::new(PropagationState::Found, None, observed);
     }
 
     if !observed.is_empty() {
-        return DnsPropagationResult {
-            state: PropagationState::WrongContent,
-            reason: Some("TXT record present with different value".to_string()),
-            observed_values: observed,
-        };
+        return DnsPropagationResult::new(
+            PropagationState::WrongContent,
+            Some(MessageCode::PropagationWrongContent),
+            observed,
+        );
     }
 
     if saw_ok {
-        return DnsPropagationResult {
-            state: PropagationState::Pending,
-            reason: Some("record not found yet".to_string()),
-            observed_values: observed,
-        };
+        return DnsPropagationResult::new(
+            PropagationState::Pending,
+            Some(MessageCode::PropagationPending),
+            observed,
+        );
     }
 
     if saw_nxdomain {
-        return DnsPropagationResult {
-            state: PropagationState::NxDomain,
-            reason: Some("record not found (NXDOMAIN)".to_string()),
-            observed_values: observed,
-        };
+        return DnsPropagationResult::new(
+            PropagationState::NxDomain,
+            Some(MessageCode::PropagationNxDomain),
+            observed,
+        );
     }
 
-    DnsPropagationResult {
-        state: PropagationState::Error,
-        reason: Some("no responses from DNS resolvers".to_string()),
-        observed_values: observed,
-    }
+    DnsPropagationResult::new(
+        PropagationState::Error,
+        Some(MessageCode::PropagationNoResponses),
+        observed,
+    )
 }
 
 #[cfg(test)]
@@ -407,6 +500,7 @@ mod tests {
             status: 0,
             answer: Some(vec![GoogleDnsAnswer {
                 data: Some("\"expected-value\"".to_string()),
+                record_type: None,
             }]),
         }];
         let result = interpret_dns_results(&responses, &make_req());
@@ -418,12 +512,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn lists_each_resolver_outcome() {
+        let reply = |index: usize, resolver: &str, response| ResolverReply {
+            index,
+            resolver: resolver.to_string(),
+            rtt: Duration::from_millis(20),
+            response,
+        };
+        let replies = vec![
+            reply(
+                0,
+                "Google DNS",
+                Ok(GoogleDnsResponse {
+                    status: 0,
+                    answer: Some(vec![GoogleDnsAnswer {
+                        data: Some("\"expected-value\"".to_string()),
+                        record_type: None,
+                    }]),
+                }),
+            ),
+            reply(1, "Cloudflare DNS", Ok(GoogleDnsResponse { status: 3, answer: None })),
+            reply(2, "DNS server 10.0.0.53:53", Err("query failed: timed out".to_string())),
+        ];
+        let result = interpret_replies(&replies, &make_req());
+        assert_eq!(result.state, PropagationState::Found);
+
+        let states: Vec<_> = result.resolvers.iter().map(|outcome| &outcome.state).collect();
+        assert_eq!(
+            states,
+            [&PropagationState::Found, &PropagationState::NxDomain, &PropagationState::Error]
+        );
+        assert_eq!(result.resolvers[0].observed_values, ["expected-value"]);
+        assert_eq!(result.resolvers[1].rtt_ms, 20);
+        assert_eq!(result.resolvers[2].error.as_deref(), Some("query failed: timed out"));
+    }
+
     #[test]
     fn interprets_wrong_content_when_value_differs() {
         let responses = vec![GoogleDnsResponse {
             status: 0,
             answer: Some(vec![GoogleDnsAnswer {
                 data: Some("\"other-value\"".to_string()),
+                record_type: None,
             }]),
         }];
         let result = interpret_dns_results(&responses, &make_req());
@@ -452,6 +583,7 @@ mod tests {
                 status: 0,
                 answer: Some(vec![GoogleDnsAnswer {
                     data: Some("\"expected-value\"".to_string()),
+                    record_type: None,
                 }]),
             },
         ];
@@ -469,6 +601,30 @@ mod tests {
         assert!(matches!(result.state, PropagationState::Pending));
     }
 
+    #[test]
+    fn cname_answers_are_followed_not_observed() {
+        let response = GoogleDnsResponse {
+            status: 0,
+            answer: Some(vec![
+                GoogleDnsAnswer {
+                    data: Some("_acme-challenge.Test.validation.example.net.".to_string()),
+                    record_type: Some(CNAME_RECORD_TYPE),
+                },
+                GoogleDnsAnswer {
+                    data: Some("\"stale-value\"".to_string()),
+                    record_type: Some(16),
+                },
+            ]),
+        };
+        assert_eq!(
+            cname_answer(&response).as_deref(),
+            Some("_acme-challenge.test.validation.example.net")
+        );
+        let result = interpret_dns_results(&[response], &make_req());
+        assert!(matches!(result.state, PropagationState::WrongContent));
+        assert_eq!(result.observed_values, vec!["stale-value".to_string()]);
+    }
+
     #[test]
     fn record_name_adds_acme_prefix() {
         assert_eq!(
@@ -491,13 +647,13 @@ mod tests {
     #[ignore]
     fn resolves_live_txt_for_ezs3_net() -> Result<()> {
         let record_name = record_name("test.ezs3.net");
-        let responses = query_txt(&record_name, None)?;
+        let replies = query_txt(&record_name, &ResolverSet::default())?;
         let req = DnsChallengeRequest {
             domain: "test.ezs3.net".into(),
             value: "cj9WcLQwCB5xDkgRQ312yXLGko4p9WY-oQjML_T7DIQ".into(),
             zone: None,
         };
-        let result = interpret_dns_results(&responses, &req);
+        let result = interpret_replies(&replies, &req);
         assert!(
             matches!(result.state, PropagationState::Found),
             "expected Found, got {:?} with observed {:?}",
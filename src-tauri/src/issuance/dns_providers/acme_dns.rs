@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result, anyhow};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    DnsProviderAdapter,
+    debug::DebugCapture,
+    http::{self, SendCaptured},
+    matches_zone,
+    testing::resolve_dns_timeout,
+};
+
+const CHALLENGE_PREFIX: &str = "_acme-challenge.";
+/// DNS RR type code for CNAME records.
+const CNAME_RECORD_TYPE: u16 = 5;
+
+#[derive(Deserialize)]
+struct CnameQueryResponse {
+    #[serde(rename = "Answer", default)]
+    answer: Vec<CnameQueryAnswer>,
+}
+
+#[derive(Deserialize)]
+struct CnameQueryAnswer {
+    #[serde(rename = "type")]
+    record_type: u16,
+    data: String,
+}
+
+/// Credentials returned by acme-dns `/register` for one domain.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AcmeDnsRegistration {
+    pub username: String,
+    pub password: String,
+    pub fulldomain: String,
+    pub subdomain: String,
+    #[serde(default)]
+    pub allowfrom: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct AcmeDnsUpdate<'a> {
+    subdomain: &'a str,
+    txt: &'a str,
+}
+
+/// Adapter for an acme-dns server. Instead of writing into the user's zone,
+/// each domain's `_acme-challenge` name is CNAMEd once to a subdomain on the
+/// acme-dns server, and challenge values are posted there.
+///
+/// acme-dns keeps the two most recent values per subdomain and has no delete
+/// call, so cleanup is a no-op.
+pub struct AcmeDnsAdapter {
+    api_url: String,
+    registrations: HashMap<String, AcmeDnsRegistration>,
+    domain_suffix: String,
+    debug: Option<DebugCapture>,
+}
+
+impl AcmeDnsAdapter {
+    pub fn new(
+        api_url: &str,
+        registrations: HashMap<String, AcmeDnsRegistration>,
+        domain_suffix: String,
+    ) -> Self {
+        Self {
+            api_url: api_url.trim().trim_end_matches('/').to_string(),
+            registrations,
+            domain_suffix,
+            debug: None,
+        }
+    }
+
+    /// Records API calls for the provider's debug log.
+    pub fn with_debug_capture(mut self, debug: Option<DebugCapture>) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    /// Registration for `domain`, falling back to the one of the closest
+    /// registered parent domain.
+    fn registration_for(&self, domain: &str) -> Option<&AcmeDnsRegistration> {
+        let domain = domain.trim_start_matches("*.");
+        self.registrations
+            .iter()
+            .filter(|(registered, _)| matches_zone(domain, registered))
+            .max_by_key(|(registered, _)| registered.len())
+            .map(|(_, registration)| registration)
+    }
+
+    fn challenge_registration(&self, record_name: &str) -> Result<&AcmeDnsRegistration> {
+        let domain = record_name.strip_prefix(CHALLENGE_PREFIX).ok_or_else(|| {
+            anyhow!("acme-dns only serves _acme-challenge records, not {record_name}")
+        })?;
+        self.registration_for(domain)
+            .ok_or_else(|| anyhow!("No acme-dns registration for {domain}"))
+    }
+
+    /// acme-dns name that `record_name` must be CNAMEd to.
+    fn delegation_target(&self, record_name: &str) -> Option<String> {
+        self.challenge_registration(record_name)
+            .ok()
+            .map(|registration| registration.fulldomain.clone())
+    }
+}
+
+/// Whether public DNS already has `record_name` CNAMEd to `target`. Lookup
+/// failures count as missing so the instruction is shown rather than hidden.
+fn cname_in_place(record_name: &str, target: &str) -> bool {
+    info!("[dns] Querying CNAME for {record_name}");
+    let agent = ureq::AgentBuilder::new()
+        .timeout(resolve_dns_timeout())
+        .build();
+    let response = agent
+        .get(&format!("https://dns.google/resolve?name={record_name}&type=CNAME"))
+        .set("Accept", "application/dns-json")
+        .call()
+        .map_err(anyhow::Error::from)
+        .and_then(|response| Ok(response.into_string()?))
+        .and_then(|body| Ok(serde_json::from_str::<CnameQueryResponse>(&body)?));
+    match response {
+        Ok(response) => response.answer.iter().any(|answer| {
+            answer.record_type == CNAME_RECORD_TYPE
+                && answer.data.trim_end_matches('.').eq_ignore_ascii_case(target)
+        }),
+        Err(err) => {
+            warn!("[dns] CNAME lookup for {record_name} failed: {err}");
+            false
+        }
+    }
+}
+
+/// Parses registrations in the JSON layout used by acme-dns clients: an
+/// object keyed by domain holding each `/register` response.
+pub fn parse_acme_dns_registrations(raw: &str) -> Result<HashMap<String, AcmeDnsRegistration>> {
+    let parsed: HashMap<String, AcmeDnsRegistration> =
+        serde_json::from_str(raw.trim()).context("Invalid acme-dns registrations")?;
+    if parsed.is_empty() {
+        return Err(anyhow!("acme-dns registrations are empty"));
+    }
+    Ok(parsed
+        .into_iter()
+        .map(|(domain, registration)| {
+            let domain = domain.trim().trim_start_matches("*.").trim_end_matches('.');
+            (domain.to_ascii_lowercase(), registration)
+        })
+        .collect())
+}
+
+/// Creates a new account on the acme-dns server at `api_url`.
+pub fn register_acme_dns_account(api_url: &str) -> Result<AcmeDnsRegistration> {
+    let url = format!("{}/register", api_url.trim().trim_end_matches('/'));
+    let response = http::HttpClient::shared()
+        .post(url)
+        .send_captured(None)
+        .context("Failed to register with acme-dns")?;
+
+    if !response.status.is_success() {
+        let status = response.status;
+        let body = response.body;
+        return Err(http::status_error("acme-dns", status, Some(body)));
+    }
+
+    response
+        .json()
+        .context("Failed to parse acme-dns registration")
+}
+
+impl DnsProviderAdapter for AcmeDnsAdapter {
+    fn create_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        let registration = self.challenge_registration(record_name)?;
+        let response = http::HttpClient::shared()
+            .post(format!("{}/update", self.api_url))
+            .header("X-Api-User", &registration.username)
+            .header("X-Api-Key", &registration.password)
+            .json(&AcmeDnsUpdate {
+                subdomain: &registration.subdomain,
+                txt: value,
+            })
+            .send_captured(self.debug.as_ref())
+            .context("Failed to update acme-dns record")?;
+
+        if !response.status.is_success() {
+            let status = response.status;
+            let body = response.body;
+            return Err(http::status_error("acme-dns", status, Some(body)));
+        }
+        Ok(())
+    }
+
+    fn cleanup_txt(&self, _record_name: &str) -> Result<()> {
+        // Old values rotate out as new ones are posted
+        Ok(())
+    }
+
+    fn verify_zone_access(&self) -> Result<()> {
+        if self.registration_for(&self.domain_suffix).is_none() {
+            return Err(anyhow!(
+                "No acme-dns registration for domain suffix: {}",
+                self.domain_suffix
+            ));
+        }
+        let response = http::HttpClient::shared()
+            .get(format!("{}/health", self.api_url))
+            .send_captured(self.debug.as_ref())
+            .context("Failed to reach acme-dns server")?;
+        if !response.status.is_success() {
+            let status = response.status;
+            let body = response.body;
+            return Err(http::status_error("acme-dns", status, Some(body)));
+        }
+        Ok(())
+    }
+
+    fn list_zones(&self) -> Result<Vec<String>> {
+        let mut domains: Vec<String> = self.registrations.keys().cloned().collect();
+        domains.sort();
+        Ok(domains)
+    }
+
+    fn cname_target(&self, record_name: &str) -> Option<String> {
+        self.delegation_target(record_name)
+            .filter(|target| !cname_in_place(record_name, target))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_registrations_and_finds_challenge_targets() {
+        let registrations = parse_acme_dns_registrations(
+            r#"{
+                "Example.com": {
+                    "username": "u1", "password": "p1",
+                    "fulldomain": "d420c923.auth.example.org", "subdomain": "d420c923"
+                },
+                "*.shop.example.com": {
+                    "username": "u2", "password": "p2",
+                    "fulldomain": "8e5700ea.auth.example.org", "subdomain": "8e5700ea",
+                    "allowfrom": []
+                }
+            }"#,
+        )
+        .unwrap();
+        let adapter = AcmeDnsAdapter::new(
+            "https://auth.example.org/",
+            registrations,
+            "example.com".to_string(),
+        );
+
+        assert_eq!(
+            adapter.delegation_target("_acme-challenge.example.com").as_deref(),
+            Some("d420c923.auth.example.org")
+        );
+        assert_eq!(
+            adapter.delegation_target("_acme-challenge.www.example.com").as_deref(),
+            Some("d420c923.auth.example.org")
+        );
+        assert_eq!(
+            adapter.delegation_target("_acme-challenge.shop.example.com").as_deref(),
+            Some("8e5700ea.auth.example.org")
+        );
+        assert!(adapter.delegation_target("_acme-challenge.other.com").is_none());
+        assert!(adapter.create_txt("_sslboard-test.example.com", "value").is_err());
+        assert!(parse_acme_dns_registrations("{}").is_err());
+    }
+}
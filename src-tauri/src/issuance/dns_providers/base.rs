@@ -1,9 +1,10 @@
-use anyhow::{Result, anyhow};
+use anyhow::Result;
 use log::{debug, warn};
 use std::thread;
 use std::time::{Duration, Instant};
 
 use crate::issuance::dns::{DnsPropagationResult, PropagationState};
+use crate::issuance::propagation_resolvers::ResolverSet;
 
 use super::testing;
 
@@ -46,23 +47,47 @@ pub struct DnsRecord {
     pub value: String,
 }
 
+/// Record ID for one value of an RRset, which has no ID of its own.
+pub(crate) fn rrset_record_id(record_name: &str, value: &str) -> String {
+    format!("{record_name} {value}")
+}
+
+/// Splits an ID from [`rrset_record_id`] into record name and value.
+pub(crate) fn parse_rrset_record_id(record_id: &str) -> Result<(&str, &str)> {
+    record_id
+        .split_once(' ')
+        .ok_or_else(|| anyhow::anyhow!("Invalid RRset record ID: {}", record_id))
+}
+
 /// Trait for atomic DNS operations that each provider must implement.
 /// These are low-level operations that interact directly with the provider's API.
+///
+/// A "record" is one TXT value. Providers that manage values as RRsets (all
+/// values of a name in one set, e.g. deSEC) map these operations onto the set:
+/// creating merges the value into the RRset, deleting removes only that value,
+/// and listing returns one record per value.
+///
+/// Operations take `&self` so one adapter can serve every create, check and
+/// cleanup of an issuance; adapters keep their zone lookups in interior-mutable
+/// caches.
 pub trait AtomicDnsOperations: Send + Sync {
     /// Creates a single TXT record and returns its ID.
     /// The implementation should handle zone discovery internally if needed.
-    fn create_one_record(&mut self, record_name: &str, value: &str) -> Result<String>;
+    /// Other values already published at the name must be kept.
+    fn create_one_record(&self, record_name: &str, value: &str) -> Result<String>;
 
-    /// Deletes a single TXT record by its ID.
-    fn delete_one_record(&mut self, record_id: &str) -> Result<()>;
+    /// Deletes a single TXT record by its ID, leaving other values at the
+    /// same name in place.
+    fn delete_one_record(&self, record_id: &str) -> Result<()>;
 
     /// Lists all TXT records matching the given record name.
-    /// Returns a vector of records with their IDs, names, and values.
-    fn list_records(&mut self, record_name: &str) -> Result<Vec<DnsRecord>>;
+    /// Returns a vector of records with their IDs, names, and values; RRset
+    /// providers return one entry per value.
+    fn list_records(&self, record_name: &str) -> Result<Vec<DnsRecord>>;
 
     /// Gets the zone ID for a given domain.
     /// Implementations should cache this value to avoid repeated API calls.
-    fn get_zone_id(&mut self, domain: &str) -> Result<String>;
+    fn get_zone_id(&self, domain: &str) -> Result<String>;
 
     /// Normalizes a TXT record value for comparison.
     /// Each provider may format values differently (with/without quotes, etc.),
@@ -78,12 +103,12 @@ pub trait AtomicDnsOperations: Send + Sync {
 /// Provides default implementations that use AtomicDnsOperations and handle
 /// retrying, parallelization, and DNS testing.
 pub trait DnsProviderBase: Send + Sync {
-    /// Returns a mutable reference to the atomic operations implementation.
-    fn atomic_ops(&mut self) -> &mut dyn AtomicDnsOperations;
+    /// Returns the atomic operations implementation.
+    fn atomic_ops(&self) -> &dyn AtomicDnsOperations;
 
     /// Sets a single TXT record with retry logic.
     /// This is a convenience method that calls set_txt_records with a single record.
-    fn set_txt_record(&mut self, record_name: &str, value: &str) -> Result<()> {
+    fn set_txt_record(&self, record_name: &str, value: &str) -> Result<()> {
         self.set_txt_records(vec![(record_name.to_string(), value.to_string())])
     }
 
@@ -91,7 +116,7 @@ pub trait DnsProviderBase: Send + Sync {
     /// Each record is created independently, and failures are collected.
     /// Note: For now, this processes records sequentially. Implementations can override
     /// this method to provide true parallelization if they can clone their state.
-    fn set_txt_records(&mut self, records: Vec<(String, String)>) -> Result<()> {
+    fn set_txt_records(&self, records: Vec<(String, String)>) -> Result<()> {
         if records.is_empty() {
             return Ok(());
         }
@@ -112,11 +137,11 @@ pub trait DnsProviderBase: Send + Sync {
         }
 
         if !errors.is_empty() {
-            return Err(anyhow!(
-                "Failed to set {} record(s): {}",
-                errors.len(),
-                errors[0].1
-            ));
+            // Keep the first error as the source so its provider error stays downcastable.
+            let count = errors.len();
+            let (_, first) = errors.swap_remove(0);
+            let message = format!("Failed to set {} record(s): {}", count, first);
+            return Err(first.context(message));
         }
 
         Ok(())
@@ -124,7 +149,7 @@ pub trait DnsProviderBase: Send + Sync {
 
     /// Sets a single TXT record with retry logic and existence checking.
     /// This is the internal method that handles retries and checks for existing records.
-    fn set_txt_record_with_retry(&mut self, record_name: &str, value: &str) -> Result<()> {
+    fn set_txt_record_with_retry(&self, record_name: &str, value: &str) -> Result<()> {
         // Check if record already exists with correct value
         let normalized_value = self.atomic_ops().normalize_value(value);
         match self.atomic_ops().list_records(record_name) {
@@ -155,15 +180,28 @@ pub trait DnsProviderBase: Send + Sync {
         )
     }
 
-    /// Tests if a TXT record is visible via Google DNS with retry logic.
-    /// Uses Google DNS HTTPS API to check for record propagation.
+    /// Makes `value` the only TXT value at `record_name`: other values are
+    /// deleted first, and a matching value is kept rather than recreated.
+    fn replace_txt_record(&self, record_name: &str, value: &str) -> Result<()> {
+        let ops = self.atomic_ops();
+        let normalized_value = ops.normalize_value(value);
+        for record in ops.list_records(record_name)? {
+            if ops.normalize_value(&record.value) != normalized_value {
+                ops.delete_one_record(&record.id)?;
+            }
+        }
+        self.set_txt_record_with_retry(record_name, value)
+    }
+
+    /// Tests if a TXT record is visible via `resolvers` with retry logic.
     /// Uses provider-specific normalization for value comparison.
     fn test_txt_record(
-        &mut self,
+        &self,
         record_name: &str,
         expected_value: &str,
         timeout: Duration,
         interval: Duration,
+        resolvers: &ResolverSet,
     ) -> Result<DnsPropagationResult> {
         let started = Instant::now();
         let mut attempt = 0;
@@ -181,6 +219,7 @@ pub trait DnsProviderBase: Send + Sync {
                 record_name,
                 &normalized_expected,
                 self.atomic_ops(),
+                resolvers,
             ) {
                 Ok(result) => match result.state {
                     PropagationState::Found => {
@@ -247,7 +286,7 @@ pub trait DnsProviderBase: Send + Sync {
 
     /// Deletes multiple TXT records.
     /// First lists all records matching the record names, then deletes them in parallel.
-    fn delete_txt_records(&mut self, record_names: Vec<String>) -> Result<()> {
+    fn delete_txt_records(&self, record_names: Vec<String>) -> Result<()> {
         if record_names.is_empty() {
             return Ok(());
         }
@@ -290,11 +329,10 @@ pub trait DnsProviderBase: Send + Sync {
         }
 
         if !errors.is_empty() {
-            return Err(anyhow!(
-                "Failed to delete {} record(s): {}",
-                errors.len(),
-                errors[0].2
-            ));
+            let count = errors.len();
+            let (_, _, first) = errors.swap_remove(0);
+            let message = format!("Failed to delete {} record(s): {}", count, first);
+            return Err(first.context(message));
         }
 
         Ok(())
@@ -302,7 +340,77 @@ pub trait DnsProviderBase: Send + Sync {
 
     /// Deletes a single TXT record by name.
     /// This is a convenience method that calls delete_txt_records with a single name.
-    fn delete_txt_record(&mut self, record_name: &str) -> Result<()> {
+    fn delete_txt_record(&self, record_name: &str) -> Result<()> {
         self.delete_txt_records(vec![record_name.to_string()])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// RRset-style provider keeping every value in memory.
+    #[derive(Default)]
+    struct MemoryRrset {
+        values: Mutex<Vec<(String, String)>>,
+    }
+
+    impl AtomicDnsOperations for MemoryRrset {
+        fn create_one_record(&self, record_name: &str, value: &str) -> Result<String> {
+            let mut values = self.values.lock().unwrap();
+            values.push((record_name.to_string(), value.to_string()));
+            Ok(rrset_record_id(record_name, value))
+        }
+
+        fn delete_one_record(&self, record_id: &str) -> Result<()> {
+            let (record_name, value) = parse_rrset_record_id(record_id)?;
+            let mut values = self.values.lock().unwrap();
+            values.retain(|(name, v)| name != record_name || v != value);
+            Ok(())
+        }
+
+        fn list_records(&self, record_name: &str) -> Result<Vec<DnsRecord>> {
+            let values = self.values.lock().unwrap();
+            Ok(values
+                .iter()
+                .filter(|(name, _)| name == record_name)
+                .map(|(name, value)| DnsRecord {
+                    id: rrset_record_id(name, value),
+                    name: name.clone(),
+                    value: value.clone(),
+                })
+                .collect())
+        }
+
+        fn get_zone_id(&self, _domain: &str) -> Result<String> {
+            Ok("zone".to_string())
+        }
+    }
+
+    impl DnsProviderBase for MemoryRrset {
+        fn atomic_ops(&self) -> &dyn AtomicDnsOperations {
+            self
+        }
+    }
+
+    fn values(provider: &MemoryRrset, record_name: &str) -> Vec<String> {
+        let records = provider.list_records(record_name).unwrap();
+        records.into_iter().map(|record| record.value).collect()
+    }
+
+    #[test]
+    fn adds_values_next_to_existing_ones_and_replaces_on_request() {
+        let provider = MemoryRrset::default();
+        let name = "_acme-challenge.example.com";
+        provider.set_txt_record(name, "apex").unwrap();
+        provider.set_txt_record(name, "wildcard").unwrap();
+        provider.set_txt_record(name, "\"apex\"").unwrap();
+        assert_eq!(values(&provider, name), ["apex", "wildcard"]);
+
+        provider.replace_txt_record(name, "wildcard").unwrap();
+        assert_eq!(values(&provider, name), ["wildcard"]);
+        provider.replace_txt_record(name, "fresh").unwrap();
+        assert_eq!(values(&provider, name), ["fresh"]);
+    }
+}
@@ -1,30 +1,75 @@
+use std::sync::OnceLock;
+
 use anyhow::{Context, Result, anyhow};
+use reqwest::StatusCode;
+use reqwest::blocking::RequestBuilder;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    DnsProviderAdapter,
+    DnsProviderAdapter, TxtRecord,
     base::{AtomicDnsOperations, DnsProviderBase, DnsRecord},
-    http, matches_zone,
+    debug::DebugCapture,
+    errors::{CloudflareApiError, MissingScopes, ProviderError},
+    http::{self, SendCaptured},
+    matches_zone,
+    zone_cache::ZoneCache,
 };
+use crate::core::types::DnsProviderErrorCategory;
 
 pub struct CloudflareAdapter {
     api_token: String,
-    zone_cache: Option<String>,
+    zone_cache: OnceLock<String>,
+    shared_zones: Option<ZoneCache>,
     domain_suffix: String,
+    record_ttl: Option<u32>,
+    debug: Option<DebugCapture>,
 }
 
 #[derive(Deserialize)]
 struct CloudflareZone {
     id: String,
     name: String,
+    /// Account owning the zone
+    #[serde(default)]
+    account: Option<CloudflareAccount>,
+}
+
+#[derive(Deserialize)]
+struct CloudflareAccount {
+    id: String,
+    #[serde(default)]
+    name: String,
 }
 
 #[derive(Deserialize)]
 struct CloudflareZoneListResponse {
     result: Vec<CloudflareZone>,
     success: bool,
+    #[serde(default)]
+    errors: Vec<CloudflareApiError>,
+    #[serde(default)]
+    result_info: Option<CloudflareResultInfo>,
+}
+
+#[derive(Deserialize)]
+struct CloudflareResultInfo {
+    #[serde(default)]
+    total_pages: u32,
 }
 
+const ZONES_URL: &str = "https://api.cloudflare.com/client/v4/zones";
+/// Largest page size the zones endpoint accepts.
+const ZONES_PER_PAGE: u32 = 50;
+/// Stops runaway paging if `total_pages` is missing or wrong.
+const MAX_ZONE_PAGES: u32 = 200;
+const RECORDS_PER_PAGE: u32 = 100;
+
+/// Token permissions issuance needs on the zone, named as in Cloudflare's token editor.
+const DNS_READ_SCOPE: &str = "DNS:Read";
+const DNS_EDIT_SCOPE: &str = "DNS:Edit";
+/// Well-formed record id no record has; deleting it changes nothing.
+const PROBE_RECORD_ID: &str = "00000000000000000000000000000000";
+
 #[derive(Serialize)]
 struct CloudflareDnsRecord {
     #[serde(rename = "type")]
@@ -38,31 +83,59 @@ struct CloudflareDnsRecord {
 struct CloudflareDnsRecordResponse {
     result: Option<CloudflareDnsRecordResult>,
     success: bool,
-    errors: Option<Vec<CloudflareError>>,
+    #[serde(default)]
+    errors: Vec<CloudflareApiError>,
 }
 
 #[derive(Deserialize)]
 struct CloudflareDnsRecordResult {
     id: String,
     #[serde(default)]
+    name: String,
+    #[serde(default)]
     content: Option<String>,
 }
 
 #[derive(Deserialize)]
-struct CloudflareError {
-    code: u32,
-    message: String,
+struct CloudflareDnsRecordPage {
+    result: Vec<CloudflareDnsRecordResult>,
+    success: bool,
+    #[serde(default)]
+    errors: Vec<CloudflareApiError>,
+    #[serde(default)]
+    result_info: Option<CloudflareResultInfo>,
 }
 
 impl CloudflareAdapter {
     pub fn new(api_token: String, domain_suffix: String) -> Self {
         Self {
             api_token,
-            zone_cache: None,
+            zone_cache: OnceLock::new(),
+            shared_zones: None,
             domain_suffix,
+            record_ttl: None,
+            debug: None,
         }
     }
 
+    /// Overrides the TTL of created TXT records.
+    pub fn with_record_ttl(mut self, ttl: Option<u32>) -> Self {
+        self.record_ttl = ttl;
+        self
+    }
+
+    /// Records API calls for the provider's debug log.
+    pub fn with_debug_capture(mut self, debug: Option<DebugCapture>) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    /// Shares discovered zones with later adapters of the same provider.
+    pub fn with_zone_cache(mut self, zones: Option<ZoneCache>) -> Self {
+        self.shared_zones = zones;
+        self
+    }
+
     fn format_txt_content(value: &str) -> String {
         let trimmed = value.trim();
         if trimmed.starts_with('"') && trimmed.ends_with('"') {
@@ -72,26 +145,38 @@ impl CloudflareAdapter {
         }
     }
 
-    fn discover_zone_id(&mut self) -> Result<String> {
-        if let Some(ref zone_id) = self.zone_cache {
-            return Ok(zone_id.clone());
+    /// Every zone the token can see, following `result_info` across pages.
+    fn fetch_zones(&self) -> Result<Vec<CloudflareZone>> {
+        let mut zones = Vec::new();
+        for page in 1..=MAX_ZONE_PAGES {
+            let (mut batch, total_pages) =
+                self.fetch_zone_page(&[("page", page.to_string())])?;
+            let done = batch.is_empty() || page >= total_pages;
+            zones.append(&mut batch);
+            if done {
+                return Ok(zones);
+            }
         }
+        log::warn!("[dns] Cloudflare zone listing stopped after {MAX_ZONE_PAGES} pages");
+        Ok(zones)
+    }
 
+    /// One page of `/zones` plus the reported page count.
+    fn fetch_zone_page(&self, query: &[(&str, String)]) -> Result<(Vec<CloudflareZone>, u32)> {
         let client = http::HttpClient::shared();
         let response = client
-            .get("https://api.cloudflare.com/client/v4/zones")
+            .get(ZONES_URL)
+            .query(&[("per_page", ZONES_PER_PAGE.to_string())])
+            .query(query)
             .header("Authorization", format!("Bearer {}", self.api_token))
             .header("Content-Type", "application/json")
-            .send()
+            .send_captured(self.debug.as_ref())
             .context("Failed to list Cloudflare zones")?;
 
-        if !response.status().is_success() {
-            if response.status() == 401 || response.status() == 403 {
-                return Err(anyhow!(
-                    "Cloudflare authentication failed: invalid API token"
-                ));
-            }
-            return Err(http::status_error("Cloudflare", response.status(), None));
+        if !response.status.is_success() {
+            let status = response.status;
+            let body = response.body;
+            return Err(http::status_error("Cloudflare", status, Some(body)));
         }
 
         let zone_list: CloudflareZoneListResponse = response
@@ -99,23 +184,74 @@ impl CloudflareAdapter {
             .context("Failed to parse Cloudflare zone list response")?;
 
         if !zone_list.success {
-            return Err(anyhow!("Cloudflare API returned unsuccessful response"));
+            return Err(ProviderError::cloudflare(None, &zone_list.errors).into());
         }
+        let total_pages = zone_list.result_info.map_or(1, |info| info.total_pages);
+        Ok((zone_list.result, total_pages))
+    }
 
-        // Find zone matching domain suffix
-        let zone = zone_list
-            .result
-            .iter()
-            .find(|z| matches_zone(&self.domain_suffix, &z.name))
-            .ok_or_else(|| {
-                anyhow!(
-                    "No Cloudflare zone found for domain suffix: {}",
-                    self.domain_suffix
-                )
-            })?;
+    /// Zone for the suffix, from this adapter's or the provider's cache when
+    /// it was found before.
+    fn discover_zone_id(&self) -> Result<String> {
+        if let Some(zone_id) = self.zone_cache.get() {
+            return Ok(zone_id.clone());
+        }
+        let shared = self.shared_zones.as_ref();
+        let zone_id = match shared.and_then(|zones| zones.get(&self.domain_suffix)) {
+            Some(zone_id) => zone_id,
+            None => {
+                let zone_id = self.lookup_zone_id()?;
+                if let Some(zones) = shared {
+                    zones.put(&self.domain_suffix, &zone_id);
+                }
+                zone_id
+            }
+        };
+        Ok(self.zone_cache.get_or_init(|| zone_id).clone())
+    }
+
+    /// Looks the zone up by name, most specific candidate first, so large
+    /// accounts need one small request per label instead of a full listing.
+    fn lookup_zone_id(&self) -> Result<String> {
+        for name in candidate_zone_names(&self.domain_suffix) {
+            let (zones, _) = self.fetch_zone_page(&[("name", name.clone())])?;
+            if let Some(zone) = zones
+                .into_iter()
+                .filter(|zone| matches_zone(&self.domain_suffix, &zone.name))
+                .max_by_key(|zone| zone.name.len())
+            {
+                return Ok(zone.id);
+            }
+        }
+
+        Err(anyhow!(
+            "No Cloudflare zone found for domain suffix: {} (the token needs Zone:Read on it)",
+            self.domain_suffix
+        ))
+    }
+
+    /// DNS permissions the token lacks on the zone, probed without changing
+    /// it: listing one record needs DNS:Read, and deleting a record that does
+    /// not exist answers "not found" only to tokens with DNS:Edit.
+    fn missing_dns_scopes(&self, zone_id: &str) -> Result<Vec<&'static str>> {
+        let client = http::HttpClient::shared();
+        let records_url = format!("{ZONES_URL}/{zone_id}/dns_records");
+        let mut missing = Vec::new();
+        if !self.permitted(client.get(&records_url).query(&[("per_page", "1")]))? {
+            missing.push(DNS_READ_SCOPE);
+        }
+        if !self.permitted(client.delete(format!("{records_url}/{PROBE_RECORD_ID}")))? {
+            missing.push(DNS_EDIT_SCOPE);
+        }
+        Ok(missing)
+    }
 
-        self.zone_cache = Some(zone.id.clone());
-        Ok(zone.id.clone())
+    fn permitted(&self, request: RequestBuilder) -> Result<bool> {
+        let response = request
+            .header("Authorization", format!("Bearer {}", self.api_token))
+            .send_captured(self.debug.as_ref())
+            .context("Failed to check Cloudflare token permissions")?;
+        probe_outcome(response.status, &response.body)
     }
 
     fn list_existing_txt_records(
@@ -131,17 +267,22 @@ impl CloudflareAdapter {
             ))
             .header("Authorization", format!("Bearer {}", self.api_token))
             .header("Content-Type", "application/json")
-            .send()
+            .send_captured(self.debug.as_ref())
             .context("Failed to list Cloudflare DNS records")?;
 
-        if !response.status().is_success() {
-            return Err(anyhow!("Failed to list Cloudflare DNS records"));
+        if !response.status.is_success() {
+            let status = response.status;
+            let body = response.body;
+            return Err(http::status_error("Cloudflare", status, Some(body))
+                .context("Failed to list Cloudflare DNS records"));
         }
 
         #[derive(Deserialize)]
         struct CloudflareDnsRecordListResponse {
             result: Vec<CloudflareDnsRecordResult>,
             success: bool,
+            #[serde(default)]
+            errors: Vec<CloudflareApiError>,
         }
 
         let list_result: CloudflareDnsRecordListResponse = response
@@ -149,15 +290,57 @@ impl CloudflareAdapter {
             .context("Failed to parse Cloudflare DNS record list")?;
 
         if !list_result.success {
-            return Err(anyhow!("Cloudflare API returned unsuccessful response"));
+            return Err(ProviderError::cloudflare(None, &list_result.errors).into());
         }
 
         Ok(list_result.result)
     }
 
+    /// Every TXT record in the zone, following `result_info` across pages.
+    fn fetch_zone_txt_records(&self) -> Result<Vec<TxtRecord>> {
+        let zone_id = self.discover_zone_id()?;
+        let client = http::HttpClient::shared();
+        let mut records = Vec::new();
+        for page in 1..=MAX_ZONE_PAGES {
+            let response = client
+                .get(format!("{ZONES_URL}/{zone_id}/dns_records"))
+                .query(&[
+                    ("type", "TXT".to_string()),
+                    ("per_page", RECORDS_PER_PAGE.to_string()),
+                    ("page", page.to_string()),
+                ])
+                .header("Authorization", format!("Bearer {}", self.api_token))
+                .send_captured(self.debug.as_ref())
+                .context("Failed to list Cloudflare DNS records")?;
+
+            if !response.status.is_success() {
+                let status = response.status;
+                let body = response.body;
+                return Err(http::status_error("Cloudflare", status, Some(body)));
+            }
+            let list: CloudflareDnsRecordPage = response
+                .json()
+                .context("Failed to parse Cloudflare DNS record list")?;
+            if !list.success {
+                return Err(ProviderError::cloudflare(None, &list.errors).into());
+            }
+
+            let total_pages = list.result_info.map_or(1, |info| info.total_pages);
+            let done = list.result.is_empty() || page >= total_pages;
+            records.extend(list.result.iter().map(|record| {
+                TxtRecord::new(&record.name, record.content.as_deref().unwrap_or_default())
+            }));
+            if done {
+                return Ok(records);
+            }
+        }
+        log::warn!("[dns] Cloudflare record listing stopped after {MAX_ZONE_PAGES} pages");
+        Ok(records)
+    }
+
     /// Atomic operation: Creates a single TXT record via Cloudflare API.
     /// Returns the record ID. Does not check for existing records or verify.
-    fn create_txt_record_atomic(&mut self, record_name: &str, value: &str) -> Result<String> {
+    fn create_txt_record_atomic(&self, record_name: &str, value: &str) -> Result<String> {
         let zone_id = self.discover_zone_id()?;
         let client = http::HttpClient::shared();
         let formatted_value = Self::format_txt_content(value);
@@ -166,7 +349,7 @@ impl CloudflareAdapter {
             record_type: "TXT".to_string(),
             name: record_name.to_string(),
             content: formatted_value,
-            ttl: 120, // Auto TTL
+            ttl: self.record_ttl.unwrap_or(120), // Auto TTL
         };
 
         let response = client
@@ -177,12 +360,12 @@ impl CloudflareAdapter {
             .header("Authorization", format!("Bearer {}", self.api_token))
             .header("Content-Type", "application/json")
             .json(&record)
-            .send()
+            .send_captured(self.debug.as_ref())
             .context("Failed to create Cloudflare DNS record")?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().unwrap_or_default();
+        if !response.status.is_success() {
+            let status = response.status;
+            let error_text = response.body;
             return Err(http::status_error("Cloudflare", status, Some(error_text)));
         }
 
@@ -191,16 +374,7 @@ impl CloudflareAdapter {
             .context("Failed to parse Cloudflare DNS record response")?;
 
         if !result.success {
-            let error_msg = result
-                .errors
-                .map(|e| {
-                    e.iter()
-                        .map(|err| format!("{}: {}", err.code, err.message))
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                })
-                .unwrap_or_else(|| "Unknown error".to_string());
-            return Err(anyhow!("Cloudflare API error: {}", error_msg));
+            return Err(ProviderError::cloudflare(None, &result.errors).into());
         }
 
         let record_id = result
@@ -213,7 +387,7 @@ impl CloudflareAdapter {
 
     /// Atomic operation: Deletes a single TXT record by ID via Cloudflare API.
     /// Does not handle parallelization or listing - just a single DELETE call.
-    fn delete_txt_record_atomic(&mut self, record_id: &str) -> Result<()> {
+    fn delete_txt_record_atomic(&self, record_id: &str) -> Result<()> {
         let zone_id = self.discover_zone_id()?;
         let client = http::HttpClient::shared();
 
@@ -223,18 +397,16 @@ impl CloudflareAdapter {
                 zone_id, record_id
             ))
             .header("Authorization", format!("Bearer {}", self.api_token))
-            .send()
+            .send_captured(self.debug.as_ref())
             .context("Failed to delete Cloudflare DNS record")?;
 
-        if delete_response.status().is_success() || delete_response.status() == 404 {
+        if delete_response.status.is_success() || delete_response.status == 404 {
             // 404 is fine, record already gone
             Ok(())
         } else {
-            Err(http::status_error(
-                "Cloudflare",
-                delete_response.status(),
-                None,
-            ))
+            let status = delete_response.status;
+            let body = delete_response.body;
+            Err(http::status_error("Cloudflare", status, Some(body)))
         }
     }
 }
@@ -245,15 +417,15 @@ impl AtomicDnsOperations for CloudflareAdapter {
         value.trim().trim_matches('"').trim().to_string()
     }
 
-    fn create_one_record(&mut self, record_name: &str, value: &str) -> Result<String> {
+    fn create_one_record(&self, record_name: &str, value: &str) -> Result<String> {
         self.create_txt_record_atomic(record_name, value)
     }
 
-    fn delete_one_record(&mut self, record_id: &str) -> Result<()> {
+    fn delete_one_record(&self, record_id: &str) -> Result<()> {
         self.delete_txt_record_atomic(record_id)
     }
 
-    fn list_records(&mut self, record_name: &str) -> Result<Vec<DnsRecord>> {
+    fn list_records(&self, record_name: &str) -> Result<Vec<DnsRecord>> {
         let zone_id = self.discover_zone_id()?;
         let client = http::HttpClient::shared();
         let existing_records = self.list_existing_txt_records(client, &zone_id, record_name)?;
@@ -268,32 +440,178 @@ impl AtomicDnsOperations for CloudflareAdapter {
             .collect())
     }
 
-    fn get_zone_id(&mut self, _domain: &str) -> Result<String> {
+    fn get_zone_id(&self, _domain: &str) -> Result<String> {
         // Cloudflare uses domain_suffix for zone discovery
         self.discover_zone_id()
     }
 }
 
 impl DnsProviderBase for CloudflareAdapter {
-    fn atomic_ops(&mut self) -> &mut dyn AtomicDnsOperations {
+    fn atomic_ops(&self) -> &dyn AtomicDnsOperations {
         self
     }
 }
 
 impl DnsProviderAdapter for CloudflareAdapter {
     fn create_txt(&self, record_name: &str, value: &str) -> Result<()> {
-        // Use DnsProviderBase for backward compatibility
-        let mut adapter =
-            CloudflareAdapter::new(self.api_token.clone(), self.domain_suffix.clone());
-        adapter.set_txt_record(record_name, value)?;
-        Ok(())
+        self.set_txt_record(record_name, value)
+    }
+
+    fn create_txt_batch(&self, records: &[(String, String)]) -> Result<()> {
+        self.set_txt_records(records.to_vec())
+    }
+
+    fn replace_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        self.replace_txt_record(record_name, value)
     }
 
     fn cleanup_txt(&self, record_name: &str) -> Result<()> {
-        // Use DnsProviderBase for backward compatibility
-        let mut adapter =
-            CloudflareAdapter::new(self.api_token.clone(), self.domain_suffix.clone());
-        adapter.delete_txt_record(record_name)?;
-        Ok(())
+        self.delete_txt_record(record_name)
+    }
+
+    /// Always asks Cloudflare, so cached zones cannot hide a revoked token.
+    /// A token that sees the zone but cannot manage its records fails with
+    /// [`MissingScopes`].
+    fn verify_zone_access(&self) -> Result<()> {
+        let zone_id = self.lookup_zone_id()?;
+        let scopes = self.missing_dns_scopes(&zone_id)?;
+        if scopes.is_empty() {
+            return Ok(());
+        }
+        Err(MissingScopes {
+            provider: "Cloudflare",
+            domain: self.domain_suffix.clone(),
+            scopes,
+        }
+        .into())
+    }
+
+    fn list_zones(&self) -> Result<Vec<String>> {
+        Ok(self.fetch_zones()?.into_iter().map(|zone| zone.name).collect())
+    }
+
+    /// The DNS:Edit probe of [`Self::verify_zone_access`] on its own.
+    fn verify_write_access(&self) -> Option<Result<()>> {
+        Some(self.lookup_zone_id().and_then(|zone_id| {
+            if self.missing_dns_scopes(&zone_id)?.contains(&DNS_EDIT_SCOPE) {
+                return Err(MissingScopes {
+                    provider: "Cloudflare",
+                    domain: self.domain_suffix.clone(),
+                    scopes: vec![DNS_EDIT_SCOPE],
+                }
+                .into());
+            }
+            Ok(())
+        }))
+    }
+
+    fn list_txt_records(&self) -> Result<Vec<TxtRecord>> {
+        self.fetch_zone_txt_records()
+    }
+
+    /// Read from the zones the token sees, since tokens scoped to DNS
+    /// cannot list `/accounts`. A token can span several accounts.
+    fn account_identity(&self) -> Option<Result<String>> {
+        Some(self.fetch_zones().and_then(|zones| {
+            let accounts = zone_accounts(&zones);
+            if accounts.is_empty() {
+                return Err(anyhow!("The token cannot see any Cloudflare zone"));
+            }
+            Ok(accounts.join(", "))
+        }))
+    }
+}
+
+/// Distinct owning accounts of `zones`, by name when Cloudflare gives one.
+fn zone_accounts(zones: &[CloudflareZone]) -> Vec<String> {
+    let mut accounts: Vec<String> = Vec::new();
+    for account in zones.iter().filter_map(|zone| zone.account.as_ref()) {
+        let label = if account.name.is_empty() {
+            account.id.clone()
+        } else {
+            format!("{} ({})", account.name, account.id)
+        };
+        if !accounts.contains(&label) {
+            accounts.push(label);
+        }
+    }
+    accounts
+}
+
+/// Whether a permission probe got past Cloudflare's permission check. Only
+/// an authorization failure means the scope is missing; other client errors,
+/// like the probe record not existing, mean the request was allowed.
+fn probe_outcome(status: StatusCode, body: &str) -> Result<bool> {
+    if status.is_success() {
+        return Ok(true);
+    }
+    let err = ProviderError::from_status("Cloudflare", status, Some(body));
+    match err.category {
+        DnsProviderErrorCategory::AuthError => Ok(false),
+        DnsProviderErrorCategory::RateLimited => Err(err.into()),
+        _ if status.is_client_error() => Ok(true),
+        _ => Err(err.into()),
+    }
+}
+
+/// `domain` and each parent with at least two labels, longest first.
+fn candidate_zone_names(domain: &str) -> Vec<String> {
+    let mut candidate = domain.trim().trim_end_matches('.').to_ascii_lowercase();
+    let mut names = Vec::new();
+    while candidate.contains('.') {
+        names.push(candidate.clone());
+        candidate = match candidate.split_once('.') {
+            Some((_, parent)) => parent.to_string(),
+            None => break,
+        };
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candidate_zones_walk_up_to_the_registrable_name() {
+        assert_eq!(
+            candidate_zone_names("App.Eu.Example.com."),
+            vec!["app.eu.example.com", "eu.example.com", "example.com"]
+        );
+        assert_eq!(candidate_zone_names("example.com"), vec!["example.com"]);
+        assert!(candidate_zone_names("localhost").is_empty());
+    }
+
+    #[test]
+    fn probe_outcome_only_counts_authorization_failures_as_missing() {
+        let denied = r#"{"success":false,"errors":[{"code":10000,"message":"Auth error"}]}"#;
+        assert!(!probe_outcome(StatusCode::FORBIDDEN, denied).unwrap());
+        let missing = r#"{"success":false,"errors":[{"code":81044,"message":"No record"}]}"#;
+        assert!(probe_outcome(StatusCode::NOT_FOUND, missing).unwrap());
+        assert!(probe_outcome(StatusCode::OK, "{}").unwrap());
+        assert!(probe_outcome(StatusCode::TOO_MANY_REQUESTS, "").is_err());
+        assert!(probe_outcome(StatusCode::BAD_GATEWAY, "").is_err());
+
+        let err = anyhow::Error::from(MissingScopes {
+            provider: "Cloudflare",
+            domain: "example.com".to_string(),
+            scopes: vec![DNS_READ_SCOPE, DNS_EDIT_SCOPE],
+        });
+        assert_eq!(
+            err.to_string(),
+            "Cloudflare token is missing DNS:Read, DNS:Edit for example.com"
+        );
+    }
+
+    #[test]
+    fn names_each_account_owning_a_visible_zone_once() {
+        let listing = r#"{"success":true,"result":[
+            {"id":"z1","name":"example.com","account":{"id":"a1","name":"Ops"}},
+            {"id":"z2","name":"example.net","account":{"id":"a1","name":"Ops"}},
+            {"id":"z3","name":"example.org","account":{"id":"a2","name":""}},
+            {"id":"z4","name":"example.dev"}
+        ]}"#;
+        let zones: CloudflareZoneListResponse = serde_json::from_str(listing).unwrap();
+        assert_eq!(zone_accounts(&zones.result), vec!["Ops (a1)", "a2"]);
     }
 }
@@ -0,0 +1,258 @@
+//! Opt-in capture of provider API traffic.
+//!
+//! When `debug_capture` is set in a provider's config, its adapter records the
+//! last [`MAX_ENTRIES`] requests and responses here so provider-specific API
+//! quirks can be diagnosed from the UI. Nothing is written to disk. Headers
+//! are never captured, the provider's own credentials are masked wherever they
+//! appear, and JSON fields with credential-like names are redacted.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use chrono::Utc;
+use serde_json::Value;
+
+use crate::core::types::ProviderDebugEntry;
+
+const MAX_ENTRIES: usize = 50;
+const MAX_BODY_CHARS: usize = 8 * 1024;
+const REDACTED: &str = "[redacted]";
+const SENSITIVE_KEYS: &[&str] = &["token", "secret", "password", "authorization", "api_key", "credential"];
+
+type DebugLogs = HashMap<String, VecDeque<ProviderDebugEntry>>;
+
+static LOGS: OnceLock<Mutex<DebugLogs>> = OnceLock::new();
+
+fn logs() -> &'static Mutex<DebugLogs> {
+    LOGS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Handle an adapter uses to record calls for one provider.
+#[derive(Clone)]
+pub struct DebugCapture {
+    provider_id: String,
+    /// Credential values masked in captured URLs and bodies
+    secrets: Vec<String>,
+}
+
+/// One call as seen by the adapter, before redaction.
+pub struct CapturedCall<'a> {
+    pub method: &'a str,
+    pub url: &'a str,
+    pub request_body: Option<&'a str>,
+    pub status: Option<u16>,
+    pub response_body: Option<&'a str>,
+    pub error: Option<String>,
+    pub elapsed: Duration,
+}
+
+impl DebugCapture {
+    pub fn new(provider_id: String, secrets: Vec<String>) -> Self {
+        Self {
+            provider_id,
+            secrets: secrets.into_iter().filter(|secret| !secret.is_empty()).collect(),
+        }
+    }
+
+    pub fn record(&self, call: CapturedCall<'_>) {
+        let entry = ProviderDebugEntry {
+            captured_at: Utc::now(),
+            method: call.method.to_string(),
+            url: self.mask(call.url),
+            request_body: call.request_body.map(|body| self.sanitize_body(body)),
+            status: call.status,
+            response_body: call.response_body.map(|body| self.sanitize_body(body)),
+            error: call.error.map(|error| self.mask(&error)),
+            elapsed_ms: call.elapsed.as_millis() as u64,
+        };
+        match logs().lock() {
+            Ok(mut logs) => {
+                let entries = logs.entry(self.provider_id.clone()).or_default();
+                if entries.len() == MAX_ENTRIES {
+                    entries.pop_front();
+                }
+                entries.push_back(entry);
+            }
+            Err(err) => log::warn!("[dns-debug] capture unavailable: {err}"),
+        }
+    }
+
+    fn mask(&self, text: &str) -> String {
+        self.secrets
+            .iter()
+            .fold(text.to_string(), |text, secret| text.replace(secret.as_str(), REDACTED))
+    }
+
+    fn sanitize_body(&self, body: &str) -> String {
+        let sanitized = match serde_json::from_str::<Value>(body) {
+            Ok(mut value) => {
+                redact_json(&mut value);
+                self.mask(&value.to_string())
+            }
+            Err(_) => self.mask(body),
+        };
+        truncate(sanitized)
+    }
+}
+
+fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if SENSITIVE_KEYS.iter().any(|sensitive| key.contains(sensitive)) {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// Redacts values that follow a credential-like name in free text, as in
+/// `api_token=...`, `"secret": "..."` or `Authorization: Bearer ...`. Used
+/// for error messages, where the values are not known in advance.
+pub fn redact_text(text: &str) -> String {
+    let lower = text.to_ascii_lowercase();
+    let bytes = lower.as_bytes();
+    let mut redacted = String::with_capacity(text.len());
+    let (mut copied, mut index) = (0, 0);
+    while index < bytes.len() {
+        let Some(key) = SENSITIVE_KEYS
+            .iter()
+            .chain(&["bearer"])
+            .find(|key| bytes[index..].starts_with(key.as_bytes()))
+        else {
+            index += 1;
+            continue;
+        };
+        let mut cursor = index + key.len();
+        // The rest of the field name, e.g. the `_id` of `secret_id`
+        while bytes
+            .get(cursor)
+            .is_some_and(|byte| byte.is_ascii_alphanumeric() || *byte == b'_')
+        {
+            cursor += 1;
+        }
+        let separator_start = cursor;
+        while cursor < bytes.len() && b"\"' :=".contains(&bytes[cursor]) {
+            cursor += 1;
+        }
+        let separators = &bytes[separator_start..cursor];
+        let assigned = separators.iter().any(|byte| *byte == b'=' || *byte == b':');
+        let bearer_token = *key == "bearer" && separators == b" ";
+        if !(assigned || bearer_token) {
+            index = cursor.max(index + 1);
+            continue;
+        }
+        for scheme in ["bearer ", "basic "] {
+            if bytes[cursor..].starts_with(scheme.as_bytes()) {
+                cursor += scheme.len();
+            }
+        }
+        let value_end = bytes[cursor..]
+            .iter()
+            .position(|byte| byte.is_ascii_whitespace() || b"\"'&,;}".contains(byte))
+            .map_or(bytes.len(), |offset| cursor + offset);
+        if value_end > cursor {
+            redacted.push_str(&text[copied..cursor]);
+            redacted.push_str(REDACTED);
+            copied = value_end;
+        }
+        index = value_end.max(index + 1);
+    }
+    redacted.push_str(&text[copied..]);
+    redacted
+}
+
+fn truncate(mut body: String) -> String {
+    if let Some((index, _)) = body.char_indices().nth(MAX_BODY_CHARS) {
+        body.truncate(index);
+        body.push_str("… [truncated]");
+    }
+    body
+}
+
+/// Captured calls for a provider, newest first.
+pub fn captured_calls(provider_id: &str) -> Vec<ProviderDebugEntry> {
+    match logs().lock() {
+        Ok(logs) => logs
+            .get(provider_id)
+            .map(|entries| entries.iter().rev().cloned().collect())
+            .unwrap_or_default(),
+        Err(err) => {
+            log::warn!("[dns-debug] capture unavailable: {err}");
+            Vec::new()
+        }
+    }
+}
+
+/// Drops the captured calls for a provider.
+pub fn clear_captured_calls(provider_id: &str) {
+    if let Ok(mut logs) = logs().lock() {
+        logs.remove(provider_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call<'a>(url: &'a str, request_body: Option<&'a str>) -> CapturedCall<'a> {
+        CapturedCall {
+            method: "GET",
+            url,
+            request_body,
+            status: Some(200),
+            response_body: Some(r#"{"result":[],"api_token":"abc","nested":{"SecretKey":"x"}}"#),
+            error: None,
+            elapsed: Duration::from_millis(5),
+        }
+    }
+
+    #[test]
+    fn redacts_credentials_and_keeps_latest_entries() {
+        let capture = DebugCapture::new("prov_debug_test".to_string(), vec!["tok123".to_string()]);
+        capture.record(call("https://api.test/zones?key=tok123", Some("token tok123")));
+
+        let entries = captured_calls("prov_debug_test");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].url, "https://api.test/zones?key=[redacted]");
+        assert_eq!(entries[0].request_body.as_deref(), Some("token [redacted]"));
+        let response = entries[0].response_body.as_deref().unwrap();
+        assert!(response.contains(r#""api_token":"[redacted]""#));
+        assert!(response.contains(r#""SecretKey":"[redacted]""#));
+        assert!(response.contains(r#""result":[]"#));
+
+        for index in 0..MAX_ENTRIES {
+            capture.record(call(&format!("https://api.test/{index}"), None));
+        }
+        let entries = captured_calls("prov_debug_test");
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0].url, format!("https://api.test/{}", MAX_ENTRIES - 1));
+
+        clear_captured_calls("prov_debug_test");
+        assert!(captured_calls("prov_debug_test").is_empty());
+    }
+
+    #[test]
+    fn redacts_credentials_in_error_text() {
+        assert_eq!(
+            redact_text("GET https://api.test/zones?api_token=abc123&page=2 failed"),
+            "GET https://api.test/zones?api_token=[redacted]&page=2 failed"
+        );
+        assert_eq!(
+            redact_text(r#"400: {"Secret": "s3cré", "zone":"example.com"}"#),
+            r#"400: {"Secret": "[redacted]", "zone":"example.com"}"#
+        );
+        assert_eq!(
+            redact_text("Authorization: Bearer xyz.789 rejected"),
+            "Authorization: Bearer [redacted] rejected"
+        );
+        assert_eq!(redact_text("invalid token for zone"), "invalid token for zone");
+    }
+}
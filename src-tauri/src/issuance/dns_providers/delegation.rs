@@ -0,0 +1,182 @@
+//! Checks that a domain suffix is actually served by the provider it is
+//! configured under: the zone's NS records must point at that provider's
+//! nameservers, and the stored credentials must be able to see the zone.
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use serde::Deserialize;
+
+use crate::core::types::DomainSuffixVerification;
+use crate::secrets::manager::SecretManager;
+use crate::storage::dns::DnsProvider;
+
+use super::{adapter_for_provider, errors::MissingScopes, testing::resolve_dns_timeout};
+
+/// DNS RR type code for NS records.
+const NS_RECORD_TYPE: u16 = 2;
+
+#[derive(Debug, Deserialize)]
+struct NsQueryResponse {
+    #[serde(rename = "Status")]
+    status: u32,
+    #[serde(rename = "Answer", default)]
+    answer: Vec<NsQueryAnswer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NsQueryAnswer {
+    name: String,
+    #[serde(rename = "type")]
+    record_type: u16,
+    data: String,
+}
+
+/// Verifies one suffix of `provider`; failures are reported in the result
+/// rather than returned as errors.
+pub fn verify_domain_suffix(
+    provider: &DnsProvider,
+    suffix: &str,
+    secrets: &SecretManager,
+) -> DomainSuffixVerification {
+    let mut verification = DomainSuffixVerification {
+        suffix: suffix.to_string(),
+        zone: None,
+        nameservers: Vec::new(),
+        delegated: None,
+        zone_accessible: None,
+        missing_scopes: Vec::new(),
+        issues: Vec::new(),
+    };
+
+    match lookup_nameservers(suffix) {
+        Ok(Some((zone, nameservers))) => {
+            verification.delegated = nameservers_match_provider(&provider.provider_type, &nameservers);
+            if verification.delegated == Some(false) {
+                verification.issues.push(format!(
+                    "{zone} is delegated to {}, not {}",
+                    nameservers.join(", "),
+                    provider.provider_type
+                ));
+            }
+            verification.zone = Some(zone);
+            verification.nameservers = nameservers;
+        }
+        Ok(None) => verification
+            .issues
+            .push(format!("no NS records found for {suffix} or its parents")),
+        Err(err) => {
+            warn!("[dns] NS lookup for {suffix} failed: {err:#}");
+            verification.issues.push(format!("NS lookup failed: {err}"));
+        }
+    }
+
+    if provider.provider_type != "manual" {
+        let scoped = DnsProvider {
+            domain_suffixes: vec![suffix.to_string()],
+            ..provider.clone()
+        };
+        match adapter_for_provider(&scoped, secrets).verify_zone_access() {
+            Ok(()) => verification.zone_accessible = Some(true),
+            Err(err) => {
+                if let Some(missing) = err.downcast_ref::<MissingScopes>() {
+                    // The zone is visible; only record management is denied.
+                    verification.zone_accessible = Some(true);
+                    verification.missing_scopes =
+                        missing.scopes.iter().map(|scope| scope.to_string()).collect();
+                    verification.issues.push(err.to_string());
+                } else {
+                    verification.zone_accessible = Some(false);
+                    verification
+                        .issues
+                        .push(format!("credentials cannot access the zone: {err}"));
+                }
+            }
+        }
+    }
+
+    verification
+}
+
+/// Finds the closest enclosing zone of `domain` that has NS records and
+/// returns its name with the lowercased nameserver host names.
+pub fn lookup_nameservers(domain: &str) -> Result<Option<(String, Vec<String>)>> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(resolve_dns_timeout())
+        .build();
+    let mut candidate = domain.trim().trim_end_matches('.').to_ascii_lowercase();
+    while candidate.contains('.') {
+        info!("[dns] Querying NS records for {candidate}");
+        let body = agent
+            .get(&format!("https://dns.google/resolve?name={candidate}&type=NS"))
+            .set("Accept", "application/dns-json")
+            .call()
+            .context("Failed to query Google DNS")?
+            .into_string()
+            .context("Failed to read Google DNS response body")?;
+        let response: NsQueryResponse =
+            serde_json::from_str(&body).context("Failed to parse Google DNS response")?;
+        let nameservers = zone_nameservers(&response, &candidate);
+        if !nameservers.is_empty() {
+            return Ok(Some((candidate, nameservers)));
+        }
+        // NXDOMAIN (3) and NOERROR without NS both mean the zone starts higher up.
+        if response.status != 0 && response.status != 3 {
+            warn!("[dns] NS query for {candidate} returned status {}", response.status);
+        }
+        candidate = match candidate.split_once('.') {
+            Some((_, parent)) => parent.to_string(),
+            None => break,
+        };
+    }
+    Ok(None)
+}
+
+fn zone_nameservers(response: &NsQueryResponse, zone: &str) -> Vec<String> {
+    response
+        .answer
+        .iter()
+        .filter(|answer| answer.record_type == NS_RECORD_TYPE)
+        .filter(|answer| answer.name.trim_end_matches('.').eq_ignore_ascii_case(zone))
+        .map(|answer| answer.data.trim().trim_end_matches('.').to_ascii_lowercase())
+        .collect()
+}
+
+/// Whether the nameservers belong to the provider type. `None` when the
+/// provider type has no well-known nameservers (manual and self-hosted
+/// providers).
+pub fn nameservers_match_provider(provider_type: &str, nameservers: &[String]) -> Option<bool> {
+    let matches: fn(&str) -> bool = match provider_type {
+        "cloudflare" => |ns| ns.ends_with(".ns.cloudflare.com"),
+        "digitalocean" => |ns| ns.ends_with(".digitalocean.com"),
+        "desec" => |ns| ns.ends_with(".desec.io") || ns.ends_with(".desec.org"),
+        "route53" => |ns| ns.contains(".awsdns-"),
+        _ => return None,
+    };
+    Some(!nameservers.is_empty() && nameservers.iter().all(|ns| matches(ns)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_provider_nameservers() {
+        let response: NsQueryResponse = serde_json::from_str(
+            r#"{"Status":0,"Answer":[
+                {"name":"example.com.","type":2,"TTL":300,"data":"ada.ns.cloudflare.com."},
+                {"name":"example.com.","type":2,"TTL":300,"data":"BOB.NS.CLOUDFLARE.COM."},
+                {"name":"example.com.","type":46,"TTL":300,"data":"sig"}
+            ]}"#,
+        )
+        .unwrap();
+        let nameservers = zone_nameservers(&response, "example.com");
+        assert_eq!(nameservers, ["ada.ns.cloudflare.com", "bob.ns.cloudflare.com"]);
+        assert_eq!(nameservers_match_provider("cloudflare", &nameservers), Some(true));
+        assert_eq!(nameservers_match_provider("route53", &nameservers), Some(false));
+        assert_eq!(nameservers_match_provider("manual", &nameservers), None);
+
+        let route53 = vec!["ns-12.awsdns-01.com".to_string(), "ns-900.awsdns-40.net".to_string()];
+        assert_eq!(nameservers_match_provider("route53", &route53), Some(true));
+        assert!(zone_nameservers(&response, "sub.example.com").is_empty());
+    }
+}
@@ -0,0 +1,358 @@
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    DnsProviderAdapter, TxtRecord,
+    base::{
+        AtomicDnsOperations, DnsProviderBase, DnsRecord, parse_rrset_record_id, rrset_record_id,
+    },
+    debug::DebugCapture,
+    http::{self, SendCaptured},
+    matches_zone,
+    zone_cache::ZoneCache,
+};
+
+const API_BASE: &str = "https://desec.io/api/v1";
+/// deSEC rejects TTLs below the account minimum, which defaults to one hour.
+const DEFAULT_TTL: u32 = 3600;
+
+/// deSEC adapter. deSEC stores all TXT values of a name as a single RRset, so
+/// creating a record merges the value into the RRset and deleting one removes
+/// only that value; the RRset itself disappears with its last value.
+///
+/// Record IDs come from [`rrset_record_id`] because values have no ID of
+/// their own inside an RRset.
+pub struct DesecAdapter {
+    api_token: String,
+    zone_cache: OnceLock<String>,
+    shared_zones: Option<ZoneCache>,
+    domain_suffix: String,
+    record_ttl: Option<u32>,
+    debug: Option<DebugCapture>,
+}
+
+#[derive(Deserialize)]
+struct DesecDomain {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct DesecRrset {
+    /// Fully qualified, with a trailing dot
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    records: Vec<String>,
+}
+
+/// Entry of a bulk RRset PATCH; an empty `records` list deletes the RRset.
+#[derive(Serialize)]
+struct DesecRrsetUpdate<'a> {
+    subname: &'a str,
+    #[serde(rename = "type")]
+    record_type: &'static str,
+    ttl: u32,
+    records: Vec<String>,
+}
+
+impl DesecAdapter {
+    pub fn new(api_token: String, domain_suffix: String) -> Self {
+        Self {
+            api_token,
+            zone_cache: OnceLock::new(),
+            shared_zones: None,
+            domain_suffix,
+            record_ttl: None,
+            debug: None,
+        }
+    }
+
+    /// Overrides the TTL of created TXT records.
+    pub fn with_record_ttl(mut self, ttl: Option<u32>) -> Self {
+        self.record_ttl = ttl;
+        self
+    }
+
+    /// Records API calls for the provider's debug log.
+    pub fn with_debug_capture(mut self, debug: Option<DebugCapture>) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    /// Shares discovered zones with later adapters of the same provider.
+    pub fn with_zone_cache(mut self, zones: Option<ZoneCache>) -> Self {
+        self.shared_zones = zones;
+        self
+    }
+
+    fn format_txt_content(value: &str) -> String {
+        format!("\"{}\"", value.trim().trim_matches('"'))
+    }
+
+    fn fetch_domains(&self) -> Result<Vec<DesecDomain>> {
+        let response = http::HttpClient::shared()
+            .get(format!("{API_BASE}/domains/"))
+            .header("Authorization", format!("Token {}", self.api_token))
+            .send_captured(self.debug.as_ref())
+            .context("Failed to list deSEC domains")?;
+
+        if !response.status.is_success() {
+            let status = response.status;
+            let body = response.body;
+            return Err(http::status_error("deSEC", status, Some(body)));
+        }
+
+        response
+            .json()
+            .context("Failed to parse deSEC domain list")
+    }
+
+    /// Domain for the suffix, from this adapter's or the provider's cache when
+    /// it was found before.
+    fn discover_zone(&self) -> Result<String> {
+        if let Some(zone) = self.zone_cache.get() {
+            return Ok(zone.clone());
+        }
+        let shared = self.shared_zones.as_ref();
+        let zone = match shared.and_then(|zones| zones.get(&self.domain_suffix)) {
+            Some(zone) => zone,
+            None => {
+                let zone = self.lookup_zone()?;
+                if let Some(zones) = shared {
+                    zones.put(&self.domain_suffix, &zone);
+                }
+                zone
+            }
+        };
+        Ok(self.zone_cache.get_or_init(|| zone).clone())
+    }
+
+    /// Finds the most specific deSEC domain containing the suffix.
+    fn lookup_zone(&self) -> Result<String> {
+        self.fetch_domains()?
+            .into_iter()
+            .filter(|domain| matches_zone(&self.domain_suffix, &domain.name))
+            .max_by_key(|domain| domain.name.len())
+            .map(|domain| domain.name)
+            .ok_or_else(|| {
+                anyhow!(
+                    "No deSEC domain found for domain suffix: {}",
+                    self.domain_suffix
+                )
+            })
+    }
+
+    /// Returns the values of the TXT RRset at `record_name`, empty when the
+    /// RRset does not exist.
+    fn fetch_rrset(&self, record_name: &str) -> Result<Vec<String>> {
+        let zone = self.discover_zone()?;
+        let response = http::HttpClient::shared()
+            .get(format!(
+                "{API_BASE}/domains/{zone}/rrsets/{}/TXT/",
+                url_subname(record_name, &zone)
+            ))
+            .header("Authorization", format!("Token {}", self.api_token))
+            .send_captured(self.debug.as_ref())
+            .context("Failed to fetch deSEC RRset")?;
+
+        if response.status == 404 {
+            return Ok(Vec::new());
+        }
+        if !response.status.is_success() {
+            let status = response.status;
+            let body = response.body;
+            return Err(http::status_error("deSEC", status, Some(body)));
+        }
+
+        let rrset: DesecRrset = response.json().context("Failed to parse deSEC RRset")?;
+        Ok(rrset.records)
+    }
+
+    /// Every TXT RRset of the zone, one entry per value.
+    fn fetch_zone_txt_records(&self) -> Result<Vec<TxtRecord>> {
+        let zone = self.discover_zone()?;
+        let response = http::HttpClient::shared()
+            .get(format!("{API_BASE}/domains/{zone}/rrsets/"))
+            .query(&[("type", "TXT")])
+            .header("Authorization", format!("Token {}", self.api_token))
+            .send_captured(self.debug.as_ref())
+            .context("Failed to list deSEC RRsets")?;
+
+        if !response.status.is_success() {
+            let status = response.status;
+            let body = response.body;
+            return Err(http::status_error("deSEC", status, Some(body)));
+        }
+
+        let rrsets: Vec<DesecRrset> = response.json().context("Failed to parse deSEC RRsets")?;
+        Ok(rrsets
+            .iter()
+            .flat_map(|rrset| {
+                rrset.records.iter().map(|value| TxtRecord::new(&rrset.name, value))
+            })
+            .collect())
+    }
+
+    /// Replaces the TXT RRset at `record_name` with `records`.
+    fn write_rrset(&self, record_name: &str, records: Vec<String>) -> Result<()> {
+        let zone = self.discover_zone()?;
+        let update = DesecRrsetUpdate {
+            subname: &subname(record_name, &zone),
+            record_type: "TXT",
+            ttl: self.record_ttl.unwrap_or(DEFAULT_TTL),
+            records,
+        };
+
+        let response = http::HttpClient::shared()
+            .patch(format!("{API_BASE}/domains/{zone}/rrsets/"))
+            .header("Authorization", format!("Token {}", self.api_token))
+            .json(&[update])
+            .send_captured(self.debug.as_ref())
+            .context("Failed to update deSEC RRset")?;
+
+        if !response.status.is_success() {
+            let status = response.status;
+            let body = response.body;
+            return Err(http::status_error("deSEC", status, Some(body)));
+        }
+        Ok(())
+    }
+}
+
+/// Name of `record_name` relative to `zone`; empty at the zone apex.
+fn subname(record_name: &str, zone: &str) -> String {
+    let record_name = record_name.trim_end_matches('.');
+    let zone = zone.trim_end_matches('.');
+    if record_name.eq_ignore_ascii_case(zone) {
+        return String::new();
+    }
+    record_name
+        .strip_suffix(zone)
+        .and_then(|relative| relative.strip_suffix('.'))
+        .unwrap_or(record_name)
+        .to_string()
+}
+
+/// deSEC addresses the apex RRset as `@` in URLs.
+fn url_subname(record_name: &str, zone: &str) -> String {
+    let subname = subname(record_name, zone);
+    if subname.is_empty() {
+        "@".to_string()
+    } else {
+        subname
+    }
+}
+
+impl AtomicDnsOperations for DesecAdapter {
+    /// Merges `value` into the TXT RRset at `record_name`, keeping the values
+    /// already there. The returned ID identifies the value within the RRset.
+    fn create_one_record(&self, record_name: &str, value: &str) -> Result<String> {
+        let normalized = self.normalize_value(value);
+        let mut records = self.fetch_rrset(record_name)?;
+        if !records
+            .iter()
+            .any(|existing| self.normalize_value(existing) == normalized)
+        {
+            records.push(Self::format_txt_content(value));
+            self.write_rrset(record_name, records)?;
+        }
+        Ok(rrset_record_id(record_name, &normalized))
+    }
+
+    /// Removes one value from its RRset; the RRset is deleted with its last value.
+    fn delete_one_record(&self, record_id: &str) -> Result<()> {
+        let (record_name, value) = parse_rrset_record_id(record_id)?;
+        let mut records = self.fetch_rrset(record_name)?;
+        let before = records.len();
+        records.retain(|existing| self.normalize_value(existing) != value);
+        if records.len() == before {
+            // Value already gone
+            return Ok(());
+        }
+        self.write_rrset(record_name, records)
+    }
+
+    /// Lists each value of the RRset as its own record.
+    fn list_records(&self, record_name: &str) -> Result<Vec<DnsRecord>> {
+        let records = self.fetch_rrset(record_name)?;
+        Ok(records
+            .iter()
+            .map(|value| DnsRecord {
+                id: rrset_record_id(record_name, &self.normalize_value(value)),
+                name: record_name.to_string(),
+                value: value.clone(),
+            })
+            .collect())
+    }
+
+    fn get_zone_id(&self, _domain: &str) -> Result<String> {
+        // deSEC identifies zones by domain name
+        self.discover_zone()
+    }
+}
+
+impl DnsProviderBase for DesecAdapter {
+    fn atomic_ops(&self) -> &dyn AtomicDnsOperations {
+        self
+    }
+}
+
+impl DnsProviderAdapter for DesecAdapter {
+    fn create_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        self.set_txt_record(record_name, value)
+    }
+
+    fn create_txt_batch(&self, records: &[(String, String)]) -> Result<()> {
+        self.set_txt_records(records.to_vec())
+    }
+
+    fn replace_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        self.replace_txt_record(record_name, value)
+    }
+
+    fn cleanup_txt(&self, record_name: &str) -> Result<()> {
+        self.delete_txt_record(record_name)
+    }
+
+    /// Always asks deSEC, so cached zones cannot hide a revoked token.
+    fn verify_zone_access(&self) -> Result<()> {
+        self.lookup_zone()?;
+        Ok(())
+    }
+
+    fn list_zones(&self) -> Result<Vec<String>> {
+        Ok(self
+            .fetch_domains()?
+            .into_iter()
+            .map(|domain| domain.name)
+            .collect())
+    }
+
+    fn list_txt_records(&self) -> Result<Vec<TxtRecord>> {
+        self.fetch_zone_txt_records()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_subnames_and_record_ids() {
+        assert_eq!(
+            subname("_acme-challenge.www.example.com", "example.com"),
+            "_acme-challenge.www"
+        );
+        assert_eq!(subname("example.com.", "example.com"), "");
+        assert_eq!(url_subname("example.com", "example.com"), "@");
+
+        let id = rrset_record_id("_acme-challenge.example.com", "abc-123");
+        assert_eq!(
+            parse_rrset_record_id(&id).unwrap(),
+            ("_acme-challenge.example.com", "abc-123")
+        );
+        assert!(parse_rrset_record_id("no-separator").is_err());
+    }
+}
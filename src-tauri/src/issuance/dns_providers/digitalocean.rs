@@ -2,14 +2,17 @@ use anyhow::{Context, Result, anyhow};
 use serde::{Deserialize, Serialize};
 
 use super::{
-    DnsProviderAdapter,
+    DnsProviderAdapter, TxtRecord,
     base::{AtomicDnsOperations, DnsProviderBase, DnsRecord},
-    http,
+    debug::DebugCapture,
+    http::{self, SendCaptured},
 };
 
 pub struct DigitalOceanAdapter {
     api_token: String,
     domain: String,
+    record_ttl: Option<u32>,
+    debug: Option<DebugCapture>,
 }
 
 #[derive(Serialize)]
@@ -42,9 +45,53 @@ struct DigitalOceanDnsRecordDetailResponse {
     domain_record: DigitalOceanDnsRecordDetail,
 }
 
+#[derive(Deserialize)]
+struct DigitalOceanDomain {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct DigitalOceanDomainListResponse {
+    domains: Vec<DigitalOceanDomain>,
+}
+
+#[derive(Deserialize)]
+struct DigitalOceanAccountResponse {
+    account: DigitalOceanAccount,
+}
+
+#[derive(Deserialize)]
+struct DigitalOceanAccount {
+    email: String,
+    #[serde(default)]
+    team: Option<DigitalOceanTeam>,
+}
+
+#[derive(Deserialize)]
+struct DigitalOceanTeam {
+    name: String,
+}
+
 impl DigitalOceanAdapter {
     pub fn new(api_token: String, domain: String) -> Self {
-        Self { api_token, domain }
+        Self {
+            api_token,
+            domain,
+            record_ttl: None,
+            debug: None,
+        }
+    }
+
+    /// Overrides the TTL of created TXT records.
+    pub fn with_record_ttl(mut self, ttl: Option<u32>) -> Self {
+        self.record_ttl = ttl;
+        self
+    }
+
+    /// Records API calls for the provider's debug log.
+    pub fn with_debug_capture(mut self, debug: Option<DebugCapture>) -> Self {
+        self.debug = debug;
+        self
     }
 
     /// Converts a full record name (FQDN) to a relative name for DigitalOcean API.
@@ -79,21 +126,23 @@ impl DigitalOceanAdapter {
         value.trim().trim_matches('"').trim().to_string()
     }
 
+    /// Lists every TXT record at `record_name`, one per value. The `name`
+    /// filter takes the fully qualified name; a relative one matches nothing.
     fn list_txt_records(&self, record_name: &str) -> Result<Vec<DigitalOceanDnsRecordListItem>> {
-        let relative_name = self.to_relative_name(record_name);
         let client = http::HttpClient::shared();
         let response = client
             .get(format!(
-                "https://api.digitalocean.com/v2/domains/{}/records?type=TXT&name={}",
-                self.domain, relative_name
+                "https://api.digitalocean.com/v2/domains/{}/records?type=TXT&name={}&per_page=200",
+                self.domain,
+                record_name.trim_end_matches('.')
             ))
             .header("Authorization", format!("Bearer {}", self.api_token))
-            .send()
+            .send_captured(self.debug.as_ref())
             .context("Failed to list DigitalOcean DNS records")?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().unwrap_or_default();
+        if !response.status.is_success() {
+            let status = response.status;
+            let body = response.body;
             return Err(http::status_error("DigitalOcean", status, Some(body)));
         }
 
@@ -104,6 +153,50 @@ impl DigitalOceanAdapter {
         Ok(list_result.domain_records)
     }
 
+    /// Every TXT record of the domain, page by page.
+    fn fetch_domain_txt_records(&self) -> Result<Vec<TxtRecord>> {
+        let client = http::HttpClient::shared();
+        let mut records = Vec::new();
+        for page in 1..=MAX_RECORD_PAGES {
+            let response = client
+                .get(format!(
+                    "https://api.digitalocean.com/v2/domains/{}/records",
+                    self.domain
+                ))
+                .query(&[
+                    ("type", "TXT".to_string()),
+                    ("per_page", RECORDS_PER_PAGE.to_string()),
+                    ("page", page.to_string()),
+                ])
+                .header("Authorization", format!("Bearer {}", self.api_token))
+                .send_captured(self.debug.as_ref())
+                .context("Failed to list DigitalOcean DNS records")?;
+
+            if !response.status.is_success() {
+                let status = response.status;
+                let body = response.body;
+                return Err(http::status_error("DigitalOcean", status, Some(body)));
+            }
+            let list: DigitalOceanDnsRecordListResponse = response
+                .json()
+                .context("Failed to parse DigitalOcean DNS record list")?;
+
+            let done = list.domain_records.len() < RECORDS_PER_PAGE;
+            records.extend(list.domain_records.iter().map(|item| {
+                let name = match item.name.as_str() {
+                    "@" | "" => self.domain.clone(),
+                    relative => format!("{relative}.{}", self.domain),
+                };
+                TxtRecord::new(&name, item.data.as_deref().unwrap_or_default())
+            }));
+            if done {
+                return Ok(records);
+            }
+        }
+        log::warn!("[dns] DigitalOcean record listing stopped after {MAX_RECORD_PAGES} pages");
+        Ok(records)
+    }
+
     fn fetch_record_data(&self, record_id: u64) -> Result<Option<String>> {
         let client = http::HttpClient::shared();
         let response = client
@@ -112,15 +205,15 @@ impl DigitalOceanAdapter {
                 self.domain, record_id
             ))
             .header("Authorization", format!("Bearer {}", self.api_token))
-            .send()
+            .send_captured(self.debug.as_ref())
             .context("Failed to fetch DigitalOcean DNS record")?;
 
-        if response.status() == 404 {
+        if response.status == 404 {
             return Ok(None);
         }
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().unwrap_or_default();
+        if !response.status.is_success() {
+            let status = response.status;
+            let error_text = response.body;
             return Err(http::status_error("DigitalOcean", status, Some(error_text)));
         }
 
@@ -140,7 +233,7 @@ impl DigitalOceanAdapter {
             record_type: "TXT".to_string(),
             name: relative_name,
             data: Self::format_txt_content(value),
-            ttl: 300,
+            ttl: self.record_ttl.unwrap_or(300),
         };
 
         let response = client
@@ -151,12 +244,12 @@ impl DigitalOceanAdapter {
             .header("Authorization", format!("Bearer {}", self.api_token))
             .header("Content-Type", "application/json")
             .json(&record)
-            .send()
+            .send_captured(self.debug.as_ref())
             .context("Failed to create DigitalOcean DNS record")?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().unwrap_or_default();
+        if !response.status.is_success() {
+            let status = response.status;
+            let error_text = response.body;
             return Err(http::status_error("DigitalOcean", status, Some(error_text)));
         }
 
@@ -177,18 +270,16 @@ impl DigitalOceanAdapter {
                 self.domain, record_id
             ))
             .header("Authorization", format!("Bearer {}", self.api_token))
-            .send()
+            .send_captured(self.debug.as_ref())
             .context("Failed to delete DigitalOcean DNS record")?;
 
-        if delete_response.status().is_success() || delete_response.status() == 404 {
+        if delete_response.status.is_success() || delete_response.status == 404 {
             // 404 is fine, record already gone
             Ok(())
         } else {
-            Err(http::status_error(
-                "DigitalOcean",
-                delete_response.status(),
-                None,
-            ))
+            let status = delete_response.status;
+            let body = delete_response.body;
+            Err(http::status_error("DigitalOcean", status, Some(body)))
         }
     }
 }
@@ -201,23 +292,30 @@ struct DigitalOceanDnsRecordListResponse {
 #[derive(Deserialize)]
 struct DigitalOceanDnsRecordListItem {
     id: u64,
+    /// Relative to the domain; `@` at the apex
     #[serde(default)]
     name: String,
+    #[serde(default)]
+    data: Option<String>,
 }
 
+const RECORDS_PER_PAGE: usize = 200;
+/// Stops runaway paging on a domain with an unexpected number of records.
+const MAX_RECORD_PAGES: usize = 100;
+
 impl AtomicDnsOperations for DigitalOceanAdapter {
     fn normalize_value(&self, value: &str) -> String {
         // DigitalOcean uses normalize_txt_content logic
         Self::normalize_txt_content(value)
     }
 
-    fn create_one_record(&mut self, record_name: &str, value: &str) -> Result<String> {
+    fn create_one_record(&self, record_name: &str, value: &str) -> Result<String> {
         // Truly atomic: just create the record
         let record_id = self.create_txt_record_atomic(record_name, value)?;
         Ok(record_id.to_string())
     }
 
-    fn delete_one_record(&mut self, record_id: &str) -> Result<()> {
+    fn delete_one_record(&self, record_id: &str) -> Result<()> {
         let record_id = record_id
             .parse::<u64>()
             .map_err(|_| anyhow!("Invalid record ID: {}", record_id))?;
@@ -226,15 +324,19 @@ impl AtomicDnsOperations for DigitalOceanAdapter {
         self.delete_txt_record_atomic(record_id)
     }
 
-    fn list_records(&mut self, record_name: &str) -> Result<Vec<DnsRecord>> {
+    fn list_records(&self, record_name: &str) -> Result<Vec<DnsRecord>> {
         let existing = self.list_txt_records(record_name)?;
         let mut records = Vec::new();
 
         for item in existing {
-            if let Ok(Some(data)) = self.fetch_record_data(item.id) {
+            let data = match item.data {
+                Some(data) => Some(data),
+                None => self.fetch_record_data(item.id).ok().flatten(),
+            };
+            if let Some(data) = data {
                 records.push(DnsRecord {
                     id: item.id.to_string(),
-                    name: item.name.clone(),
+                    name: record_name.to_string(),
                     value: data,
                 });
             }
@@ -243,30 +345,85 @@ impl AtomicDnsOperations for DigitalOceanAdapter {
         Ok(records)
     }
 
-    fn get_zone_id(&mut self, _domain: &str) -> Result<String> {
+    fn get_zone_id(&self, _domain: &str) -> Result<String> {
         // DigitalOcean uses domain name as the zone identifier
         Ok(self.domain.clone())
     }
 }
 
 impl DnsProviderBase for DigitalOceanAdapter {
-    fn atomic_ops(&mut self) -> &mut dyn AtomicDnsOperations {
+    fn atomic_ops(&self) -> &dyn AtomicDnsOperations {
         self
     }
 }
 
 impl DnsProviderAdapter for DigitalOceanAdapter {
     fn create_txt(&self, record_name: &str, value: &str) -> Result<()> {
-        // Use DnsProviderBase for backward compatibility
-        let mut adapter = DigitalOceanAdapter::new(self.api_token.clone(), self.domain.clone());
-        adapter.set_txt_record(record_name, value)?;
-        Ok(())
+        self.set_txt_record(record_name, value)
+    }
+
+    fn create_txt_batch(&self, records: &[(String, String)]) -> Result<()> {
+        self.set_txt_records(records.to_vec())
+    }
+
+    fn replace_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        self.replace_txt_record(record_name, value)
     }
 
     fn cleanup_txt(&self, record_name: &str) -> Result<()> {
-        // Use DnsProviderBase for backward compatibility
-        let mut adapter = DigitalOceanAdapter::new(self.api_token.clone(), self.domain.clone());
-        adapter.delete_txt_record(record_name)?;
+        self.delete_txt_record(record_name)
+    }
+
+    fn verify_zone_access(&self) -> Result<()> {
+        // Listing records fails with 404 when the domain is not in the account.
+        self.list_txt_records(&format!("_acme-challenge.{}", self.domain))?;
         Ok(())
     }
+
+    fn list_zones(&self) -> Result<Vec<String>> {
+        let response = http::HttpClient::shared()
+            .get("https://api.digitalocean.com/v2/domains?per_page=200")
+            .header("Authorization", format!("Bearer {}", self.api_token))
+            .send_captured(self.debug.as_ref())
+            .context("Failed to list DigitalOcean domains")?;
+
+        if !response.status.is_success() {
+            let status = response.status;
+            let body = response.body;
+            return Err(http::status_error("DigitalOcean", status, Some(body)));
+        }
+
+        let list: DigitalOceanDomainListResponse = response
+            .json()
+            .context("Failed to parse DigitalOcean domain list")?;
+        Ok(list.domains.into_iter().map(|domain| domain.name).collect())
+    }
+
+    fn list_txt_records(&self) -> Result<Vec<TxtRecord>> {
+        self.fetch_domain_txt_records()
+    }
+
+    /// The team the token was created in, and the user who created it.
+    fn account_identity(&self) -> Option<Result<String>> {
+        let fetch = || -> Result<String> {
+            let response = http::HttpClient::shared()
+                .get("https://api.digitalocean.com/v2/account")
+                .header("Authorization", format!("Bearer {}", self.api_token))
+                .send_captured(self.debug.as_ref())
+                .context("Failed to read the DigitalOcean account")?;
+            if !response.status.is_success() {
+                let status = response.status;
+                let body = response.body;
+                return Err(http::status_error("DigitalOcean", status, Some(body)));
+            }
+            let DigitalOceanAccountResponse { account } = response
+                .json()
+                .context("Failed to parse the DigitalOcean account")?;
+            Ok(match account.team {
+                Some(team) => format!("{} ({})", team.name, account.email),
+                None => account.email,
+            })
+        };
+        Some(fetch())
+    }
 }
@@ -0,0 +1,140 @@
+//! Dry runs of record operations.
+//!
+//! Wrapping an adapter in [`DryRunAdapter`] turns its writes into log lines:
+//! TXT records that would be created or deleted are recorded and reported
+//! instead of being sent to the provider's API. Read-only calls such as zone
+//! access checks still reach the provider, so a dry run can validate a new
+//! configuration against production zones without changing them.
+
+use std::sync::Mutex;
+
+use anyhow::Result;
+use log::info;
+
+use super::{DnsProviderAdapter, TxtRecord};
+use crate::core::types::{PlannedRecordOperation, RecordOperationKind};
+
+pub struct DryRunAdapter {
+    label: String,
+    inner: Box<dyn DnsProviderAdapter>,
+    planned: Mutex<Vec<PlannedRecordOperation>>,
+}
+
+impl DryRunAdapter {
+    pub fn new(label: String, inner: Box<dyn DnsProviderAdapter>) -> Self {
+        Self {
+            label,
+            inner,
+            planned: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Writes skipped so far, in the order they were requested.
+    pub fn planned(&self) -> Vec<PlannedRecordOperation> {
+        self.planned
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    fn plan(&self, operation: RecordOperationKind, record_name: &str, value: Option<&str>) {
+        match (operation, value) {
+            (RecordOperationKind::Create, Some(value)) => info!(
+                "[dns] dry run: would create TXT {record_name} = {value} via {}",
+                self.label
+            ),
+            _ => info!("[dns] dry run: would delete TXT {record_name} via {}", self.label),
+        }
+        self.planned
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(PlannedRecordOperation {
+                operation,
+                record_name: record_name.to_string(),
+                value: value.map(str::to_string),
+            });
+    }
+}
+
+impl DnsProviderAdapter for DryRunAdapter {
+    fn create_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        self.plan(RecordOperationKind::Create, record_name, Some(value));
+        Ok(())
+    }
+
+    fn create_txt_batch(&self, records: &[(String, String)]) -> Result<()> {
+        for (record_name, value) in records {
+            self.plan(RecordOperationKind::Create, record_name, Some(value));
+        }
+        Ok(())
+    }
+
+    fn replace_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        self.plan(RecordOperationKind::Delete, record_name, None);
+        self.plan(RecordOperationKind::Create, record_name, Some(value));
+        Ok(())
+    }
+
+    fn cleanup_txt(&self, record_name: &str) -> Result<()> {
+        self.plan(RecordOperationKind::Delete, record_name, None);
+        Ok(())
+    }
+
+    fn verify_zone_access(&self) -> Result<()> {
+        self.inner.verify_zone_access()
+    }
+
+    fn list_zones(&self) -> Result<Vec<String>> {
+        self.inner.list_zones()
+    }
+
+    fn verify_write_access(&self) -> Option<Result<()>> {
+        self.inner.verify_write_access()
+    }
+
+    fn list_txt_records(&self) -> Result<Vec<TxtRecord>> {
+        self.inner.list_txt_records()
+    }
+
+    fn cname_target(&self, record_name: &str) -> Option<String> {
+        self.inner.cname_target(record_name)
+    }
+
+    fn account_identity(&self) -> Option<Result<String>> {
+        self.inner.account_identity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::issuance::dns_providers::UnsupportedDnsProviderAdapter;
+
+    #[test]
+    fn records_writes_without_calling_the_provider() {
+        // Every call on this adapter fails, so any write reaching it would error
+        let inner = UnsupportedDnsProviderAdapter::new("no API in tests".to_string());
+        let adapter = DryRunAdapter::new("Production".to_string(), Box::new(inner));
+
+        adapter.create_txt("_acme-challenge.example.com", "token").unwrap();
+        adapter.replace_txt("_acme-challenge.example.com", "token-2").unwrap();
+        adapter.cleanup_txt("_acme-challenge.example.com").unwrap();
+        assert!(adapter.verify_zone_access().is_err());
+
+        let planned = adapter.planned();
+        let operations: Vec<_> = planned
+            .iter()
+            .map(|op| (op.operation, op.value.as_deref()))
+            .collect();
+        assert_eq!(
+            operations,
+            vec![
+                (RecordOperationKind::Create, Some("token")),
+                (RecordOperationKind::Delete, None),
+                (RecordOperationKind::Create, Some("token-2")),
+                (RecordOperationKind::Delete, None),
+            ]
+        );
+        assert!(planned.iter().all(|op| op.record_name == "_acme-challenge.example.com"));
+    }
+}
@@ -0,0 +1,269 @@
+//! Typed errors for DNS provider API failures.
+//!
+//! Each provider reports failures with its own structured payload: Cloudflare
+//! returns numeric codes in an `errors` array, DigitalOcean a string `id`, and
+//! AWS an error code on the SDK error. Adapters turn those into a
+//! [`ProviderError`] so callers can categorize failures from the code instead
+//! of matching on message text.
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::core::types::DnsProviderErrorCategory;
+
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{message}")]
+pub struct ProviderError {
+    pub provider: &'static str,
+    pub category: DnsProviderErrorCategory,
+    /// Provider-specific error code, e.g. `9109`, `not_found` or `AccessDenied`
+    pub code: Option<String>,
+    pub status: Option<u16>,
+    pub message: String,
+}
+
+/// Credentials that work but lack permissions issuance needs.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{provider} token is missing {} for {domain}", .scopes.join(", "))]
+pub struct MissingScopes {
+    pub provider: &'static str,
+    pub domain: String,
+    /// Permission names as the provider's token editor shows them, e.g. `DNS:Edit`
+    pub scopes: Vec<&'static str>,
+}
+
+/// One entry of the `errors` array in Cloudflare API responses.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CloudflareApiError {
+    pub code: u32,
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+struct CloudflareErrorBody {
+    #[serde(default)]
+    errors: Vec<CloudflareApiError>,
+}
+
+#[derive(Deserialize)]
+struct DigitalOceanErrorBody {
+    id: String,
+    #[serde(default)]
+    message: String,
+}
+
+impl ProviderError {
+    /// Builds the error for a non-success HTTP response, reading a structured
+    /// code from the body when it has one.
+    pub fn from_status(provider: &'static str, status: StatusCode, body: Option<&str>) -> Self {
+        let body = body.map(str::trim).filter(|body| !body.is_empty());
+        if let Some(raw) = body {
+            if let Ok(parsed) = serde_json::from_str::<CloudflareErrorBody>(raw)
+                && !parsed.errors.is_empty()
+            {
+                return Self::cloudflare(Some(status), &parsed.errors);
+            }
+            if let Ok(parsed) = serde_json::from_str::<DigitalOceanErrorBody>(raw) {
+                return Self::digitalocean(status, &parsed.id, &parsed.message);
+            }
+        }
+        let category = status_category(status).unwrap_or(DnsProviderErrorCategory::Unknown);
+        let detail = body.map(str::to_string).unwrap_or_else(|| status.to_string());
+        Self::new(provider, category, None, Some(status.as_u16()), &detail)
+    }
+
+    /// Builds the error for Cloudflare's `errors` array, which also accompanies
+    /// `success: false` on otherwise successful responses.
+    pub fn cloudflare(status: Option<StatusCode>, errors: &[CloudflareApiError]) -> Self {
+        let category = errors
+            .iter()
+            .find_map(|err| cloudflare_category(err.code))
+            .or_else(|| status.and_then(status_category))
+            .unwrap_or(DnsProviderErrorCategory::Unknown);
+        let detail = if errors.is_empty() {
+            "unsuccessful response".to_string()
+        } else {
+            errors
+                .iter()
+                .map(|err| format!("{}: {}", err.code, err.message))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        Self::new(
+            "Cloudflare",
+            category,
+            errors.first().map(|err| err.code.to_string()),
+            status.map(|status| status.as_u16()),
+            &detail,
+        )
+    }
+
+    fn digitalocean(status: StatusCode, id: &str, message: &str) -> Self {
+        let category = digitalocean_category(id)
+            .or_else(|| status_category(status))
+            .unwrap_or(DnsProviderErrorCategory::Unknown);
+        let detail = if message.is_empty() {
+            id.to_string()
+        } else {
+            format!("{id}: {message}")
+        };
+        Self::new(
+            "DigitalOcean",
+            category,
+            Some(id.to_string()),
+            Some(status.as_u16()),
+            &detail,
+        )
+    }
+
+    /// Builds the error for a failed Route 53 SDK call from its AWS error code.
+    pub fn route53(context: &str, code: Option<&str>, message: Option<&str>) -> Self {
+        let category = code
+            .and_then(route53_category)
+            .unwrap_or(DnsProviderErrorCategory::Unknown);
+        let detail = match (code, message) {
+            (Some(code), Some(message)) => format!("{context}: {code}: {message}"),
+            (Some(code), None) => format!("{context}: {code}"),
+            (None, Some(message)) => format!("{context}: {message}"),
+            (None, None) => context.to_string(),
+        };
+        Self {
+            provider: "Route 53",
+            category,
+            code: code.map(str::to_string),
+            status: None,
+            message: detail,
+        }
+    }
+
+    fn new(
+        provider: &'static str,
+        category: DnsProviderErrorCategory,
+        code: Option<String>,
+        status: Option<u16>,
+        detail: &str,
+    ) -> Self {
+        let message = match category {
+            DnsProviderErrorCategory::AuthError => {
+                format!("{provider} authentication failed: {detail}")
+            }
+            DnsProviderErrorCategory::RateLimited => {
+                format!("{provider} rate limit exceeded: {detail}")
+            }
+            _ => format!("{provider} API error: {detail}"),
+        };
+        Self {
+            provider,
+            category,
+            code,
+            status,
+            message,
+        }
+    }
+}
+
+fn status_category(status: StatusCode) -> Option<DnsProviderErrorCategory> {
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Some(DnsProviderErrorCategory::AuthError),
+        StatusCode::NOT_FOUND => Some(DnsProviderErrorCategory::NotFound),
+        StatusCode::TOO_MANY_REQUESTS => Some(DnsProviderErrorCategory::RateLimited),
+        _ => None,
+    }
+}
+
+/// Cloudflare API v4 error codes; unlisted codes fall back to the HTTP status.
+fn cloudflare_category(code: u32) -> Option<DnsProviderErrorCategory> {
+    match code {
+        // Authentication error, invalid/missing auth headers, invalid token
+        6003 | 6111 | 9103 | 9106 | 9107 | 9109 | 10000 | 10001 => {
+            Some(DnsProviderErrorCategory::AuthError)
+        }
+        // No route for the zone id, invalid zone identifier, record does not exist
+        7000 | 7003 | 1001 | 81044 => Some(DnsProviderErrorCategory::NotFound),
+        // Request throttled
+        971 | 10013 => Some(DnsProviderErrorCategory::RateLimited),
+        _ => None,
+    }
+}
+
+fn digitalocean_category(id: &str) -> Option<DnsProviderErrorCategory> {
+    match id {
+        "unauthorized" | "forbidden" => Some(DnsProviderErrorCategory::AuthError),
+        "not_found" => Some(DnsProviderErrorCategory::NotFound),
+        "too_many_requests" => Some(DnsProviderErrorCategory::RateLimited),
+        _ => None,
+    }
+}
+
+fn route53_category(code: &str) -> Option<DnsProviderErrorCategory> {
+    match code {
+        "AccessDenied"
+        | "AccessDeniedException"
+        | "InvalidClientTokenId"
+        | "SignatureDoesNotMatch"
+        | "UnrecognizedClientException"
+        | "ExpiredToken"
+        | "InvalidSignatureException" => Some(DnsProviderErrorCategory::AuthError),
+        "NoSuchHostedZone" | "NoSuchChange" => Some(DnsProviderErrorCategory::NotFound),
+        "Throttling" | "ThrottlingException" | "PriorRequestNotComplete" => {
+            Some(DnsProviderErrorCategory::RateLimited)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_cloudflare_codes_over_status() {
+        let body = r#"{"success":false,"errors":[{"code":9109,"message":"Invalid access token"}]}"#;
+        let err = ProviderError::from_status("Cloudflare", StatusCode::BAD_REQUEST, Some(body));
+        assert_eq!(err.category, DnsProviderErrorCategory::AuthError);
+        assert_eq!(err.code.as_deref(), Some("9109"));
+        assert_eq!(err.status, Some(400));
+        assert!(err.message.contains("Invalid access token"));
+
+        let err = ProviderError::cloudflare(
+            None,
+            &[CloudflareApiError {
+                code: 81044,
+                message: "Record does not exist.".to_string(),
+            }],
+        );
+        assert_eq!(err.category, DnsProviderErrorCategory::NotFound);
+    }
+
+    #[test]
+    fn reads_digitalocean_ids() {
+        let body = r#"{"id":"too_many_requests","message":"API Rate limit exceeded."}"#;
+        let err = ProviderError::from_status("DigitalOcean", StatusCode::BAD_REQUEST, Some(body));
+        assert_eq!(err.category, DnsProviderErrorCategory::RateLimited);
+        assert_eq!(err.code.as_deref(), Some("too_many_requests"));
+
+        // Unknown ids fall back to the status code.
+        let body = r#"{"id":"something_new","message":"nope"}"#;
+        let err = ProviderError::from_status("DigitalOcean", StatusCode::NOT_FOUND, Some(body));
+        assert_eq!(err.category, DnsProviderErrorCategory::NotFound);
+    }
+
+    #[test]
+    fn reads_route53_codes() {
+        let err = ProviderError::route53(
+            "Failed to list Route 53 hosted zones",
+            Some("InvalidClientTokenId"),
+            Some("The security token included in the request is invalid."),
+        );
+        assert_eq!(err.category, DnsProviderErrorCategory::AuthError);
+        assert!(err.message.starts_with("Failed to list Route 53 hosted zones"));
+        assert_eq!(
+            ProviderError::route53("x", Some("Throttling"), None).category,
+            DnsProviderErrorCategory::RateLimited
+        );
+        assert_eq!(
+            ProviderError::route53("x", None, None).category,
+            DnsProviderErrorCategory::Unknown
+        );
+    }
+}
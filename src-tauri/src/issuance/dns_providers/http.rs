@@ -1,10 +1,18 @@
 use std::sync::OnceLock;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use anyhow::anyhow;
+use chrono::Utc;
 use log::warn;
 use reqwest::StatusCode;
-use reqwest::blocking::Client;
+use reqwest::blocking::{Client, RequestBuilder};
+use reqwest::header::RETRY_AFTER;
+use serde::de::DeserializeOwned;
+
+use crate::core::user_agent::OutboundIdentity;
+
+use super::debug::{CapturedCall, DebugCapture};
+use super::errors::ProviderError;
+use super::retry::{MAX_HTTP_ATTEMPTS, http_retry_delay, is_retryable_status, parse_retry_after};
 
 pub struct HttpClient;
 
@@ -41,17 +49,93 @@ fn parse_timeout(env_value: Option<&str>) -> Duration {
     Duration::from_secs(timeout)
 }
 
-pub fn status_error(provider: &str, status: StatusCode, body: Option<String>) -> anyhow::Error {
-    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
-        return anyhow!("{provider} authentication failed");
-    }
-    if status == StatusCode::TOO_MANY_REQUESTS {
-        return anyhow!("{provider} rate limit exceeded");
+/// Provider API response with its body already read, so it can be captured.
+pub struct ApiResponse {
+    pub status: StatusCode,
+    pub body: String,
+}
+
+impl ApiResponse {
+    pub fn json<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_str(&self.body)
     }
-    if let Some(body) = body {
-        return anyhow!("{provider} API error: {body}");
+}
+
+/// Sends provider API requests and reads the body, recording the call when
+/// debug capture is on for the provider.
+///
+/// Responses with 429 or a 5xx status are retried, honouring `Retry-After`
+/// and otherwise backing off with jitter. Once retries run out the last
+/// response is returned, so callers surface it through [`status_error`].
+pub trait SendCaptured {
+    fn send_captured(self, debug: Option<&DebugCapture>) -> reqwest::Result<ApiResponse>;
+}
+
+impl SendCaptured for RequestBuilder {
+    fn send_captured(self, debug: Option<&DebugCapture>) -> reqwest::Result<ApiResponse> {
+        let mut request = self.build()?;
+        OutboundIdentity::current().apply_headers(request.headers_mut());
+        let method = request.method().to_string();
+        let url = request.url().to_string();
+        let request_body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned());
+
+        let mut attempt = 1;
+        loop {
+            // Streaming bodies cannot be cloned; such requests are sent once.
+            let retry_request = request.try_clone();
+            let started = Instant::now();
+            let result = HttpClient::shared().execute(request).and_then(|response| {
+                let status = response.status();
+                let retry_after = response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| parse_retry_after(value, Utc::now()));
+                response
+                    .text()
+                    .map(|body| (ApiResponse { status, body }, retry_after))
+            });
+
+            if let Some(debug) = debug {
+                let response = result.as_ref().ok().map(|(response, _)| response);
+                debug.record(CapturedCall {
+                    method: &method,
+                    url: &url,
+                    request_body: request_body.as_deref(),
+                    status: response.map(|response| response.status.as_u16()),
+                    response_body: response.map(|response| response.body.as_str()),
+                    error: result.as_ref().err().map(ToString::to_string),
+                    elapsed: started.elapsed(),
+                });
+            }
+
+            let (response, retry_after) = result?;
+            if !is_retryable_status(response.status) {
+                return Ok(response);
+            }
+            let (Some(next), Some(delay)) = (retry_request, http_retry_delay(attempt, retry_after))
+            else {
+                return Ok(response);
+            };
+            warn!(
+                "[dns-http] {method} {url} returned {}; retrying in {}ms ({attempt}/{})",
+                response.status,
+                delay.as_millis(),
+                MAX_HTTP_ATTEMPTS
+            );
+            std::thread::sleep(delay);
+            request = next;
+            attempt += 1;
+        }
     }
-    anyhow!("{provider} API error: {status}")
+}
+
+/// Error for a non-success response, categorized from the provider's error payload.
+pub fn status_error(provider: &'static str, status: StatusCode, body: Option<String>) -> anyhow::Error {
+    ProviderError::from_status(provider, status, body.as_deref()).into()
 }
 
 #[cfg(test)]
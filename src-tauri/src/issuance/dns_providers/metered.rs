@@ -0,0 +1,98 @@
+//! Per-provider call metrics.
+//!
+//! Every adapter built for a configured provider is wrapped so each API
+//! operation counts towards that provider's row in the local metrics.
+
+use std::time::Instant;
+
+use anyhow::Result;
+
+use super::{DnsProviderAdapter, TxtRecord};
+use crate::core::{metrics, types::MetricKind};
+
+pub struct MeteredAdapter {
+    provider_id: String,
+    /// Shown in the metrics view, e.g. `Production (route53)`
+    label: String,
+    inner: Box<dyn DnsProviderAdapter>,
+}
+
+impl MeteredAdapter {
+    pub fn new(provider_id: String, label: String, inner: Box<dyn DnsProviderAdapter>) -> Self {
+        Self {
+            provider_id,
+            label,
+            inner,
+        }
+    }
+
+    fn measure<T>(&self, operation: impl FnOnce() -> Result<T>) -> Result<T> {
+        let started = Instant::now();
+        let result = operation();
+        let error = result.as_ref().err().map(|err| format!("{err:#}"));
+        metrics::record(
+            MetricKind::DnsProvider,
+            &self.provider_id,
+            &self.label,
+            started.elapsed(),
+            error.as_deref(),
+        );
+        result
+    }
+}
+
+impl DnsProviderAdapter for MeteredAdapter {
+    fn create_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        self.measure(|| self.inner.create_txt(record_name, value))
+    }
+
+    fn create_txt_batch(&self, records: &[(String, String)]) -> Result<()> {
+        self.measure(|| self.inner.create_txt_batch(records))
+    }
+
+    fn replace_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        self.measure(|| self.inner.replace_txt(record_name, value))
+    }
+
+    fn cleanup_txt(&self, record_name: &str) -> Result<()> {
+        self.measure(|| self.inner.cleanup_txt(record_name))
+    }
+
+    fn verify_zone_access(&self) -> Result<()> {
+        self.measure(|| self.inner.verify_zone_access())
+    }
+
+    fn list_zones(&self) -> Result<Vec<String>> {
+        self.measure(|| self.inner.list_zones())
+    }
+
+    fn verify_write_access(&self) -> Option<Result<()>> {
+        let mut supported = true;
+        let result = self.measure(|| {
+            self.inner.verify_write_access().unwrap_or_else(|| {
+                supported = false;
+                Ok(())
+            })
+        });
+        supported.then_some(result)
+    }
+
+    fn list_txt_records(&self) -> Result<Vec<TxtRecord>> {
+        self.measure(|| self.inner.list_txt_records())
+    }
+
+    fn cname_target(&self, record_name: &str) -> Option<String> {
+        self.inner.cname_target(record_name)
+    }
+
+    fn account_identity(&self) -> Option<Result<String>> {
+        let mut supported = true;
+        let result = self.measure(|| {
+            self.inner.account_identity().unwrap_or_else(|| {
+                supported = false;
+                Ok(String::new())
+            })
+        });
+        supported.then_some(result)
+    }
+}
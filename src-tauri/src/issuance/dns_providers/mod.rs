@@ -1,26 +1,119 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 
-use crate::{domain::normalize_domain_for_storage, secrets::manager::SecretManager, storage::dns::DnsProvider};
+use crate::{
+    core::mappers::{
+        provider_api_url, provider_config_str, provider_debug_capture, provider_server_id,
+        provider_zone_override,
+    },
+    domain::normalize_domain_for_storage,
+    secrets::manager::SecretManager,
+    storage::dns::{DnsConfigStore, DnsProvider},
+};
 
+mod acme_dns;
 mod base;
 mod cloudflare;
+mod debug;
+mod delegation;
+mod desec;
 mod digitalocean;
+mod dry_run;
+mod metered;
+pub(crate) mod errors;
 pub(crate) mod http;
+mod powerdns;
 mod retry;
+mod rfc2136;
 mod route53;
+mod script;
+mod suffix_router;
 mod testing;
+mod zone_access;
+mod zone_cache;
 
 pub use base::{AtomicDnsOperations, DnsProviderBase, DnsRecord};
+pub use debug::{DebugCapture, captured_calls, clear_captured_calls, redact_text};
+pub use delegation::{lookup_nameservers, nameservers_match_provider, verify_domain_suffix};
 pub use testing::query_google_dns;
+pub use zone_access::resolve_provider_by_zone_access;
+pub(crate) use testing::resolve_dns_timeout;
 pub use retry::{poll_dns_propagation, retry_provider_verification};
 
+pub use acme_dns::{
+    AcmeDnsAdapter, AcmeDnsRegistration, parse_acme_dns_registrations, register_acme_dns_account,
+};
 pub use cloudflare::CloudflareAdapter;
+pub use desec::DesecAdapter;
 pub use digitalocean::DigitalOceanAdapter;
-pub use route53::Route53Adapter;
+pub use dry_run::DryRunAdapter;
+pub use metered::MeteredAdapter;
+pub use powerdns::PowerDnsAdapter;
+pub use rfc2136::{Rfc2136Adapter, TsigAlgorithm, decode_tsig_secret};
+pub use route53::{profile_from_settings, AssumeRole, Route53Adapter};
+pub use script::{ScriptAdapter, ScriptHook};
+pub use suffix_router::SuffixRouter;
+pub use zone_cache::ZoneCache;
+
+/// One TXT value published in a provider's zone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxtRecord {
+    /// Fully qualified name, without a trailing dot
+    pub name: String,
+    /// Value without surrounding quotes
+    pub value: String,
+}
+
+impl TxtRecord {
+    pub fn new(name: &str, value: &str) -> Self {
+        Self {
+            name: name.trim_end_matches('.').to_ascii_lowercase(),
+            value: value.trim().trim_matches('"').to_string(),
+        }
+    }
+}
 
 pub trait DnsProviderAdapter: Send + Sync {
+    /// Adds `value` to the TXT values at `record_name`, keeping the others, so
+    /// a wildcard and its apex can publish their challenges under one name.
     fn create_txt(&self, record_name: &str, value: &str) -> Result<()>;
+    /// Adds every `(record name, value)` pair as [`Self::create_txt`] does.
+    /// Providers that can apply several changes in one request override it.
+    fn create_txt_batch(&self, records: &[(String, String)]) -> Result<()> {
+        for (record_name, value) in records {
+            self.create_txt(record_name, value)?;
+        }
+        Ok(())
+    }
+    /// Publishes `value` as the only TXT value at `record_name`.
+    fn replace_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        self.cleanup_txt(record_name)?;
+        self.create_txt(record_name, value)
+    }
+    /// Removes every TXT value at `record_name`.
     fn cleanup_txt(&self, record_name: &str) -> Result<()>;
+    /// Confirms the credentials can see the zone for each configured suffix.
+    fn verify_zone_access(&self) -> Result<()>;
+    /// Lists every zone the credentials can see, by name.
+    fn list_zones(&self) -> Result<Vec<String>>;
+    /// Confirms the credentials may change the zone's TXT records without
+    /// changing any; `None` where the API has no way to tell short of a write.
+    fn verify_write_access(&self) -> Option<Result<()>> {
+        None
+    }
+    /// Lists every TXT value in the zones of the configured suffixes.
+    fn list_txt_records(&self) -> Result<Vec<TxtRecord>> {
+        Err(anyhow!("This provider cannot list its TXT records"))
+    }
+    /// For providers that answer challenges outside the user's zone, the
+    /// name the challenge record must be CNAMEd to while that CNAME is missing.
+    fn cname_target(&self, _record_name: &str) -> Option<String> {
+        None
+    }
+    /// The account the credentials belong to, as the provider's API names
+    /// it; `None` where the API does not say.
+    fn account_identity(&self) -> Option<Result<String>> {
+        None
+    }
 }
 
 pub(crate) fn matches_zone(domain_suffix: &str, zone_name: &str) -> bool {
@@ -53,13 +146,119 @@ impl DnsProviderAdapter for UnsupportedDnsProviderAdapter {
     fn cleanup_txt(&self, _record_name: &str) -> Result<()> {
         Err(anyhow::anyhow!(self.reason.clone()))
     }
+
+    fn verify_zone_access(&self) -> Result<()> {
+        Err(anyhow::anyhow!(self.reason.clone()))
+    }
+
+    fn list_zones(&self) -> Result<Vec<String>> {
+        Err(anyhow::anyhow!(self.reason.clone()))
+    }
 }
 
 pub fn adapter_for_provider(
     provider: &DnsProvider,
     secrets: &SecretManager,
+) -> Box<dyn DnsProviderAdapter> {
+    build_adapter(provider, secrets, None, None)
+}
+
+/// Like [`adapter_for_provider`], with created TXT records using `record_ttl`
+/// instead of the provider's default TTL.
+pub fn adapter_with_record_ttl(
+    provider: &DnsProvider,
+    secrets: &SecretManager,
+    record_ttl: Option<u32>,
+) -> Box<dyn DnsProviderAdapter> {
+    build_adapter(provider, secrets, record_ttl, None)
+}
+
+/// Adapter for TXT operations during issuance. Zones it discovers are kept
+/// in `dns_store` so later operations on the provider skip the lookup.
+pub fn cached_adapter_for_provider(
+    provider: &DnsProvider,
+    secrets: &SecretManager,
+    dns_store: &DnsConfigStore,
+) -> Box<dyn DnsProviderAdapter> {
+    let zones = ZoneCache::new(dns_store.clone(), provider.id.clone());
+    build_adapter(provider, secrets, None, Some(zones))
+}
+
+/// One adapter per configured suffix; with several suffixes, a
+/// [`SuffixRouter`] sends each record to the adapter whose suffix covers it.
+/// The result is wrapped in a [`MeteredAdapter`] for the provider's metrics.
+fn build_adapter(
+    provider: &DnsProvider,
+    secrets: &SecretManager,
+    record_ttl: Option<u32>,
+    zones: Option<ZoneCache>,
+) -> Box<dyn DnsProviderAdapter> {
+    let adapter: Box<dyn DnsProviderAdapter> = match provider.domain_suffixes.as_slice() {
+        [] => build_suffix_adapter(provider, String::new(), secrets, record_ttl, zones),
+        [suffix] => build_suffix_adapter(provider, suffix.clone(), secrets, record_ttl, zones),
+        suffixes => Box::new(SuffixRouter::new(
+            suffixes
+                .iter()
+                .map(|suffix| {
+                    let adapter = build_suffix_adapter(
+                        provider,
+                        suffix.clone(),
+                        secrets,
+                        record_ttl,
+                        zones.clone(),
+                    );
+                    (suffix.clone(), adapter)
+                })
+                .collect(),
+        )),
+    };
+    let label = format!("{} ({})", provider.label, provider.provider_type);
+    Box::new(MeteredAdapter::new(provider.id.clone(), label, adapter))
+}
+
+fn build_suffix_adapter(
+    provider: &DnsProvider,
+    domain_suffix: String,
+    secrets: &SecretManager,
+    record_ttl: Option<u32>,
+    zones: Option<ZoneCache>,
 ) -> Box<dyn DnsProviderAdapter> {
     match provider.provider_type.as_str() {
+        "acme_dns" => {
+            let Some(api_url) = provider_api_url(provider) else {
+                return Box::new(UnsupportedDnsProviderAdapter::new(
+                    "acme-dns provider missing server URL".to_string(),
+                ));
+            };
+            if provider.secret_refs.is_empty() {
+                return Box::new(UnsupportedDnsProviderAdapter::new(
+                    "acme-dns provider missing registrations".to_string(),
+                ));
+            }
+            let registrations = secrets
+                .resolve_secret(&provider.secret_refs[0])
+                .map_err(|err| anyhow::anyhow!("Failed to resolve acme-dns registrations: {}", err))
+                .and_then(|bytes| {
+                    String::from_utf8(bytes)
+                        .map_err(|_| anyhow::anyhow!("Failed to decode acme-dns registrations"))
+                })
+                .and_then(|raw| parse_acme_dns_registrations(&raw));
+            match registrations {
+                Ok(registrations) => {
+                    let passwords = registrations
+                        .values()
+                        .map(|registration| registration.password.clone())
+                        .collect();
+                    let debug = provider_debug_capture(provider)
+                        .then(|| DebugCapture::new(provider.id.clone(), passwords));
+                    Box::new(
+                        AcmeDnsAdapter::new(&api_url, registrations, domain_suffix)
+                            .with_debug_capture(debug),
+                    )
+                }
+                Err(err) => Box::new(UnsupportedDnsProviderAdapter::new(err.to_string())),
+            }
+        }
         "cloudflare" => {
             if provider.secret_refs.is_empty() {
                 return Box::new(UnsupportedDnsProviderAdapter::new(
@@ -70,12 +269,13 @@ pub fn adapter_for_provider(
             match secrets.resolve_secret(token_ref) {
                 Ok(token_bytes) => {
                     if let Ok(token) = String::from_utf8(token_bytes) {
-                        let domain_suffix = provider
-                            .domain_suffixes
-                            .first()
-                            .cloned()
-                            .unwrap_or_default();
-                        Box::new(CloudflareAdapter::new(token, domain_suffix))
+                        let debug = debug_capture(provider, &token);
+                        Box::new(
+                            CloudflareAdapter::new(token, domain_suffix)
+                                .with_record_ttl(record_ttl)
+                                .with_debug_capture(debug)
+                                .with_zone_cache(zones),
+                        )
                     } else {
                         Box::new(UnsupportedDnsProviderAdapter::new(
                             "Failed to decode Cloudflare API token".to_string(),
@@ -98,12 +298,12 @@ pub fn adapter_for_provider(
             match secrets.resolve_secret(token_ref) {
                 Ok(token_bytes) => {
                     if let Ok(token) = String::from_utf8(token_bytes) {
-                        let domain = provider
-                            .domain_suffixes
-                            .first()
-                            .cloned()
-                            .unwrap_or_default();
-                        Box::new(DigitalOceanAdapter::new(token, domain))
+                        let debug = debug_capture(provider, &token);
+                        Box::new(
+                            DigitalOceanAdapter::new(token, domain_suffix)
+                                .with_record_ttl(record_ttl)
+                                .with_debug_capture(debug),
+                        )
                     } else {
                         Box::new(UnsupportedDnsProviderAdapter::new(
                             "Failed to decode DigitalOcean API token".to_string(),
@@ -116,8 +316,148 @@ pub fn adapter_for_provider(
                 ))),
             }
         }
+        "desec" => {
+            if provider.secret_refs.is_empty() {
+                return Box::new(UnsupportedDnsProviderAdapter::new(
+                    "deSEC provider missing API token".to_string(),
+                ));
+            }
+            let token_ref = &provider.secret_refs[0];
+            match secrets.resolve_secret(token_ref) {
+                Ok(token_bytes) => {
+                    if let Ok(token) = String::from_utf8(token_bytes) {
+                        let debug = debug_capture(provider, &token);
+                        Box::new(
+                            DesecAdapter::new(token, domain_suffix)
+                                .with_record_ttl(record_ttl)
+                                .with_debug_capture(debug)
+                                .with_zone_cache(zones),
+                        )
+                    } else {
+                        Box::new(UnsupportedDnsProviderAdapter::new(
+                            "Failed to decode deSEC API token".to_string(),
+                        ))
+                    }
+                }
+                Err(err) => Box::new(UnsupportedDnsProviderAdapter::new(format!(
+                    "Failed to resolve deSEC API token: {}",
+                    err
+                ))),
+            }
+        }
+        "powerdns" => {
+            let Some(api_url) = provider_api_url(provider) else {
+                return Box::new(UnsupportedDnsProviderAdapter::new(
+                    "PowerDNS provider missing API URL".to_string(),
+                ));
+            };
+            if provider.secret_refs.is_empty() {
+                return Box::new(UnsupportedDnsProviderAdapter::new(
+                    "PowerDNS provider missing API key".to_string(),
+                ));
+            }
+            let key_ref = &provider.secret_refs[0];
+            match secrets.resolve_secret(key_ref) {
+                Ok(key_bytes) => {
+                    if let Ok(api_key) = String::from_utf8(key_bytes) {
+                        let server_id = provider_server_id(provider);
+                        let debug = debug_capture(provider, &api_key);
+                        Box::new(
+                            PowerDnsAdapter::new(
+                                &api_url,
+                                server_id.as_deref(),
+                                api_key,
+                                domain_suffix,
+                            )
+                            .with_record_ttl(record_ttl)
+                            .with_debug_capture(debug),
+                        )
+                    } else {
+                        Box::new(UnsupportedDnsProviderAdapter::new(
+                            "Failed to decode PowerDNS API key".to_string(),
+                        ))
+                    }
+                }
+                Err(err) => Box::new(UnsupportedDnsProviderAdapter::new(format!(
+                    "Failed to resolve PowerDNS API key: {}",
+                    err
+                ))),
+            }
+        }
+        "rfc2136" => {
+            let (Some(server), Some(key_name)) = (
+                provider_config_str(provider, "server"),
+                provider_config_str(provider, "tsig_key_name"),
+            ) else {
+                return Box::new(UnsupportedDnsProviderAdapter::new(
+                    "RFC 2136 provider missing server or TSIG key name".to_string(),
+                ));
+            };
+            let algorithm = match TsigAlgorithm::from_name(
+                provider_config_str(provider, "tsig_algorithm").as_deref(),
+            ) {
+                Ok(algorithm) => algorithm,
+                Err(err) => return Box::new(UnsupportedDnsProviderAdapter::new(err.to_string())),
+            };
+            if provider.secret_refs.is_empty() {
+                return Box::new(UnsupportedDnsProviderAdapter::new(
+                    "RFC 2136 provider missing TSIG secret".to_string(),
+                ));
+            }
+            let secret = secrets
+                .resolve_secret(&provider.secret_refs[0])
+                .map_err(|err| anyhow::anyhow!("Failed to resolve TSIG secret: {}", err))
+                .and_then(|bytes| {
+                    String::from_utf8(bytes)
+                        .map_err(|_| anyhow::anyhow!("Failed to decode TSIG secret"))
+                })
+                .and_then(|secret| decode_tsig_secret(&secret));
+            match secret {
+                Ok(secret) => {
+                    Box::new(
+                        Rfc2136Adapter::new(server, key_name, algorithm, secret, domain_suffix)
+                            .with_zone(provider_zone_override(provider))
+                            .with_record_ttl(record_ttl),
+                    )
+                }
+                Err(err) => Box::new(UnsupportedDnsProviderAdapter::new(err.to_string())),
+            }
+        }
         "route53" => {
+            let assume_role = match AssumeRole::from_settings(
+                provider_config_str(provider, "role_arn").as_deref(),
+                provider_config_str(provider, "external_id").as_deref(),
+            ) {
+                Ok(role) => role,
+                Err(err) => return Box::new(UnsupportedDnsProviderAdapter::new(err.to_string())),
+            };
+            let profile =
+                match profile_from_settings(provider_config_str(provider, "profile").as_deref()) {
+                    Ok(profile) => profile,
+                    Err(err) => {
+                        return Box::new(UnsupportedDnsProviderAdapter::new(err.to_string()))
+                    }
+                };
+            if profile.is_some() {
+                // A named profile replaces stored keys
+                return Box::new(
+                    Route53Adapter::from_default_chain(domain_suffix)
+                        .with_profile(profile)
+                        .with_assume_role(assume_role)
+                        .with_record_ttl(record_ttl)
+                        .with_zone_cache(zones),
+                );
+            }
             if provider.secret_refs.len() < 2 {
+                if assume_role.is_some() {
+                    // The role is assumed with the default AWS credential chain
+                    return Box::new(
+                        Route53Adapter::from_default_chain(domain_suffix)
+                            .with_assume_role(assume_role)
+                            .with_record_ttl(record_ttl)
+                            .with_zone_cache(zones),
+                    );
+                }
                 return Box::new(UnsupportedDnsProviderAdapter::new(
                     "Route 53 provider missing access key or secret key".to_string(),
                 ));
@@ -134,12 +474,12 @@ pub fn adapter_for_provider(
                         String::from_utf8(secret_key_bytes),
                     ) {
                         (Ok(access_key), Ok(secret_key)) => {
-                            let domain_suffix = provider
-                                .domain_suffixes
-                                .first()
-                                .cloned()
-                                .unwrap_or_default();
-                            Box::new(Route53Adapter::new(access_key, secret_key, domain_suffix))
+                            Box::new(
+                                Route53Adapter::new(access_key, secret_key, domain_suffix)
+                                    .with_assume_role(assume_role)
+                                    .with_record_ttl(record_ttl)
+                                    .with_zone_cache(zones),
+                            )
                         }
                         _ => Box::new(UnsupportedDnsProviderAdapter::new(
                             "Failed to decode Route 53 credentials".to_string(),
@@ -151,6 +491,45 @@ pub fn adapter_for_provider(
                 )),
             }
         }
+        "script" => {
+            let hook = match (
+                provider_config_str(provider, "command"),
+                provider_config_str(provider, "webhook_url"),
+            ) {
+                (Some(command), None) => ScriptHook::Command(command),
+                (None, Some(url)) => ScriptHook::Webhook(url),
+                _ => {
+                    return Box::new(UnsupportedDnsProviderAdapter::new(
+                        "Script provider needs either a command or a webhook URL".to_string(),
+                    ));
+                }
+            };
+            // The webhook token is optional
+            let token = match provider.secret_refs.first() {
+                Some(token_ref) => match secrets
+                    .resolve_secret(token_ref)
+                    .map_err(|err| err.to_string())
+                    .and_then(|bytes| String::from_utf8(bytes).map_err(|err| err.to_string()))
+                {
+                    Ok(token) => Some(token),
+                    Err(err) => {
+                        return Box::new(UnsupportedDnsProviderAdapter::new(format!(
+                            "Failed to resolve webhook token: {}",
+                            err
+                        )));
+                    }
+                },
+                None => None,
+            };
+            let debug = provider_debug_capture(provider).then(|| {
+                DebugCapture::new(provider.id.clone(), token.iter().cloned().collect())
+            });
+            Box::new(
+                ScriptAdapter::new(hook, domain_suffix)
+                    .with_webhook_token(token)
+                    .with_debug_capture(debug),
+            )
+        }
         "manual" => Box::new(UnsupportedDnsProviderAdapter::new(
             "manual DNS providers do not support automated test connections".to_string(),
         )),
@@ -161,6 +540,12 @@ pub fn adapter_for_provider(
     }
 }
 
+/// Capture handle when `debug_capture` is on for the provider.
+fn debug_capture(provider: &DnsProvider, api_token: &str) -> Option<DebugCapture> {
+    provider_debug_capture(provider)
+        .then(|| DebugCapture::new(provider.id.clone(), vec![api_token.to_string()]))
+}
+
 #[cfg(test)]
 mod tests {
     use super::matches_zone;
@@ -0,0 +1,416 @@
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result, anyhow};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    DnsProviderAdapter, TxtRecord,
+    base::{
+        AtomicDnsOperations, DnsProviderBase, DnsRecord, parse_rrset_record_id, rrset_record_id,
+    },
+    debug::DebugCapture,
+    http::{self, SendCaptured},
+    matches_zone,
+};
+
+const DEFAULT_SERVER_ID: &str = "localhost";
+const DEFAULT_TTL: u32 = 60;
+
+/// Adapter for a self-hosted PowerDNS authoritative server's HTTP API.
+/// PowerDNS keeps all TXT values of a name in one RRset, so values are merged
+/// into and removed from the RRset like deSEC. After each change the zone is
+/// notified so secondaries transfer it before propagation polling starts.
+pub struct PowerDnsAdapter {
+    api_key: String,
+    server_url: String,
+    zone_cache: OnceLock<PowerDnsZone>,
+    domain_suffix: String,
+    record_ttl: Option<u32>,
+    debug: Option<DebugCapture>,
+}
+
+#[derive(Clone, Deserialize)]
+struct PowerDnsZone {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct PowerDnsZoneDetail {
+    #[serde(default)]
+    rrsets: Vec<PowerDnsRrset>,
+}
+
+#[derive(Deserialize)]
+struct PowerDnsRrset {
+    name: String,
+    #[serde(rename = "type")]
+    record_type: String,
+    #[serde(default)]
+    records: Vec<PowerDnsRecord>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct PowerDnsRecord {
+    content: String,
+    #[serde(default)]
+    disabled: bool,
+}
+
+#[derive(Serialize)]
+struct PowerDnsRrsetPatch<'a> {
+    name: &'a str,
+    #[serde(rename = "type")]
+    record_type: &'static str,
+    ttl: u32,
+    changetype: &'static str,
+    records: Vec<PowerDnsRecord>,
+}
+
+#[derive(Serialize)]
+struct PowerDnsZonePatch<'a> {
+    rrsets: [PowerDnsRrsetPatch<'a>; 1],
+}
+
+impl PowerDnsAdapter {
+    /// `api_url` is the webserver address, with or without the `/api/v1` path.
+    pub fn new(
+        api_url: &str,
+        server_id: Option<&str>,
+        api_key: String,
+        domain_suffix: String,
+    ) -> Self {
+        Self {
+            api_key,
+            server_url: server_url(api_url, server_id),
+            zone_cache: OnceLock::new(),
+            domain_suffix,
+            record_ttl: None,
+            debug: None,
+        }
+    }
+
+    /// Overrides the TTL of created TXT records.
+    pub fn with_record_ttl(mut self, ttl: Option<u32>) -> Self {
+        self.record_ttl = ttl;
+        self
+    }
+
+    /// Records API calls for the provider's debug log.
+    pub fn with_debug_capture(mut self, debug: Option<DebugCapture>) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    fn format_txt_content(value: &str) -> String {
+        format!("\"{}\"", value.trim().trim_matches('"'))
+    }
+
+    fn fetch_zones(&self) -> Result<Vec<PowerDnsZone>> {
+        let response = http::HttpClient::shared()
+            .get(format!("{}/zones", self.server_url))
+            .header("X-API-Key", &self.api_key)
+            .send_captured(self.debug.as_ref())
+            .context("Failed to list PowerDNS zones")?;
+
+        if !response.status.is_success() {
+            let status = response.status;
+            let body = response.body;
+            return Err(http::status_error("PowerDNS", status, Some(body)));
+        }
+
+        response
+            .json()
+            .context("Failed to parse PowerDNS zone list")
+    }
+
+    /// Zone for the suffix, looked up once per adapter.
+    fn discover_zone(&self) -> Result<PowerDnsZone> {
+        if let Some(zone) = self.zone_cache.get() {
+            return Ok(zone.clone());
+        }
+        let zone = self.lookup_zone()?;
+        Ok(self.zone_cache.get_or_init(|| zone).clone())
+    }
+
+    /// Finds the most specific zone containing the suffix.
+    fn lookup_zone(&self) -> Result<PowerDnsZone> {
+        self.fetch_zones()?
+            .into_iter()
+            .filter(|zone| matches_zone(&self.domain_suffix, zone.name.trim_end_matches('.')))
+            .max_by_key(|zone| zone.name.len())
+            .ok_or_else(|| {
+                anyhow!(
+                    "No PowerDNS zone found for domain suffix: {}",
+                    self.domain_suffix
+                )
+            })
+    }
+
+    /// Returns the contents of the TXT RRset at `record_name`, empty when the
+    /// RRset does not exist.
+    fn fetch_rrset(&self, record_name: &str) -> Result<Vec<String>> {
+        let zone = self.discover_zone()?;
+        let name = canonical_name(record_name);
+        let response = http::HttpClient::shared()
+            .get(format!("{}/zones/{}", self.server_url, zone.id))
+            .query(&[("rrset_name", name.as_str()), ("rrset_type", "TXT")])
+            .header("X-API-Key", &self.api_key)
+            .send_captured(self.debug.as_ref())
+            .context("Failed to fetch PowerDNS RRset")?;
+
+        if !response.status.is_success() {
+            let status = response.status;
+            let body = response.body;
+            return Err(http::status_error("PowerDNS", status, Some(body)));
+        }
+
+        // Older servers ignore the rrset filters and return the whole zone
+        let detail: PowerDnsZoneDetail =
+            response.json().context("Failed to parse PowerDNS zone")?;
+        Ok(detail
+            .rrsets
+            .into_iter()
+            .filter(|rrset| rrset.record_type == "TXT" && rrset.name.eq_ignore_ascii_case(&name))
+            .flat_map(|rrset| rrset.records)
+            .map(|record| record.content)
+            .collect())
+    }
+
+    /// Every TXT value of the zone. Disabled records are not published and
+    /// are left out.
+    fn fetch_zone_txt_records(&self) -> Result<Vec<TxtRecord>> {
+        let zone = self.discover_zone()?;
+        let response = http::HttpClient::shared()
+            .get(format!("{}/zones/{}", self.server_url, zone.id))
+            .header("X-API-Key", &self.api_key)
+            .send_captured(self.debug.as_ref())
+            .context("Failed to fetch PowerDNS zone")?;
+
+        if !response.status.is_success() {
+            let status = response.status;
+            let body = response.body;
+            return Err(http::status_error("PowerDNS", status, Some(body)));
+        }
+
+        let detail: PowerDnsZoneDetail =
+            response.json().context("Failed to parse PowerDNS zone")?;
+        Ok(detail
+            .rrsets
+            .iter()
+            .filter(|rrset| rrset.record_type == "TXT")
+            .flat_map(|rrset| {
+                rrset
+                    .records
+                    .iter()
+                    .filter(|record| !record.disabled)
+                    .map(|record| TxtRecord::new(&rrset.name, &record.content))
+            })
+            .collect())
+    }
+
+    /// Replaces the TXT RRset at `record_name` with `records`, deleting the
+    /// RRset when none are left.
+    fn write_rrset(&self, record_name: &str, records: Vec<String>) -> Result<()> {
+        let zone = self.discover_zone()?;
+        let name = canonical_name(record_name);
+        let patch = PowerDnsZonePatch {
+            rrsets: [PowerDnsRrsetPatch {
+                name: &name,
+                record_type: "TXT",
+                ttl: self.record_ttl.unwrap_or(DEFAULT_TTL),
+                changetype: if records.is_empty() { "DELETE" } else { "REPLACE" },
+                records: records
+                    .into_iter()
+                    .map(|content| PowerDnsRecord {
+                        content,
+                        disabled: false,
+                    })
+                    .collect(),
+            }],
+        };
+
+        let response = http::HttpClient::shared()
+            .patch(format!("{}/zones/{}", self.server_url, zone.id))
+            .header("X-API-Key", &self.api_key)
+            .json(&patch)
+            .send_captured(self.debug.as_ref())
+            .context("Failed to update PowerDNS RRset")?;
+
+        if !response.status.is_success() {
+            let status = response.status;
+            let body = response.body;
+            return Err(http::status_error("PowerDNS", status, Some(body)));
+        }
+        Ok(())
+    }
+
+    /// Asks the server to send NOTIFY to the zone's secondaries.
+    fn notify_zone(&self) -> Result<()> {
+        let zone = self.discover_zone()?;
+        let response = http::HttpClient::shared()
+            .put(format!("{}/zones/{}/notify", self.server_url, zone.id))
+            .header("X-API-Key", &self.api_key)
+            .send_captured(self.debug.as_ref())
+            .context("Failed to notify PowerDNS zone")?;
+
+        if !response.status.is_success() {
+            let status = response.status;
+            let body = response.body;
+            return Err(http::status_error("PowerDNS", status, Some(body)));
+        }
+        Ok(())
+    }
+
+    /// Notifies secondaries; a failure only delays propagation, so it is
+    /// logged instead of failing the change.
+    fn notify_secondaries(&self) {
+        if let Err(err) = self.notify_zone() {
+            warn!(
+                "[dns] PowerDNS zone notify failed for {}: {}",
+                self.domain_suffix, err
+            );
+        }
+    }
+}
+
+/// API base for one server, e.g. `https://pdns:8081/api/v1/servers/localhost`.
+fn server_url(api_url: &str, server_id: Option<&str>) -> String {
+    let base = api_url.trim().trim_end_matches('/');
+    let base = base.strip_suffix("/api/v1").unwrap_or(base);
+    format!(
+        "{base}/api/v1/servers/{}",
+        server_id.unwrap_or(DEFAULT_SERVER_ID)
+    )
+}
+
+/// PowerDNS names RRsets with a trailing dot.
+fn canonical_name(record_name: &str) -> String {
+    format!("{}.", record_name.trim_end_matches('.'))
+}
+
+impl AtomicDnsOperations for PowerDnsAdapter {
+    /// Merges `value` into the TXT RRset at `record_name`, keeping the values
+    /// already there. The returned ID identifies the value within the RRset.
+    fn create_one_record(&self, record_name: &str, value: &str) -> Result<String> {
+        let normalized = self.normalize_value(value);
+        let mut records = self.fetch_rrset(record_name)?;
+        if !records
+            .iter()
+            .any(|existing| self.normalize_value(existing) == normalized)
+        {
+            records.push(Self::format_txt_content(value));
+            self.write_rrset(record_name, records)?;
+        }
+        Ok(rrset_record_id(record_name, &normalized))
+    }
+
+    /// Removes one value from its RRset; the RRset is deleted with its last value.
+    fn delete_one_record(&self, record_id: &str) -> Result<()> {
+        let (record_name, value) = parse_rrset_record_id(record_id)?;
+        let mut records = self.fetch_rrset(record_name)?;
+        let before = records.len();
+        records.retain(|existing| self.normalize_value(existing) != value);
+        if records.len() == before {
+            // Value already gone
+            return Ok(());
+        }
+        self.write_rrset(record_name, records)
+    }
+
+    /// Lists each value of the RRset as its own record.
+    fn list_records(&self, record_name: &str) -> Result<Vec<DnsRecord>> {
+        let records = self.fetch_rrset(record_name)?;
+        Ok(records
+            .iter()
+            .map(|value| DnsRecord {
+                id: rrset_record_id(record_name, &self.normalize_value(value)),
+                name: record_name.to_string(),
+                value: value.clone(),
+            })
+            .collect())
+    }
+
+    fn get_zone_id(&self, _domain: &str) -> Result<String> {
+        Ok(self.discover_zone()?.id)
+    }
+}
+
+impl DnsProviderBase for PowerDnsAdapter {
+    fn atomic_ops(&self) -> &dyn AtomicDnsOperations {
+        self
+    }
+}
+
+impl DnsProviderAdapter for PowerDnsAdapter {
+    fn create_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        self.set_txt_record(record_name, value)?;
+        self.notify_secondaries();
+        Ok(())
+    }
+
+    /// Secondaries are notified once for the whole batch.
+    fn create_txt_batch(&self, records: &[(String, String)]) -> Result<()> {
+        self.set_txt_records(records.to_vec())?;
+        self.notify_secondaries();
+        Ok(())
+    }
+
+    fn replace_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        self.replace_txt_record(record_name, value)?;
+        self.notify_secondaries();
+        Ok(())
+    }
+
+    fn cleanup_txt(&self, record_name: &str) -> Result<()> {
+        self.delete_txt_record(record_name)?;
+        self.notify_secondaries();
+        Ok(())
+    }
+
+    fn verify_zone_access(&self) -> Result<()> {
+        self.lookup_zone()?;
+        Ok(())
+    }
+
+    /// PowerDNS API keys are not scoped: a key that can read the zone can
+    /// also change it.
+    fn verify_write_access(&self) -> Option<Result<()>> {
+        Some(self.verify_zone_access())
+    }
+
+    fn list_zones(&self) -> Result<Vec<String>> {
+        Ok(self
+            .fetch_zones()?
+            .into_iter()
+            .map(|zone| zone.name.trim_end_matches('.').to_string())
+            .collect())
+    }
+
+    fn list_txt_records(&self) -> Result<Vec<TxtRecord>> {
+        self.fetch_zone_txt_records()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_server_urls_and_names() {
+        assert_eq!(
+            server_url("https://pdns.internal:8081/", None),
+            "https://pdns.internal:8081/api/v1/servers/localhost"
+        );
+        assert_eq!(
+            server_url("http://10.0.0.5:8081/api/v1", Some("ns1")),
+            "http://10.0.0.5:8081/api/v1/servers/ns1"
+        );
+        assert_eq!(
+            canonical_name("_acme-challenge.example.com"),
+            "_acme-challenge.example.com."
+        );
+        assert_eq!(canonical_name("example.com."), "example.com.");
+    }
+}
@@ -1,9 +1,13 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use log::{debug, warn};
+use rand::Rng;
+use reqwest::StatusCode;
 use std::time::{Duration, Instant};
 
 use crate::issuance::dns::{DnsPropagationResult, PropagationState};
 use crate::issuance::dns_providers::query_google_dns;
+use crate::issuance::propagation_resolvers::ResolverSet;
 
 /// Retries checking DNS propagation via public DNS (DoH) until the record is found
 /// or timeout is reached. This is used for both testing and issuance flows.
@@ -14,6 +18,7 @@ pub fn poll_dns_propagation(
     expected_value: &str,
     timeout: Duration,
     interval: Duration,
+    resolvers: &ResolverSet,
 ) -> Result<DnsPropagationResult> {
     let started = Instant::now();
     let mut attempt = 0;
@@ -25,7 +30,7 @@ pub fn poll_dns_propagation(
             attempt, record_name
         );
 
-        let result = query_google_dns(record_name, expected_value)?;
+        let result = query_google_dns(record_name, expected_value, resolvers)?;
 
         match result.state {
             PropagationState::Found => {
@@ -144,6 +149,50 @@ where
     }
 }
 
+/// Attempts made for a provider API call answered with 429 or a 5xx status.
+pub const MAX_HTTP_ATTEMPTS: u32 = 4;
+const HTTP_BACKOFF_BASE: Duration = Duration::from_millis(500);
+const HTTP_BACKOFF_MAX: Duration = Duration::from_secs(30);
+/// Longest `Retry-After` honoured. A provider asking for more is not retried,
+/// so the caller reports it as rate limited straight away.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Whether a provider API response is worth retrying: rate limiting or a
+/// server-side failure.
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Parses a `Retry-After` header, given either as seconds or as an HTTP date.
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?.with_timezone(&Utc);
+    Some((at - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Delay before retrying after failed attempt `attempt` (1-based), or `None`
+/// when the call should not be retried.
+///
+/// A `Retry-After` from the provider wins; otherwise the delay is exponential
+/// backoff with jitter, so parallel issuances do not retry in lockstep.
+pub fn http_retry_delay(attempt: u32, retry_after: Option<Duration>) -> Option<Duration> {
+    if attempt >= MAX_HTTP_ATTEMPTS {
+        return None;
+    }
+    if let Some(retry_after) = retry_after {
+        return (retry_after <= MAX_RETRY_AFTER).then_some(retry_after);
+    }
+    let backoff = HTTP_BACKOFF_BASE
+        .saturating_mul(1 << (attempt - 1).min(16))
+        .min(HTTP_BACKOFF_MAX);
+    let half = backoff / 2;
+    let jitter_ms = rand::thread_rng().gen_range(0..=half.as_millis() as u64);
+    Some(half + Duration::from_millis(jitter_ms))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,4 +265,39 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(*calls.lock().unwrap(), 1);
     }
+
+    #[test]
+    fn parse_retry_after_accepts_seconds_and_dates() {
+        let now = DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(parse_retry_after(" 12 ", now), Some(Duration::from_secs(12)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn http_retry_delay_backs_off_with_jitter_until_exhausted() {
+        for attempt in 1..MAX_HTTP_ATTEMPTS {
+            let backoff = HTTP_BACKOFF_BASE * (1 << (attempt - 1));
+            let delay = http_retry_delay(attempt, None).unwrap();
+            assert!(delay >= backoff / 2 && delay <= backoff);
+        }
+        assert_eq!(http_retry_delay(MAX_HTTP_ATTEMPTS, None), None);
+        assert_eq!(
+            http_retry_delay(1, Some(Duration::from_secs(7))),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(http_retry_delay(1, Some(MAX_RETRY_AFTER * 2)), None);
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(!is_retryable_status(StatusCode::BAD_REQUEST));
+    }
 }
@@ -0,0 +1,442 @@
+use std::{net::ToSocketAddrs, sync::OnceLock, time::Duration};
+
+use anyhow::{Context, Result, anyhow};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use chrono::Utc;
+use openssl::{hash::MessageDigest, pkey::PKey, sign::Signer};
+use zeroize::Zeroizing;
+
+use crate::issuance::dns_wire::{
+    self, CLASS_IN, RCODE_NXDOMAIN, Response, TYPE_TXT, exchange_tcp, header, parse_response,
+    parse_txt_rdata, push_name, push_u16, server_address,
+};
+
+use super::{
+    DnsProviderAdapter,
+    base::{
+        AtomicDnsOperations, DnsProviderBase, DnsRecord, parse_rrset_record_id, rrset_record_id,
+    },
+};
+
+const DEFAULT_TTL: u32 = 60;
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+/// Allowed clock difference between us and the server when checking TSIG.
+const TSIG_FUDGE: u16 = 300;
+
+const TYPE_SOA: u16 = 6;
+const TYPE_TSIG: u16 = 250;
+const CLASS_NONE: u16 = 254;
+const CLASS_ANY: u16 = 255;
+const OPCODE_UPDATE: u16 = 5 << 11;
+
+/// TSIG MAC algorithms (RFC 8945), by their configured names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TsigAlgorithm {
+    HmacMd5,
+    HmacSha1,
+    HmacSha224,
+    HmacSha256,
+    HmacSha384,
+    HmacSha512,
+}
+
+impl TsigAlgorithm {
+    /// Parses names as written in BIND/Knot key files; defaults to HMAC-SHA256.
+    pub fn from_name(name: Option<&str>) -> Result<Self> {
+        let Some(name) = name else {
+            return Ok(Self::HmacSha256);
+        };
+        match name.trim().trim_end_matches('.').to_ascii_lowercase().as_str() {
+            "hmac-md5" | "hmac-md5.sig-alg.reg.int" => Ok(Self::HmacMd5),
+            "hmac-sha1" => Ok(Self::HmacSha1),
+            "hmac-sha224" => Ok(Self::HmacSha224),
+            "hmac-sha256" => Ok(Self::HmacSha256),
+            "hmac-sha384" => Ok(Self::HmacSha384),
+            "hmac-sha512" => Ok(Self::HmacSha512),
+            other => Err(anyhow!("unsupported TSIG algorithm: {other}")),
+        }
+    }
+
+    fn wire_name(self) -> &'static str {
+        match self {
+            Self::HmacMd5 => "hmac-md5.sig-alg.reg.int",
+            Self::HmacSha1 => "hmac-sha1",
+            Self::HmacSha224 => "hmac-sha224",
+            Self::HmacSha256 => "hmac-sha256",
+            Self::HmacSha384 => "hmac-sha384",
+            Self::HmacSha512 => "hmac-sha512",
+        }
+    }
+
+    fn digest(self) -> MessageDigest {
+        match self {
+            Self::HmacMd5 => MessageDigest::md5(),
+            Self::HmacSha1 => MessageDigest::sha1(),
+            Self::HmacSha224 => MessageDigest::sha224(),
+            Self::HmacSha256 => MessageDigest::sha256(),
+            Self::HmacSha384 => MessageDigest::sha384(),
+            Self::HmacSha512 => MessageDigest::sha512(),
+        }
+    }
+}
+
+/// Decodes a base64 TSIG secret as found in `key` statements.
+pub fn decode_tsig_secret(secret: &str) -> Result<Zeroizing<Vec<u8>>> {
+    let decoded = STANDARD
+        .decode(secret.trim())
+        .map_err(|err| anyhow!("TSIG secret is not valid base64: {err}"))?;
+    if decoded.is_empty() {
+        return Err(anyhow!("TSIG secret is empty"));
+    }
+    Ok(Zeroizing::new(decoded))
+}
+
+struct TsigKey {
+    name: String,
+    algorithm: TsigAlgorithm,
+    secret: Zeroizing<Vec<u8>>,
+}
+
+/// RFC 2136 dynamic update adapter for authoritative servers such as BIND
+/// and Knot. Messages are signed with a TSIG key and sent over TCP to the
+/// primary. TXT values are RRset members, so adds and deletes touch only the
+/// given value; record IDs come from [`rrset_record_id`].
+///
+/// Responses are checked for their RCODE; their TSIG signature is not verified.
+pub struct Rfc2136Adapter {
+    server: String,
+    key: TsigKey,
+    zone_cache: OnceLock<String>,
+    domain_suffix: String,
+    record_ttl: Option<u32>,
+}
+
+impl Rfc2136Adapter {
+    /// `server` is `host` or `host:port` of the primary accepting updates.
+    pub fn new(
+        server: String,
+        key_name: String,
+        algorithm: TsigAlgorithm,
+        secret: Zeroizing<Vec<u8>>,
+        domain_suffix: String,
+    ) -> Self {
+        Self {
+            server,
+            key: TsigKey {
+                name: key_name,
+                algorithm,
+                secret,
+            },
+            zone_cache: OnceLock::new(),
+            domain_suffix,
+            record_ttl: None,
+        }
+    }
+
+    /// Overrides the TTL of created TXT records.
+    pub fn with_record_ttl(mut self, ttl: Option<u32>) -> Self {
+        self.record_ttl = ttl;
+        self
+    }
+
+    /// Uses a configured zone instead of asking the server for the SOA.
+    pub fn with_zone(mut self, zone: Option<String>) -> Self {
+        self.zone_cache = zone
+            .map(|zone| OnceLock::from(zone.trim_end_matches('.').to_string()))
+            .unwrap_or_default();
+        self
+    }
+
+    /// Finds the zone holding the suffix from the SOA the server returns.
+    fn discover_zone(&self) -> Result<String> {
+        if let Some(zone) = self.zone_cache.get() {
+            return Ok(zone.clone());
+        }
+
+        let response = self.query(&self.domain_suffix, TYPE_SOA)?;
+        let zone = response
+            .answers
+            .iter()
+            .chain(response.authority.iter())
+            .find(|record| record.rtype == TYPE_SOA)
+            .map(|record| record.name.clone())
+            .ok_or_else(|| {
+                anyhow!(
+                    "{} is not authoritative for domain suffix: {}",
+                    self.server,
+                    self.domain_suffix
+                )
+            })?;
+
+        Ok(self.zone_cache.get_or_init(|| zone).clone())
+    }
+
+    fn query(&self, name: &str, rtype: u16) -> Result<Response> {
+        let response = self.exchange(dns_wire::query(name, rtype)?)?;
+        if response.rcode != 0 && response.rcode != RCODE_NXDOMAIN {
+            return Err(rcode_error(response.rcode));
+        }
+        Ok(response)
+    }
+
+    /// Sends an UPDATE for the zone with the given prerequisite and update RRs.
+    fn update(&self, prerequisites: &[Vec<u8>], updates: &[Vec<u8>]) -> Result<()> {
+        let zone = self.discover_zone()?;
+        let mut message = header(
+            OPCODE_UPDATE,
+            1,
+            prerequisites.len() as u16,
+            updates.len() as u16,
+            0,
+        );
+        push_name(&mut message, &zone)?;
+        push_u16(&mut message, TYPE_SOA);
+        push_u16(&mut message, CLASS_IN);
+        for record in prerequisites.iter().chain(updates) {
+            message.extend_from_slice(record);
+        }
+
+        let response = self.exchange(message)?;
+        if response.rcode != 0 {
+            return Err(rcode_error(response.rcode));
+        }
+        Ok(())
+    }
+
+    /// Signs `message` and exchanges it with the server over TCP.
+    fn exchange(&self, mut message: Vec<u8>) -> Result<Response> {
+        let id: u16 = rand::random();
+        message[..2].copy_from_slice(&id.to_be_bytes());
+        sign_message(&mut message, &self.key, Utc::now().timestamp() as u64)?;
+
+        let address = server_address(&self.server)
+            .to_socket_addrs()
+            .with_context(|| format!("Failed to resolve DNS server {}", self.server))?
+            .next()
+            .ok_or_else(|| anyhow!("DNS server {} has no address", self.server))?;
+        let reply = exchange_tcp(address, &message, IO_TIMEOUT)
+            .with_context(|| format!("DNS exchange with {} failed", self.server))?;
+
+        if reply.len() < 12 || reply[..2] != id.to_be_bytes() {
+            return Err(anyhow!("DNS response does not match the request"));
+        }
+        parse_response(&reply)
+    }
+}
+
+fn rcode_error(rcode: u8) -> anyhow::Error {
+    let name = match rcode {
+        1 => "FORMERR",
+        2 => "SERVFAIL",
+        3 => "NXDOMAIN",
+        4 => "NOTIMP",
+        5 => "REFUSED (check the server's update policy for this key)",
+        6 => "YXDOMAIN",
+        7 => "YXRRSET",
+        8 => "NXRRSET",
+        9 => "NOTAUTH (check the TSIG key and the server's clock)",
+        10 => "NOTZONE",
+        _ => "unknown error",
+    };
+    anyhow!("DNS server returned {name} (rcode {rcode})")
+}
+
+fn resource_record(
+    name: &str,
+    rtype: u16,
+    class: u16,
+    ttl: u32,
+    rdata: &[u8],
+) -> Result<Vec<u8>> {
+    let mut record = Vec::new();
+    push_name(&mut record, name)?;
+    push_u16(&mut record, rtype);
+    push_u16(&mut record, class);
+    record.extend_from_slice(&ttl.to_be_bytes());
+    push_u16(&mut record, u16::try_from(rdata.len()).context("record data too large")?);
+    record.extend_from_slice(rdata);
+    Ok(record)
+}
+
+/// TXT RDATA: the value split into character-strings of at most 255 bytes.
+fn txt_rdata(value: &str) -> Vec<u8> {
+    let mut rdata = Vec::new();
+    for chunk in value.as_bytes().chunks(255) {
+        rdata.push(chunk.len() as u8);
+        rdata.extend_from_slice(chunk);
+    }
+    if rdata.is_empty() {
+        rdata.push(0);
+    }
+    rdata
+}
+
+/// Appends a TSIG record (RFC 8945) signing the message as it stands.
+fn sign_message(message: &mut Vec<u8>, key: &TsigKey, time_signed: u64) -> Result<()> {
+    let mut key_name = Vec::new();
+    push_name(&mut key_name, &key.name.to_ascii_lowercase())?;
+    let mut algorithm = Vec::new();
+    push_name(&mut algorithm, key.algorithm.wire_name())?;
+    let time = &time_signed.to_be_bytes()[2..];
+
+    let mut variables = key_name.clone();
+    push_u16(&mut variables, CLASS_ANY);
+    variables.extend_from_slice(&0u32.to_be_bytes());
+    variables.extend_from_slice(&algorithm);
+    variables.extend_from_slice(time);
+    push_u16(&mut variables, TSIG_FUDGE);
+    push_u16(&mut variables, 0); // error
+    push_u16(&mut variables, 0); // other len
+
+    let hmac = PKey::hmac(&key.secret)?;
+    let mut signer = Signer::new(key.algorithm.digest(), &hmac)?;
+    signer.update(message)?;
+    signer.update(&variables)?;
+    let mac = signer.sign_to_vec()?;
+
+    let mut rdata = algorithm;
+    rdata.extend_from_slice(time);
+    push_u16(&mut rdata, TSIG_FUDGE);
+    push_u16(&mut rdata, mac.len() as u16);
+    rdata.extend_from_slice(&mac);
+    rdata.extend_from_slice(&message[..2]); // original ID
+    push_u16(&mut rdata, 0);
+    push_u16(&mut rdata, 0);
+
+    message.extend_from_slice(&key_name);
+    push_u16(message, TYPE_TSIG);
+    push_u16(message, CLASS_ANY);
+    message.extend_from_slice(&0u32.to_be_bytes());
+    push_u16(message, rdata.len() as u16);
+    message.extend_from_slice(&rdata);
+
+    let arcount = u16::from_be_bytes([message[10], message[11]]) + 1;
+    message[10..12].copy_from_slice(&arcount.to_be_bytes());
+    Ok(())
+}
+
+impl AtomicDnsOperations for Rfc2136Adapter {
+    /// Adds `value` to the TXT RRset at `record_name`; other values stay.
+    fn create_one_record(&self, record_name: &str, value: &str) -> Result<String> {
+        let normalized = self.normalize_value(value);
+        let ttl = self.record_ttl.unwrap_or(DEFAULT_TTL);
+        let rdata = txt_rdata(&normalized);
+        let record = resource_record(record_name, TYPE_TXT, CLASS_IN, ttl, &rdata)?;
+        self.update(&[], &[record])?;
+        Ok(rrset_record_id(record_name, &normalized))
+    }
+
+    /// Deletes one value from its RRset (class NONE update).
+    fn delete_one_record(&self, record_id: &str) -> Result<()> {
+        let (record_name, value) = parse_rrset_record_id(record_id)?;
+        let record = resource_record(record_name, TYPE_TXT, CLASS_NONE, 0, &txt_rdata(value))?;
+        self.update(&[], &[record])
+    }
+
+    /// Lists each value of the RRset as its own record, as seen by the primary.
+    fn list_records(&self, record_name: &str) -> Result<Vec<DnsRecord>> {
+        let response = self.query(record_name, TYPE_TXT)?;
+        Ok(response
+            .answers
+            .iter()
+            .filter(|record| record.rtype == TYPE_TXT)
+            .map(|record| {
+                let value = parse_txt_rdata(&record.rdata);
+                DnsRecord {
+                    id: rrset_record_id(record_name, &self.normalize_value(&value)),
+                    name: record_name.to_string(),
+                    value,
+                }
+            })
+            .collect())
+    }
+
+    fn get_zone_id(&self, _domain: &str) -> Result<String> {
+        // Updates are addressed to the zone by name
+        self.discover_zone()
+    }
+}
+
+impl DnsProviderBase for Rfc2136Adapter {
+    fn atomic_ops(&self) -> &dyn AtomicDnsOperations {
+        self
+    }
+}
+
+impl DnsProviderAdapter for Rfc2136Adapter {
+    fn create_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        self.set_txt_record(record_name, value)
+    }
+
+    fn create_txt_batch(&self, records: &[(String, String)]) -> Result<()> {
+        self.set_txt_records(records.to_vec())
+    }
+
+    fn replace_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        self.replace_txt_record(record_name, value)
+    }
+
+    fn cleanup_txt(&self, record_name: &str) -> Result<()> {
+        self.delete_txt_record(record_name)
+    }
+
+    /// Sends an update whose only content is the prerequisite "the zone's SOA
+    /// exists", which checks the key and update permission without changes.
+    fn verify_zone_access(&self) -> Result<()> {
+        let zone = self.discover_zone()?;
+        let prerequisite = resource_record(&zone, TYPE_SOA, CLASS_ANY, 0, &[])?;
+        self.update(&[prerequisite], &[])
+    }
+
+    /// The zone access check is already an UPDATE with nothing to change,
+    /// signed and authorized like a real one.
+    fn verify_write_access(&self) -> Option<Result<()>> {
+        Some(self.verify_zone_access())
+    }
+
+    /// Servers do not list their zones over DNS, so this is the suffix's zone.
+    fn list_zones(&self) -> Result<Vec<String>> {
+        Ok(vec![self.discover_zone()?])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::issuance::dns_wire::read_u16;
+
+    #[test]
+    fn signs_and_parses_messages() {
+        let key = TsigKey {
+            name: "acme-key".to_string(),
+            algorithm: TsigAlgorithm::from_name(None).unwrap(),
+            secret: decode_tsig_secret("c2VjcmV0").unwrap(),
+        };
+        let mut message = header(OPCODE_UPDATE, 1, 0, 1, 0);
+        push_name(&mut message, "example.com").unwrap();
+        push_u16(&mut message, TYPE_SOA);
+        push_u16(&mut message, CLASS_IN);
+        let record = resource_record(
+            "_acme-challenge.example.com",
+            TYPE_TXT,
+            CLASS_IN,
+            60,
+            &txt_rdata("abc"),
+        )
+        .unwrap();
+        message.extend_from_slice(&record);
+        sign_message(&mut message, &key, 1_700_000_000).unwrap();
+
+        assert_eq!(read_u16(&message, 10).unwrap(), 1);
+        let parsed = parse_response(&message).unwrap();
+        // The update section sits in the NSCOUNT position
+        assert_eq!(parsed.authority[0].name, "_acme-challenge.example.com");
+        assert_eq!(parse_txt_rdata(&parsed.authority[0].rdata), "abc");
+
+        assert_eq!(server_address("ns1.example.com"), "ns1.example.com:53");
+        assert_eq!(server_address("10.0.0.1:5353"), "10.0.0.1:5353");
+        assert_eq!(server_address("2001:db8::1"), "[2001:db8::1]:53");
+        assert!(TsigAlgorithm::from_name(Some("hmac-sha3")).is_err());
+        assert!(decode_tsig_secret("not base64!").is_err());
+    }
+}
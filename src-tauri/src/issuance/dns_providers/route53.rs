@@ -1,27 +1,188 @@
-use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use anyhow::{anyhow, Result};
+use aws_config::sts::AssumeRoleProvider;
+use aws_config::{BehaviorVersion, ConfigLoader, Region, SdkConfig};
+use aws_sdk_route53::Client;
+use aws_sdk_route53::config::Credentials;
+use aws_sdk_route53::error::ProvideErrorMetadata;
+use aws_sdk_route53::types::{
+    Change, ChangeAction, ChangeBatch, ResourceRecord, ResourceRecordSet, RrType,
+};
+
+use crate::core::runtime;
 
 use super::{
-    base::{AtomicDnsOperations, DnsProviderBase, DnsRecord},
-    matches_zone, DnsProviderAdapter,
+    base::{
+        parse_rrset_record_id, rrset_record_id, AtomicDnsOperations, DnsProviderBase, DnsRecord,
+    },
+    errors::ProviderError,
+    matches_zone,
+    zone_cache::ZoneCache,
+    DnsProviderAdapter, TxtRecord,
 };
 
+/// Wraps a failed SDK call so its AWS error code is kept for categorization.
+fn sdk_error<E>(context: &'static str) -> impl FnOnce(E) -> anyhow::Error
+where
+    E: ProvideErrorMetadata + std::error::Error + Send + Sync + 'static,
+{
+    move |err| {
+        let provider_error = ProviderError::route53(context, err.code(), err.message());
+        anyhow::Error::new(err).context(provider_error)
+    }
+}
+
+/// Id of the longest hosted zone name containing `domain_suffix`, from
+/// `(name, id)` pairs as listed by Route 53.
+fn most_specific_zone(domain_suffix: &str, zones: Vec<(String, String)>) -> Option<String> {
+    zones
+        .into_iter()
+        .map(|(name, id)| (name.trim_end_matches('.').to_string(), id))
+        .filter(|(name, _)| matches_zone(domain_suffix, name))
+        .max_by_key(|(name, _)| name.len())
+        .map(|(_, id)| id)
+}
+
+/// Session name recorded in CloudTrail for assumed roles.
+const ROLE_SESSION_NAME: &str = "sslboard";
+/// STS is global; its us-east-1 endpoint serves every partition-default account.
+const STS_REGION: &str = "us-east-1";
+
+/// Role the adapter assumes through STS instead of using its base
+/// credentials directly, from `role_arn` and `external_id` in the provider's
+/// `config_json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssumeRole {
+    pub role_arn: String,
+    pub external_id: Option<String>,
+}
+
+impl AssumeRole {
+    /// Reads the role from provider settings; `None` when no role ARN is set.
+    pub fn from_settings(role_arn: Option<&str>, external_id: Option<&str>) -> Result<Option<Self>> {
+        let Some(role_arn) = role_arn.map(str::trim).filter(|arn| !arn.is_empty()) else {
+            return Ok(None);
+        };
+        if !is_role_arn(role_arn) {
+            return Err(anyhow!(
+                "Role ARN must look like arn:aws:iam::123456789012:role/name"
+            ));
+        }
+        let external_id = external_id.map(str::trim).filter(|id| !id.is_empty());
+        if let Some(id) = external_id {
+            let allowed = |c: char| c.is_ascii_alphanumeric() || "_+=,.@:/-".contains(c);
+            if !(2..=1224).contains(&id.len()) || !id.chars().all(allowed) {
+                return Err(anyhow!(
+                    "External ID must be 2 to 1224 letters, digits or _+=,.@:/- characters"
+                ));
+            }
+        }
+        Ok(Some(Self {
+            role_arn: role_arn.to_string(),
+            external_id: external_id.map(str::to_string),
+        }))
+    }
+}
+
+/// Reads the named AWS profile from provider settings; `None` when unset.
+pub fn profile_from_settings(profile: Option<&str>) -> Result<Option<String>> {
+    let Some(profile) = profile.map(str::trim).filter(|name| !name.is_empty()) else {
+        return Ok(None);
+    };
+    let allowed = |c: char| c.is_ascii_alphanumeric() || "_+=,.@-".contains(c);
+    if profile.len() > 128 || !profile.chars().all(allowed) {
+        return Err(anyhow!(
+            "AWS profile name may only contain letters, digits and _+=,.@- characters"
+        ));
+    }
+    Ok(Some(profile.to_string()))
+}
+
+fn is_role_arn(arn: &str) -> bool {
+    let parts: Vec<&str> = arn.splitn(6, ':').collect();
+    matches!(
+        parts.as_slice(),
+        ["arn", partition, "iam", "", account, resource]
+            if partition.starts_with("aws")
+                && account.len() == 12
+                && account.bytes().all(|b| b.is_ascii_digit())
+                && resource.strip_prefix("role/").is_some_and(|name| !name.is_empty())
+    )
+}
+
+/// SDK configurations for assumed roles, kept for the app session so every
+/// adapter reuses the temporary credentials until the SDK refreshes them.
+fn role_sessions() -> MutexGuard<'static, HashMap<String, SdkConfig>> {
+    static SESSIONS: OnceLock<Mutex<HashMap<String, SdkConfig>>> = OnceLock::new();
+    SESSIONS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+}
+
 pub struct Route53Adapter {
-    access_key: String,
-    secret_key: String,
-    hosted_zone_cache: Option<String>,
+    /// Access key and secret key; `None` uses the default AWS credential chain
+    keys: Option<(String, String)>,
+    /// Named profile the default chain reads from instead of the active one
+    profile: Option<String>,
+    assume_role: Option<AssumeRole>,
+    hosted_zone_cache: OnceLock<String>,
+    shared_zones: Option<ZoneCache>,
     domain_suffix: String,
+    record_ttl: Option<u32>,
 }
 
 impl Route53Adapter {
     pub fn new(access_key: String, secret_key: String, domain_suffix: String) -> Self {
+        Self::with_keys(Some((access_key, secret_key)), domain_suffix)
+    }
+
+    /// Uses the default AWS credential chain (environment, profile, SSO), so
+    /// no long-lived keys are stored. Combine with [`Self::with_profile`] or
+    /// [`Self::with_assume_role`].
+    pub fn from_default_chain(domain_suffix: String) -> Self {
+        Self::with_keys(None, domain_suffix)
+    }
+
+    fn with_keys(keys: Option<(String, String)>, domain_suffix: String) -> Self {
         Self {
-            access_key,
-            secret_key,
-            hosted_zone_cache: None,
+            keys,
+            profile: None,
+            assume_role: None,
+            hosted_zone_cache: OnceLock::new(),
+            shared_zones: None,
             domain_suffix,
+            record_ttl: None,
         }
     }
 
+    /// Loads credentials from a named profile in the AWS config files,
+    /// including SSO sessions signed in with `aws sso login`.
+    pub fn with_profile(mut self, profile: Option<String>) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Assumes `role` with the base credentials for every call.
+    pub fn with_assume_role(mut self, role: Option<AssumeRole>) -> Self {
+        self.assume_role = role;
+        self
+    }
+
+    /// Overrides the TTL of created TXT records.
+    pub fn with_record_ttl(mut self, ttl: Option<u32>) -> Self {
+        self.record_ttl = ttl;
+        self
+    }
+
+    /// Shares discovered zones with later adapters of the same provider.
+    pub fn with_zone_cache(mut self, zones: Option<ZoneCache>) -> Self {
+        self.shared_zones = zones;
+        self
+    }
+
     fn format_txt_content(value: &str) -> String {
         let trimmed = value.trim();
         if trimmed.starts_with('"') && trimmed.ends_with('"') {
@@ -31,27 +192,83 @@ impl Route53Adapter {
         }
     }
 
-    async fn discover_hosted_zone_id(&mut self) -> Result<String> {
-        if let Some(ref zone_id) = self.hosted_zone_cache {
-            return Ok(zone_id.clone());
-        }
+    fn base_credentials(&self) -> Option<Credentials> {
+        self.keys.as_ref().map(|(access_key, secret_key)| {
+            Credentials::new(access_key, secret_key, None, None, "sslboard")
+        })
+    }
 
-        use aws_config::BehaviorVersion;
-        use aws_sdk_route53::config::Credentials;
-        use aws_sdk_route53::Client;
+    /// Loader for the base credentials: stored keys, or the default chain
+    /// read from the configured profile.
+    fn base_loader(&self) -> ConfigLoader {
+        let mut loader = aws_config::defaults(BehaviorVersion::latest());
+        if let Some(profile) = &self.profile {
+            loader = loader.profile_name(profile.clone());
+        }
+        match self.base_credentials() {
+            Some(credentials) => loader.credentials_provider(credentials),
+            None => loader,
+        }
+    }
 
-        let credentials = Credentials::new(
-            &self.access_key,
-            &self.secret_key,
-            None,
-            None,
-            "sslboard",
+    /// SDK configuration for the adapter's credentials. Assumed-role
+    /// configurations come from the session cache.
+    async fn sdk_config(&self) -> SdkConfig {
+        let Some(role) = &self.assume_role else {
+            return self.base_loader().load().await;
+        };
+
+        let session_key = format!(
+            "{}|{}|{}|{}",
+            role.role_arn,
+            role.external_id.as_deref().unwrap_or_default(),
+            self.keys.as_ref().map_or("", |(access_key, _)| access_key.as_str()),
+            self.profile.as_deref().unwrap_or_default()
         );
+        if let Some(config) = role_sessions().get(&session_key) {
+            return config.clone();
+        }
 
+        let mut builder = AssumeRoleProvider::builder(role.role_arn.clone())
+            .session_name(ROLE_SESSION_NAME)
+            .region(Region::new(STS_REGION));
+        if let Some(external_id) = &role.external_id {
+            builder = builder.external_id(external_id.clone());
+        }
+        let provider = match self.base_credentials() {
+            Some(credentials) => builder.build_from_provider(credentials).await,
+            None => builder.configure(&self.base_loader().load().await).build().await,
+        };
         let config = aws_config::defaults(BehaviorVersion::latest())
-            .credentials_provider(credentials)
+            .credentials_provider(provider)
             .load()
             .await;
+        role_sessions().insert(session_key, config.clone());
+        config
+    }
+
+    /// Hosted zone for the suffix, from this adapter's or the provider's
+    /// cache when it was found before.
+    async fn discover_hosted_zone_id(&self) -> Result<String> {
+        if let Some(zone_id) = self.hosted_zone_cache.get() {
+            return Ok(zone_id.clone());
+        }
+        let shared = self.shared_zones.as_ref();
+        let zone_id = match shared.and_then(|zones| zones.get(&self.domain_suffix)) {
+            Some(zone_id) => zone_id,
+            None => {
+                let zone_id = self.lookup_hosted_zone_id().await?;
+                if let Some(zones) = shared {
+                    zones.put(&self.domain_suffix, &zone_id);
+                }
+                zone_id
+            }
+        };
+        Ok(self.hosted_zone_cache.get_or_init(|| zone_id).clone())
+    }
+
+    async fn lookup_hosted_zone_id(&self) -> Result<String> {
+        let config = self.sdk_config().await;
 
         let client = Client::new(&config);
 
@@ -61,138 +278,267 @@ impl Route53Adapter {
             .page_size(100)
             .send();
 
+        // Every page is read so a delegated subzone wins over its parent
+        // regardless of listing order
+        let mut zones = Vec::new();
         while let Some(page) = paginator.next().await {
-            let page = page.context("Failed to list Route 53 hosted zones")?;
-            
+            let page = page.map_err(sdk_error("Failed to list Route 53 hosted zones"))?;
+
             // hosted_zones() returns &[HostedZone] directly (not Option)
             // name() and id() return &str directly (not Option)
-            let zones = page.hosted_zones();
-            for zone in zones {
-                let name = zone.name();
-                let id = zone.id();
-                let zone_name = name.trim_end_matches('.');
-                if matches_zone(&self.domain_suffix, zone_name) {
-                    let zone_id = id.to_string();
-                    self.hosted_zone_cache = Some(zone_id.clone());
-                    return Ok(zone_id);
-                }
-            }
+            zones.extend(
+                page.hosted_zones()
+                    .iter()
+                    .map(|zone| (zone.name().to_string(), zone.id().to_string())),
+            );
         }
 
-        Err(anyhow!(
-            "No Route 53 hosted zone found for domain suffix: {}",
-            self.domain_suffix
-        ))
+        most_specific_zone(&self.domain_suffix, zones).ok_or_else(|| {
+            anyhow!(
+                "No Route 53 hosted zone found for domain suffix: {}",
+                self.domain_suffix
+            )
+        })
     }
 
-    /// Atomic operation: Creates a single TXT record via Route53 API.
-    /// Returns the record name as ID (Route53 doesn't return a separate ID).
-    /// Does not check for existing records or verify.
-    async fn create_txt_record_atomic(&mut self, record_name: &str, value: &str) -> Result<String> {
-        use aws_config::BehaviorVersion;
-        use aws_sdk_route53::config::Credentials;
-        use aws_sdk_route53::Client;
-        use aws_sdk_route53::types::{Change, ChangeBatch, ResourceRecord, ResourceRecordSet, RrType};
-
-        let hosted_zone_id = self.discover_hosted_zone_id().await?;
-        let formatted_value = Self::format_txt_content(value);
-
-        let credentials = Credentials::new(
-            &self.access_key,
-            &self.secret_key,
-            None,
-            None,
-            "sslboard",
-        );
-
-        let config = aws_config::defaults(BehaviorVersion::latest())
-            .credentials_provider(credentials)
-            .load()
-            .await;
+    /// Lists the names of every hosted zone visible to the credentials.
+    async fn list_hosted_zone_names(&self) -> Result<Vec<String>> {
+        let config = self.sdk_config().await;
 
         let client = Client::new(&config);
 
-        let record_set = ResourceRecordSet::builder()
-            .name(record_name)
-            .set_resource_records(Some(vec![ResourceRecord::builder()
-                .value(formatted_value)
-                .build()
-                .map_err(|e| anyhow!("Failed to build ResourceRecord: {}", e))?]))
-            .ttl(300)
-            .set_type(Some(RrType::Txt))
-            .build()
-            .map_err(|e| anyhow!("Failed to build ResourceRecordSet: {}", e))?;
+        let mut paginator = client
+            .list_hosted_zones()
+            .into_paginator()
+            .page_size(100)
+            .send();
 
-        let change = Change::builder()
-            .action(aws_sdk_route53::types::ChangeAction::Upsert)
-            .resource_record_set(record_set)
-            .build()
-            .map_err(|e| anyhow!("Failed to build Change: {}", e))?;
+        let mut names = Vec::new();
+        while let Some(page) = paginator.next().await {
+            let page = page.map_err(sdk_error("Failed to list Route 53 hosted zones"))?;
+            names.extend(
+                page.hosted_zones()
+                    .iter()
+                    .map(|zone| zone.name().trim_end_matches('.').to_string()),
+            );
+        }
+        Ok(names)
+    }
 
-        let change_batch = ChangeBatch::builder()
-            .changes(change)
-            .build()
-            .map_err(|e| anyhow!("Failed to build ChangeBatch: {}", e))?;
+    /// Account id and caller ARN from STS, so an assumed role shows the
+    /// account it lands in rather than the one holding the keys.
+    async fn caller_identity(&self) -> Result<String> {
+        let config = self.sdk_config().await;
+        let mut sts_config = aws_sdk_sts::config::Builder::from(&config);
+        if config.region().is_none() {
+            sts_config = sts_config.region(Region::new(STS_REGION));
+        }
+        let identity = aws_sdk_sts::Client::from_conf(sts_config.build())
+            .get_caller_identity()
+            .send()
+            .await
+            .map_err(sdk_error("Failed to read the AWS caller identity"))?;
+        let account = identity
+            .account()
+            .ok_or_else(|| anyhow!("STS did not return an account id"))?;
+        Ok(match identity.arn() {
+            Some(arn) => format!("{account} ({arn})"),
+            None => account.to_string(),
+        })
+    }
 
-        let _result = client
-            .change_resource_record_sets()
+    /// The TXT RRset at `record_name`, if any. Record sets are listed in
+    /// name order from the start name, so the first one is the only candidate.
+    async fn fetch_txt_rrset(&self, record_name: &str) -> Result<Option<ResourceRecordSet>> {
+        let hosted_zone_id = self.discover_hosted_zone_id().await?;
+        let client = Client::new(&self.sdk_config().await);
+        let response = client
+            .list_resource_record_sets()
             .hosted_zone_id(&hosted_zone_id)
-            .change_batch(change_batch)
+            .start_record_name(record_name)
+            .start_record_type(RrType::Txt)
+            .max_items(1)
             .send()
             .await
-            .context("Failed to create Route 53 DNS record")?;
-
-        // Route53 doesn't return a record ID, so we use the record name
-        Ok(format!("route53:{}", record_name))
+            .map_err(sdk_error("Failed to list Route 53 DNS records"))?;
+        Ok(response
+            .resource_record_sets()
+            .iter()
+            .find(|set| same_record_name(set.name(), record_name) && set.r#type() == &RrType::Txt)
+            .cloned())
     }
 
-    /// Atomic operation: Deletes a single TXT record via Route53 API.
-    /// Does not handle listing - expects the record_set to be provided.
-    async fn delete_txt_record_atomic(&mut self, record_set: aws_sdk_route53::types::ResourceRecordSet) -> Result<()> {
-        use aws_config::BehaviorVersion;
-        use aws_sdk_route53::config::Credentials;
-        use aws_sdk_route53::Client;
-        use aws_sdk_route53::types::{Change, ChangeBatch};
-
+    /// Every TXT value in the hosted zone, following `next_record_name`
+    /// across pages.
+    async fn fetch_zone_txt_records(&self) -> Result<Vec<TxtRecord>> {
         let hosted_zone_id = self.discover_hosted_zone_id().await?;
+        let client = Client::new(&self.sdk_config().await);
+        let mut records = Vec::new();
+        let mut start: Option<String> = None;
+        loop {
+            let mut request = client
+                .list_resource_record_sets()
+                .hosted_zone_id(&hosted_zone_id)
+                .max_items(300);
+            if let Some(name) = start.take() {
+                request = request.start_record_name(name);
+            }
+            let page = request
+                .send()
+                .await
+                .map_err(sdk_error("Failed to list Route 53 DNS records"))?;
+            // A page resuming at a name lists all its types again
+            for set in page.resource_record_sets() {
+                if set.r#type() != &RrType::Txt {
+                    continue;
+                }
+                for record in set.resource_records() {
+                    let record = TxtRecord::new(set.name(), record.value());
+                    if !records.contains(&record) {
+                        records.push(record);
+                    }
+                }
+            }
+            match page.next_record_name() {
+                Some(next) if page.is_truncated() => start = Some(next.to_string()),
+                _ => return Ok(records),
+            }
+        }
+    }
 
-        let credentials = Credentials::new(
-            &self.access_key,
-            &self.secret_key,
-            None,
-            None,
-            "sslboard",
-        );
-
-        let config = aws_config::defaults(BehaviorVersion::latest())
-            .credentials_provider(credentials)
-            .load()
-            .await;
-
-        let client = Client::new(&config);
+    /// Change publishing `values` as the whole TXT RRset at `record_name`.
+    /// With no values left, `existing` is deleted instead, as Route 53
+    /// requires the full record set for that; `None` when there is nothing
+    /// to delete.
+    fn txt_change(
+        &self,
+        record_name: &str,
+        values: Vec<String>,
+        existing: Option<ResourceRecordSet>,
+    ) -> Result<Option<Change>> {
+        let (action, record_set) = if values.is_empty() {
+            let Some(existing) = existing else {
+                return Ok(None);
+            };
+            (ChangeAction::Delete, existing)
+        } else {
+            let records = values
+                .into_iter()
+                .map(|value| {
+                    ResourceRecord::builder()
+                        .value(value)
+                        .build()
+                        .map_err(|e| anyhow!("Failed to build ResourceRecord: {}", e))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let record_set = ResourceRecordSet::builder()
+                .name(record_name)
+                .set_resource_records(Some(records))
+                .ttl(i64::from(self.record_ttl.unwrap_or(300)))
+                .set_type(Some(RrType::Txt))
+                .build()
+                .map_err(|e| anyhow!("Failed to build ResourceRecordSet: {}", e))?;
+            (ChangeAction::Upsert, record_set)
+        };
 
         let change = Change::builder()
-            .action(aws_sdk_route53::types::ChangeAction::Delete)
+            .action(action)
             .resource_record_set(record_set)
             .build()
             .map_err(|e| anyhow!("Failed to build Change: {}", e))?;
+        Ok(Some(change))
+    }
+
+    /// Applies `changes` in one ChangeBatch, which Route 53 commits atomically.
+    async fn submit_changes(&self, changes: Vec<Change>) -> Result<()> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let hosted_zone_id = self.discover_hosted_zone_id().await?;
+        let client = Client::new(&self.sdk_config().await);
 
         let change_batch = ChangeBatch::builder()
-            .changes(change)
+            .set_changes(Some(changes))
             .build()
             .map_err(|e| anyhow!("Failed to build ChangeBatch: {}", e))?;
 
-        let _result = client
+        client
             .change_resource_record_sets()
             .hosted_zone_id(&hosted_zone_id)
             .change_batch(change_batch)
             .send()
             .await
-            .context("Failed to delete Route 53 DNS record")?;
-
+            .map_err(sdk_error("Failed to update Route 53 DNS record"))?;
         Ok(())
     }
 
+    async fn write_txt_rrset(
+        &self,
+        record_name: &str,
+        values: Vec<String>,
+        existing: Option<ResourceRecordSet>,
+    ) -> Result<()> {
+        let change = self.txt_change(record_name, values, existing)?;
+        self.submit_changes(change.into_iter().collect()).await
+    }
+
+    /// Current values of the TXT RRset at `record_name`, as Route 53 stores them.
+    fn txt_values(record_set: Option<&ResourceRecordSet>) -> Vec<String> {
+        record_set
+            .map(|set| set.resource_records().iter().map(|r| r.value().to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Adds every value to its TXT RRset, keeping the values already there,
+    /// in a single ChangeBatch.
+    async fn add_txt_values(&self, records: &[(String, String)]) -> Result<()> {
+        let mut by_name: Vec<(&str, Vec<&str>)> = Vec::new();
+        for (record_name, value) in records {
+            match by_name.iter_mut().find(|(name, _)| same_record_name(name, record_name)) {
+                Some((_, values)) => values.push(value),
+                None => by_name.push((record_name, vec![value])),
+            }
+        }
+
+        let mut changes = Vec::new();
+        for (record_name, added) in by_name {
+            let existing = self.fetch_txt_rrset(record_name).await?;
+            let mut values = Self::txt_values(existing.as_ref());
+            let before = values.len();
+            for value in added {
+                let normalized = self.normalize_value(value);
+                if !values.iter().any(|v| self.normalize_value(v) == normalized) {
+                    values.push(Self::format_txt_content(value));
+                }
+            }
+            if values.len() > before {
+                changes.extend(self.txt_change(record_name, values, existing)?);
+            }
+        }
+        self.submit_changes(changes).await
+    }
+
+    /// Removes `value` from the TXT RRset, deleting the set with its last value.
+    async fn remove_txt_value(&self, record_name: &str, value: &str) -> Result<()> {
+        let Some(existing) = self.fetch_txt_rrset(record_name).await? else {
+            return Ok(());
+        };
+        let mut values = Self::txt_values(Some(&existing));
+        let normalized = self.normalize_value(value);
+        let before = values.len();
+        values.retain(|v| self.normalize_value(v) != normalized);
+        if values.len() == before {
+            return Ok(());
+        }
+        self.write_txt_rrset(record_name, values, Some(existing)).await
+    }
+}
+
+/// Route 53 returns names fully qualified with a trailing dot.
+fn same_record_name(listed: &str, record_name: &str) -> bool {
+    listed
+        .trim_end_matches('.')
+        .eq_ignore_ascii_case(record_name.trim_end_matches('.'))
 }
 
 impl AtomicDnsOperations for Route53Adapter {
@@ -201,147 +547,132 @@ impl AtomicDnsOperations for Route53Adapter {
         value.trim().trim_matches('"').trim().to_string()
     }
 
-    fn create_one_record(&mut self, record_name: &str, value: &str) -> Result<String> {
-        // Truly atomic: just create the record
-        let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
-        rt.block_on(self.create_txt_record_atomic(record_name, value))
-    }
-
-    fn delete_one_record(&mut self, record_id: &str) -> Result<()> {
-        // Extract record name from our ID format
-        let record_name = record_id
-            .strip_prefix("route53:")
-            .ok_or_else(|| anyhow!("Invalid Route53 record ID format"))?;
-        
-        // For Route53, we need to get the record_set first to delete it
-        // This is a limitation of Route53 API - deletion requires the full record_set
-        // So we need to list first, then delete
-        use aws_config::BehaviorVersion;
-        use aws_sdk_route53::config::Credentials;
-        use aws_sdk_route53::Client;
-        use aws_sdk_route53::types::RrType;
-
-        let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
-        let hosted_zone_id = rt.block_on(self.discover_hosted_zone_id())?;
-        
-        let credentials = Credentials::new(
-            &self.access_key,
-            &self.secret_key,
-            None,
-            None,
-            "sslboard",
-        );
-
-        let config = rt.block_on(
-            aws_config::defaults(BehaviorVersion::latest())
-                .credentials_provider(credentials)
-                .load()
-        );
+    fn create_one_record(&self, record_name: &str, value: &str) -> Result<String> {
+        let records = [(record_name.to_string(), value.to_string())];
+        runtime::block_on(self.add_txt_values(&records))??;
+        Ok(rrset_record_id(record_name, value))
+    }
 
-        let client = Client::new(&config);
+    fn delete_one_record(&self, record_id: &str) -> Result<()> {
+        let (record_name, value) = parse_rrset_record_id(record_id)?;
+        runtime::block_on(self.remove_txt_value(record_name, value))?
+    }
 
-        let response = rt.block_on(
-            client
-                .list_resource_record_sets()
-                .hosted_zone_id(&hosted_zone_id)
-                .send()
-        )
-        .context("Failed to list Route 53 DNS records")?;
+    fn list_records(&self, record_name: &str) -> Result<Vec<DnsRecord>> {
+        let record_set = runtime::block_on(self.fetch_txt_rrset(record_name))??;
+        Ok(Self::txt_values(record_set.as_ref())
+            .into_iter()
+            .map(|value| DnsRecord {
+                id: rrset_record_id(record_name, &value),
+                name: record_name.to_string(),
+                value,
+            })
+            .collect())
+    }
 
-        let record_set = response
-            .resource_record_sets()
-            .iter()
-            .find(|rs| rs.name() == record_name && rs.r#type() == &RrType::Txt)
-            .cloned()
-            .ok_or_else(|| anyhow!("TXT record not found: {}", record_name))?;
+    fn get_zone_id(&self, _domain: &str) -> Result<String> {
+        runtime::block_on(self.discover_hosted_zone_id())?
+    }
+}
 
-        // Now delete using the record_set
-        rt.block_on(self.delete_txt_record_atomic(record_set))?;
-        Ok(())
+impl DnsProviderBase for Route53Adapter {
+    fn atomic_ops(&self) -> &dyn AtomicDnsOperations {
+        self
     }
+}
 
-    fn list_records(&mut self, record_name: &str) -> Result<Vec<DnsRecord>> {
-        use aws_config::BehaviorVersion;
-        use aws_sdk_route53::config::Credentials;
-        use aws_sdk_route53::Client;
-        use aws_sdk_route53::types::RrType;
-
-        let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
-        
-        let hosted_zone_id = rt.block_on(self.discover_hosted_zone_id())?;
-        
-        let credentials = Credentials::new(
-            &self.access_key,
-            &self.secret_key,
-            None,
-            None,
-            "sslboard",
-        );
+impl DnsProviderAdapter for Route53Adapter {
+    fn create_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        self.set_txt_record(record_name, value)
+    }
 
-        let config = rt.block_on(
-            aws_config::defaults(BehaviorVersion::latest())
-                .credentials_provider(credentials)
-                .load()
-        );
+    /// Every record name of the batch is changed in one ChangeBatch.
+    fn create_txt_batch(&self, records: &[(String, String)]) -> Result<()> {
+        runtime::block_on(self.add_txt_values(records))?
+    }
 
-        let client = Client::new(&config);
+    /// One UPSERT publishes the value as the whole RRset.
+    fn replace_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        let values = vec![Self::format_txt_content(value)];
+        runtime::block_on(self.write_txt_rrset(record_name, values, None))?
+    }
 
-        let response = rt.block_on(
-            client
-                .list_resource_record_sets()
-                .hosted_zone_id(&hosted_zone_id)
-                .send()
-        )
-        .context("Failed to list Route 53 DNS records")?;
+    fn cleanup_txt(&self, record_name: &str) -> Result<()> {
+        self.delete_txt_record(record_name)
+    }
 
-        let mut records = Vec::new();
-        for record_set in response.resource_record_sets() {
-            if record_set.name() == record_name && record_set.r#type() == &RrType::Txt {
-                for record in record_set.resource_records() {
-                    records.push(DnsRecord {
-                        id: format!("route53:{}", record_name),
-                        name: record_name.to_string(),
-                        value: record.value().to_string(),
-                    });
-                }
-            }
-        }
+    /// Always asks Route 53, so cached zones cannot hide revoked credentials.
+    fn verify_zone_access(&self) -> Result<()> {
+        runtime::block_on(self.lookup_hosted_zone_id())??;
+        Ok(())
+    }
 
-        Ok(records)
+    fn list_zones(&self) -> Result<Vec<String>> {
+        runtime::block_on(self.list_hosted_zone_names())?
     }
 
-    fn get_zone_id(&mut self, _domain: &str) -> Result<String> {
-        let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
-        rt.block_on(self.discover_hosted_zone_id())
+    fn list_txt_records(&self) -> Result<Vec<TxtRecord>> {
+        runtime::block_on(self.fetch_zone_txt_records())?
     }
-}
 
-impl DnsProviderBase for Route53Adapter {
-    fn atomic_ops(&mut self) -> &mut dyn AtomicDnsOperations {
-        self
+    fn account_identity(&self) -> Option<Result<String>> {
+        Some(runtime::block_on(self.caller_identity()).and_then(|result| result))
     }
 }
 
-impl DnsProviderAdapter for Route53Adapter {
-    fn create_txt(&self, record_name: &str, value: &str) -> Result<()> {
-        // Use DnsProviderBase for backward compatibility
-        let mut adapter = Route53Adapter::new(
-            self.access_key.clone(),
-            self.secret_key.clone(),
-            self.domain_suffix.clone(),
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_the_most_specific_hosted_zone() {
+        let zones = vec![
+            ("example.com.".to_string(), "/hostedzone/PARENT".to_string()),
+            ("sub.example.com.".to_string(), "/hostedzone/CHILD".to_string()),
+            ("other.com.".to_string(), "/hostedzone/OTHER".to_string()),
+        ];
+        assert_eq!(
+            most_specific_zone("app.sub.example.com", zones.clone()).as_deref(),
+            Some("/hostedzone/CHILD")
         );
-        adapter.set_txt_record(record_name, value)?;
-        Ok(())
+        assert_eq!(
+            most_specific_zone("www.example.com", zones.clone()).as_deref(),
+            Some("/hostedzone/PARENT")
+        );
+        assert!(most_specific_zone("example.org", zones).is_none());
     }
 
-    fn cleanup_txt(&self, record_name: &str) -> Result<()> {
-        // Use DnsProviderBase for backward compatibility
-        let mut adapter = Route53Adapter::new(
-            self.access_key.clone(),
-            self.secret_key.clone(),
-            self.domain_suffix.clone(),
+    #[test]
+    fn matches_listed_record_names_with_trailing_dot() {
+        let name = "_acme-challenge.example.com";
+        assert!(same_record_name("_acme-challenge.Example.com.", name));
+        assert!(!same_record_name("_acme-challenge.www.example.com.", name));
+    }
+
+    #[test]
+    fn reads_role_and_profile_settings() {
+        let role = AssumeRole::from_settings(
+            Some(" arn:aws:iam::123456789012:role/dns/sslboard "),
+            Some("tenant-42"),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(role.role_arn, "arn:aws:iam::123456789012:role/dns/sslboard");
+        assert_eq!(role.external_id.as_deref(), Some("tenant-42"));
+
+        assert!(AssumeRole::from_settings(None, Some("ignored")).unwrap().is_none());
+        assert!(AssumeRole::from_settings(Some("arn:aws:iam::123:role/x"), None).is_err());
+        assert!(AssumeRole::from_settings(Some("arn:aws:iam::123456789012:user/x"), None).is_err());
+        let arn = Some("arn:aws-cn:iam::123456789012:role/x");
+        assert!(AssumeRole::from_settings(arn, None).unwrap().is_some());
+        assert!(AssumeRole::from_settings(arn, Some("a")).is_err());
+        assert!(AssumeRole::from_settings(arn, Some("has space")).is_err());
+
+        assert_eq!(
+            profile_from_settings(Some(" dns-admin ")).unwrap().as_deref(),
+            Some("dns-admin")
         );
-        adapter.delete_txt_record(record_name)?;
-        Ok(())
+        assert!(profile_from_settings(Some("")).unwrap().is_none());
+        assert!(profile_from_settings(Some("prod account")).is_err());
     }
 }
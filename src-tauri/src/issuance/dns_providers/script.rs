@@ -0,0 +1,225 @@
+use std::{
+    io::Read,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, anyhow};
+use serde::Serialize;
+
+use super::{
+    DnsProviderAdapter,
+    debug::DebugCapture,
+    http::{self, SendCaptured},
+};
+
+const SCRIPT_TIMEOUT: Duration = Duration::from_secs(120);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Where the user's automation lives.
+pub enum ScriptHook {
+    /// Shell command run with `RECORD_NAME`, `TXT_VALUE` and `ACTION` set
+    Command(String),
+    /// URL receiving the same fields as a JSON POST
+    Webhook(String),
+}
+
+#[derive(Clone, Copy)]
+enum ScriptAction {
+    Present,
+    Cleanup,
+}
+
+impl ScriptAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            ScriptAction::Present => "present",
+            ScriptAction::Cleanup => "cleanup",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    action: &'static str,
+    record_name: &'a str,
+    txt_value: &'a str,
+}
+
+/// Adapter that hands TXT changes to a user-provided command or webhook, for
+/// DNS hosts without a built-in adapter. Cleanup gets an empty `TXT_VALUE`
+/// and should remove every value the hook created at `RECORD_NAME`.
+pub struct ScriptAdapter {
+    hook: ScriptHook,
+    webhook_token: Option<String>,
+    domain_suffix: String,
+    debug: Option<DebugCapture>,
+}
+
+impl ScriptAdapter {
+    pub fn new(hook: ScriptHook, domain_suffix: String) -> Self {
+        Self {
+            hook,
+            webhook_token: None,
+            domain_suffix,
+            debug: None,
+        }
+    }
+
+    /// Bearer token sent with webhook calls.
+    pub fn with_webhook_token(mut self, token: Option<String>) -> Self {
+        self.webhook_token = token;
+        self
+    }
+
+    /// Records webhook calls for the provider's debug log.
+    pub fn with_debug_capture(mut self, debug: Option<DebugCapture>) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    fn invoke(&self, action: ScriptAction, record_name: &str, value: &str) -> Result<()> {
+        match &self.hook {
+            ScriptHook::Command(command) => run_command(command, action, record_name, value),
+            ScriptHook::Webhook(url) => self.call_webhook(url, action, record_name, value),
+        }
+    }
+
+    fn call_webhook(
+        &self,
+        url: &str,
+        action: ScriptAction,
+        record_name: &str,
+        value: &str,
+    ) -> Result<()> {
+        let mut request = http::HttpClient::shared().post(url).json(&WebhookPayload {
+            action: action.as_str(),
+            record_name,
+            txt_value: value,
+        });
+        if let Some(token) = &self.webhook_token {
+            request = request.bearer_auth(token);
+        }
+        let response = request
+            .send_captured(self.debug.as_ref())
+            .context("Failed to call DNS webhook")?;
+
+        if !response.status.is_success() {
+            let status = response.status;
+            let body = response.body;
+            return Err(http::status_error("DNS webhook", status, Some(body)));
+        }
+        Ok(())
+    }
+}
+
+fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C").arg(command);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c").arg(command);
+        shell
+    }
+}
+
+/// Runs `command` through the shell, failing on a non-zero exit or when it
+/// outlives [`SCRIPT_TIMEOUT`]. Stderr is included in the error.
+fn run_command(command: &str, action: ScriptAction, record_name: &str, value: &str) -> Result<()> {
+    let mut child = shell_command(command)
+        .env("ACTION", action.as_str())
+        .env("RECORD_NAME", record_name)
+        .env("TXT_VALUE", value)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to start DNS script")?;
+
+    // Drained on a thread so a chatty script cannot block on a full pipe
+    let stderr = child.stderr.take();
+    let reader = thread::spawn(move || {
+        let mut output = String::new();
+        if let Some(mut stderr) = stderr {
+            let _ = stderr.read_to_string(&mut output);
+        }
+        output
+    });
+
+    let deadline = Instant::now() + SCRIPT_TIMEOUT;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(anyhow!(
+                "DNS script timed out after {}s",
+                SCRIPT_TIMEOUT.as_secs()
+            ));
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+    let stderr = reader.join().unwrap_or_default();
+
+    if !status.success() {
+        let detail = stderr.trim();
+        return Err(if detail.is_empty() {
+            anyhow!("DNS script failed ({status})")
+        } else {
+            anyhow!("DNS script failed ({status}): {detail}")
+        });
+    }
+    Ok(())
+}
+
+impl DnsProviderAdapter for ScriptAdapter {
+    fn create_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        self.invoke(ScriptAction::Present, record_name, value)
+    }
+
+    fn cleanup_txt(&self, record_name: &str) -> Result<()> {
+        self.invoke(ScriptAction::Cleanup, record_name, "")
+    }
+
+    fn verify_zone_access(&self) -> Result<()> {
+        // The hook's own access is unknown until it runs; the connection test
+        // exercises it end to end.
+        Ok(())
+    }
+
+    fn list_zones(&self) -> Result<Vec<String>> {
+        Ok(vec![self.domain_suffix.clone()])
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_commands_with_record_environment() {
+        let command = [
+            r#"test "$ACTION" = present"#,
+            r#"test "$RECORD_NAME" = _acme-challenge.example.com"#,
+            r#"test "$TXT_VALUE" = abc"#,
+        ]
+        .join(" && ");
+        let adapter = ScriptAdapter::new(ScriptHook::Command(command), "example.com".to_string());
+        adapter
+            .create_txt("_acme-challenge.example.com", "abc")
+            .unwrap();
+        assert!(adapter.cleanup_txt("_acme-challenge.example.com").is_err());
+
+        let failing = ScriptAdapter::new(
+            ScriptHook::Command("echo zone locked >&2; exit 3".to_string()),
+            "example.com".to_string(),
+        );
+        let err = failing.create_txt("_acme-challenge.example.com", "abc").unwrap_err();
+        assert!(err.to_string().contains("zone locked"));
+    }
+}
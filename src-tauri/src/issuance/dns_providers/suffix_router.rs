@@ -0,0 +1,197 @@
+//! Dispatch for providers configured with several domain suffixes.
+//!
+//! Adapters discover one zone for one suffix. A provider covering
+//! `example.com, example.net` gets an adapter per suffix, and each record goes
+//! to the adapter with the most specific suffix containing the record name.
+
+use anyhow::{Context, Result, anyhow};
+
+use super::{DnsProviderAdapter, TxtRecord, matches_zone};
+
+pub struct SuffixRouter {
+    adapters: Vec<(String, Box<dyn DnsProviderAdapter>)>,
+}
+
+impl SuffixRouter {
+    pub fn new(adapters: Vec<(String, Box<dyn DnsProviderAdapter>)>) -> Self {
+        Self { adapters }
+    }
+
+    /// Index of the adapter with the most specific suffix covering `record_name`.
+    fn route(&self, record_name: &str) -> Result<usize> {
+        self.adapters
+            .iter()
+            .enumerate()
+            .filter(|(_, (suffix, _))| matches_zone(record_name, suffix))
+            .max_by_key(|(_, (suffix, _))| suffix.len())
+            .map(|(index, _)| index)
+            .ok_or_else(|| anyhow!("No domain suffix of this provider covers {record_name}"))
+    }
+
+    fn adapter_for(&self, record_name: &str) -> Result<&dyn DnsProviderAdapter> {
+        Ok(self.adapters[self.route(record_name)?].1.as_ref())
+    }
+}
+
+impl DnsProviderAdapter for SuffixRouter {
+    fn create_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        self.adapter_for(record_name)?.create_txt(record_name, value)
+    }
+
+    /// Splits the batch by suffix so each adapter gets one batch.
+    fn create_txt_batch(&self, records: &[(String, String)]) -> Result<()> {
+        let mut batches = vec![Vec::new(); self.adapters.len()];
+        for record in records {
+            batches[self.route(&record.0)?].push(record.clone());
+        }
+        for ((suffix, adapter), batch) in self.adapters.iter().zip(batches) {
+            if !batch.is_empty() {
+                adapter
+                    .create_txt_batch(&batch)
+                    .with_context(|| format!("domain suffix {suffix}"))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn replace_txt(&self, record_name: &str, value: &str) -> Result<()> {
+        self.adapter_for(record_name)?.replace_txt(record_name, value)
+    }
+
+    fn cleanup_txt(&self, record_name: &str) -> Result<()> {
+        self.adapter_for(record_name)?.cleanup_txt(record_name)
+    }
+
+    fn verify_zone_access(&self) -> Result<()> {
+        for (suffix, adapter) in &self.adapters {
+            adapter
+                .verify_zone_access()
+                .with_context(|| format!("domain suffix {suffix}"))?;
+        }
+        Ok(())
+    }
+
+    fn list_zones(&self) -> Result<Vec<String>> {
+        let mut zones = Vec::new();
+        for (_, adapter) in &self.adapters {
+            for zone in adapter.list_zones()? {
+                if !zones.contains(&zone) {
+                    zones.push(zone);
+                }
+            }
+        }
+        Ok(zones)
+    }
+
+    /// Checked for every suffix whose adapter can tell.
+    fn verify_write_access(&self) -> Option<Result<()>> {
+        let mut checked = false;
+        for (suffix, adapter) in &self.adapters {
+            match adapter.verify_write_access() {
+                Some(Ok(())) => checked = true,
+                Some(Err(err)) => return Some(Err(err.context(format!("domain suffix {suffix}")))),
+                None => {}
+            }
+        }
+        checked.then_some(Ok(()))
+    }
+
+    /// Suffixes sharing a zone list it once.
+    fn list_txt_records(&self) -> Result<Vec<TxtRecord>> {
+        let mut records = Vec::new();
+        for (suffix, adapter) in &self.adapters {
+            let listed = adapter
+                .list_txt_records()
+                .with_context(|| format!("domain suffix {suffix}"))?;
+            for record in listed {
+                if !records.contains(&record) {
+                    records.push(record);
+                }
+            }
+        }
+        Ok(records)
+    }
+
+    fn cname_target(&self, record_name: &str) -> Option<String> {
+        self.adapter_for(record_name).ok()?.cname_target(record_name)
+    }
+
+    /// Every suffix uses the same credentials, so the first one answers.
+    fn account_identity(&self) -> Option<Result<String>> {
+        self.adapters.first()?.1.account_identity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    struct Recording {
+        suffix: &'static str,
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl DnsProviderAdapter for Recording {
+        fn create_txt(&self, record_name: &str, _value: &str) -> Result<()> {
+            self.calls.lock().unwrap().push(format!("{}:{record_name}", self.suffix));
+            Ok(())
+        }
+
+        fn cleanup_txt(&self, _record_name: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn verify_zone_access(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn list_zones(&self) -> Result<Vec<String>> {
+            Ok(vec![self.suffix.to_string(), "shared.org".to_string()])
+        }
+    }
+
+    #[test]
+    fn routes_records_to_the_most_specific_suffix() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let adapter = |suffix| -> (String, Box<dyn DnsProviderAdapter>) {
+            let recording = Recording { suffix, calls: calls.clone() };
+            (suffix.to_string(), Box::new(recording))
+        };
+        let router = SuffixRouter::new(vec![
+            adapter("example.com"),
+            adapter("example.net"),
+            adapter("dev.example.com"),
+        ]);
+
+        router.create_txt("_acme-challenge.example.net", "a").unwrap();
+        router.create_txt("_acme-challenge.www.example.com", "b").unwrap();
+        router.create_txt("_acme-challenge.api.dev.example.com", "c").unwrap();
+        assert!(router.create_txt("_acme-challenge.example.org", "d").is_err());
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "example.net:_acme-challenge.example.net",
+                "example.com:_acme-challenge.www.example.com",
+                "dev.example.com:_acme-challenge.api.dev.example.com",
+            ]
+        );
+        assert_eq!(router.list_zones().unwrap().len(), 4);
+
+        calls.lock().unwrap().clear();
+        let batch = [
+            ("_acme-challenge.www.example.com".to_string(), "e".to_string()),
+            ("_acme-challenge.example.net".to_string(), "f".to_string()),
+            ("_acme-challenge.example.com".to_string(), "g".to_string()),
+        ];
+        router.create_txt_batch(&batch).unwrap();
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "example.com:_acme-challenge.www.example.com",
+                "example.com:_acme-challenge.example.com",
+                "example.net:_acme-challenge.example.net",
+            ]
+        );
+    }
+}
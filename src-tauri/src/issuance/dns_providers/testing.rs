@@ -1,79 +1,117 @@
-use anyhow::{Context, Result};
+use anyhow::{Result, anyhow};
 use log::{info, warn};
 use serde::Deserialize;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use super::base::AtomicDnsOperations;
-use crate::issuance::dns::{DnsPropagationResult, PropagationState};
-
-/// Queries Google DNS via HTTPS for a TXT record.
+use crate::core::messages::MessageCode;
+use crate::issuance::dns::{
+    CNAME_RECORD_TYPE, DnsPropagationResult, PropagationState, ResolverOutcome,
+};
+use crate::issuance::dns_wire::TYPE_TXT;
+use crate::issuance::propagation_cache::propagation_cache;
+use crate::issuance::propagation_resolvers::{ResolverResponse, ResolverSet};
+
+/// Queries the propagation resolvers for a TXT record.
 /// This is a public function that can be used by other modules for DNS testing.
 /// Uses default normalization (trim quotes and whitespace).
-pub fn query_google_dns(record_name: &str, expected_value: &str) -> Result<DnsPropagationResult> {
+pub fn query_google_dns(
+    record_name: &str,
+    expected_value: &str,
+    resolvers: &ResolverSet,
+) -> Result<DnsPropagationResult> {
     // Use default normalization for backward compatibility
     let normalized_expected = expected_value.trim().trim_matches('"').trim().to_string();
-    query_google_dns_with_normalization(record_name, &normalized_expected, &DefaultNormalizer)
+    propagation_cache().get_or_check(record_name, &normalized_expected, resolvers, || {
+        query_google_dns_with_normalization(
+            record_name,
+            &normalized_expected,
+            &DefaultNormalizer,
+            resolvers,
+        )
+    })
 }
 
-/// Internal function that queries Google DNS with provider-specific normalization.
+/// Internal function that queries each resolver in turn with provider-specific
+/// normalization. Every resolver is asked so the result lists each one's
+/// outcome; the first resolver that sees the value decides the overall
+/// state, otherwise the first answer received does.
 pub fn query_google_dns_with_normalization(
     record_name: &str,
     normalized_expected: &str,
     normalizer: &dyn AtomicDnsOperations,
+    resolvers: &ResolverSet,
 ) -> Result<DnsPropagationResult> {
-    let url = format!(
-        "https://dns.google/resolve?name={}&type=TXT&random_padding=x",
-        record_name
-    );
     let timeout = resolve_dns_timeout();
+    let mut found: Option<DnsPropagationResult> = None;
+    let mut first: Option<DnsPropagationResult> = None;
+    let mut outcomes = Vec::new();
+    let mut last_error = None;
+
+    for resolver in resolvers.resolvers() {
+        info!("[dns-test] Querying {} for {}", resolver.name(), record_name);
+        let started = Instant::now();
+        let queried = resolver.query_txt(record_name, timeout);
+        let rtt_ms = started.elapsed().as_millis() as u64;
+        let response = match queried {
+            Ok(response) => GoogleDnsResponse::from(response),
+            Err(err) => {
+                warn!("[dns-test] {} query failed for {}: {}", resolver.name(), record_name, err);
+                let error = format!("query failed: {err}");
+                outcomes.push(ResolverOutcome::failed(&resolver.name(), error, rtt_ms));
+                last_error = Some(err);
+                continue;
+            }
+        };
 
-    info!("[dns-test] Querying Google DNS for {}", record_name);
-
-    let agent = ureq::AgentBuilder::new().timeout(timeout).build();
-    let response = agent
-        .get(&url)
-        .set("Accept", "application/dns-json")
-        .call()
-        .context("Failed to query Google DNS")?;
-
-    let body = response
-        .into_string()
-        .context("Failed to read Google DNS response body")?;
-
-    let dns_response: GoogleDnsResponse =
-        serde_json::from_str(&body).context("Failed to parse Google DNS response")?;
+        info!(
+            "[dns-test] {} responded: status={}, has_answer={}",
+            resolver.name(),
+            response.status,
+            response.answer.is_some()
+        );
 
-    info!(
-        "[dns-test] Google DNS responded: status={}, has_answer={}",
-        dns_response.status,
-        dns_response.answer.is_some()
-    );
+        let result = interpret_dns_response_with_normalization(
+            &response,
+            record_name,
+            normalized_expected,
+            normalizer,
+        );
+        outcomes.push(ResolverOutcome::answered(&resolver.name(), result.clone(), rtt_ms));
+        if result.state == PropagationState::Found {
+            found.get_or_insert(result);
+        } else {
+            first.get_or_insert(result);
+        }
+    }
 
-    Ok(interpret_dns_response_with_normalization(
-        &dns_response,
-        record_name,
-        normalized_expected,
-        normalizer,
-    ))
+    match (found.or(first), last_error) {
+        (Some(mut result), _) => {
+            result.resolvers = outcomes;
+            Ok(result)
+        }
+        (None, Some(err)) => Err(err.context(format!("DNS query failed for {record_name}"))),
+        (None, None) => Err(anyhow!("no propagation resolvers are configured")),
+    }
 }
 
 /// Default normalizer for backward compatibility (used by query_google_dns).
 pub struct DefaultNormalizer;
 
 impl AtomicDnsOperations for DefaultNormalizer {
-    fn create_one_record(&mut self, _record_name: &str, _value: &str) -> Result<String> {
+    fn create_one_record(&self, _record_name: &str, _value: &str) -> Result<String> {
         unreachable!("DefaultNormalizer is only used for normalization")
     }
 
-    fn delete_one_record(&mut self, _record_id: &str) -> Result<()> {
+    fn delete_one_record(&self, _record_id: &str) -> Result<()> {
         unreachable!("DefaultNormalizer is only used for normalization")
     }
 
-    fn list_records(&mut self, _record_name: &str) -> Result<Vec<super::base::DnsRecord>> {
+    fn list_records(&self, _record_name: &str) -> Result<Vec<super::base::DnsRecord>> {
         unreachable!("DefaultNormalizer is only used for normalization")
     }
 
-    fn get_zone_id(&mut self, _domain: &str) -> Result<String> {
+    fn get_zone_id(&self, _domain: &str) -> Result<String> {
         unreachable!("DefaultNormalizer is only used for normalization")
     }
 }
@@ -91,7 +129,7 @@ pub fn interpret_dns_response_with_normalization(
     let mut saw_ok = false;
 
     if let Some(answers) = &response.answer {
-        for ans in answers {
+        for ans in answers.iter().filter(|ans| ans.record_type != Some(CNAME_RECORD_TYPE)) {
             if let Some(data) = &ans.data {
                 // Google DNS returns quoted values, normalize using provider logic
                 let normalized = normalizer.normalize_value(data);
@@ -114,42 +152,38 @@ pub fn interpret_dns_response_with_normalization(
     }
 
     if observed.iter().any(|val| val == normalized_expected) {
-        return DnsPropagationResult {
-            state: PropagationState::Found,
-            reason: None,
-            observed_values: observed,
-        };
+        return DnsPropagationResult::new(PropagationState::Found, None, observed);
     }
 
     if !observed.is_empty() {
-        return DnsPropagationResult {
-            state: PropagationState::WrongContent,
-            reason: Some("TXT record present with different value".to_string()),
-            observed_values: observed,
-        };
+        return DnsPropagationResult::new(
+            PropagationState::WrongContent,
+            Some(MessageCode::PropagationWrongContent),
+            observed,
+        );
     }
 
     if saw_ok {
-        return DnsPropagationResult {
-            state: PropagationState::Pending,
-            reason: Some("record not found yet".to_string()),
-            observed_values: observed,
-        };
+        return DnsPropagationResult::new(
+            PropagationState::Pending,
+            Some(MessageCode::PropagationPending),
+            observed,
+        );
     }
 
     if saw_nxdomain {
-        return DnsPropagationResult {
-            state: PropagationState::NxDomain,
-            reason: Some("record not found (NXDOMAIN)".to_string()),
-            observed_values: observed,
-        };
+        return DnsPropagationResult::new(
+            PropagationState::NxDomain,
+            Some(MessageCode::PropagationNxDomain),
+            observed,
+        );
     }
 
-    DnsPropagationResult {
-        state: PropagationState::Error,
-        reason: Some("no response from DNS resolver".to_string()),
-        observed_values: observed,
-    }
+    DnsPropagationResult::new(
+        PropagationState::Error,
+        Some(MessageCode::PropagationNoResponses),
+        observed,
+    )
 }
 
 /// Interprets a Google DNS response into a DnsPropagationResult.
@@ -174,7 +208,7 @@ fn trim_txt_quotes(value: &str) -> String {
     value.trim().trim_matches('"').trim().to_string()
 }
 
-fn resolve_dns_timeout() -> Duration {
+pub(crate) fn resolve_dns_timeout() -> Duration {
     const DEFAULT_TIMEOUT_SECS: u64 = 10;
     let timeout = std::env::var("SSLBOARD_HTTP_TIMEOUT_SECS")
         .ok()
@@ -191,6 +225,26 @@ fn resolve_dns_timeout() -> Duration {
 pub struct GoogleDnsAnswer {
     #[serde(rename = "data")]
     pub data: Option<String>,
+    /// CNAME answers precede the TXT records when the name is an alias
+    #[serde(rename = "type", default)]
+    pub record_type: Option<u16>,
+}
+
+impl From<ResolverResponse> for GoogleDnsResponse {
+    fn from(response: ResolverResponse) -> Self {
+        let answers: Vec<GoogleDnsAnswer> = response
+            .values
+            .into_iter()
+            .map(|value| GoogleDnsAnswer {
+                data: Some(value),
+                record_type: Some(TYPE_TXT),
+            })
+            .collect();
+        Self {
+            status: response.status,
+            answer: (!answers.is_empty()).then_some(answers),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -211,6 +265,7 @@ mod tests {
             status: 0,
             answer: Some(vec![GoogleDnsAnswer {
                 data: Some("\"expected-value\"".to_string()),
+                record_type: None,
             }]),
         };
         let result = interpret_dns_response(&response, "test.example.com", "expected-value");
@@ -228,6 +283,7 @@ mod tests {
             status: 0,
             answer: Some(vec![GoogleDnsAnswer {
                 data: Some("\"other-value\"".to_string()),
+                record_type: None,
             }]),
         };
         let result = interpret_dns_response(&response, "test.example.com", "expected-value");
@@ -251,6 +307,7 @@ mod tests {
             status: 0,
             answer: Some(vec![GoogleDnsAnswer {
                 data: Some("\"expected-value\"".to_string()),
+                record_type: None,
             }]),
         };
         let result = interpret_dns_response(&response, "test.example.com", "expected-value");
@@ -283,9 +340,11 @@ mod tests {
             answer: Some(vec![
                 GoogleDnsAnswer {
                     data: Some("\"value1\"".to_string()),
+                    record_type: None,
                 },
                 GoogleDnsAnswer {
                     data: Some("\"value2\"".to_string()),
+                    record_type: None,
                 },
             ]),
         };
@@ -303,9 +362,11 @@ mod tests {
             answer: Some(vec![
                 GoogleDnsAnswer {
                     data: Some("\"value1\"".to_string()),
+                    record_type: None,
                 },
                 GoogleDnsAnswer {
                     data: Some("\"value2\"".to_string()),
+                    record_type: None,
                 },
             ]),
         };
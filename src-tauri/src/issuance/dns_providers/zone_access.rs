@@ -0,0 +1,93 @@
+//! Choice between providers configured with the same suffix.
+//!
+//! Several providers of one type may hold credentials for different
+//! accounts and claim the same suffixes, e.g. a staging and a production
+//! Cloudflare account both listing `example.com`. Priority decides between
+//! them when it differs. When it does not, each tied provider's account is
+//! asked for the zone of the name, and only the providers whose account
+//! holds it stay in the running.
+
+use anyhow::Result;
+
+use super::{cached_adapter_for_provider, matches_zone};
+use crate::secrets::manager::SecretManager;
+use crate::storage::dns::{DnsConfigStore, DnsProvider, DnsProviderResolution};
+
+/// Resolves the provider for `hostname` as
+/// [`DnsConfigStore::resolve_provider_for_domain`] does, then settles a tie
+/// by zone access. The tie is kept when no account, or every one, holds the
+/// zone.
+pub fn resolve_provider_by_zone_access(
+    dns_store: &DnsConfigStore,
+    secrets: &SecretManager,
+    hostname: &str,
+) -> Result<DnsProviderResolution> {
+    let mut resolution = dns_store.resolve_provider_for_domain(hostname)?;
+    if resolution.ambiguous.len() <= 1 {
+        return Ok(resolution);
+    }
+    let holding = holding_providers(&resolution.ambiguous, |provider| {
+        let adapter = cached_adapter_for_provider(provider, secrets, dns_store);
+        adapter.list_zones().map(|zones| zones.iter().any(|zone| matches_zone(hostname, zone)))
+    });
+    if holding.is_empty() || holding.len() == resolution.ambiguous.len() {
+        return Ok(resolution);
+    }
+    let labels: Vec<&str> = holding.iter().map(|provider| provider.label.as_str()).collect();
+    log::info!("[dns] only {} can see the zone of {hostname}", labels.join(", "));
+    resolution.provider = holding.first().cloned();
+    resolution.ambiguous = holding;
+    Ok(resolution)
+}
+
+/// Tied providers whose account holds the zone, in resolution order. A
+/// provider whose zones cannot be listed is left out.
+fn holding_providers(
+    tied: &[DnsProvider],
+    holds_zone: impl Fn(&DnsProvider) -> Result<bool>,
+) -> Vec<DnsProvider> {
+    tied.iter()
+        .filter(|provider| match holds_zone(provider) {
+            Ok(holds) => holds,
+            Err(err) => {
+                log::warn!("[dns] failed to list the zones of {}: {err:#}", provider.label);
+                false
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+    use chrono::Utc;
+
+    use super::*;
+
+    fn provider(id: &str) -> DnsProvider {
+        DnsProvider {
+            id: id.to_string(),
+            provider_type: "cloudflare".to_string(),
+            label: id.to_string(),
+            domain_suffixes: vec!["example.com".to_string()],
+            secret_refs: Vec::new(),
+            config_json: None,
+            priority: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn keeps_providers_whose_account_holds_the_zone() {
+        let tied = [provider("staging"), provider("production"), provider("revoked")];
+        let holding = holding_providers(&tied, |provider| match provider.id.as_str() {
+            "production" => Ok(true),
+            "revoked" => Err(anyhow!("HTTP 403")),
+            _ => Ok(false),
+        });
+        let ids: Vec<&str> = holding.iter().map(|provider| provider.id.as_str()).collect();
+        assert_eq!(ids, vec!["production"]);
+    }
+}
@@ -0,0 +1,39 @@
+//! Zone lookups shared between adapter instances of one provider.
+//!
+//! Adapters are rebuilt for every TXT operation, so the zone they discover
+//! only lives for that call. Discovered zones are kept in the
+//! `dns_zone_cache` table for [`ZONE_CACHE_TTL`]; updating or deleting the
+//! provider drops them. Cache failures are logged and treated as misses.
+
+use chrono::Duration;
+
+use crate::storage::dns::DnsConfigStore;
+
+pub const ZONE_CACHE_TTL: Duration = Duration::hours(24);
+
+#[derive(Clone)]
+pub struct ZoneCache {
+    store: DnsConfigStore,
+    provider_id: String,
+}
+
+impl ZoneCache {
+    pub fn new(store: DnsConfigStore, provider_id: String) -> Self {
+        Self { store, provider_id }
+    }
+
+    pub fn get(&self, domain_suffix: &str) -> Option<String> {
+        self.store
+            .cached_zone(&self.provider_id, domain_suffix, ZONE_CACHE_TTL)
+            .unwrap_or_else(|err| {
+                log::warn!("[dns] failed to read zone cache for {domain_suffix}: {err}");
+                None
+            })
+    }
+
+    pub fn put(&self, domain_suffix: &str, zone_id: &str) {
+        if let Err(err) = self.store.cache_zone(&self.provider_id, domain_suffix, zone_id) {
+            log::warn!("[dns] failed to cache zone for {domain_suffix}: {err}");
+        }
+    }
+}
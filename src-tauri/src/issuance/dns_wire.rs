@@ -0,0 +1,362 @@
+//! Minimal DNS wire format used where we talk to nameservers directly.
+//!
+//! Covers what the RFC 2136 adapter and the authoritative propagation check
+//! need: building queries, parsing the answer and authority sections, and
+//! exchanging messages over UDP or TCP. Additional records are not parsed.
+
+use std::{
+    io::{Read, Write},
+    net::{SocketAddr, TcpStream, UdpSocket},
+    time::Duration,
+};
+
+use anyhow::{Context, Result, anyhow};
+use serde::Serialize;
+
+pub(crate) const DEFAULT_PORT: u16 = 53;
+
+pub(crate) const TYPE_TXT: u16 = 16;
+pub(crate) const TYPE_SOA: u16 = 6;
+pub(crate) const CLASS_IN: u16 = 1;
+pub(crate) const RCODE_NXDOMAIN: u8 = 3;
+/// Recursion desired.
+const FLAG_RD: u16 = 0x0100;
+
+/// Receive buffer for UDP replies; servers cap them at 512 bytes without EDNS.
+const UDP_BUFFER: usize = 4096;
+
+pub(crate) struct ResourceRecord {
+    pub name: String,
+    pub rtype: u16,
+    pub rdata: Vec<u8>,
+}
+
+pub(crate) struct Response {
+    pub rcode: u8,
+    /// AA bit: the answer came from a server authoritative for the name.
+    pub authoritative: bool,
+    /// TC bit: the UDP reply was cut short and should be retried over TCP.
+    pub truncated: bool,
+    pub answers: Vec<ResourceRecord>,
+    pub authority: Vec<ResourceRecord>,
+}
+
+/// Appends a default port when `server` has none; IPv6 literals need brackets.
+pub(crate) fn server_address(server: &str) -> String {
+    let server = server.trim();
+    if server.starts_with('[') {
+        if server.contains("]:") {
+            server.to_string()
+        } else {
+            format!("{server}:{DEFAULT_PORT}")
+        }
+    } else if server.matches(':').count() == 1 {
+        server.to_string()
+    } else if server.contains(':') {
+        format!("[{server}]:{DEFAULT_PORT}")
+    } else {
+        format!("{server}:{DEFAULT_PORT}")
+    }
+}
+
+pub(crate) fn header(
+    flags: u16,
+    qdcount: u16,
+    ancount: u16,
+    nscount: u16,
+    arcount: u16,
+) -> Vec<u8> {
+    let mut message = Vec::with_capacity(512);
+    push_u16(&mut message, 0);
+    for value in [flags, qdcount, ancount, nscount, arcount] {
+        push_u16(&mut message, value);
+    }
+    message
+}
+
+/// Builds a single-question query for `name` without recursion desired.
+pub(crate) fn query(name: &str, rtype: u16) -> Result<Vec<u8>> {
+    question(0, name, rtype)
+}
+
+/// Builds a single-question query for `name` asking a resolver to recurse.
+pub(crate) fn recursive_query(name: &str, rtype: u16) -> Result<Vec<u8>> {
+    question(FLAG_RD, name, rtype)
+}
+
+fn question(flags: u16, name: &str, rtype: u16) -> Result<Vec<u8>> {
+    let mut message = header(flags, 1, 0, 0, 0);
+    push_name(&mut message, name)?;
+    push_u16(&mut message, rtype);
+    push_u16(&mut message, CLASS_IN);
+    Ok(message)
+}
+
+pub(crate) fn push_u16(buf: &mut Vec<u8>, value: u16) {
+    buf.extend_from_slice(&value.to_be_bytes());
+}
+
+/// Writes `name` in uncompressed wire format.
+pub(crate) fn push_name(buf: &mut Vec<u8>, name: &str) -> Result<()> {
+    let name = name.trim_end_matches('.');
+    if !name.is_empty() {
+        for label in name.split('.') {
+            if label.is_empty() || label.len() > 63 {
+                return Err(anyhow!("invalid DNS name: {name}"));
+            }
+            buf.push(label.len() as u8);
+            buf.extend_from_slice(label.as_bytes());
+        }
+    }
+    buf.push(0);
+    Ok(())
+}
+
+pub(crate) fn parse_txt_rdata(rdata: &[u8]) -> String {
+    let mut value = Vec::new();
+    let mut pos = 0;
+    while pos < rdata.len() {
+        let len = rdata[pos] as usize;
+        let end = (pos + 1 + len).min(rdata.len());
+        value.extend_from_slice(&rdata[pos + 1..end]);
+        pos = end;
+    }
+    String::from_utf8_lossy(&value).into_owned()
+}
+
+/// Serial of an SOA record. The MNAME and RNAME before it may end in a
+/// compression pointer, which is skipped rather than followed.
+pub(crate) fn soa_serial(rdata: &[u8]) -> Result<u32> {
+    let mut pos = 0;
+    for _ in 0..2 {
+        loop {
+            let len = *rdata.get(pos).ok_or_else(|| anyhow!("Truncated SOA record"))?;
+            if len & 0xC0 == 0xC0 {
+                pos += 2;
+                break;
+            }
+            pos += 1 + len as usize;
+            if len == 0 {
+                break;
+            }
+        }
+    }
+    let serial = rdata
+        .get(pos..pos + 4)
+        .ok_or_else(|| anyhow!("Truncated SOA record"))?;
+    Ok(u32::from_be_bytes([serial[0], serial[1], serial[2], serial[3]]))
+}
+
+pub(crate) fn read_u16(message: &[u8], pos: usize) -> Result<u16> {
+    message
+        .get(pos..pos + 2)
+        .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+        .ok_or_else(|| anyhow!("Truncated DNS response"))
+}
+
+/// Reads a possibly compressed name at `pos`, returning it and the offset after it.
+pub(crate) fn read_name(message: &[u8], mut pos: usize) -> Result<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut end = None;
+    for _ in 0..128 {
+        let len = *message.get(pos).ok_or_else(|| anyhow!("Truncated DNS name"))? as usize;
+        if len == 0 {
+            return Ok((labels.join("."), end.unwrap_or(pos + 1)));
+        }
+        if len & 0xC0 == 0xC0 {
+            let pointer = (read_u16(message, pos)? & 0x3FFF) as usize;
+            end.get_or_insert(pos + 2);
+            pos = pointer;
+            continue;
+        }
+        let label = message
+            .get(pos + 1..pos + 1 + len)
+            .ok_or_else(|| anyhow!("Truncated DNS name"))?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        pos += 1 + len;
+    }
+    Err(anyhow!("DNS name compression loop"))
+}
+
+pub(crate) fn parse_response(message: &[u8]) -> Result<Response> {
+    if message.len() < 12 {
+        return Err(anyhow!("Truncated DNS response"));
+    }
+    let authoritative = message[2] & 0x04 != 0;
+    let truncated = message[2] & 0x02 != 0;
+    let rcode = message[3] & 0x0F;
+    let qdcount = read_u16(message, 4)?;
+    let ancount = read_u16(message, 6)?;
+    let nscount = read_u16(message, 8)?;
+
+    let mut pos = 12;
+    for _ in 0..qdcount {
+        pos = read_name(message, pos)?.1 + 4;
+    }
+    let mut read_records = |count: u16| -> Result<Vec<ResourceRecord>> {
+        let mut records = Vec::new();
+        for _ in 0..count {
+            let (name, next) = read_name(message, pos)?;
+            let rtype = read_u16(message, next)?;
+            let rdlength = read_u16(message, next + 8)? as usize;
+            let start = next + 10;
+            let rdata = message
+                .get(start..start + rdlength)
+                .ok_or_else(|| anyhow!("Truncated DNS record"))?
+                .to_vec();
+            pos = start + rdlength;
+            records.push(ResourceRecord { name, rtype, rdata });
+        }
+        Ok(records)
+    };
+    // A truncated reply may stop mid-record; keep what parsed and let the
+    // caller retry over TCP
+    let answers = match read_records(ancount) {
+        Ok(records) => records,
+        Err(_) if truncated => Vec::new(),
+        Err(err) => return Err(err),
+    };
+    let authority = match read_records(nscount) {
+        Ok(records) => records,
+        Err(_) if truncated => Vec::new(),
+        Err(err) => return Err(err),
+    };
+    Ok(Response {
+        rcode,
+        authoritative,
+        truncated,
+        answers,
+        authority,
+    })
+}
+
+/// Gives `message` a random ID, returning it so the reply can be matched.
+fn stamp_id(message: &mut [u8]) -> [u8; 2] {
+    let id = rand::random::<u16>().to_be_bytes();
+    message[..2].copy_from_slice(&id);
+    id
+}
+
+/// Sends `message` over TCP with the two-byte length prefix and returns the
+/// raw reply. The caller sets the message ID.
+pub(crate) fn exchange_tcp(
+    address: SocketAddr,
+    message: &[u8],
+    timeout: Duration,
+) -> Result<Vec<u8>> {
+    let mut stream = TcpStream::connect_timeout(&address, timeout)
+        .with_context(|| format!("Failed to connect to DNS server {address}"))?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    let length = u16::try_from(message.len()).context("DNS message too large")?;
+    stream.write_all(&length.to_be_bytes())?;
+    stream.write_all(message)?;
+
+    let mut length = [0u8; 2];
+    stream
+        .read_exact(&mut length)
+        .context("DNS server closed the connection")?;
+    let mut reply = vec![0u8; u16::from_be_bytes(length) as usize];
+    stream.read_exact(&mut reply).context("Truncated DNS response")?;
+    Ok(reply)
+}
+
+/// Sends `message` as a single UDP datagram and returns the first reply
+/// carrying the same ID.
+pub(crate) fn exchange_udp(
+    address: SocketAddr,
+    message: &[u8],
+    timeout: Duration,
+) -> Result<Vec<u8>> {
+    let bind: SocketAddr = if address.is_ipv4() {
+        ([0, 0, 0, 0], 0).into()
+    } else {
+        ([0u16; 8], 0).into()
+    };
+    let socket = UdpSocket::bind(bind).context("Failed to open a UDP socket")?;
+    socket.set_read_timeout(Some(timeout))?;
+    socket.connect(address)?;
+    socket
+        .send(message)
+        .with_context(|| format!("Failed to send to DNS server {address}"))?;
+
+    let mut reply = vec![0u8; UDP_BUFFER];
+    loop {
+        let len = socket
+            .recv(&mut reply)
+            .with_context(|| format!("No UDP reply from DNS server {address}"))?;
+        // Ignore stray datagrams that answer some other query
+        if len >= 2 && reply[..2] == message[..2] {
+            reply.truncate(len);
+            return Ok(reply);
+        }
+    }
+}
+
+/// Sends a query over UDP and retries over TCP when the reply is truncated
+/// or UDP gets no answer. Returns the response and the transport that
+/// produced it.
+pub(crate) fn resolve(
+    address: SocketAddr,
+    mut message: Vec<u8>,
+    timeout: Duration,
+) -> Result<(Response, DnsTransport)> {
+    let id = stamp_id(&mut message);
+    let udp = exchange_udp(address, &message, timeout)
+        .and_then(|reply| parse_response(&reply));
+    match udp {
+        Ok(response) if !response.truncated => return Ok((response, DnsTransport::Udp)),
+        Ok(_) => {}
+        Err(err) => log::debug!("[dns] UDP query to {address} failed, trying TCP: {err}"),
+    }
+    let reply = exchange_tcp(address, &message, timeout)?;
+    if reply.len() < 12 || reply[..2] != id {
+        return Err(anyhow!("DNS response does not match the request"));
+    }
+    Ok((parse_response(&reply)?, DnsTransport::Tcp))
+}
+
+/// Transport a direct query was answered over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DnsTransport {
+    Udp,
+    Tcp,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_header_flags_and_tolerates_truncation() {
+        let mut message = query("_acme-challenge.example.com", TYPE_TXT).unwrap();
+        // QR | AA, one answer that the truncated reply leaves out
+        message[2] = 0x84;
+        message[7] = 1;
+        let parsed = parse_response(&message);
+        assert!(parsed.is_err());
+
+        message[2] |= 0x02;
+        let parsed = parse_response(&message).unwrap();
+        assert!(parsed.authoritative);
+        assert!(parsed.truncated);
+        assert!(parsed.answers.is_empty());
+        assert!(parse_response(&message[..8]).is_err());
+    }
+
+    #[test]
+    fn reads_soa_serial_past_compressed_names() {
+        // MNAME ns1.<pointer>, RNAME as a bare pointer, then SERIAL
+        let mut rdata = vec![3, b'n', b's', b'1', 0xC0, 0x0C, 0xC0, 0x10];
+        rdata.extend_from_slice(&2024061501u32.to_be_bytes());
+        rdata.extend_from_slice(&[0; 16]);
+        assert_eq!(soa_serial(&rdata).unwrap(), 2024061501);
+
+        let mut uncompressed = vec![1, b'a', 0, 0];
+        uncompressed.extend_from_slice(&7u32.to_be_bytes());
+        assert_eq!(soa_serial(&uncompressed).unwrap(), 7);
+        assert!(soa_serial(&rdata[..10]).is_err());
+    }
+}
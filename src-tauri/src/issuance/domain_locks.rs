@@ -0,0 +1,117 @@
+//! Per-name locks held by pending issuances.
+//!
+//! Every name of an order is answered at `_acme-challenge.<name>`, and
+//! cleaning up a session deletes the TXT records there. Two sessions for the
+//! same name would remove each other's challenge values, so a name belongs to
+//! one session from start until that session is completed or dropped. A
+//! wildcard and its base name share a record and therefore share a lock.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use anyhow::Result;
+
+use crate::core::errors::IssuanceInProgress;
+
+fn locks() -> MutexGuard<'static, HashMap<String, String>> {
+    static LOCKS: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    LOCKS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+}
+
+/// Name whose challenge record `domain` is validated through.
+fn lock_key(domain: &str) -> String {
+    domain
+        .trim()
+        .trim_start_matches("*.")
+        .trim_end_matches('.')
+        .to_ascii_lowercase()
+}
+
+/// Names locked by `request_id`; released when dropped.
+#[derive(Debug)]
+pub struct DomainLock {
+    request_id: String,
+    keys: Vec<String>,
+}
+
+impl Drop for DomainLock {
+    fn drop(&mut self) {
+        let mut locks = locks();
+        for key in &self.keys {
+            if locks.get(key) == Some(&self.request_id) {
+                locks.remove(key);
+            }
+        }
+    }
+}
+
+/// Locks every name of `domains` for `request_id`, or fails without locking
+/// any when another session holds one of them.
+pub fn acquire(domains: &[String], request_id: &str) -> Result<DomainLock, IssuanceInProgress> {
+    let mut keys: Vec<String> = domains.iter().map(|domain| lock_key(domain)).collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut locks = locks();
+    check_unlocked(&locks, &keys)?;
+    for key in &keys {
+        locks.insert(key.clone(), request_id.to_string());
+    }
+    Ok(DomainLock {
+        request_id: request_id.to_string(),
+        keys,
+    })
+}
+
+/// Fails when a pending issuance holds any of `domains`, e.g. before removing
+/// challenge records left by an interrupted one.
+pub fn ensure_unlocked(domains: &[String]) -> Result<()> {
+    let keys: Vec<String> = domains.iter().map(|domain| lock_key(domain)).collect();
+    check_unlocked(&locks(), &keys)?;
+    Ok(())
+}
+
+fn check_unlocked(
+    locks: &HashMap<String, String>,
+    keys: &[String],
+) -> Result<(), IssuanceInProgress> {
+    let mut held: Vec<(&String, &String)> = keys
+        .iter()
+        .filter_map(|key| locks.get(key).map(|owner| (key, owner)))
+        .collect();
+    if held.is_empty() {
+        return Ok(());
+    }
+    held.sort();
+    held.dedup();
+    Err(IssuanceInProgress::new(
+        held.iter().map(|(key, _)| (*key).clone()).collect(),
+        held[0].1.clone(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_held_until_the_lock_drops() {
+        let domains = vec!["lock-test.example".to_string(), "*.lock-test.example".to_string()];
+        let lock = acquire(&domains, "req_1").expect("first lock");
+
+        let err = acquire(&["LOCK-TEST.example.".to_string()], "req_2").unwrap_err();
+        assert_eq!(err.domains, vec!["lock-test.example"]);
+        assert_eq!(err.request_id, "req_1");
+        assert!(ensure_unlocked(&["*.lock-test.example".to_string()]).is_err());
+        // A failed attempt locks none of its names
+        let partial = vec!["other.lock-test.example".to_string(), "lock-test.example".to_string()];
+        assert!(acquire(&partial, "req_3").is_err());
+        assert!(ensure_unlocked(&["other.lock-test.example".to_string()]).is_ok());
+
+        drop(lock);
+        assert!(acquire(&domains, "req_2").is_ok());
+    }
+}
@@ -1,27 +1,44 @@
 use std::{
     collections::HashMap,
     sync::{Mutex, OnceLock},
-    time::Duration,
 };
 
 use acme_lib::{
-    Certificate, Error as AcmeError,
-    order::NewOrder,
+    Error as AcmeError,
+    order::{Auth, NewOrder},
     persist::{Persist, PersistKey, PersistKind},
 };
 use anyhow::{Result, anyhow};
-use chrono::{TimeZone, Utc};
+use chrono::{DateTime, Duration, TimeZone, Utc};
 use sha2::{Digest, Sha256};
 use uuid::Uuid;
 use x509_parser::pem::parse_x509_pem;
+use zeroize::Zeroizing;
 
 use crate::{
-    core::types::{CertificateRecord, CertificateSource, KeyAlgorithm, KeyCurve},
+    core::types::{
+        CaPin, CertificateRecord, CertificateSource, CertificateStatus, ChallengeSolverConfig,
+        FinalizationStage, IssuanceProgress, JobKind, KeyAlgorithm, KeyCurve,
+    },
+    domain::normalize_domain_for_storage,
     issuance::acme_workflow,
-    issuance::dns::{record_name, DnsRecordInstruction, PropagationState},
-    issuance::dns_providers::{adapter_for_provider, poll_dns_propagation},
-    secrets::{manager::SecretManager, types::SecretKind},
-    storage::{dns::DnsConfigStore, inventory::InventoryStore, issuer::IssuerConfigStore},
+    issuance::artifacts,
+    issuance::ca_pinning::{self, PinCheck, CA_PIN_MISMATCH_TAG},
+    issuance::chain_repair::repair_chain,
+    issuance::chains::{self, CertificateChain},
+    issuance::dns::DnsRecordInstruction,
+    issuance::dns_providers::{
+        DnsProviderAdapter, cached_adapter_for_provider, resolve_provider_by_zone_access,
+    },
+    issuance::domain_locks::{self, DomainLock},
+    issuance::order_polling::{OrderPoller, PollSettings},
+    issuance::propagation_presets::PropagationWaits,
+    issuance::propagation_resolvers::ResolverSet,
+    issuance::solvers::{self, ChallengeSolver, SolverContext},
+    secrets::manager::SecretManager,
+    storage::{
+        dns::DnsConfigStore, inventory::InventoryStore, issuer::IssuerConfigStore, jobs::JobStore,
+    },
 };
 
 /// In-memory persistence for acme-lib that avoids disk I/O and lets us seed the ACME account key.
@@ -63,13 +80,31 @@ impl Persist for EphemeralPersist {
 struct PendingIssuance {
     order: NewOrder<EphemeralPersist>,
     domains: Vec<String>,
+    /// The key itself stays in the vault until the CSR is signed.
     managed_key_ref: String,
-    managed_key_pem: String,
     key_algorithm: KeyAlgorithm,
     key_size: Option<u16>,
     key_curve: Option<KeyCurve>,
-    /// DNS records that were automatically created and need cleanup after issuance
-    dns_records_to_cleanup: Vec<(String, String)>, // (domain, record_name)
+    /// Solver answering each name's challenge, keyed as the name was
+    /// requested (`*.` for wildcard names)
+    solvers: HashMap<String, Box<dyn ChallengeSolver>>,
+    directory_url: String,
+    issuer_id: String,
+    /// Issuer environment, recorded with the names once the certificate is issued
+    environment: String,
+    /// Staging certificate being promoted, linked from the new record
+    promoted_from: Option<String>,
+    account_key_pem: Zeroizing<String>,
+    /// Root name of the chain to prefer when the CA offers alternates
+    preferred_chain: Option<String>,
+    ca_pins: Vec<CaPin>,
+    enforce_ca_pins: bool,
+    /// Furthest finalization stage reached; completion resumes from here
+    stage: FinalizationStage,
+    /// Start, or the last completion attempt that kept the session
+    last_active: DateTime<Utc>,
+    /// Keeps other sessions off these names until this one is dropped
+    _lock: DomainLock,
 }
 
 static SESSIONS: OnceLock<Mutex<HashMap<String, PendingIssuance>>> = OnceLock::new();
@@ -78,7 +113,130 @@ fn sessions() -> &'static Mutex<HashMap<String, PendingIssuance>> {
     SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
-/// Starts a managed-key ACME DNS-01 issuance and returns DNS instructions plus a request id.
+/// Domain sets of issuances that have been started but not yet completed.
+pub fn pending_issuance_domains() -> Vec<Vec<String>> {
+    match sessions().lock() {
+        Ok(guard) => guard.values().map(|pending| pending.domains.clone()).collect(),
+        Err(err) => {
+            log::warn!("[issuance] pending sessions unavailable: {err}");
+            Vec::new()
+        }
+    }
+}
+
+/// Whether `request_id` was started and is still waiting to be completed.
+pub fn is_issuance_pending(request_id: &str) -> bool {
+    match sessions().lock() {
+        Ok(guard) => guard.contains_key(request_id),
+        Err(err) => {
+            log::warn!("[issuance] pending sessions unavailable: {err}");
+            false
+        }
+    }
+}
+
+/// Drops every pending issuance and removes its challenge responses. Used
+/// when the database is replaced underneath the sessions; returns how many
+/// were dropped.
+pub fn abandon_pending_issuances(dns_store: &DnsConfigStore, secrets: &SecretManager) -> usize {
+    let mut abandoned: Vec<PendingIssuance> = match sessions().lock() {
+        Ok(mut guard) => guard.drain().map(|(_, pending)| pending).collect(),
+        Err(err) => {
+            log::warn!("[issuance] pending sessions unavailable: {err}");
+            return 0;
+        }
+    };
+    let context = SolverContext::new(dns_store, secrets);
+    for pending in &mut abandoned {
+        log::info!("[issuance] abandoning pending issuance for {:?}", pending.domains);
+        cleanup_solvers(&mut pending.solvers, &context);
+    }
+    abandoned.len()
+}
+
+/// Drops sessions idle for longer than `idle_limit`: their challenge records
+/// are removed, the provisional managed key is deleted and the journal entry
+/// is cleared. Returns how many were dropped.
+pub fn collect_expired_issuances(
+    idle_limit: Duration,
+    dns_store: &DnsConfigStore,
+    secrets: &SecretManager,
+    jobs: &JobStore,
+) -> usize {
+    let cutoff = Utc::now() - idle_limit;
+    let mut expired = match sessions().lock() {
+        Ok(mut guard) => take_idle_sessions(&mut guard, cutoff, |pending| pending.last_active),
+        Err(err) => {
+            log::warn!("[issuance] pending sessions unavailable: {err}");
+            return 0;
+        }
+    };
+    let context = SolverContext::new(dns_store, secrets);
+    for (request_id, pending) in &mut expired {
+        log::warn!(
+            "[issuance] abandoning issuance {request_id} for {:?}, idle since {}",
+            pending.domains,
+            pending.last_active
+        );
+        cleanup_solvers(&mut pending.solvers, &context);
+        release_abandoned(
+            request_id,
+            &pending.managed_key_ref,
+            |key_ref| secrets.delete_secret(key_ref).map_err(anyhow::Error::from),
+            jobs,
+        );
+    }
+    expired.len()
+}
+
+/// Removes and returns the sessions last active before `cutoff`.
+fn take_idle_sessions<S>(
+    sessions: &mut HashMap<String, S>,
+    cutoff: DateTime<Utc>,
+    last_active: impl Fn(&S) -> DateTime<Utc>,
+) -> Vec<(String, S)> {
+    let ids: Vec<String> = sessions
+        .iter()
+        .filter(|(_, session)| last_active(session) < cutoff)
+        .map(|(id, _)| id.clone())
+        .collect();
+    ids.into_iter().filter_map(|id| sessions.remove_entry(&id)).collect()
+}
+
+/// Deletes the provisional managed key of an abandoned session and clears
+/// its journal entry. A key that cannot be deleted is logged, not raised.
+fn release_abandoned(
+    request_id: &str,
+    managed_key_ref: &str,
+    delete_key: impl FnOnce(&str) -> Result<()>,
+    jobs: &JobStore,
+) {
+    if let Err(err) = delete_key(managed_key_ref) {
+        log::warn!(
+            "[issuance] failed to delete managed key {managed_key_ref} of {request_id}: {err}"
+        );
+    }
+    jobs.finish_quietly(request_id);
+}
+
+/// Logs an issuance that cannot be resumed so periodic reports can count it.
+fn record_failure(inventory: &InventoryStore, domains: &[String], err: &anyhow::Error) {
+    if let Err(log_err) = inventory.record_issuance_failure(domains, &err.to_string()) {
+        log::warn!("[issuance] failed to record issuance failure: {log_err}");
+    }
+}
+
+/// Updates the issuer's ACME statistics; failures are logged, not raised.
+fn record_issuer_stat(issuer_id: &str, update: impl FnOnce(&str) -> Result<()>) {
+    if let Err(err) = update(issuer_id) {
+        log::warn!("[issuance] failed to update stats for issuer {issuer_id}: {err}");
+    }
+}
+
+/// Starts a managed-key ACME issuance and returns DNS instructions plus a request id.
+///
+/// `solvers` picks how each name's challenge is answered; names without an
+/// entry use dns-01 through their provider.
 #[allow(clippy::too_many_arguments)]
 pub fn start_managed_dns01(
     domains: Vec<String>,
@@ -86,15 +244,25 @@ pub fn start_managed_dns01(
     key_algorithm: Option<KeyAlgorithm>,
     key_size: Option<u16>,
     key_curve: Option<KeyCurve>,
+    promoted_from: Option<String>,
+    solvers: HashMap<String, ChallengeSolverConfig>,
     issuer_store: &IssuerConfigStore,
     dns_store: &DnsConfigStore,
     secrets: &SecretManager,
+    jobs: &JobStore,
 ) -> Result<(String, Vec<DnsRecordInstruction>)> {
     let normalized = acme_workflow::validate_and_normalize_domains(domains)?;
+    let mut configured = configured_solvers(solvers, &normalized)?;
+    let request_id = Uuid::new_v4().to_string();
+    // Held before any challenge record is written; released on every early return
+    let lock = domain_locks::acquire(&normalized, &request_id)?;
 
     let issuer = issuer_store
         .get(&issuer_id)?
         .ok_or_else(|| anyhow!("Issuer not found: {}", issuer_id))?;
+    if issuer.params().disabled {
+        return Err(anyhow!("issuer \"{}\" is disabled", issuer.label));
+    }
     if !issuer.tos_agreed {
         return Err(anyhow!(
             "Issuer requires Terms of Service acceptance before issuance"
@@ -112,49 +280,119 @@ pub fn start_managed_dns01(
     let account_key_pem = secrets
         .resolve_secret(&account_key_ref)
         .map_err(|e| anyhow!(e.to_string()))?;
-    let account_key_pem = String::from_utf8(account_key_pem)
-        .map_err(|_| anyhow!("Stored ACME account key is not valid UTF-8"))?;
+    let account_key_pem = Zeroizing::new(
+        String::from_utf8(account_key_pem)
+            .map_err(|_| anyhow!("Stored ACME account key is not valid UTF-8"))?,
+    );
 
     let (key_algorithm, key_size, key_curve) =
         acme_workflow::resolve_key_params(key_algorithm, key_size, key_curve)?;
 
-    let (_directory, account) =
-        acme_workflow::setup_acme_account(&issuer.directory_url, &contact_email, &account_key_pem)?;
-
-    let new_order = acme_workflow::create_acme_order(&account, &normalized)?;
+    artifacts::begin(&request_id, &normalized, &issuer.issuer_id, &issuer.directory_url);
+    let new_order = acme_workflow::setup_acme_account(
+        &issuer.directory_url,
+        &contact_email,
+        &account_key_pem,
+    )
+    .and_then(|(_directory, account)| acme_workflow::create_acme_order(&account, &normalized));
+    let new_order = match new_order {
+        Ok(order) => {
+            record_issuer_stat(&issuer.issuer_id, |id| issuer_store.record_order(id));
+            artifacts::record_order(&request_id, order.api_order());
+            order
+        }
+        Err(err) => {
+            artifacts::record_error(&request_id, "creating the order", &err);
+            let message = err.to_string();
+            record_issuer_stat(&issuer.issuer_id, |id| issuer_store.record_failure(id, &message));
+            return Err(err);
+        }
+    };
 
-    let (dns_records, _auths, dns_records_to_cleanup) =
-        acme_workflow::prepare_dns_challenges(&new_order, dns_store, secrets)?;
+    let context = SolverContext::new(dns_store, secrets);
+    let auths = new_order.authorizations().map_err(|e| anyhow!(e.to_string()))?;
+    artifacts::record_authorizations(&request_id, &auths);
+    let mut solvers = HashMap::new();
+    let mut dns_records = Vec::new();
+    let mut dns_records_to_cleanup = Vec::new();
+    for auth in &auths {
+        let name = solvers::auth_name(auth);
+        let presented = configured
+            .remove(&name)
+            .map_or_else(|| solvers::solver_for(None), Ok)
+            .and_then(|mut solver| {
+                let presented = solver.present(auth, &context)?;
+                Ok((solver, presented))
+            });
+        let (solver, presented) = match presented {
+            Ok(presented) => presented,
+            Err(err) => {
+                cleanup_solvers(&mut solvers, &context);
+                let err = err.context(format!("failed to answer the challenge for {name}"));
+                artifacts::record_error(&request_id, "presenting challenges", &err);
+                return Err(err);
+            }
+        };
+        dns_records.extend(presented.dns_record);
+        dns_records_to_cleanup.extend(presented.cleanup_record);
+        solvers.insert(name, solver);
+    }
+    if let Err(err) = context.write_queued_txt() {
+        cleanup_solvers(&mut solvers, &context);
+        let err = err.context("failed to write the challenge records");
+        artifacts::record_error(&request_id, "writing challenge records", &err);
+        return Err(err);
+    }
+    artifacts::record_challenge_records(&request_id, &dns_records);
 
     let primary = normalized
         .first()
         .cloned()
         .ok_or_else(|| anyhow!("primary domain missing"))?;
-    let key_pem_str =
-        acme_workflow::generate_private_key(&key_algorithm, key_size, key_curve.as_ref())?;
     let key_label = format!(
         "Managed {} key for {}",
         format_key_label(&key_algorithm, key_size, key_curve.as_ref()),
         primary
     );
     let managed_key = secrets
-        .create_secret(
-            SecretKind::ManagedPrivateKey,
-            key_label,
-            key_pem_str.clone(),
-        )
+        .create_managed_key(key_label, &key_algorithm, key_size, key_curve.as_ref())
         .map_err(|e| anyhow!(e.to_string()))?;
 
-    let request_id = Uuid::new_v4().to_string();
+    // Journaled so challenge records can be cleaned up if the app dies before completion
+    jobs.start(
+        &request_id,
+        JobKind::Issuance,
+        &serde_json::json!({
+            "domains": normalized,
+            "managed_key_ref": managed_key.id,
+            "dns_records": dns_records_to_cleanup
+                .iter()
+                .map(|(domain, record_name)| {
+                    serde_json::json!({ "domain": domain, "record_name": record_name })
+                })
+                .collect::<Vec<_>>(),
+        }),
+    )?;
+    let issuer_params = issuer.params();
     let pending = PendingIssuance {
         order: new_order,
         domains: normalized,
         managed_key_ref: managed_key.id.clone(),
-        managed_key_pem: key_pem_str,
         key_algorithm,
         key_size,
         key_curve,
-        dns_records_to_cleanup,
+        solvers,
+        directory_url: issuer.directory_url.clone(),
+        issuer_id: issuer.issuer_id.clone(),
+        environment: issuer.environment.clone(),
+        promoted_from,
+        account_key_pem,
+        preferred_chain: issuer_params.preferred_chain,
+        ca_pins: issuer_params.ca_pins,
+        enforce_ca_pins: issuer_params.enforce_ca_pins,
+        stage: FinalizationStage::CheckingDns,
+        last_active: Utc::now(),
+        _lock: lock,
     };
 
     sessions()
@@ -165,137 +403,204 @@ pub fn start_managed_dns01(
     Ok((request_id, dns_records))
 }
 
-/// Finalizes a pending issuance by validating DNS-01, finalizing the order, and persisting metadata.
+/// Finalizes a pending issuance by validating its challenges, finalizing the order, and
+/// persisting metadata.
+///
+/// Each stage is bounded by `settings`; when one runs out of time before the
+/// CSR is submitted, the session is kept so a later call resumes from that stage.
+#[allow(clippy::too_many_arguments)]
 pub fn complete_managed_dns01(
     request_id: &str,
     inventory: &InventoryStore,
+    issuer_store: &IssuerConfigStore,
     secrets: &SecretManager,
     dns_store: &DnsConfigStore,
+    jobs: &JobStore,
+    settings: PollSettings,
+    waits: &PropagationWaits,
+    resolvers: &ResolverSet,
+    report: &dyn Fn(IssuanceProgress),
 ) -> Result<CertificateRecord> {
-    let pending = sessions()
+    let mut pending = sessions()
         .lock()
         .map_err(|e| anyhow!(e.to_string()))?
         .remove(request_id)
         .ok_or_else(|| anyhow!("Issuance session not found or already finalized"))?;
 
+    let poller = OrderPoller::new(request_id, settings, report);
+    let issued = drive_order(&mut pending, secrets, waits, resolvers, &poller);
+    let (certificate, certificate_url) = match issued {
+        Ok(issued) => issued,
+        Err(err) => {
+            artifacts::record_stage_error(request_id, pending.stage, &err);
+            record_order_state(request_id, &pending.order);
+            if pending.stage < FinalizationStage::Finalizing
+                && !pending.order.api_order().is_status_invalid()
+            {
+                log::info!(
+                    "[issuance] keeping session {request_id} at {:?} for a later attempt",
+                    pending.stage
+                );
+                pending.last_active = Utc::now();
+                sessions()
+                    .lock()
+                    .map_err(|e| anyhow!(e.to_string()))?
+                    .insert(request_id.to_string(), pending);
+            } else {
+                cleanup_solvers(&mut pending.solvers, &SolverContext::new(dns_store, secrets));
+                record_failure(inventory, &pending.domains, &err);
+                let message = err.to_string();
+                record_issuer_stat(&pending.issuer_id, |id| {
+                    issuer_store.record_failure(id, &message)
+                });
+                jobs.finish_quietly(request_id);
+            }
+            return Err(err);
+        }
+    };
+    poller.report(FinalizationStage::Completed, 1, "Certificate issued");
+    // Challenge responses are no longer needed once the certificate is
+    // downloaded; failures are logged but don't fail the issuance
+    cleanup_solvers(&mut pending.solvers, &SolverContext::new(dns_store, secrets));
+
     let PendingIssuance {
-        mut order,
         domains,
         managed_key_ref,
-        managed_key_pem,
         key_algorithm,
         key_size,
         key_curve,
-        dns_records_to_cleanup,
+        directory_url,
+        issuer_id,
+        environment,
+        promoted_from,
+        account_key_pem,
+        preferred_chain,
+        ca_pins,
+        enforce_ca_pins,
+        ..
     } = pending;
 
-    let auths = order.authorizations().map_err(|e| anyhow!(e.to_string()))?;
-    for auth in &auths {
-        let dns = auth.dns_challenge();
-        let proof = dns.dns_proof();
-        let domain = auth.domain_name().to_string();
-
-        // Poll for DNS propagation with retries using unified retry logic
-        let timeout = Duration::from_secs(30);
-        let interval = Duration::from_secs(2);
-        let record_name = record_name(&domain);
-
-        let propagation_result = poll_dns_propagation(&record_name, &proof, timeout, interval)?;
+    let chain = select_certificate_chain(
+        certificate.certificate().to_string(),
+        certificate_url.as_deref(),
+        &directory_url,
+        &account_key_pem,
+        preferred_chain.as_deref(),
+    );
 
-        // Check final state after polling
-        match propagation_result.state {
-            PropagationState::Found => {
-                // Already handled in loop, continue to next domain
-            }
-            PropagationState::NxDomain => {
-                return Err(anyhow!(
-                    "No TXT record found at {} after {}s. Please ensure the DNS record is created and propagated.",
-                    record_name,
-                    timeout.as_secs()
-                ));
-            }
-            PropagationState::Pending => {
-                return Err(anyhow!(
-                    "TXT record not found at {} after {}s. Please wait for DNS propagation and try again.",
-                    record_name,
-                    timeout.as_secs()
-                ));
-            }
-            PropagationState::WrongContent => {
-                // Should have been caught in loop, but handle just in case
-                return Err(anyhow!(
-                    "TXT record at {} has wrong value. Expected: {}. Observed: {:?}",
-                    record_name,
-                    proof,
-                    propagation_result.observed_values
-                ));
-            }
-            PropagationState::Error => {
-                return Err(anyhow!(
-                    "Failed to check DNS propagation for {}: {}",
-                    record_name,
-                    propagation_result
-                        .reason
-                        .unwrap_or_else(|| "Unknown error".to_string())
-                ));
-            }
+    let pin_check = ca_pinning::check_chain(&chain.pem, &ca_pins)?;
+    if let PinCheck::Mismatch { observed } = &pin_check {
+        log::error!(
+            "[issuance] certificate for {:?} was issued by an unpinned CA: {}",
+            domains,
+            observed.join("; ")
+        );
+        if enforce_ca_pins {
+            let err = anyhow!(
+                "Issued certificate chain does not match the issuer's pinned CA (got {}). \
+                 The certificate was not saved.",
+                observed.join("; ")
+            );
+            artifacts::record_error(request_id, "checking CA pins", &err);
+            record_failure(inventory, &domains, &err);
+            let message = err.to_string();
+            record_issuer_stat(&issuer_id, |id| issuer_store.record_failure(id, &message));
+            jobs.finish_quietly(request_id);
+            return Err(err);
         }
     }
 
-    // All DNS records are present, proceed with ACME validation
-    for auth in auths {
-        let dns = auth.dns_challenge();
-        dns.validate(2000).map_err(|e| anyhow!(e.to_string()))?;
-    }
-
-    let csr_order = loop {
-        if let Some(csr) = order.confirm_validations() {
-            break csr;
-        }
-        order.refresh().map_err(|e| anyhow!(e.to_string()))?;
-    };
-
-    let cert_order = csr_order
-        .finalize(&managed_key_pem, 5000)
-        .map_err(|e| anyhow!(e.to_string()))?;
-    let certificate = cert_order
-        .download_and_save_cert()
-        .map_err(|e| anyhow!(e.to_string()))?;
-
-    let record = build_record(
-        &certificate,
-        domains,
-        managed_key_ref.clone(),
+    let mut record = build_record(
+        chain,
+        domains.clone(),
+        managed_key_ref,
         key_algorithm,
         key_size,
         key_curve,
     )?;
+    if matches!(pin_check, PinCheck::Mismatch { .. }) {
+        record.tags.push(CA_PIN_MISMATCH_TAG.to_string());
+    }
+    record_issuer_stat(&issuer_id, |id| issuer_store.record_success(id));
+    record.issuer_id = Some(issuer_id);
+    record.promoted_from = promoted_from;
     inventory.insert_certificate(&record)?;
+    if let Err(err) = inventory.record_issued_names(&domains, &environment) {
+        log::warn!("[issuance] failed to record issued names: {err}");
+    }
 
-    // Best-effort check the key still resolves
-    if let Err(err) = secrets.resolve_secret(&managed_key_ref) {
-        log::warn!(
-            "[issuance] managed key ref {} failed to resolve after issuance: {}",
-            managed_key_ref,
-            err
-        );
+    jobs.finish_quietly(request_id);
+    artifacts::discard(request_id);
+
+    Ok(record)
+}
+
+/// Records the order and its authorizations as the CA now reports them,
+/// including the CA's reason for any failed challenge.
+fn record_order_state(request_id: &str, order: &NewOrder<EphemeralPersist>) {
+    artifacts::record_order(request_id, order.api_order());
+    match order.authorizations() {
+        Ok(auths) => artifacts::record_authorizations(request_id, &auths),
+        Err(err) => log::warn!("[issuance] failed to fetch authorizations of {request_id}: {err}"),
+    }
+}
+
+/// Parses the per-name solver choices of a request, keyed by normalized
+/// name. Every name must be part of the order.
+fn configured_solvers(
+    configs: HashMap<String, ChallengeSolverConfig>,
+    domains: &[String],
+) -> Result<HashMap<String, Box<dyn ChallengeSolver>>> {
+    let mut configured = HashMap::new();
+    for (name, config) in configs {
+        let normalized = normalize_domain_for_storage(&name)
+            .map_err(|err| anyhow!("Invalid domain name \"{name}\": {err}"))?;
+        if !domains.contains(&normalized) {
+            return Err(anyhow!("A challenge solver was chosen for {name}, which is not requested"));
+        }
+        let solver = solvers::solver_for(Some(&config))
+            .map_err(|err| err.context(format!("invalid challenge solver for {name}")))?;
+        configured.insert(normalized, solver);
+    }
+    Ok(configured)
+}
+
+/// Removes every solver's challenge response. Failures are logged, not raised.
+fn cleanup_solvers(
+    solvers: &mut HashMap<String, Box<dyn ChallengeSolver>>,
+    context: &SolverContext,
+) {
+    for solver in solvers.values_mut() {
+        solver.cleanup(context);
     }
+}
 
-    // Clean up DNS challenge records after successful issuance
-    for (domain, record_name) in dns_records_to_cleanup {
-        match dns_store.resolve_provider_for_domain(&domain) {
+/// Removes automatically created challenge records, returning a description
+/// of each one that could not be removed. Failures are logged, not raised.
+pub fn cleanup_challenge_records(
+    records: &[(String, String)], // (domain, record_name)
+    dns_store: &DnsConfigStore,
+    secrets: &SecretManager,
+) -> Vec<String> {
+    let mut failures = Vec::new();
+    // One adapter per provider, so zone lookups are shared between records
+    let mut adapters: HashMap<String, Box<dyn DnsProviderAdapter>> = HashMap::new();
+    for (domain, record_name) in records {
+        match resolve_provider_by_zone_access(dns_store, secrets, domain) {
             Ok(resolution) => {
                 if let Some(provider) = resolution.provider.as_ref()
                     && resolution.ambiguous.len() <= 1 {
-                    let provider_adapter = adapter_for_provider(provider, secrets);
-                    if let Err(err) = provider_adapter.cleanup_txt(&record_name) {
-                        // Log but don't fail issuance if cleanup fails
+                    let provider_adapter = adapters.entry(provider.id.clone()).or_insert_with(|| {
+                        cached_adapter_for_provider(provider, secrets, dns_store)
+                    });
+                    if let Err(err) = provider_adapter.cleanup_txt(record_name) {
                         log::warn!(
                             "[dns] Failed to cleanup TXT record {} for domain {}: {}",
                             record_name,
                             domain,
                             err
                         );
+                        failures.push(format!("{record_name}: {err}"));
                     } else {
                         log::debug!(
                             "[dns] Successfully cleaned up TXT record {} for domain {}",
@@ -311,22 +616,136 @@ pub fn complete_managed_dns01(
                     domain,
                     err
                 );
+                failures.push(format!("{record_name}: {err}"));
             }
         }
     }
+    failures
+}
+
+/// Runs the order from its current stage up to a downloaded certificate,
+/// recording each stage on `pending` as it is reached.
+fn drive_order(
+    pending: &mut PendingIssuance,
+    secrets: &SecretManager,
+    waits: &PropagationWaits,
+    resolvers: &ResolverSet,
+    poller: &OrderPoller,
+) -> Result<(acme_lib::Certificate, Option<String>)> {
+    if pending.stage == FinalizationStage::CheckingDns {
+        poller.report(FinalizationStage::CheckingDns, 1, "Checking challenge responses");
+        let auths = pending.order.authorizations().map_err(|e| anyhow!(e.to_string()))?;
+        for auth in &auths {
+            solver_for_auth(&pending.solvers, auth)?.await_ready(auth, waits, resolvers)?;
+        }
+        pending.stage = FinalizationStage::ValidatingChallenges;
+    }
 
-    Ok(record)
+    if pending.stage == FinalizationStage::ValidatingChallenges {
+        // All responses are in place, proceed with ACME validation
+        let auths = pending.order.authorizations().map_err(|e| anyhow!(e.to_string()))?;
+        for auth in &auths {
+            solver_for_auth(&pending.solvers, auth)?.validate(auth, poller)?;
+        }
+        pending.stage = FinalizationStage::AwaitingOrder;
+    }
+
+    let csr_order = acme_workflow::await_order_ready(&mut pending.order, poller)?;
+    pending.stage = FinalizationStage::Finalizing;
+
+    let key_pem = secrets
+        .with_secret(&pending.managed_key_ref, |key_pem| {
+            std::str::from_utf8(key_pem)
+                .map(|pem| Zeroizing::new(pem.to_string()))
+                .map_err(|_| anyhow!("Managed key material was not valid UTF-8"))
+        })
+        .map_err(|e| anyhow!(e.to_string()))??;
+    acme_workflow::finalize_acme_certificate(csr_order, key_pem, poller)
+}
+
+fn solver_for_auth<'a>(
+    solvers: &'a HashMap<String, Box<dyn ChallengeSolver>>,
+    auth: &Auth<EphemeralPersist>,
+) -> Result<&'a dyn ChallengeSolver> {
+    let name = solvers::auth_name(auth);
+    solvers
+        .get(&name)
+        .map(|solver| solver.as_ref())
+        .ok_or_else(|| anyhow!("No challenge solver was set up for {name}"))
+}
+
+/// Chooses between the default chain and any alternates offered by the CA.
+/// Alternates are only fetched when the issuer has a preferred chain set;
+/// failures fall back to the default chain so issuance still succeeds.
+fn select_certificate_chain(
+    default_pem: String,
+    certificate_url: Option<&str>,
+    directory_url: &str,
+    account_key_pem: &str,
+    preferred_chain: Option<&str>,
+) -> CertificateChain {
+    let default_chain = CertificateChain::from_pem(default_pem);
+    let Some(preferred) = preferred_chain else {
+        return default_chain;
+    };
+    if default_chain
+        .name
+        .as_deref()
+        .is_some_and(|name| name.eq_ignore_ascii_case(preferred))
+    {
+        return default_chain;
+    }
+    let Some(certificate_url) = certificate_url else {
+        log::warn!("[issuance] order has no certificate URL; using default chain");
+        return default_chain;
+    };
+
+    let mut candidates = vec![default_chain.clone()];
+    match chains::fetch_alternate_chains(directory_url, account_key_pem, certificate_url) {
+        Ok(alternates) => {
+            log::info!(
+                "[issuance] CA offered {} alternate chain(s): {:?}",
+                alternates.len(),
+                alternates.iter().map(|c| c.name.clone()).collect::<Vec<_>>()
+            );
+            candidates.extend(alternates);
+        }
+        Err(err) => log::warn!("[issuance] failed to fetch alternate chains: {err}"),
+    }
+
+    let selected = chains::select_chain(candidates, Some(preferred)).unwrap_or(default_chain);
+    if !selected
+        .name
+        .as_deref()
+        .is_some_and(|name| name.eq_ignore_ascii_case(preferred))
+    {
+        log::warn!(
+            "[issuance] preferred chain \"{}\" not offered; using {:?}",
+            preferred,
+            selected.name
+        );
+    }
+    selected
 }
 
 fn build_record(
-    certificate: &Certificate,
+    chain: CertificateChain,
     domains: Vec<String>,
     managed_key_ref: String,
     key_algorithm: KeyAlgorithm,
     key_size: Option<u16>,
     key_curve: Option<KeyCurve>,
 ) -> Result<CertificateRecord> {
-    let pem = certificate.certificate();
+    let (pem, chain_repairs) = match repair_chain(&chain.pem) {
+        Ok(repaired) => (repaired.pem, repaired.repairs),
+        Err(err) => {
+            log::warn!("[issuance] keeping certificate chain as downloaded: {err}");
+            (chain.pem, Vec::new())
+        }
+    };
+    if !chain_repairs.is_empty() {
+        log::info!("[issuance] repaired certificate chain: {chain_repairs:?}");
+    }
     let (_, pem_block) = parse_x509_pem(pem.as_bytes())
         .map_err(|e| anyhow!("failed to parse issued certificate PEM: {e}"))?;
     let cert = pem_block.parse_x509().map_err(|e| anyhow!(e.to_string()))?;
@@ -364,11 +783,17 @@ fn build_record(
         source: CertificateSource::Managed,
         domain_roots: domains.iter().map(|d| root_from_hostname(d)).collect(),
         tags: vec![],
-        chain_pem: Some(pem.to_string()),
+        chain_pem: Some(pem),
+        chain_name: chain.name,
         managed_key_ref: Some(managed_key_ref),
         key_algorithm: Some(key_algorithm),
         key_size,
         key_curve,
+        status: CertificateStatus::Active,
+        issuer_id: None,
+        promoted_from: None,
+        chain_repairs,
+        name_forms: Vec::new(),
     })
 }
 
@@ -392,8 +817,17 @@ fn format_key_label(
 
 #[cfg(test)]
 mod tests {
-    use super::{KeyAlgorithm, KeyCurve};
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::fs;
+
+    use chrono::{Duration, Utc};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::{release_abandoned, take_idle_sessions, JobKind, JobStore, KeyAlgorithm, KeyCurve};
     use crate::issuance::acme_workflow;
+    use crate::storage::db::Db;
 
     #[test]
     fn defaults_to_rsa_2048_when_missing() {
@@ -431,9 +865,46 @@ mod tests {
             .unwrap_err();
         assert!(err.to_string().contains("Unsupported RSA key size"));
     }
+
+    #[test]
+    fn idle_sessions_are_dropped_with_their_key_and_job() -> anyhow::Result<()> {
+        let path = std::env::temp_dir()
+            .join(format!("sslboard_session_gc_test_{}", Uuid::new_v4().as_simple()));
+        fs::create_dir_all(&path)?;
+        let jobs = JobStore::initialize(Db::initialize_with_path(&path)?)?;
+        jobs.start("req-stale", JobKind::Issuance, &json!({}))?;
+        jobs.start("req-fresh", JobKind::Issuance, &json!({}))?;
+
+        let now = Utc::now();
+        let mut sessions = HashMap::from([
+            ("req-stale".to_string(), (now - Duration::hours(3), "sec_stale_key".to_string())),
+            ("req-fresh".to_string(), (now - Duration::minutes(5), "sec_fresh_key".to_string())),
+        ]);
+        let expired = take_idle_sessions(&mut sessions, now - Duration::hours(1), |s| s.0);
+        let deleted = RefCell::new(Vec::new());
+        for (request_id, (_, key_ref)) in &expired {
+            release_abandoned(
+                request_id,
+                key_ref,
+                |key_ref| {
+                    deleted.borrow_mut().push(key_ref.to_string());
+                    Ok(())
+                },
+                &jobs,
+            );
+        }
+
+        assert_eq!(expired.len(), 1);
+        assert!(sessions.contains_key("req-fresh") && !sessions.contains_key("req-stale"));
+        assert_eq!(deleted.into_inner(), vec!["sec_stale_key".to_string()]);
+        jobs.mark_interrupted()?;
+        let left: Vec<String> = jobs.list_interrupted()?.into_iter().map(|job| job.id).collect();
+        assert_eq!(left, vec!["req-fresh".to_string()]);
+        Ok(())
+    }
 }
 
-fn root_from_hostname(hostname: &str) -> String {
+pub(crate) fn root_from_hostname(hostname: &str) -> String {
     let parts: Vec<&str> = hostname.trim_end_matches('.').split('.').collect();
     if parts.len() >= 2 {
         format!("{}.{}", parts[parts.len() - 2], parts[parts.len() - 1])
@@ -0,0 +1,280 @@
+//! Picks an issuer when issuance starts without an explicit one.
+//!
+//! The smart policy sends test TLDs and names that never issued before to a
+//! staging issuer, and names with an earlier successful issuance to
+//! production, so a first attempt never spends production rate limits.
+//! Fixed environments and the older "selected issuer" behaviour remain
+//! available as policies.
+
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Result};
+
+use crate::core::mappers::{environment_from_str, issuer_policy_from_str};
+use crate::core::types::{
+    CertificateRecord, IssuerSelectionPolicy, IssuerSelectionReason, IssuerSuggestion,
+};
+use crate::issuance::acme_workflow::validate_and_normalize_domains;
+use crate::storage::{
+    inventory::InventoryStore,
+    issuer::{IssuerConfigRecord, IssuerConfigStore},
+    preferences::PreferencesStore,
+};
+
+pub const ISSUER_POLICY_PREFERENCE: &str = "issuer_selection_policy";
+
+/// Reserved and private-use suffixes that public CAs can never validate.
+const TEST_SUFFIXES: &[&str] = &[
+    "test",
+    "example",
+    "invalid",
+    "localhost",
+    "local",
+    "localdomain",
+    "internal",
+    "lan",
+    "home.arpa",
+];
+
+const STAGING: &str = "staging";
+const PRODUCTION: &str = "production";
+
+pub fn policy_from_preferences(prefs: &PreferencesStore) -> IssuerSelectionPolicy {
+    match prefs.get(ISSUER_POLICY_PREFERENCE) {
+        Ok(Some(pref)) => issuer_policy_from_str(&pref.value),
+        Ok(None) => IssuerSelectionPolicy::default(),
+        Err(err) => {
+            log::warn!("[issuance] failed to read {ISSUER_POLICY_PREFERENCE} preference: {err}");
+            IssuerSelectionPolicy::default()
+        }
+    }
+}
+
+pub fn is_test_domain(domain: &str) -> bool {
+    let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+    TEST_SUFFIXES
+        .iter()
+        .any(|suffix| domain == *suffix || domain.ends_with(&format!(".{suffix}")))
+}
+
+/// Suggests an issuer for `domains` using the configured policy and the
+/// names recorded by earlier issuances.
+pub fn suggest_issuer_for_domains(
+    domains: Vec<String>,
+    issuer_store: &IssuerConfigStore,
+    inventory: &InventoryStore,
+    prefs: &PreferencesStore,
+) -> Result<IssuerSuggestion> {
+    let domains = validate_and_normalize_domains(domains)?;
+    let issued = inventory.issued_names(&domains)?;
+    suggest_issuer(
+        &domains,
+        &issued,
+        policy_from_preferences(prefs),
+        &issuer_store.list()?,
+    )
+}
+
+/// Applies `policy` to `domains`; `issued` holds the names that issued
+/// before in any environment.
+pub fn suggest_issuer(
+    domains: &[String],
+    issued: &HashSet<String>,
+    policy: IssuerSelectionPolicy,
+    issuers: &[IssuerConfigRecord],
+) -> Result<IssuerSuggestion> {
+    let enabled: Vec<&IssuerConfigRecord> =
+        issuers.iter().filter(|issuer| !issuer.params().disabled).collect();
+    let first = preferred(&enabled).ok_or_else(|| anyhow!("No enabled issuer is configured"))?;
+
+    let (environment, reason) = match policy {
+        IssuerSelectionPolicy::Selected => {
+            let selected = enabled.iter().find(|issuer| issuer.is_selected);
+            return Ok(match selected {
+                Some(issuer) => suggestion(issuer, policy, IssuerSelectionReason::SelectedIssuer),
+                None => suggestion(first, policy, IssuerSelectionReason::Fallback),
+            });
+        }
+        IssuerSelectionPolicy::Staging => (STAGING, IssuerSelectionReason::Policy),
+        IssuerSelectionPolicy::Production => (PRODUCTION, IssuerSelectionReason::Policy),
+        IssuerSelectionPolicy::Smart => {
+            if domains.iter().any(|domain| is_test_domain(domain)) {
+                (STAGING, IssuerSelectionReason::TestDomain)
+            } else if !domains.is_empty() && domains.iter().all(|domain| issued.contains(domain)) {
+                (PRODUCTION, IssuerSelectionReason::PreviouslyIssued)
+            } else {
+                (STAGING, IssuerSelectionReason::NewDomain)
+            }
+        }
+    };
+
+    let in_environment: Vec<&IssuerConfigRecord> = enabled
+        .iter()
+        .copied()
+        .filter(|issuer| issuer.environment == environment)
+        .collect();
+    Ok(match preferred(&in_environment) {
+        Some(issuer) => suggestion(issuer, policy, reason),
+        None => suggestion(first, policy, IssuerSelectionReason::Fallback),
+    })
+}
+
+/// Enabled production issuer to promote staging certificates with.
+pub fn production_issuer(issuers: &[IssuerConfigRecord]) -> Option<&IssuerConfigRecord> {
+    let candidates: Vec<&IssuerConfigRecord> = issuers
+        .iter()
+        .filter(|issuer| is_enabled_production(issuer))
+        .collect();
+    preferred(&candidates)
+}
+
+/// Issuer a promotion runs with: `issuer_id` when given, which has to be an
+/// enabled production issuer, otherwise [`production_issuer`].
+pub fn promotion_issuer<'a>(
+    issuers: &'a [IssuerConfigRecord],
+    issuer_id: Option<&str>,
+) -> Result<&'a IssuerConfigRecord> {
+    match issuer_id {
+        Some(issuer_id) => issuers
+            .iter()
+            .find(|issuer| issuer.issuer_id == issuer_id)
+            .filter(|issuer| is_enabled_production(issuer))
+            .ok_or_else(|| anyhow!("Issuer {issuer_id} is not an enabled production issuer")),
+        None => production_issuer(issuers)
+            .ok_or_else(|| anyhow!("No enabled production issuer is configured")),
+    }
+}
+
+fn is_enabled_production(issuer: &IssuerConfigRecord) -> bool {
+    issuer.environment == PRODUCTION && !issuer.params().disabled
+}
+
+/// Whether `record` came from a staging issuer. Certificates issued before
+/// the issuer was recorded fall back to the "(STAGING)" prefix that Let's
+/// Encrypt staging puts in its intermediate names.
+pub fn is_staging_certificate(record: &CertificateRecord, issuers: &[IssuerConfigRecord]) -> bool {
+    match record.issuer_id.as_deref() {
+        Some(issuer_id) => issuers
+            .iter()
+            .any(|issuer| issuer.issuer_id == issuer_id && issuer.environment == STAGING),
+        None => record.issuer.to_ascii_uppercase().contains("(STAGING)"),
+    }
+}
+
+/// Issuers with a ready ACME account first, then the selected one.
+fn preferred<'a>(issuers: &[&'a IssuerConfigRecord]) -> Option<&'a IssuerConfigRecord> {
+    issuers.iter().copied().min_by_key(|issuer| {
+        let ready =
+            issuer.tos_agreed && issuer.contact_email.is_some() && issuer.account_key_ref.is_some();
+        (!ready, !issuer.is_selected)
+    })
+}
+
+fn suggestion(
+    issuer: &IssuerConfigRecord,
+    policy: IssuerSelectionPolicy,
+    reason: IssuerSelectionReason,
+) -> IssuerSuggestion {
+    IssuerSuggestion {
+        issuer_id: issuer.issuer_id.clone(),
+        environment: environment_from_str(&issuer.environment),
+        policy,
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn issuer(id: &str, environment: &str, selected: bool) -> IssuerConfigRecord {
+        IssuerConfigRecord {
+            issuer_id: id.to_string(),
+            label: id.to_string(),
+            directory_url: format!("https://{id}.example.org/directory"),
+            environment: environment.to_string(),
+            issuer_type: "acme".to_string(),
+            params_json: "{}".to_string(),
+            contact_email: Some("ops@example.com".to_string()),
+            account_key_ref: Some(format!("key_{id}")),
+            tos_agreed: true,
+            is_selected: selected,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn smart_policy_follows_issuance_history() {
+        let issuers = vec![issuer("prod", PRODUCTION, true), issuer("stage", STAGING, false)];
+        let domains = vec!["example.com".to_string(), "www.example.com".to_string()];
+        let smart = IssuerSelectionPolicy::Smart;
+
+        let fresh = suggest_issuer(&domains, &HashSet::new(), smart, &issuers).unwrap();
+        assert_eq!(fresh.issuer_id, "stage");
+        assert_eq!(fresh.reason, IssuerSelectionReason::NewDomain);
+
+        let partly: HashSet<String> = ["example.com".to_string()].into();
+        let partial = suggest_issuer(&domains, &partly, smart, &issuers).unwrap();
+        assert_eq!(partial.issuer_id, "stage");
+
+        let issued: HashSet<String> = domains.iter().cloned().collect();
+        let proven = suggest_issuer(&domains, &issued, smart, &issuers).unwrap();
+        assert_eq!(proven.issuer_id, "prod");
+        assert_eq!(proven.reason, IssuerSelectionReason::PreviouslyIssued);
+
+        let lab = vec!["app.internal".to_string()];
+        let lab_issued: HashSet<String> = lab.iter().cloned().collect();
+        let test = suggest_issuer(&lab, &lab_issued, smart, &issuers).unwrap();
+        assert_eq!(test.reason, IssuerSelectionReason::TestDomain);
+
+        let selected = IssuerSelectionPolicy::Selected;
+        let legacy = suggest_issuer(&domains, &HashSet::new(), selected, &issuers).unwrap();
+        assert_eq!(legacy.issuer_id, "prod");
+
+        let only_prod = vec![issuer("prod", PRODUCTION, false)];
+        let fallback = suggest_issuer(&domains, &HashSet::new(), smart, &only_prod).unwrap();
+        assert_eq!(fallback.reason, IssuerSelectionReason::Fallback);
+        assert!(suggest_issuer(&domains, &HashSet::new(), smart, &[]).is_err());
+    }
+
+    #[test]
+    fn promotion_uses_enabled_production_issuers() {
+        let mut disabled = issuer("prod_off", PRODUCTION, true);
+        disabled.params_json = r#"{"disabled":true}"#.to_string();
+        let mut unready = issuer("prod_new", PRODUCTION, false);
+        unready.account_key_ref = None;
+        let issuers = vec![
+            issuer("stage", STAGING, false),
+            disabled,
+            unready,
+            issuer("prod", PRODUCTION, false),
+        ];
+
+        assert_eq!(production_issuer(&issuers).unwrap().issuer_id, "prod");
+        assert_eq!(promotion_issuer(&issuers, None).unwrap().issuer_id, "prod");
+        assert_eq!(promotion_issuer(&issuers, Some("prod_new")).unwrap().issuer_id, "prod_new");
+        assert!(promotion_issuer(&issuers, Some("prod_off")).is_err());
+        assert!(promotion_issuer(&issuers, Some("stage")).is_err());
+        assert!(promotion_issuer(&issuers, Some("missing")).is_err());
+        assert!(production_issuer(&issuers[..2]).is_none());
+        assert!(promotion_issuer(&issuers[..2], None).is_err());
+    }
+
+    #[test]
+    fn staging_certificates_are_told_by_issuer_or_ca_name() {
+        let issuers = vec![issuer("stage", STAGING, false), issuer("prod", PRODUCTION, false)];
+        let certificate = |issuer_id: Option<&str>, ca: &str| CertificateRecord {
+            issuer: ca.to_string(),
+            issuer_id: issuer_id.map(str::to_string),
+            ..CertificateRecord::test_record("cert", &["example.com"])
+        };
+
+        assert!(is_staging_certificate(&certificate(Some("stage"), "R11"), &issuers));
+        assert!(!is_staging_certificate(&certificate(Some("prod"), "(STAGING) Ersatz"), &issuers));
+        assert!(!is_staging_certificate(&certificate(Some("deleted"), "R11"), &issuers));
+        assert!(is_staging_certificate(&certificate(None, "(STAGING) Pretend Pear X1"), &issuers));
+        assert!(!is_staging_certificate(&certificate(None, "R11"), &issuers));
+    }
+}
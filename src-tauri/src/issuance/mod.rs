@@ -1,5 +1,25 @@
 pub mod acme;
 pub mod acme_workflow;
+pub mod artifacts;
+pub mod authoritative_dns;
+pub mod auto_complete;
+pub mod ca_pinning;
+pub mod certificate_import;
+pub mod certificate_text;
+pub mod chain_repair;
+pub mod chains;
+pub mod clock_skew;
 pub mod dns;
 pub mod dns_providers;
+pub mod dns_wire;
+pub mod domain_locks;
 pub mod flow;
+pub mod issuer_selection;
+pub mod order_polling;
+pub mod preflight;
+pub mod propagation_cache;
+pub mod propagation_presets;
+pub mod propagation_resolvers;
+pub mod propagation_watch;
+pub mod session_gc;
+pub mod solvers;
@@ -0,0 +1,248 @@
+//! Bounded polling for ACME order finalization.
+//!
+//! acme-lib blocks until the CA moves an authorization or order out of its
+//! pending state and never gives up on its own. Every step of finalization
+//! therefore runs against one deadline: status checks back off between
+//! attempts, calls that block inside acme-lib run on a helper thread, and a
+//! step that runs out of time fails with [`IssuanceTimeout`] naming the stage
+//! it reached so the session can be resumed later. Each attempt is reported
+//! through a callback that the command forwards as an `issuance-progress` event.
+
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+use crate::core::errors::IssuanceTimeout;
+use crate::core::types::{FinalizationStage, IssuanceProgress};
+use crate::storage::preferences::PreferencesStore;
+
+pub const ISSUANCE_PROGRESS_EVENT: &str = "issuance-progress";
+pub const POLL_INTERVAL_PREFERENCE: &str = "acme_poll_interval_secs";
+pub const POLL_TIMEOUT_PREFERENCE: &str = "acme_poll_timeout_secs";
+
+const DEFAULT_INTERVAL_SECS: u64 = 2;
+const DEFAULT_TIMEOUT_SECS: u64 = 180;
+const MAX_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollSettings {
+    /// Delay before the first re-check; doubles up to `max_interval`
+    pub interval: Duration,
+    pub max_interval: Duration,
+    /// Budget for one completion attempt, across all stages
+    pub timeout: Duration,
+}
+
+impl Default for PollSettings {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(DEFAULT_INTERVAL_SECS),
+            max_interval: MAX_INTERVAL,
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+        }
+    }
+}
+
+impl PollSettings {
+    /// Reads the polling preferences, clamping them to sane bounds.
+    pub fn from_preferences(prefs: &PreferencesStore) -> Self {
+        let read = |name: &str, default: u64, min: u64, max: u64| match prefs.get(name) {
+            Ok(Some(pref)) => match pref.value.trim().parse::<u64>() {
+                Ok(value) => value.clamp(min, max),
+                Err(_) => {
+                    log::warn!("[issuance] ignoring invalid {name} preference: {}", pref.value);
+                    default
+                }
+            },
+            Ok(None) => default,
+            Err(err) => {
+                log::warn!("[issuance] failed to read {name} preference: {err}");
+                default
+            }
+        };
+        let interval = read(POLL_INTERVAL_PREFERENCE, DEFAULT_INTERVAL_SECS, 1, 30);
+        let timeout = read(POLL_TIMEOUT_PREFERENCE, DEFAULT_TIMEOUT_SECS, 30, 1800);
+        Self {
+            interval: Duration::from_secs(interval),
+            max_interval: MAX_INTERVAL.max(Duration::from_secs(interval)),
+            timeout: Duration::from_secs(timeout),
+        }
+    }
+}
+
+/// Tracks the deadline of one completion attempt and reports progress.
+pub struct OrderPoller<'a> {
+    request_id: &'a str,
+    settings: PollSettings,
+    started: Instant,
+    report: &'a dyn Fn(IssuanceProgress),
+}
+
+impl<'a> OrderPoller<'a> {
+    pub fn new(
+        request_id: &'a str,
+        settings: PollSettings,
+        report: &'a dyn Fn(IssuanceProgress),
+    ) -> Self {
+        Self {
+            request_id,
+            settings,
+            started: Instant::now(),
+            report,
+        }
+    }
+
+    pub fn settings(&self) -> PollSettings {
+        self.settings
+    }
+
+    pub fn report(&self, stage: FinalizationStage, attempt: u32, message: impl Into<String>) {
+        (self.report)(IssuanceProgress {
+            request_id: self.request_id.to_string(),
+            stage,
+            attempt,
+            elapsed_secs: self.started.elapsed().as_secs(),
+            message: message.into(),
+        });
+    }
+
+    fn remaining(&self) -> Duration {
+        self.settings.timeout.saturating_sub(self.started.elapsed())
+    }
+
+    /// The error for a stage that ran out of time.
+    pub fn timed_out(&self, stage: FinalizationStage, resumable: bool) -> anyhow::Error {
+        let hint = if resumable {
+            "Try completing the issuance again to resume."
+        } else {
+            "Start the issuance again."
+        };
+        IssuanceTimeout {
+            message: format!(
+                "The CA did not finish {} within {}s. {hint}",
+                describe(stage),
+                self.settings.timeout.as_secs()
+            ),
+            request_id: self.request_id.to_string(),
+            stage,
+            resumable,
+        }
+        .into()
+    }
+
+    /// Calls `step` until it yields a value, backing off between attempts.
+    pub fn poll<T>(
+        &self,
+        stage: FinalizationStage,
+        mut step: impl FnMut() -> Result<Option<T>>,
+    ) -> Result<T> {
+        let mut delay = self.settings.interval;
+        let mut attempt = 1;
+        loop {
+            self.report(stage, attempt, format!("Waiting for the CA ({})", describe(stage)));
+            if let Some(value) = step()? {
+                return Ok(value);
+            }
+            let remaining = self.remaining();
+            if remaining.is_zero() {
+                return Err(self.timed_out(stage, true));
+            }
+            thread::sleep(delay.min(remaining));
+            delay = (delay * 2).min(self.settings.max_interval);
+            attempt += 1;
+        }
+    }
+
+    /// Runs a call that blocks inside acme-lib on a helper thread and stops
+    /// waiting for it at the deadline. A helper that outlives the deadline
+    /// exits once the CA settles the authorization or order.
+    pub fn run_blocking<T: Send + 'static>(
+        &self,
+        stage: FinalizationStage,
+        resumable: bool,
+        call: impl FnOnce() -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        self.report(stage, 1, format!("Waiting for the CA ({})", describe(stage)));
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(call());
+        });
+        match rx.recv_timeout(self.remaining()) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => Err(self.timed_out(stage, resumable)),
+            Err(RecvTimeoutError::Disconnected) => {
+                Err(anyhow!("ACME worker stopped before {} finished", describe(stage)))
+            }
+        }
+    }
+}
+
+fn describe(stage: FinalizationStage) -> &'static str {
+    match stage {
+        FinalizationStage::CheckingDns => "checking DNS propagation",
+        FinalizationStage::ValidatingChallenges => "validating the DNS challenges",
+        FinalizationStage::AwaitingOrder => "marking the order ready",
+        FinalizationStage::Finalizing => "issuing the certificate",
+        FinalizationStage::Completed => "completing the issuance",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::errors::CommandError;
+    use std::cell::RefCell;
+
+    fn settings(timeout_ms: u64) -> PollSettings {
+        PollSettings {
+            interval: Duration::from_millis(1),
+            max_interval: Duration::from_millis(4),
+            timeout: Duration::from_millis(timeout_ms),
+        }
+    }
+
+    #[test]
+    fn polls_until_ready_and_reports_each_attempt() {
+        let events = RefCell::new(Vec::new());
+        let report = |progress: IssuanceProgress| events.borrow_mut().push(progress.attempt);
+        let poller = OrderPoller::new("req", settings(1_000), &report);
+        let mut calls = 0;
+        let value = poller
+            .poll(FinalizationStage::AwaitingOrder, || {
+                calls += 1;
+                Ok((calls == 3).then_some("ready"))
+            })
+            .unwrap();
+        assert_eq!(value, "ready");
+        assert_eq!(*events.borrow(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn stuck_stages_time_out_with_their_stage() {
+        let report = |_: IssuanceProgress| {};
+        let poller = OrderPoller::new("req", settings(20), &report);
+        let err = poller
+            .poll(FinalizationStage::AwaitingOrder, || Ok(None::<()>))
+            .unwrap_err();
+        match CommandError::from(err) {
+            CommandError::TimedOut(timeout) => {
+                assert_eq!(timeout.stage, FinalizationStage::AwaitingOrder);
+                assert_eq!(timeout.request_id, "req");
+                assert!(timeout.resumable);
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+
+        let err = poller
+            .run_blocking(FinalizationStage::Finalizing, false, || {
+                thread::sleep(Duration::from_millis(200));
+                Ok(())
+            })
+            .unwrap_err();
+        let timeout = err.downcast::<IssuanceTimeout>().unwrap();
+        assert_eq!(timeout.stage, FinalizationStage::Finalizing);
+        assert!(!timeout.resumable);
+    }
+}
@@ -0,0 +1,588 @@
+//! Go/no-go checks run before an issuance is confirmed.
+//!
+//! For each name the preflight looks at which DNS provider will publish the
+//! challenge, whether CAA records allow the issuer's CA, whether the zone is
+//! delegated to that provider, and which current certificates already cover
+//! it. Let's Encrypt rate-limit headroom is estimated from the inventory, so
+//! certificates issued outside SSLBoard are not counted. The challenge
+//! records the provider will be asked to write are listed from a dry run
+//! of its adapter, which sends nothing to the provider's API.
+
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use log::{info, warn};
+use serde::Deserialize;
+
+use crate::core::types::{
+    CertificateRecord, CertificateStatus, DomainPreflight, PreflightCheck, PreflightCheckKind,
+    PreflightReport, PreflightStatus, RecordOperationKind,
+};
+use crate::issuance::acme_workflow::validate_and_normalize_domains;
+use crate::issuance::dns::{follow_cname, record_name};
+use crate::issuance::dns_providers::{
+    DnsProviderAdapter, DryRunAdapter, adapter_for_provider, lookup_nameservers,
+    nameservers_match_provider, resolve_dns_timeout, resolve_provider_by_zone_access,
+};
+use crate::issuance::flow::root_from_hostname;
+use crate::issuance::issuer_selection::is_staging_certificate;
+use crate::secrets::manager::SecretManager;
+use crate::storage::{
+    dns::DnsConfigStore,
+    inventory::InventoryStore,
+    issuer::{IssuerConfigRecord, IssuerConfigStore},
+};
+
+/// Stands in for the challenge value, which only exists once the order is placed.
+const PLANNED_CHALLENGE_VALUE: &str = "<challenge digest>";
+
+/// DNS RR type code for CAA records.
+const CAA_RECORD_TYPE: u16 = 257;
+
+/// Let's Encrypt: new certificates per registered domain per week.
+const CERTIFICATES_PER_DOMAIN: usize = 50;
+/// Let's Encrypt: certificates for the same set of names per week.
+const DUPLICATE_CERTIFICATES: usize = 5;
+const RATE_LIMIT_WINDOW_DAYS: i64 = 7;
+/// A certificate with more days left than this makes a new one premature.
+const RENEWAL_WINDOW_DAYS: i64 = 30;
+
+#[derive(Debug, Deserialize)]
+struct CaaQueryResponse {
+    #[serde(rename = "Status")]
+    status: u32,
+    #[serde(rename = "Answer", default)]
+    answer: Vec<CaaQueryAnswer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CaaQueryAnswer {
+    #[serde(rename = "type")]
+    record_type: u16,
+    data: String,
+}
+
+/// One `issue` or `issuewild` property of a CAA record set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CaaProperty {
+    wildcard: bool,
+    /// CA domain, empty when the property forbids issuance
+    issuer: String,
+}
+
+/// Runs every check for `domains` against the issuer `issuer_id`.
+pub fn preflight_domains(
+    domains: Vec<String>,
+    issuer_id: &str,
+    issuer_store: &IssuerConfigStore,
+    dns_store: &DnsConfigStore,
+    inventory: &InventoryStore,
+    secrets: &SecretManager,
+) -> Result<PreflightReport> {
+    let domains = validate_and_normalize_domains(domains)?;
+    let issuers = issuer_store.list()?;
+    let issuer = issuers
+        .iter()
+        .find(|issuer| issuer.issuer_id == issuer_id)
+        .with_context(|| format!("Issuer not found: {issuer_id}"))?;
+    let records = inventory.list_certificates()?;
+    // Only production Let's Encrypt certificates count against its limits
+    let recent = tracks_rate_limits(issuer).then(|| {
+        let since = Utc::now() - Duration::days(RATE_LIMIT_WINDOW_DAYS);
+        records
+            .iter()
+            .filter(|record| {
+                record.not_before >= since && !is_staging_certificate(record, &issuers)
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let domains: Vec<DomainPreflight> = domains
+        .iter()
+        .map(|domain| {
+            let checks = vec![
+                provider_check(dns_store, secrets, domain),
+                caa_check(issuer, domain),
+                delegation_check(dns_store, domain),
+                record_plan_check(dns_store, secrets, domain),
+                inventory_check(&records, domain, Utc::now()),
+                rate_limit_check(recent.as_deref(), domain),
+            ];
+            DomainPreflight {
+                domain: domain.clone(),
+                status: worst(&checks),
+                checks,
+            }
+        })
+        .collect();
+    let names: Vec<String> = domains.iter().map(|domain| domain.domain.clone()).collect();
+    let order_checks = vec![duplicate_check(recent.as_deref(), &names)];
+
+    let go = worst(&order_checks) != PreflightStatus::Fail
+        && domains
+            .iter()
+            .all(|domain| domain.status != PreflightStatus::Fail);
+    Ok(PreflightReport {
+        issuer_id: issuer.issuer_id.clone(),
+        go,
+        domains,
+        order_checks,
+    })
+}
+
+fn check(kind: PreflightCheckKind, status: PreflightStatus, detail: String) -> PreflightCheck {
+    PreflightCheck {
+        kind,
+        status,
+        detail,
+    }
+}
+
+fn worst(checks: &[PreflightCheck]) -> PreflightStatus {
+    checks
+        .iter()
+        .map(|check| check.status)
+        .max()
+        .unwrap_or(PreflightStatus::Pass)
+}
+
+/// Name without a leading wildcard label.
+fn base_name(domain: &str) -> &str {
+    domain.strip_prefix("*.").unwrap_or(domain)
+}
+
+fn provider_check(
+    dns_store: &DnsConfigStore,
+    secrets: &SecretManager,
+    domain: &str,
+) -> PreflightCheck {
+    let kind = PreflightCheckKind::Provider;
+    match resolve_provider_by_zone_access(dns_store, secrets, base_name(domain)) {
+        Ok(resolution) => match resolution.provider {
+            Some(provider) if resolution.ambiguous.len() > 1 => check(
+                kind,
+                PreflightStatus::Warn,
+                format!(
+                    "{} providers match equally and can see the zone; {} will be used",
+                    resolution.ambiguous.len(),
+                    provider.label
+                ),
+            ),
+            Some(provider) if provider.provider_type == "manual" => check(
+                kind,
+                PreflightStatus::Pass,
+                format!("Manual DNS via {}; TXT records are added by hand", provider.label),
+            ),
+            Some(provider) => check(
+                kind,
+                PreflightStatus::Pass,
+                format!(
+                    "{} ({}) for {}",
+                    provider.label,
+                    provider.provider_type,
+                    resolution.matched_suffix.unwrap_or_default()
+                ),
+            ),
+            None => check(
+                kind,
+                PreflightStatus::Pass,
+                "No provider configured; TXT records are added by hand".to_string(),
+            ),
+        },
+        Err(err) => check(kind, PreflightStatus::Fail, format!("Provider lookup failed: {err}")),
+    }
+}
+
+fn delegation_check(dns_store: &DnsConfigStore, domain: &str) -> PreflightCheck {
+    let kind = PreflightCheckKind::Delegation;
+    let provider_type = dns_store
+        .resolve_provider_for_domain(base_name(domain))
+        .ok()
+        .and_then(|resolution| resolution.provider)
+        .map(|provider| provider.provider_type);
+    match lookup_nameservers(base_name(domain)) {
+        Ok(Some((zone, nameservers))) => {
+            let delegated = provider_type
+                .as_deref()
+                .and_then(|provider_type| nameservers_match_provider(provider_type, &nameservers));
+            let served_by = format!("{zone} is served by {}", nameservers.join(", "));
+            match delegated {
+                Some(false) => check(
+                    kind,
+                    PreflightStatus::Warn,
+                    format!(
+                        "{served_by}, not {}; challenge records may not be visible",
+                        provider_type.unwrap_or_default()
+                    ),
+                ),
+                _ => check(kind, PreflightStatus::Pass, served_by),
+            }
+        }
+        Ok(None) => check(
+            kind,
+            PreflightStatus::Fail,
+            format!("No NS records found for {} or its parents", base_name(domain)),
+        ),
+        Err(err) => {
+            warn!("[preflight] NS lookup for {domain} failed: {err:#}");
+            check(kind, PreflightStatus::Warn, format!("NS lookup failed: {err}"))
+        }
+    }
+}
+
+/// Dry run of the challenge record's creation and removal through the
+/// provider that will write it, following a CNAME at the challenge name as
+/// the dns-01 solver does.
+fn record_plan_check(
+    dns_store: &DnsConfigStore,
+    secrets: &SecretManager,
+    domain: &str,
+) -> PreflightCheck {
+    let kind = PreflightCheckKind::RecordPlan;
+    let plan = || -> Result<String> {
+        let mut challenge = record_name(base_name(domain));
+        let mut resolution =
+            resolve_provider_by_zone_access(dns_store, secrets, base_name(domain))?;
+        // acme-dns adapters write through their own CNAME
+        let uses_acme_dns = resolution
+            .provider
+            .as_ref()
+            .is_some_and(|provider| provider.provider_type == "acme_dns");
+        if !uses_acme_dns && let Some(target) = follow_cname(&challenge)? {
+            resolution = resolve_provider_by_zone_access(dns_store, secrets, &target)?;
+            challenge = target;
+        }
+        let provider = match resolution.provider {
+            Some(provider)
+                if provider.provider_type != "manual" && resolution.ambiguous.len() <= 1 =>
+            {
+                provider
+            }
+            _ => return Ok(format!("TXT {challenge} is added by hand")),
+        };
+        let adapter = DryRunAdapter::new(
+            provider.label.clone(),
+            adapter_for_provider(&provider, secrets),
+        );
+        adapter.create_txt(&challenge, PLANNED_CHALLENGE_VALUE)?;
+        adapter.cleanup_txt(&challenge)?;
+        let steps: Vec<String> = adapter
+            .planned()
+            .iter()
+            .map(|op| match op.operation {
+                RecordOperationKind::Create => format!("create TXT {}", op.record_name),
+                RecordOperationKind::Delete => format!("delete TXT {}", op.record_name),
+            })
+            .collect();
+        Ok(format!("{} would {}", provider.label, steps.join(", then ")))
+    };
+    match plan() {
+        Ok(detail) => check(kind, PreflightStatus::Pass, detail),
+        Err(err) => {
+            warn!("[preflight] record plan for {domain} failed: {err}");
+            check(kind, PreflightStatus::Warn, format!("Could not plan the record: {err}"))
+        }
+    }
+}
+
+fn caa_check(issuer: &IssuerConfigRecord, domain: &str) -> PreflightCheck {
+    let kind = PreflightCheckKind::Caa;
+    let properties = match lookup_caa(base_name(domain)) {
+        Ok(properties) => properties,
+        Err(err) => {
+            warn!("[preflight] CAA lookup for {domain} failed: {err:#}");
+            return check(kind, PreflightStatus::Warn, format!("CAA lookup failed: {err}"));
+        }
+    };
+    let Some((owner, properties)) = properties else {
+        return check(kind, PreflightStatus::Pass, "No CAA records; any CA may issue".to_string());
+    };
+    let allowed = caa_allowed_issuers(&properties, domain.starts_with("*."));
+    let Some(allowed) = allowed else {
+        return check(
+            kind,
+            PreflightStatus::Pass,
+            format!("CAA records at {owner} do not restrict issuance"),
+        );
+    };
+    let ca_domains = ca_domains(&issuer.directory_url);
+    if ca_domains.is_empty() {
+        return check(
+            kind,
+            PreflightStatus::Warn,
+            format!("CAA at {owner} allows {}; the issuer's CA is unknown", list(&allowed)),
+        );
+    }
+    if allowed.iter().any(|name| ca_domains.contains(&name.as_str())) {
+        check(kind, PreflightStatus::Pass, format!("CAA at {owner} allows {}", ca_domains[0]))
+    } else {
+        check(
+            kind,
+            PreflightStatus::Fail,
+            format!("CAA at {owner} allows only {}, not {}", list(&allowed), ca_domains[0]),
+        )
+    }
+}
+
+fn list(names: &[String]) -> String {
+    if names.is_empty() {
+        "no CA".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+/// CA domains that may appear in CAA records for the issuer's ACME server.
+fn ca_domains(directory_url: &str) -> &'static [&'static str] {
+    let host = reqwest::Url::parse(directory_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        .unwrap_or_default();
+    let known: &[(&str, &'static [&'static str])] = &[
+        ("letsencrypt.org", &["letsencrypt.org"]),
+        ("zerossl.com", &["sectigo.com", "zerossl.com"]),
+        ("sectigo.com", &["sectigo.com"]),
+        ("pki.goog", &["pki.goog"]),
+        ("buypass.com", &["buypass.com"]),
+        ("digicert.com", &["digicert.com"]),
+    ];
+    known
+        .iter()
+        .find(|(suffix, _)| host == *suffix || host.ends_with(&format!(".{suffix}")))
+        .map_or(&[], |(_, domains)| domains)
+}
+
+/// The relevant CAA record set: the closest one found climbing from the
+/// name towards the root, with the name it was found at.
+fn lookup_caa(domain: &str) -> Result<Option<(String, Vec<CaaProperty>)>> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(resolve_dns_timeout())
+        .build();
+    let mut candidate = domain.trim().trim_end_matches('.').to_ascii_lowercase();
+    loop {
+        info!("[preflight] Querying CAA records for {candidate}");
+        let body = agent
+            .get(&format!("https://dns.google/resolve?name={candidate}&type=CAA"))
+            .set("Accept", "application/dns-json")
+            .call()
+            .context("Failed to query Google DNS")?
+            .into_string()
+            .context("Failed to read Google DNS response body")?;
+        let response: CaaQueryResponse =
+            serde_json::from_str(&body).context("Failed to parse Google DNS response")?;
+        if response.status == 2 {
+            // SERVFAIL: CAs treat this as a refusal to issue
+            anyhow::bail!("{candidate} returned SERVFAIL for CAA");
+        }
+        let records: Vec<&str> = response
+            .answer
+            .iter()
+            .filter(|answer| answer.record_type == CAA_RECORD_TYPE)
+            .map(|answer| answer.data.as_str())
+            .collect();
+        if !records.is_empty() {
+            let properties = records.into_iter().filter_map(parse_caa).collect();
+            return Ok(Some((candidate, properties)));
+        }
+        candidate = match candidate.split_once('.') {
+            Some((_, parent)) if parent.contains('.') => parent.to_string(),
+            _ => return Ok(None),
+        };
+    }
+}
+
+/// Parses the presentation form `0 issue "letsencrypt.org; accounturi=..."`,
+/// keeping only `issue` and `issuewild`.
+fn parse_caa(data: &str) -> Option<CaaProperty> {
+    let mut parts = data.trim().splitn(3, ' ');
+    let _flags = parts.next()?;
+    let tag = parts.next()?.to_ascii_lowercase();
+    let value = parts.next().unwrap_or_default().trim().trim_matches('"');
+    let wildcard = match tag.as_str() {
+        "issue" => false,
+        "issuewild" => true,
+        _ => return None,
+    };
+    let issuer = value.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    Some(CaaProperty { wildcard, issuer })
+}
+
+/// CA domains a record set allows, or `None` when it has no issuance
+/// properties for the name. Wildcards use `issuewild` when present.
+fn caa_allowed_issuers(properties: &[CaaProperty], wildcard: bool) -> Option<Vec<String>> {
+    let relevant: Vec<&CaaProperty> = if wildcard && properties.iter().any(|prop| prop.wildcard) {
+        properties.iter().filter(|prop| prop.wildcard).collect()
+    } else {
+        properties.iter().filter(|prop| !prop.wildcard).collect()
+    };
+    if relevant.is_empty() {
+        return None;
+    }
+    let mut allowed: Vec<String> = relevant
+        .into_iter()
+        .map(|prop| prop.issuer.clone())
+        .filter(|issuer| !issuer.is_empty())
+        .collect();
+    allowed.sort();
+    allowed.dedup();
+    Some(allowed)
+}
+
+/// Whether a certificate name covers `domain`, directly or by wildcard.
+fn covers(name: &str, domain: &str) -> bool {
+    let name = name.trim().to_ascii_lowercase();
+    if name == domain {
+        return true;
+    }
+    match (name.strip_prefix("*."), domain.split_once('.')) {
+        (Some(parent), Some((label, rest))) => label != "*" && parent == rest,
+        _ => false,
+    }
+}
+
+fn inventory_check(
+    records: &[CertificateRecord],
+    domain: &str,
+    now: DateTime<Utc>,
+) -> PreflightCheck {
+    let kind = PreflightCheckKind::Inventory;
+    let covering = records
+        .iter()
+        .filter(|record| {
+            !matches!(
+                record.status,
+                CertificateStatus::Superseded | CertificateStatus::Revoked
+            ) && record.not_after > now
+        })
+        .filter(|record| record.sans.iter().any(|name| covers(name, domain)))
+        .max_by_key(|record| record.not_after);
+    match covering {
+        Some(record) => {
+            let days_left = (record.not_after - now).num_days();
+            let detail = format!(
+                "Covered by a {} certificate valid for {days_left} more days",
+                record.issuer
+            );
+            let status = if days_left > RENEWAL_WINDOW_DAYS {
+                PreflightStatus::Warn
+            } else {
+                PreflightStatus::Pass
+            };
+            check(kind, status, detail)
+        }
+        None => check(kind, PreflightStatus::Pass, "No current certificate".to_string()),
+    }
+}
+
+fn tracks_rate_limits(issuer: &IssuerConfigRecord) -> bool {
+    issuer.environment == "production" && ca_domains(&issuer.directory_url) == ["letsencrypt.org"]
+}
+
+fn headroom_status(used: usize, limit: usize) -> PreflightStatus {
+    if used >= limit {
+        PreflightStatus::Fail
+    } else if used + (limit / 10).max(1) >= limit {
+        PreflightStatus::Warn
+    } else {
+        PreflightStatus::Pass
+    }
+}
+
+fn rate_limit_check(recent: Option<&[&CertificateRecord]>, domain: &str) -> PreflightCheck {
+    let kind = PreflightCheckKind::RateLimit;
+    let Some(recent) = recent else {
+        return check(
+            kind,
+            PreflightStatus::Pass,
+            "Rate limits are not tracked for this issuer".to_string(),
+        );
+    };
+    let root = root_from_hostname(base_name(domain));
+    let used = recent
+        .iter()
+        .filter(|record| record.domain_roots.iter().any(|existing| existing == &root))
+        .count();
+    check(
+        kind,
+        headroom_status(used, CERTIFICATES_PER_DOMAIN),
+        format!(
+            "{used} of {CERTIFICATES_PER_DOMAIN} certificates for {root} issued in the last \
+             {RATE_LIMIT_WINDOW_DAYS} days"
+        ),
+    )
+}
+
+fn duplicate_check(recent: Option<&[&CertificateRecord]>, names: &[String]) -> PreflightCheck {
+    let kind = PreflightCheckKind::RateLimit;
+    let Some(recent) = recent else {
+        return check(
+            kind,
+            PreflightStatus::Pass,
+            "Rate limits are not tracked for this issuer".to_string(),
+        );
+    };
+    let wanted: HashSet<String> = names.iter().cloned().collect();
+    let used = recent
+        .iter()
+        .filter(|record| {
+            let sans: HashSet<String> =
+                record.sans.iter().map(|name| name.to_ascii_lowercase()).collect();
+            sans == wanted
+        })
+        .count();
+    check(
+        kind,
+        headroom_status(used, DUPLICATE_CERTIFICATES),
+        format!(
+            "{used} of {DUPLICATE_CERTIFICATES} certificates for this exact set of names \
+             issued in the last {RATE_LIMIT_WINDOW_DAYS} days"
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_caa_issuers_for_plain_and_wildcard_names() {
+        let properties: Vec<CaaProperty> = [
+            "0 issue \"letsencrypt.org; validationmethods=dns-01\"",
+            "0 issue \"pki.goog\"",
+            "0 issuewild \";\"",
+            "0 iodef \"mailto:security@example.com\"",
+        ]
+        .into_iter()
+        .filter_map(parse_caa)
+        .collect();
+        assert_eq!(properties.len(), 3);
+        assert_eq!(
+            caa_allowed_issuers(&properties, false).unwrap(),
+            ["letsencrypt.org", "pki.goog"]
+        );
+        assert!(caa_allowed_issuers(&properties, true).unwrap().is_empty());
+        assert_eq!(caa_allowed_issuers(&properties[..1], true).unwrap(), ["letsencrypt.org"]);
+        assert!(caa_allowed_issuers(&[], false).is_none());
+
+        assert_eq!(
+            ca_domains("https://acme-v02.api.letsencrypt.org/directory"),
+            ["letsencrypt.org"]
+        );
+        assert!(ca_domains("https://acme.internal/directory").is_empty());
+    }
+
+    #[test]
+    fn matches_covering_names_and_rate_limit_headroom() {
+        assert!(covers("*.example.com", "www.example.com"));
+        assert!(covers("WWW.example.com", "www.example.com"));
+        assert!(!covers("*.example.com", "example.com"));
+        assert!(!covers("*.example.com", "a.b.example.com"));
+
+        assert_eq!(headroom_status(10, CERTIFICATES_PER_DOMAIN), PreflightStatus::Pass);
+        assert_eq!(headroom_status(45, CERTIFICATES_PER_DOMAIN), PreflightStatus::Warn);
+        assert_eq!(headroom_status(50, CERTIFICATES_PER_DOMAIN), PreflightStatus::Fail);
+        assert_eq!(headroom_status(3, DUPLICATE_CERTIFICATES), PreflightStatus::Pass);
+        assert_eq!(headroom_status(4, DUPLICATE_CERTIFICATES), PreflightStatus::Warn);
+    }
+}
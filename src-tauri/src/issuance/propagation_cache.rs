@@ -0,0 +1,255 @@
+//! Short-lived cache for DoH propagation checks.
+//!
+//! Results are keyed by record name, expected value and the resolvers asked,
+//! and reused for a couple of seconds, and a check already running for the
+//! same key is joined instead of repeated. Repeated "check again" clicks and
+//! the issuance poller therefore share one set of resolver queries. Failed
+//! checks are never cached.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+use crate::issuance::dns::{DnsPropagationResult, PropagationState};
+use crate::issuance::propagation_resolvers::ResolverSet;
+
+/// Shorter than the propagation poll interval so polling still sees fresh answers.
+const CACHE_WINDOW: Duration = Duration::from_secs(2);
+
+/// Record name, expected value and canonical resolver set
+type Key = (String, String, String);
+
+enum Entry {
+    Checking(Arc<InFlight>),
+    Done {
+        at: Instant,
+        result: DnsPropagationResult,
+    },
+}
+
+#[derive(Default)]
+struct InFlight {
+    outcome: Mutex<Option<Result<DnsPropagationResult, String>>>,
+    finished: Condvar,
+}
+
+impl InFlight {
+    fn finish(&self, outcome: Result<DnsPropagationResult, String>) {
+        if let Ok(mut slot) = self.outcome.lock() {
+            *slot = Some(outcome);
+        }
+        self.finished.notify_all();
+    }
+
+    fn wait(&self) -> Result<DnsPropagationResult> {
+        let mut slot = self
+            .outcome
+            .lock()
+            .map_err(|e| anyhow!(e.to_string()))?;
+        while slot.is_none() {
+            slot = self
+                .finished
+                .wait(slot)
+                .map_err(|e| anyhow!(e.to_string()))?;
+        }
+        match slot.as_ref() {
+            Some(Ok(result)) => Ok(result.clone()),
+            Some(Err(message)) => Err(anyhow!(message.clone())),
+            None => unreachable!(),
+        }
+    }
+}
+
+/// Wakes waiters, and forgets the running check, even if the check panics.
+struct FinishGuard<'a> {
+    cache: &'a PropagationCache,
+    key: &'a Key,
+    in_flight: Arc<InFlight>,
+    outcome: Option<Result<DnsPropagationResult, String>>,
+}
+
+impl Drop for FinishGuard<'_> {
+    fn drop(&mut self) {
+        let outcome = match self.outcome.take() {
+            Some(outcome) => outcome,
+            None => {
+                if let Ok(mut entries) = self.cache.entries.lock() {
+                    entries.remove(self.key);
+                }
+                Err("propagation check aborted".to_string())
+            }
+        };
+        self.in_flight.finish(outcome);
+    }
+}
+
+pub struct PropagationCache {
+    window: Duration,
+    entries: Mutex<HashMap<Key, Entry>>,
+}
+
+impl PropagationCache {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns a recent result for the record from the same resolvers, waits
+    /// for a running check, or runs `check`.
+    pub fn get_or_check(
+        &self,
+        record_name: &str,
+        expected_value: &str,
+        resolvers: &ResolverSet,
+        check: impl FnOnce() -> Result<DnsPropagationResult>,
+    ) -> Result<DnsPropagationResult> {
+        let key = (
+            record_name.trim_end_matches('.').to_ascii_lowercase(),
+            expected_value.to_string(),
+            resolvers.canonical(),
+        );
+        let in_flight = {
+            let mut entries = self.entries.lock().map_err(|e| anyhow!(e.to_string()))?;
+            entries.retain(|_, entry| match entry {
+                Entry::Done { at, .. } => at.elapsed() < self.window,
+                Entry::Checking(_) => true,
+            });
+            match entries.get(&key) {
+                Some(Entry::Done { result, .. }) => {
+                    log::debug!("[dns] reusing recent propagation result for {}", key.0);
+                    return Ok(result.clone());
+                }
+                Some(Entry::Checking(in_flight)) => {
+                    let in_flight = in_flight.clone();
+                    drop(entries);
+                    log::debug!("[dns] joining running propagation check for {}", key.0);
+                    return in_flight.wait();
+                }
+                None => {
+                    let in_flight = Arc::new(InFlight::default());
+                    entries.insert(key.clone(), Entry::Checking(in_flight.clone()));
+                    in_flight
+                }
+            }
+        };
+
+        let mut guard = FinishGuard {
+            cache: self,
+            key: &key,
+            in_flight,
+            outcome: None,
+        };
+        let result = check();
+        if let Ok(mut entries) = self.entries.lock() {
+            match &result {
+                Ok(found) if !matches!(found.state, PropagationState::Error) => {
+                    entries.insert(
+                        key.clone(),
+                        Entry::Done {
+                            at: Instant::now(),
+                            result: found.clone(),
+                        },
+                    );
+                }
+                _ => {
+                    entries.remove(&key);
+                }
+            }
+        }
+        guard.outcome = Some(match &result {
+            Ok(found) => Ok(found.clone()),
+            Err(err) => Err(err.to_string()),
+        });
+        result
+    }
+}
+
+/// Process-wide cache shared by every propagation check.
+pub fn propagation_cache() -> &'static PropagationCache {
+    static CACHE: OnceLock<PropagationCache> = OnceLock::new();
+    CACHE.get_or_init(|| PropagationCache::new(CACHE_WINDOW))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    fn pending() -> DnsPropagationResult {
+        DnsPropagationResult::new(PropagationState::Pending, None, Vec::new())
+    }
+
+    #[test]
+    fn concurrent_checks_share_one_query() {
+        let cache = Arc::new(PropagationCache::new(Duration::from_secs(60)));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let defaults = ResolverSet::default();
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let cache = cache.clone();
+                let calls = calls.clone();
+                let defaults = defaults.clone();
+                thread::spawn(move || {
+                    cache.get_or_check("_acme-challenge.Example.com.", "token", &defaults, || {
+                        calls.fetch_add(1, Ordering::SeqCst);
+                        thread::sleep(Duration::from_millis(50));
+                        Ok(pending())
+                    })
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert!(matches!(handle.join().unwrap().unwrap().state, PropagationState::Pending));
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // A different expected value is a different question.
+        cache
+            .get_or_check("_acme-challenge.example.com", "other", &defaults, || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok(pending())
+            })
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // So is asking other resolvers.
+        let internal = ResolverSet::parse("10.0.0.53").unwrap();
+        cache
+            .get_or_check("_acme-challenge.example.com", "token", &internal, || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok(pending())
+            })
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn failures_and_expired_results_are_checked_again() {
+        let cache = PropagationCache::new(Duration::from_millis(20));
+        let calls = AtomicUsize::new(0);
+        let defaults = ResolverSet::default();
+        let check = || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(anyhow!("resolver down"))
+        };
+        assert!(cache.get_or_check("a.example.com", "v", &defaults, check).is_err());
+        assert!(cache.get_or_check("a.example.com", "v", &defaults, check).is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let count = || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(pending())
+        };
+        cache.get_or_check("b.example.com", "v", &defaults, count).unwrap();
+        cache.get_or_check("b.example.com", "v", &defaults, count).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        thread::sleep(Duration::from_millis(30));
+        cache.get_or_check("b.example.com", "v", &defaults, count).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+}
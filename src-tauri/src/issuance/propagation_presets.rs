@@ -0,0 +1,252 @@
+//! How long to wait for challenge records to propagate, per provider type.
+//!
+//! Providers differ widely: Cloudflare answers within seconds, Route 53 takes
+//! about a minute to sync its name servers, and records entered by hand at a
+//! registrar can take several minutes. Each provider type ships a preset
+//! tuned to that, and the `propagation_wait_overrides` preference (a JSON
+//! object keyed by provider type) replaces presets the user wants changed.
+//! A single provider can go further through `propagation_timeout_secs` and
+//! `propagation_interval_secs` in its config, and an issuance request can
+//! override the wait for all of its records.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+
+use crate::core::types::{PropagationPreset, PropagationWait};
+use crate::storage::preferences::PreferencesStore;
+
+pub const PROPAGATION_WAIT_PREFERENCE: &str = "propagation_wait_overrides";
+/// Provider config keys for a provider's own wait.
+pub const TIMEOUT_CONFIG_KEY: &str = "propagation_timeout_secs";
+pub const INTERVAL_CONFIG_KEY: &str = "propagation_interval_secs";
+
+/// Provider type used for challenge records no provider created.
+pub const MANUAL_PROVIDER_TYPE: &str = "manual";
+
+/// (provider type, timeout, interval) in seconds.
+const PRESETS: &[(&str, u64, u64)] = &[
+    ("cloudflare", 30, 2),
+    ("acme_dns", 30, 2),
+    ("digitalocean", 60, 3),
+    ("powerdns", 60, 3),
+    ("rfc2136", 60, 3),
+    ("desec", 120, 5),
+    ("route53", 120, 5),
+    ("script", 300, 10),
+    (MANUAL_PROVIDER_TYPE, 600, 15),
+];
+
+/// For provider types without a preset.
+const FALLBACK: PropagationWait = PropagationWait {
+    timeout_secs: 60,
+    interval_secs: 3,
+};
+
+const MIN_TIMEOUT_SECS: u64 = 10;
+const MAX_TIMEOUT_SECS: u64 = 1800;
+const MIN_INTERVAL_SECS: u64 = 1;
+const MAX_INTERVAL_SECS: u64 = 60;
+
+pub fn preset_for(provider_type: &str) -> PropagationWait {
+    PRESETS
+        .iter()
+        .find(|(kind, _, _)| *kind == provider_type)
+        .map(|&(_, timeout_secs, interval_secs)| PropagationWait {
+            timeout_secs,
+            interval_secs,
+        })
+        .unwrap_or(FALLBACK)
+}
+
+pub fn timeout(wait: PropagationWait) -> Duration {
+    Duration::from_secs(wait.timeout_secs)
+}
+
+pub fn interval(wait: PropagationWait) -> Duration {
+    Duration::from_secs(wait.interval_secs)
+}
+
+/// A provider's own wait from its config; each field replaces the one of
+/// its provider type when set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProviderWait {
+    pub timeout_secs: Option<u64>,
+    pub interval_secs: Option<u64>,
+}
+
+impl ProviderWait {
+    /// Reads and checks the wait keys of a provider config.
+    pub fn from_config(config: Option<&Value>) -> Result<Self> {
+        let read = |key: &str, min: u64, max: u64| -> Result<Option<u64>> {
+            match config.and_then(|value| value.get(key)).filter(|v| !v.is_null()) {
+                None => Ok(None),
+                Some(value) => value
+                    .as_u64()
+                    .filter(|secs| (min..=max).contains(secs))
+                    .map(Some)
+                    .ok_or_else(|| anyhow!("{key} must be between {min} and {max} seconds")),
+            }
+        };
+        Ok(Self {
+            timeout_secs: read(TIMEOUT_CONFIG_KEY, MIN_TIMEOUT_SECS, MAX_TIMEOUT_SECS)?,
+            interval_secs: read(INTERVAL_CONFIG_KEY, MIN_INTERVAL_SECS, MAX_INTERVAL_SECS)?,
+        })
+    }
+
+    /// Like [`Self::from_config`] for a stored `config_json`; a config that
+    /// no longer parses is logged and ignored.
+    pub fn from_config_json(config_json: Option<&str>) -> Self {
+        let parsed = config_json
+            .map(serde_json::from_str::<Value>)
+            .transpose()
+            .map_err(anyhow::Error::from)
+            .and_then(|config| Self::from_config(config.as_ref()));
+        parsed.unwrap_or_else(|err| {
+            log::warn!("[dns] ignoring invalid provider propagation wait: {err}");
+            Self::default()
+        })
+    }
+}
+
+/// Presets with the user's overrides applied.
+#[derive(Debug, Clone, Default)]
+pub struct PropagationWaits {
+    overrides: HashMap<String, PropagationWait>,
+    /// Wait chosen for one issuance, ahead of every other setting
+    request: Option<PropagationWait>,
+}
+
+impl PropagationWaits {
+    /// Reads the overrides preference. An unreadable or invalid preference
+    /// is logged and leaves every preset in place.
+    pub fn from_preferences(prefs: &PreferencesStore) -> Self {
+        match prefs.get(PROPAGATION_WAIT_PREFERENCE) {
+            Ok(Some(pref)) if !pref.value.trim().is_empty() => Self::parse(&pref.value),
+            Ok(_) => Self::default(),
+            Err(err) => {
+                log::warn!("[dns] failed to read {PROPAGATION_WAIT_PREFERENCE} preference: {err}");
+                Self::default()
+            }
+        }
+    }
+
+    fn parse(raw: &str) -> Self {
+        match serde_json::from_str::<HashMap<String, PropagationWait>>(raw) {
+            Ok(overrides) => Self {
+                overrides: overrides
+                    .into_iter()
+                    .map(|(provider_type, wait)| (provider_type, clamp(wait)))
+                    .collect(),
+                request: None,
+            },
+            Err(err) => {
+                log::warn!(
+                    "[dns] ignoring invalid {PROPAGATION_WAIT_PREFERENCE} preference: {err}"
+                );
+                Self::default()
+            }
+        }
+    }
+
+    pub fn for_type(&self, provider_type: &str) -> PropagationWait {
+        self.overrides
+            .get(provider_type)
+            .copied()
+            .unwrap_or_else(|| preset_for(provider_type))
+    }
+
+    /// Makes `wait`, when set, the wait of every record.
+    pub fn with_request(mut self, wait: Option<PropagationWait>) -> Self {
+        self.request = wait.map(clamp);
+        self
+    }
+
+    /// The wait for a record written by a provider of `provider_type`: the
+    /// request's wait, else the provider's own, else the type's.
+    pub fn resolve(&self, provider_type: &str, provider: ProviderWait) -> PropagationWait {
+        if let Some(wait) = self.request {
+            return wait;
+        }
+        let base = self.for_type(provider_type);
+        clamp(PropagationWait {
+            timeout_secs: provider.timeout_secs.unwrap_or(base.timeout_secs),
+            interval_secs: provider.interval_secs.unwrap_or(base.interval_secs),
+        })
+    }
+
+    /// Every provider type with a preset, in preset order.
+    pub fn presets(&self) -> Vec<PropagationPreset> {
+        PRESETS
+            .iter()
+            .map(|(provider_type, _, _)| PropagationPreset {
+                provider_type: provider_type.to_string(),
+                preset: preset_for(provider_type),
+                effective: self.for_type(provider_type),
+                overridden: self.overrides.contains_key(*provider_type),
+            })
+            .collect()
+    }
+}
+
+fn clamp(wait: PropagationWait) -> PropagationWait {
+    let timeout_secs = wait.timeout_secs.clamp(MIN_TIMEOUT_SECS, MAX_TIMEOUT_SECS);
+    PropagationWait {
+        timeout_secs,
+        interval_secs: wait
+            .interval_secs
+            .clamp(MIN_INTERVAL_SECS, MAX_INTERVAL_SECS)
+            .min(timeout_secs),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_replace_presets_within_bounds() {
+        let waits = PropagationWaits::parse(
+            r#"{"route53": {"timeout_secs": 90, "interval_secs": 4},
+                "cloudflare": {"timeout_secs": 1, "interval_secs": 600}}"#,
+        );
+        assert_eq!(
+            waits.for_type("route53"),
+            PropagationWait { timeout_secs: 90, interval_secs: 4 }
+        );
+        assert_eq!(
+            waits.for_type("cloudflare"),
+            PropagationWait { timeout_secs: MIN_TIMEOUT_SECS, interval_secs: MIN_TIMEOUT_SECS }
+        );
+        assert_eq!(waits.for_type("desec"), preset_for("desec"));
+        assert_eq!(waits.for_type("unknown"), FALLBACK);
+        assert!(waits.presets().iter().any(|preset| preset.provider_type == "route53"
+            && preset.overridden
+            && preset.preset.timeout_secs == 120));
+
+        let invalid = PropagationWaits::parse("not json");
+        assert_eq!(invalid.for_type("route53"), preset_for("route53"));
+    }
+
+    #[test]
+    fn provider_and_request_waits_take_precedence() {
+        let config = serde_json::json!({ "propagation_timeout_secs": 600 });
+        let provider = ProviderWait::from_config(Some(&config)).unwrap();
+        let waits = PropagationWaits::default();
+        assert_eq!(
+            waits.resolve("route53", provider),
+            PropagationWait { timeout_secs: 600, interval_secs: 5 }
+        );
+        assert_eq!(waits.resolve("route53", ProviderWait::default()), preset_for("route53"));
+
+        let request = PropagationWait { timeout_secs: 900, interval_secs: 20 };
+        let waits = waits.with_request(Some(request));
+        assert_eq!(waits.resolve("route53", provider), request);
+
+        let too_long = serde_json::json!({ "propagation_interval_secs": 3600 });
+        assert!(ProviderWait::from_config(Some(&too_long)).is_err());
+        assert_eq!(ProviderWait::from_config_json(Some("not json")), ProviderWait::default());
+    }
+}
@@ -0,0 +1,249 @@
+//! Resolvers asked whether a challenge record has propagated.
+//!
+//! By default public checks go to Google and Cloudflare over DNS-over-HTTPS.
+//! The `dns_propagation_resolvers` preference replaces that set with a
+//! comma-separated list of `google`, `cloudflare`, `https://` URLs of other
+//! DoH endpoints speaking the `application/dns-json` format, and IP
+//! addresses (optionally with a port) of plain DNS servers, such as an
+//! internal corporate resolver that sees split-horizon zones.
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+
+use crate::issuance::dns::CNAME_RECORD_TYPE;
+use crate::issuance::dns_wire::{self, TYPE_TXT};
+use crate::storage::preferences::PreferencesStore;
+
+pub const RESOLVERS_PREFERENCE: &str = "dns_propagation_resolvers";
+
+const GOOGLE_DOH_URL: &str = "https://dns.google/resolve";
+const CLOUDFLARE_DOH_URL: &str = "https://cloudflare-dns.com/dns-query";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolver {
+    /// DNS-over-HTTPS endpoint answering JSON queries
+    Doh { name: String, url: String },
+    /// Recursive DNS server queried over UDP, or TCP for truncated replies
+    Server { address: SocketAddr },
+}
+
+/// One resolver's answer to a TXT query: the RCODE and the TXT values,
+/// without CNAMEs the resolver followed on the way.
+#[derive(Debug, Clone)]
+pub struct ResolverResponse {
+    pub status: u32,
+    pub values: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct DohResponse {
+    #[serde(rename = "Status")]
+    status: u32,
+    #[serde(rename = "Answer", default)]
+    answer: Vec<DohAnswer>,
+}
+
+#[derive(Deserialize)]
+struct DohAnswer {
+    data: Option<String>,
+    #[serde(rename = "type", default)]
+    record_type: Option<u16>,
+}
+
+impl Resolver {
+    pub fn name(&self) -> String {
+        match self {
+            Self::Doh { name, .. } => name.clone(),
+            Self::Server { address } => format!("DNS server {address}"),
+        }
+    }
+
+    pub fn query_txt(&self, record_name: &str, timeout: Duration) -> Result<ResolverResponse> {
+        match self {
+            Self::Doh { url, .. } => query_doh(url, record_name, timeout),
+            Self::Server { address } => query_server(*address, record_name, timeout),
+        }
+    }
+
+    fn parse(entry: &str) -> Result<Self> {
+        match entry.to_ascii_lowercase().as_str() {
+            "google" => return Ok(google()),
+            "cloudflare" => return Ok(cloudflare()),
+            _ => {}
+        }
+        if let Some(rest) = entry.strip_prefix("https://") {
+            let host = rest.split(['/', '?', ':']).next().unwrap_or_default();
+            if host.is_empty() {
+                return Err(anyhow!("DoH URL has no host: {entry}"));
+            }
+            return Ok(Self::Doh {
+                name: host.to_string(),
+                url: entry.to_string(),
+            });
+        }
+        dns_wire::server_address(entry)
+            .parse()
+            .map(|address| Self::Server { address })
+            .map_err(|_| {
+                anyhow!(
+                    "unknown resolver \"{entry}\": use google, cloudflare, an https:// DoH URL \
+                     or an IP address"
+                )
+            })
+    }
+}
+
+fn google() -> Resolver {
+    Resolver::Doh {
+        name: "Google DNS".to_string(),
+        url: GOOGLE_DOH_URL.to_string(),
+    }
+}
+
+fn cloudflare() -> Resolver {
+    Resolver::Doh {
+        name: "Cloudflare DNS".to_string(),
+        url: CLOUDFLARE_DOH_URL.to_string(),
+    }
+}
+
+/// Resolvers a propagation check asks, in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolverSet {
+    resolvers: Vec<Resolver>,
+}
+
+impl Default for ResolverSet {
+    fn default() -> Self {
+        Self {
+            resolvers: vec![google(), cloudflare()],
+        }
+    }
+}
+
+impl ResolverSet {
+    /// Parses a comma- or whitespace-separated list; an empty list is the
+    /// default set.
+    pub fn parse(raw: &str) -> Result<Self> {
+        let resolvers = raw
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(Resolver::parse)
+            .collect::<Result<Vec<_>>>()?;
+        if resolvers.is_empty() {
+            return Ok(Self::default());
+        }
+        Ok(Self { resolvers })
+    }
+
+    pub fn from_preferences(prefs: &PreferencesStore) -> Self {
+        match prefs.get(RESOLVERS_PREFERENCE) {
+            Ok(Some(pref)) => Self::parse(&pref.value).unwrap_or_else(|err| {
+                log::warn!("[dns] ignoring invalid {RESOLVERS_PREFERENCE} preference: {err}");
+                Self::default()
+            }),
+            Ok(None) => Self::default(),
+            Err(err) => {
+                log::warn!("[dns] failed to read {RESOLVERS_PREFERENCE} preference: {err}");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn resolvers(&self) -> &[Resolver] {
+        &self.resolvers
+    }
+
+    /// Identifies the set by each resolver's URL or address, in order, so
+    /// sets parsed from different spellings of one list compare equal.
+    pub fn canonical(&self) -> String {
+        self.resolvers
+            .iter()
+            .map(|resolver| match resolver {
+                Resolver::Doh { url, .. } => url.clone(),
+                Resolver::Server { address } => address.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+fn query_doh(url: &str, record_name: &str, timeout: Duration) -> Result<ResolverResponse> {
+    let separator = if url.contains('?') { '&' } else { '?' };
+    // Google caches per query string; padding keeps us off a stale entry
+    let padding = if url == GOOGLE_DOH_URL { "&random_padding=x" } else { "" };
+    let agent = ureq::AgentBuilder::new().timeout(timeout).build();
+    let body = agent
+        .get(&format!("{url}{separator}name={record_name}&type=TXT{padding}"))
+        .set("Accept", "application/dns-json")
+        .call()
+        .with_context(|| format!("query to {url} failed"))?
+        .into_string()
+        .with_context(|| format!("failed to read the response of {url}"))?;
+    let response: DohResponse =
+        serde_json::from_str(&body).with_context(|| format!("{url} returned invalid JSON"))?;
+    Ok(ResolverResponse {
+        status: response.status,
+        values: response
+            .answer
+            .into_iter()
+            .filter(|answer| answer.record_type != Some(CNAME_RECORD_TYPE))
+            .filter_map(|answer| answer.data)
+            .collect(),
+    })
+}
+
+fn query_server(
+    address: SocketAddr,
+    record_name: &str,
+    timeout: Duration,
+) -> Result<ResolverResponse> {
+    let message = dns_wire::recursive_query(record_name, TYPE_TXT)?;
+    let (response, _) = dns_wire::resolve(address, message, timeout)?;
+    Ok(ResolverResponse {
+        status: u32::from(response.rcode),
+        values: response
+            .answers
+            .iter()
+            .filter(|record| record.rtype == TYPE_TXT)
+            .map(|record| dns_wire::parse_txt_rdata(&record.rdata))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_resolver_lists() {
+        assert_eq!(ResolverSet::parse("").unwrap(), ResolverSet::default());
+        assert_eq!(ResolverSet::parse(" google, cloudflare ").unwrap(), ResolverSet::default());
+
+        let set = ResolverSet::parse("10.0.0.53\nhttps://doh.corp.example/dns-query, [::1]:5353")
+            .unwrap();
+        assert_eq!(
+            set.resolvers(),
+            &[
+                Resolver::Server {
+                    address: "10.0.0.53:53".parse().unwrap()
+                },
+                Resolver::Doh {
+                    name: "doh.corp.example".to_string(),
+                    url: "https://doh.corp.example/dns-query".to_string(),
+                },
+                Resolver::Server {
+                    address: "[::1]:5353".parse().unwrap()
+                },
+            ]
+        );
+
+        assert!(ResolverSet::parse("dns.corp.example").is_err());
+        assert!(ResolverSet::parse("http://doh.example/dns-query").is_err());
+        assert!(ResolverSet::parse("https://").is_err());
+    }
+}
@@ -0,0 +1,189 @@
+//! Background propagation polls that report through events.
+//!
+//! A watch checks one TXT record on its preset's interval and emits
+//! `dns-propagation` whenever the observed state changes, until the record
+//! is found, the preset's timeout passes or the watch is cancelled. Public
+//! checks go through the propagation cache, so a watch and the issuance
+//! poller share resolver queries; authoritative checks always ask the
+//! zone's nameservers and report each one's state.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+use crate::core::types::{
+    DnsPropagationUpdate, NameserverCheck, PropagationCheckMode, PropagationWait,
+    PropagationWatchOutcome,
+};
+use crate::issuance::authoritative_dns::check_authoritative;
+use crate::issuance::dns::{DnsPropagationResult, PropagationState, check_txt_record};
+use crate::issuance::propagation_presets;
+use crate::issuance::propagation_resolvers::ResolverSet;
+
+pub const PROPAGATION_WATCH_EVENT: &str = "dns-propagation";
+
+/// How often a sleeping watch looks at its cancel flag.
+const CANCEL_POLL: Duration = Duration::from_millis(250);
+
+fn watches() -> MutexGuard<'static, HashMap<String, Arc<AtomicBool>>> {
+    static WATCHES: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+    WATCHES
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+}
+
+/// Starts watching `record_name` for `value` and returns the watch id
+/// carried by its events.
+pub fn start(
+    app: AppHandle,
+    record_name: String,
+    value: String,
+    wait: PropagationWait,
+    mode: PropagationCheckMode,
+    resolvers: ResolverSet,
+) -> String {
+    let watch_id = Uuid::new_v4().to_string();
+    let cancelled = Arc::new(AtomicBool::new(false));
+    watches().insert(watch_id.clone(), cancelled.clone());
+    let id = watch_id.clone();
+    thread::spawn(move || {
+        let emit = |update: &DnsPropagationUpdate| {
+            if let Err(err) = app.emit(PROPAGATION_WATCH_EVENT, update) {
+                log::warn!("[dns] failed to emit propagation update: {err}");
+            }
+        };
+        let check = Check {
+            mode,
+            resolvers: &resolvers,
+        };
+        run(&id, &record_name, &value, wait, check, &cancelled, emit);
+        watches().remove(&id);
+    });
+    watch_id
+}
+
+/// Stops a running watch; false when it already ended.
+pub fn cancel(watch_id: &str) -> bool {
+    match watches().get(watch_id) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+fn run(
+    watch_id: &str,
+    record_name: &str,
+    value: &str,
+    wait: PropagationWait,
+    check: Check,
+    cancelled: &AtomicBool,
+    emit: impl Fn(&DnsPropagationUpdate),
+) {
+    let started = Instant::now();
+    let timeout = propagation_presets::timeout(wait);
+    let interval = propagation_presets::interval(wait).max(Duration::from_secs(1));
+    let mut last: Option<DnsPropagationUpdate> = None;
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let (result, nameservers) = check.run(record_name, value);
+        let outcome = if result.state == PropagationState::Found {
+            Some(PropagationWatchOutcome::Found)
+        } else if cancelled.load(Ordering::Relaxed) {
+            Some(PropagationWatchOutcome::Cancelled)
+        } else if started.elapsed() >= timeout {
+            Some(PropagationWatchOutcome::TimedOut)
+        } else {
+            None
+        };
+        let update = DnsPropagationUpdate {
+            watch_id: watch_id.to_string(),
+            record_name: record_name.to_string(),
+            result,
+            attempt,
+            elapsed_secs: started.elapsed().as_secs(),
+            outcome,
+            nameservers,
+        };
+        let changed = last.as_ref().map(state_key) != Some(state_key(&update));
+        if outcome.is_some() || changed {
+            emit(&update);
+        }
+        if outcome.is_some() {
+            return;
+        }
+        last = Some(update);
+
+        // Sleep in short steps so a cancel ends the watch promptly
+        let next_check = Instant::now() + interval;
+        while !cancelled.load(Ordering::Relaxed) {
+            let remaining = next_check.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            thread::sleep(CANCEL_POLL.min(remaining));
+        }
+        if cancelled.load(Ordering::Relaxed) {
+            if let Some(mut update) = last.take() {
+                update.elapsed_secs = started.elapsed().as_secs();
+                update.outcome = Some(PropagationWatchOutcome::Cancelled);
+                emit(&update);
+            }
+            return;
+        }
+    }
+}
+
+/// How a watch looks for its record.
+struct Check<'a> {
+    mode: PropagationCheckMode,
+    /// Asked by public checks
+    resolvers: &'a ResolverSet,
+}
+
+impl Check<'_> {
+    fn run(
+        &self,
+        record_name: &str,
+        value: &str,
+    ) -> (DnsPropagationResult, Option<Vec<NameserverCheck>>) {
+        let checked = match self.mode {
+            PropagationCheckMode::Public => {
+                check_txt_record(record_name, value, self.resolvers).map(|r| (r, None))
+            }
+            PropagationCheckMode::Authoritative | PropagationCheckMode::SerialSync => {
+                let compare_serials = self.mode == PropagationCheckMode::SerialSync;
+                check_authoritative(record_name, value, compare_serials)
+                    .map(|result| (result.summary(), Some(result.nameservers)))
+            }
+        };
+        checked.unwrap_or_else(|err| {
+            let result = DnsPropagationResult {
+                state: PropagationState::Error,
+                reason: Some(err.to_string()),
+                reason_code: None,
+                observed_values: Vec::new(),
+                resolvers: Vec::new(),
+            };
+            (result, None)
+        })
+    }
+}
+
+/// States an update is compared on; a nameserver catching up counts as a change.
+fn state_key(update: &DnsPropagationUpdate) -> Vec<PropagationState> {
+    let mut key = vec![update.result.state.clone()];
+    if let Some(nameservers) = &update.nameservers {
+        key.extend(nameservers.iter().map(|check| check.result.state.clone()));
+    }
+    key
+}
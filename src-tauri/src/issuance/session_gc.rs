@@ -0,0 +1,48 @@
+//! Garbage collection of abandoned issuance sessions.
+//!
+//! A started issuance holds its challenge records, a provisional managed key
+//! and a journal entry until it is completed. Sessions the user walks away
+//! from would keep all three for as long as the app runs, so once an hour
+//! any session idle for longer than [`SESSION_IDLE_LIMIT`] is dropped and
+//! cleaned up. Nothing is removed while read-only mode is on.
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::core::read_only::ReadOnlyMode;
+use crate::issuance::flow::collect_expired_issuances;
+use crate::secrets::manager::SecretManager;
+use crate::storage::{dns::DnsConfigStore, jobs::JobStore};
+
+const GC_TICK: Duration = Duration::from_secs(60 * 60);
+
+/// Longer than any DNS propagation wait; ACME authorizations outlive it.
+const SESSION_IDLE_LIMIT: chrono::Duration = chrono::Duration::hours(24);
+
+fn collect(app: &AppHandle) -> usize {
+    if app.state::<ReadOnlyMode>().is_enabled() {
+        return 0;
+    }
+    collect_expired_issuances(
+        SESSION_IDLE_LIMIT,
+        app.state::<DnsConfigStore>().inner(),
+        app.state::<SecretManager>().inner(),
+        app.state::<JobStore>().inner(),
+    )
+}
+
+/// Starts the hourly collection of abandoned sessions.
+pub fn spawn_session_gc(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(GC_TICK).await;
+            let handle = app.clone();
+            match tauri::async_runtime::spawn_blocking(move || collect(&handle)).await {
+                Ok(0) => {}
+                Ok(count) => log::info!("[session-gc] dropped {count} abandoned issuance(s)"),
+                Err(err) => log::warn!("[session-gc] collection join error: {err}"),
+            }
+        }
+    });
+}
@@ -0,0 +1,181 @@
+//! dns-01: a TXT record at `_acme-challenge.<name>`, written through the
+//! name's DNS provider or shown to the user to add by hand.
+
+use acme_lib::order::Auth;
+use anyhow::Result;
+
+use crate::core::mappers::provider_zone_override;
+use crate::core::types::ChallengeSolverKind;
+use crate::issuance::acme_workflow;
+use crate::issuance::dns::{
+    DnsAdapter, DnsChallengeRequest, DnsRecordInstruction, ManualDnsAdapter, derive_zone,
+    follow_cname,
+};
+use crate::issuance::dns_providers::{cached_adapter_for_provider, resolve_provider_by_zone_access};
+use crate::issuance::flow::{cleanup_challenge_records, EphemeralPersist};
+use crate::issuance::order_polling::OrderPoller;
+use crate::issuance::propagation_presets::{MANUAL_PROVIDER_TYPE, PropagationWaits, ProviderWait};
+use crate::issuance::propagation_resolvers::ResolverSet;
+use crate::secrets::manager::SecretManager;
+use crate::storage::dns::{DnsConfigStore, DnsProviderResolution};
+
+use super::{ChallengeSolver, PresentedChallenge, SolverContext};
+
+pub struct DnsSolver {
+    /// Show the record even when a provider could write it
+    manual_only: bool,
+    /// Provider type that wrote the record, for its propagation wait
+    provider_type: Option<String>,
+    /// That provider's own propagation wait settings
+    provider_wait: ProviderWait,
+    /// Record written through a provider as (domain, record name)
+    created: Option<(String, String)>,
+    /// Record presented for this authorization, checked for propagation
+    presented: Option<DnsRecordInstruction>,
+}
+
+impl DnsSolver {
+    pub fn new(manual_only: bool) -> Self {
+        Self {
+            manual_only,
+            provider_type: None,
+            provider_wait: ProviderWait::default(),
+            created: None,
+            presented: None,
+        }
+    }
+}
+
+impl ChallengeSolver for DnsSolver {
+    fn kind(&self) -> ChallengeSolverKind {
+        if self.manual_only {
+            ChallengeSolverKind::Dns01Manual
+        } else {
+            ChallengeSolverKind::Dns01Provider
+        }
+    }
+
+    fn present(
+        &mut self,
+        auth: &Auth<EphemeralPersist>,
+        context: &SolverContext,
+    ) -> Result<PresentedChallenge> {
+        let dns_store = context.dns_store;
+        let proof = auth.dns_challenge().dns_proof();
+        let domain = auth.domain_name().to_string();
+        let PlannedChallenge {
+            mut record,
+            resolution,
+            cleanup_domain,
+        } = plan_challenge_record(dns_store, context.secrets, &domain, proof)?;
+
+        if !self.manual_only
+            && let Some(provider) = resolution.provider.as_ref()
+            && resolution.ambiguous.len() <= 1
+        {
+            let adapter = cached_adapter_for_provider(provider, context.secrets, dns_store);
+            context.queue_txt(provider, record.record_name.clone(), record.value.clone());
+            record.adapter = provider.provider_type.clone();
+            record.cname_target = adapter.cname_target(&record.record_name);
+            self.provider_type = Some(provider.provider_type.clone());
+            self.provider_wait = ProviderWait::from_config_json(provider.config_json.as_deref());
+            self.created = Some((cleanup_domain, record.record_name.clone()));
+        }
+
+        self.presented = Some(record.clone());
+        Ok(PresentedChallenge {
+            dns_record: Some(record),
+            cleanup_record: self.created.clone(),
+        })
+    }
+
+    fn await_ready(
+        &self,
+        auth: &Auth<EphemeralPersist>,
+        waits: &PropagationWaits,
+        resolvers: &ResolverSet,
+    ) -> Result<()> {
+        let provider_type = self.provider_type.as_deref().unwrap_or(MANUAL_PROVIDER_TYPE);
+        let wait = waits.resolve(provider_type, self.provider_wait);
+        acme_workflow::check_dns_propagation(auth, self.presented.as_ref(), wait, resolvers)
+    }
+
+    fn validate(&self, auth: &Auth<EphemeralPersist>, poller: &OrderPoller) -> Result<()> {
+        acme_workflow::validate_challenge(auth.dns_challenge(), poller)
+    }
+
+    fn cleanup(&mut self, context: &SolverContext) -> Vec<String> {
+        match self.created.take() {
+            Some(record) => {
+                cleanup_challenge_records(&[record], context.dns_store, context.secrets)
+            }
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Where the TXT record for one authorization goes.
+pub struct PlannedChallenge {
+    pub record: DnsRecordInstruction,
+    /// Provider responsible for `record.record_name`
+    pub resolution: DnsProviderResolution,
+    /// Name the record is cleaned up through
+    pub cleanup_domain: String,
+}
+
+/// Places the challenge record for `domain` as issuance does: in the zone
+/// the provider config overrides, at the target of a CNAME at the
+/// challenge name, and with the provider of that name.
+pub fn plan_challenge_record(
+    dns_store: &DnsConfigStore,
+    secrets: &SecretManager,
+    domain: &str,
+    value: String,
+) -> Result<PlannedChallenge> {
+    let mut resolution = resolve_provider_by_zone_access(dns_store, secrets, domain)?;
+    let request = DnsChallengeRequest {
+        domain: domain.to_string(),
+        value,
+        zone: resolution.provider.as_ref().and_then(provider_zone_override),
+    };
+    let mut record = ManualDnsAdapter::new().present_txt(&request)?;
+
+    // A CNAME at the challenge name moves the TXT record to its target,
+    // which may belong to another provider. acme-dns adapters write
+    // through their own CNAME, so theirs is left alone.
+    let mut cleanup_domain = domain.to_string();
+    let uses_acme_dns = resolution
+        .provider
+        .as_ref()
+        .is_some_and(|provider| provider.provider_type == "acme_dns");
+    if !uses_acme_dns && let Some(target) = delegated_challenge_name(&record.record_name) {
+        log::info!("[issuance] {} is delegated to {target}", record.record_name);
+        resolution = resolve_provider_by_zone_access(dns_store, secrets, &target)?;
+        record.zone = resolution
+            .provider
+            .as_ref()
+            .and_then(provider_zone_override)
+            .unwrap_or_else(|| derive_zone(&target));
+        let challenge_name = std::mem::replace(&mut record.record_name, target.clone());
+        record.delegated_from = Some(challenge_name);
+        cleanup_domain = target;
+    }
+
+    Ok(PlannedChallenge {
+        record,
+        resolution,
+        cleanup_domain,
+    })
+}
+
+/// Target of a CNAME at the challenge name. A failed lookup is logged and
+/// treated as no delegation.
+fn delegated_challenge_name(record_name: &str) -> Option<String> {
+    match follow_cname(record_name) {
+        Ok(target) => target,
+        Err(err) => {
+            log::warn!("[issuance] could not check {record_name} for a CNAME: {err}");
+            None
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Built-in listeners for the standalone http-01 and tls-alpn-01 solvers.
+//!
+//! One listener runs per address while any challenge is registered on it,
+//! so every name of an order can share port 80 or 443. Connections are
+//! answered one at a time from a snapshot of the registered responses.
+//! Stopping a listener waits for its thread, so the address can be bound
+//! again right away.
+
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+/// Registered responses by key: token for http-01, name for tls-alpn-01.
+pub(super) type Responses = HashMap<String, Vec<u8>>;
+pub(super) type Handler = fn(TcpStream, &Responses) -> Result<()>;
+
+const ACCEPT_POLL: Duration = Duration::from_millis(100);
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Listener {
+    responses: Arc<Mutex<Responses>>,
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+fn listeners() -> MutexGuard<'static, HashMap<String, Listener>> {
+    static LISTENERS: OnceLock<Mutex<HashMap<String, Listener>>> = OnceLock::new();
+    lock(LISTENERS.get_or_init(|| Mutex::new(HashMap::new())))
+}
+
+/// Serves `response` under `key` on `address`, starting a listener there
+/// when none runs yet.
+pub(super) fn register(
+    address: &str,
+    key: String,
+    response: Vec<u8>,
+    handler: Handler,
+) -> Result<()> {
+    let mut listeners = listeners();
+    if let Some(listener) = listeners.get(address) {
+        lock(&listener.responses).insert(key, response);
+        return Ok(());
+    }
+
+    let socket = TcpListener::bind(address)
+        .with_context(|| format!("failed to listen on {address}"))?;
+    socket.set_nonblocking(true)?;
+    let responses = Arc::new(Mutex::new(HashMap::from([(key, response)])));
+    let stop = Arc::new(AtomicBool::new(false));
+    let (thread_responses, thread_stop) = (responses.clone(), stop.clone());
+    let thread = thread::spawn(move || serve(socket, thread_responses, thread_stop, handler));
+    log::info!("[issuance] challenge listener started on {address}");
+    listeners.insert(
+        address.to_string(),
+        Listener {
+            responses,
+            stop,
+            thread,
+        },
+    );
+    Ok(())
+}
+
+/// Stops serving `key`; the listener closes with its last response, once
+/// its socket is released.
+pub(super) fn unregister(address: &str, key: &str) {
+    let mut listeners = listeners();
+    let Some(listener) = listeners.get(address) else {
+        return;
+    };
+    let idle = {
+        let mut responses = lock(&listener.responses);
+        responses.remove(key);
+        responses.is_empty()
+    };
+    if !idle {
+        return;
+    }
+    let Some(listener) = listeners.remove(address) else {
+        return;
+    };
+    listener.stop.store(true, Ordering::Relaxed);
+    // Joined under the registry lock so no register can race the socket
+    if listener.thread.join().is_err() {
+        log::warn!("[issuance] challenge listener on {address} panicked");
+    }
+    log::info!("[issuance] challenge listener on {address} stopped");
+}
+
+fn serve(
+    socket: TcpListener,
+    responses: Arc<Mutex<Responses>>,
+    stop: Arc<AtomicBool>,
+    handler: Handler,
+) {
+    while !stop.load(Ordering::Relaxed) {
+        match socket.accept() {
+            Ok((stream, peer)) => {
+                let snapshot = lock(&responses).clone();
+                let prepared = stream
+                    .set_nonblocking(false)
+                    .and_then(|()| stream.set_read_timeout(Some(IO_TIMEOUT)))
+                    .and_then(|()| stream.set_write_timeout(Some(IO_TIMEOUT)));
+                let outcome = prepared
+                    .map_err(anyhow::Error::from)
+                    .and_then(|()| handler(stream, &snapshot));
+                if let Err(err) = outcome {
+                    log::debug!("[issuance] challenge request from {peer} failed: {err:#}");
+                }
+            }
+            Err(err) if err.kind() == ErrorKind::WouldBlock => thread::sleep(ACCEPT_POLL),
+            Err(err) => {
+                log::warn!("[issuance] challenge listener accept failed: {err}");
+                thread::sleep(ACCEPT_POLL);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+
+    use super::*;
+
+    fn reply(mut stream: TcpStream, responses: &Responses) -> Result<()> {
+        let mut key = String::new();
+        stream.read_to_string(&mut key)?;
+        stream.write_all(responses.get(&key).map(Vec::as_slice).unwrap_or_default())?;
+        Ok(())
+    }
+
+    fn fetch(address: &str, key: &str) -> Result<Vec<u8>> {
+        let mut stream = TcpStream::connect(address)?;
+        stream.write_all(key.as_bytes())?;
+        stream.shutdown(std::net::Shutdown::Write)?;
+        let mut body = Vec::new();
+        stream.read_to_end(&mut body)?;
+        Ok(body)
+    }
+
+    #[test]
+    fn address_can_be_registered_again_after_unregister() -> Result<()> {
+        let address = TcpListener::bind("127.0.0.1:0")?.local_addr()?.to_string();
+
+        register(&address, "first".into(), b"one".to_vec(), reply)?;
+        register(&address, "second".into(), b"two".to_vec(), reply)?;
+        assert_eq!(fetch(&address, "second")?, b"two");
+        unregister(&address, "first");
+        assert_eq!(fetch(&address, "second")?, b"two");
+        unregister(&address, "second");
+        assert!(!listeners().contains_key(&address));
+
+        register(&address, "third".into(), b"three".to_vec(), reply)?;
+        assert_eq!(fetch(&address, "third")?, b"three");
+        unregister(&address, "third");
+        Ok(())
+    }
+}
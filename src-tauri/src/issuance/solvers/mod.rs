@@ -0,0 +1,239 @@
+//! Challenge solvers: the ways an ACME authorization can be answered.
+//!
+//! Each name in an order gets its own solver, chosen from the issuance
+//! request and kept on the pending session until the order completes. The
+//! issuance flow only talks to the [`ChallengeSolver`] trait, so adding a
+//! challenge type means adding a solver here and a variant to
+//! [`ChallengeSolverKind`].
+
+use std::cell::RefCell;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use acme_lib::order::Auth;
+use anyhow::{Context, Result, anyhow};
+
+use crate::core::types::{ChallengeSolverConfig, ChallengeSolverKind};
+use crate::issuance::dns::DnsRecordInstruction;
+use crate::issuance::dns_providers::cached_adapter_for_provider;
+use crate::issuance::flow::EphemeralPersist;
+use crate::issuance::order_polling::OrderPoller;
+use crate::issuance::propagation_presets::PropagationWaits;
+use crate::issuance::propagation_resolvers::ResolverSet;
+use crate::secrets::manager::SecretManager;
+use crate::storage::dns::{DnsConfigStore, DnsProvider};
+
+mod dns01;
+mod listener;
+mod standalone;
+mod tls_alpn;
+mod webroot;
+
+pub use dns01::{plan_challenge_record, DnsSolver, PlannedChallenge};
+pub use standalone::StandaloneHttpSolver;
+pub use tls_alpn::TlsAlpnSolver;
+pub use webroot::WebrootSolver;
+
+/// Path the CA requests http-01 tokens under.
+pub(crate) const HTTP_CHALLENGE_PATH: &str = "/.well-known/acme-challenge/";
+
+const DEFAULT_HTTP_LISTEN: &str = "0.0.0.0:80";
+const DEFAULT_TLS_LISTEN: &str = "0.0.0.0:443";
+
+/// Stores solvers need to publish and remove their responses.
+pub struct SolverContext<'a> {
+    pub dns_store: &'a DnsConfigStore,
+    pub secrets: &'a SecretManager,
+    /// TXT records dns-01 solvers queued for their provider, written
+    /// together by [`SolverContext::write_queued_txt`]
+    queued_txt: RefCell<Vec<(DnsProvider, String, String)>>,
+}
+
+impl<'a> SolverContext<'a> {
+    pub fn new(dns_store: &'a DnsConfigStore, secrets: &'a SecretManager) -> Self {
+        Self {
+            dns_store,
+            secrets,
+            queued_txt: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Queues a TXT value to be written through `provider`.
+    pub fn queue_txt(&self, provider: &DnsProvider, record_name: String, value: String) {
+        self.queued_txt
+            .borrow_mut()
+            .push((provider.clone(), record_name, value));
+    }
+
+    /// Writes the queued TXT values, one batch per provider, so an order with
+    /// many names costs each provider a single batch rather than a call per name.
+    pub fn write_queued_txt(&self) -> Result<()> {
+        let mut batches: Vec<(DnsProvider, Vec<(String, String)>)> = Vec::new();
+        for (provider, record_name, value) in self.queued_txt.take() {
+            match batches.iter_mut().find(|(queued, _)| queued.id == provider.id) {
+                Some((_, records)) => records.push((record_name, value)),
+                None => batches.push((provider, vec![(record_name, value)])),
+            }
+        }
+        for (provider, records) in batches {
+            cached_adapter_for_provider(&provider, self.secrets, self.dns_store)
+                .create_txt_batch(&records)
+                .with_context(|| format!("failed to write TXT records via {}", provider.label))?;
+        }
+        Ok(())
+    }
+}
+
+/// What presenting a challenge produced.
+#[derive(Debug, Default)]
+pub struct PresentedChallenge {
+    /// Record to show the user, for dns-01 solvers
+    pub dns_record: Option<DnsRecordInstruction>,
+    /// TXT record written through a provider as (domain, record name),
+    /// journaled so it can be removed if the app stops mid-issuance
+    pub cleanup_record: Option<(String, String)>,
+}
+
+pub trait ChallengeSolver: Send {
+    fn kind(&self) -> ChallengeSolverKind;
+    /// Publishes the response to the solver's challenge for `auth`. TXT
+    /// records may only be queued on `context`; the caller writes them with
+    /// [`SolverContext::write_queued_txt`] once every name is presented.
+    fn present(
+        &mut self,
+        auth: &Auth<EphemeralPersist>,
+        context: &SolverContext,
+    ) -> Result<PresentedChallenge>;
+    /// Waits until the CA should be able to see the response.
+    fn await_ready(
+        &self,
+        _auth: &Auth<EphemeralPersist>,
+        _waits: &PropagationWaits,
+        _resolvers: &ResolverSet,
+    ) -> Result<()> {
+        Ok(())
+    }
+    /// Asks the CA to validate the solver's challenge, unless an earlier
+    /// attempt already did.
+    fn validate(&self, auth: &Auth<EphemeralPersist>, poller: &OrderPoller) -> Result<()>;
+    /// Removes the response, returning a description of anything that could
+    /// not be removed. Failures are logged, not raised.
+    fn cleanup(&mut self, context: &SolverContext) -> Vec<String>;
+}
+
+/// Builds the solver for one name. Without a configuration the name uses
+/// dns-01 through its provider, falling back to manual records.
+pub fn solver_for(config: Option<&ChallengeSolverConfig>) -> Result<Box<dyn ChallengeSolver>> {
+    let Some(config) = config else {
+        return Ok(Box::new(DnsSolver::new(false)));
+    };
+    Ok(match config.kind {
+        ChallengeSolverKind::Dns01Provider => Box::new(DnsSolver::new(false)),
+        ChallengeSolverKind::Dns01Manual => Box::new(DnsSolver::new(true)),
+        ChallengeSolverKind::Http01Webroot => {
+            let webroot = config
+                .webroot
+                .as_deref()
+                .map(str::trim)
+                .filter(|path| !path.is_empty())
+                .ok_or_else(|| anyhow!("A web root folder is required for http-01 webroot"))?;
+            let webroot = PathBuf::from(webroot);
+            if !webroot.is_dir() {
+                return Err(anyhow!("Web root {} is not a folder", webroot.display()));
+            }
+            Box::new(WebrootSolver::new(webroot))
+        }
+        ChallengeSolverKind::Http01Standalone => Box::new(StandaloneHttpSolver::new(
+            listen_address(config.listen.as_deref(), DEFAULT_HTTP_LISTEN)?,
+        )),
+        ChallengeSolverKind::TlsAlpn01 => Box::new(TlsAlpnSolver::new(listen_address(
+            config.listen.as_deref(),
+            DEFAULT_TLS_LISTEN,
+        )?)),
+    })
+}
+
+fn listen_address(raw: Option<&str>, default: &str) -> Result<String> {
+    let address = raw.map(str::trim).filter(|raw| !raw.is_empty()).unwrap_or(default);
+    address
+        .parse::<SocketAddr>()
+        .map_err(|_| anyhow!("Listen address must look like {default}, got {address}"))?;
+    Ok(address.to_string())
+}
+
+/// Name an authorization is for, with `*.` for wildcard names, as it is
+/// written in the issuance request.
+pub fn auth_name(auth: &Auth<EphemeralPersist>) -> String {
+    if auth.api_auth().wildcard == Some(true) {
+        format!("*.{}", auth.domain_name())
+    } else {
+        auth.domain_name().to_string()
+    }
+}
+
+/// Fails unless the CA offered `challenge_type` for `auth`. CAs only offer
+/// dns-01 for wildcard names, which gets its own hint.
+fn ensure_offered(
+    auth: &Auth<EphemeralPersist>,
+    kind: ChallengeSolverKind,
+    challenge_type: &str,
+) -> Result<()> {
+    let api_auth = auth.api_auth();
+    if api_auth.wildcard == Some(true) {
+        return Err(anyhow!(
+            "{} cannot validate the wildcard name {}; use dns-01",
+            describe(kind),
+            auth_name(auth)
+        ));
+    }
+    if !api_auth.challenges.iter().any(|challenge| challenge._type == challenge_type) {
+        return Err(anyhow!(
+            "The CA did not offer {challenge_type} for {}",
+            auth.domain_name()
+        ));
+    }
+    Ok(())
+}
+
+pub fn describe(kind: ChallengeSolverKind) -> &'static str {
+    match kind {
+        ChallengeSolverKind::Dns01Provider => "dns-01",
+        ChallengeSolverKind::Dns01Manual => "dns-01 (manual)",
+        ChallengeSolverKind::Http01Webroot => "http-01 (webroot)",
+        ChallengeSolverKind::Http01Standalone => "http-01 (standalone)",
+        ChallengeSolverKind::TlsAlpn01 => "tls-alpn-01",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(kind: ChallengeSolverKind) -> ChallengeSolverConfig {
+        ChallengeSolverConfig {
+            kind,
+            webroot: None,
+            listen: None,
+        }
+    }
+
+    #[test]
+    fn builds_solvers_from_config() {
+        assert_eq!(solver_for(None).unwrap().kind(), ChallengeSolverKind::Dns01Provider);
+        let manual = config(ChallengeSolverKind::Dns01Manual);
+        assert_eq!(solver_for(Some(&manual)).unwrap().kind(), ChallengeSolverKind::Dns01Manual);
+
+        let mut webroot = config(ChallengeSolverKind::Http01Webroot);
+        assert!(solver_for(Some(&webroot)).is_err());
+        webroot.webroot = Some(std::env::temp_dir().display().to_string());
+        assert!(solver_for(Some(&webroot)).is_ok());
+
+        let mut standalone = config(ChallengeSolverKind::Http01Standalone);
+        assert!(solver_for(Some(&standalone)).is_ok());
+        standalone.listen = Some("localhost".to_string());
+        assert!(solver_for(Some(&standalone)).is_err());
+        let mut tls = config(ChallengeSolverKind::TlsAlpn01);
+        tls.listen = Some("127.0.0.1:8443".to_string());
+        assert_eq!(solver_for(Some(&tls)).unwrap().kind(), ChallengeSolverKind::TlsAlpn01);
+    }
+}
@@ -0,0 +1,129 @@
+//! http-01 standalone: a built-in listener answers the token request, for
+//! hosts with no web server of their own on port 80.
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+use acme_lib::order::Auth;
+use anyhow::Result;
+
+use crate::core::types::ChallengeSolverKind;
+use crate::issuance::acme_workflow;
+use crate::issuance::flow::EphemeralPersist;
+use crate::issuance::order_polling::OrderPoller;
+
+use super::listener::{self, Responses};
+use super::webroot::check_token;
+use super::{
+    ChallengeSolver, HTTP_CHALLENGE_PATH, PresentedChallenge, SolverContext, ensure_offered,
+};
+
+const NOT_FOUND: &[u8] =
+    b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+pub struct StandaloneHttpSolver {
+    address: String,
+    /// Token registered on the listener
+    token: Option<String>,
+}
+
+impl StandaloneHttpSolver {
+    pub fn new(address: String) -> Self {
+        Self {
+            address,
+            token: None,
+        }
+    }
+}
+
+/// Answers one `GET /.well-known/acme-challenge/<token>` request.
+fn answer(mut stream: TcpStream, responses: &Responses) -> Result<()> {
+    let mut buffer = [0u8; 4096];
+    let read = stream.read(&mut buffer)?;
+    let request = String::from_utf8_lossy(&buffer[..read]);
+    let mut request_line = request.lines().next().unwrap_or_default().split(' ');
+    let body = match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some(path)) => path
+            .strip_prefix(HTTP_CHALLENGE_PATH)
+            .and_then(|token| responses.get(token)),
+        _ => None,
+    };
+    let response = match body {
+        Some(body) => {
+            let mut response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n",
+                body.len()
+            )
+            .into_bytes();
+            response.extend_from_slice(body);
+            response
+        }
+        None => NOT_FOUND.to_vec(),
+    };
+    stream.write_all(&response)?;
+    Ok(())
+}
+
+impl ChallengeSolver for StandaloneHttpSolver {
+    fn kind(&self) -> ChallengeSolverKind {
+        ChallengeSolverKind::Http01Standalone
+    }
+
+    fn present(
+        &mut self,
+        auth: &Auth<EphemeralPersist>,
+        _context: &SolverContext,
+    ) -> Result<PresentedChallenge> {
+        ensure_offered(auth, self.kind(), "http-01")?;
+        let challenge = auth.http_challenge();
+        let token = challenge.http_token().to_string();
+        check_token(&token)?;
+        listener::register(
+            &self.address,
+            token.clone(),
+            challenge.http_proof().into_bytes(),
+            answer,
+        )?;
+        self.token = Some(token);
+        Ok(PresentedChallenge::default())
+    }
+
+    fn validate(&self, auth: &Auth<EphemeralPersist>, poller: &OrderPoller) -> Result<()> {
+        acme_workflow::validate_challenge(auth.http_challenge(), poller)
+    }
+
+    fn cleanup(&mut self, _context: &SolverContext) -> Vec<String> {
+        if let Some(token) = self.token.take() {
+            listener::unregister(&self.address, &token);
+        }
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn answers_registered_tokens_only() {
+        let socket = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = socket.local_addr().unwrap();
+        let responses = Responses::from([("abc".to_string(), b"abc.thumbprint".to_vec())]);
+        let fetch = |path: &str| {
+            let mut client = TcpStream::connect(address).unwrap();
+            write!(client, "GET {path} HTTP/1.1\r\nHost: example.com\r\n\r\n").unwrap();
+            let (stream, _) = socket.accept().unwrap();
+            answer(stream, &responses).unwrap();
+            let mut reply = String::new();
+            client.read_to_string(&mut reply).unwrap();
+            reply
+        };
+
+        let found = fetch("/.well-known/acme-challenge/abc");
+        assert!(found.starts_with("HTTP/1.1 200"));
+        assert!(found.ends_with("\r\n\r\nabc.thumbprint"));
+        assert!(fetch("/.well-known/acme-challenge/other").starts_with("HTTP/1.1 404"));
+    }
+}
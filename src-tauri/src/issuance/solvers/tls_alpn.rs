@@ -0,0 +1,137 @@
+//! tls-alpn-01: a built-in TLS listener presents a self-signed certificate
+//! carrying the key authorization digest to clients asking for `acme-tls/1`.
+
+use std::net::TcpStream;
+use std::sync::Arc;
+
+use acme_lib::order::Auth;
+use anyhow::{Result, anyhow};
+use openssl::{
+    pkey::PKey,
+    ssl::{self, AlpnError, NameType, SniError, SslAcceptor, SslMethod},
+    x509::X509,
+};
+use rcgen::{CertificateParams, CustomExtension, KeyPair};
+
+use crate::core::types::ChallengeSolverKind;
+use crate::issuance::acme_workflow;
+use crate::issuance::flow::EphemeralPersist;
+use crate::issuance::order_polling::OrderPoller;
+
+use super::listener::{self, Responses};
+use super::{ChallengeSolver, PresentedChallenge, SolverContext, ensure_offered};
+
+/// ALPN protocol list offering only `acme-tls/1`, in wire format.
+const ACME_TLS_ALPN: &[u8] = b"\x0aacme-tls/1";
+
+pub struct TlsAlpnSolver {
+    address: String,
+    /// Name registered on the listener
+    domain: Option<String>,
+}
+
+impl TlsAlpnSolver {
+    pub fn new(address: String) -> Self {
+        Self {
+            address,
+            domain: None,
+        }
+    }
+}
+
+/// Certificate and key PEM for the challenge response to `domain`.
+fn challenge_certificate(domain: &str, proof: &[u8; 32]) -> Result<Vec<u8>> {
+    let mut params = CertificateParams::new(vec![domain.to_string()])?;
+    params.custom_extensions = vec![CustomExtension::new_acme_identifier(proof)];
+    let key = KeyPair::generate()?;
+    let cert = params.self_signed(&key)?;
+    Ok(format!("{}{}", cert.pem(), key.serialize_pem()).into_bytes())
+}
+
+/// Completes one handshake, choosing the certificate by SNI.
+fn answer(stream: TcpStream, responses: &Responses) -> Result<()> {
+    let responses = Arc::new(responses.clone());
+    let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls_server())?;
+    builder.set_alpn_select_callback(|_, client| {
+        ssl::select_next_proto(ACME_TLS_ALPN, client).ok_or(AlpnError::ALERT_FATAL)
+    });
+    builder.set_servername_callback(move |ssl, _alert| {
+        let name = ssl.servername(NameType::HOST_NAME).map(str::to_ascii_lowercase);
+        let Some(pem) = name.as_deref().and_then(|name| responses.get(name)) else {
+            return Err(SniError::ALERT_FATAL);
+        };
+        let cert = X509::from_pem(pem).map_err(|_| SniError::ALERT_FATAL)?;
+        let key = PKey::private_key_from_pem(pem).map_err(|_| SniError::ALERT_FATAL)?;
+        ssl.set_certificate(&cert).map_err(|_| SniError::ALERT_FATAL)?;
+        ssl.set_private_key(&key).map_err(|_| SniError::ALERT_FATAL)?;
+        Ok(())
+    });
+    let mut tls = builder
+        .build()
+        .accept(stream)
+        .map_err(|err| anyhow!("TLS handshake failed: {err}"))?;
+    // The CA only needs the handshake; it sends no request.
+    let _ = tls.shutdown();
+    Ok(())
+}
+
+impl ChallengeSolver for TlsAlpnSolver {
+    fn kind(&self) -> ChallengeSolverKind {
+        ChallengeSolverKind::TlsAlpn01
+    }
+
+    fn present(
+        &mut self,
+        auth: &Auth<EphemeralPersist>,
+        _context: &SolverContext,
+    ) -> Result<PresentedChallenge> {
+        ensure_offered(auth, self.kind(), "tls-alpn-01")?;
+        let domain = auth.domain_name().to_ascii_lowercase();
+        let response = challenge_certificate(&domain, &auth.tls_alpn_challenge().tls_alpn_proof())?;
+        listener::register(&self.address, domain.clone(), response, answer)?;
+        self.domain = Some(domain);
+        Ok(PresentedChallenge::default())
+    }
+
+    fn validate(&self, auth: &Auth<EphemeralPersist>, poller: &OrderPoller) -> Result<()> {
+        acme_workflow::validate_challenge(auth.tls_alpn_challenge(), poller)
+    }
+
+    fn cleanup(&mut self, _context: &SolverContext) -> Vec<String> {
+        if let Some(domain) = self.domain.take() {
+            listener::unregister(&self.address, &domain);
+        }
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::ssl::{SslConnector, SslVerifyMode};
+    use std::net::TcpListener;
+
+    #[test]
+    fn presents_challenge_certificate_for_acme_tls() {
+        let socket = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = socket.local_addr().unwrap();
+        let response = challenge_certificate("example.com", &[7; 32]).unwrap();
+        let responses = Responses::from([("example.com".to_string(), response)]);
+        let server = std::thread::spawn(move || {
+            let (stream, _) = socket.accept().unwrap();
+            answer(stream, &responses).unwrap();
+        });
+
+        let mut connector = SslConnector::builder(SslMethod::tls_client()).unwrap();
+        connector.set_verify(SslVerifyMode::NONE);
+        connector.set_alpn_protos(ACME_TLS_ALPN).unwrap();
+        let stream = TcpStream::connect(address).unwrap();
+        let tls = connector.build().connect("example.com", stream).unwrap();
+        assert_eq!(tls.ssl().selected_alpn_protocol(), Some(&b"acme-tls/1"[..]));
+        let cert = tls.ssl().peer_certificate().unwrap();
+        let names = cert.subject_alt_names().unwrap();
+        assert_eq!(names.iter().next().and_then(|name| name.dnsname()), Some("example.com"));
+        drop(tls);
+        server.join().unwrap();
+    }
+}
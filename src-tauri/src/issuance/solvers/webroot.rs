@@ -0,0 +1,127 @@
+//! http-01 webroot: the token file is written under a folder the name's
+//! existing web server already serves.
+
+use std::fs;
+use std::path::PathBuf;
+
+use acme_lib::order::Auth;
+use anyhow::{Context, Result, anyhow};
+
+use crate::core::types::ChallengeSolverKind;
+use crate::issuance::acme_workflow;
+use crate::issuance::flow::EphemeralPersist;
+use crate::issuance::order_polling::OrderPoller;
+
+use super::{
+    ChallengeSolver, HTTP_CHALLENGE_PATH, PresentedChallenge, SolverContext, ensure_offered,
+};
+
+pub struct WebrootSolver {
+    webroot: PathBuf,
+    /// Token file written by `present`
+    written: Option<PathBuf>,
+}
+
+impl WebrootSolver {
+    pub fn new(webroot: PathBuf) -> Self {
+        Self {
+            webroot,
+            written: None,
+        }
+    }
+
+    /// Writes `proof` as the file for `token` under the challenge folder.
+    fn write_token(&mut self, token: &str, proof: &str) -> Result<PathBuf> {
+        check_token(token)?;
+        let folder = self.webroot.join(HTTP_CHALLENGE_PATH.trim_matches('/'));
+        fs::create_dir_all(&folder)
+            .with_context(|| format!("failed to create {}", folder.display()))?;
+        let path = folder.join(token);
+        fs::write(&path, proof).with_context(|| format!("failed to write {}", path.display()))?;
+        self.written = Some(path.clone());
+        Ok(path)
+    }
+
+    /// Removes the token file written last, if any.
+    fn remove_token(&mut self) -> Vec<String> {
+        let Some(path) = self.written.take() else {
+            return Vec::new();
+        };
+        match fs::remove_file(&path) {
+            Ok(()) => Vec::new(),
+            Err(err) => {
+                log::warn!("[issuance] failed to remove {}: {err}", path.display());
+                vec![format!("{}: {err}", path.display())]
+            }
+        }
+    }
+}
+
+/// Tokens are base64url; anything else could escape the challenge folder.
+pub(super) fn check_token(token: &str) -> Result<()> {
+    let valid = !token.is_empty()
+        && token
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow!("The CA sent an invalid http-01 token"))
+    }
+}
+
+impl ChallengeSolver for WebrootSolver {
+    fn kind(&self) -> ChallengeSolverKind {
+        ChallengeSolverKind::Http01Webroot
+    }
+
+    fn present(
+        &mut self,
+        auth: &Auth<EphemeralPersist>,
+        _context: &SolverContext,
+    ) -> Result<PresentedChallenge> {
+        ensure_offered(auth, self.kind(), "http-01")?;
+        let challenge = auth.http_challenge();
+        let path = self.write_token(challenge.http_token(), &challenge.http_proof())?;
+        log::info!(
+            "[issuance] wrote http-01 token for {} to {}",
+            auth.domain_name(),
+            path.display()
+        );
+        Ok(PresentedChallenge::default())
+    }
+
+    fn validate(&self, auth: &Auth<EphemeralPersist>, poller: &OrderPoller) -> Result<()> {
+        acme_workflow::validate_challenge(auth.http_challenge(), poller)
+    }
+
+    fn cleanup(&mut self, _context: &SolverContext) -> Vec<String> {
+        self.remove_token()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn token_file_is_written_under_the_challenge_path_and_removed() -> Result<()> {
+        let webroot = std::env::temp_dir().join(format!("sslboard-webroot-{}", Uuid::new_v4()));
+        fs::create_dir_all(&webroot)?;
+        let mut solver = WebrootSolver::new(webroot.clone());
+
+        assert!(solver.write_token("../escape", "proof").is_err());
+        let path = solver.write_token("tok_en-1", "tok_en-1.thumbprint")?;
+        assert_eq!(path, webroot.join(".well-known/acme-challenge/tok_en-1"));
+        assert_eq!(fs::read_to_string(&path)?, "tok_en-1.thumbprint");
+
+        assert!(solver.remove_token().is_empty());
+        assert!(!path.exists());
+        assert!(solver.remove_token().is_empty());
+
+        fs::remove_dir_all(&webroot)?;
+        Ok(())
+    }
+}
@@ -6,37 +6,68 @@ mod secrets;
 mod storage;
 
 use core::commands::{
-    complete_managed_issuance, create_issuer, delete_issuer, dns_provider_create,
-    dns_provider_delete, dns_provider_list, dns_provider_test, dns_provider_update,
-    dns_resolve_provider, export_certificate_pem, get_certificate, get_preference,
-    list_certificates, list_issuers, list_secret_refs, lock_vault, select_issuer, set_preference,
-    start_managed_issuance, update_issuer,
+    add_certificate_usage, apply_recovery_action, cancel_dns_propagation_watch,
+    certificate_verification_qr, check_authoritative_propagation, check_certificate_usages,
+    check_for_updates, check_issuer_clock_skew, cleanup_provider_test_records, clear_metrics,
+    clear_provider_debug_log, complete_managed_issuance, create_backup, create_issuer,
+    delete_issuer, deploy_certificate, deploy_target_create, deploy_target_delete,
+    deploy_target_list, deploy_target_update, discover_provider_zones, dns_propagation_presets,
+    dns_provider_account, dns_provider_create, dns_provider_delete, dns_provider_export,
+    dns_provider_health_history, dns_provider_import, dns_provider_list,
+    dns_provider_set_priority, dns_provider_test, dns_provider_update, dns_resolve_provider,
+    export_certificate_pem, export_certificates_bulk, export_issuance_artifacts,
+    export_key_escrow, generate_certificate_report, get_background_status, get_certificate,
+    get_certificate_text, get_config_issues, get_confirmation_pin_status, get_demo_mode,
+    get_endpoint_timeline, get_managed_key_directory, get_metrics, get_preference,
+    get_provider_debug_log, get_read_only_status, import_certificate,
+    import_dns_providers_from_env, list_certificate_usages, list_certificates,
+    list_issuance_artifacts, list_issuers, list_recovery_actions, list_secret_refs, lock_vault,
+    parse_domain_list, preflight_domains, prepare_dns_challenges, preview_dns_provider_import,
+    promote_to_production, recover_escrowed_key, reload_app_state, remove_certificate_usage,
+    repair_config_issue, run_background_tasks_now, select_issuer, set_confirmation_pin,
+    set_demo_mode, set_key_export_policy, set_managed_key_directory, set_preference,
+    start_managed_issuance, suggest_issuer, take_pending_deep_link, update_issuer,
+    validate_domains, verify_backup, verify_export, watch_dns_propagation, write_status_feed,
 };
+use core::confirmation_pin::ConfirmationPin;
+use core::read_only::ReadOnlyMode;
 use secrets::manager::SecretManager;
 use std::sync::Once;
+use tauri_plugin_deep_link::DeepLinkExt;
 use storage::{
     db::Db,
+    demo,
+    deploy::DeployTargetStore,
     dns::DnsConfigStore, inventory::InventoryStore, issuer::IssuerConfigStore,
+    jobs::JobStore,
+    metrics::MetricsStore,
     preferences::PreferencesStore,
+    provider_health::ProviderHealthStore,
+    usages::CertificateUsageStore,
 };
 use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     init_logging();
-    if let Err(err) = tauri::Builder::default()
+    // Single-instance goes first so links opened while the app runs reach it.
+    let builder = tauri::Builder::default()
+        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.set_focus();
+            }
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_dialog::init())
-        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_opener::init());
+    #[cfg(feature = "updater")]
+    let builder = builder.plugin(tauri_plugin_updater::Builder::new().build());
+    if let Err(err) = builder
         .setup(|app| {
             let db = Db::initialize(app.handle().clone())?;
             app.manage(db.clone());
 
             let inventory_store = InventoryStore::initialize(db.clone())?;
-            if cfg!(debug_assertions) {
-                // Seed a demo record for manual testing in development builds.
-                inventory_store.seed_dev_certificate()?;
-            }
-            app.manage(inventory_store);
 
             let secret_manager = SecretManager::initialize(app.handle().clone(), db.clone())?;
             app.manage(secret_manager);
@@ -47,18 +78,87 @@ pub fn run() {
             let dns_store = DnsConfigStore::initialize(db.clone())?;
             app.manage(dns_store);
 
-            let preferences_store = PreferencesStore::initialize(db)?;
+            let deploy_store = DeployTargetStore::initialize(db.clone())?;
+            app.manage(deploy_store);
+
+            let job_store = JobStore::initialize(db.clone())?;
+            let interrupted = job_store.mark_interrupted()?;
+            if interrupted > 0 {
+                log::warn!("[jobs] {interrupted} operation(s) interrupted by the last shutdown");
+            }
+            app.manage(job_store);
+
+            let usage_store = CertificateUsageStore::initialize(db.clone())?;
+            app.manage(usage_store);
+
+            let health_store = ProviderHealthStore::initialize(db.clone())?;
+            app.manage(health_store);
+
+            let metrics_store = MetricsStore::initialize(db.clone())?;
+            core::metrics::install(metrics_store.clone());
+            app.manage(metrics_store);
+
+            let preferences_store = PreferencesStore::initialize(db.clone())?;
+            core::user_agent::install(preferences_store.clone());
+            core::state_reload::apply_managed_key_directory(
+                &app.state::<SecretManager>(),
+                &preferences_store,
+            )?;
+            if demo::is_enabled(&preferences_store)? {
+                // Refresh demo data so relative dates (expiring/expired) stay meaningful.
+                demo::seed_demo_data(&db, &inventory_store)?;
+            }
+            app.manage(inventory_store);
+            app.manage(ReadOnlyMode::from_args(preferences_store.clone()));
+            app.manage(ConfirmationPin::new(preferences_store.clone()));
             app.manage(preferences_store);
+
+            core::updates::spawn_scheduled_check(app.handle().clone());
+            core::reports::spawn_scheduled_reports(app.handle().clone());
+            core::backups::spawn_scheduled_backups(app.handle().clone());
+            core::status_feed::spawn_scheduled_status_feed(app.handle().clone());
+            core::provider_health::spawn_scheduled_health_checks(app.handle().clone());
+            issuance::session_gc::spawn_session_gc(app.handle().clone());
+            core::config_check::spawn_startup_check(app.handle().clone());
+            register_deep_links(app)?;
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             list_certificates,
             get_certificate,
+            get_certificate_text,
+            import_certificate,
             export_certificate_pem,
+            export_certificates_bulk,
+            verify_export,
+            certificate_verification_qr,
+            export_key_escrow,
+            recover_escrowed_key,
+            generate_certificate_report,
+            write_status_feed,
+            create_backup,
+            verify_backup,
+            deploy_target_list,
+            deploy_target_create,
+            deploy_target_update,
+            deploy_target_delete,
+            deploy_certificate,
+            list_certificate_usages,
+            add_certificate_usage,
+            remove_certificate_usage,
+            check_certificate_usages,
+            get_endpoint_timeline,
+            list_recovery_actions,
+            get_config_issues,
+            repair_config_issue,
+            apply_recovery_action,
             list_secret_refs,
             lock_vault,
+            get_managed_key_directory,
+            set_managed_key_directory,
             list_issuers,
             select_issuer,
+            suggest_issuer,
             create_issuer,
             update_issuer,
             delete_issuer,
@@ -66,12 +166,48 @@ pub fn run() {
             dns_provider_create,
             dns_provider_update,
             dns_provider_delete,
+            dns_provider_set_priority,
             dns_provider_test,
+            dns_provider_account,
+            dns_provider_export,
+            dns_provider_import,
+            preview_dns_provider_import,
+            discover_provider_zones,
             dns_resolve_provider,
+            dns_propagation_presets,
+            get_provider_debug_log,
+            clear_provider_debug_log,
+            check_issuer_clock_skew,
+            preflight_domains,
+            prepare_dns_challenges,
+            list_issuance_artifacts,
+            export_issuance_artifacts,
+            validate_domains,
+            parse_domain_list,
             start_managed_issuance,
+            promote_to_production,
             complete_managed_issuance,
             get_preference,
-            set_preference
+            set_preference,
+            get_read_only_status,
+            get_confirmation_pin_status,
+            set_confirmation_pin,
+            set_key_export_policy,
+            get_demo_mode,
+            set_demo_mode,
+            check_for_updates,
+            get_metrics,
+            clear_metrics,
+            reload_app_state,
+            get_background_status,
+            run_background_tasks_now,
+            cleanup_provider_test_records,
+            dns_provider_health_history,
+            import_dns_providers_from_env,
+            watch_dns_propagation,
+            cancel_dns_propagation_watch,
+            check_authoritative_propagation,
+            take_pending_deep_link
         ])
         .run(tauri::generate_context!())
     {
@@ -79,6 +215,26 @@ pub fn run() {
     }
 }
 
+/// Routes `sslboard://` links, including the one that launched the app.
+fn register_deep_links(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    // Linux and Windows only pick up the scheme once registered at runtime.
+    #[cfg(any(target_os = "linux", windows))]
+    app.deep_link().register_all()?;
+
+    let handle = app.handle().clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            core::deep_links::handle_deep_link(&handle, url.as_str());
+        }
+    });
+    if let Some(urls) = app.deep_link().get_current()? {
+        for url in urls {
+            core::deep_links::handle_deep_link(app.handle(), url.as_str());
+        }
+    }
+    Ok(())
+}
+
 fn init_logging() {
     static INIT: Once = Once::new();
     INIT.call_once(|| {
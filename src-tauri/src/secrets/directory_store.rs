@@ -0,0 +1,135 @@
+//! Managed key files in a user-chosen directory.
+//!
+//! Some backup setups need key material in a specific location. When a key
+//! directory is configured, new managed keys are written there as
+//! `<secret id>.key.enc`, encrypted with the vault master key exactly like the
+//! database ciphertext. The metadata row records the backend and directory so
+//! existing keys keep resolving after the setting changes.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+
+use anyhow::{Context, Result, anyhow};
+
+use super::{
+    locked::LockedBuffer,
+    store::{SecretStoreError, open, seal},
+    vault::MasterKeyVault,
+};
+
+/// Preference holding the key directory; empty or absent keeps keys in the vault.
+pub const MANAGED_KEY_DIRECTORY_PREFERENCE: &str = "managed_key_directory";
+
+const KEY_FILE_SUFFIX: &str = ".key.enc";
+
+pub struct DirectorySecretStore {
+    vault: Arc<MasterKeyVault>,
+}
+
+impl DirectorySecretStore {
+    pub fn new(vault: Arc<MasterKeyVault>) -> Self {
+        Self { vault }
+    }
+
+    pub fn store(&self, dir: &Path, id: &str, value: &[u8]) -> Result<(), SecretStoreError> {
+        let payload = seal(&self.vault, value)?;
+        let path = key_path(dir, id);
+        let temp = dir.join(format!(".{id}{KEY_FILE_SUFFIX}.tmp"));
+        write_private_file(&temp, &payload)
+            .and_then(|()| {
+                fs::rename(&temp, &path)
+                    .with_context(|| format!("failed to move key file into {}", path.display()))
+            })
+            .map_err(|err| {
+                let _ = fs::remove_file(&temp);
+                SecretStoreError::Store(format!("{err:#}"))
+            })
+    }
+
+    pub fn retrieve(&self, dir: &Path, id: &str) -> Result<LockedBuffer, SecretStoreError> {
+        let path = key_path(dir, id);
+        let payload = match fs::read(&path) {
+            Ok(payload) => payload,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return Err(SecretStoreError::NotFound(id.to_string()));
+            }
+            Err(err) => {
+                return Err(SecretStoreError::Unavailable(format!(
+                    "failed to read {}: {err}",
+                    path.display()
+                )));
+            }
+        };
+        open(&self.vault, &payload)
+    }
+
+    pub fn delete(&self, dir: &Path, id: &str) -> Result<(), SecretStoreError> {
+        match fs::remove_file(key_path(dir, id)) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(SecretStoreError::Store(err.to_string())),
+        }
+    }
+
+    pub fn exists(&self, dir: &Path, id: &str) -> Result<bool, SecretStoreError> {
+        key_path(dir, id)
+            .try_exists()
+            .map_err(|err| SecretStoreError::Unavailable(format!("{}: {err}", dir.display())))
+    }
+}
+
+fn key_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{id}{KEY_FILE_SUFFIX}"))
+}
+
+fn write_private_file(path: &Path, content: &[u8]) -> Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    file.write_all(content)
+        .and_then(|()| file.sync_all())
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Checks that `raw` names an existing, writable directory and returns it.
+pub fn validate_key_directory(raw: &str) -> Result<PathBuf> {
+    let dir = PathBuf::from(raw.trim());
+    if !dir.is_absolute() {
+        return Err(anyhow!("key directory must be an absolute path"));
+    }
+    if !dir.is_dir() {
+        return Err(anyhow!("key directory does not exist: {}", dir.display()));
+    }
+    let probe = dir.join(".sslboard-write-test");
+    write_private_file(&probe, b"")
+        .and_then(|()| fs::remove_file(&probe).context("failed to remove write test file"))
+        .with_context(|| format!("key directory is not writable: {}", dir.display()))?;
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_key_directory() {
+        let dir = std::env::temp_dir().join(format!("sslboard-keys-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+
+        assert_eq!(validate_key_directory(dir.to_str().unwrap()).unwrap(), dir);
+        assert!(fs::read_dir(&dir).unwrap().next().is_none());
+        assert!(validate_key_directory("relative/keys").is_err());
+        assert!(validate_key_directory(dir.join("missing").to_str().unwrap()).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
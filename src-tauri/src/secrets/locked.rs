@@ -0,0 +1,112 @@
+//! Best-effort memory locking for key material.
+//!
+//! [`LockedBuffer`] pins its pages with `mlock` (Unix) or `VirtualLock`
+//! (Windows) so the master key and decrypted secrets are less likely to be
+//! written to swap. Locking can fail when the process exceeds its locked
+//! memory limit (`RLIMIT_MEMLOCK`); the buffer still works, it is just not
+//! pinned. Locks are per page and not reference counted, so unlocking one
+//! buffer can unpin a neighbour that shares its page.
+
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use zeroize::{Zeroize, Zeroizing};
+
+/// Zeroized-on-drop byte buffer whose pages are locked in memory when permitted.
+pub struct LockedBuffer {
+    bytes: Zeroizing<Vec<u8>>,
+    locked: bool,
+}
+
+impl LockedBuffer {
+    pub fn new(bytes: Zeroizing<Vec<u8>>) -> Self {
+        let locked = !bytes.is_empty() && lock_pages(bytes.as_ptr(), bytes.len());
+        if !locked && !bytes.is_empty() {
+            warn_once();
+        }
+        Self { bytes, locked }
+    }
+}
+
+impl From<Vec<u8>> for LockedBuffer {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(Zeroizing::new(bytes))
+    }
+}
+
+impl Deref for LockedBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl Drop for LockedBuffer {
+    fn drop(&mut self) {
+        // The buffer is never resized after construction, so this is the locked range.
+        let (ptr, len) = (self.bytes.as_ptr(), self.bytes.len());
+        self.bytes.zeroize();
+        if self.locked {
+            unlock_pages(ptr, len);
+        }
+    }
+}
+
+fn warn_once() {
+    static WARNED: AtomicBool = AtomicBool::new(false);
+    if !WARNED.swap(true, Ordering::Relaxed) {
+        log::warn!("[secrets] memory locking unavailable; key material may be swapped to disk");
+    }
+}
+
+#[cfg(unix)]
+fn lock_pages(ptr: *const u8, len: usize) -> bool {
+    // SAFETY: the range is a live allocation owned by the caller.
+    unsafe { libc::mlock(ptr.cast(), len) == 0 }
+}
+
+#[cfg(unix)]
+fn unlock_pages(ptr: *const u8, len: usize) {
+    // SAFETY: the range was locked by `lock_pages` and is still allocated.
+    unsafe {
+        libc::munlock(ptr.cast(), len);
+    }
+}
+
+#[cfg(windows)]
+fn lock_pages(ptr: *const u8, len: usize) -> bool {
+    use windows_sys::Win32::System::Memory::VirtualLock;
+    // SAFETY: the range is a live allocation owned by the caller.
+    unsafe { VirtualLock(ptr.cast(), len) != 0 }
+}
+
+#[cfg(windows)]
+fn unlock_pages(ptr: *const u8, len: usize) {
+    use windows_sys::Win32::System::Memory::VirtualUnlock;
+    // SAFETY: the range was locked by `lock_pages` and is still allocated.
+    unsafe {
+        VirtualUnlock(ptr.cast(), len);
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn lock_pages(_ptr: *const u8, _len: usize) -> bool {
+    false
+}
+
+#[cfg(not(any(unix, windows)))]
+fn unlock_pages(_ptr: *const u8, _len: usize) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exposes_bytes_whether_or_not_locked() {
+        let buffer = LockedBuffer::from(vec![7u8; 32]);
+        assert_eq!(&*buffer, &[7u8; 32][..]);
+        let empty = LockedBuffer::from(Vec::new());
+        assert!(!empty.locked);
+    }
+}
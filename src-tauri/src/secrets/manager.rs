@@ -1,4 +1,5 @@
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 
 use anyhow::Result;
 use chrono::Utc;
@@ -6,14 +7,17 @@ use log::{error, info, warn};
 use uuid::Uuid;
 
 use super::{
+    directory_store::DirectorySecretStore,
+    managed_keys,
     metadata::SecretMetadataStore,
-    store::{EncryptedSecretStore, SecretStore, SecretStoreError},
-    types::{SecretKind, SecretMetadata},
+    store::{self, EncryptedSecretStore, SecretStore, SecretStoreError},
+    types::{SecretKind, SecretMetadata, SecretStorageBackend},
     vault::MasterKeyVault,
     create_master_key_store,
 };
 use tauri::Emitter;
 use zeroize::Zeroizing;
+use crate::core::types::{KeyAlgorithm, KeyCurve};
 use crate::storage::db::Db;
 
 #[derive(thiserror::Error, Debug)]
@@ -30,6 +34,8 @@ pub enum SecretError {
     InvalidReference(String),
     #[error("secret vault is locked: {0}")]
     Locked(String),
+    #[error("key generation failed: {0}")]
+    KeyGeneration(String),
 }
 
 impl From<SecretStoreError> for SecretError {
@@ -49,6 +55,9 @@ impl From<SecretStoreError> for SecretError {
 #[derive(Clone)]
 pub struct SecretManager {
     store: Arc<dyn SecretStore>,
+    directory_store: Arc<DirectorySecretStore>,
+    /// When set, new managed keys are written here instead of the database.
+    key_directory: Arc<RwLock<Option<PathBuf>>>,
     metadata: SecretMetadataStore,
     vault: Arc<MasterKeyVault>,
     app: tauri::AppHandle,
@@ -62,9 +71,12 @@ impl SecretManager {
         let vault = Arc::new(MasterKeyVault::new(master_key_store));
         let encrypted_store: Arc<dyn SecretStore> =
             Arc::new(EncryptedSecretStore::new(metadata.clone(), vault.clone()));
+        let directory_store = Arc::new(DirectorySecretStore::new(vault.clone()));
 
         Ok(Self {
             store: encrypted_store,
+            directory_store,
+            key_directory: Arc::new(RwLock::new(None)),
             metadata,
             vault,
             app: app.clone(),
@@ -84,27 +96,76 @@ impl SecretManager {
         label: String,
         secret_value: String,
     ) -> Result<SecretMetadata, SecretError> {
-        self.ensure_unlocked()?;
         let secret_bytes = Zeroizing::new(secret_value.into_bytes());
+        self.create_secret_bytes(kind, label, &secret_bytes, None)
+    }
+
+    /// Directory new managed keys are written to, or `None` for the vault.
+    pub fn managed_key_directory(&self) -> Option<PathBuf> {
+        self.key_directory
+            .read()
+            .map(|dir| dir.clone())
+            .unwrap_or_default()
+    }
+
+    /// Changes where new managed keys are stored. Existing keys stay where
+    /// their metadata says they are.
+    pub fn set_managed_key_directory(&self, directory: Option<PathBuf>) {
+        if let Ok(mut current) = self.key_directory.write() {
+            *current = directory;
+        }
+    }
+
+    /// Generates a managed private key inside the vault and returns only its metadata.
+    pub fn create_managed_key(
+        &self,
+        label: String,
+        key_algorithm: &KeyAlgorithm,
+        key_size: Option<u16>,
+        key_curve: Option<&KeyCurve>,
+    ) -> Result<SecretMetadata, SecretError> {
+        self.ensure_unlocked()?;
+        let key_pem = managed_keys::generate_key_pem(key_algorithm, key_size, key_curve)
+            .map_err(|err| SecretError::KeyGeneration(err.to_string()))?;
+        let directory = self.managed_key_directory();
+        self.create_secret_bytes(SecretKind::ManagedPrivateKey, label, &key_pem, directory)
+    }
+
+    fn create_secret_bytes(
+        &self,
+        kind: SecretKind,
+        label: String,
+        secret_bytes: &[u8],
+        directory: Option<PathBuf>,
+    ) -> Result<SecretMetadata, SecretError> {
+        self.ensure_unlocked()?;
         let id = self.generate_ref();
 
+        let storage_backend = if directory.is_some() {
+            SecretStorageBackend::Directory
+        } else {
+            SecretStorageBackend::Vault
+        };
         let record = SecretMetadata {
             id,
             kind,
             label,
             created_at: Utc::now(),
+            storage_backend,
+            storage_path: directory.map(|dir| dir.to_string_lossy().into_owned()),
         };
         info!(
-            "[secrets] create_secret kind={} id={}",
+            "[secrets] create_secret kind={} id={} backend={}",
             record.kind.as_str(),
-            record.id
+            record.id,
+            record.storage_backend.as_str()
         );
 
         self.metadata
             .insert(&record)
             .map_err(|err| SecretError::Metadata(err.to_string()))?;
 
-        if let Err(err) = self.store_secret(&record.id, &secret_bytes) {
+        if let Err(err) = self.store_secret(&record, secret_bytes) {
             if let Err(delete_err) = self.metadata.delete(&record.id) {
                 warn!(
                     "[secrets] failed to rollback metadata for {}: {}",
@@ -134,7 +195,7 @@ impl SecretManager {
             return Err(SecretError::NotFound(id.to_string()));
         };
 
-        self.store_secret(id, &secret_bytes)?;
+        self.store_secret(&existing, &secret_bytes)?;
 
         if let Some(ref new_label) = label {
             self.metadata
@@ -152,14 +213,30 @@ impl SecretManager {
 
     pub fn delete_secret(&self, id: &str) -> Result<(), SecretError> {
         self.ensure_prefix(id)?;
-        self.store
-            .delete(id)
-            .map_err(|err| self.map_store_error(err, id))?;
+        let existing = self
+            .metadata
+            .get(id)
+            .map_err(|err| SecretError::Metadata(err.to_string()))?;
+        match existing.as_ref().and_then(directory_of) {
+            Some(dir) => self.directory_store.delete(dir, id),
+            None => self.store.delete(id),
+        }
+        .map_err(|err| self.map_store_error(err, id))?;
         self.metadata
             .delete(id)
             .map_err(|err| SecretError::Metadata(err.to_string()))
     }
 
+    /// Whether the payload behind `metadata` is actually stored: the vault
+    /// ciphertext or, for directory-backed keys, the key file.
+    pub fn payload_exists(&self, metadata: &SecretMetadata) -> Result<bool, SecretError> {
+        match directory_of(metadata) {
+            Some(dir) => self.directory_store.exists(dir, &metadata.id),
+            None => self.store.exists(&metadata.id),
+        }
+        .map_err(|err| self.map_store_error(err, &metadata.id))
+    }
+
     pub fn get_metadata(&self, id: &str) -> Result<Option<SecretMetadata>, SecretError> {
         self.ensure_prefix(id)?;
         self.metadata
@@ -168,14 +245,40 @@ impl SecretManager {
     }
 
     /// Internal helper for other Rust modules to resolve a secret by reference.
+    /// Returns an unlocked copy; prefer [`Self::with_secret`] for key material.
     pub fn resolve_secret(&self, id: &str) -> Result<Vec<u8>, SecretError> {
+        self.with_secret(id, <[u8]>::to_vec)
+    }
+
+    /// Lends the plaintext of a secret to `f` from a locked buffer that is
+    /// zeroized afterwards.
+    pub fn with_secret<T>(&self, id: &str, f: impl FnOnce(&[u8]) -> T) -> Result<T, SecretError> {
         self.ensure_prefix(id)?;
         self.ensure_unlocked()?;
-        self.store.retrieve(id).map_err(Into::into)
+        let existing = self
+            .metadata
+            .get(id)
+            .map_err(|err| SecretError::Metadata(err.to_string()))?;
+        let secret = match existing.as_ref().and_then(directory_of) {
+            Some(dir) => self.directory_store.retrieve(dir, id)?,
+            None => self.store.retrieve(id)?,
+        };
+        Ok(f(&secret))
+    }
+
+    /// Whether a vault ciphertext, e.g. one read from a backup, decrypts with
+    /// the current master key.
+    pub fn can_open_ciphertext(&self, payload: &[u8]) -> Result<bool, SecretError> {
+        self.ensure_unlocked()?;
+        match store::open(&self.vault, payload) {
+            Ok(_) => Ok(true),
+            Err(SecretStoreError::MasterKeyMismatch | SecretStoreError::Store(_)) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
     }
 
     pub fn unlock(&self) -> Result<(), SecretError> {
-        self.vault.unlock().map_err(SecretError::from)?;
+        self.unlock_vault().map_err(SecretError::from)?;
         self.emit_vault_state(true);
         Ok(())
     }
@@ -189,10 +292,12 @@ impl SecretManager {
         self.vault.is_unlocked()
     }
 
-    fn store_secret(&self, id: &str, secret_value: &[u8]) -> Result<(), SecretError> {
-        self.store
-            .store(id, secret_value)
-            .map_err(|err| self.map_store_error(err, id))
+    fn store_secret(&self, record: &SecretMetadata, secret_value: &[u8]) -> Result<(), SecretError> {
+        match directory_of(record) {
+            Some(dir) => self.directory_store.store(dir, &record.id, secret_value),
+            None => self.store.store(&record.id, secret_value),
+        }
+        .map_err(|err| self.map_store_error(err, &record.id))
     }
 
     fn generate_ref(&self) -> String {
@@ -215,7 +320,7 @@ impl SecretManager {
         if self.vault.is_unlocked() {
             return Ok(());
         }
-        self.vault.unlock().map_err(|err| match err {
+        self.unlock_vault().map_err(|err| match err {
             SecretStoreError::Unavailable(msg) => SecretError::Unavailable(msg),
             SecretStoreError::Locked(msg) => SecretError::Locked(msg),
             _ => SecretError::Store(err.to_string()),
@@ -224,6 +329,17 @@ impl SecretManager {
         Ok(())
     }
 
+    /// Unlocks the vault, recording failed attempts in the audit table.
+    fn unlock_vault(&self) -> Result<(), SecretStoreError> {
+        let result = self.vault.unlock();
+        if let Err(err) = &result
+            && let Err(audit_err) = self.metadata.record_unlock_failure(&err.to_string())
+        {
+            warn!("[secrets] failed to record unlock failure: {audit_err}");
+        }
+        result
+    }
+
     fn map_store_error(&self, err: SecretStoreError, id: &str) -> SecretError {
         match err {
             SecretStoreError::Unavailable(msg) => SecretError::Unavailable(msg),
@@ -243,3 +359,11 @@ impl SecretManager {
         }
     }
 }
+
+/// Key directory of a directory-backed secret.
+fn directory_of(record: &SecretMetadata) -> Option<&Path> {
+    match (record.storage_backend, record.storage_path.as_deref()) {
+        (SecretStorageBackend::Directory, Some(path)) => Some(Path::new(path)),
+        _ => None,
+    }
+}
@@ -1,13 +1,12 @@
-use std::{
-    sync::MutexGuard,
-};
-
 use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection, Row};
+use rusqlite::{params, OptionalExtension, Row};
+
+use super::types::{SecretMetadata, SecretStorageBackend};
+use crate::storage::db::{Db, PooledConn};
 
-use super::types::SecretMetadata;
-use crate::storage::db::Db;
+/// Number of failed unlock audit entries kept.
+const UNLOCK_AUDIT_RETENTION: i64 = 500;
 
 #[derive(Clone)]
 pub struct SecretMetadataStore {
@@ -20,7 +19,7 @@ impl SecretMetadataStore {
     }
 
     pub fn store_ciphertext(&self, id: &str, ciphertext: &[u8]) -> Result<()> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         conn.execute(
             r#"
             UPDATE secret_metadata
@@ -33,7 +32,7 @@ impl SecretMetadataStore {
     }
 
     pub fn get_ciphertext(&self, id: &str) -> Result<Option<Vec<u8>>> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         let mut stmt = conn.prepare(
             r#"
             SELECT ciphertext
@@ -50,8 +49,21 @@ impl SecretMetadataStore {
         }
     }
 
+    /// Whether the row for `id` holds a ciphertext, without reading it.
+    pub fn has_ciphertext(&self, id: &str) -> Result<bool> {
+        let conn = self.conn()?;
+        let found = conn
+            .query_row(
+                "SELECT ciphertext IS NOT NULL FROM secret_metadata WHERE id = ?1",
+                params![id],
+                |row| row.get::<_, bool>(0),
+            )
+            .optional()?;
+        Ok(found.unwrap_or(false))
+    }
+
     pub fn clear_ciphertext(&self, id: &str) -> Result<()> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         conn.execute(
             r#"
             UPDATE secret_metadata
@@ -64,10 +76,10 @@ impl SecretMetadataStore {
     }
 
     pub fn list(&self) -> Result<Vec<SecretMetadata>> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         let mut stmt = conn.prepare(
             r#"
-            SELECT id, kind, label, created_at
+            SELECT id, kind, label, created_at, storage_backend, storage_path
             FROM secret_metadata
             ORDER BY datetime(created_at) DESC
             "#,
@@ -82,10 +94,10 @@ impl SecretMetadataStore {
     }
 
     pub fn get(&self, id: &str) -> Result<Option<SecretMetadata>> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         let mut stmt = conn.prepare(
             r#"
-            SELECT id, kind, label, created_at
+            SELECT id, kind, label, created_at, storage_backend, storage_path
             FROM secret_metadata
             WHERE id = ?1
             "#,
@@ -100,24 +112,26 @@ impl SecretMetadataStore {
     }
 
     pub fn insert(&self, record: &SecretMetadata) -> Result<()> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         conn.execute(
             r#"
-            INSERT INTO secret_metadata (id, kind, label, created_at)
-            VALUES (?1, ?2, ?3, ?4)
+            INSERT INTO secret_metadata (id, kind, label, created_at, storage_backend, storage_path)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
             "#,
             params![
                 record.id,
                 record.kind.as_str(),
                 record.label,
                 record.created_at.to_rfc3339(),
+                record.storage_backend.as_str(),
+                record.storage_path,
             ],
         )?;
         Ok(())
     }
 
     pub fn update_label(&self, id: &str, label: &str) -> Result<()> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         conn.execute(
             r#"
             UPDATE secret_metadata
@@ -130,7 +144,7 @@ impl SecretMetadataStore {
     }
 
     pub fn delete(&self, id: &str) -> Result<()> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         conn.execute(
             r#"
             DELETE FROM secret_metadata
@@ -141,11 +155,32 @@ impl SecretMetadataStore {
         Ok(())
     }
 
+    /// Appends an audit entry for a failed vault unlock, keeping the most recent entries.
+    pub fn record_unlock_failure(&self, reason: &str) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT INTO vault_unlock_failures (attempted_at, reason) VALUES (?1, ?2)",
+            params![Utc::now().to_rfc3339(), reason],
+        )?;
+        conn.execute(
+            r#"
+            DELETE FROM vault_unlock_failures
+            WHERE id NOT IN (
+                SELECT id FROM vault_unlock_failures ORDER BY id DESC LIMIT ?1
+            )
+            "#,
+            params![UNLOCK_AUDIT_RETENTION],
+        )?;
+        Ok(())
+    }
+
     fn row_to_record(row: &Row<'_>) -> Result<SecretMetadata> {
         let id: String = row.get(0)?;
         let kind_raw: String = row.get(1)?;
         let label: String = row.get(2)?;
         let created_raw: String = row.get(3)?;
+        let backend_raw: String = row.get(4)?;
+        let storage_path: Option<String> = row.get(5)?;
 
         let kind = match kind_raw.as_str() {
             "dns_credential" | "dns_provider_token" => super::types::SecretKind::DnsProviderToken,
@@ -153,9 +188,16 @@ impl SecretMetadataStore {
             "dns_provider_secret_key" => super::types::SecretKind::DnsProviderSecretKey,
             "acme_account_key" => super::types::SecretKind::AcmeAccountKey,
             "managed_private_key" => super::types::SecretKind::ManagedPrivateKey,
+            "deploy_target_credentials" => super::types::SecretKind::DeployTargetCredentials,
             other => return Err(anyhow!("unknown secret kind: {other}")),
         };
 
+        let storage_backend = match backend_raw.as_str() {
+            "vault" => SecretStorageBackend::Vault,
+            "directory" => SecretStorageBackend::Directory,
+            other => return Err(anyhow!("unknown secret storage backend: {other}")),
+        };
+
         let created_at = DateTime::parse_from_rfc3339(&created_raw)
             .map(|dt| dt.with_timezone(&Utc))
             .context("failed to parse secret created_at")?;
@@ -165,12 +207,51 @@ impl SecretMetadataStore {
             kind,
             label,
             created_at,
+            storage_backend,
+            storage_path,
         })
     }
 
-    fn lock_conn(&self) -> Result<MutexGuard<'_, Connection>> {
+    fn conn(&self) -> Result<PooledConn> {
         self.db
-            .lock_conn()
-            .map_err(|err| anyhow!("secrets db mutex poisoned: {err}"))
+            .conn()
+            .map_err(|err| anyhow!("secrets db unavailable: {err}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::secrets::types::SecretKind;
+
+    #[test]
+    fn rows_without_ciphertext_have_no_payload() -> Result<()> {
+        let dir = std::env::temp_dir()
+            .join(format!("sslboard_secret_metadata_test_{}", Uuid::new_v4().as_simple()));
+        fs::create_dir_all(&dir)?;
+        let store = SecretMetadataStore::initialize(Db::initialize_with_path(&dir)?)?;
+        store.insert(&SecretMetadata {
+            id: "sec_row".to_string(),
+            kind: SecretKind::DnsProviderToken,
+            label: "Token".to_string(),
+            created_at: Utc::now(),
+            storage_backend: SecretStorageBackend::Vault,
+            storage_path: None,
+        })?;
+
+        assert!(!store.has_ciphertext("sec_row")?);
+        store.store_ciphertext("sec_row", b"sealed")?;
+        assert!(store.has_ciphertext("sec_row")?);
+        store.clear_ciphertext("sec_row")?;
+        assert!(!store.has_ciphertext("sec_row")?);
+        assert!(!store.has_ciphertext("sec_missing")?);
+
+        drop(store);
+        fs::remove_dir_all(&dir)?;
+        Ok(())
     }
 }
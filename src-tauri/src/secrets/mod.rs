@@ -1,6 +1,11 @@
+pub mod directory_store;
+pub mod escrow;
 pub mod keyring_store;
+pub mod locked;
+pub mod managed_keys;
 pub mod manager;
 pub mod metadata;
+pub mod sealed;
 pub mod store;
 pub mod types;
 pub mod vault;
@@ -0,0 +1,63 @@
+//! Passphrase-sealed secrets for files that leave this machine.
+//!
+//! Uses the escrow share scheme (scrypt + AES-256-GCM) for a single blob,
+//! so a shared file is useless without the passphrase given on export.
+
+use aes_gcm::{aead::Aead, Aes256Gcm, KeyInit, Nonce};
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use super::escrow::{derive_key, MIN_PASSPHRASE_LEN, SCRYPT_LOG_N};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SealedSecret {
+    pub scrypt_log_n: u8,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+/// Encrypts `plaintext` to `passphrase`.
+pub fn seal(plaintext: &[u8], passphrase: &str) -> Result<SealedSecret> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(anyhow!("passphrase must be at least {MIN_PASSPHRASE_LEN} characters"));
+    }
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let key = derive_key(passphrase, &salt, SCRYPT_LOG_N)?;
+    let cipher = Aes256Gcm::new_from_slice(&key[..]).map_err(|err| anyhow!(err.to_string()))?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|_| anyhow!("failed to encrypt secret"))?;
+    Ok(SealedSecret {
+        scrypt_log_n: SCRYPT_LOG_N,
+        salt: STANDARD.encode(salt),
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(ciphertext),
+    })
+}
+
+/// Decrypts a sealed secret; fails on a wrong passphrase.
+pub fn open(sealed: &SealedSecret, passphrase: &str) -> Result<Zeroizing<Vec<u8>>> {
+    let salt = STANDARD.decode(&sealed.salt).context("invalid sealed secret salt")?;
+    let nonce = STANDARD.decode(&sealed.nonce).context("invalid sealed secret nonce")?;
+    if nonce.len() != 12 {
+        return Err(anyhow!("invalid sealed secret nonce"));
+    }
+    let ciphertext = STANDARD
+        .decode(&sealed.ciphertext)
+        .context("invalid sealed secret ciphertext")?;
+
+    let key = derive_key(passphrase, &salt, sealed.scrypt_log_n)?;
+    let cipher = Aes256Gcm::new_from_slice(&key[..]).map_err(|err| anyhow!(err.to_string()))?;
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| anyhow!("wrong passphrase"))?;
+    Ok(Zeroizing::new(plaintext))
+}
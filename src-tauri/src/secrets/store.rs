@@ -4,7 +4,7 @@ use aes_gcm::{aead::Aead, Aes256Gcm, KeyInit, Nonce};
 use rand::{rngs::OsRng, RngCore};
 use thiserror::Error;
 
-use super::{metadata::SecretMetadataStore, vault::MasterKeyVault};
+use super::{locked::LockedBuffer, metadata::SecretMetadataStore, vault::MasterKeyVault};
 
 /// Errors produced by secret storage backends.
 #[derive(Debug, Error)]
@@ -24,8 +24,11 @@ pub enum SecretStoreError {
 /// Abstraction for storing and retrieving secrets inside the trusted core.
 pub trait SecretStore: Send + Sync {
     fn store(&self, id: &str, value: &[u8]) -> Result<(), SecretStoreError>;
-    fn retrieve(&self, id: &str) -> Result<Vec<u8>, SecretStoreError>;
+    /// Returns the plaintext in a locked, zeroize-on-drop buffer.
+    fn retrieve(&self, id: &str) -> Result<LockedBuffer, SecretStoreError>;
     fn delete(&self, id: &str) -> Result<(), SecretStoreError>;
+    /// Whether a payload is stored for `id`; the vault need not be unlocked.
+    fn exists(&self, id: &str) -> Result<bool, SecretStoreError>;
 }
 
 /// AES-256-GCM encrypted secret storage backed by SQLite.
@@ -42,29 +45,13 @@ impl EncryptedSecretStore {
 
 impl SecretStore for EncryptedSecretStore {
     fn store(&self, id: &str, value: &[u8]) -> Result<(), SecretStoreError> {
-        self.vault.with_key(|key| {
-            let cipher = Aes256Gcm::new_from_slice(key)
-                .map_err(|err| SecretStoreError::Store(err.to_string()))?;
-
-            let mut nonce_bytes = [0u8; 12];
-            OsRng.fill_bytes(&mut nonce_bytes);
-            let nonce = Nonce::from_slice(&nonce_bytes);
-
-            let mut ciphertext = cipher
-                .encrypt(nonce, value)
-                .map_err(|err| SecretStoreError::Store(err.to_string()))?;
-
-            let mut payload = Vec::with_capacity(nonce_bytes.len() + ciphertext.len());
-            payload.extend_from_slice(&nonce_bytes);
-            payload.append(&mut ciphertext);
-
-            self.metadata
-                .store_ciphertext(id, &payload)
-                .map_err(|err| SecretStoreError::Store(err.to_string()))
-        })
+        let payload = seal(&self.vault, value)?;
+        self.metadata
+            .store_ciphertext(id, &payload)
+            .map_err(|err| SecretStoreError::Store(err.to_string()))
     }
 
-    fn retrieve(&self, id: &str) -> Result<Vec<u8>, SecretStoreError> {
+    fn retrieve(&self, id: &str) -> Result<LockedBuffer, SecretStoreError> {
         let ciphertext = self
             .metadata
             .get_ciphertext(id)
@@ -72,25 +59,7 @@ impl SecretStore for EncryptedSecretStore {
         let Some(ciphertext) = ciphertext else {
             return Err(SecretStoreError::NotFound(id.to_string()));
         };
-        if ciphertext.len() < 12 {
-            return Err(SecretStoreError::Store(
-                "stored ciphertext missing nonce".into(),
-            ));
-        }
-
-        self.vault.with_key(|key| {
-            let cipher = Aes256Gcm::new_from_slice(key)
-                .map_err(|err| SecretStoreError::Store(err.to_string()))?;
-            let (nonce_bytes, data) = ciphertext.split_at(12);
-            let nonce = Nonce::from_slice(nonce_bytes);
-            cipher
-                .decrypt(nonce, data)
-                .map_err(|_err| {
-                    // AEAD decryption failures almost always mean master key mismatch
-                    // (data was encrypted with a different key than we're trying to decrypt with)
-                    SecretStoreError::MasterKeyMismatch
-                })
-        })
+        open(&self.vault, &ciphertext)
     }
 
     fn delete(&self, id: &str) -> Result<(), SecretStoreError> {
@@ -98,4 +67,55 @@ impl SecretStore for EncryptedSecretStore {
             .clear_ciphertext(id)
             .map_err(|err| SecretStoreError::Store(err.to_string()))
     }
+
+    fn exists(&self, id: &str) -> Result<bool, SecretStoreError> {
+        self.metadata
+            .has_ciphertext(id)
+            .map_err(|err| SecretStoreError::Store(err.to_string()))
+    }
+}
+
+/// Encrypts `value` with the master key as `nonce || ciphertext`.
+pub(crate) fn seal(vault: &MasterKeyVault, value: &[u8]) -> Result<Vec<u8>, SecretStoreError> {
+    vault.with_key(|key| {
+        let cipher = Aes256Gcm::new_from_slice(key)
+            .map_err(|err| SecretStoreError::Store(err.to_string()))?;
+
+        let mut nonce_bytes = [0u8; 12];
+        OsRng.fill_bytes(&mut nonce_bytes);
+        let nonce = Nonce::from_slice(&nonce_bytes);
+
+        let mut ciphertext = cipher
+            .encrypt(nonce, value)
+            .map_err(|err| SecretStoreError::Store(err.to_string()))?;
+
+        let mut payload = Vec::with_capacity(nonce_bytes.len() + ciphertext.len());
+        payload.extend_from_slice(&nonce_bytes);
+        payload.append(&mut ciphertext);
+        Ok(payload)
+    })
+}
+
+/// Decrypts a payload produced by [`seal`].
+pub(crate) fn open(vault: &MasterKeyVault, payload: &[u8]) -> Result<LockedBuffer, SecretStoreError> {
+    if payload.len() < 12 {
+        return Err(SecretStoreError::Store(
+            "stored ciphertext missing nonce".into(),
+        ));
+    }
+
+    vault.with_key(|key| {
+        let cipher = Aes256Gcm::new_from_slice(key)
+            .map_err(|err| SecretStoreError::Store(err.to_string()))?;
+        let (nonce_bytes, data) = payload.split_at(12);
+        let nonce = Nonce::from_slice(nonce_bytes);
+        cipher
+            .decrypt(nonce, data)
+            .map(LockedBuffer::from)
+            .map_err(|_err| {
+                // AEAD decryption failures almost always mean master key mismatch
+                // (data was encrypted with a different key than we're trying to decrypt with)
+                SecretStoreError::MasterKeyMismatch
+            })
+    })
 }
@@ -10,6 +10,7 @@ pub enum SecretKind {
     DnsProviderSecretKey,
     AcmeAccountKey,
     ManagedPrivateKey,
+    DeployTargetCredentials,
 }
 
 impl SecretKind {
@@ -20,6 +21,28 @@ impl SecretKind {
             SecretKind::DnsProviderSecretKey => "dns_provider_secret_key",
             SecretKind::AcmeAccountKey => "acme_account_key",
             SecretKind::ManagedPrivateKey => "managed_private_key",
+            SecretKind::DeployTargetCredentials => "deploy_target_credentials",
+        }
+    }
+}
+
+/// Where the ciphertext of a secret is kept. Both backends encrypt with the
+/// vault master key.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretStorageBackend {
+    /// In the app database
+    #[default]
+    Vault,
+    /// As a file in a user-chosen directory
+    Directory,
+}
+
+impl SecretStorageBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecretStorageBackend::Vault => "vault",
+            SecretStorageBackend::Directory => "directory",
         }
     }
 }
@@ -31,4 +54,9 @@ pub struct SecretMetadata {
     pub kind: SecretKind,
     pub label: String,
     pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub storage_backend: SecretStorageBackend,
+    /// Directory holding the key file for the directory backend
+    #[serde(default)]
+    pub storage_path: Option<String>,
 }
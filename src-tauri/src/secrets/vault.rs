@@ -1,14 +1,13 @@
 use std::sync::{Arc, PoisonError, RwLock};
 
 use log::debug;
-use zeroize::{Zeroize, Zeroizing};
-
-use super::{MasterKeyStoreTrait, store::SecretStoreError};
+use super::{MasterKeyStoreTrait, locked::LockedBuffer, store::SecretStoreError};
 
 /// Caches the master key in memory and provides explicit lock/unlock control.
+/// The cached key lives in a [`LockedBuffer`] so it stays out of swap where permitted.
 pub struct MasterKeyVault {
     store: Box<dyn MasterKeyStoreTrait>,
-    cached: Arc<RwLock<Option<Zeroizing<Vec<u8>>>>>,
+    cached: Arc<RwLock<Option<LockedBuffer>>>,
 }
 
 impl MasterKeyVault {
@@ -36,19 +35,15 @@ impl MasterKeyVault {
         let key = self.store.get_or_create()?;
         debug!("[vault] unlock: keyring access complete, caching key");
         let mut guard = self.cached.write().map_err(map_poison)?;
-        if let Some(mut existing) = guard.take() {
-            existing.zeroize();
-        }
-        *guard = Some(key);
+        // Dropping the previous buffer zeroizes and unlocks it.
+        *guard = Some(LockedBuffer::new(key));
         debug!("[vault] unlock: done, vault is now unlocked");
         Ok(())
     }
 
     pub fn lock(&self) {
-        if let Ok(mut guard) = self.cached.write()
-            && let Some(mut key) = guard.take()
-        {
-            key.zeroize();
+        if let Ok(mut guard) = self.cached.write() {
+            drop(guard.take());
         }
     }
 
@@ -1,12 +1,13 @@
 use std::{
     fs,
     path::{Path, PathBuf},
-    sync::{Arc, Mutex, MutexGuard},
     thread,
     time::Duration,
 };
 
 use anyhow::{Context, Result, anyhow};
+use r2d2::{Pool, PooledConnection};
+use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::{Connection, OpenFlags, params};
 use tauri::{AppHandle, Manager};
 
@@ -17,9 +18,19 @@ unsafe extern "system" {
     fn SetFileAttributesW(path: *const u16, attributes: u32) -> i32;
 }
 
+/// Connection checked out from the pool; derefs to `rusqlite::Connection`.
+pub type PooledConn = PooledConnection<SqliteConnectionManager>;
+
+/// Upper bound on open connections. WAL lets readers proceed alongside the
+/// single writer, so background work no longer queues behind one mutex.
+const POOL_MAX_SIZE: u32 = 8;
+/// How long a connection waits on a lock held by another connection before
+/// failing with "database is locked". Every connection, pooled or not, gets it.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[derive(Clone)]
 pub struct Db {
-    conn: Arc<Mutex<Connection>>,
+    pool: Pool<SqliteConnectionManager>,
     db_path: PathBuf,
 }
 
@@ -51,34 +62,64 @@ impl Db {
         // Re-run lightweight migrations/backfills after importing legacy data.
         migrations::run_all(&conn)?;
         Self::enforce_permissions(&db_path, created)?;
+        drop(conn);
+
+        let manager = SqliteConnectionManager::file(&db_path)
+            .with_flags(OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+            .with_init(|conn| Self::apply_connection_pragmas(conn));
+        let pool = Pool::builder()
+            .max_size(POOL_MAX_SIZE)
+            .build(manager)
+            .with_context(|| format!("failed to open SQLite pool for {}", db_path.display()))?;
+
+        Ok(Self { pool, db_path })
+    }
 
-        Ok(Self {
-            conn: Arc::new(Mutex::new(conn)),
-            db_path,
-        })
+    /// Brings the database up to date after its contents were replaced, by a
+    /// restore or a profile switch, and imports legacy databases dropped
+    /// next to it since startup. Replacements must go through SQLite (e.g.
+    /// the backup API) so pooled connections see the new contents.
+    pub fn reload(&self) -> Result<()> {
+        let data_dir = self
+            .db_path
+            .parent()
+            .ok_or_else(|| anyhow!("database path has no parent directory"))?;
+        let mut conn = self.conn()?;
+        // A restored file may have been written in rollback-journal mode.
+        conn.execute_batch("PRAGMA journal_mode = WAL;")?;
+        migrations::run_all(&conn)?;
+        Self::import_legacy_databases(data_dir, &mut conn)?;
+        migrations::run_all(&conn)
     }
 
     pub fn db_path(&self) -> &Path {
         &self.db_path
     }
 
-    pub fn lock_conn(&self) -> Result<MutexGuard<'_, Connection>> {
-        self.conn
-            .lock()
-            .map_err(|err| anyhow!("SQLite connection poisoned: {err}"))
+    /// Checks out a connection, waiting for one to free up if the pool is exhausted.
+    pub fn conn(&self) -> Result<PooledConn> {
+        self.pool
+            .get()
+            .map_err(|err| anyhow!("failed to get SQLite connection from pool: {err}"))
     }
 
     fn configure_connection(conn: &Connection) -> Result<()> {
+        // WAL is persistent on the database file, so pooled connections inherit it.
+        conn.execute_batch("PRAGMA journal_mode = WAL;")?;
+        // Ensure we don't crash on transient locks during startup migrations/import.
+        Self::apply_connection_pragmas(conn).context("failed to configure SQLite connection")
+    }
+
+    /// Per-connection settings shared by the bootstrap connection and the pool,
+    /// so every store sees the same locking and durability behavior.
+    fn apply_connection_pragmas(conn: &Connection) -> rusqlite::Result<()> {
         conn.execute_batch(
             r#"
-            PRAGMA journal_mode = WAL;
             PRAGMA foreign_keys = ON;
+            PRAGMA synchronous = NORMAL;
             "#,
         )?;
-        // Ensure we don't crash on transient locks during startup migrations/import.
-        conn.busy_timeout(Duration::from_secs(5))
-            .context("failed to set SQLite busy timeout")?;
-        Ok(())
+        conn.busy_timeout(BUSY_TIMEOUT)
     }
 
     fn import_legacy_databases(data_dir: &Path, conn: &mut Connection) -> Result<()> {
@@ -334,3 +375,96 @@ impl Db {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn pooled_connections_read_while_another_writes() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("sslboard_db_test_{}", Uuid::new_v4().as_simple()));
+        fs::create_dir_all(&dir)?;
+        let db = Db::initialize_with_path(&dir)?;
+
+        let writer = db.conn()?;
+        writer.execute_batch("BEGIN IMMEDIATE; CREATE TABLE pool_probe (id INTEGER);")?;
+        // A second connection can still read the committed state while the write is open.
+        let reader = db.conn()?;
+        let tables: i64 = reader.query_row(
+            "SELECT COUNT(1) FROM sqlite_master WHERE name = 'pool_probe'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(tables, 0);
+        writer.execute_batch("COMMIT;")?;
+        drop(writer);
+        drop(reader);
+
+        drop(db);
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn pooled_writers_wait_for_the_lock_instead_of_failing() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("sslboard_db_test_{}", Uuid::new_v4().as_simple()));
+        fs::create_dir_all(&dir)?;
+        let db = Db::initialize_with_path(&dir)?;
+        db.conn()?.execute_batch("CREATE TABLE lock_probe (id INTEGER);")?;
+
+        let holder = db.conn()?;
+        let synchronous: i64 = holder.query_row("PRAGMA synchronous", [], |row| row.get(0))?;
+        assert_eq!(synchronous, 1, "pooled connections run with synchronous = NORMAL");
+        holder.execute_batch("BEGIN IMMEDIATE; INSERT INTO lock_probe (id) VALUES (1);")?;
+
+        let waiter = {
+            let db = db.clone();
+            thread::spawn(move || -> Result<()> {
+                let conn = db.conn()?;
+                conn.execute_batch(
+                    "BEGIN IMMEDIATE; INSERT INTO lock_probe (id) VALUES (2); COMMIT;",
+                )?;
+                Ok(())
+            })
+        };
+        thread::sleep(Duration::from_millis(100));
+        holder.execute_batch("COMMIT;")?;
+        waiter.join().expect("writer thread panicked")?;
+
+        let rows: i64 = holder.query_row("SELECT COUNT(1) FROM lock_probe", [], |row| row.get(0))?;
+        assert_eq!(rows, 2);
+        drop(holder);
+
+        drop(db);
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn reload_imports_legacy_databases_added_after_startup() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("sslboard_db_test_{}", Uuid::new_v4().as_simple()));
+        fs::create_dir_all(&dir)?;
+        let db = Db::initialize_with_path(&dir)?;
+
+        let legacy = Connection::open(dir.join("preferences.sqlite"))?;
+        legacy.execute_batch(
+            "CREATE TABLE preferences (name TEXT PRIMARY KEY, value TEXT, updated_at TEXT);
+             INSERT INTO preferences VALUES ('legacy_pref', 'kept', '2024-01-01T00:00:00Z');",
+        )?;
+        drop(legacy);
+
+        db.reload()?;
+        let value: String = db.conn()?.query_row(
+            "SELECT value FROM preferences WHERE name = 'legacy_pref'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(value, "kept");
+        assert!(!dir.join("preferences.sqlite").exists());
+
+        drop(db);
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,378 @@
+//! Demo mode data.
+//!
+//! Seeds a realistic inventory (managed and external certificates, expiring and
+//! expired entries, earlier renewals as history), staging issuers, and
+//! credential-free manual DNS providers so the app can be explored without any
+//! real accounts. Every seeded row uses a `demo_`/`cert_demo_` id and demo
+//! certificates carry the `demo` tag, which is how `clear_demo_data` finds them.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::params;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::core::types::{
+    CertificateRecord, CertificateSource, CertificateStatus, KeyAlgorithm, KeyCurve,
+};
+use crate::storage::{db::Db, inventory::InventoryStore, preferences::PreferencesStore};
+
+pub const DEMO_MODE_PREFERENCE: &str = "demo_mode";
+pub const DEMO_TAG: &str = "demo";
+
+const DEMO_CERT_PREFIX: &str = "cert_demo_";
+const DEMO_ID_PREFIX: &str = "demo_";
+
+/// Whether `id` belongs to a seeded demo row.
+pub fn is_demo_id(id: &str) -> bool {
+    id.starts_with(DEMO_ID_PREFIX) || id.starts_with(DEMO_CERT_PREFIX)
+}
+
+/// Row counts touched by a seed or clear operation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DemoDataCounts {
+    pub certificates: usize,
+    pub issuers: usize,
+    pub providers: usize,
+}
+
+pub fn is_enabled(prefs: &PreferencesStore) -> Result<bool> {
+    Ok(prefs
+        .get(DEMO_MODE_PREFERENCE)?
+        .is_some_and(|pref| pref.value == "true"))
+}
+
+/// Inserts (or refreshes) the demo data set. Dates are relative to now, so
+/// re-seeding on startup keeps the expiring/expired examples meaningful.
+pub fn seed_demo_data(db: &Db, inventory: &InventoryStore) -> Result<DemoDataCounts> {
+    let now = Utc::now();
+    let certificates = demo_certificates(now);
+    for record in &certificates {
+        inventory.insert_certificate(record)?;
+    }
+
+    let conn = db.conn()?;
+    let timestamp = now.to_rfc3339();
+    let issuers = [
+        (
+            "demo_issuer_le_staging",
+            "Demo · Let's Encrypt Staging",
+            "https://acme-staging-v02.api.letsencrypt.org/directory",
+            "staging",
+        ),
+        (
+            "demo_issuer_le_production",
+            "Demo · Let's Encrypt",
+            "https://acme-v02.api.letsencrypt.org/directory",
+            "production",
+        ),
+    ];
+    for (issuer_id, label, directory_url, environment) in issuers {
+        let params_json = json!({
+            "directory_url": directory_url,
+            "environment": environment,
+            "demo": true,
+        })
+        .to_string();
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO issuer_configs (
+                issuer_id, label, directory_url, environment, issuer_type, params_json,
+                contact_email, account_key_ref, tos_agreed, is_selected, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, 'acme', ?5, 'demo@sslboard.test', NULL, 1, 0, ?6, ?6)
+            "#,
+            params![issuer_id, label, directory_url, environment, params_json, timestamp],
+        )?;
+    }
+
+    let providers = [
+        ("demo_dns_sslboard", "Demo · Manual DNS (sslboard.test)", "sslboard.test"),
+        ("demo_dns_shop", "Demo · Manual DNS (example-demo.test)", "example-demo.test"),
+    ];
+    for (provider_id, label, suffix) in providers {
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO dns_providers (
+                id, provider_type, label, domain_suffixes, secret_ref, config_json, created_at, updated_at
+            ) VALUES (?1, 'manual', ?2, ?3, NULL, ?4, ?5, ?5)
+            "#,
+            params![
+                provider_id,
+                label,
+                serde_json::to_string(&[suffix])?,
+                json!({ "demo": true }).to_string(),
+                timestamp
+            ],
+        )?;
+    }
+
+    Ok(DemoDataCounts {
+        certificates: certificates.len(),
+        issuers: issuers.len(),
+        providers: providers.len(),
+    })
+}
+
+/// Removes every demo row, leaving user data untouched. Ids are matched on
+/// their exact prefix: `LIKE` would treat `_` as a wildcard and ignore case.
+pub fn clear_demo_data(db: &Db) -> Result<DemoDataCounts> {
+    let conn = db.conn()?;
+    conn.execute(
+        "DELETE FROM certificate_usages WHERE substr(certificate_id, 1, length(?1)) = ?1",
+        params![DEMO_CERT_PREFIX],
+    )?;
+    let certificates = conn.execute(
+        "DELETE FROM certificate_records WHERE substr(id, 1, length(?1)) = ?1",
+        params![DEMO_CERT_PREFIX],
+    )?;
+    let issuers = conn.execute(
+        "DELETE FROM issuer_configs WHERE substr(issuer_id, 1, length(?1)) = ?1",
+        params![DEMO_ID_PREFIX],
+    )?;
+    let providers = conn.execute(
+        "DELETE FROM dns_providers WHERE substr(id, 1, length(?1)) = ?1",
+        params![DEMO_ID_PREFIX],
+    )?;
+    Ok(DemoDataCounts {
+        certificates,
+        issuers,
+        providers,
+    })
+}
+
+/// Counts demo rows currently present.
+pub fn count_demo_data(db: &Db) -> Result<DemoDataCounts> {
+    let conn = db.conn()?;
+    let count = |sql: &str, prefix: &str| -> Result<usize> {
+        let value: i64 = conn.query_row(sql, params![prefix], |row| row.get(0))?;
+        Ok(value as usize)
+    };
+    Ok(DemoDataCounts {
+        certificates: count(
+            "SELECT COUNT(1) FROM certificate_records WHERE substr(id, 1, length(?1)) = ?1",
+            DEMO_CERT_PREFIX,
+        )?,
+        issuers: count(
+            "SELECT COUNT(1) FROM issuer_configs WHERE substr(issuer_id, 1, length(?1)) = ?1",
+            DEMO_ID_PREFIX,
+        )?,
+        providers: count(
+            "SELECT COUNT(1) FROM dns_providers WHERE substr(id, 1, length(?1)) = ?1",
+            DEMO_ID_PREFIX,
+        )?,
+    })
+}
+
+struct DemoCertificate {
+    id: &'static str,
+    domains: &'static [&'static str],
+    issuer: &'static str,
+    source: CertificateSource,
+    /// Days relative to now; the certificate is valid from `start` to `end`.
+    start: i64,
+    end: i64,
+    key: Option<(KeyAlgorithm, Option<u16>, Option<KeyCurve>)>,
+    extra_tags: &'static [&'static str],
+}
+
+fn demo_certificates(now: DateTime<Utc>) -> Vec<CertificateRecord> {
+    let specs = [
+        DemoCertificate {
+            id: "cert_demo_edge_001",
+            domains: &["edge.sslboard.test", "api.sslboard.test"],
+            issuer: "Let's Encrypt (Sandbox)",
+            source: CertificateSource::Managed,
+            start: -30,
+            end: 60,
+            key: Some((KeyAlgorithm::Rsa, Some(2048), None)),
+            extra_tags: &["production"],
+        },
+        DemoCertificate {
+            id: "cert_demo_edge_000",
+            domains: &["edge.sslboard.test", "api.sslboard.test"],
+            issuer: "Let's Encrypt (Sandbox)",
+            source: CertificateSource::Managed,
+            start: -120,
+            end: -30,
+            key: Some((KeyAlgorithm::Rsa, Some(2048), None)),
+            extra_tags: &["history"],
+        },
+        DemoCertificate {
+            id: "cert_demo_shop_002",
+            domains: &["shop.example-demo.test", "www.shop.example-demo.test"],
+            issuer: "Let's Encrypt (Sandbox)",
+            source: CertificateSource::Managed,
+            start: -78,
+            end: 12,
+            key: Some((KeyAlgorithm::Ecdsa, None, Some(KeyCurve::P256))),
+            extra_tags: &["ecommerce"],
+        },
+        DemoCertificate {
+            id: "cert_demo_shop_001",
+            domains: &["shop.example-demo.test", "www.shop.example-demo.test"],
+            issuer: "Let's Encrypt (Sandbox)",
+            source: CertificateSource::Managed,
+            start: -168,
+            end: -78,
+            key: Some((KeyAlgorithm::Ecdsa, None, Some(KeyCurve::P256))),
+            extra_tags: &["ecommerce", "history"],
+        },
+        DemoCertificate {
+            id: "cert_demo_legacy_003",
+            domains: &["legacy.sslboard.test"],
+            issuer: "Demo Legacy CA",
+            source: CertificateSource::External,
+            start: -370,
+            end: -5,
+            key: None,
+            extra_tags: &["legacy"],
+        },
+        DemoCertificate {
+            id: "cert_demo_internal_004",
+            domains: &["*.internal.sslboard.test"],
+            issuer: "Demo Corporate CA",
+            source: CertificateSource::External,
+            start: -165,
+            end: 200,
+            key: None,
+            extra_tags: &["internal"],
+        },
+        DemoCertificate {
+            id: "cert_demo_status_005",
+            domains: &["status.example-demo.test"],
+            issuer: "Let's Encrypt (Sandbox)",
+            source: CertificateSource::Managed,
+            start: -5,
+            end: 85,
+            key: Some((KeyAlgorithm::Ecdsa, None, Some(KeyCurve::P384))),
+            extra_tags: &[],
+        },
+    ];
+
+    specs
+        .into_iter()
+        .map(|spec| {
+            let digest = Sha256::digest(spec.id.as_bytes());
+            let fingerprint = digest
+                .iter()
+                .map(|byte| format!("{byte:02X}"))
+                .collect::<Vec<_>>()
+                .join(":");
+            let serial = hex::encode_upper(&digest[..8]);
+            let domains: Vec<String> = spec.domains.iter().map(|d| d.to_string()).collect();
+            let domain_roots = domains
+                .iter()
+                .map(|domain| {
+                    let parts: Vec<&str> = domain.split('.').collect();
+                    parts[parts.len().saturating_sub(2)..].join(".")
+                })
+                .fold(Vec::new(), |mut roots: Vec<String>, root| {
+                    if !roots.contains(&root) {
+                        roots.push(root);
+                    }
+                    roots
+                });
+            let mut tags = vec![DEMO_TAG.to_string()];
+            tags.extend(spec.extra_tags.iter().map(|tag| tag.to_string()));
+            let (key_algorithm, key_size, key_curve) = match spec.key {
+                Some((algorithm, size, curve)) => (Some(algorithm), size, curve),
+                None => (None, None, None),
+            };
+
+            CertificateRecord {
+                id: spec.id.to_string(),
+                subjects: domains.clone(),
+                sans: domains,
+                issuer: spec.issuer.to_string(),
+                serial,
+                not_before: now + Duration::days(spec.start),
+                not_after: now + Duration::days(spec.end),
+                fingerprint,
+                source: spec.source,
+                domain_roots,
+                tags,
+                managed_key_ref: None,
+                chain_pem: None,
+                chain_name: None,
+                key_algorithm,
+                key_size,
+                key_curve,
+                status: CertificateStatus::Active,
+                issuer_id: None,
+                promoted_from: None,
+                chain_repairs: Vec::new(),
+                name_forms: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use uuid::Uuid;
+
+    #[test]
+    fn seeds_and_clears_only_demo_rows() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("sslboard_demo_test_{}", Uuid::new_v4().as_simple()));
+        fs::create_dir_all(&dir)?;
+        let db = Db::initialize_with_path(&dir)?;
+        let inventory = InventoryStore::initialize(db.clone())?;
+
+        let seeded = seed_demo_data(&db, &inventory)?;
+        // Seeding twice refreshes rather than duplicating.
+        seed_demo_data(&db, &inventory)?;
+        assert_eq!(count_demo_data(&db)?, seeded);
+        assert!(
+            inventory
+                .list_certificates()?
+                .iter()
+                .all(|cert| cert.tags.contains(&DEMO_TAG.to_string()))
+        );
+
+        // User rows whose ids only look like demo ids
+        inventory.insert_certificate(&CertificateRecord::test_record(
+            "cert_demoXuser",
+            &["user.example.com"],
+        ))?;
+        let conn = db.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO dns_providers (
+                id, provider_type, label, domain_suffixes, secret_ref, config_json,
+                created_at, updated_at
+            ) VALUES ('demoX_dns', 'manual', 'User', '["example.com"]', NULL, NULL, ?1, ?1)
+            "#,
+            params![Utc::now().to_rfc3339()],
+        )?;
+        conn.execute(
+            r#"
+            INSERT INTO issuer_configs (
+                issuer_id, label, directory_url, environment, issuer_type, params_json,
+                contact_email, account_key_ref, tos_agreed, is_selected, created_at, updated_at
+            ) VALUES ('DEMO_issuer', 'User', 'https://acme.example/directory', 'production',
+                      'acme', '{}', NULL, NULL, 1, 0, ?1, ?1)
+            "#,
+            params![Utc::now().to_rfc3339()],
+        )?;
+        assert_eq!(count_demo_data(&db)?, seeded);
+
+        let cleared = clear_demo_data(&db)?;
+        assert_eq!(cleared, seeded);
+        assert_eq!(count_demo_data(&db)?, DemoDataCounts::default());
+        assert!(inventory.get_certificate("cert_demoXuser")?.is_some());
+        let user_rows: i64 = conn.query_row(
+            "SELECT (SELECT COUNT(1) FROM dns_providers) + (SELECT COUNT(1) FROM issuer_configs)",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(user_rows, 2);
+        drop(conn);
+
+        drop(inventory);
+        drop(db);
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+//! Deployment target storage.
+//!
+//! Targets describe where issued certificates are pushed (object storage,
+//! cloud load balancers, reverse proxies). Non-secret settings live in
+//! `config_json`; credentials are kept in the secret store and referenced by id.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::storage::db::{Db, PooledConn};
+
+#[derive(Clone, Debug)]
+pub struct DeployTarget {
+    pub id: String,
+    pub target_type: String,
+    pub label: String,
+    pub config: Value,
+    pub secret_ref: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub struct DeployTargetStore {
+    db: Db,
+}
+
+impl DeployTargetStore {
+    pub fn initialize(db: Db) -> Result<Self> {
+        Ok(Self { db })
+    }
+
+    pub fn list_targets(&self) -> Result<Vec<DeployTarget>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, target_type, label, config_json, secret_ref, created_at, updated_at
+            FROM deploy_targets
+            ORDER BY created_at DESC
+            "#,
+        )?;
+        let mut rows = stmt.query([])?;
+        let mut targets = Vec::new();
+        while let Some(row) = rows.next()? {
+            targets.push(Self::row_to_target(row)?);
+        }
+        Ok(targets)
+    }
+
+    pub fn get_target(&self, target_id: &str) -> Result<Option<DeployTarget>> {
+        let conn = self.conn()?;
+        Self::get_with_conn(&conn, target_id)
+    }
+
+    pub fn create_target(
+        &self,
+        target_type: &str,
+        label: &str,
+        config: &Value,
+        secret_ref: Option<&str>,
+    ) -> Result<DeployTarget> {
+        let conn = self.conn()?;
+        let now = Utc::now().to_rfc3339();
+        let target_id = format!("deploy_{}", Uuid::new_v4().as_simple());
+        conn.execute(
+            r#"
+            INSERT INTO deploy_targets (
+                id, target_type, label, config_json, secret_ref, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+            "#,
+            params![
+                target_id,
+                target_type,
+                label,
+                serde_json::to_string(config).context("failed to serialize target config")?,
+                secret_ref,
+                now
+            ],
+        )?;
+        Self::get_with_conn(&conn, &target_id)?
+            .ok_or_else(|| anyhow!("deploy target not found after create: {target_id}"))
+    }
+
+    pub fn update_target(
+        &self,
+        target_id: &str,
+        label: &str,
+        config: &Value,
+        secret_ref: Option<&str>,
+    ) -> Result<DeployTarget> {
+        let conn = self.conn()?;
+        let updated = conn.execute(
+            r#"
+            UPDATE deploy_targets
+            SET label = ?2,
+                config_json = ?3,
+                secret_ref = ?4,
+                updated_at = ?5
+            WHERE id = ?1
+            "#,
+            params![
+                target_id,
+                label,
+                serde_json::to_string(config).context("failed to serialize target config")?,
+                secret_ref,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        if updated == 0 {
+            return Err(anyhow!("deploy target not found when updating: {target_id}"));
+        }
+        Self::get_with_conn(&conn, target_id)?
+            .ok_or_else(|| anyhow!("deploy target not found after update: {target_id}"))
+    }
+
+    pub fn delete_target(&self, target_id: &str) -> Result<DeployTarget> {
+        let conn = self.conn()?;
+        let existing = Self::get_with_conn(&conn, target_id)?
+            .ok_or_else(|| anyhow!("deploy target not found when deleting: {target_id}"))?;
+        conn.execute("DELETE FROM deploy_targets WHERE id = ?1", params![target_id])?;
+        Ok(existing)
+    }
+
+    fn get_with_conn(conn: &Connection, target_id: &str) -> Result<Option<DeployTarget>> {
+        conn.query_row(
+            r#"
+            SELECT id, target_type, label, config_json, secret_ref, created_at, updated_at
+            FROM deploy_targets
+            WHERE id = ?1
+            "#,
+            params![target_id],
+            |row| Ok(Self::row_to_target(row)),
+        )
+        .optional()?
+        .transpose()
+    }
+
+    fn row_to_target(row: &Row<'_>) -> Result<DeployTarget> {
+        let id: String = row.get(0)?;
+        let config_raw: String = row.get(3)?;
+        let created_raw: String = row.get(5)?;
+        let updated_raw: String = row.get(6)?;
+        Ok(DeployTarget {
+            config: serde_json::from_str(&config_raw)
+                .with_context(|| format!("failed to parse config for deploy target {id}"))?,
+            target_type: row.get(1)?,
+            label: row.get(2)?,
+            secret_ref: row.get(4)?,
+            created_at: DateTime::parse_from_rfc3339(&created_raw)
+                .map(|dt| dt.with_timezone(&Utc))
+                .context("failed to parse deploy target created_at")?,
+            updated_at: DateTime::parse_from_rfc3339(&updated_raw)
+                .map(|dt| dt.with_timezone(&Utc))
+                .context("failed to parse deploy target updated_at")?,
+            id,
+        })
+    }
+
+    fn conn(&self) -> Result<PooledConn> {
+        self.db.conn()
+    }
+}
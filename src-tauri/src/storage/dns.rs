@@ -1,14 +1,14 @@
-use std::{collections::HashMap, sync::MutexGuard};
+use std::collections::HashMap;
 
 use anyhow::{Context, Result, anyhow};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use log::warn;
 use rusqlite::{Connection, Row, params};
 use serde_json::Value;
 use uuid::Uuid;
 
 use crate::domain::{normalize_domain_for_storage, normalize_domain_suffix_for_storage};
-use crate::storage::db::Db;
+use crate::storage::db::{Db, PooledConn};
 
 #[derive(Clone, Debug)]
 pub struct DnsProvider {
@@ -18,10 +18,30 @@ pub struct DnsProvider {
     pub domain_suffixes: Vec<String>,
     pub secret_refs: Vec<String>, // Changed from Option<String> to Vec<String> to support multiple secrets
     pub config_json: Option<String>,
+    /// Breaks ties between providers matching a hostname with equally long
+    /// suffixes; the highest priority wins
+    pub priority: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// How a requested suffix nests inside another provider's suffix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SuffixNesting {
+    /// The requested suffix is a subdomain of the existing one and takes its names over
+    Shadows,
+    /// The existing suffix is a subdomain of the requested one and keeps its names
+    ShadowedBy,
+}
+
+#[derive(Clone, Debug)]
+pub struct SuffixShadowing {
+    pub provider: DnsProvider,
+    pub suffix: String,
+    pub existing_suffix: String,
+    pub nesting: SuffixNesting,
+}
+
 #[derive(Clone, Debug)]
 pub struct DnsProviderResolution {
     pub provider: Option<DnsProvider>,
@@ -38,17 +58,18 @@ pub struct DnsConfigStore {
 impl DnsConfigStore {
     pub fn initialize(db: Db) -> Result<Self> {
         {
-            let conn = db.lock_conn()?;
+            let conn = db.conn()?;
             Self::migrate_zone_mappings(&conn)?;
         }
         Ok(Self { db })
     }
 
     pub fn list_providers(&self) -> Result<Vec<DnsProvider>> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         let mut stmt = conn.prepare(
             r#"
             SELECT id, provider_type, label, domain_suffixes, secret_ref, config_json, created_at, updated_at
+            , priority
             FROM dns_providers
             ORDER BY created_at DESC
             "#,
@@ -63,7 +84,7 @@ impl DnsConfigStore {
     }
 
     pub fn get_provider(&self, provider_id: &str) -> Result<Option<DnsProvider>> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         Self::get_provider_with_conn(&conn, provider_id)
     }
 
@@ -75,7 +96,7 @@ impl DnsConfigStore {
         secret_refs: Vec<String>,
         config: Option<Value>,
     ) -> Result<DnsProvider> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         let now = Utc::now().to_rfc3339();
         let provider_id = format!("dns_prov_{}", Uuid::new_v4().as_simple());
         let domain_suffixes_json =
@@ -120,7 +141,7 @@ impl DnsConfigStore {
         domain_suffixes: Vec<String>,
         config: Option<Value>,
     ) -> Result<DnsProvider> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         let now = Utc::now().to_rfc3339();
         let domain_suffixes_json =
             serde_json::to_string(&domain_suffixes).context("failed to serialize suffixes")?;
@@ -146,6 +167,7 @@ impl DnsConfigStore {
         if updated == 0 {
             return Err(anyhow!("provider not found when updating: {provider_id}"));
         }
+        Self::clear_zone_cache(&conn, provider_id)?;
 
         Self::get_provider_with_conn(&conn, provider_id)?
             .ok_or_else(|| anyhow!("provider not found after update: {provider_id}"))
@@ -156,7 +178,7 @@ impl DnsConfigStore {
         provider_id: &str,
         secret_refs: Vec<String>,
     ) -> Result<DnsProvider> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         let now = Utc::now().to_rfc3339();
         let secret_refs_json = if secret_refs.is_empty() {
             None
@@ -177,21 +199,141 @@ impl DnsConfigStore {
                 "provider not found when updating secret: {provider_id}"
             ));
         }
+        Self::clear_zone_cache(&conn, provider_id)?;
         Self::get_provider_with_conn(&conn, provider_id)?
             .ok_or_else(|| anyhow!("provider not found after secret update: {provider_id}"))
     }
 
+    pub fn set_provider_priority(&self, provider_id: &str, priority: i64) -> Result<DnsProvider> {
+        let conn = self.conn()?;
+        let updated = conn.execute(
+            "UPDATE dns_providers SET priority = ?2, updated_at = ?3 WHERE id = ?1",
+            params![provider_id, priority, Utc::now().to_rfc3339()],
+        )?;
+        if updated == 0 {
+            return Err(anyhow!("provider not found when setting priority: {provider_id}"));
+        }
+        Self::get_provider_with_conn(&conn, provider_id)?
+            .ok_or_else(|| anyhow!("provider not found after priority update: {provider_id}"))
+    }
+
     pub fn delete_provider(&self, provider_id: &str) -> Result<DnsProvider> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         let existing = Self::get_provider_with_conn(&conn, provider_id)?
             .ok_or_else(|| anyhow!("provider not found when deleting: {provider_id}"))?;
         conn.execute(
             "DELETE FROM dns_providers WHERE id = ?1",
             params![provider_id],
         )?;
+        Self::clear_zone_cache(&conn, provider_id)?;
+        conn.execute(
+            "DELETE FROM dns_provider_health WHERE provider_id = ?1",
+            params![provider_id],
+        )?;
         Ok(existing)
     }
 
+    /// Zone the provider's discovery returned for `domain_suffix`, unless it
+    /// was stored more than `max_age` ago.
+    pub fn cached_zone(
+        &self,
+        provider_id: &str,
+        domain_suffix: &str,
+        max_age: Duration,
+    ) -> Result<Option<String>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT zone_id, cached_at FROM dns_zone_cache
+            WHERE provider_id = ?1 AND domain_suffix = ?2
+            "#,
+        )?;
+        let mut rows = stmt.query(params![provider_id, domain_suffix])?;
+        let Some(row) = rows.next()? else {
+            return Ok(None);
+        };
+        let zone_id: String = row.get(0)?;
+        let cached_at: String = row.get(1)?;
+        let cached_at = DateTime::parse_from_rfc3339(&cached_at)
+            .context("invalid zone cache timestamp")?
+            .with_timezone(&Utc);
+        Ok((Utc::now() - cached_at <= max_age).then_some(zone_id))
+    }
+
+    pub fn cache_zone(&self, provider_id: &str, domain_suffix: &str, zone_id: &str) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO dns_zone_cache (provider_id, domain_suffix, zone_id, cached_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT(provider_id, domain_suffix) DO UPDATE SET
+                zone_id = excluded.zone_id,
+                cached_at = excluded.cached_at
+            "#,
+            params![provider_id, domain_suffix, zone_id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Providers that already claim any of `suffixes`, with the shared suffixes.
+    /// Identical suffixes are what make `resolve_provider_for_domain` ambiguous.
+    pub fn find_suffix_overlaps(&self, suffixes: &[String]) -> Result<Vec<(DnsProvider, Vec<String>)>> {
+        let wanted: Vec<String> = suffixes
+            .iter()
+            .filter_map(|suffix| normalize_suffix(suffix).ok())
+            .collect();
+        let mut overlaps = Vec::new();
+        for provider in self.list_providers()? {
+            let shared: Vec<String> = provider
+                .domain_suffixes
+                .iter()
+                .filter_map(|suffix| normalize_suffix(suffix).ok())
+                .filter(|suffix| wanted.contains(suffix))
+                .collect();
+            if !shared.is_empty() {
+                overlaps.push((provider, shared));
+            }
+        }
+        Ok(overlaps)
+    }
+
+    /// Suffixes of other providers that nest with `suffixes` (one is a subdomain
+    /// of the other). Resolution picks the longest suffix, so nesting silently
+    /// moves names between providers; identical suffixes are reported by
+    /// [`Self::find_suffix_overlaps`] instead.
+    pub fn find_suffix_shadowing(
+        &self,
+        suffixes: &[String],
+        exclude_provider_id: Option<&str>,
+    ) -> Result<Vec<SuffixShadowing>> {
+        let wanted: Vec<String> = suffixes
+            .iter()
+            .filter_map(|suffix| normalize_suffix(suffix).ok())
+            .collect();
+        let mut shadowing = Vec::new();
+        for provider in self.list_providers()? {
+            if Some(provider.id.as_str()) == exclude_provider_id {
+                continue;
+            }
+            for existing_suffix in &provider.domain_suffixes {
+                let Ok(existing_suffix) = normalize_suffix(existing_suffix) else {
+                    continue;
+                };
+                for suffix in &wanted {
+                    if let Some(nesting) = suffix_nesting(suffix, &existing_suffix) {
+                        shadowing.push(SuffixShadowing {
+                            provider: provider.clone(),
+                            suffix: suffix.clone(),
+                            existing_suffix: existing_suffix.clone(),
+                            nesting,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(shadowing)
+    }
+
     pub fn resolve_provider_for_domain(&self, hostname: &str) -> Result<DnsProviderResolution> {
         let providers = self.list_providers()?;
         let normalized = normalize_hostname(hostname)?;
@@ -217,6 +359,7 @@ impl DnsConfigStore {
             b_suffix
                 .len()
                 .cmp(&a_suffix.len())
+                .then_with(|| b_provider.priority.cmp(&a_provider.priority))
                 .then_with(|| a_provider.id.cmp(&b_provider.id))
         });
 
@@ -224,10 +367,14 @@ impl DnsConfigStore {
             .first()
             .expect("matches should not be empty after empty check");
         let best_suffix_len = first_match.1.len();
+        let best_priority = first_match.0.priority;
 
+        // Only providers tied on both suffix length and priority are ambiguous
         let ambiguous: Vec<DnsProvider> = matches
             .iter()
-            .filter(|(_, suffix)| suffix.len() == best_suffix_len)
+            .filter(|(provider, suffix)| {
+                suffix.len() == best_suffix_len && provider.priority == best_priority
+            })
             .map(|(provider, _)| provider.clone())
             .collect();
 
@@ -400,6 +547,7 @@ impl DnsConfigStore {
         let config_json: Option<String> = row.get(5)?;
         let created_at_raw: String = row.get(6)?;
         let updated_at_raw: String = row.get(7)?;
+        let priority: i64 = row.get(8)?;
 
         let domain_suffixes: Vec<String> = match serde_json::from_str(&domain_suffixes_raw) {
             Ok(suffixes) => suffixes,
@@ -468,6 +616,7 @@ impl DnsConfigStore {
             domain_suffixes,
             secret_refs,
             config_json,
+            priority,
             created_at,
             updated_at,
         })
@@ -477,6 +626,7 @@ impl DnsConfigStore {
         let mut stmt = conn.prepare(
             r#"
             SELECT id, provider_type, label, domain_suffixes, secret_ref, config_json, created_at, updated_at
+            , priority
             FROM dns_providers
             WHERE id = ?1
             "#,
@@ -489,8 +639,17 @@ impl DnsConfigStore {
         }
     }
 
-    fn lock_conn(&self) -> Result<MutexGuard<'_, Connection>> {
-        self.db.lock_conn()
+    /// Forgets cached zones; called whenever the provider changes.
+    fn clear_zone_cache(conn: &Connection, provider_id: &str) -> Result<()> {
+        conn.execute(
+            "DELETE FROM dns_zone_cache WHERE provider_id = ?1",
+            params![provider_id],
+        )?;
+        Ok(())
+    }
+
+    fn conn(&self) -> Result<PooledConn> {
+        self.db.conn()
     }
 }
 
@@ -550,9 +709,100 @@ fn matches_suffix(hostname: &str, suffix: &str) -> bool {
     hostname == suffix || hostname.ends_with(&format!(".{suffix}"))
 }
 
+/// Nesting of two normalized suffixes; None when they are equal or unrelated.
+fn suffix_nesting(suffix: &str, existing: &str) -> Option<SuffixNesting> {
+    if suffix.is_empty() || existing.is_empty() || suffix == existing {
+        None
+    } else if matches_suffix(suffix, existing) {
+        Some(SuffixNesting::Shadows)
+    } else if matches_suffix(existing, suffix) {
+        Some(SuffixNesting::ShadowedBy)
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{matches_suffix, normalize_hostname};
+    use super::{
+        DnsConfigStore, Duration, SuffixNesting, matches_suffix, normalize_hostname,
+        suffix_nesting,
+    };
+    use crate::storage::db::Db;
+    use uuid::Uuid;
+
+    #[test]
+    fn zone_cache_expires_and_clears_on_update() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("sslboard_dns_test_{}", Uuid::new_v4().as_simple()));
+        std::fs::create_dir_all(&dir)?;
+        let store = DnsConfigStore::initialize(Db::initialize_with_path(&dir)?)?;
+        let provider = store.create_provider(
+            "cloudflare".to_string(),
+            "Cloudflare".to_string(),
+            vec!["example.com".to_string()],
+            Vec::new(),
+            None,
+        )?;
+        let day = Duration::hours(24);
+
+        assert_eq!(store.cached_zone(&provider.id, "example.com", day)?, None);
+        store.cache_zone(&provider.id, "example.com", "zone-1")?;
+        assert_eq!(
+            store.cached_zone(&provider.id, "example.com", day)?.as_deref(),
+            Some("zone-1")
+        );
+        assert_eq!(store.cached_zone(&provider.id, "example.com", Duration::seconds(-1))?, None);
+
+        store.update_provider(&provider.id, provider.label.clone(), provider.domain_suffixes, None)?;
+        assert_eq!(store.cached_zone(&provider.id, "example.com", day)?, None);
+
+        drop(store);
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn priority_breaks_ties_between_equal_suffixes() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("sslboard_dns_test_{}", Uuid::new_v4().as_simple()));
+        std::fs::create_dir_all(&dir)?;
+        let store = DnsConfigStore::initialize(Db::initialize_with_path(&dir)?)?;
+        let create = |label: &str| {
+            store.create_provider(
+                "manual".to_string(),
+                label.to_string(),
+                vec!["example.com".to_string()],
+                Vec::new(),
+                None,
+            )
+        };
+        create("First")?;
+        let second = create("Second")?;
+
+        assert_eq!(store.resolve_provider_for_domain("www.example.com")?.ambiguous.len(), 2);
+        store.set_provider_priority(&second.id, 10)?;
+        let resolution = store.resolve_provider_for_domain("www.example.com")?;
+        assert_eq!(resolution.provider.map(|provider| provider.id), Some(second.id));
+        assert_eq!(resolution.ambiguous.len(), 1);
+
+        drop(store);
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn detects_nested_suffixes() {
+        assert_eq!(
+            suffix_nesting("dev.example.com", "example.com"),
+            Some(SuffixNesting::Shadows)
+        );
+        assert_eq!(
+            suffix_nesting("example.com", "dev.example.com"),
+            Some(SuffixNesting::ShadowedBy)
+        );
+        assert_eq!(suffix_nesting("example.com", "example.com"), None);
+        assert_eq!(suffix_nesting("myexample.com", "example.com"), None);
+        assert_eq!(suffix_nesting("example.net", "example.com"), None);
+    }
 
     #[test]
     fn matches_idn_suffix_with_unicode_input() {
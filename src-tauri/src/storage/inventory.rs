@@ -4,21 +4,35 @@
 //! using SQLite as the backend. It handles certificate metadata storage,
 //! retrieval, and basic inventory management operations.
 
-use std::sync::MutexGuard;
+use std::collections::HashSet;
 
 use anyhow::{Context, Result, anyhow};
-use chrono::{Duration, Utc};
-use rusqlite::{Connection, Row, params};
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, Row, TransactionBehavior, params};
 
-use crate::core::types::{CertificateRecord, CertificateSource, KeyAlgorithm, KeyCurve};
-use crate::storage::db::Db;
+use crate::core::status::derive_statuses;
+use crate::core::types::{
+    CertificateRecord, CertificateSource, CertificateStatus, KeyAlgorithm, KeyCurve,
+};
+use crate::storage::db::{Db, PooledConn};
+
+/// Number of failed issuances kept for reporting.
+const ISSUANCE_FAILURE_RETENTION: i64 = 1000;
+
+/// A managed issuance that failed and could not be resumed.
+#[derive(Debug, Clone)]
+pub struct IssuanceFailure {
+    pub failed_at: DateTime<Utc>,
+    pub domains: Vec<String>,
+    pub reason: String,
+}
 
 /// SQLite-based storage for certificate inventory data.
 /// Provides thread-safe access to certificate records with CRUD operations.
 ///
 /// The store uses a single SQLite database file stored in the application's
-/// data directory. All operations are protected by a mutex to ensure
-/// thread safety across async operations.
+/// data directory. Each operation checks out its own pooled connection, so
+/// reads are not serialized behind other async operations.
 #[derive(Clone)]
 pub struct InventoryStore {
     db: Db,
@@ -40,11 +54,12 @@ impl InventoryStore {
     /// # Errors
     /// Returns an error if the database query fails or record deserialization fails
     pub fn list_certificates(&self) -> Result<Vec<CertificateRecord>> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         let mut stmt = conn.prepare(
             r#"
             SELECT id, subjects, sans, issuer, serial, not_before, not_after, fingerprint, source, domain_roots, tags, managed_key_ref, chain_pem
-            , key_algorithm, key_size, key_curve FROM certificate_records
+            , key_algorithm, key_size, key_curve, chain_name, status, issuer_id, promoted_from, chain_repairs
+            FROM certificate_records
             ORDER BY not_after DESC
             "#,
         )?;
@@ -71,11 +86,12 @@ impl InventoryStore {
     /// # Errors
     /// Returns an error if the database query fails or record deserialization fails
     pub fn get_certificate(&self, id: &str) -> Result<Option<CertificateRecord>> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         let mut stmt = conn.prepare(
             r#"
             SELECT id, subjects, sans, issuer, serial, not_before, not_after, fingerprint, source, domain_roots, tags, managed_key_ref, chain_pem
-            , key_algorithm, key_size, key_curve FROM certificate_records
+            , key_algorithm, key_size, key_curve, chain_name, status, issuer_id, promoted_from, chain_repairs
+            FROM certificate_records
             WHERE id = ?1
             "#,
         )?;
@@ -88,72 +104,81 @@ impl InventoryStore {
         }
     }
 
-    /// Inserts or replaces a certificate record in the inventory.
-    ///
-    /// Stores a certificate record in the database. If a record with the same ID
-    /// already exists, it will be replaced (upsert behavior).
+    /// Returns every certificate with its status re-derived, without writing.
     ///
     /// # Arguments
-    /// * `record` - The certificate record to insert
+    /// * `pending_renewals` - Domain sets of managed issuances currently in progress
     ///
     /// # Returns
-    /// A Result indicating success or failure of the insertion
+    /// A Result containing all records with up-to-date statuses, ordered like `list_certificates`
     ///
     /// # Errors
-    /// Returns an error if the database operation fails or serialization fails
-    pub fn insert_certificate(&self, record: &CertificateRecord) -> Result<()> {
-        let mut conn = self.lock_conn()?;
-        Self::insert_with_conn(&mut conn, record)
+    /// Returns an error if the records cannot be read
+    pub fn current_statuses(
+        &self,
+        pending_renewals: &[Vec<String>],
+    ) -> Result<Vec<CertificateRecord>> {
+        let mut records = self.list_certificates()?;
+        let statuses = derive_statuses(&records, pending_renewals, Utc::now());
+        for (record, status) in records.iter_mut().zip(statuses) {
+            record.status = status;
+        }
+        Ok(records)
     }
 
-    /// Seeds the database with a sample development certificate.
+    /// Recomputes the derived status of every certificate and persists changes.
+    /// The write transaction is only opened when a status differs.
     ///
-    /// Inserts a fake certificate record for development and testing purposes.
-    /// Only adds the sample certificate if the inventory is currently empty.
-    /// This helps with UI development and testing without requiring real certificates.
+    /// # Arguments
+    /// * `pending_renewals` - Domain sets of managed issuances currently in progress
     ///
     /// # Returns
-    /// A Result indicating success or failure of the seeding operation
+    /// A Result containing all records with up-to-date statuses, ordered like `list_certificates`
     ///
     /// # Errors
-    /// Returns an error if the database operation fails or if insertion fails
-    pub fn seed_dev_certificate(&self) -> Result<()> {
-        let mut conn = self.lock_conn()?;
-        let count: i64 = conn.query_row("SELECT COUNT(1) FROM certificate_records", [], |row| {
-            row.get(0)
-        })?;
-
-        if count > 0 {
-            return Ok(());
+    /// Returns an error if the records cannot be read or the status update fails
+    pub fn refresh_statuses(&self, pending_renewals: &[Vec<String>]) -> Result<Vec<CertificateRecord>> {
+        let mut records = self.list_certificates()?;
+        let statuses = derive_statuses(&records, pending_renewals, Utc::now());
+        let mut changed = Vec::new();
+        for (record, status) in records.iter_mut().zip(statuses) {
+            if record.status != status {
+                record.status = status;
+                changed.push((record.id.clone(), status));
+            }
+        }
+        if changed.is_empty() {
+            return Ok(records);
         }
 
-        let now = Utc::now();
-        let sample = CertificateRecord {
-            id: "cert_demo_edge_001".to_string(),
-            subjects: vec![
-                "edge.sslboard.test".to_string(),
-                "api.sslboard.test".to_string(),
-            ],
-            sans: vec![
-                "edge.sslboard.test".to_string(),
-                "api.sslboard.test".to_string(),
-            ],
-            issuer: "Let's Encrypt (Sandbox)".to_string(),
-            serial: "04C8F8A8DFE1B2C7".to_string(),
-            not_before: now - Duration::days(30),
-            not_after: now + Duration::days(330),
-            fingerprint: "15:9A:53:1E:72:2B:B3:91:DD:41:18:52:73:AF:35:A4:10:AC:9C:0A:68:F3:1C:90:E2:8B:F4:0C:CB:12:EF".to_string(),
-            source: CertificateSource::Managed,
-            domain_roots: vec!["sslboard.test".to_string()],
-            tags: vec!["demo".to_string(), "sandbox".to_string()],
-            managed_key_ref: None,
-            chain_pem: None,
-            key_algorithm: None,
-            key_size: None,
-            key_curve: None,
-        };
+        let mut conn = self.conn()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        for (id, status) in &changed {
+            tx.execute(
+                "UPDATE certificate_records SET status = ?2 WHERE id = ?1",
+                params![id, status_to_db(*status)],
+            )?;
+        }
+        tx.commit()?;
+        Ok(records)
+    }
 
-        Self::insert_with_conn(&mut conn, &sample)
+    /// Inserts or replaces a certificate record in the inventory.
+    ///
+    /// Stores a certificate record in the database. If a record with the same ID
+    /// already exists, it will be replaced (upsert behavior).
+    ///
+    /// # Arguments
+    /// * `record` - The certificate record to insert
+    ///
+    /// # Returns
+    /// A Result indicating success or failure of the insertion
+    ///
+    /// # Errors
+    /// Returns an error if the database operation fails or serialization fails
+    pub fn insert_certificate(&self, record: &CertificateRecord) -> Result<()> {
+        let mut conn = self.conn()?;
+        Self::insert_with_conn(&mut conn, record)
     }
 
     /// Inserts a certificate record using an existing database connection.
@@ -174,8 +199,9 @@ impl InventoryStore {
         conn.execute(
             r#"
             INSERT OR REPLACE INTO certificate_records (
-                id, subjects, sans, issuer, serial, not_before, not_after, fingerprint, source, domain_roots, tags, managed_key_ref, chain_pem, key_algorithm, key_size, key_curve
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
+                id, subjects, sans, issuer, serial, not_before, not_after, fingerprint, source, domain_roots, tags, managed_key_ref, chain_pem, key_algorithm, key_size, key_curve, chain_name, status,
+                issuer_id, promoted_from, chain_repairs
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)
             "#,
             params![
                 record.id,
@@ -197,6 +223,11 @@ impl InventoryStore {
                 key_algorithm_to_db(&record.key_algorithm),
                 record.key_size,
                 key_curve_to_db(&record.key_curve),
+                record.chain_name,
+                status_to_db(record.status),
+                record.issuer_id,
+                record.promoted_from,
+                serde_json::to_string(&record.chain_repairs)?,
             ],
         )?;
         Ok(())
@@ -233,6 +264,11 @@ impl InventoryStore {
         let key_algorithm_raw: Option<String> = row.get(13)?;
         let key_size: Option<u16> = row.get(14)?;
         let key_curve_raw: Option<String> = row.get(15)?;
+        let chain_name: Option<String> = row.get(16)?;
+        let status_raw: String = row.get(17)?;
+        let issuer_id: Option<String> = row.get(18)?;
+        let promoted_from: Option<String> = row.get(19)?;
+        let chain_repairs_raw: Option<String> = row.get(20)?;
 
         let source = match source_raw.as_str() {
             "External" => CertificateSource::External,
@@ -263,24 +299,148 @@ impl InventoryStore {
             tags: serde_json::from_str(&tags_raw).context("failed to deserialize tags")?,
             managed_key_ref,
             chain_pem,
+            chain_name,
             key_algorithm: parse_key_algorithm(key_algorithm_raw)?,
             key_size,
             key_curve: parse_key_curve(key_curve_raw)?,
+            status: parse_status(&status_raw)?,
+            issuer_id,
+            promoted_from,
+            chain_repairs: match chain_repairs_raw {
+                Some(raw) => {
+                    serde_json::from_str(&raw).context("failed to deserialize chain_repairs")?
+                }
+                None => Vec::new(),
+            },
+            name_forms: Vec::new(),
         })
     }
 
-    /// Acquires a lock on the database connection for thread-safe access.
-    ///
-    /// Returns a mutex guard that provides exclusive access to the SQLite connection.
-    /// This ensures thread safety when performing database operations.
+    /// Drops the managed key reference of a certificate whose key is gone.
+    pub fn detach_managed_key(&self, id: &str) -> Result<()> {
+        let conn = self.conn()?;
+        let updated = conn.execute(
+            "UPDATE certificate_records SET managed_key_ref = NULL WHERE id = ?1",
+            params![id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow!("certificate not found: {id}"));
+        }
+        Ok(())
+    }
+
+    /// Records a managed issuance that failed for good, keeping the most recent entries.
+    pub fn record_issuance_failure(&self, domains: &[String], reason: &str) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT INTO issuance_failures (failed_at, domains, reason) VALUES (?1, ?2, ?3)",
+            params![Utc::now().to_rfc3339(), serde_json::to_string(domains)?, reason],
+        )?;
+        conn.execute(
+            r#"
+            DELETE FROM issuance_failures
+            WHERE id NOT IN (
+                SELECT id FROM issuance_failures ORDER BY id DESC LIMIT ?1
+            )
+            "#,
+            params![ISSUANCE_FAILURE_RETENTION],
+        )?;
+        Ok(())
+    }
+
+    /// Remembers that `domains` were issued by an issuer in `environment`, so
+    /// issuer selection can tell new names from proven ones.
+    pub fn record_issued_names(&self, domains: &[String], environment: &str) -> Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let now = Utc::now().to_rfc3339();
+        for domain in domains {
+            tx.execute(
+                r#"
+                INSERT INTO issued_names (name, environment, last_issued_at)
+                VALUES (?1, ?2, ?3)
+                ON CONFLICT(name, environment) DO UPDATE
+                SET last_issued_at = excluded.last_issued_at
+                "#,
+                params![domain, environment, now],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Those of `domains` that were issued before, in any environment.
+    pub fn issued_names(&self, domains: &[String]) -> Result<HashSet<String>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare("SELECT 1 FROM issued_names WHERE name = ?1 LIMIT 1")?;
+        let mut issued = HashSet::new();
+        for domain in domains {
+            if stmt.exists(params![domain])? {
+                issued.insert(domain.clone());
+            }
+        }
+        Ok(issued)
+    }
+
+    /// Lists failed issuances recorded at or after `since`, oldest first.
+    pub fn issuance_failures_since(&self, since: DateTime<Utc>) -> Result<Vec<IssuanceFailure>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT failed_at, domains, reason FROM issuance_failures ORDER BY id ASC",
+        )?;
+        let mut rows = stmt.query([])?;
+        let mut failures = Vec::new();
+        while let Some(row) = rows.next()? {
+            let failed_at_raw: String = row.get(0)?;
+            let domains_raw: String = row.get(1)?;
+            let failed_at = DateTime::parse_from_rfc3339(&failed_at_raw)
+                .map(|dt| dt.with_timezone(&Utc))
+                .context("failed to parse failed_at timestamp")?;
+            if failed_at < since {
+                continue;
+            }
+            failures.push(IssuanceFailure {
+                failed_at,
+                domains: serde_json::from_str(&domains_raw)
+                    .context("failed to deserialize failure domains")?,
+                reason: row.get(2)?,
+            });
+        }
+        Ok(failures)
+    }
+
+    /// Checks out a pooled connection for the duration of one operation.
     ///
     /// # Returns
-    /// A Result containing a MutexGuard for the connection or an error if the mutex is poisoned
+    /// A Result containing a pooled connection
     ///
     /// # Errors
-    /// Returns an error if the mutex has been poisoned by a previous panic
-    fn lock_conn(&self) -> Result<MutexGuard<'_, Connection>> {
-        self.db.lock_conn()
+    /// Returns an error if no connection could be obtained from the pool
+    fn conn(&self) -> Result<PooledConn> {
+        self.db.conn()
+    }
+}
+
+fn status_to_db(status: CertificateStatus) -> &'static str {
+    match status {
+        CertificateStatus::Active => "active",
+        CertificateStatus::ExpiringSoon => "expiring_soon",
+        CertificateStatus::Expired => "expired",
+        CertificateStatus::Revoked => "revoked",
+        CertificateStatus::Superseded => "superseded",
+        CertificateStatus::PendingRenewal => "pending_renewal",
+    }
+}
+
+fn parse_status(value: &str) -> Result<CertificateStatus> {
+    match value {
+        "active" => Ok(CertificateStatus::Active),
+        "expiring_soon" => Ok(CertificateStatus::ExpiringSoon),
+        "expired" => Ok(CertificateStatus::Expired),
+        "revoked" => Ok(CertificateStatus::Revoked),
+        "superseded" => Ok(CertificateStatus::Superseded),
+        "pending_renewal" => Ok(CertificateStatus::PendingRenewal),
+        _ => Err(anyhow!("Unknown certificate status: {}", value)),
     }
 }
 
@@ -4,18 +4,34 @@
 //! choice and account references survive restarts. This keeps issuer state
 //! alongside other local metadata without exposing secrets.
 
-use std::{
-    sync::MutexGuard,
-};
+use std::collections::HashMap;
 
 use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Utc};
-use log::debug;
-use rusqlite::{params, Connection, Row};
+use log::{debug, warn};
+use rusqlite::{params, Connection, Row, TransactionBehavior};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use uuid::Uuid;
 
-use crate::storage::db::Db;
+use crate::core::types::{CaPin, IssuerStats};
+use crate::issuance::dns_providers::redact_text;
+use crate::storage::db::{Db, PooledConn};
+
+/// Issuer settings kept in `params_json` rather than in their own columns.
+/// Unknown or missing keys fall back to defaults so older rows still load.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssuerParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preferred_chain: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ca_pins: Vec<CaPin>,
+    #[serde(default)]
+    pub enforce_ca_pins: bool,
+    /// Disabled issuers stay configured but cannot be selected or issue
+    #[serde(default)]
+    pub disabled: bool,
+}
 
 #[derive(Clone, Debug)]
 pub struct IssuerConfigRecord {
@@ -33,6 +49,27 @@ pub struct IssuerConfigRecord {
     pub updated_at: DateTime<Utc>,
 }
 
+impl IssuerConfigRecord {
+    /// Settings stored in `params_json`; a malformed value yields defaults.
+    pub fn params(&self) -> IssuerParams {
+        serde_json::from_str(&self.params_json).unwrap_or_else(|err| {
+            warn!(
+                "[issuer_store] invalid params_json for {}: {err}",
+                self.issuer_id
+            );
+            IssuerParams::default()
+        })
+    }
+
+    /// Preferred certificate chain (root common name) stored in `params_json`.
+    pub fn preferred_chain(&self) -> Option<String> {
+        self.params().preferred_chain
+    }
+}
+
+/// Longest error message kept in the issuer stats.
+const STATS_ERROR_MAX_CHARS: usize = 500;
+
 /// SQLite-backed issuer configuration store.
 #[derive(Clone)]
 pub struct IssuerConfigStore {
@@ -46,7 +83,7 @@ impl IssuerConfigStore {
 
     pub fn list(&self) -> Result<Vec<IssuerConfigRecord>> {
         debug!("[issuer_store] list() begin");
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         let records = Self::query_all(&conn)?;
 
         debug!(
@@ -60,8 +97,19 @@ impl IssuerConfigStore {
         Ok(records)
     }
 
+    /// Issuers pointing at the same ACME directory, ignoring case and a trailing slash.
+    pub fn find_by_directory_url(&self, directory_url: &str) -> Result<Vec<IssuerConfigRecord>> {
+        let normalize = |url: &str| url.trim().trim_end_matches('/').to_ascii_lowercase();
+        let wanted = normalize(directory_url);
+        Ok(self
+            .list()?
+            .into_iter()
+            .filter(|record| normalize(&record.directory_url) == wanted)
+            .collect())
+    }
+
     pub fn get(&self, issuer_id: &str) -> Result<Option<IssuerConfigRecord>> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         Self::get_with_conn(&conn, issuer_id)
     }
 
@@ -75,11 +123,13 @@ impl IssuerConfigStore {
         contact_email: Option<String>,
         account_key_ref: Option<String>,
         tos_agreed: bool,
+        params: &IssuerParams,
     ) -> Result<IssuerConfigRecord> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         let now = Utc::now().to_rfc3339();
         let issuer_id = format!("{}_{}", issuer_type, Uuid::new_v4());
-        let params_json = Self::build_params_json(&directory_url, &environment)?;
+        let params_json =
+            Self::build_params_json(&directory_url, &environment, params)?;
 
         conn.execute(
             r#"
@@ -106,6 +156,7 @@ impl IssuerConfigStore {
             .ok_or_else(|| anyhow!("issuer not found after create: {issuer_id}"))
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn update(
         &self,
         issuer_id: &str,
@@ -114,10 +165,12 @@ impl IssuerConfigStore {
         directory_url: String,
         contact_email: Option<String>,
         tos_agreed: bool,
+        params: &IssuerParams,
     ) -> Result<IssuerConfigRecord> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         let now = Utc::now().to_rfc3339();
-        let params_json = Self::build_params_json(&directory_url, &environment)?;
+        let params_json =
+            Self::build_params_json(&directory_url, &environment, params)?;
 
         let updated = conn.execute(
             r#"
@@ -128,6 +181,7 @@ impl IssuerConfigStore {
                 params_json = ?5,
                 contact_email = ?6,
                 tos_agreed = ?7,
+                is_selected = CASE WHEN ?9 THEN 0 ELSE is_selected END,
                 updated_at = ?8
             WHERE issuer_id = ?1
             "#,
@@ -139,7 +193,9 @@ impl IssuerConfigStore {
                 params_json,
                 contact_email,
                 if tos_agreed { 1 } else { 0 },
-                now
+                now,
+                // A disabled issuer can't stay selected.
+                params.disabled
             ],
         )?;
 
@@ -154,15 +210,13 @@ impl IssuerConfigStore {
     /// Sets the selected issuer, ensuring only one issuer is marked selected.
     pub fn set_selected(&self, issuer_id: &str) -> Result<IssuerConfigRecord> {
         debug!("[issuer_store] set_selected({issuer_id})");
-        let mut conn = self.lock_conn()?;
-        let tx = conn.transaction()?;
-
-        let exists: i64 =
-            tx.query_row("SELECT COUNT(1) FROM issuer_configs WHERE issuer_id = ?1", params![issuer_id], |row| {
-                row.get(0)
-            })?;
-        if exists == 0 {
-            return Err(anyhow!("issuer not found: {issuer_id}"));
+        let mut conn = self.conn()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+        let record = Self::get_with_conn(&tx, issuer_id)?
+            .ok_or_else(|| anyhow!("issuer not found: {issuer_id}"))?;
+        if record.params().disabled {
+            return Err(anyhow!("issuer \"{}\" is disabled", record.label));
         }
 
         tx.execute("UPDATE issuer_configs SET is_selected = 0", [])?;
@@ -181,7 +235,7 @@ impl IssuerConfigStore {
         issuer_id: &str,
         account_key_ref: String,
     ) -> Result<IssuerConfigRecord> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         let now = Utc::now().to_rfc3339();
         let updated = conn.execute(
             "UPDATE issuer_configs SET account_key_ref = ?2, updated_at = ?3 WHERE issuer_id = ?1",
@@ -195,7 +249,7 @@ impl IssuerConfigStore {
     }
 
     pub fn delete(&self, issuer_id: &str) -> Result<()> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         let updated = conn.execute(
             "DELETE FROM issuer_configs WHERE issuer_id = ?1",
             params![issuer_id],
@@ -203,6 +257,94 @@ impl IssuerConfigStore {
         if updated == 0 {
             return Err(anyhow!("issuer not found when deleting: {issuer_id}"));
         }
+        conn.execute("DELETE FROM issuer_stats WHERE issuer_id = ?1", params![issuer_id])?;
+        Ok(())
+    }
+
+    /// Counts an order created with the issuer's ACME server.
+    pub fn record_order(&self, issuer_id: &str) -> Result<()> {
+        self.bump_stats(issuer_id, (1, 0, 0), None, None)
+    }
+
+    pub fn record_success(&self, issuer_id: &str) -> Result<()> {
+        self.bump_stats(issuer_id, (0, 1, 0), None, Some(Utc::now()))
+    }
+
+    /// Counts a failed order or account call, keeping `error`, redacted, as
+    /// the last one.
+    pub fn record_failure(&self, issuer_id: &str, error: &str) -> Result<()> {
+        let error: String = redact_text(error).chars().take(STATS_ERROR_MAX_CHARS).collect();
+        self.bump_stats(issuer_id, (0, 0, 1), Some((&error, Utc::now())), None)
+    }
+
+    /// Activity counters keyed by issuer id; issuers without activity are absent.
+    pub fn stats(&self) -> Result<HashMap<String, IssuerStats>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT issuer_id, orders, successes, failures, last_error, last_error_at,
+                   last_success_at
+            FROM issuer_stats
+            "#,
+        )?;
+        let mut rows = stmt.query([])?;
+        let mut stats = HashMap::new();
+        while let Some(row) = rows.next()? {
+            let parse = |index: usize| -> Result<Option<DateTime<Utc>>> {
+                row.get::<_, Option<String>>(index)?
+                    .map(|raw| {
+                        DateTime::parse_from_rfc3339(&raw)
+                            .map(|dt| dt.with_timezone(&Utc))
+                            .context("failed to parse issuer stats timestamp")
+                    })
+                    .transpose()
+            };
+            stats.insert(
+                row.get::<_, String>(0)?,
+                IssuerStats {
+                    orders: row.get::<_, i64>(1)?.max(0) as u64,
+                    successes: row.get::<_, i64>(2)?.max(0) as u64,
+                    failures: row.get::<_, i64>(3)?.max(0) as u64,
+                    last_error: row.get(4)?,
+                    last_error_at: parse(5)?,
+                    last_success_at: parse(6)?,
+                },
+            );
+        }
+        Ok(stats)
+    }
+
+    fn bump_stats(
+        &self,
+        issuer_id: &str,
+        (orders, successes, failures): (i64, i64, i64),
+        error: Option<(&str, DateTime<Utc>)>,
+        success_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO issuer_stats (
+                issuer_id, orders, successes, failures, last_error, last_error_at, last_success_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            ON CONFLICT(issuer_id) DO UPDATE SET
+                orders = orders + excluded.orders,
+                successes = successes + excluded.successes,
+                failures = failures + excluded.failures,
+                last_error = COALESCE(excluded.last_error, last_error),
+                last_error_at = COALESCE(excluded.last_error_at, last_error_at),
+                last_success_at = COALESCE(excluded.last_success_at, last_success_at)
+            "#,
+            params![
+                issuer_id,
+                orders,
+                successes,
+                failures,
+                error.map(|(message, _)| message),
+                error.map(|(_, at)| at.to_rfc3339()),
+                success_at.map(|at| at.to_rfc3339()),
+            ],
+        )?;
         Ok(())
     }
 
@@ -266,15 +408,142 @@ impl IssuerConfigStore {
         })
     }
 
-    fn build_params_json(directory_url: &str, environment: &str) -> Result<String> {
-        serde_json::to_string(&json!({
-            "directory_url": directory_url,
-            "environment": environment,
-        }))
+    fn build_params_json(
+        directory_url: &str,
+        environment: &str,
+        params: &IssuerParams,
+    ) -> Result<String> {
+        let mut params = IssuerParams {
+            preferred_chain: params
+                .preferred_chain
+                .as_deref()
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .map(str::to_string),
+            ..params.clone()
+        };
+        if params.ca_pins.is_empty() {
+            params.enforce_ca_pins = false;
+        }
+        let mut value = serde_json::to_value(&params).context("failed to serialize issuer params")?;
+        value["directory_url"] = json!(directory_url);
+        value["environment"] = json!(environment);
+        serde_json::to_string(&value)
         .context("failed to serialize issuer params")
     }
 
-    fn lock_conn(&self) -> Result<MutexGuard<'_, Connection>> {
-        self.db.lock_conn()
+    fn conn(&self) -> Result<PooledConn> {
+        self.db.conn()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::CaPinKind;
+    use std::fs;
+
+    #[test]
+    fn params_round_trip_and_disabled_issuers_cannot_be_selected() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("sslboard_issuer_test_{}", Uuid::new_v4().as_simple()));
+        fs::create_dir_all(&dir)?;
+        let db = Db::initialize_with_path(&dir)?;
+        let store = IssuerConfigStore::initialize(db.clone())?;
+
+        let params = IssuerParams {
+            preferred_chain: Some(" ISRG Root X1 ".to_string()),
+            ca_pins: vec![CaPin {
+                kind: CaPinKind::Subject,
+                value: "R11".to_string(),
+            }],
+            enforce_ca_pins: true,
+            disabled: false,
+        };
+        let record = store.create(
+            "Test".to_string(),
+            "acme".to_string(),
+            "staging".to_string(),
+            "https://acme.test/directory".to_string(),
+            Some("ops@example.com".to_string()),
+            None,
+            true,
+            &params,
+        )?;
+        assert_eq!(record.preferred_chain().as_deref(), Some("ISRG Root X1"));
+        assert_eq!(record.params().ca_pins, params.ca_pins);
+        assert!(record.params().enforce_ca_pins);
+
+        store.set_selected(&record.issuer_id)?;
+        let disabled = store.update(
+            &record.issuer_id,
+            record.label.clone(),
+            record.environment.clone(),
+            record.directory_url.clone(),
+            record.contact_email.clone(),
+            true,
+            &IssuerParams {
+                disabled: true,
+                ..IssuerParams::default()
+            },
+        )?;
+        assert!(disabled.params().disabled);
+        assert!(!disabled.is_selected);
+        assert!(store.set_selected(&record.issuer_id).is_err());
+
+        store.record_order(&record.issuer_id)?;
+        store.record_failure(&record.issuer_id, "urn:ietf:params:acme:error:unauthorized")?;
+        store.record_order(&record.issuer_id)?;
+        store.record_success(&record.issuer_id)?;
+        let stats = store.stats()?.remove(&record.issuer_id).unwrap_or_default();
+        assert_eq!((stats.orders, stats.successes, stats.failures), (2, 1, 1));
+        assert_eq!(stats.last_error.as_deref(), Some("urn:ietf:params:acme:error:unauthorized"));
+        assert!(stats.last_error_at.is_some() && stats.last_success_at.is_some());
+
+        let malformed = IssuerConfigRecord {
+            params_json: "not json".to_string(),
+            ..disabled
+        };
+        assert_eq!(malformed.params(), IssuerParams::default());
+
+        drop(store);
+        drop(db);
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn stats_are_counted_per_issuer_with_redacted_errors() -> Result<()> {
+        let dir = std::env::temp_dir()
+            .join(format!("sslboard_issuer_test_{}", Uuid::new_v4().as_simple()));
+        fs::create_dir_all(&dir)?;
+        let db = Db::initialize_with_path(&dir)?;
+        let store = IssuerConfigStore::initialize(db.clone())?;
+
+        assert!(store.stats()?.is_empty());
+        store.record_order("iss_a")?;
+        store.record_failure("iss_a", "HTTP 403 for /zones?api_token=abc123")?;
+        store.record_order("iss_a")?;
+        store.record_failure("iss_a", &"x".repeat(STATS_ERROR_MAX_CHARS + 50))?;
+        store.record_order("iss_b")?;
+        store.record_success("iss_b")?;
+
+        let mut stats = store.stats()?;
+        let a = stats.remove("iss_a").unwrap();
+        assert_eq!((a.orders, a.successes, a.failures), (2, 0, 2));
+        assert_eq!(a.last_error.map(|error| error.len()), Some(STATS_ERROR_MAX_CHARS));
+        assert!(a.last_error_at.is_some() && a.last_success_at.is_none());
+        let b = stats.remove("iss_b").unwrap();
+        assert_eq!((b.orders, b.successes, b.failures), (1, 1, 0));
+        assert!(b.last_error.is_none() && b.last_success_at.is_some());
+
+        store.record_failure("iss_b", "HTTP 403 for /zones?api_token=abc123")?;
+        let b = store.stats()?.remove("iss_b").unwrap();
+        assert_eq!(b.last_error.as_deref(), Some("HTTP 403 for /zones?api_token=[redacted]"));
+        assert!(b.last_success_at.is_some());
+
+        drop(store);
+        drop(db);
+        fs::remove_dir_all(&dir)?;
+        Ok(())
     }
 }
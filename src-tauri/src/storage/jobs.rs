@@ -0,0 +1,155 @@
+//! Journal of operations that change state outside the app.
+//!
+//! Issuances, exports and deployments record an entry when they start and
+//! remove it when they finish. Entries still present when the app starts
+//! belong to operations cut short by a crash or quit; startup marks them
+//! interrupted so recovery can clean up, resume or discard them.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Row};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::core::mappers::{job_kind_from_str, job_kind_to_str};
+use crate::core::types::JobKind;
+use crate::storage::db::{Db, PooledConn};
+
+/// Fresh journal id for operations that have no id of their own.
+pub fn new_job_id() -> String {
+    format!("job_{}", Uuid::new_v4().as_simple())
+}
+
+#[derive(Clone, Debug)]
+pub struct JobEntry {
+    pub id: String,
+    pub kind: JobKind,
+    pub state: Value,
+    pub started_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub struct JobStore {
+    db: Db,
+}
+
+impl JobStore {
+    pub fn initialize(db: Db) -> Result<Self> {
+        Ok(Self { db })
+    }
+
+    /// Records that an operation started. `state` holds what recovery needs.
+    pub fn start(&self, id: &str, kind: JobKind, state: &Value) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO job_journal (id, kind, state_json, started_at, interrupted)
+            VALUES (?1, ?2, ?3, ?4, 0)
+            "#,
+            params![
+                id,
+                job_kind_to_str(kind),
+                serde_json::to_string(state).context("failed to serialize job state")?,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Removes the entry of a finished operation, whatever its outcome.
+    pub fn finish(&self, id: &str) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute("DELETE FROM job_journal WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// Like [`JobStore::finish`], logging instead of failing so a journal
+    /// problem never masks the operation's own result.
+    pub fn finish_quietly(&self, id: &str) {
+        if let Err(err) = self.finish(id) {
+            log::warn!("[jobs] failed to clear journal entry {id}: {err}");
+        }
+    }
+
+    /// Flags every entry left over from a previous run; called at startup and
+    /// after the database is reloaded.
+    pub fn mark_interrupted(&self) -> Result<usize> {
+        let conn = self.conn()?;
+        Ok(conn.execute("UPDATE job_journal SET interrupted = 1", [])?)
+    }
+
+    /// Operations interrupted by a previous run, oldest first.
+    pub fn list_interrupted(&self) -> Result<Vec<JobEntry>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, kind, state_json, started_at
+            FROM job_journal
+            WHERE interrupted = 1
+            ORDER BY started_at
+            "#,
+        )?;
+        let mut rows = stmt.query([])?;
+        let mut entries = Vec::new();
+        while let Some(row) = rows.next()? {
+            match Self::row_to_entry(row) {
+                Ok(entry) => entries.push(entry),
+                Err(err) => log::warn!("[jobs] skipping unreadable journal entry: {err}"),
+            }
+        }
+        Ok(entries)
+    }
+
+    pub fn get_interrupted(&self, id: &str) -> Result<Option<JobEntry>> {
+        Ok(self.list_interrupted()?.into_iter().find(|entry| entry.id == id))
+    }
+
+    fn row_to_entry(row: &Row<'_>) -> Result<JobEntry> {
+        let id: String = row.get(0)?;
+        let kind_raw: String = row.get(1)?;
+        let state_raw: String = row.get(2)?;
+        let started_raw: String = row.get(3)?;
+        Ok(JobEntry {
+            kind: job_kind_from_str(&kind_raw)
+                .with_context(|| format!("unknown job kind for {id}: {kind_raw}"))?,
+            state: serde_json::from_str(&state_raw)
+                .with_context(|| format!("failed to parse state for job {id}"))?,
+            started_at: DateTime::parse_from_rfc3339(&started_raw)
+                .map(|dt| dt.with_timezone(&Utc))
+                .context("failed to parse job started_at")?,
+            id,
+        })
+    }
+
+    fn conn(&self) -> Result<PooledConn> {
+        self.db.conn()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn only_entries_from_previous_runs_are_interrupted() -> Result<()> {
+        let mut path = std::env::temp_dir();
+        path.push(format!("sslboard_jobs_test_{}", Uuid::new_v4().as_simple()));
+        fs::create_dir_all(&path)?;
+        let store = JobStore::initialize(Db::initialize_with_path(&path)?)?;
+
+        store.start("deploy-1", JobKind::Deployment, &json!({ "target_id": "t1" }))?;
+        store.start("export-1", JobKind::Export, &json!({}))?;
+        store.finish("export-1")?;
+        assert_eq!(store.mark_interrupted()?, 1);
+
+        store.start("issue-1", JobKind::Issuance, &json!({}))?;
+        let interrupted = store.list_interrupted()?;
+        assert_eq!(interrupted.len(), 1);
+        assert_eq!(interrupted[0].id, "deploy-1");
+        assert_eq!(interrupted[0].state["target_id"], "t1");
+        assert!(store.get_interrupted("issue-1")?.is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+//! Local-only call statistics.
+//!
+//! Each row aggregates every call of one command or DNS provider: counts,
+//! failures, total and worst duration, and the latest error, redacted. Nothing
+//! here leaves the machine; it exists so slow or flaky providers are visible.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Row};
+
+use crate::core::mappers::{metric_kind_from_str, metric_kind_to_str};
+use crate::core::types::{MetricKind, MetricSummary};
+use crate::issuance::dns_providers::redact_text;
+use crate::storage::db::{Db, PooledConn};
+
+/// Longest error message kept per row.
+const MAX_ERROR_CHARS: usize = 500;
+
+#[derive(Clone)]
+pub struct MetricsStore {
+    db: Db,
+}
+
+impl MetricsStore {
+    pub fn initialize(db: Db) -> Result<Self> {
+        Ok(Self { db })
+    }
+
+    /// Adds one call to the row for `kind`/`name`, refreshing its label.
+    pub fn record(
+        &self,
+        kind: MetricKind,
+        name: &str,
+        label: &str,
+        elapsed_ms: u64,
+        error: Option<&str>,
+    ) -> Result<()> {
+        let error: Option<String> =
+            error.map(|error| redact_text(error).chars().take(MAX_ERROR_CHARS).collect());
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO metrics
+                (kind, name, label, calls, failures, total_ms, max_ms, last_error, last_called_at)
+            VALUES (?1, ?2, ?3, 1, ?4, ?5, ?5, ?6, ?7)
+            ON CONFLICT (kind, name) DO UPDATE SET
+                label = excluded.label,
+                calls = calls + 1,
+                failures = failures + excluded.failures,
+                total_ms = total_ms + excluded.total_ms,
+                max_ms = MAX(max_ms, excluded.max_ms),
+                last_error = COALESCE(excluded.last_error, last_error),
+                last_called_at = excluded.last_called_at
+            "#,
+            params![
+                metric_kind_to_str(kind),
+                name,
+                label,
+                error.is_some() as i64,
+                elapsed_ms as i64,
+                error,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Every row, slowest on average first.
+    pub fn list(&self) -> Result<Vec<MetricSummary>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT kind, name, label, calls, failures, total_ms, max_ms, last_error, last_called_at
+            FROM metrics
+            ORDER BY total_ms / calls DESC, name
+            "#,
+        )?;
+        let mut rows = stmt.query([])?;
+        let mut metrics = Vec::new();
+        while let Some(row) = rows.next()? {
+            if let Some(metric) = Self::row_to_summary(row)? {
+                metrics.push(metric);
+            }
+        }
+        Ok(metrics)
+    }
+
+    pub fn clear(&self) -> Result<()> {
+        self.conn()?.execute("DELETE FROM metrics", [])?;
+        Ok(())
+    }
+
+    fn row_to_summary(row: &Row<'_>) -> Result<Option<MetricSummary>> {
+        let kind_raw: String = row.get(0)?;
+        let Some(kind) = metric_kind_from_str(&kind_raw) else {
+            return Ok(None);
+        };
+        let calls = row.get::<_, i64>(3)?.max(0) as u64;
+        let total_ms = row.get::<_, i64>(5)?.max(0) as u64;
+        let last_called_raw: String = row.get(8)?;
+        Ok(Some(MetricSummary {
+            kind,
+            name: row.get(1)?,
+            label: row.get(2)?,
+            calls,
+            failures: row.get::<_, i64>(4)?.max(0) as u64,
+            avg_ms: total_ms.checked_div(calls).unwrap_or(0),
+            max_ms: row.get::<_, i64>(6)?.max(0) as u64,
+            last_error: row.get(7)?,
+            last_called_at: DateTime::parse_from_rfc3339(&last_called_raw)
+                .context("failed to parse last_called_at")?
+                .with_timezone(&Utc),
+        }))
+    }
+
+    fn conn(&self) -> Result<PooledConn> {
+        self.db.conn()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use uuid::Uuid;
+
+    #[test]
+    fn calls_are_aggregated_per_kind_and_name() -> Result<()> {
+        let mut path = std::env::temp_dir();
+        path.push(format!("sslboard_metrics_test_{}", Uuid::new_v4().as_simple()));
+        fs::create_dir_all(&path)?;
+        let store = MetricsStore::initialize(Db::initialize_with_path(&path)?)?;
+
+        store.record(MetricKind::DnsProvider, "dns_1", "Main (route53)", 300, None)?;
+        store.record(
+            MetricKind::DnsProvider,
+            "dns_1",
+            "Main (route53)",
+            900,
+            Some("throttled ?api_token=abc123"),
+        )?;
+        store.record(MetricKind::DnsProvider, "dns_1", "Main (route53)", 600, None)?;
+        store.record(MetricKind::Command, "dns_1", "dns_1", 10, None)?;
+
+        let metrics = store.list()?;
+        assert_eq!(metrics.len(), 2);
+        let provider = &metrics[0];
+        assert_eq!(provider.kind, MetricKind::DnsProvider);
+        assert_eq!((provider.calls, provider.failures), (3, 1));
+        assert_eq!((provider.avg_ms, provider.max_ms), (600, 900));
+        assert_eq!(provider.last_error.as_deref(), Some("throttled ?api_token=[redacted]"));
+        assert_eq!(metrics[1].calls, 1);
+
+        store.clear()?;
+        assert!(store.list()?.is_empty());
+        Ok(())
+    }
+}
@@ -1,13 +1,26 @@
 use anyhow::{Context, Result};
 use rusqlite::Connection;
 
+/// Schema version stamped into `PRAGMA user_version` once migrations ran.
+/// Bump it when a migration changes the schema in a way older builds cannot read.
+pub const SCHEMA_VERSION: i64 = 1;
+
 /// Runs all schema creation and migrations for the unified SQLite database.
 pub fn run_all(conn: &Connection) -> Result<()> {
     create_tables(conn)?;
     migrate_tables(conn)?;
+    if schema_version(conn)? < SCHEMA_VERSION {
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    }
     Ok(())
 }
 
+/// Schema version recorded in a database; 0 for databases from before versioning.
+pub fn schema_version(conn: &Connection) -> Result<i64> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+        .context("failed to read schema version")
+}
+
 fn create_tables(conn: &Connection) -> Result<()> {
     conn.execute_batch(
         r#"
@@ -34,6 +47,17 @@ fn create_tables(conn: &Connection) -> Result<()> {
             secret_ref TEXT,
             config_json TEXT,
             created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            priority INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE TABLE IF NOT EXISTS deploy_targets (
+            id TEXT PRIMARY KEY,
+            target_type TEXT NOT NULL,
+            label TEXT NOT NULL,
+            config_json TEXT NOT NULL,
+            secret_ref TEXT,
+            created_at TEXT NOT NULL,
             updated_at TEXT NOT NULL
         );
 
@@ -47,6 +71,15 @@ fn create_tables(conn: &Connection) -> Result<()> {
             updated_at TEXT NOT NULL
         );
 
+        -- Zones found by provider discovery, so TXT operations skip the lookup.
+        CREATE TABLE IF NOT EXISTS dns_zone_cache (
+            provider_id TEXT NOT NULL,
+            domain_suffix TEXT NOT NULL,
+            zone_id TEXT NOT NULL,
+            cached_at TEXT NOT NULL,
+            PRIMARY KEY (provider_id, domain_suffix)
+        );
+
         CREATE TABLE IF NOT EXISTS certificate_records (
             id TEXT PRIMARY KEY,
             subjects TEXT NOT NULL,
@@ -61,9 +94,14 @@ fn create_tables(conn: &Connection) -> Result<()> {
             tags TEXT NOT NULL,
             managed_key_ref TEXT,
             chain_pem TEXT,
+            chain_name TEXT,
             key_algorithm TEXT,
             key_size INTEGER,
-            key_curve TEXT
+            key_curve TEXT,
+            status TEXT NOT NULL DEFAULT 'active',
+            issuer_id TEXT,
+            promoted_from TEXT,
+            chain_repairs TEXT
         );
 
         CREATE TABLE IF NOT EXISTS preferences (
@@ -77,8 +115,93 @@ fn create_tables(conn: &Connection) -> Result<()> {
             kind TEXT NOT NULL,
             label TEXT NOT NULL,
             created_at TEXT NOT NULL,
-            ciphertext BLOB
+            ciphertext BLOB,
+            storage_backend TEXT NOT NULL DEFAULT 'vault',
+            storage_path TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS vault_unlock_failures (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            attempted_at TEXT NOT NULL,
+            reason TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS issuance_failures (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            failed_at TEXT NOT NULL,
+            domains TEXT NOT NULL,
+            reason TEXT NOT NULL
         );
+
+        CREATE TABLE IF NOT EXISTS issuer_stats (
+            issuer_id TEXT PRIMARY KEY,
+            orders INTEGER NOT NULL DEFAULT 0,
+            successes INTEGER NOT NULL DEFAULT 0,
+            failures INTEGER NOT NULL DEFAULT 0,
+            last_error TEXT,
+            last_error_at TEXT,
+            last_success_at TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS issued_names (
+            name TEXT NOT NULL,
+            environment TEXT NOT NULL,
+            last_issued_at TEXT NOT NULL,
+            PRIMARY KEY (name, environment)
+        );
+
+        CREATE TABLE IF NOT EXISTS job_journal (
+            id TEXT PRIMARY KEY,
+            kind TEXT NOT NULL,
+            state_json TEXT NOT NULL,
+            started_at TEXT NOT NULL,
+            interrupted INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE TABLE IF NOT EXISTS certificate_usages (
+            id TEXT PRIMARY KEY,
+            certificate_id TEXT NOT NULL,
+            endpoint TEXT,
+            deploy_target_id TEXT,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS endpoint_observations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            endpoint TEXT NOT NULL,
+            fingerprint TEXT NOT NULL,
+            certificate_id TEXT,
+            first_seen TEXT NOT NULL,
+            last_seen TEXT NOT NULL
+        );
+
+        -- Local-only call counters, aggregated per command or DNS provider.
+        CREATE TABLE IF NOT EXISTS metrics (
+            kind TEXT NOT NULL,
+            name TEXT NOT NULL,
+            label TEXT NOT NULL,
+            calls INTEGER NOT NULL,
+            failures INTEGER NOT NULL,
+            total_ms INTEGER NOT NULL,
+            max_ms INTEGER NOT NULL,
+            last_error TEXT,
+            last_called_at TEXT NOT NULL,
+            PRIMARY KEY (kind, name)
+        );
+
+        -- Results of the scheduled DNS provider health checks.
+        CREATE TABLE IF NOT EXISTS dns_provider_health (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            provider_id TEXT NOT NULL,
+            checked_at TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            success INTEGER NOT NULL,
+            error TEXT,
+            error_category TEXT,
+            elapsed_ms INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_dns_provider_health_provider
+            ON dns_provider_health (provider_id, checked_at);
         "#,
     )?;
     Ok(())
@@ -95,11 +218,20 @@ fn migrate_tables(conn: &Connection) -> Result<()> {
         ("key_algorithm", "ALTER TABLE certificate_records ADD COLUMN key_algorithm TEXT"),
         ("key_size", "ALTER TABLE certificate_records ADD COLUMN key_size INTEGER"),
         ("key_curve", "ALTER TABLE certificate_records ADD COLUMN key_curve TEXT"),
+        ("chain_name", "ALTER TABLE certificate_records ADD COLUMN chain_name TEXT"),
+        ("status", "ALTER TABLE certificate_records ADD COLUMN status TEXT NOT NULL DEFAULT 'active'"),
+        ("issuer_id", "ALTER TABLE certificate_records ADD COLUMN issuer_id TEXT"),
+        ("promoted_from", "ALTER TABLE certificate_records ADD COLUMN promoted_from TEXT"),
+        ("chain_repairs", "ALTER TABLE certificate_records ADD COLUMN chain_repairs TEXT"),
+    ])?;
+    ensure_columns(conn, "dns_providers", &[
+        ("priority", "ALTER TABLE dns_providers ADD COLUMN priority INTEGER NOT NULL DEFAULT 0"),
+    ])?;
+    ensure_columns(conn, "secret_metadata", &[
+        ("ciphertext", "ALTER TABLE secret_metadata ADD COLUMN ciphertext BLOB"),
+        ("storage_backend", "ALTER TABLE secret_metadata ADD COLUMN storage_backend TEXT NOT NULL DEFAULT 'vault'"),
+        ("storage_path", "ALTER TABLE secret_metadata ADD COLUMN storage_path TEXT"),
     ])?;
-    ensure_columns(conn, "secret_metadata", &[(
-        "ciphertext",
-        "ALTER TABLE secret_metadata ADD COLUMN ciphertext BLOB",
-    )])?;
 
     backfill_issuer_params_json(conn)?;
     migrate_dns_credential_kind(conn)?;
@@ -1,6 +1,12 @@
+pub mod demo;
+pub mod deploy;
 pub mod dns;
 pub mod inventory;
+pub mod jobs;
+pub mod metrics;
 pub mod issuer;
 pub mod preferences;
+pub mod provider_health;
+pub mod usages;
 pub mod db;
 pub mod migrations;
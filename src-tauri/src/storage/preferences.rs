@@ -1,6 +1,4 @@
-use std::sync::MutexGuard;
-
-use crate::storage::db::Db;
+use crate::storage::db::{Db, PooledConn};
 use anyhow::{Result, anyhow};
 use chrono::{DateTime, Utc};
 use rusqlite::{Connection, OptionalExtension, Row, params};
@@ -24,7 +22,7 @@ impl PreferencesStore {
     }
 
     pub fn get(&self, name: &str) -> Result<Option<PreferenceRecord>> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         let mut stmt = conn.prepare(
             r#"
             SELECT name, value, updated_at
@@ -40,7 +38,7 @@ impl PreferencesStore {
     }
 
     pub fn set(&self, name: &str, value: &str) -> Result<PreferenceRecord> {
-        let conn = self.lock_conn()?;
+        let conn = self.conn()?;
         let now = Utc::now().to_rfc3339();
 
         let updated = conn.execute(
@@ -95,10 +93,10 @@ impl PreferencesStore {
         })
     }
 
-    fn lock_conn(&self) -> Result<MutexGuard<'_, Connection>> {
+    fn conn(&self) -> Result<PooledConn> {
         self.db
-            .lock_conn()
-            .map_err(|err| anyhow!("preferences db mutex poisoned: {err}"))
+            .conn()
+            .map_err(|err| anyhow!("preferences db unavailable: {err}"))
     }
 }
 
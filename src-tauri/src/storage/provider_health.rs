@@ -0,0 +1,176 @@
+//! History of the scheduled DNS provider health checks.
+//!
+//! Only the newest [`MAX_CHECKS_PER_PROVIDER`] results are kept per provider;
+//! enough to show a trend without the table growing forever.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Row};
+
+use crate::core::mappers::{
+    dns_error_category_from_str, dns_error_category_to_str, health_check_kind_from_str,
+    health_check_kind_to_str,
+};
+use crate::core::types::DnsProviderHealthCheck;
+use crate::storage::db::{Db, PooledConn};
+
+const MAX_CHECKS_PER_PROVIDER: i64 = 200;
+/// Longest error message kept per check.
+const MAX_ERROR_CHARS: usize = 500;
+
+#[derive(Clone)]
+pub struct ProviderHealthStore {
+    db: Db,
+}
+
+impl ProviderHealthStore {
+    pub fn initialize(db: Db) -> Result<Self> {
+        Ok(Self { db })
+    }
+
+    /// Stores `check` and drops the provider's oldest results past the limit.
+    pub fn record(&self, check: &DnsProviderHealthCheck) -> Result<()> {
+        let error: Option<String> = check
+            .error
+            .as_ref()
+            .map(|error| error.chars().take(MAX_ERROR_CHARS).collect());
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO dns_provider_health
+                (provider_id, checked_at, kind, success, error, error_category, elapsed_ms)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+            params![
+                check.provider_id,
+                // Fixed precision keeps the text ordering chronological
+                check.checked_at.to_rfc3339_opts(SecondsFormat::Millis, true),
+                health_check_kind_to_str(check.kind),
+                check.success as i64,
+                error,
+                check.error_category.map(dns_error_category_to_str),
+                check.elapsed_ms as i64
+            ],
+        )?;
+        conn.execute(
+            r#"
+            DELETE FROM dns_provider_health
+            WHERE provider_id = ?1 AND id NOT IN (
+                SELECT id FROM dns_provider_health
+                WHERE provider_id = ?1
+                ORDER BY checked_at DESC, id DESC
+                LIMIT ?2
+            )
+            "#,
+            params![check.provider_id, MAX_CHECKS_PER_PROVIDER],
+        )?;
+        Ok(())
+    }
+
+    /// Newest checks first, for one provider or all of them.
+    pub fn history(
+        &self,
+        provider_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<DnsProviderHealthCheck>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT provider_id, checked_at, kind, success, error, error_category, elapsed_ms
+            FROM dns_provider_health
+            WHERE ?1 IS NULL OR provider_id = ?1
+            ORDER BY checked_at DESC, id DESC
+            LIMIT ?2
+            "#,
+        )?;
+        let mut rows = stmt.query(params![provider_id, limit as i64])?;
+        let mut checks = Vec::new();
+        while let Some(row) = rows.next()? {
+            if let Some(check) = Self::row_to_check(row)? {
+                checks.push(check);
+            }
+        }
+        Ok(checks)
+    }
+
+    /// When the provider was last checked, if ever.
+    pub fn last_checked_at(&self, provider_id: &str) -> Result<Option<DateTime<Utc>>> {
+        Ok(self
+            .history(Some(provider_id), 1)?
+            .into_iter()
+            .next()
+            .map(|check| check.checked_at))
+    }
+
+    fn row_to_check(row: &Row<'_>) -> Result<Option<DnsProviderHealthCheck>> {
+        let kind_raw: String = row.get(2)?;
+        let Some(kind) = health_check_kind_from_str(&kind_raw) else {
+            return Ok(None);
+        };
+        let checked_raw: String = row.get(1)?;
+        let category_raw: Option<String> = row.get(5)?;
+        Ok(Some(DnsProviderHealthCheck {
+            provider_id: row.get(0)?,
+            checked_at: DateTime::parse_from_rfc3339(&checked_raw)
+                .context("failed to parse checked_at")?
+                .with_timezone(&Utc),
+            kind,
+            success: row.get::<_, i64>(3)? != 0,
+            error: row.get(4)?,
+            error_category: category_raw.as_deref().and_then(dns_error_category_from_str),
+            elapsed_ms: row.get::<_, i64>(6)?.max(0) as u64,
+        }))
+    }
+
+    fn conn(&self) -> Result<PooledConn> {
+        self.db.conn()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{DnsProviderErrorCategory, ProviderHealthCheckKind};
+    use std::fs;
+    use uuid::Uuid;
+
+    fn check(provider_id: &str, minutes_ago: i64, error: Option<&str>) -> DnsProviderHealthCheck {
+        DnsProviderHealthCheck {
+            provider_id: provider_id.to_string(),
+            checked_at: Utc::now() - chrono::Duration::minutes(minutes_ago),
+            kind: ProviderHealthCheckKind::Credentials,
+            success: error.is_none(),
+            error: error.map(str::to_string),
+            error_category: error.map(|_| DnsProviderErrorCategory::AuthError),
+            elapsed_ms: 120,
+        }
+    }
+
+    #[test]
+    fn history_is_newest_first_and_capped_per_provider() -> Result<()> {
+        let mut path = std::env::temp_dir();
+        path.push(format!("sslboard_health_test_{}", Uuid::new_v4().as_simple()));
+        fs::create_dir_all(&path)?;
+        let store = ProviderHealthStore::initialize(Db::initialize_with_path(&path)?)?;
+
+        store.record(&check("dns_1", 30, None))?;
+        store.record(&check("dns_1", 10, Some("invalid token")))?;
+        store.record(&check("dns_2", 20, None))?;
+
+        let history = store.history(Some("dns_1"), 10)?;
+        assert_eq!(history.len(), 2);
+        assert!(!history[0].success);
+        assert_eq!(history[0].error_category, Some(DnsProviderErrorCategory::AuthError));
+        assert_eq!(store.history(None, 10)?.len(), 3);
+        let latest = store.history(Some("dns_2"), 1)?[0].checked_at;
+        assert_eq!(store.last_checked_at("dns_2")?, Some(latest));
+        assert_eq!(store.last_checked_at("dns_3")?, None);
+
+        for minutes_ago in 0..MAX_CHECKS_PER_PROVIDER + 5 {
+            store.record(&check("dns_2", minutes_ago, None))?;
+        }
+        assert_eq!(store.history(Some("dns_2"), 1000)?.len(), MAX_CHECKS_PER_PROVIDER as usize);
+        let _ = fs::remove_dir_all(path);
+        Ok(())
+    }
+}
@@ -0,0 +1,266 @@
+//! Links between inventory certificates and where they are used.
+//!
+//! A usage ties a certificate to a monitored endpoint (`host[:port]`) or a
+//! deployment target. Links are many-to-many: a certificate can be served by
+//! several endpoints and one target receives every renewal of a certificate.
+//!
+//! Endpoint checks also append to a per-endpoint history of served
+//! certificates, so an unexpected swap on a host shows up in its timeline.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension, Row};
+use uuid::Uuid;
+
+use crate::core::types::EndpointObservation;
+use crate::storage::db::{Db, PooledConn};
+
+#[derive(Clone, Debug)]
+pub struct UsageRow {
+    pub id: String,
+    pub certificate_id: String,
+    pub endpoint: Option<String>,
+    pub deploy_target_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub struct CertificateUsageStore {
+    db: Db,
+}
+
+impl CertificateUsageStore {
+    pub fn initialize(db: Db) -> Result<Self> {
+        Ok(Self { db })
+    }
+
+    /// Usages of any of `certificate_ids`, oldest first.
+    pub fn list_for_certificates(&self, certificate_ids: &[String]) -> Result<Vec<UsageRow>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, certificate_id, endpoint, deploy_target_id, created_at
+            FROM certificate_usages
+            ORDER BY created_at
+            "#,
+        )?;
+        let mut rows = stmt.query([])?;
+        let mut usages = Vec::new();
+        while let Some(row) = rows.next()? {
+            let usage = Self::row_to_usage(row)?;
+            if certificate_ids.contains(&usage.certificate_id) {
+                usages.push(usage);
+            }
+        }
+        Ok(usages)
+    }
+
+    /// Links a certificate to an endpoint or a deployment target. Linking the
+    /// same pair twice returns the existing link.
+    pub fn add(
+        &self,
+        certificate_id: &str,
+        endpoint: Option<&str>,
+        deploy_target_id: Option<&str>,
+    ) -> Result<UsageRow> {
+        let existing = self
+            .list_for_certificates(&[certificate_id.to_string()])?
+            .into_iter()
+            .find(|usage| {
+                usage.endpoint.as_deref() == endpoint
+                    && usage.deploy_target_id.as_deref() == deploy_target_id
+            });
+        if let Some(usage) = existing {
+            return Ok(usage);
+        }
+
+        let usage = UsageRow {
+            id: format!("usage_{}", Uuid::new_v4().as_simple()),
+            certificate_id: certificate_id.to_string(),
+            endpoint: endpoint.map(str::to_string),
+            deploy_target_id: deploy_target_id.map(str::to_string),
+            created_at: Utc::now(),
+        };
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO certificate_usages
+                (id, certificate_id, endpoint, deploy_target_id, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+            params![
+                usage.id,
+                usage.certificate_id,
+                usage.endpoint,
+                usage.deploy_target_id,
+                usage.created_at.to_rfc3339()
+            ],
+        )?;
+        Ok(usage)
+    }
+
+    pub fn remove(&self, usage_id: &str) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute("DELETE FROM certificate_usages WHERE id = ?1", params![usage_id])?;
+        Ok(())
+    }
+
+    /// Drops every link to a deleted deployment target.
+    pub fn remove_for_target(&self, deploy_target_id: &str) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "DELETE FROM certificate_usages WHERE deploy_target_id = ?1",
+            params![deploy_target_id],
+        )?;
+        Ok(())
+    }
+
+    /// Records that `endpoint` served `fingerprint` at `seen_at`. Seeing the
+    /// same certificate as last time extends that entry; anything else starts
+    /// a new one, so a swap back to an older certificate is kept as well.
+    pub fn record_observation(
+        &self,
+        endpoint: &str,
+        fingerprint: &str,
+        certificate_id: Option<&str>,
+        seen_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let conn = self.conn()?;
+        let latest: Option<(i64, String)> = conn
+            .query_row(
+                r#"
+                SELECT id, fingerprint
+                FROM endpoint_observations
+                WHERE endpoint = ?1
+                ORDER BY id DESC
+                LIMIT 1
+                "#,
+                params![endpoint],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        match latest {
+            Some((id, latest_fingerprint)) if latest_fingerprint == fingerprint => {
+                conn.execute(
+                    r#"
+                    UPDATE endpoint_observations
+                    SET last_seen = ?1, certificate_id = ?2
+                    WHERE id = ?3
+                    "#,
+                    params![seen_at.to_rfc3339(), certificate_id, id],
+                )?;
+            }
+            _ => {
+                conn.execute(
+                    r#"
+                    INSERT INTO endpoint_observations
+                        (endpoint, fingerprint, certificate_id, first_seen, last_seen)
+                    VALUES (?1, ?2, ?3, ?4, ?4)
+                    "#,
+                    params![endpoint, fingerprint, certificate_id, seen_at.to_rfc3339()],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Certificates `endpoint` has served, most recent first.
+    pub fn endpoint_timeline(&self, endpoint: &str) -> Result<Vec<EndpointObservation>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT endpoint, fingerprint, certificate_id, first_seen, last_seen
+            FROM endpoint_observations
+            WHERE endpoint = ?1
+            ORDER BY id DESC
+            "#,
+        )?;
+        let mut rows = stmt.query(params![endpoint])?;
+        let mut timeline = Vec::new();
+        while let Some(row) = rows.next()? {
+            let first_raw: String = row.get(3)?;
+            let last_raw: String = row.get(4)?;
+            timeline.push(EndpointObservation {
+                endpoint: row.get(0)?,
+                fingerprint: row.get(1)?,
+                certificate_id: row.get(2)?,
+                first_seen: DateTime::parse_from_rfc3339(&first_raw)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .context("failed to parse observation first_seen")?,
+                last_seen: DateTime::parse_from_rfc3339(&last_raw)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .context("failed to parse observation last_seen")?,
+            });
+        }
+        Ok(timeline)
+    }
+
+    fn row_to_usage(row: &Row<'_>) -> Result<UsageRow> {
+        let created_raw: String = row.get(4)?;
+        Ok(UsageRow {
+            id: row.get(0)?,
+            certificate_id: row.get(1)?,
+            endpoint: row.get(2)?,
+            deploy_target_id: row.get(3)?,
+            created_at: DateTime::parse_from_rfc3339(&created_raw)
+                .map(|dt| dt.with_timezone(&Utc))
+                .context("failed to parse usage created_at")?,
+        })
+    }
+
+    fn conn(&self) -> Result<PooledConn> {
+        self.db.conn()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn links_are_deduplicated_and_follow_targets() -> Result<()> {
+        let mut path = std::env::temp_dir();
+        path.push(format!("sslboard_usages_test_{}", Uuid::new_v4().as_simple()));
+        fs::create_dir_all(&path)?;
+        let store = CertificateUsageStore::initialize(Db::initialize_with_path(&path)?)?;
+
+        let endpoint = store.add("cert-1", Some("example.com:443"), None)?;
+        assert_eq!(store.add("cert-1", Some("example.com:443"), None)?.id, endpoint.id);
+        store.add("cert-1", None, Some("target-1"))?;
+        store.add("cert-2", None, Some("target-1"))?;
+
+        let ids = ["cert-1".to_string(), "cert-2".to_string()];
+        assert_eq!(store.list_for_certificates(&ids[..1])?.len(), 2);
+        assert_eq!(store.list_for_certificates(&ids)?.len(), 3);
+
+        store.remove_for_target("target-1")?;
+        store.remove(&endpoint.id)?;
+        assert!(store.list_for_certificates(&ids)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn timeline_keeps_each_change_of_certificate() -> Result<()> {
+        let mut path = std::env::temp_dir();
+        path.push(format!("sslboard_usages_test_{}", Uuid::new_v4().as_simple()));
+        fs::create_dir_all(&path)?;
+        let store = CertificateUsageStore::initialize(Db::initialize_with_path(&path)?)?;
+
+        let start = Utc::now();
+        let at = |hours| start + chrono::Duration::hours(hours);
+        store.record_observation("example.com:443", "aaa", Some("cert-1"), at(0))?;
+        store.record_observation("example.com:443", "aaa", Some("cert-1"), at(1))?;
+        store.record_observation("example.com:443", "bbb", None, at(2))?;
+        store.record_observation("example.com:443", "aaa", Some("cert-1"), at(3))?;
+        store.record_observation("other.com:443", "ccc", None, at(3))?;
+
+        let timeline = store.endpoint_timeline("example.com:443")?;
+        let fingerprints: Vec<&str> = timeline.iter().map(|o| o.fingerprint.as_str()).collect();
+        assert_eq!(fingerprints, ["aaa", "bbb", "aaa"]);
+        assert_eq!(timeline[2].first_seen, at(0));
+        assert_eq!(timeline[2].last_seen, at(1));
+        assert!(timeline[1].certificate_id.is_none());
+        Ok(())
+    }
+}
@@ -185,7 +185,7 @@ fn cloudflare_atomic_create_one_record() -> Result<()> {
     let name = record_name(&config.zone, "atomic-create");
     let _cleanup = ensure_record_cleanup(config.clone(), &name)?;
 
-    let mut adapter = CloudflareAdapter::new(config.token.clone(), config.zone.clone());
+    let adapter = CloudflareAdapter::new(config.token.clone(), config.zone.clone());
     let record_id = adapter.create_one_record(&name, "integration-test-atomic-create")?;
 
     // Verify record was created
@@ -214,7 +214,7 @@ fn cloudflare_atomic_delete_one_record() -> Result<()> {
     let _cleanup = ensure_record_cleanup(config.clone(), &name)?;
 
     // Create a record first
-    let mut adapter = CloudflareAdapter::new(config.token.clone(), config.zone.clone());
+    let adapter = CloudflareAdapter::new(config.token.clone(), config.zone.clone());
     let record_id = adapter.create_one_record(&name, "integration-test-atomic-delete")?;
 
     // Wait for record to be visible
@@ -244,7 +244,7 @@ fn cloudflare_atomic_list_records() -> Result<()> {
     let _cleanup = ensure_record_cleanup(config.clone(), &name)?;
 
     // Create a record first
-    let mut adapter = CloudflareAdapter::new(config.token.clone(), config.zone.clone());
+    let adapter = CloudflareAdapter::new(config.token.clone(), config.zone.clone());
     let record_id = adapter.create_one_record(&name, "integration-test-atomic-list")?;
 
     // Wait for record to be visible
@@ -267,7 +267,7 @@ fn cloudflare_atomic_list_records() -> Result<()> {
 #[test]
 fn cloudflare_atomic_get_zone_id() -> Result<()> {
     let config = load_cloudflare_config()?;
-    let mut adapter = CloudflareAdapter::new(config.token.clone(), config.zone.clone());
+    let adapter = CloudflareAdapter::new(config.token.clone(), config.zone.clone());
 
     // Get zone ID using atomic operation
     let zone_id = adapter.get_zone_id(&config.zone)?;
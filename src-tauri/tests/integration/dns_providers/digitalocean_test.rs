@@ -218,7 +218,7 @@ fn digitalocean_atomic_create_one_record() -> Result<()> {
     let name = record_name(&config.domain, "atomic-create");
     let _cleanup = ensure_digitalocean_record_cleanup(config.clone(), &name)?;
 
-    let mut adapter = DigitalOceanAdapter::new(config.token.clone(), config.domain.clone());
+    let adapter = DigitalOceanAdapter::new(config.token.clone(), config.domain.clone());
     let record_id = adapter.create_one_record(&name, "integration-test-atomic-create")?;
 
     // Verify record was created
@@ -241,7 +241,7 @@ fn digitalocean_atomic_delete_one_record() -> Result<()> {
     let _cleanup = ensure_digitalocean_record_cleanup(config.clone(), &name)?;
 
     // Create a record first
-    let mut adapter = DigitalOceanAdapter::new(config.token.clone(), config.domain.clone());
+    let adapter = DigitalOceanAdapter::new(config.token.clone(), config.domain.clone());
     let record_id = adapter.create_one_record(&name, "integration-test-atomic-delete")?;
 
     // Wait for record to be visible
@@ -270,7 +270,7 @@ fn digitalocean_atomic_list_records() -> Result<()> {
     let _cleanup = ensure_digitalocean_record_cleanup(config.clone(), &name)?;
 
     // Create a record first
-    let mut adapter = DigitalOceanAdapter::new(config.token.clone(), config.domain.clone());
+    let adapter = DigitalOceanAdapter::new(config.token.clone(), config.domain.clone());
     let record_id = adapter.create_one_record(&name, "integration-test-atomic-list")?;
 
     // Wait for record to be visible via API (this uses the test utils which query directly)
@@ -306,7 +306,7 @@ fn digitalocean_atomic_list_records() -> Result<()> {
 #[test]
 fn digitalocean_atomic_get_zone_id() -> Result<()> {
     let config = load_digitalocean_config()?;
-    let mut adapter = DigitalOceanAdapter::new(config.token.clone(), config.domain.clone());
+    let adapter = DigitalOceanAdapter::new(config.token.clone(), config.domain.clone());
 
     // Get zone ID using atomic operation (should return domain name)
     let zone_id = adapter.get_zone_id(&config.domain)?;
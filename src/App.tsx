@@ -1,3 +1,4 @@
+import { useEffect } from "react";
 import {
   ShieldCheck,
   Wand2,
@@ -15,6 +16,10 @@ import { DiscoverPage } from "./pages/Discover";
 import { SettingsPage } from "./pages/Settings";
 import { DnsProvidersPage } from "./pages/settings/DnsProviders";
 import type { NavItem } from "./components/layout/sidebar";
+import { useAutoCompleteNotifications } from "./hooks/useAutoCompleteNotifications";
+import { useDeepLinkActions } from "./hooks/useDeepLinkActions";
+import { useStateReload } from "./hooks/useStateReload";
+import { loadMessageLocale } from "./lib/messages";
 
 const navItems: NavItem[] = [
   {
@@ -50,10 +55,18 @@ const navItems: NavItem[] = [
 ];
 
 function App() {
+  useDeepLinkActions();
+  useAutoCompleteNotifications();
+  const reloadGeneration = useStateReload();
+
+  useEffect(() => {
+    loadMessageLocale().catch(() => undefined);
+  }, []);
+
   return (
     <ThemeProvider>
       <AppShell navItems={navItems}>
-        <Routes>
+        <Routes key={reloadGeneration}>
           <Route path="/" element={<Navigate to="/certificates" replace />} />
           <Route path="/certificates" element={<CertificatesPage />} />
           <Route path="/issue" element={<IssuePage />} />
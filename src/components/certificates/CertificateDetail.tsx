@@ -1,8 +1,18 @@
 import { AlertCircle, Clock } from "lucide-react";
-import { useState } from "react";
-import type { CertificateRecord } from "../../lib/certificates";
+import { useEffect, useState } from "react";
+import { Link } from "react-router-dom";
+import {
+  getCertificateText,
+  punycodeOf,
+  type ChainRepair,
+  type CertificateRecord,
+} from "../../lib/certificates";
+import { normalizeError } from "../../lib/errors";
+import { isStagingCertificate, listIssuers, type IssuerConfig } from "../../lib/issuers";
 import { Button } from "../ui/button";
 import { CertificateExportModal } from "./CertificateExportModal";
+import { CertificateQrModal } from "./CertificateQrModal";
+import { CertificateUsages } from "./CertificateUsages";
 import { DetailItem } from "./DetailItem";
 import { SubjectPill } from "./SubjectPill";
 import { formatCertificateDate, primarySubject } from "./certificate-utils";
@@ -29,22 +39,89 @@ function formatKeyInfo(record: CertificateRecord): string {
   return "ECDSA";
 }
 
+const CHAIN_REPAIR_LABELS: Record<ChainRepair, string> = {
+  reordered: "reordered",
+  duplicate_removed: "duplicate removed",
+  root_removed: "root removed",
+  unrelated_removed: "unrelated certificate removed",
+  intermediate_fetched: "missing intermediate fetched",
+};
+
+function formatChainRepair(repair: ChainRepair): string {
+  return CHAIN_REPAIR_LABELS[repair] ?? repair;
+}
+
 export function CertificateDetail({
   selected,
   loading,
   error,
 }: CertificateDetailProps) {
   const [isExportOpen, setIsExportOpen] = useState(false);
+  const [isQrOpen, setIsQrOpen] = useState(false);
+  const [issuers, setIssuers] = useState<IssuerConfig[]>([]);
+  const [textCopied, setTextCopied] = useState(false);
+  const [textError, setTextError] = useState<string | null>(null);
+
+  useEffect(() => {
+    listIssuers()
+      .then(setIssuers)
+      .catch(() => setIssuers([]));
+  }, []);
+
+  useEffect(() => {
+    setTextCopied(false);
+    setTextError(null);
+  }, [selected?.id]);
+
+  async function handleCopyText() {
+    if (!selected) {
+      return;
+    }
+    setTextError(null);
+    try {
+      await navigator.clipboard.writeText(await getCertificateText(selected.id));
+      setTextCopied(true);
+      window.setTimeout(() => setTextCopied(false), 1500);
+    } catch (err) {
+      setTextError(normalizeError(err));
+    }
+  }
+
+  const canPromote =
+    selected?.source === "Managed" && isStagingCertificate(selected, issuers);
+
   return (
     <div className="rounded-xl border bg-card p-4 shadow-soft">
       <div className="flex items-center gap-2 border-b pb-3">
         <Clock className="h-4 w-4 text-primary" />
         <div className="text-sm font-semibold text-muted-foreground">Details</div>
-        {selected?.source === "Managed" && (
-          <div className="ml-auto">
-            <Button size="sm" variant="outline" onClick={() => setIsExportOpen(true)}>
-              Export...
+        {selected && (
+          <div className="ml-auto flex gap-2">
+            <Button size="sm" variant="outline" onClick={() => setIsQrOpen(true)}>
+              QR code
             </Button>
+            {selected.chain_pem && (
+              <Button size="sm" variant="outline" onClick={() => void handleCopyText()}>
+                {textCopied ? "Copied" : "Copy as text"}
+              </Button>
+            )}
+            {selected.source === "Managed" && (
+              <Button size="sm" variant="outline" onClick={() => setIsExportOpen(true)}>
+                Export...
+              </Button>
+            )}
+            {canPromote && (
+              <Button size="sm" asChild>
+                <Link
+                  to={`/issue?${new URLSearchParams({
+                    domains: selected.sans.join(","),
+                    promote: selected.id,
+                  }).toString()}`}
+                >
+                  Promote to production
+                </Link>
+              </Button>
+            )}
           </div>
         )}
       </div>
@@ -58,6 +135,12 @@ export function CertificateDetail({
       ) : selected ? (
         <>
           <div className="space-y-4 pt-4">
+            {textError && (
+              <div className="flex items-center gap-2 text-sm text-red-500">
+                <AlertCircle className="h-4 w-4" />
+                {textError}
+              </div>
+            )}
             <div>
               <div className="text-xs uppercase tracking-wide text-muted-foreground">
                 Primary subject
@@ -72,7 +155,11 @@ export function CertificateDetail({
               </div>
               <div className="flex flex-wrap gap-2">
                 {selected.sans.map((name) => (
-                  <SubjectPill key={name} text={name} />
+                  <SubjectPill
+                    key={name}
+                    text={name}
+                    title={punycodeOf(selected.name_forms, name)}
+                  />
                 ))}
               </div>
             </div>
@@ -94,6 +181,19 @@ export function CertificateDetail({
               />
               <DetailItem label="Key algorithm" value={formatKeyInfo(selected)} />
               <DetailItem label="Source" value={selected.source} />
+              {selected.promoted_from && (
+                <DetailItem
+                  label="Promoted from"
+                  value={selected.promoted_from}
+                  truncate={true}
+                />
+              )}
+              {selected.chain_repairs?.length ? (
+                <DetailItem
+                  label="Chain repairs"
+                  value={selected.chain_repairs.map(formatChainRepair).join(", ")}
+                />
+              ) : null}
             </div>
             <div>
               <div className="text-xs font-semibold uppercase tracking-wide text-muted-foreground">
@@ -114,7 +214,13 @@ export function CertificateDetail({
                 )}
               </div>
             </div>
+            <CertificateUsages certificateId={selected.id} />
           </div>
+          <CertificateQrModal
+            certificate={selected}
+            isOpen={isQrOpen}
+            onClose={() => setIsQrOpen(false)}
+          />
           {selected.source === "Managed" && (
             <CertificateExportModal
               certificate={selected}
@@ -1,7 +1,16 @@
 import { useEffect, useMemo, useState } from "react";
 
-import type { CertificateRecord, ExportBundle } from "../../lib/certificates";
-import { exportCertificatePem } from "../../lib/certificates";
+import type {
+  CertificateRecord,
+  ExportBundle,
+  KeyExportPolicy,
+} from "../../lib/certificates";
+import {
+  exportCertificatePem,
+  KEY_EXPORT_POLICY_PREFERENCE,
+  parseKeyExportPolicy,
+} from "../../lib/certificates";
+import { getPreference } from "../../lib/preferences";
 import { Button } from "../ui/button";
 import {
   Dialog,
@@ -39,7 +48,10 @@ export function CertificateExportModal({
   const [bundle, setBundle] = useState<ExportBundle>("fullchain");
   const [includeKey, setIncludeKey] = useState(false);
   const [confirmKeyExport, setConfirmKeyExport] = useState(false);
+  const [keyPassphrase, setKeyPassphrase] = useState("");
+  const [keyPolicy, setKeyPolicy] = useState<KeyExportPolicy>("allowed");
   const [folderName, setFolderName] = useState(defaultFolder);
+  const [fileNameTemplate, setFileNameTemplate] = useState("");
   const [isSubmitting, setIsSubmitting] = useState(false);
   const [exportError, setExportError] = useState<string | null>(null);
   const [successPath, setSuccessPath] = useState<string | null>(null);
@@ -54,9 +66,14 @@ export function CertificateExportModal({
     setBundle("fullchain");
     setIncludeKey(false);
     setConfirmKeyExport(false);
+    setKeyPassphrase("");
     setFolderName(defaultFolder);
+    setFileNameTemplate("");
     setExportError(null);
     setSuccessPath(null);
+    getPreference(KEY_EXPORT_POLICY_PREFERENCE)
+      .then((saved) => setKeyPolicy(parseKeyExportPolicy(saved?.value)))
+      .catch(() => setKeyPolicy("forbidden"));
   }, [defaultFolder, isOpen]);
 
   if (!isOpen) {
@@ -76,6 +93,10 @@ export function CertificateExportModal({
       setExportError("Confirm the private key warning before exporting.");
       return;
     }
+    if (includeKey && keyPolicy === "encrypted_only" && !keyPassphrase) {
+      setExportError("Enter a passphrase to encrypt the private key.");
+      return;
+    }
     setIsSubmitting(true);
     setExportError(null);
     try {
@@ -83,7 +104,9 @@ export function CertificateExportModal({
         certificateId: certificate.id,
         destinationDir,
         folderName: folderName.trim(),
+        fileNameTemplate: fileNameTemplate.trim() || undefined,
         includePrivateKey: includeKey,
+        keyPassphrase: includeKey && keyPassphrase ? keyPassphrase : undefined,
         bundle,
         overwrite,
       });
@@ -140,16 +163,21 @@ export function CertificateExportModal({
           <ExportDestinationPicker
             destinationDir={destinationDir}
             folderName={folderName}
+            fileNameTemplate={fileNameTemplate}
             onSelectDestination={selectDestination}
             onFolderNameChange={setFolderName}
+            onFileNameTemplateChange={setFileNameTemplate}
           />
 
           <PrivateKeyExportWarning
             keyAvailable={keyAvailable}
+            keyPolicy={keyPolicy}
             includeKey={includeKey}
             confirmKeyExport={confirmKeyExport}
+            passphrase={keyPassphrase}
             onIncludeKeyChange={setIncludeKey}
             onConfirmKeyExportChange={setConfirmKeyExport}
+            onPassphraseChange={setKeyPassphrase}
           />
 
           {destinationError && (
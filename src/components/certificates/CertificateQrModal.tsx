@@ -0,0 +1,109 @@
+import { useEffect, useState } from "react";
+
+import type { CertificateRecord } from "../../lib/certificates";
+import { certificateVerificationQr } from "../../lib/certificates";
+import { normalizeError } from "../../lib/errors";
+import { Button } from "../ui/button";
+import {
+  Dialog,
+  DialogClose,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "../ui/dialog";
+
+type CertificateQrModalProps = {
+  certificate: CertificateRecord;
+  isOpen: boolean;
+  onClose: () => void;
+};
+
+export function CertificateQrModal({
+  certificate,
+  isOpen,
+  onClose,
+}: CertificateQrModalProps) {
+  const [imageUrl, setImageUrl] = useState<string | null>(null);
+  const [payload, setPayload] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!isOpen) {
+      return;
+    }
+    let active = true;
+    let url: string | null = null;
+    setError(null);
+    certificateVerificationQr(certificate.id)
+      .then((qr) => {
+        if (!active) {
+          return;
+        }
+        url = URL.createObjectURL(
+          new Blob([new Uint8Array(qr.png)], { type: "image/png" }),
+        );
+        setImageUrl(url);
+        setPayload(qr.payload);
+      })
+      .catch((err) => {
+        if (active) {
+          setError(normalizeError(err));
+        }
+      });
+    return () => {
+      active = false;
+      if (url) {
+        URL.revokeObjectURL(url);
+      }
+      setImageUrl(null);
+    };
+  }, [certificate.id, isOpen]);
+
+  return (
+    <Dialog
+      open={isOpen}
+      onOpenChange={(open) => {
+        if (!open) {
+          onClose();
+        }
+      }}
+    >
+      <DialogContent className="max-w-md">
+        <DialogHeader>
+          <DialogTitle>Verification QR code</DialogTitle>
+          <DialogDescription>
+            Scan to compare the fingerprint and validity of{" "}
+            {certificate.sans[0] ?? certificate.id}. No key material is included.
+          </DialogDescription>
+        </DialogHeader>
+
+        {error ? (
+          <div className="rounded-lg border border-red-200 bg-red-50 p-3 text-sm text-red-700">
+            {error}
+          </div>
+        ) : imageUrl ? (
+          <div className="space-y-3">
+            <img
+              src={imageUrl}
+              alt="Certificate verification QR code"
+              className="mx-auto h-64 w-64 rounded-lg border bg-white"
+            />
+            <pre className="whitespace-pre-wrap break-all rounded-lg border bg-muted/40 p-3 text-xs text-muted-foreground">
+              {payload}
+            </pre>
+          </div>
+        ) : (
+          <div className="py-6 text-sm text-muted-foreground">Generating...</div>
+        )}
+
+        <DialogFooter>
+          <DialogClose asChild>
+            <Button variant="ghost">Done</Button>
+          </DialogClose>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
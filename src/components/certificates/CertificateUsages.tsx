@@ -0,0 +1,175 @@
+import { History, Link2, RefreshCw, X } from "lucide-react";
+import { useEffect, useState } from "react";
+import { useCertificateUsages } from "../../hooks/useCertificateUsages";
+import { listDeployTargets, type DeployTargetRecord } from "../../lib/deploy";
+import type { EndpointServingState } from "../../lib/usages";
+import { Button } from "../ui/button";
+import { Input } from "../ui/input";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "../ui/select";
+import { EndpointTimeline } from "./EndpointTimeline";
+
+const STATE_LABELS: Record<EndpointServingState, string> = {
+  current: "Serving this certificate",
+  outdated: "Still serving an older certificate",
+  newer: "Serving a newer certificate",
+  other: "Serving a different certificate",
+  unreachable: "Unreachable",
+};
+
+const STATE_CLASSES: Record<EndpointServingState, string> = {
+  current: "text-emerald-600",
+  outdated: "text-amber-600",
+  newer: "text-muted-foreground",
+  other: "text-red-500",
+  unreachable: "text-red-500",
+};
+
+interface CertificateUsagesProps {
+  certificateId: string;
+}
+
+/** Endpoints and deploy targets where the certificate is deployed. */
+export function CertificateUsages({ certificateId }: CertificateUsagesProps) {
+  const { usages, checks, checkedAt, checking, error, add, remove, check } =
+    useCertificateUsages(certificateId);
+  const [targets, setTargets] = useState<DeployTargetRecord[]>([]);
+  const [endpoint, setEndpoint] = useState("");
+  const [historyFor, setHistoryFor] = useState<string | null>(null);
+
+  useEffect(() => {
+    listDeployTargets()
+      .then(setTargets)
+      .catch(() => setTargets([]));
+  }, []);
+
+  const linkedTargets = new Set(usages.map((usage) => usage.deploy_target_id));
+  const availableTargets = targets.filter((target) => !linkedTargets.has(target.id));
+  const hasEndpoints = usages.some((usage) => usage.endpoint);
+
+  async function handleAddEndpoint() {
+    if (await add({ endpoint })) {
+      setEndpoint("");
+    }
+  }
+
+  return (
+    <div className="space-y-2">
+      <div className="flex items-center gap-2">
+        <Link2 className="h-3.5 w-3.5 text-muted-foreground" />
+        <div className="text-xs font-semibold uppercase tracking-wide text-muted-foreground">
+          Deployed to
+        </div>
+        {hasEndpoints && (
+          <Button
+            size="sm"
+            variant="ghost"
+            className="ml-auto h-7"
+            disabled={checking}
+            onClick={() => void check()}
+          >
+            <RefreshCw className={`mr-1 h-3.5 w-3.5 ${checking ? "animate-spin" : ""}`} />
+            Check endpoints
+          </Button>
+        )}
+      </div>
+      {usages.length ? (
+        <div className="space-y-1">
+          {usages.map((usage) => {
+            const result = checks.find((item) => item.usage_id === usage.id);
+            return (
+              <div key={usage.id} className="space-y-2 rounded-lg border px-3 py-2 text-sm">
+                <div className="flex items-center gap-2">
+                  <div className="min-w-0 flex-1">
+                    <div className="truncate font-medium">
+                      {usage.endpoint ??
+                        `Target: ${usage.deploy_target_label ?? usage.deploy_target_id}`}
+                    </div>
+                    {usage.inherited && (
+                      <div className="text-xs text-muted-foreground">
+                        Linked to a previous certificate for these names
+                      </div>
+                    )}
+                    {result && (
+                      <div className={`text-xs ${STATE_CLASSES[result.state]}`}>
+                        {result.error ?? STATE_LABELS[result.state]}
+                      </div>
+                    )}
+                  </div>
+                  {usage.endpoint && (
+                    <Button
+                      size="icon"
+                      variant="ghost"
+                      className="h-7 w-7"
+                      aria-label="Show history"
+                      onClick={() =>
+                        setHistoryFor(historyFor === usage.id ? null : usage.id)
+                      }
+                    >
+                      <History className="h-3.5 w-3.5" />
+                    </Button>
+                  )}
+                  <Button
+                    size="icon"
+                    variant="ghost"
+                    className="h-7 w-7"
+                    aria-label="Remove link"
+                    onClick={() => void remove(usage.id)}
+                  >
+                    <X className="h-3.5 w-3.5" />
+                  </Button>
+                </div>
+                {usage.endpoint && historyFor === usage.id && (
+                  <EndpointTimeline endpoint={usage.endpoint} refreshKey={checkedAt} />
+                )}
+              </div>
+            );
+          })}
+        </div>
+      ) : (
+        <span className="text-xs text-muted-foreground">
+          Not linked to any endpoint or deploy target
+        </span>
+      )}
+      <div className="flex flex-wrap gap-2">
+        <Input
+          className="h-8 max-w-xs"
+          placeholder="host[:port]"
+          value={endpoint}
+          onChange={(event) => setEndpoint(event.target.value)}
+          onKeyDown={(event) => {
+            if (event.key === "Enter" && endpoint.trim()) void handleAddEndpoint();
+          }}
+        />
+        <Button
+          size="sm"
+          variant="outline"
+          disabled={!endpoint.trim()}
+          onClick={() => void handleAddEndpoint()}
+        >
+          Add endpoint
+        </Button>
+        {availableTargets.length > 0 && (
+          <Select value="" onValueChange={(value) => void add({ deployTargetId: value })}>
+            <SelectTrigger className="h-8 w-48" aria-label="Link deploy target">
+              <SelectValue placeholder="Link deploy target" />
+            </SelectTrigger>
+            <SelectContent>
+              {availableTargets.map((target) => (
+                <SelectItem key={target.id} value={target.id}>
+                  {target.label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        )}
+      </div>
+      {error && <div className="text-xs text-red-500">{error}</div>}
+    </div>
+  );
+}
@@ -0,0 +1,51 @@
+import { useEffect, useState } from "react";
+import { normalizeError } from "../../lib/errors";
+import { getEndpointTimeline, type EndpointObservation } from "../../lib/usages";
+
+interface EndpointTimelineProps {
+  endpoint: string;
+  /** Changes after each endpoint check so new history is fetched */
+  refreshKey: number;
+}
+
+/** Certificates an endpoint has served; unknown ones may be unauthorized swaps. */
+export function EndpointTimeline({ endpoint, refreshKey }: EndpointTimelineProps) {
+  const [timeline, setTimeline] = useState<EndpointObservation[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getEndpointTimeline(endpoint)
+      .then(setTimeline)
+      .catch((err) => setError(normalizeError(err)));
+  }, [endpoint, refreshKey]);
+
+  if (error) {
+    return <div className="text-xs text-red-500">{error}</div>;
+  }
+  if (!timeline.length) {
+    return (
+      <div className="text-xs text-muted-foreground">
+        No history yet. Check endpoints to start recording it.
+      </div>
+    );
+  }
+
+  return (
+    <ol className="space-y-1 border-l pl-3">
+      {timeline.map((entry) => (
+        <li key={`${entry.fingerprint}:${entry.first_seen}`} className="text-xs">
+          <div className="truncate font-mono">{entry.fingerprint}</div>
+          <div className="text-muted-foreground">
+            {new Date(entry.first_seen).toLocaleString()}
+            {entry.last_seen !== entry.first_seen
+              ? ` – ${new Date(entry.last_seen).toLocaleString()}`
+              : ""}
+            {!entry.certificate_id && (
+              <span className="ml-2 font-semibold text-amber-600">Not in inventory</span>
+            )}
+          </div>
+        </li>
+      ))}
+    </ol>
+  );
+}
@@ -1,6 +1,9 @@
-export function SubjectPill({ text }: { text: string }) {
+export function SubjectPill({ text, title }: { text: string; title?: string }) {
   return (
-    <span className="rounded-full bg-muted px-3 py-1 text-s font-semibold text-foreground">
+    <span
+      title={title}
+      className="rounded-full bg-muted px-3 py-1 text-s font-semibold text-foreground"
+    >
       {text}
     </span>
   );
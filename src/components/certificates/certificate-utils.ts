@@ -21,19 +21,29 @@ export function daysUntil(dateString: string) {
 
 export function certificateStatus(record: CertificateRecord) {
   const days = daysUntil(record.not_after);
-  if (days < 0) {
-    return { label: "Expired", tone: "text-red-500 bg-red-50 dark:bg-red-950/40" };
+  switch (record.status) {
+    case "revoked":
+      return { label: "Revoked", tone: "text-red-500 bg-red-50 dark:bg-red-950/40" };
+    case "superseded":
+      return { label: "Superseded", tone: "text-muted-foreground bg-muted" };
+    case "expired":
+      return { label: "Expired", tone: "text-red-500 bg-red-50 dark:bg-red-950/40" };
+    case "pending_renewal":
+      return {
+        label: `Renewing · ${days}d left`,
+        tone: "text-sky-600 bg-sky-50 dark:bg-sky-950/40",
+      };
+    case "expiring_soon":
+      return {
+        label: `Expiring in ${days}d`,
+        tone: "text-amber-500 bg-amber-50 dark:bg-amber-950/40",
+      };
+    default:
+      return {
+        label: `Healthy · ${days}d left`,
+        tone: "text-emerald-600 bg-emerald-50 dark:bg-emerald-950/40",
+      };
   }
-  if (days < 30) {
-    return {
-      label: `Expiring in ${days}d`,
-      tone: "text-amber-500 bg-amber-50 dark:bg-amber-950/40",
-    };
-  }
-  return {
-    label: `Healthy · ${days}d left`,
-    tone: "text-emerald-600 bg-emerald-50 dark:bg-emerald-950/40",
-  };
 }
 
 export function exportFolderDefault(record: CertificateRecord) {
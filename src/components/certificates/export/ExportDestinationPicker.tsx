@@ -5,15 +5,19 @@ import { Label } from "../../ui/label";
 interface ExportDestinationPickerProps {
   destinationDir: string | null;
   folderName: string;
+  fileNameTemplate: string;
   onSelectDestination: () => void;
   onFolderNameChange: (name: string) => void;
+  onFileNameTemplateChange: (template: string) => void;
 }
 
 export function ExportDestinationPicker({
   destinationDir,
   folderName,
+  fileNameTemplate,
   onSelectDestination,
   onFolderNameChange,
+  onFileNameTemplateChange,
 }: ExportDestinationPickerProps) {
   return (
     <div className="rounded-lg border bg-muted/40 p-4">
@@ -41,6 +45,24 @@ export function ExportDestinationPicker({
           onChange={(event) => onFolderNameChange(event.target.value)}
           className="mt-2"
         />
+        <p className="mt-1 text-xs text-muted-foreground">
+          Supports {"{domain}"}, {"{serial}"}, {"{not_before}"} and {"{not_after:%Y%m%d}"}.
+        </p>
+      </div>
+      <div className="mt-3">
+        <Label
+          htmlFor="export-file-name-template"
+          className="text-xs font-semibold uppercase tracking-wide text-muted-foreground"
+        >
+          File name template (optional)
+        </Label>
+        <Input
+          id="export-file-name-template"
+          value={fileNameTemplate}
+          placeholder="{file}.pem"
+          onChange={(event) => onFileNameTemplateChange(event.target.value)}
+          className="mt-2"
+        />
       </div>
     </div>
   );
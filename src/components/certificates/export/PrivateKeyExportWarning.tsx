@@ -1,20 +1,28 @@
+import type { KeyExportPolicy } from "../../../lib/certificates";
 import { Checkbox } from "../../ui/checkbox";
+import { Input } from "../../ui/input";
 import { Label } from "../../ui/label";
 
 interface PrivateKeyExportWarningProps {
   keyAvailable: boolean;
+  keyPolicy: KeyExportPolicy;
   includeKey: boolean;
   confirmKeyExport: boolean;
+  passphrase: string;
   onIncludeKeyChange: (include: boolean) => void;
   onConfirmKeyExportChange: (confirm: boolean) => void;
+  onPassphraseChange: (passphrase: string) => void;
 }
 
 export function PrivateKeyExportWarning({
   keyAvailable,
+  keyPolicy,
   includeKey,
   confirmKeyExport,
+  passphrase,
   onIncludeKeyChange,
   onConfirmKeyExportChange,
+  onPassphraseChange,
 }: PrivateKeyExportWarningProps) {
   return (
     <div className="rounded-lg border border-amber-200/60 bg-amber-50/70 p-4 text-sm text-amber-900">
@@ -27,7 +35,7 @@ export function PrivateKeyExportWarning({
         <Checkbox
           id="include-key"
           className="mt-1 border-amber-400"
-          disabled={!keyAvailable}
+          disabled={!keyAvailable || keyPolicy === "forbidden"}
           checked={includeKey}
           onCheckedChange={(checked) => {
             const shouldInclude = checked === true;
@@ -47,6 +55,27 @@ export function PrivateKeyExportWarning({
           managed key reference.
         </div>
       )}
+      {keyAvailable && keyPolicy === "forbidden" && (
+        <div className="mt-2 text-xs text-amber-700">
+          The key export policy in Settings forbids exporting private keys.
+        </div>
+      )}
+      {includeKey && (
+        <div className="mt-3 space-y-1">
+          <Label htmlFor="key-passphrase" className="text-xs text-amber-900">
+            {keyPolicy === "encrypted_only"
+              ? "Passphrase (required by the key export policy)"
+              : "Passphrase (optional, encrypts privkey.pem)"}
+          </Label>
+          <Input
+            id="key-passphrase"
+            type="password"
+            autoComplete="new-password"
+            value={passphrase}
+            onChange={(event) => onPassphraseChange(event.target.value)}
+          />
+        </div>
+      )}
       {includeKey && (
         <div className="mt-3 flex items-start gap-2 rounded-md bg-amber-100/80 p-2">
           <Checkbox
@@ -1,6 +1,7 @@
-import type { Dispatch, FormEvent, SetStateAction } from "react";
+import { useEffect, useState, type Dispatch, type FormEvent, type SetStateAction } from "react";
 import { Plus, RefreshCw } from "lucide-react";
 import { Button } from "../ui/button";
+import { Checkbox } from "../ui/checkbox";
 import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
 import { Input } from "../ui/input";
 import { Label } from "../ui/label";
@@ -12,10 +13,12 @@ import {
   SelectValue,
 } from "../ui/select";
 import { Textarea } from "../ui/textarea";
-import type {
-  DnsProviderType,
+import {
+  getPropagationPresets,
+  type DnsProviderType,
+  type PropagationPreset,
 } from "../../lib/dns-providers";
-import { PROVIDER_OPTIONS } from "./provider-constants";
+import { PROVIDER_OPTIONS, TSIG_ALGORITHMS } from "./provider-constants";
 import type { ProviderFormState } from "../../hooks/useDnsProviderManager";
 import { ProviderCredentialsFields } from "./ProviderCredentialsFields";
 
@@ -35,11 +38,41 @@ export function DnsProviderForm({
   onCancel: () => void;
 }) {
   const requiresToken = formState.provider_type !== "manual";
+  const [presets, setPresets] = useState<PropagationPreset[]>([]);
+  const wait = presets.find(
+    (preset) => preset.provider_type === formState.provider_type,
+  )?.effective;
+
+  useEffect(() => {
+    getPropagationPresets()
+      .then(setPresets)
+      .catch(() => setPresets([]));
+  }, []);
 
   function handleFormStateChange(updates: Partial<ProviderFormState>) {
     setFormState((prev) => ({ ...prev, ...updates }));
   }
 
+  function configValue(key: string) {
+    const value = formState.config?.[key];
+    return value === undefined || value === null ? "" : String(value);
+  }
+
+  function handleConfigChange(
+    key: string,
+    value: string | number | boolean | null,
+  ) {
+    setFormState((prev) => {
+      const config = { ...(prev.config ?? {}) };
+      if (value === null || value === "") {
+        delete config[key];
+      } else {
+        config[key] = value;
+      }
+      return { ...prev, config: Object.keys(config).length ? config : null };
+    });
+  }
+
   return (
     <Card className="shadow-soft">
       <CardHeader className="flex-row items-start justify-between gap-3 space-y-0">
@@ -81,6 +114,12 @@ export function DnsProviderForm({
               ))}
             </SelectContent>
           </Select>
+          {wait ? (
+            <p className="text-xs text-muted-foreground">
+              Waits up to {wait.timeout_secs}s for challenge records to propagate,
+              checking every {wait.interval_secs}s. Adjust under Propagation wait.
+            </p>
+          ) : null}
         </div>
 
         <div className="space-y-2">
@@ -108,6 +147,160 @@ export function DnsProviderForm({
           />
         </div>
 
+        {formState.provider_type === "powerdns" ? (
+          <div className="space-y-2">
+            <Label htmlFor="provider-api-url">API URL</Label>
+            <div className="grid gap-2 sm:grid-cols-3">
+              <Input
+                id="provider-api-url"
+                className="sm:col-span-2"
+                placeholder="https://pdns.internal:8081"
+                value={configValue("api_url")}
+                onChange={(e) => handleConfigChange("api_url", e.target.value)}
+                required
+              />
+              <Input
+                aria-label="Server ID"
+                placeholder="Server ID (localhost)"
+                value={configValue("server_id")}
+                onChange={(e) => handleConfigChange("server_id", e.target.value)}
+              />
+            </div>
+            <p className="text-xs text-muted-foreground">
+              PowerDNS webserver address. The zone is notified after each change so
+              secondaries pick it up.
+            </p>
+          </div>
+        ) : null}
+
+        {formState.provider_type === "acme_dns" ? (
+          <div className="space-y-2">
+            <Label htmlFor="provider-api-url">acme-dns server URL</Label>
+            <Input
+              id="provider-api-url"
+              placeholder="https://auth.acme-dns.io"
+              value={configValue("api_url")}
+              onChange={(e) => handleConfigChange("api_url", e.target.value)}
+              required
+            />
+            <p className="text-xs text-muted-foreground">
+              Challenges are posted to acme-dns. Each domain needs a one-time CNAME from
+              its _acme-challenge name, shown when issuance starts.
+            </p>
+          </div>
+        ) : null}
+
+        {formState.provider_type === "script" ? (
+          <div className="space-y-2">
+            <Label htmlFor="provider-script-command">Command</Label>
+            <Input
+              id="provider-script-command"
+              className="font-mono text-xs"
+              placeholder="/usr/local/bin/dns-hook.sh"
+              value={configValue("command")}
+              onChange={(e) => handleConfigChange("command", e.target.value)}
+              disabled={Boolean(configValue("webhook_url"))}
+            />
+            <Label htmlFor="provider-webhook-url">or webhook URL</Label>
+            <Input
+              id="provider-webhook-url"
+              placeholder="https://automation.example.com/dns"
+              value={configValue("webhook_url")}
+              onChange={(e) => handleConfigChange("webhook_url", e.target.value)}
+              disabled={Boolean(configValue("command"))}
+            />
+            <p className="text-xs text-muted-foreground">
+              The command runs through the shell with RECORD_NAME, TXT_VALUE and ACTION
+              (present or cleanup) set. A webhook receives the same fields as a JSON POST.
+              Cleanup passes an empty value and should remove the record.
+            </p>
+          </div>
+        ) : null}
+
+        {formState.provider_type === "rfc2136" ? (
+          <div className="space-y-2">
+            <Label htmlFor="provider-dns-server">DNS server</Label>
+            <Input
+              id="provider-dns-server"
+              placeholder="ns1.example.internal:53"
+              value={configValue("server")}
+              onChange={(e) => handleConfigChange("server", e.target.value)}
+              required
+            />
+            <div className="grid gap-2 sm:grid-cols-2">
+              <Input
+                aria-label="TSIG key name"
+                placeholder="TSIG key name"
+                value={configValue("tsig_key_name")}
+                onChange={(e) => handleConfigChange("tsig_key_name", e.target.value)}
+                required
+              />
+              <Select
+                value={configValue("tsig_algorithm") || "hmac-sha256"}
+                onValueChange={(value) =>
+                  handleConfigChange(
+                    "tsig_algorithm",
+                    value === "hmac-sha256" ? null : value,
+                  )
+                }
+              >
+                <SelectTrigger aria-label="TSIG algorithm">
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  {TSIG_ALGORITHMS.map((algorithm) => (
+                    <SelectItem key={algorithm} value={algorithm}>
+                      {algorithm}
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+            </div>
+            <p className="text-xs text-muted-foreground">
+              Updates are sent over TCP to the primary; its update policy must allow the
+              key to change TXT records.
+            </p>
+          </div>
+        ) : null}
+
+        {formState.provider_type === "route53" ? (
+          <div className="space-y-2">
+            <Label htmlFor="provider-aws-profile">AWS profile (optional)</Label>
+            <Input
+              id="provider-aws-profile"
+              placeholder="default"
+              value={configValue("profile")}
+              onChange={(e) => handleConfigChange("profile", e.target.value || null)}
+            />
+            <p className="text-xs text-muted-foreground">
+              Reads credentials from this profile in ~/.aws, including SSO sessions
+              signed in with aws sso login, so no keys are stored. Saving removes any
+              stored keys.
+            </p>
+            <Label htmlFor="provider-role-arn">Assume role (optional)</Label>
+            <div className="grid gap-2 sm:grid-cols-3">
+              <Input
+                id="provider-role-arn"
+                className="font-mono text-xs sm:col-span-2"
+                placeholder="arn:aws:iam::123456789012:role/sslboard-dns"
+                value={configValue("role_arn")}
+                onChange={(e) => handleConfigChange("role_arn", e.target.value || null)}
+              />
+              <Input
+                aria-label="External ID"
+                placeholder="External ID (optional)"
+                value={configValue("external_id")}
+                onChange={(e) => handleConfigChange("external_id", e.target.value || null)}
+                disabled={!configValue("role_arn")}
+              />
+            </div>
+            <p className="text-xs text-muted-foreground">
+              Route 53 calls use temporary credentials from this role, assumed with the
+              profile or keys; with neither, the default AWS profile or environment.
+            </p>
+          </div>
+        ) : null}
+
         {requiresToken ? (
           <ProviderCredentialsFields
             providerType={formState.provider_type}
@@ -117,6 +310,115 @@ export function DnsProviderForm({
           />
         ) : null}
 
+        {requiresToken && formState.provider_type !== "acme_dns" ? (
+          <div className="space-y-2">
+            <Label>Test record</Label>
+            <p className="text-xs text-muted-foreground">
+              Where connection tests may write their temporary TXT record.
+            </p>
+            <div className="grid gap-2 sm:grid-cols-3">
+              <Input
+                aria-label="Test record prefix"
+                placeholder="Prefix (default)"
+                value={configValue("test_record_prefix")}
+                onChange={(e) => handleConfigChange("test_record_prefix", e.target.value)}
+              />
+              <Input
+                aria-label="Test record subdomain"
+                placeholder="Subdomain (optional)"
+                value={configValue("test_record_subdomain")}
+                onChange={(e) => handleConfigChange("test_record_subdomain", e.target.value)}
+              />
+              <Input
+                aria-label="Test record TTL"
+                type="number"
+                min={30}
+                max={86400}
+                placeholder="TTL (default)"
+                value={configValue("test_record_ttl")}
+                onChange={(e) =>
+                  handleConfigChange(
+                    "test_record_ttl",
+                    e.target.value ? Number(e.target.value) : null,
+                  )
+                }
+              />
+            </div>
+          </div>
+        ) : null}
+
+        {requiresToken ? (
+          <div className="space-y-2">
+            <Label>Propagation wait</Label>
+            <p className="text-xs text-muted-foreground">
+              How long issuance and tests wait for this provider&apos;s records to
+              appear in public DNS. Empty fields use the provider type&apos;s wait.
+            </p>
+            <div className="grid gap-2 sm:grid-cols-2">
+              <Input
+                aria-label="Propagation timeout"
+                type="number"
+                min={10}
+                max={1800}
+                placeholder="Wait up to (s)"
+                value={configValue("propagation_timeout_secs")}
+                onChange={(e) =>
+                  handleConfigChange(
+                    "propagation_timeout_secs",
+                    e.target.value ? Number(e.target.value) : null,
+                  )
+                }
+              />
+              <Input
+                aria-label="Propagation check interval"
+                type="number"
+                min={1}
+                max={60}
+                placeholder="Check every (s)"
+                value={configValue("propagation_interval_secs")}
+                onChange={(e) =>
+                  handleConfigChange(
+                    "propagation_interval_secs",
+                    e.target.value ? Number(e.target.value) : null,
+                  )
+                }
+              />
+            </div>
+          </div>
+        ) : null}
+
+        {requiresToken &&
+        formState.provider_type !== "route53" &&
+        formState.provider_type !== "rfc2136" ? (
+          <div className="flex items-center gap-2">
+            <Checkbox
+              id="debug-capture"
+              checked={formState.config?.debug_capture === true}
+              onCheckedChange={(checked) =>
+                handleConfigChange("debug_capture", checked === true ? true : null)
+              }
+            />
+            <Label htmlFor="debug-capture" className="text-sm font-medium">
+              Capture API calls for debugging (credentials redacted)
+            </Label>
+          </div>
+        ) : null}
+
+        {formMode === "create" ? (
+          <div className="flex items-center gap-2">
+            <Checkbox
+              id="verify-suffixes"
+              checked={formState.verify_suffixes ?? false}
+              onCheckedChange={(checked) =>
+                handleFormStateChange({ verify_suffixes: checked === true })
+              }
+            />
+            <Label htmlFor="verify-suffixes" className="text-sm font-medium">
+              Verify NS delegation and zone access
+            </Label>
+          </div>
+        ) : null}
+
         <Button type="submit" className="w-full gap-2" disabled={saving}>
           {saving ? (
             <RefreshCw className="h-4 w-4 animate-spin" />
@@ -7,13 +7,32 @@ import {
   XCircle,
 } from "lucide-react";
 import { Button } from "../ui/button";
-import type { DnsProviderRecord, DnsProviderTestResult } from "../../lib/dns-providers";
+import type {
+  DnsProviderRecord,
+  DnsProviderTestResult,
+  ProviderTestMode,
+} from "../../lib/dns-providers";
+import { propagationReason, resolverConsensus } from "../../lib/dns";
 import { cn } from "../../lib/utils";
 import {
   ERROR_CATEGORY_LABELS,
   ERROR_CATEGORY_SUGGESTIONS,
   PROVIDER_LABELS,
 } from "./provider-constants";
+import { ProviderAccountLabel } from "./ProviderAccountLabel";
+import { ProviderDebugLogPanel } from "./ProviderDebugLogPanel";
+import { ProviderRecordCleanupPanel } from "./ProviderRecordCleanupPanel";
+import { ProviderPriorityField } from "./ProviderPriorityField";
+import { ZoneDiscoveryPanel } from "./ZoneDiscoveryPanel";
+
+/** Provider types whose API can list TXT records. */
+const CLEANUP_PROVIDER_TYPES = new Set<string>([
+  "cloudflare",
+  "digitalocean",
+  "route53",
+  "desec",
+  "powerdns",
+]);
 
 function formatDate(iso: string) {
   const date = new Date(iso);
@@ -40,6 +59,7 @@ export function DnsProviderList({
   testResults,
   testLoading,
   onTest,
+  onProviderUpdated,
 }: {
   providers: DnsProviderRecord[];
   loading: boolean;
@@ -51,7 +71,8 @@ export function DnsProviderList({
   onCancelDelete: () => void;
   testResults: Record<string, DnsProviderTestResult | null>;
   testLoading: Record<string, boolean>;
-  onTest: (providerId: string) => void;
+  onTest: (providerId: string, mode?: ProviderTestMode) => void;
+  onProviderUpdated: (provider: DnsProviderRecord) => void;
 }) {
   const overlapSuffixes = useMemo(() => {
     const counts = new Map<string, number>();
@@ -73,7 +94,8 @@ export function DnsProviderList({
         <div>
           <div className="text-sm font-semibold">Configured providers</div>
           <p className="text-xs text-muted-foreground">
-            Providers are matched by domain suffix; longest suffix wins.
+            Providers are matched by domain suffix; longest suffix wins, then
+            the highest priority, then the account that can see the zone.
           </p>
         </div>
         <Button
@@ -118,9 +140,13 @@ export function DnsProviderList({
                       {PROVIDER_LABELS[provider.provider_type]}
                     </span>
                   </div>
-                  <div className="mt-1 text-xs text-muted-foreground">
+                  <div
+                    className="mt-1 text-xs text-muted-foreground"
+                    title={provider.suffix_forms?.map((form) => form.ascii).join(", ")}
+                  >
                     Domains: {provider.domain_suffixes.join(", ") || "-"}
                   </div>
+                  <ProviderAccountLabel provider={provider} />
                   <div className="mt-1 text-xs text-muted-foreground">
                     Updated {formatDate(provider.updated_at)}
                   </div>
@@ -130,6 +156,12 @@ export function DnsProviderList({
                       Overlapping suffixes: {overlaps.join(", ")}
                     </div>
                   ) : null}
+                  {overlaps.length ? (
+                    <ProviderPriorityField
+                      provider={provider}
+                      onUpdated={onProviderUpdated}
+                    />
+                  ) : null}
                   {testResult ? (
                     <div className="mt-2 flex items-center gap-2 text-xs">
                       {testResult.success ? (
@@ -145,11 +177,39 @@ export function DnsProviderList({
                         }
                       >
                         {testResult.success
-                          ? "Connection verified"
-                          : testResult.error || "Connection failed"}
+                          ? testResult.mode === "credentials_only"
+                            ? testResult.write_access === "verified"
+                              ? "Credentials and write access verified"
+                              : "Credentials verified; write access is only confirmed by a full test"
+                            : testResult.mode === "dry_run"
+                              ? "Zone access verified; no records were changed"
+                              : "Connection verified"
+                          : testResult.error ||
+                            propagationReason(testResult.propagation) ||
+                            "Connection failed"}
                       </span>
                     </div>
                   ) : null}
+                  {resolverConsensus(testResult?.propagation) ? (
+                    <div className="mt-1 text-xs text-muted-foreground">
+                      {resolverConsensus(testResult?.propagation)}
+                    </div>
+                  ) : null}
+                  {testResult?.zones?.length ? (
+                    <div className="mt-1 text-xs text-muted-foreground">
+                      Zones visible: {testResult.zones.join(", ")}
+                    </div>
+                  ) : null}
+                  {testResult?.planned_operations?.length ? (
+                    <ul className="mt-1 space-y-0.5 text-xs text-muted-foreground">
+                      {testResult.planned_operations.map((op, index) => (
+                        <li key={index} className="font-mono">
+                          Would {op.operation} TXT {op.record_name}
+                          {op.value ? ` = ${op.value}` : ""}
+                        </li>
+                      ))}
+                    </ul>
+                  ) : null}
                   {testResult && !testResult.success && testResult.error_category ? (
                     <div className="mt-1 text-xs text-rose-700">
                       {ERROR_CATEGORY_LABELS[testResult.error_category]} ·{" "}
@@ -170,6 +230,26 @@ export function DnsProviderList({
                     ) : null}
                     Test connection
                   </Button>
+                  <Button
+                    type="button"
+                    variant="outline"
+                    size="sm"
+                    title="Checks the token, zones and write permission without creating records"
+                    onClick={() => onTest(provider.id, "credentials_only")}
+                    disabled={testInFlight}
+                  >
+                    Check credentials
+                  </Button>
+                  <Button
+                    type="button"
+                    variant="outline"
+                    size="sm"
+                    title="Checks zone access and lists the test record changes without making them"
+                    onClick={() => onTest(provider.id, "dry_run")}
+                    disabled={testInFlight}
+                  >
+                    Dry run
+                  </Button>
                   <Button
                     type="button"
                     variant="ghost"
@@ -190,6 +270,15 @@ export function DnsProviderList({
                   </Button>
                 </div>
               </div>
+              {provider.provider_type !== "manual" ? (
+                <ZoneDiscoveryPanel provider={provider} onUpdated={onProviderUpdated} />
+              ) : null}
+              {CLEANUP_PROVIDER_TYPES.has(provider.provider_type) ? (
+                <ProviderRecordCleanupPanel provider={provider} />
+              ) : null}
+              {provider.config?.debug_capture === true ? (
+                <ProviderDebugLogPanel provider={provider} />
+              ) : null}
               {confirmDeleteId === provider.id ? (
                 <div className="mt-3 flex flex-wrap items-center gap-2 rounded-md border border-destructive/30 bg-destructive/5 px-3 py-2 text-xs text-destructive">
                   <span className="font-semibold">
@@ -0,0 +1,137 @@
+import { useEffect, useState } from "react";
+import { Timer } from "lucide-react";
+import { Button } from "../ui/button";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+import { Input } from "../ui/input";
+import {
+  getPropagationPresets,
+  setPropagationOverrides,
+  type DnsProviderType,
+  type PropagationPreset,
+  type PropagationWait,
+} from "../../lib/dns-providers";
+import { normalizeError } from "../../lib/errors";
+import { PROVIDER_LABELS } from "./provider-constants";
+
+function sameWait(a: PropagationWait, b: PropagationWait) {
+  return a.timeout_secs === b.timeout_secs && a.interval_secs === b.interval_secs;
+}
+
+/** Overrides for every row whose wait differs from its preset. */
+function overridesOf(presets: PropagationPreset[]) {
+  const overrides: Partial<Record<DnsProviderType, PropagationWait>> = {};
+  for (const row of presets) {
+    if (!sameWait(row.effective, row.preset)) {
+      overrides[row.provider_type] = row.effective;
+    }
+  }
+  return overrides;
+}
+
+export function PropagationWaitSettings() {
+  const [presets, setPresets] = useState<PropagationPreset[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  async function load() {
+    try {
+      setPresets(await getPropagationPresets());
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  useEffect(() => {
+    void load();
+  }, []);
+
+  function edit(providerType: DnsProviderType, field: keyof PropagationWait, raw: string) {
+    const value = Number.parseInt(raw, 10);
+    if (Number.isNaN(value)) return;
+    setPresets((rows) =>
+      rows.map((row) =>
+        row.provider_type === providerType
+          ? { ...row, effective: { ...row.effective, [field]: value } }
+          : row,
+      ),
+    );
+  }
+
+  async function save(next: PropagationPreset[]) {
+    setError(null);
+    try {
+      await setPropagationOverrides(overridesOf(next));
+      await load();
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  function reset(providerType: DnsProviderType) {
+    const next = presets.map((row) =>
+      row.provider_type === providerType ? { ...row, effective: row.preset } : row,
+    );
+    setPresets(next);
+    void save(next);
+  }
+
+  return (
+    <Card className="shadow-soft">
+      <CardHeader className="flex-row items-center gap-3 space-y-0">
+        <Timer className="h-5 w-5 text-primary" />
+        <div>
+          <CardTitle className="text-sm font-semibold">Propagation wait</CardTitle>
+          <p className="text-sm text-muted-foreground">
+            How long issuance and provider tests wait for challenge records to
+            appear in public DNS, and how often they check. Each provider type
+            starts from a tuned preset.
+          </p>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-2">
+        {error ? (
+          <div className="rounded-lg border border-destructive/50 bg-destructive/10 px-4 py-3 text-sm text-destructive">
+            {error}
+          </div>
+        ) : null}
+        <div className="grid grid-cols-[1.5fr_1fr_1fr_auto] gap-2 text-xs font-medium text-muted-foreground">
+          <div>Provider</div>
+          <div>Wait up to (s)</div>
+          <div>Check every (s)</div>
+          <div />
+        </div>
+        {presets.map((row) => (
+          <div
+            key={row.provider_type}
+            className="grid grid-cols-[1.5fr_1fr_1fr_auto] items-center gap-2 text-sm"
+          >
+            <div>{PROVIDER_LABELS[row.provider_type] ?? row.provider_type}</div>
+            <Input
+              type="number"
+              min={10}
+              className="h-8"
+              value={row.effective.timeout_secs}
+              onChange={(event) => edit(row.provider_type, "timeout_secs", event.target.value)}
+              onBlur={() => void save(presets)}
+            />
+            <Input
+              type="number"
+              min={1}
+              className="h-8"
+              value={row.effective.interval_secs}
+              onChange={(event) => edit(row.provider_type, "interval_secs", event.target.value)}
+              onBlur={() => void save(presets)}
+            />
+            <Button
+              variant="ghost"
+              size="sm"
+              disabled={sameWait(row.effective, row.preset)}
+              onClick={() => reset(row.provider_type)}
+            >
+              Preset
+            </Button>
+          </div>
+        ))}
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,41 @@
+import { useEffect, useState } from "react";
+import { getDnsProviderAccount, type DnsProviderRecord } from "../../lib/dns-providers";
+import { normalizeError } from "../../lib/errors";
+
+/** Provider types whose API reports the account behind the credentials. */
+const ACCOUNT_PROVIDER_TYPES = new Set<string>(["cloudflare", "route53", "digitalocean"]);
+
+/**
+ * Account the provider's credentials belong to, asked from its API so
+ * providers of one type with different credentials can be told apart.
+ */
+export function ProviderAccountLabel({ provider }: { provider: DnsProviderRecord }) {
+  const [account, setAccount] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const supported = ACCOUNT_PROVIDER_TYPES.has(provider.provider_type);
+
+  useEffect(() => {
+    if (!supported) return;
+    let cancelled = false;
+    setAccount(null);
+    setError(null);
+    getDnsProviderAccount(provider.id)
+      .then((next) => {
+        if (!cancelled) setAccount(next);
+      })
+      .catch((err) => {
+        if (!cancelled) setError(normalizeError(err));
+      });
+    return () => {
+      cancelled = true;
+    };
+    // Credentials change with the provider's updated_at
+  }, [provider.id, provider.updated_at, supported]);
+
+  if (!supported || (!account && !error)) return null;
+  return (
+    <div className="mt-1 text-xs text-muted-foreground" title={error ?? undefined}>
+      Account: {account ?? "could not be read"}
+    </div>
+  );
+}
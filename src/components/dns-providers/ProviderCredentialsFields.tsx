@@ -1,5 +1,6 @@
 import { Input } from "../ui/input";
 import { Label } from "../ui/label";
+import { Textarea } from "../ui/textarea";
 import type {
   DnsProviderType,
 } from "../../lib/dns-providers";
@@ -19,6 +20,8 @@ export function ProviderCredentialsFields({
   onFormStateChange,
 }: ProviderCredentialsFieldsProps) {
   const isRoute53 = providerType === "route53";
+  const usesProfile = Boolean(formState.config?.profile);
+  const keysRequired = formMode === "create" && !formState.config?.role_arn && !usesProfile;
 
   return (
     <div className="space-y-2">
@@ -39,7 +42,8 @@ export function ProviderCredentialsFields({
               onChange={(e) => {
                 onFormStateChange({ route53_access_key: e.target.value });
               }}
-              required={formMode === "create"}
+              required={keysRequired}
+              disabled={usesProfile}
             />
           </div>
           <div className="space-y-2">
@@ -57,13 +61,42 @@ export function ProviderCredentialsFields({
               onChange={(e) => {
                 onFormStateChange({ route53_secret_key: e.target.value });
               }}
-              required={formMode === "create"}
+              required={keysRequired}
+              disabled={usesProfile}
             />
           </div>
         </div>
+      ) : providerType === "acme_dns" ? (
+        <div className="space-y-2">
+          <Label htmlFor="provider-api-token">Registrations (JSON)</Label>
+          <Textarea
+            id="provider-api-token"
+            autoComplete="off"
+            rows={4}
+            className="font-mono text-xs"
+            placeholder={
+              formMode === "edit"
+                ? "Paste new registrations to replace them (optional)"
+                : '{"example.com": {"username": "...", "password": "...", "fulldomain": "...", "subdomain": "..."}}'
+            }
+            value={formState.api_token || ""}
+            onChange={(e) => {
+              onFormStateChange({ api_token: e.target.value });
+            }}
+          />
+          <p className="text-xs text-muted-foreground">
+            Leave empty to register a new acme-dns account for each domain suffix.
+          </p>
+        </div>
       ) : (
         <div className="space-y-2">
-          <Label htmlFor="provider-api-token">API token</Label>
+          <Label htmlFor="provider-api-token">
+            {providerType === "rfc2136"
+              ? "TSIG secret (base64)"
+              : providerType === "script"
+                ? "Webhook bearer token (optional)"
+                : "API token"}
+          </Label>
           <Input
             id="provider-api-token"
             type="password"
@@ -77,7 +110,7 @@ export function ProviderCredentialsFields({
             onChange={(e) => {
               onFormStateChange({ api_token: e.target.value });
             }}
-            required={formMode === "create"}
+            required={formMode === "create" && providerType !== "script"}
           />
         </div>
       )}
@@ -0,0 +1,106 @@
+import { useState } from "react";
+import { RefreshCw } from "lucide-react";
+import { Button } from "../ui/button";
+import {
+  clearProviderDebugLog,
+  getProviderDebugLog,
+  type DnsProviderRecord,
+  type ProviderDebugEntry,
+} from "../../lib/dns-providers";
+import { normalizeError } from "../../lib/errors";
+
+export function ProviderDebugLogPanel({ provider }: { provider: DnsProviderRecord }) {
+  const [entries, setEntries] = useState<ProviderDebugEntry[] | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  async function handleLoad() {
+    setLoading(true);
+    setError(null);
+    try {
+      setEntries(await getProviderDebugLog(provider.id));
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setLoading(false);
+    }
+  }
+
+  async function handleClear() {
+    setError(null);
+    try {
+      await clearProviderDebugLog(provider.id);
+      setEntries([]);
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  if (entries === null) {
+    return (
+      <div className="mt-3 space-y-2">
+        <Button
+          type="button"
+          variant="outline"
+          size="sm"
+          onClick={() => void handleLoad()}
+          disabled={loading}
+        >
+          {loading ? <RefreshCw className="mr-2 h-4 w-4 animate-spin" /> : null}
+          Show debug log
+        </Button>
+        {error ? <div className="text-xs text-rose-700">{error}</div> : null}
+      </div>
+    );
+  }
+
+  return (
+    <div className="mt-3 space-y-2 rounded-md border bg-muted/40 p-3 text-xs">
+      <div className="font-semibold">Captured API calls (credentials redacted)</div>
+      {entries.length === 0 ? (
+        <div className="text-muted-foreground">
+          No calls captured yet. Run a connection test or an issuance.
+        </div>
+      ) : (
+        <div className="max-h-96 space-y-2 overflow-y-auto">
+          {entries.map((entry, index) => (
+            <details key={`${entry.captured_at}-${index}`} className="rounded border bg-background p-2">
+              <summary className="cursor-pointer font-mono">
+                {entry.method} {entry.status ?? "—"} {entry.url}{" "}
+                <span className="text-muted-foreground">({entry.elapsed_ms} ms)</span>
+              </summary>
+              <div className="mt-2 space-y-1">
+                <div className="text-muted-foreground">
+                  {new Date(entry.captured_at).toLocaleString()}
+                </div>
+                {entry.error ? <div className="text-rose-700">{entry.error}</div> : null}
+                {entry.request_body ? (
+                  <pre className="whitespace-pre-wrap break-all font-mono">
+                    {entry.request_body}
+                  </pre>
+                ) : null}
+                {entry.response_body ? (
+                  <pre className="whitespace-pre-wrap break-all font-mono text-muted-foreground">
+                    {entry.response_body}
+                  </pre>
+                ) : null}
+              </div>
+            </details>
+          ))}
+        </div>
+      )}
+      {error ? <div className="text-rose-700">{error}</div> : null}
+      <div className="flex gap-2">
+        <Button type="button" size="sm" variant="outline" onClick={() => void handleLoad()}>
+          Refresh
+        </Button>
+        <Button type="button" size="sm" variant="outline" onClick={() => void handleClear()}>
+          Clear
+        </Button>
+        <Button type="button" variant="ghost" size="sm" onClick={() => setEntries(null)}>
+          Close
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,168 @@
+import { useEffect, useState } from "react";
+import { HeartPulse } from "lucide-react";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+import { Input } from "../ui/input";
+import { Label } from "../ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "../ui/select";
+import {
+  HEALTH_CHECK_MODE_PREFERENCE,
+  HEALTH_INTERVAL_PREFERENCE,
+  getDnsProviderHealthHistory,
+  listenProviderHealthFailed,
+  type DnsProviderHealthCheck,
+  type DnsProviderRecord,
+  type ProviderHealthCheckKind,
+} from "../../lib/dns-providers";
+import { normalizeError } from "../../lib/errors";
+import { getPreference, setPreference } from "../../lib/preferences";
+import { cn } from "../../lib/utils";
+import { ERROR_CATEGORY_LABELS } from "./provider-constants";
+
+/** Checks shown per provider in the trend strip. */
+const TREND_LENGTH = 20;
+
+export function ProviderHealthDashboard({ providers }: { providers: DnsProviderRecord[] }) {
+  const [history, setHistory] = useState<DnsProviderHealthCheck[]>([]);
+  const [intervalHours, setIntervalHours] = useState("");
+  const [mode, setMode] = useState<ProviderHealthCheckKind>("credentials");
+  const [error, setError] = useState<string | null>(null);
+
+  async function load() {
+    try {
+      const [checks, interval, savedMode] = await Promise.all([
+        getDnsProviderHealthHistory(undefined, 500),
+        getPreference(HEALTH_INTERVAL_PREFERENCE),
+        getPreference(HEALTH_CHECK_MODE_PREFERENCE),
+      ]);
+      setHistory(checks);
+      setIntervalHours(interval?.value ?? "");
+      setMode(savedMode?.value === "full_test" ? "full_test" : "credentials");
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  useEffect(() => {
+    void load();
+    const unlisten = listenProviderHealthFailed(() => void load());
+    return () => {
+      void unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  async function save(name: string, value: string) {
+    setError(null);
+    try {
+      await setPreference(name, value);
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  const automated = providers.filter((provider) => provider.provider_type !== "manual");
+
+  return (
+    <Card className="shadow-soft">
+      <CardHeader className="flex-row items-center gap-3 space-y-0">
+        <HeartPulse className="h-5 w-5 text-primary" />
+        <div>
+          <CardTitle className="text-sm font-semibold">Provider health</CardTitle>
+          <p className="text-sm text-muted-foreground">
+            Checks each provider in the background so a revoked or expired token
+            shows up before an issuance needs it.
+          </p>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {error ? (
+          <div className="rounded-lg border border-destructive/50 bg-destructive/10 px-4 py-3 text-sm text-destructive">
+            {error}
+          </div>
+        ) : null}
+        <div className="flex flex-wrap items-end gap-4">
+          <div className="space-y-1">
+            <Label htmlFor="provider-health-interval">Check every (hours)</Label>
+            <Input
+              id="provider-health-interval"
+              type="number"
+              min={0}
+              className="h-8 w-32"
+              placeholder="Off"
+              value={intervalHours}
+              onChange={(event) => setIntervalHours(event.target.value)}
+              onBlur={() => void save(HEALTH_INTERVAL_PREFERENCE, intervalHours.trim())}
+            />
+          </div>
+          <div className="space-y-1">
+            <Label>Check</Label>
+            <Select
+              value={mode}
+              onValueChange={(value) => {
+                setMode(value as ProviderHealthCheckKind);
+                void save(HEALTH_CHECK_MODE_PREFERENCE, value);
+              }}
+            >
+              <SelectTrigger className="h-8 w-56" aria-label="Health check">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="credentials">Credentials and zone access</SelectItem>
+                <SelectItem value="full_test">Full test record</SelectItem>
+              </SelectContent>
+            </Select>
+          </div>
+        </div>
+        {automated.length === 0 ? (
+          <div className="text-sm text-muted-foreground">No automated providers yet.</div>
+        ) : (
+          <div className="space-y-2">
+            {automated.map((provider) => {
+              const checks = history
+                .filter((check) => check.provider_id === provider.id)
+                .slice(0, TREND_LENGTH);
+              const latest = checks[0];
+              return (
+                <div key={provider.id} className="rounded-md border px-3 py-2 text-sm">
+                  <div className="flex flex-wrap items-center justify-between gap-2">
+                    <span className="font-medium">{provider.label}</span>
+                    <div className="flex gap-0.5">
+                      {[...checks].reverse().map((check) => (
+                        <span
+                          key={check.checked_at}
+                          title={new Date(check.checked_at).toLocaleString()}
+                          className={cn(
+                            "h-3 w-1.5 rounded-sm",
+                            check.success ? "bg-emerald-500" : "bg-rose-500",
+                          )}
+                        />
+                      ))}
+                    </div>
+                  </div>
+                  <div className="text-xs text-muted-foreground">
+                    {latest
+                      ? `Last checked ${new Date(latest.checked_at).toLocaleString()}`
+                      : "Not checked yet"}
+                  </div>
+                  {latest && !latest.success ? (
+                    <div className="mt-1 text-xs text-rose-700">
+                      {latest.error_category
+                        ? `${ERROR_CATEGORY_LABELS[latest.error_category]}: `
+                        : null}
+                      {latest.error}
+                    </div>
+                  ) : null}
+                </div>
+              );
+            })}
+          </div>
+        )}
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,326 @@
+import { open, save } from "@tauri-apps/plugin-dialog";
+import { Download, FileKey, Upload } from "lucide-react";
+import { useState } from "react";
+import {
+  createDnsProvider,
+  exportDnsProviders,
+  importDnsProviders,
+  importDnsProvidersFromEnv,
+  previewDnsProviderImport,
+  type DnsCredentialField,
+  type DnsProviderFileImportResult,
+  type DnsProviderImportCandidate,
+  type EnvProviderImportResult,
+} from "../../lib/dns-providers";
+import { normalizeError } from "../../lib/errors";
+import { Button } from "../ui/button";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+import { Input } from "../ui/input";
+import { Label } from "../ui/label";
+import { PROVIDER_LABELS } from "./provider-constants";
+
+const CREDENTIAL_LABELS: Record<DnsCredentialField, string> = {
+  api_token: "API token",
+  route53_access_key: "Access key ID",
+  route53_secret_key: "Secret access key",
+};
+
+type Credentials = Partial<Record<DnsCredentialField, string>>;
+
+interface ProviderImportExportProps {
+  onImported: () => void;
+}
+
+/**
+ * Moves provider settings between machines. Credentials are re-entered, or
+ * travel in the file encrypted to a passphrase.
+ */
+export function ProviderImportExport({ onImported }: ProviderImportExportProps) {
+  const [passphrase, setPassphrase] = useState("");
+  const [importPath, setImportPath] = useState<string | null>(null);
+  const [candidates, setCandidates] = useState<DnsProviderImportCandidate[]>([]);
+  const [credentials, setCredentials] = useState<Record<number, Credentials>>({});
+  const [imported, setImported] = useState<Set<number>>(new Set());
+  const [envResult, setEnvResult] = useState<EnvProviderImportResult | null>(null);
+  const [fileResult, setFileResult] = useState<DnsProviderFileImportResult | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  async function handleExport() {
+    const path = await save({
+      defaultPath: "sslboard-dns-providers.json",
+      filters: [{ name: "JSON", extensions: ["json"] }],
+    });
+    if (!path) return;
+    setBusy(true);
+    setError(null);
+    try {
+      const result = await exportDnsProviders(path, [], passphrase);
+      setMessage(
+        result.credentials_included
+          ? `Exported ${result.exported} provider(s) with encrypted credentials to ${result.path}`
+          : `Exported ${result.exported} provider(s) to ${result.path}`,
+      );
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function handleOpen() {
+    const selection = await open({
+      multiple: false,
+      filters: [{ name: "JSON", extensions: ["json"] }],
+    });
+    const path = Array.isArray(selection) ? selection[0] : selection;
+    if (typeof path !== "string" || !path) return;
+    setBusy(true);
+    setError(null);
+    setMessage(null);
+    try {
+      setCandidates(await previewDnsProviderImport(path));
+      setImportPath(path);
+      setFileResult(null);
+      setCredentials({});
+      setImported(new Set());
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function handleEnvImport() {
+    const selection = await open({ multiple: false });
+    const path = Array.isArray(selection) ? selection[0] : selection;
+    if (typeof path !== "string" || !path) return;
+    setBusy(true);
+    setError(null);
+    setMessage(null);
+    try {
+      const result = await importDnsProvidersFromEnv(path);
+      setEnvResult(result);
+      if (result.imported.length > 0) onImported();
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function handleImportAll() {
+    if (!importPath) return;
+    setBusy(true);
+    setError(null);
+    try {
+      const result = await importDnsProviders(importPath, passphrase);
+      setFileResult(result);
+      const added = new Set(result.imported.map((entry) => entry.label));
+      setImported((current) => {
+        const next = new Set(current);
+        candidates.forEach((candidate, index) => {
+          if (added.has(candidate.label)) next.add(index);
+        });
+        return next;
+      });
+      if (result.imported.length > 0) onImported();
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  function setCredential(index: number, field: DnsCredentialField, value: string) {
+    setCredentials((current) => ({
+      ...current,
+      [index]: { ...current[index], [field]: value },
+    }));
+  }
+
+  async function handleImport(index: number) {
+    const candidate = candidates[index];
+    const entered = credentials[index] ?? {};
+    setBusy(true);
+    setError(null);
+    try {
+      await createDnsProvider({
+        provider_type: candidate.provider_type,
+        label: candidate.label,
+        domain_suffixes: candidate.domain_suffixes.join(", "),
+        api_token: entered.api_token?.trim() || undefined,
+        route53_access_key: entered.route53_access_key?.trim() || undefined,
+        route53_secret_key: entered.route53_secret_key?.trim() || undefined,
+        config: candidate.config ?? null,
+      });
+      setImported((current) => new Set(current).add(index));
+      onImported();
+    } catch (err) {
+      setError(`${candidate.label}: ${normalizeError(err)}`);
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  return (
+    <Card className="shadow-soft">
+      <CardHeader className="flex-row flex-wrap items-center justify-between gap-3 space-y-0">
+        <div>
+          <CardTitle className="text-sm font-semibold">Share configuration</CardTitle>
+          <p className="text-sm text-muted-foreground">
+            Export provider settings without credentials, then import them on
+            another workstation and enter only the credentials. With a
+            passphrase, credentials are included encrypted to it. Credentials
+            saved by lego or acme.sh can be imported from their env files.
+          </p>
+        </div>
+        <div className="flex flex-wrap items-center gap-2">
+          <Input
+            type="password"
+            autoComplete="off"
+            className="h-8 w-48"
+            placeholder="Passphrase (optional)"
+            aria-label="Export passphrase"
+            value={passphrase}
+            onChange={(event) => setPassphrase(event.target.value)}
+          />
+          <Button
+            size="sm"
+            variant="outline"
+            className="gap-2"
+            disabled={busy}
+            onClick={() => void handleExport()}
+          >
+            <Download className="h-4 w-4" />
+            Export
+          </Button>
+          <Button
+            size="sm"
+            variant="outline"
+            className="gap-2"
+            disabled={busy}
+            onClick={() => void handleOpen()}
+          >
+            <Upload className="h-4 w-4" />
+            Import
+          </Button>
+          <Button
+            size="sm"
+            variant="outline"
+            className="gap-2"
+            disabled={busy}
+            onClick={() => void handleEnvImport()}
+          >
+            <FileKey className="h-4 w-4" />
+            From lego / acme.sh
+          </Button>
+        </div>
+      </CardHeader>
+      {error || message || envResult || fileResult || candidates.length > 0 ? (
+        <CardContent className="space-y-3">
+          {error ? <div className="text-sm text-destructive">{error}</div> : null}
+          {message ? <div className="text-sm text-muted-foreground">{message}</div> : null}
+          {envResult ? (
+            <div className="space-y-1 rounded-lg border bg-background/80 p-3 text-sm">
+              {envResult.imported.map((entry) => (
+                <div key={entry.provider_id ?? entry.label}>
+                  Added <span className="font-semibold">{entry.label}</span> ·{" "}
+                  {entry.domain_suffixes.join(", ")}
+                </div>
+              ))}
+              {envResult.skipped.map((entry) => (
+                <div key={`${entry.label}-skipped`} className="text-amber-700">
+                  Skipped {entry.label} ({entry.source_keys.join(", ")}): {entry.skipped_reason}
+                </div>
+              ))}
+            </div>
+          ) : null}
+          {candidates.length > 0 ? (
+            <div className="flex flex-wrap items-center justify-between gap-2 text-sm">
+              <span className="text-muted-foreground">
+                {candidates.some((candidate) => candidate.sealed_credentials)
+                  ? "This file includes encrypted credentials."
+                  : "This file has no credentials; enter them per provider."}
+              </span>
+              <Button size="sm" disabled={busy} onClick={() => void handleImportAll()}>
+                Import all
+              </Button>
+            </div>
+          ) : null}
+          {fileResult ? (
+            <div className="space-y-1 rounded-lg border bg-background/80 p-3 text-sm">
+              {fileResult.imported.map((entry) => (
+                <div key={entry.provider_id ?? entry.label}>
+                  Added <span className="font-semibold">{entry.label}</span>
+                </div>
+              ))}
+              {fileResult.skipped.map((entry) => (
+                <div key={`${entry.label}-skipped`} className="text-amber-700">
+                  Skipped {entry.label}: {entry.skipped_reason}
+                </div>
+              ))}
+            </div>
+          ) : null}
+          {candidates.map((candidate, index) => {
+            const done = imported.has(index);
+            const entered = credentials[index] ?? {};
+            const missing = candidate.credentials.some(
+              (placeholder) => placeholder.required && !entered[placeholder.field]?.trim(),
+            );
+            return (
+              <div
+                key={`${candidate.label}-${index}`}
+                className="space-y-2 rounded-lg border bg-background/80 p-3"
+              >
+                <div className="flex flex-wrap items-center justify-between gap-2">
+                  <div>
+                    <div className="text-sm font-semibold">{candidate.label}</div>
+                    <div className="text-xs text-muted-foreground">
+                      {PROVIDER_LABELS[candidate.provider_type]} ·{" "}
+                      {candidate.domain_suffixes.join(", ")}
+                    </div>
+                    {candidate.existing_label ? (
+                      <div className="text-xs text-amber-600">
+                        Already configured as “{candidate.existing_label}”
+                      </div>
+                    ) : null}
+                  </div>
+                  <Button
+                    size="sm"
+                    disabled={busy || done || missing}
+                    onClick={() => void handleImport(index)}
+                  >
+                    {done ? "Imported" : "Add provider"}
+                  </Button>
+                </div>
+                {!done &&
+                  candidate.credentials.map((placeholder) => {
+                    const id = `import-${index}-${placeholder.field}`;
+                    return (
+                      <div key={placeholder.field} className="space-y-1">
+                        <Label htmlFor={id}>
+                          {CREDENTIAL_LABELS[placeholder.field]}
+                          {placeholder.required ? "" : " (optional)"}
+                        </Label>
+                        <Input
+                          id={id}
+                          type="password"
+                          autoComplete="off"
+                          value={entered[placeholder.field] ?? ""}
+                          onChange={(event) =>
+                            setCredential(index, placeholder.field, event.target.value)
+                          }
+                        />
+                      </div>
+                    );
+                  })}
+              </div>
+            );
+          })}
+        </CardContent>
+      ) : null}
+    </Card>
+  );
+}
@@ -0,0 +1,66 @@
+import { useEffect, useState } from "react";
+import { Input } from "../ui/input";
+import { Label } from "../ui/label";
+import {
+  setDnsProviderPriority,
+  type DnsProviderRecord,
+} from "../../lib/dns-providers";
+import { normalizeError } from "../../lib/errors";
+
+/** Priority input shown for providers whose suffixes overlap another's. */
+export function ProviderPriorityField({
+  provider,
+  onUpdated,
+}: {
+  provider: DnsProviderRecord;
+  onUpdated: (provider: DnsProviderRecord) => void;
+}) {
+  const [value, setValue] = useState(String(provider.priority ?? 0));
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setValue(String(provider.priority ?? 0));
+  }, [provider.priority]);
+
+  async function save() {
+    const priority = Number.parseInt(value, 10);
+    if (Number.isNaN(priority) || priority === provider.priority) {
+      setValue(String(provider.priority ?? 0));
+      return;
+    }
+    setSaving(true);
+    setError(null);
+    try {
+      onUpdated(await setDnsProviderPriority(provider.id, priority));
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  return (
+    <div className="mt-2 space-y-1">
+      <div className="flex items-center gap-2">
+        <Label htmlFor={`priority-${provider.id}`} className="text-xs">
+          Priority
+        </Label>
+        <Input
+          id={`priority-${provider.id}`}
+          type="number"
+          step={1}
+          className="h-7 w-20 text-xs"
+          value={value}
+          disabled={saving}
+          onChange={(event) => setValue(event.target.value)}
+          onBlur={() => void save()}
+        />
+        <span className="text-xs text-muted-foreground">
+          Higher wins for names both providers match.
+        </span>
+      </div>
+      {error ? <div className="text-xs text-destructive">{error}</div> : null}
+    </div>
+  );
+}
@@ -0,0 +1,133 @@
+import { useState } from "react";
+import { RefreshCw } from "lucide-react";
+import { Button } from "../ui/button";
+import {
+  cleanupProviderTestRecords,
+  type DnsProviderRecord,
+  type LeftoverRecordKind,
+  type TestRecordCleanup,
+} from "../../lib/dns-providers";
+import { normalizeError } from "../../lib/errors";
+
+const KIND_LABELS: Record<LeftoverRecordKind, string> = {
+  provider_test: "Connection test",
+  stale_challenge: "Stale challenge",
+};
+
+export function ProviderRecordCleanupPanel({ provider }: { provider: DnsProviderRecord }) {
+  const [preview, setPreview] = useState<TestRecordCleanup | null>(null);
+  const [result, setResult] = useState<TestRecordCleanup | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  async function run(dryRun: boolean) {
+    setLoading(true);
+    setError(null);
+    try {
+      const cleanup = await cleanupProviderTestRecords(provider.id, dryRun);
+      if (dryRun) {
+        setPreview(cleanup);
+        setResult(null);
+      } else {
+        setResult(cleanup);
+        setPreview(null);
+      }
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setLoading(false);
+    }
+  }
+
+  if (preview === null && result === null) {
+    return (
+      <div className="mt-3 space-y-2">
+        <Button
+          type="button"
+          variant="outline"
+          size="sm"
+          onClick={() => void run(true)}
+          disabled={loading}
+        >
+          {loading ? <RefreshCw className="mr-2 h-4 w-4 animate-spin" /> : null}
+          Find leftover TXT records
+        </Button>
+        {error ? <div className="text-xs text-rose-700">{error}</div> : null}
+      </div>
+    );
+  }
+
+  return (
+    <div className="mt-3 space-y-2 rounded-md border bg-muted/40 p-3 text-xs">
+      {preview ? (
+        <>
+          <div className="font-semibold">
+            Leftover test and challenge records ({preview.records.length})
+          </div>
+          {preview.records.length === 0 ? (
+            <div className="text-muted-foreground">Nothing to clean up.</div>
+          ) : (
+            <ul className="max-h-64 space-y-1 overflow-y-auto">
+              {preview.records.map((record) => (
+                <li key={record.record_name} className="rounded border bg-background p-2">
+                  <div className="font-mono">{record.record_name}</div>
+                  <div className="text-muted-foreground">
+                    {KIND_LABELS[record.kind]} · {record.values.join(", ")}
+                  </div>
+                </li>
+              ))}
+            </ul>
+          )}
+        </>
+      ) : null}
+      {result ? (
+        <>
+          <div className="font-semibold">
+            Deleted {result.deleted.length} of {result.records.length} record(s)
+          </div>
+          {result.failed.map((failure) => (
+            <div key={failure} className="text-rose-700">
+              {failure}
+            </div>
+          ))}
+        </>
+      ) : null}
+      {error ? <div className="text-rose-700">{error}</div> : null}
+      <div className="flex gap-2">
+        {preview && preview.records.length > 0 ? (
+          <Button
+            type="button"
+            size="sm"
+            variant="outline"
+            className="border-destructive/40 text-destructive hover:bg-destructive/10"
+            onClick={() => void run(false)}
+            disabled={loading}
+          >
+            {loading ? <RefreshCw className="mr-2 h-4 w-4 animate-spin" /> : null}
+            Delete {preview.records.length} record(s)
+          </Button>
+        ) : null}
+        <Button
+          type="button"
+          size="sm"
+          variant="outline"
+          onClick={() => void run(true)}
+          disabled={loading}
+        >
+          Refresh
+        </Button>
+        <Button
+          type="button"
+          variant="ghost"
+          size="sm"
+          onClick={() => {
+            setPreview(null);
+            setResult(null);
+          }}
+        >
+          Close
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,68 @@
+import { useEffect, useState } from "react";
+import { Globe } from "lucide-react";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+import { Input } from "../ui/input";
+import { Label } from "../ui/label";
+import { DNS_RESOLVERS_PREFERENCE, invalidResolverEntries } from "../../lib/dns";
+import { normalizeError } from "../../lib/errors";
+import { getPreference, setPreference } from "../../lib/preferences";
+
+export function ResolverSettings() {
+  const [resolvers, setResolvers] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getPreference(DNS_RESOLVERS_PREFERENCE)
+      .then((saved) => setResolvers(saved?.value ?? ""))
+      .catch((err) => setError(normalizeError(err)));
+  }, []);
+
+  async function save() {
+    const value = resolvers.trim();
+    const invalid = invalidResolverEntries(value);
+    if (invalid.length > 0) {
+      setError(`Not a resolver: ${invalid.join(", ")}`);
+      return;
+    }
+    setError(null);
+    try {
+      await setPreference(DNS_RESOLVERS_PREFERENCE, value);
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  return (
+    <Card className="shadow-soft">
+      <CardHeader className="flex-row items-center gap-3 space-y-0">
+        <Globe className="h-5 w-5 text-primary" />
+        <div>
+          <CardTitle className="text-sm font-semibold">Propagation resolvers</CardTitle>
+          <p className="text-sm text-muted-foreground">
+            Resolvers asked whether challenge and test records are visible. Use
+            google, cloudflare, https:// DNS-over-HTTPS URLs or the IP address of
+            a DNS server, such as an internal resolver for split-horizon zones.
+          </p>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {error ? (
+          <div className="rounded-lg border border-destructive/50 bg-destructive/10 px-4 py-3 text-sm text-destructive">
+            {error}
+          </div>
+        ) : null}
+        <div className="space-y-1">
+          <Label htmlFor="dns-resolvers">Resolvers (comma-separated)</Label>
+          <Input
+            id="dns-resolvers"
+            className="h-8"
+            placeholder="google, cloudflare"
+            value={resolvers}
+            onChange={(event) => setResolvers(event.target.value)}
+            onBlur={() => void save()}
+          />
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
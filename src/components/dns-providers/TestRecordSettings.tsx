@@ -0,0 +1,87 @@
+import { useEffect, useState } from "react";
+import { FlaskConical } from "lucide-react";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+import { Input } from "../ui/input";
+import { Label } from "../ui/label";
+import {
+  TEST_RECORD_PREFIX_PREFERENCE,
+  TEST_RECORD_TTL_PREFERENCE,
+} from "../../lib/dns-providers";
+import { normalizeError } from "../../lib/errors";
+import { getPreference, setPreference } from "../../lib/preferences";
+
+export function TestRecordSettings() {
+  const [prefix, setPrefix] = useState("");
+  const [ttl, setTtl] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    Promise.all([
+      getPreference(TEST_RECORD_PREFIX_PREFERENCE),
+      getPreference(TEST_RECORD_TTL_PREFERENCE),
+    ])
+      .then(([savedPrefix, savedTtl]) => {
+        setPrefix(savedPrefix?.value ?? "");
+        setTtl(savedTtl?.value ?? "");
+      })
+      .catch((err) => setError(normalizeError(err)));
+  }, []);
+
+  async function save(name: string, value: string) {
+    setError(null);
+    try {
+      await setPreference(name, value);
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  return (
+    <Card className="shadow-soft">
+      <CardHeader className="flex-row items-center gap-3 space-y-0">
+        <FlaskConical className="h-5 w-5 text-primary" />
+        <div>
+          <CardTitle className="text-sm font-semibold">Test records</CardTitle>
+          <p className="text-sm text-muted-foreground">
+            Name prefix and TTL of the temporary TXT record written by provider
+            tests. A provider's own test record settings take precedence.
+          </p>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {error ? (
+          <div className="rounded-lg border border-destructive/50 bg-destructive/10 px-4 py-3 text-sm text-destructive">
+            {error}
+          </div>
+        ) : null}
+        <div className="flex flex-wrap items-end gap-4">
+          <div className="space-y-1">
+            <Label htmlFor="test-record-prefix">Record prefix</Label>
+            <Input
+              id="test-record-prefix"
+              className="h-8 w-56"
+              placeholder="_sslboard-test"
+              value={prefix}
+              onChange={(event) => setPrefix(event.target.value)}
+              onBlur={() => void save(TEST_RECORD_PREFIX_PREFERENCE, prefix.trim())}
+            />
+          </div>
+          <div className="space-y-1">
+            <Label htmlFor="test-record-ttl">TTL (seconds)</Label>
+            <Input
+              id="test-record-ttl"
+              type="number"
+              min={30}
+              max={86400}
+              className="h-8 w-32"
+              placeholder="Provider default"
+              value={ttl}
+              onChange={(event) => setTtl(event.target.value)}
+              onBlur={() => void save(TEST_RECORD_TTL_PREFERENCE, ttl.trim())}
+            />
+          </div>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
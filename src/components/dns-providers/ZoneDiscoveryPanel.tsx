@@ -0,0 +1,145 @@
+import { useState } from "react";
+import { RefreshCw } from "lucide-react";
+import { Button } from "../ui/button";
+import { Checkbox } from "../ui/checkbox";
+import {
+  discoverProviderZones,
+  updateDnsProvider,
+  type DiscoveredZone,
+  type DnsProviderRecord,
+} from "../../lib/dns-providers";
+import { normalizeError } from "../../lib/errors";
+
+export function ZoneDiscoveryPanel({
+  provider,
+  onUpdated,
+}: {
+  provider: DnsProviderRecord;
+  onUpdated: (provider: DnsProviderRecord) => void;
+}) {
+  const [zones, setZones] = useState<DiscoveredZone[] | null>(null);
+  const [selected, setSelected] = useState<Set<string>>(new Set());
+  const [loading, setLoading] = useState(false);
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  async function handleDiscover() {
+    setLoading(true);
+    setError(null);
+    try {
+      const found = await discoverProviderZones(provider.id);
+      setZones(found);
+      setSelected(
+        new Set(
+          found
+            .filter((zone) => !zone.configured && !zone.claimed_by)
+            .map((zone) => zone.name),
+        ),
+      );
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setLoading(false);
+    }
+  }
+
+  async function handleAdd() {
+    if (selected.size === 0) return;
+    setSaving(true);
+    setError(null);
+    try {
+      const updated = await updateDnsProvider({
+        provider_id: provider.id,
+        label: provider.label,
+        domain_suffixes: [...provider.domain_suffixes, ...selected].join(", "),
+        config: provider.config ?? null,
+      });
+      onUpdated(updated);
+      setZones(null);
+      setSelected(new Set());
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  function toggle(name: string, checked: boolean) {
+    setSelected((prev) => {
+      const next = new Set(prev);
+      if (checked) {
+        next.add(name);
+      } else {
+        next.delete(name);
+      }
+      return next;
+    });
+  }
+
+  if (zones === null) {
+    return (
+      <div className="mt-3 space-y-2">
+        <Button
+          type="button"
+          variant="outline"
+          size="sm"
+          onClick={() => void handleDiscover()}
+          disabled={loading}
+        >
+          {loading ? <RefreshCw className="mr-2 h-4 w-4 animate-spin" /> : null}
+          Discover zones
+        </Button>
+        {error ? <div className="text-xs text-rose-700">{error}</div> : null}
+      </div>
+    );
+  }
+
+  return (
+    <div className="mt-3 space-y-2 rounded-md border bg-muted/40 p-3 text-xs">
+      <div className="font-semibold">Zones visible to these credentials</div>
+      {zones.length === 0 ? (
+        <div className="text-muted-foreground">No zones found.</div>
+      ) : (
+        <div className="space-y-1.5">
+          {zones.map((zone) => (
+            <div key={zone.name} className="flex items-center gap-2">
+              <Checkbox
+                id={`zone-${provider.id}-${zone.name}`}
+                checked={zone.configured || selected.has(zone.name)}
+                disabled={zone.configured}
+                onCheckedChange={(checked) => toggle(zone.name, checked === true)}
+              />
+              <label
+                htmlFor={`zone-${provider.id}-${zone.name}`}
+                className="font-mono"
+              >
+                {zone.name}
+              </label>
+              {zone.configured ? (
+                <span className="text-muted-foreground">already added</span>
+              ) : null}
+              {zone.claimed_by ? (
+                <span className="text-amber-700">also used by {zone.claimed_by}</span>
+              ) : null}
+            </div>
+          ))}
+        </div>
+      )}
+      {error ? <div className="text-rose-700">{error}</div> : null}
+      <div className="flex gap-2">
+        <Button
+          type="button"
+          size="sm"
+          onClick={() => void handleAdd()}
+          disabled={saving || selected.size === 0}
+        >
+          {saving ? <RefreshCw className="mr-2 h-4 w-4 animate-spin" /> : null}
+          Add {selected.size} selected
+        </Button>
+        <Button type="button" variant="ghost" size="sm" onClick={() => setZones(null)}>
+          Close
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -4,19 +4,38 @@ import type {
 } from "../../lib/dns-providers";
 
 export const PROVIDER_LABELS: Record<DnsProviderType, string> = {
+  acme_dns: "acme-dns",
   cloudflare: "Cloudflare",
   digitalocean: "DigitalOcean",
+  desec: "deSEC",
+  powerdns: "PowerDNS",
+  rfc2136: "RFC 2136 (TSIG)",
   route53: "Route 53",
+  script: "Script / webhook",
   manual: "Manual",
 };
 
 export const PROVIDER_OPTIONS: { value: DnsProviderType; label: string }[] = [
   { value: "cloudflare", label: "Cloudflare" },
   { value: "digitalocean", label: "DigitalOcean" },
+  { value: "desec", label: "deSEC" },
+  { value: "powerdns", label: "PowerDNS" },
+  { value: "rfc2136", label: "RFC 2136 (TSIG)" },
+  { value: "acme_dns", label: "acme-dns" },
   { value: "route53", label: "Route 53" },
+  { value: "script", label: "Script / webhook" },
   { value: "manual", label: "Manual" },
 ];
 
+export const TSIG_ALGORITHMS = [
+  "hmac-sha256",
+  "hmac-sha384",
+  "hmac-sha512",
+  "hmac-sha224",
+  "hmac-sha1",
+  "hmac-md5",
+] as const;
+
 export const ERROR_CATEGORY_LABELS: Record<DnsProviderErrorCategory, string> = {
   auth_error: "Authentication error",
   not_found: "Not found",
@@ -6,6 +6,7 @@ import { CertificateExportModal } from "../certificates/CertificateExportModal";
 import { DetailItem } from "../certificates/DetailItem";
 import { SubjectPill } from "../certificates/SubjectPill";
 import { formatCertificateDate, primarySubject } from "../certificates/certificate-utils";
+import { StaleEndpointsNotice } from "./StaleEndpointsNotice";
 
 interface CompletedCertificateCardProps {
   certificate: CertificateRecord;
@@ -105,6 +106,8 @@ export function CompletedCertificateCard({
         <DetailItem label="Key type" value={formatKeyInfo(certificate)} />
       </div>
 
+      <StaleEndpointsNotice certificateId={certificate.id} />
+
       {copied && (
         <div className="mt-3 text-xs font-semibold text-emerald-600">
           Copied certificate details to clipboard.
@@ -1,16 +1,20 @@
 import { Loader2 } from "lucide-react";
 import { Button } from "../ui/button";
 import { InstructionCard } from "./InstructionCard";
-import type { StartIssuanceResponse } from "../../lib/issuance";
+import { InstructionField } from "./InstructionField";
+import type { IssuanceProgress, StartIssuanceResponse } from "../../lib/issuance";
 
 interface DnsInstructionsPanelProps {
   hasManual: boolean;
   hasManaged: boolean;
   dnsModeLabel: string;
   manualRecords: StartIssuanceResponse["dns_records"];
+  cnameRecords: StartIssuanceResponse["dns_records"];
   finalizing: boolean;
   awaitingManual: boolean;
   finalizeFailed: boolean;
+  finalizeResumable: boolean;
+  progress: IssuanceProgress | null;
   hasCertificate: boolean;
   onContinue: () => void;
   onRetryFinalize: () => void;
@@ -21,9 +25,12 @@ export function DnsInstructionsPanel({
   hasManaged,
   dnsModeLabel,
   manualRecords,
+  cnameRecords,
   finalizing,
   awaitingManual,
   finalizeFailed,
+  finalizeResumable,
+  progress,
   hasCertificate,
   onContinue,
   onRetryFinalize,
@@ -40,7 +47,8 @@ export function DnsInstructionsPanel({
       : finalizing
         ? "Running"
         : "Queued";
-  const showContinue = hasManual && awaitingManual;
+  const hasCnames = cnameRecords.length > 0;
+  const showContinue = (hasManual || hasCnames) && awaitingManual;
   const showRetryFinalize = finalizeFailed;
 
   return (
@@ -60,12 +68,21 @@ export function DnsInstructionsPanel({
           <span>DNS verification</span>
           <span className="text-xs font-semibold">{dnsStatus}</span>
         </div>
-        <div className="flex items-center justify-between rounded-md border bg-background px-3 py-2">
-          <span>Finalize issuance</span>
-          <span className="text-xs font-semibold">{finalizeStatus}</span>
+        <div className="rounded-md border bg-background px-3 py-2">
+          <div className="flex items-center justify-between">
+            <span>Finalize issuance</span>
+            <span className="text-xs font-semibold">{finalizeStatus}</span>
+          </div>
+          {finalizing && progress && (
+            <div className="mt-1 text-xs">
+              {progress.message}
+              {progress.attempt > 1 ? ` · attempt ${progress.attempt}` : ""} ·{" "}
+              {progress.elapsed_secs}s
+            </div>
+          )}
         </div>
       </div>
-      {hasManaged && !hasManual && (
+      {hasManaged && !hasManual && !hasCnames && (
         <div className="rounded-md border bg-background px-3 py-2 text-xs text-muted-foreground">
           Automatic DNS provider is configured. Monitoring propagation and finalizing automatically.
         </div>
@@ -75,6 +92,22 @@ export function DnsInstructionsPanel({
           Some DNS records are handled automatically. Add the manual TXT records, then continue.
         </div>
       )}
+      {hasCnames && (
+        <div className="space-y-2">
+          <div className="text-xs text-muted-foreground">
+            Create these CNAME records once; later renewals reuse them. Continue when they
+            resolve.
+          </div>
+          <div className="grid gap-3 md:grid-cols-2">
+            {cnameRecords.map((rec) => (
+              <div key={rec.record_name} className="space-y-2 rounded-lg border bg-background p-3">
+                <InstructionField label="CNAME name" value={rec.record_name} />
+                <InstructionField label="Target" value={rec.cname_target ?? ""} />
+              </div>
+            ))}
+          </div>
+        </div>
+      )}
       {hasManual && (
         <div className="grid gap-3 md:grid-cols-2">
           {manualRecords.map((rec) => {
@@ -99,7 +132,7 @@ export function DnsInstructionsPanel({
           {showRetryFinalize && (
             <Button onClick={() => void onRetryFinalize()} disabled={finalizing}>
               {finalizing && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
-              Retry finalization
+              {finalizeResumable ? "Resume finalization" : "Retry finalization"}
             </Button>
           )}
         </div>
@@ -0,0 +1,77 @@
+import { useEffect, useState } from "react";
+import { Button } from "../ui/button";
+import { validateDomains, type DomainIssue } from "../../lib/issuance";
+
+const VALIDATE_DELAY_MS = 300;
+
+/** The name with the character at the issue's position marked. */
+function HighlightedName({ issue }: { issue: DomainIssue }) {
+  const chars = Array.from(issue.input);
+  const position = issue.position ?? -1;
+  if (position < 0 || position >= chars.length) {
+    return <span className="font-mono">{issue.input}</span>;
+  }
+  return (
+    <span className="font-mono">
+      {chars.slice(0, position).join("")}
+      <mark className="rounded-sm bg-rose-200 px-0.5 text-rose-900">
+        {chars[position] === " " ? "␣" : chars[position]}
+      </mark>
+      {chars.slice(position + 1).join("")}
+    </span>
+  );
+}
+
+interface DomainIssuesListProps {
+  domains: string[];
+  onReplace: (index: number, value: string) => void;
+}
+
+/** Explains what is wrong with pasted names and offers corrections. */
+export function DomainIssuesList({ domains, onReplace }: DomainIssuesListProps) {
+  const [issues, setIssues] = useState<DomainIssue[]>([]);
+  const key = domains.join("\n");
+
+  useEffect(() => {
+    let cancelled = false;
+    const timer = window.setTimeout(() => {
+      validateDomains(domains)
+        .then((result) => {
+          if (!cancelled) setIssues(result);
+        })
+        .catch(() => {
+          if (!cancelled) setIssues([]);
+        });
+    }, VALIDATE_DELAY_MS);
+    return () => {
+      cancelled = true;
+      window.clearTimeout(timer);
+    };
+  }, [key]);
+
+  if (issues.length === 0) return null;
+
+  return (
+    <div className="space-y-1 rounded-md border border-rose-200 bg-rose-50 px-3 py-2 text-xs text-rose-800">
+      {issues.map((issue) => (
+        <div
+          key={`${issue.index ?? ""}-${issue.input}`}
+          className="flex flex-wrap items-center gap-2"
+        >
+          <HighlightedName issue={issue} />
+          <span>{issue.message}</span>
+          {issue.suggestion && issue.index != null ? (
+            <Button
+              size="sm"
+              variant="outline"
+              className="h-6 px-2 text-xs"
+              onClick={() => onReplace(issue.index as number, issue.suggestion as string)}
+            >
+              Use {issue.suggestion}
+            </Button>
+          ) : null}
+        </div>
+      ))}
+    </div>
+  );
+}
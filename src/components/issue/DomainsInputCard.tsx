@@ -1,6 +1,9 @@
+import { useState, type ClipboardEvent } from "react";
 import { Loader2 } from "lucide-react";
 import { Button } from "../ui/button";
 import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+import { Checkbox } from "../ui/checkbox";
+import { Input } from "../ui/input";
 import { Label } from "../ui/label";
 import {
   Select,
@@ -11,12 +14,19 @@ import {
 } from "../ui/select";
 import { Textarea } from "../ui/textarea";
 import { DnsProviderPreviewCard } from "./DnsProviderPreviewCard";
+import { DomainIssuesList } from "./DomainIssuesList";
+import { PreflightSummary } from "./PreflightSummary";
 import type { DnsProviderResolution } from "../../lib/dns-providers";
-import type { IssuanceKeyOption } from "../../lib/issuance";
+import {
+  parseDomainList,
+  type IssuanceKeyOption,
+  type ParsedDomainList,
+} from "../../lib/issuance";
 
 interface DomainsInputCardProps {
   domainsInput: string;
   parsedDomains: string[];
+  issuerId: string | null;
   issuerLabel: string;
   issuerEnvironment: string;
   issuerReady: boolean;
@@ -26,8 +36,14 @@ interface DomainsInputCardProps {
   providerLoading: boolean;
   providerError: string | null;
   keyOption: IssuanceKeyOption;
+  waitTimeout: string;
+  waitInterval: string;
+  autoComplete: boolean;
   onDomainsChange: (value: string) => void;
   onKeyOptionChange: (value: IssuanceKeyOption) => void;
+  onWaitTimeoutChange: (value: string) => void;
+  onWaitIntervalChange: (value: string) => void;
+  onAutoCompleteChange: (value: boolean) => void;
   onStart: () => void;
   onReset: () => void;
 }
@@ -35,6 +51,7 @@ interface DomainsInputCardProps {
 export function DomainsInputCard({
   domainsInput,
   parsedDomains,
+  issuerId,
   issuerLabel,
   issuerEnvironment,
   issuerReady,
@@ -44,11 +61,34 @@ export function DomainsInputCard({
   providerLoading,
   providerError,
   keyOption,
+  waitTimeout,
+  waitInterval,
+  autoComplete,
   onDomainsChange,
   onKeyOptionChange,
+  onWaitTimeoutChange,
+  onWaitIntervalChange,
+  onAutoCompleteChange,
   onStart,
   onReset,
 }: DomainsInputCardProps) {
+  const [rejected, setRejected] = useState<ParsedDomainList["rejected"]>([]);
+
+  // Lists pasted from spreadsheets, URLs or configs go through the backend
+  // parser; plain typing and single names paste as usual.
+  function handlePaste(event: ClipboardEvent<HTMLTextAreaElement>) {
+    const text = event.clipboardData.getData("text");
+    if (!/[\n\t;]|:\/\//.test(text)) return;
+    event.preventDefault();
+    void parseDomainList(text)
+      .then((parsed) => {
+        const merged = Array.from(new Set([...parsedDomains, ...parsed.domains]));
+        onDomainsChange(merged.join(", "));
+        setRejected(parsed.rejected);
+      })
+      .catch(() => onDomainsChange(`${domainsInput}${text}`));
+  }
+
   return (
     <Card className="shadow-soft">
       <CardHeader className="flex-row items-start justify-between gap-4 space-y-0">
@@ -77,12 +117,42 @@ export function DomainsInputCard({
             id="domains-input"
             value={domainsInput}
             onChange={(e) => onDomainsChange(e.target.value.normalize("NFC"))}
+            onPaste={handlePaste}
             rows={3}
             placeholder="test.ezs3.net, test1.ezs3.net"
           />
           <p className="text-xs text-muted-foreground">
             Comma or newline separated. Issuer: {issuerLabel} ({issuerEnvironment}).
           </p>
+          {rejected.length > 0 ? (
+            <div className="space-y-1 rounded-md border bg-muted px-3 py-2 text-xs text-muted-foreground">
+              <div className="flex items-center justify-between gap-2">
+                <span className="font-semibold">Skipped from the pasted text</span>
+                <button
+                  type="button"
+                  className="underline"
+                  onClick={() => setRejected([])}
+                >
+                  Dismiss
+                </button>
+              </div>
+              {rejected.map((entry, index) => (
+                <div key={`${entry.entry}-${index}`}>
+                  <span className="font-mono">{entry.entry}</span>: {entry.reason}
+                </div>
+              ))}
+            </div>
+          ) : null}
+          {parsedDomains.length > 0 ? (
+            <DomainIssuesList
+              domains={parsedDomains}
+              onReplace={(index, value) =>
+                onDomainsChange(
+                  parsedDomains.map((domain, i) => (i === index ? value : domain)).join(", "),
+                )
+              }
+            />
+          ) : null}
         </div>
 
         {parsedDomains.length > 0 ? (
@@ -94,6 +164,10 @@ export function DomainsInputCard({
           />
         ) : null}
 
+        {parsedDomains.length > 0 && !hasStartResult ? (
+          <PreflightSummary domains={parsedDomains} issuerId={issuerId} />
+        ) : null}
+
         <div className="space-y-2 text-sm">
           <Label className="text-muted-foreground">Key algorithm</Label>
           <Select
@@ -119,6 +193,55 @@ export function DomainsInputCard({
           </p>
         </div>
 
+        <div className="space-y-2 text-sm">
+          <Label className="text-muted-foreground">Propagation wait</Label>
+          <div className="grid gap-2 sm:grid-cols-2">
+            <Input
+              aria-label="Wait up to (seconds)"
+              type="number"
+              min={10}
+              max={1800}
+              placeholder="Wait up to (s)"
+              value={waitTimeout}
+              onChange={(event) => onWaitTimeoutChange(event.target.value)}
+              disabled={loadingStart}
+            />
+            <Input
+              aria-label="Check every (seconds)"
+              type="number"
+              min={1}
+              max={60}
+              placeholder="Check every (s)"
+              value={waitInterval}
+              onChange={(event) => onWaitIntervalChange(event.target.value)}
+              disabled={loadingStart}
+            />
+          </div>
+          <p className="text-xs text-muted-foreground">
+            Leave empty to use each provider&apos;s wait. Useful when a slow
+            provider needs longer for this run only.
+          </p>
+        </div>
+
+        <div className="flex items-start gap-2">
+          <Checkbox
+            id="auto-complete"
+            className="mt-0.5"
+            checked={autoComplete}
+            onCheckedChange={(checked) => onAutoCompleteChange(checked === true)}
+            disabled={loadingStart || hasStartResult}
+          />
+          <div className="space-y-1">
+            <Label htmlFor="auto-complete" className="text-sm">
+              Complete automatically
+            </Label>
+            <p className="text-xs text-muted-foreground">
+              Finish the issuance once records you add by hand are visible in
+              DNS, and notify you of the result.
+            </p>
+          </div>
+        </div>
+
         <div className="flex flex-wrap gap-3">
           <Button
             onClick={() => void onStart()}
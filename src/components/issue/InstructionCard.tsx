@@ -1,4 +1,15 @@
+import { useEffect, useState } from "react";
+import {
+  cancelDnsPropagationWatch,
+  listenDnsPropagation,
+  propagationReason,
+  resolverConsensus,
+  watchDnsPropagation,
+  type DnsPropagationUpdate,
+  type PropagationCheckMode,
+} from "../../lib/dns";
 import type { StartIssuanceResponse } from "../../lib/issuance";
+import { cn } from "../../lib/utils";
 import { InstructionField } from "./InstructionField";
 
 function formatRecordName(recordName: string) {
@@ -7,19 +18,153 @@ function formatRecordName(recordName: string) {
     : recordName;
 }
 
+function statusLabel(update: DnsPropagationUpdate | null, mode: PropagationCheckMode) {
+  if (!update) return "Checking DNS…";
+  if (update.result.state === "found") {
+    if (mode === "serial_sync") return "Every nameserver has loaded the zone with the record";
+    return mode === "authoritative"
+      ? "Served by every authoritative nameserver"
+      : "Visible in public DNS";
+  }
+  if (update.outcome === "timed_out") return "Not visible yet; stopped checking";
+  return propagationReason(update.result) ?? "Waiting for the record to appear";
+}
+
+/** Follows the record in DNS while the card is shown. */
+function usePropagationWatch(recordName: string, value: string, mode: PropagationCheckMode) {
+  const [update, setUpdate] = useState<DnsPropagationUpdate | null>(null);
+
+  useEffect(() => {
+    let watchId: string | null = null;
+    let stopped = false;
+    // Updates can arrive before the command returns the watch id
+    const early = new Map<string, DnsPropagationUpdate>();
+    const unlisten = listenDnsPropagation((next) => {
+      if (watchId === null) early.set(next.watch_id, next);
+      else if (next.watch_id === watchId) setUpdate(next);
+    });
+    void unlisten
+      .then(() => watchDnsPropagation(recordName, value, undefined, mode))
+      .then((id) => {
+        watchId = id;
+        if (stopped) {
+          void cancelDnsPropagationWatch(id);
+          return;
+        }
+        const first = early.get(id);
+        if (first) setUpdate(first);
+        early.clear();
+      })
+      .catch(() => undefined);
+    return () => {
+      stopped = true;
+      if (watchId) void cancelDnsPropagationWatch(watchId);
+      void unlisten.then((stop) => stop());
+    };
+  }, [recordName, value, mode]);
+
+  return update;
+}
+
 export function InstructionCard({
   record,
 }: {
   record: StartIssuanceResponse["dns_records"][number];
 }) {
+  const [mode, setMode] = useState<PropagationCheckMode>("public");
+  const propagation = usePropagationWatch(record.record_name, record.value, mode);
+  const found = propagation?.result.state === "found";
+  const forms = record.record_name_forms;
+  const unicodeName = forms && forms.unicode !== forms.ascii ? forms.unicode : null;
+
   return (
     <div className="space-y-2 rounded-lg border bg-background p-3">
       <div className="flex items-center justify-between gap-2 text-xs font-semibold uppercase text-muted-foreground">
-        {formatRecordName(record.record_name)}
+        {formatRecordName(unicodeName ?? record.record_name)}
       </div>
       <InstructionField label="Record name" value={record.record_name} />
+      {unicodeName ? (
+        <div className="text-xs text-muted-foreground">
+          Punycode form of {unicodeName}; DNS providers expect this form.
+        </div>
+      ) : null}
       <InstructionField label="Value" value={record.value} />
       <InstructionField label="Zone" value={record.zone} />
+      {record.delegated_from ? (
+        <div className="text-xs text-muted-foreground">
+          {record.delegated_from} is a CNAME to this name, so the TXT record goes here.
+        </div>
+      ) : null}
+      <div className="flex items-center justify-between gap-2 text-xs">
+        <span className={found ? "text-emerald-700" : "text-muted-foreground"}>
+          {statusLabel(propagation, mode)}
+        </span>
+        <button
+          type="button"
+          className="text-primary underline-offset-2 hover:underline"
+          onClick={() => setMode(mode === "public" ? "authoritative" : "public")}
+        >
+          {mode === "public" ? "Ask authoritative nameservers" : "Check public DNS"}
+        </button>
+      </div>
+      {mode !== "public" ? (
+        <label className="flex items-center gap-2 text-xs text-muted-foreground">
+          <input
+            type="checkbox"
+            checked={mode === "serial_sync"}
+            onChange={(event) => setMode(event.target.checked ? "serial_sync" : "authoritative")}
+          />
+          Wait until every nameserver has the same zone serial
+        </label>
+      ) : null}
+      {propagation?.result.resolvers?.length ? (
+        <details className="text-xs">
+          <summary className="cursor-pointer text-muted-foreground">
+            {resolverConsensus(propagation.result)}
+          </summary>
+          <ul className="mt-1 space-y-1">
+            {propagation.result.resolvers.map((outcome) => (
+              <li key={outcome.resolver} className="flex justify-between gap-2">
+                <span className="font-mono">
+                  {outcome.resolver} ({outcome.rtt_ms} ms)
+                </span>
+                <span
+                  className={cn(
+                    outcome.state === "found" ? "text-emerald-700" : "text-muted-foreground",
+                  )}
+                  title={outcome.observed_values.join(", ") || undefined}
+                >
+                  {outcome.state === "found"
+                    ? "Sees the value"
+                    : (outcome.error ?? outcome.state.replace("_", " "))}
+                </span>
+              </li>
+            ))}
+          </ul>
+        </details>
+      ) : null}
+      {propagation?.nameservers ? (
+        <ul className="space-y-1 text-xs">
+          {propagation.nameservers.map((check) => (
+            <li key={check.nameserver} className="flex justify-between gap-2">
+              <span className="font-mono" title={check.address ?? undefined}>
+                {check.nameserver}
+                {check.transport ? ` (${check.transport.toUpperCase()})` : null}
+                {check.soa_serial !== undefined ? ` serial ${check.soa_serial}` : null}
+              </span>
+              <span
+                className={cn(
+                  check.result.state === "found" ? "text-emerald-700" : "text-muted-foreground",
+                )}
+              >
+                {check.result.state === "found"
+                  ? "Serving the value"
+                  : (propagationReason(check.result) ?? "Not serving the value yet")}
+              </span>
+            </li>
+          ))}
+        </ul>
+      ) : null}
     </div>
   );
 }
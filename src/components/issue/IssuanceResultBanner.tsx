@@ -1,16 +1,48 @@
+import { save } from "@tauri-apps/plugin-dialog";
 import { AlertTriangle, CheckCircle2 } from "lucide-react";
+import { toast } from "sonner";
+import { normalizeError } from "../../lib/errors";
+import { exportIssuanceArtifacts, listIssuanceArtifacts } from "../../lib/issuance";
 
 interface IssuanceResultBannerProps {
   error: string | null;
   successMessage: string | null;
 }
 
+/** Saves the most recent failed attempt, which is the one the error came from. */
+async function exportLatestAttempt() {
+  try {
+    const [latest] = await listIssuanceArtifacts();
+    if (!latest) {
+      toast.error("No failed issuance was recorded");
+      return;
+    }
+    const path = await save({
+      defaultPath: `sslboard-issuance-${latest.domains[0] ?? latest.request_id}.json`,
+      filters: [{ name: "JSON", extensions: ["json"] }],
+    });
+    if (!path) return;
+    await exportIssuanceArtifacts(latest.request_id, path);
+    toast.success(`Saved issuance details to ${path}`);
+  } catch (err) {
+    toast.error(`Export failed: ${normalizeError(err)}`);
+  }
+}
+
 export function IssuanceResultBanner({ error, successMessage }: IssuanceResultBannerProps) {
   if (error) {
     return (
       <div className="flex items-center gap-2 rounded-md bg-destructive/10 px-3 py-2 text-sm text-destructive">
         <AlertTriangle className="h-4 w-4" />
-        {error}
+        <span className="flex-1">{error}</span>
+        <button
+          type="button"
+          className="shrink-0 text-xs underline-offset-2 hover:underline"
+          title="Order, challenges and errors with credentials redacted, for sharing when asking for help"
+          onClick={() => void exportLatestAttempt()}
+        >
+          Export details
+        </button>
       </div>
     );
   }
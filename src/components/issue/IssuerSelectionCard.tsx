@@ -1,4 +1,8 @@
-import type { IssuerConfig } from "../../lib/issuers";
+import type {
+  IssuerConfig,
+  IssuerSelectionReason,
+  IssuerSuggestion,
+} from "../../lib/issuers";
 import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
 import { Label } from "../ui/label";
 import {
@@ -9,9 +13,19 @@ import {
   SelectValue,
 } from "../ui/select";
 
+const REASON_LABELS: Record<IssuerSelectionReason, string> = {
+  test_domain: "Suggested: test domains can only be issued by a staging CA.",
+  new_domain: "Suggested: staging first, since some names have not been issued before.",
+  previously_issued: "Suggested: every name has issued successfully before.",
+  policy: "Suggested by the issuer policy in Settings.",
+  selected_issuer: "Suggested: the issuer marked as selected in Settings.",
+  fallback: "No enabled issuer matches the policy's environment; using the closest one.",
+};
+
 interface IssuerSelectionCardProps {
   issuers: IssuerConfig[];
   selectedIssuer: IssuerConfig | null;
+  suggestion: IssuerSuggestion | null;
   issuerLoading: boolean;
   issuerError: string | null;
   issuerReady: boolean;
@@ -21,6 +35,7 @@ interface IssuerSelectionCardProps {
 export function IssuerSelectionCard({
   issuers,
   selectedIssuer,
+  suggestion,
   issuerLoading,
   issuerError,
   issuerReady,
@@ -62,6 +77,11 @@ export function IssuerSelectionCard({
         <p className="text-xs text-muted-foreground">
           {selectedIssuer?.directory_url ?? "https://acme-staging-v02.api.letsencrypt.org/directory"}
         </p>
+        {suggestion && suggestion.issuer_id === selectedIssuer?.issuer_id ? (
+          <p className="text-xs text-muted-foreground">
+            {REASON_LABELS[suggestion.reason]}
+          </p>
+        ) : null}
         {!issuerReady ? (
           <p className="text-xs text-muted-foreground">
             Configure the issuer&apos;s ACME account in Settings before issuing.
@@ -0,0 +1,122 @@
+import { useEffect, useState } from "react";
+import { AlertTriangle, CheckCircle2, Loader2, XCircle } from "lucide-react";
+import { Button } from "../ui/button";
+import { normalizeError } from "../../lib/errors";
+import {
+  preflightDomains,
+  type PreflightCheck,
+  type PreflightCheckKind,
+  type PreflightReport,
+  type PreflightStatus,
+} from "../../lib/issuance";
+
+interface PreflightSummaryProps {
+  domains: string[];
+  issuerId: string | null;
+}
+
+const CHECK_LABELS: Record<PreflightCheckKind, string> = {
+  provider: "Provider",
+  caa: "CAA",
+  delegation: "NS",
+  record_plan: "Records",
+  inventory: "Inventory",
+  rate_limit: "Rate limit",
+};
+
+function StatusIcon({ status }: { status: PreflightStatus }) {
+  if (status === "fail") return <XCircle className="h-3.5 w-3.5 shrink-0 text-destructive" />;
+  if (status === "warn") return <AlertTriangle className="h-3.5 w-3.5 shrink-0 text-amber-600" />;
+  return <CheckCircle2 className="h-3.5 w-3.5 shrink-0 text-emerald-600" />;
+}
+
+function CheckRow({ check }: { check: PreflightCheck }) {
+  return (
+    <div className="flex items-start gap-2 text-xs">
+      <StatusIcon status={check.status} />
+      <span className="w-16 shrink-0 font-medium">{CHECK_LABELS[check.kind]}</span>
+      <span className="text-muted-foreground">{check.detail}</span>
+    </div>
+  );
+}
+
+/** Runs provider, CAA, delegation, inventory and rate-limit checks on request. */
+export function PreflightSummary({ domains, issuerId }: PreflightSummaryProps) {
+  const [report, setReport] = useState<PreflightReport | null>(null);
+  const [running, setRunning] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const domainsKey = domains.join(",");
+
+  useEffect(() => {
+    setReport(null);
+    setError(null);
+  }, [domainsKey, issuerId]);
+
+  async function run() {
+    setRunning(true);
+    setError(null);
+    try {
+      setReport(await preflightDomains(domains, issuerId));
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setRunning(false);
+    }
+  }
+
+  return (
+    <div className="rounded-lg border bg-background/70 p-3 shadow-sm">
+      <div className="flex items-center justify-between gap-2">
+        <div className="text-xs font-semibold uppercase tracking-wide text-muted-foreground">
+          Preflight
+        </div>
+        <Button
+          variant="outline"
+          size="sm"
+          disabled={running || !domains.length}
+          onClick={() => void run()}
+        >
+          {running && <Loader2 className="mr-2 h-3.5 w-3.5 animate-spin" />}
+          {report ? "Check again" : "Run checks"}
+        </Button>
+      </div>
+      {error ? (
+        <div className="mt-2 flex items-center gap-2 rounded-md bg-destructive/10 px-2 py-1 text-xs text-destructive">
+          <AlertTriangle className="h-3.5 w-3.5" />
+          {error}
+        </div>
+      ) : null}
+      {report ? (
+        <div className="mt-2 space-y-3">
+          <div
+            className={
+              report.go
+                ? "text-sm font-medium text-emerald-700"
+                : "text-sm font-medium text-destructive"
+            }
+          >
+            {report.go
+              ? "Go: nothing is expected to block issuance"
+              : "No-go: fix the failed checks before issuing"}
+          </div>
+          {report.domains.map((domain) => (
+            <div key={domain.domain} className="space-y-1">
+              <div className="flex items-center gap-2 text-sm font-medium">
+                <StatusIcon status={domain.status} />
+                {domain.domain}
+              </div>
+              <div className="space-y-1 pl-5">
+                {domain.checks.map((check) => (
+                  <CheckRow key={check.kind} check={check} />
+                ))}
+              </div>
+            </div>
+          ))}
+          {report.order_checks.map((check) => (
+            <CheckRow key={check.kind} check={check} />
+          ))}
+        </div>
+      ) : null}
+    </div>
+  );
+}
@@ -0,0 +1,32 @@
+import { AlertTriangle } from "lucide-react";
+import { useEffect, useState } from "react";
+import { checkCertificateUsages, type EndpointCheck } from "../../lib/usages";
+
+interface StaleEndpointsNoticeProps {
+  certificateId: string;
+}
+
+/** Warns when endpoints linked to the renewed names still serve an older certificate. */
+export function StaleEndpointsNotice({ certificateId }: StaleEndpointsNoticeProps) {
+  const [stale, setStale] = useState<EndpointCheck[]>([]);
+
+  useEffect(() => {
+    checkCertificateUsages(certificateId)
+      .then((checks) => setStale(checks.filter((check) => check.state === "outdated")))
+      .catch(() => setStale([]));
+  }, [certificateId]);
+
+  if (!stale.length) return null;
+
+  return (
+    <div className="mt-4 flex gap-2 rounded-lg border border-amber-200/60 bg-amber-50/70 p-3 text-sm text-amber-900">
+      <AlertTriangle className="mt-0.5 h-4 w-4 shrink-0" />
+      <div>
+        <div className="font-medium">Endpoints still serve the previous certificate</div>
+        <div className="text-xs">
+          Deploy the new certificate to {stale.map((check) => check.endpoint).join(", ")}.
+        </div>
+      </div>
+    </div>
+  );
+}
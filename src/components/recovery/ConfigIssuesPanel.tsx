@@ -0,0 +1,55 @@
+import { Wrench } from "lucide-react";
+import { useConfigIssues } from "../../hooks/useConfigIssues";
+import type { ConfigRepair } from "../../lib/recovery";
+import { Button } from "../ui/button";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+
+const REPAIR_LABELS: Record<ConfigRepair, string> = {
+  create_account_key: "Create account key",
+  detach_managed_key: "Forget key",
+};
+
+/** Lists configuration that refers to deleted secrets, with safe repairs. */
+export function ConfigIssuesPanel() {
+  const { issues, pendingId, error, repair } = useConfigIssues();
+
+  if (!issues.length && !error) return null;
+
+  return (
+    <Card className="border-amber-200 shadow-soft dark:border-amber-900">
+      <CardHeader className="space-y-1">
+        <CardTitle className="flex items-center gap-2 text-sm font-semibold">
+          <Wrench className="h-4 w-4 text-amber-600" />
+          Configuration issues
+        </CardTitle>
+        <p className="text-xs text-muted-foreground">
+          These settings refer to credentials or keys that no longer exist.
+        </p>
+      </CardHeader>
+      <CardContent className="space-y-3">
+        {issues.map((issue) => (
+          <div
+            key={issue.id}
+            className="flex flex-wrap items-center justify-between gap-2 rounded-lg border p-3"
+          >
+            <div>
+              <p className="text-sm font-medium">{issue.subject_label}</p>
+              <p className="text-xs text-muted-foreground">{issue.detail}</p>
+            </div>
+            {issue.repair ? (
+              <Button
+                size="sm"
+                variant="outline"
+                disabled={pendingId !== null}
+                onClick={() => repair(issue.id)}
+              >
+                {REPAIR_LABELS[issue.repair]}
+              </Button>
+            ) : null}
+          </div>
+        ))}
+        {error ? <p className="text-xs text-red-600">{error}</p> : null}
+      </CardContent>
+    </Card>
+  );
+}
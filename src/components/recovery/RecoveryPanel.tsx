@@ -0,0 +1,61 @@
+import { History } from "lucide-react";
+import { useRecoveryActions } from "../../hooks/useRecoveryActions";
+import type { RecoveryAction } from "../../lib/recovery";
+import { Button } from "../ui/button";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+
+const ACTION_LABELS: Record<RecoveryAction, string> = {
+  cleanup: "Clean up",
+  resume: "Resume",
+  discard: "Discard",
+};
+
+/** Offers recovery for operations interrupted by the last shutdown. */
+export function RecoveryPanel() {
+  const { jobs, pendingId, error, apply } = useRecoveryActions();
+
+  if (!jobs.length && !error) return null;
+
+  return (
+    <Card className="border-amber-200 shadow-soft dark:border-amber-900">
+      <CardHeader className="space-y-1">
+        <CardTitle className="flex items-center gap-2 text-sm font-semibold">
+          <History className="h-4 w-4 text-amber-600" />
+          Interrupted operations
+        </CardTitle>
+        <p className="text-xs text-muted-foreground">
+          These were still running when SSLBoard last closed.
+        </p>
+      </CardHeader>
+      <CardContent className="space-y-3">
+        {jobs.map((job) => (
+          <div
+            key={job.id}
+            className="flex flex-wrap items-center justify-between gap-2 rounded-lg border p-3"
+          >
+            <div>
+              <p className="text-sm font-medium">{job.summary}</p>
+              <p className="text-xs text-muted-foreground">
+                Started {new Date(job.started_at).toLocaleString()}
+              </p>
+            </div>
+            <div className="flex gap-2">
+              {job.actions.map((action) => (
+                <Button
+                  key={action}
+                  size="sm"
+                  variant={action === "discard" ? "ghost" : "outline"}
+                  disabled={pendingId !== null}
+                  onClick={() => apply(job.id, action)}
+                >
+                  {ACTION_LABELS[action]}
+                </Button>
+              ))}
+            </div>
+          </div>
+        ))}
+        {error ? <p className="text-xs text-red-600">{error}</p> : null}
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,174 @@
+import { useEffect, useState } from "react";
+import { Moon } from "lucide-react";
+import { Button } from "../ui/button";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+import { Checkbox } from "../ui/checkbox";
+import { Input } from "../ui/input";
+import { Label } from "../ui/label";
+import { normalizeError } from "../../lib/errors";
+import { getPreference, setPreference } from "../../lib/preferences";
+import {
+  PAUSE_ON_BATTERY_PREFERENCE,
+  PAUSE_ON_METERED_PREFERENCE,
+  QUIET_HOURS_PREFERENCE,
+  getBackgroundStatus,
+  runBackgroundTasksNow,
+  type BackgroundDeferral,
+  type BackgroundStatus,
+} from "../../lib/scheduler";
+
+const DEFERRAL_LABELS: Record<BackgroundDeferral, string> = {
+  quiet_hours: "Paused for quiet hours",
+  on_battery: "Paused while on battery",
+  metered_connection: "Paused on a metered connection",
+};
+
+export function BackgroundActivitySettings() {
+  const [quietStart, setQuietStart] = useState("");
+  const [quietEnd, setQuietEnd] = useState("");
+  const [pauseOnBattery, setPauseOnBattery] = useState(false);
+  const [pauseOnMetered, setPauseOnMetered] = useState(false);
+  const [status, setStatus] = useState<BackgroundStatus | null>(null);
+  const [triggered, setTriggered] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    void load();
+  }, []);
+
+  async function load() {
+    try {
+      const [quietHours, battery, metered, current] = await Promise.all([
+        getPreference(QUIET_HOURS_PREFERENCE),
+        getPreference(PAUSE_ON_BATTERY_PREFERENCE),
+        getPreference(PAUSE_ON_METERED_PREFERENCE),
+        getBackgroundStatus(),
+      ]);
+      const [start = "", end = ""] = (quietHours?.value ?? "").split("-");
+      setQuietStart(start.trim());
+      setQuietEnd(end.trim());
+      setPauseOnBattery(battery?.value === "true");
+      setPauseOnMetered(metered?.value === "true");
+      setStatus(current);
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  async function save(name: string, value: string) {
+    setError(null);
+    try {
+      await setPreference(name, value);
+      setStatus(await getBackgroundStatus());
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  async function updateQuietHours(start: string, end: string) {
+    setQuietStart(start);
+    setQuietEnd(end);
+    if ((start && end) || (!start && !end)) {
+      await save(QUIET_HOURS_PREFERENCE, start && end ? `${start}-${end}` : "");
+    }
+  }
+
+  async function runNow() {
+    setError(null);
+    try {
+      await runBackgroundTasksNow();
+      setTriggered(true);
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  return (
+    <Card className="shadow-soft">
+      <CardHeader className="flex-row items-center gap-3 space-y-0">
+        <Moon className="h-5 w-5 text-primary" />
+        <div>
+          <CardTitle className="text-sm font-semibold">Background activity</CardTitle>
+          <p className="text-sm text-muted-foreground">
+            Update checks, scheduled reports, backups, and the status feed run
+            in the background. Hold them back at times that suit this device.
+          </p>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {error ? (
+          <div className="rounded-lg border border-destructive/50 bg-destructive/10 px-4 py-3 text-sm text-destructive">
+            {error}
+          </div>
+        ) : null}
+
+        <div className="space-y-2">
+          <Label className="text-sm font-medium">Quiet hours</Label>
+          <div className="flex items-center gap-2">
+            <Input
+              type="time"
+              className="w-32"
+              aria-label="Quiet hours start"
+              value={quietStart}
+              onChange={(event) => void updateQuietHours(event.target.value, quietEnd)}
+            />
+            <span className="text-sm text-muted-foreground">to</span>
+            <Input
+              type="time"
+              className="w-32"
+              aria-label="Quiet hours end"
+              value={quietEnd}
+              onChange={(event) => void updateQuietHours(quietStart, event.target.value)}
+            />
+            {quietStart || quietEnd ? (
+              <Button variant="ghost" size="sm" onClick={() => void updateQuietHours("", "")}>
+                Clear
+              </Button>
+            ) : null}
+          </div>
+        </div>
+
+        <div className="flex items-center gap-2">
+          <Checkbox
+            id="scheduler-pause-on-battery"
+            checked={pauseOnBattery}
+            onCheckedChange={(checked) => {
+              setPauseOnBattery(checked === true);
+              void save(PAUSE_ON_BATTERY_PREFERENCE, String(checked === true));
+            }}
+          />
+          <Label htmlFor="scheduler-pause-on-battery" className="text-sm font-medium">
+            Pause while on battery
+          </Label>
+        </div>
+        <div className="flex items-center gap-2">
+          <Checkbox
+            id="scheduler-pause-on-metered"
+            checked={pauseOnMetered}
+            onCheckedChange={(checked) => {
+              setPauseOnMetered(checked === true);
+              void save(PAUSE_ON_METERED_PREFERENCE, String(checked === true));
+            }}
+          />
+          <Label htmlFor="scheduler-pause-on-metered" className="text-sm font-medium">
+            Pause on metered connections
+            {status && status.metered === null ? " (not detected on this system)" : ""}
+          </Label>
+        </div>
+
+        <div className="flex flex-wrap items-center justify-between gap-3">
+          <div className="text-sm text-muted-foreground">
+            {triggered
+              ? "Background tasks are running now"
+              : status?.deferred_by
+                ? DEFERRAL_LABELS[status.deferred_by]
+                : "Background tasks run on schedule"}
+          </div>
+          <Button size="sm" onClick={() => void runNow()}>
+            Run now
+          </Button>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,190 @@
+import { DatabaseBackup, FolderOpen, ShieldCheck } from "lucide-react";
+import { Button } from "../ui/button";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+import { Input } from "../ui/input";
+import { Label } from "../ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "../ui/select";
+import type { BackupSchedule } from "../../lib/backups";
+import { useBackupSettings } from "../../hooks/useBackupSettings";
+
+function formatDate(iso: string) {
+  const date = new Date(iso);
+  return Number.isNaN(date.getTime())
+    ? "—"
+    : date.toLocaleString(undefined, {
+      month: "short",
+      day: "numeric",
+      year: "numeric",
+      hour: "2-digit",
+      minute: "2-digit",
+    });
+}
+
+export function BackupSettings() {
+  const {
+    schedule,
+    folder,
+    retain,
+    lastRun,
+    busy,
+    lastResult,
+    verification,
+    error,
+    updateSchedule,
+    updateRetain,
+    selectFolder,
+    backupNow,
+    verify,
+  } = useBackupSettings();
+
+  return (
+    <Card className="shadow-soft">
+      <CardHeader className="flex-row items-center gap-3 space-y-0">
+        <DatabaseBackup className="h-5 w-5 text-primary" />
+        <div>
+          <CardTitle className="text-sm font-semibold">Backups</CardTitle>
+          <p className="text-sm text-muted-foreground">
+            Copies of the inventory, settings, and encrypted secrets. The vault
+            master key stays in the system keychain, so verify backups to make
+            sure they still open on this device.
+          </p>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {error ? (
+          <div className="rounded-lg border border-destructive/50 bg-destructive/10 px-4 py-3 text-sm text-destructive">
+            {error}
+          </div>
+        ) : null}
+
+        <div className="grid gap-4 md:grid-cols-2">
+          <div className="space-y-1">
+            <Label>Automatic backups</Label>
+            <Select
+              value={schedule}
+              onValueChange={(value) =>
+                void updateSchedule(value as BackupSchedule)
+              }
+            >
+              <SelectTrigger>
+                <SelectValue placeholder="Select schedule" />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="off">Off</SelectItem>
+                <SelectItem value="daily">Daily</SelectItem>
+                <SelectItem value="weekly">Weekly</SelectItem>
+              </SelectContent>
+            </Select>
+          </div>
+          <div className="space-y-1">
+            <Label htmlFor="backup-retain">Automatic backups to keep</Label>
+            <Input
+              id="backup-retain"
+              type="number"
+              min={1}
+              value={retain}
+              onChange={(event) =>
+                void updateRetain(Number.parseInt(event.target.value, 10))
+              }
+            />
+          </div>
+        </div>
+
+        <div className="flex flex-wrap items-center justify-between gap-3">
+          <div className="text-sm text-muted-foreground">
+            {folder ?? "No backup folder selected"}
+          </div>
+          <Button
+            variant="outline"
+            size="sm"
+            className="gap-2"
+            onClick={() => void selectFolder()}
+          >
+            <FolderOpen className="h-4 w-4" />
+            Choose folder
+          </Button>
+        </div>
+
+        <div className="flex flex-wrap items-center gap-2">
+          <Button
+            size="sm"
+            disabled={busy || !folder}
+            onClick={() => void backupNow()}
+          >
+            Back up now
+          </Button>
+          <Button
+            size="sm"
+            variant="secondary"
+            className="gap-2"
+            disabled={busy}
+            onClick={() => void verify()}
+          >
+            <ShieldCheck className="h-4 w-4" />
+            Verify a backup
+          </Button>
+          {lastResult ? (
+            <Button
+              size="sm"
+              variant="ghost"
+              disabled={busy}
+              onClick={() => void verify(lastResult.path)}
+            >
+              Verify latest
+            </Button>
+          ) : null}
+          {lastRun ? (
+            <span className="text-xs text-muted-foreground">
+              Last backup {formatDate(lastRun)}
+            </span>
+          ) : null}
+        </div>
+
+        {lastResult ? (
+          <div className="rounded-lg border bg-background/80 p-3 text-sm">
+            <div className="font-medium">{lastResult.path}</div>
+          </div>
+        ) : null}
+
+        {verification ? (
+          <div
+            className={`rounded-lg border p-3 text-sm ${
+              verification.valid
+                ? "border-emerald-500/40 bg-emerald-500/10"
+                : "border-destructive/50 bg-destructive/10"
+            }`}
+          >
+            <div className="font-medium">
+              {verification.valid ? "Backup is usable" : "Backup has problems"}
+            </div>
+            <div className="break-all text-xs text-muted-foreground">
+              {verification.path}
+            </div>
+            <div className="mt-1 text-muted-foreground">
+              Schema {verification.schema_version} ·{" "}
+              {verification.certificates} certificates ·{" "}
+              {verification.secrets_decryptable}/{verification.secrets_in_backup}{" "}
+              secrets decrypt
+              {verification.secrets_external
+                ? ` · ${verification.secrets_external} kept outside the backup`
+                : ""}
+            </div>
+            {verification.problems.length ? (
+              <ul className="mt-2 list-disc space-y-1 pl-5 text-destructive">
+                {verification.problems.map((problem) => (
+                  <li key={problem}>{problem}</li>
+                ))}
+              </ul>
+            ) : null}
+          </div>
+        ) : null}
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,107 @@
+import { useEffect, useState } from "react";
+import { ShieldAlert } from "lucide-react";
+import { Button } from "../ui/button";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+import { Input } from "../ui/input";
+import { Label } from "../ui/label";
+import { normalizeError } from "../../lib/errors";
+import {
+  getConfirmationPinStatus,
+  setConfirmationPin,
+} from "../../lib/preferences";
+
+export function ConfirmationPinSettings() {
+  const [enabled, setEnabled] = useState(false);
+  const [currentPin, setCurrentPin] = useState("");
+  const [newPin, setNewPin] = useState("");
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getConfirmationPinStatus()
+      .then(setEnabled)
+      .catch((err) => setError(normalizeError(err)));
+  }, []);
+
+  async function save(pin: string | null) {
+    setSaving(true);
+    setError(null);
+    try {
+      await setConfirmationPin(enabled ? currentPin : null, pin);
+      setEnabled(pin !== null);
+      setCurrentPin("");
+      setNewPin("");
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  return (
+    <Card className="shadow-soft">
+      <CardHeader className="flex-row items-center gap-3 space-y-0">
+        <ShieldAlert className="h-5 w-5 text-primary" />
+        <div>
+          <CardTitle className="text-sm font-semibold">Confirmation PIN</CardTitle>
+          <p className="text-sm text-muted-foreground">
+            When set, deleting an issuer and its account key asks for this PIN.
+            It is checked in Rust, not in the interface.
+          </p>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-3">
+        {error ? (
+          <div className="rounded-lg border border-destructive/50 bg-destructive/10 px-4 py-3 text-sm text-destructive">
+            {error}
+          </div>
+        ) : null}
+        <div className="grid max-w-md gap-3 sm:grid-cols-2">
+          {enabled ? (
+            <div className="space-y-1">
+              <Label htmlFor="current-confirmation-pin">Current PIN</Label>
+              <Input
+                id="current-confirmation-pin"
+                type="password"
+                autoComplete="off"
+                value={currentPin}
+                onChange={(event) => setCurrentPin(event.target.value)}
+              />
+            </div>
+          ) : null}
+          <div className="space-y-1">
+            <Label htmlFor="new-confirmation-pin">
+              {enabled ? "New PIN" : "PIN"}
+            </Label>
+            <Input
+              id="new-confirmation-pin"
+              type="password"
+              autoComplete="off"
+              value={newPin}
+              onChange={(event) => setNewPin(event.target.value)}
+            />
+          </div>
+        </div>
+        <div className="flex flex-wrap items-center gap-2">
+          <Button
+            size="sm"
+            disabled={saving || !newPin || (enabled && !currentPin)}
+            onClick={() => void save(newPin)}
+          >
+            {enabled ? "Change PIN" : "Set PIN"}
+          </Button>
+          {enabled ? (
+            <Button
+              variant="ghost"
+              size="sm"
+              disabled={saving || !currentPin}
+              onClick={() => void save(null)}
+            >
+              Remove PIN
+            </Button>
+          ) : null}
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
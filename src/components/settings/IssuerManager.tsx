@@ -4,12 +4,19 @@ import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
 import {
   createIssuer,
   deleteIssuer,
+  formatCaPins,
   listIssuers,
+  parseCaPins,
   updateIssuer,
+  type CreateIssuerRequest,
   type IssuerConfig,
   type IssuerEnvironment,
 } from "../../lib/issuers";
-import { normalizeError } from "../../lib/errors";
+import {
+  chooseConflictResolution,
+  normalizeError,
+  withConfirmationPin,
+} from "../../lib/errors";
 import { validateIssuerForm, type IssuerFormState } from "../../lib/issuers/validation";
 import { IssuerList } from "./issuers/IssuerList";
 import { IssuerForm } from "./issuers/IssuerForm";
@@ -30,6 +37,10 @@ export function IssuerManager() {
     directory_url: ACME_DIRECTORY_URLS.staging,
     contact_email: "",
     tos_agreed: false,
+    preferred_chain: "",
+    ca_pins: "",
+    enforce_ca_pins: false,
+    disabled: false,
   });
   const [issuerFormSaving, setIssuerFormSaving] = useState(false);
   const [issuerFormError, setIssuerFormError] = useState<string | null>(null);
@@ -60,6 +71,10 @@ export function IssuerManager() {
       directory_url: ACME_DIRECTORY_URLS.staging,
       contact_email: "",
       tos_agreed: false,
+      preferred_chain: "",
+      ca_pins: "",
+      enforce_ca_pins: false,
+      disabled: false,
     });
     setIssuerFormError(null);
   }
@@ -89,15 +104,30 @@ export function IssuerManager() {
     setIssuerFormSaving(true);
     try {
       if (issuerFormMode === "create") {
-        const created = await createIssuer({
+        const request: CreateIssuerRequest = {
           label: issuerForm.label.trim(),
           issuer_type: "acme",
           environment: issuerForm.environment,
           directory_url: issuerForm.directory_url.trim(),
           contact_email: issuerForm.contact_email.trim(),
           tos_agreed: issuerForm.tos_agreed,
-        });
-        setIssuers((prev) => [...prev, created]);
+          preferred_chain: issuerForm.preferred_chain.trim() || null,
+          ca_pins: parseCaPins(issuerForm.ca_pins),
+          enforce_ca_pins: issuerForm.enforce_ca_pins,
+        };
+        let created: IssuerConfig;
+        try {
+          created = await createIssuer(request);
+        } catch (err) {
+          const resolution = chooseConflictResolution(err);
+          if (!resolution) throw err;
+          created = await createIssuer({ ...request, on_conflict: resolution });
+        }
+        // A merge returns the existing issuer unchanged.
+        setIssuers((prev) => [
+          ...prev.filter((issuer) => issuer.issuer_id !== created.issuer_id),
+          created,
+        ]);
       } else if (issuerForm.issuer_id) {
         const updated = await updateIssuer({
           issuer_id: issuerForm.issuer_id,
@@ -106,6 +136,10 @@ export function IssuerManager() {
           directory_url: issuerForm.directory_url.trim(),
           contact_email: issuerForm.contact_email.trim(),
           tos_agreed: issuerForm.tos_agreed,
+          preferred_chain: issuerForm.preferred_chain.trim() || null,
+          ca_pins: parseCaPins(issuerForm.ca_pins),
+          enforce_ca_pins: issuerForm.enforce_ca_pins,
+          disabled: issuerForm.disabled,
         });
         setIssuers((prev) =>
           prev.map((issuer) =>
@@ -131,6 +165,10 @@ export function IssuerManager() {
       directory_url: issuer.directory_url,
       contact_email: issuer.contact_email ?? "",
       tos_agreed: issuer.tos_agreed,
+      preferred_chain: issuer.preferred_chain ?? "",
+      ca_pins: formatCaPins(issuer.ca_pins ?? []),
+      enforce_ca_pins: issuer.enforce_ca_pins ?? false,
+      disabled: issuer.disabled ?? false,
     });
     setIssuerFormError(null);
   }
@@ -140,7 +178,9 @@ export function IssuerManager() {
     setIssuerError(null);
     setIssuerLoading(true);
     try {
-      const deletedId = await deleteIssuer({ issuer_id: issuer.issuer_id });
+      const deletedId = await withConfirmationPin((pin) =>
+        deleteIssuer({ issuer_id: issuer.issuer_id, confirmation_pin: pin }),
+      );
       setIssuers((prev) => prev.filter((entry) => entry.issuer_id !== deletedId));
     } catch (err) {
       setIssuerError(normalizeError(err));
@@ -0,0 +1,94 @@
+import { useEffect, useState } from "react";
+import { Route } from "lucide-react";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+import { Label } from "../ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "../ui/select";
+import { normalizeError } from "../../lib/errors";
+import {
+  ISSUER_POLICY_PREFERENCE,
+  type IssuerSelectionPolicy,
+} from "../../lib/issuers";
+import { getPreference, setPreference } from "../../lib/preferences";
+
+const POLICY_OPTIONS: { value: IssuerSelectionPolicy; label: string }[] = [
+  { value: "smart", label: "Staging until proven, then production" },
+  { value: "staging", label: "Always staging" },
+  { value: "production", label: "Always production" },
+  { value: "selected", label: "Selected issuer" },
+];
+
+function parsePolicy(value: string | undefined): IssuerSelectionPolicy {
+  return POLICY_OPTIONS.some((option) => option.value === value)
+    ? (value as IssuerSelectionPolicy)
+    : "smart";
+}
+
+export function IssuerPolicySettings() {
+  const [policy, setPolicy] = useState<IssuerSelectionPolicy>("smart");
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getPreference(ISSUER_POLICY_PREFERENCE)
+      .then((saved) => setPolicy(parsePolicy(saved?.value)))
+      .catch((err) => setError(normalizeError(err)));
+  }, []);
+
+  async function updatePolicy(next: IssuerSelectionPolicy) {
+    setPolicy(next);
+    setError(null);
+    try {
+      await setPreference(ISSUER_POLICY_PREFERENCE, next);
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  return (
+    <Card className="shadow-soft">
+      <CardHeader className="flex-row items-center gap-3 space-y-0">
+        <Route className="h-5 w-5 text-primary" />
+        <div>
+          <CardTitle className="text-sm font-semibold">Default issuer</CardTitle>
+          <p className="text-sm text-muted-foreground">
+            How the issuer is preselected for new issuances. Test TLDs and names
+            that have never been issued go to staging; names that issued before
+            go to production.
+          </p>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-3">
+        {error ? (
+          <div className="rounded-lg border border-destructive/50 bg-destructive/10 px-4 py-3 text-sm text-destructive">
+            {error}
+          </div>
+        ) : null}
+        <div className="max-w-xs space-y-1">
+          <Label>Selection policy</Label>
+          <Select
+            value={policy}
+            onValueChange={(value) =>
+              void updatePolicy(value as IssuerSelectionPolicy)
+            }
+          >
+            <SelectTrigger>
+              <SelectValue placeholder="Select policy" />
+            </SelectTrigger>
+            <SelectContent>
+              {POLICY_OPTIONS.map((option) => (
+                <SelectItem key={option.value} value={option.value}>
+                  {option.label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,86 @@
+import { useEffect, useState } from "react";
+import { KeySquare } from "lucide-react";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+import { Label } from "../ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "../ui/select";
+import { normalizeError, withConfirmationPin } from "../../lib/errors";
+import {
+  KEY_EXPORT_POLICY_PREFERENCE,
+  parseKeyExportPolicy,
+  setKeyExportPolicy,
+  type KeyExportPolicy,
+} from "../../lib/certificates";
+import { getPreference } from "../../lib/preferences";
+
+const POLICY_OPTIONS: { value: KeyExportPolicy; label: string }[] = [
+  { value: "allowed", label: "Allowed" },
+  { value: "encrypted_only", label: "Encrypted exports only" },
+  { value: "forbidden", label: "Never leave the vault" },
+];
+
+export function KeyExportPolicySettings() {
+  const [policy, setPolicy] = useState<KeyExportPolicy>("allowed");
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getPreference(KEY_EXPORT_POLICY_PREFERENCE)
+      .then((saved) => setPolicy(parseKeyExportPolicy(saved?.value)))
+      .catch((err) => setError(normalizeError(err)));
+  }, []);
+
+  async function updatePolicy(next: KeyExportPolicy) {
+    setError(null);
+    try {
+      setPolicy(await withConfirmationPin((pin) => setKeyExportPolicy(next, pin)));
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  return (
+    <Card className="shadow-soft">
+      <CardHeader className="flex-row items-center gap-3 space-y-0">
+        <KeySquare className="h-5 w-5 text-primary" />
+        <div>
+          <CardTitle className="text-sm font-semibold">Private key export</CardTitle>
+          <p className="text-sm text-muted-foreground">
+            Applies to exports and to deployment targets that receive the key.
+            Deployments always send the key unencrypted, so they need
+            &quot;Allowed&quot;. Key escrow shares count as encrypted.
+          </p>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-3">
+        {error ? (
+          <div className="rounded-lg border border-destructive/50 bg-destructive/10 px-4 py-3 text-sm text-destructive">
+            {error}
+          </div>
+        ) : null}
+        <div className="max-w-xs space-y-1">
+          <Label>Export policy</Label>
+          <Select
+            value={policy}
+            onValueChange={(value) => void updatePolicy(value as KeyExportPolicy)}
+          >
+            <SelectTrigger>
+              <SelectValue placeholder="Select policy" />
+            </SelectTrigger>
+            <SelectContent>
+              {POLICY_OPTIONS.map((option) => (
+                <SelectItem key={option.value} value={option.value}>
+                  {option.label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,81 @@
+import { useEffect, useState } from "react";
+import { Languages } from "lucide-react";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+import { Label } from "../ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "../ui/select";
+import { normalizeError } from "../../lib/errors";
+import {
+  SUPPORTED_LOCALES,
+  loadMessageLocale,
+  saveMessageLocale,
+} from "../../lib/messages";
+
+const SYSTEM_LOCALE = "system";
+
+export function LanguageSettings() {
+  const [locale, setLocale] = useState(SYSTEM_LOCALE);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    loadMessageLocale()
+      .then((saved) => setLocale(saved || SYSTEM_LOCALE))
+      .catch((err) => setError(normalizeError(err)));
+  }, []);
+
+  async function updateLocale(next: string) {
+    setLocale(next);
+    setError(null);
+    try {
+      await saveMessageLocale(next === SYSTEM_LOCALE ? "" : next);
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  return (
+    <Card className="shadow-soft">
+      <CardHeader className="flex-row items-center gap-3 space-y-0">
+        <Languages className="h-5 w-5 text-primary" />
+        <div>
+          <CardTitle className="text-sm font-semibold">Language</CardTitle>
+          <p className="text-sm text-muted-foreground">
+            Language of validation errors and DNS check results. Messages
+            without a translation stay in English.
+          </p>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-3">
+        {error ? (
+          <div className="rounded-lg border border-destructive/50 bg-destructive/10 px-4 py-3 text-sm text-destructive">
+            {error}
+          </div>
+        ) : null}
+        <div className="max-w-xs space-y-1">
+          <Label>Message language</Label>
+          <Select
+            value={locale}
+            onValueChange={(value) => void updateLocale(value)}
+          >
+            <SelectTrigger>
+              <SelectValue placeholder="Select language" />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value={SYSTEM_LOCALE}>System default</SelectItem>
+              {SUPPORTED_LOCALES.map((option) => (
+                <SelectItem key={option.value} value={option.value}>
+                  {option.label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,57 @@
+import { FolderLock } from "lucide-react";
+import { Button } from "../ui/button";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+import { useManagedKeyDirectory } from "../../hooks/useManagedKeyDirectory";
+
+export function ManagedKeyStorage() {
+  const { directory, saving, error, selectDirectory, resetToVault } =
+    useManagedKeyDirectory();
+
+  return (
+    <Card className="shadow-soft">
+      <CardHeader className="flex-row items-center gap-3 space-y-0">
+        <FolderLock className="h-5 w-5 text-primary" />
+        <div>
+          <CardTitle className="text-sm font-semibold">
+            Managed key storage
+          </CardTitle>
+          <p className="text-sm text-muted-foreground">
+            New managed keys are kept in the vault database, or as encrypted
+            files in a directory you back up. Existing keys stay where they
+            are.
+          </p>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-3">
+        {error ? (
+          <div className="rounded-lg border border-destructive/50 bg-destructive/10 px-4 py-3 text-sm text-destructive">
+            {error}
+          </div>
+        ) : null}
+        <div className="text-sm text-muted-foreground">
+          {directory ?? "Vault database"}
+        </div>
+        <div className="flex flex-wrap items-center gap-2">
+          <Button
+            variant="outline"
+            size="sm"
+            disabled={saving}
+            onClick={() => void selectDirectory()}
+          >
+            Choose directory
+          </Button>
+          {directory ? (
+            <Button
+              variant="ghost"
+              size="sm"
+              disabled={saving}
+              onClick={() => void resetToVault()}
+            >
+              Use vault
+            </Button>
+          ) : null}
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,114 @@
+import { useEffect, useState } from "react";
+import { Activity } from "lucide-react";
+import { Button } from "../ui/button";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+import { normalizeError } from "../../lib/errors";
+import {
+  clearMetrics,
+  getMetrics,
+  type MetricKind,
+  type MetricSummary,
+} from "../../lib/metrics";
+
+const SECTIONS: { kind: MetricKind; title: string }[] = [
+  { kind: "dns_provider", title: "DNS providers" },
+  { kind: "command", title: "Operations" },
+];
+
+function formatDuration(ms: number) {
+  return ms >= 1000 ? `${(ms / 1000).toFixed(1)} s` : `${ms} ms`;
+}
+
+function MetricRow({ metric }: { metric: MetricSummary }) {
+  const failureRate = metric.calls > 0 ? metric.failures / metric.calls : 0;
+  return (
+    <div className="grid grid-cols-[2fr_repeat(4,1fr)] items-baseline gap-2 border-t py-2 text-sm">
+      <div className="min-w-0">
+        <div className="truncate font-medium">{metric.label}</div>
+        {metric.last_error ? (
+          <div className="truncate text-xs text-muted-foreground" title={metric.last_error}>
+            Last error: {metric.last_error}
+          </div>
+        ) : null}
+      </div>
+      <div>{metric.calls}</div>
+      <div className={failureRate >= 0.1 ? "text-destructive" : undefined}>
+        {Math.round(failureRate * 100)}%
+      </div>
+      <div>{formatDuration(metric.avg_ms)}</div>
+      <div>{formatDuration(metric.max_ms)}</div>
+    </div>
+  );
+}
+
+export function MetricsSettings() {
+  const [metrics, setMetrics] = useState<MetricSummary[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getMetrics()
+      .then(setMetrics)
+      .catch((err) => setError(normalizeError(err)));
+  }, []);
+
+  async function reset() {
+    setError(null);
+    try {
+      await clearMetrics();
+      setMetrics([]);
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  return (
+    <Card className="shadow-soft">
+      <CardHeader className="flex-row items-center gap-3 space-y-0">
+        <Activity className="h-5 w-5 text-primary" />
+        <div className="flex-1">
+          <CardTitle className="text-sm font-semibold">Metrics</CardTitle>
+          <p className="text-sm text-muted-foreground">
+            Call counts, failure rates, and durations, slowest first. Kept on
+            this device only.
+          </p>
+        </div>
+        <Button
+          variant="outline"
+          size="sm"
+          disabled={metrics.length === 0}
+          onClick={() => void reset()}
+        >
+          Reset
+        </Button>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {error ? (
+          <div className="rounded-lg border border-destructive/50 bg-destructive/10 px-4 py-3 text-sm text-destructive">
+            {error}
+          </div>
+        ) : null}
+        {metrics.length === 0 ? (
+          <p className="text-sm text-muted-foreground">Nothing recorded yet.</p>
+        ) : null}
+        {SECTIONS.map(({ kind, title }) => {
+          const rows = metrics.filter((metric) => metric.kind === kind);
+          if (rows.length === 0) return null;
+          return (
+            <div key={kind}>
+              <div className="grid grid-cols-[2fr_repeat(4,1fr)] gap-2 pb-1 text-xs font-medium text-muted-foreground">
+                <div>{title}</div>
+                <div>Calls</div>
+                <div>Failures</div>
+                <div>Average</div>
+                <div>Slowest</div>
+              </div>
+              {rows.map((metric) => (
+                <MetricRow key={`${metric.kind}:${metric.name}`} metric={metric} />
+              ))}
+            </div>
+          );
+        })}
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,84 @@
+import { useEffect, useState } from "react";
+import { Fingerprint } from "lucide-react";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+import { Input } from "../ui/input";
+import { Label } from "../ui/label";
+import { normalizeError } from "../../lib/errors";
+import {
+  OUTBOUND_CONTACT_PREFERENCE,
+  OUTBOUND_USER_AGENT_PREFERENCE,
+  getPreference,
+  setPreference,
+} from "../../lib/preferences";
+
+export function OutboundIdentitySettings() {
+  const [userAgent, setUserAgent] = useState("");
+  const [contact, setContact] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    Promise.all([
+      getPreference(OUTBOUND_USER_AGENT_PREFERENCE),
+      getPreference(OUTBOUND_CONTACT_PREFERENCE),
+    ])
+      .then(([savedUserAgent, savedContact]) => {
+        setUserAgent(savedUserAgent?.value ?? "");
+        setContact(savedContact?.value ?? "");
+      })
+      .catch((err) => setError(normalizeError(err)));
+  }, []);
+
+  async function save(name: string, value: string) {
+    setError(null);
+    try {
+      await setPreference(name, value.trim());
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  return (
+    <Card className="shadow-soft">
+      <CardHeader className="flex-row items-center gap-3 space-y-0">
+        <Fingerprint className="h-5 w-5 text-primary" />
+        <div>
+          <CardTitle className="text-sm font-semibold">Request identity</CardTitle>
+          <p className="text-sm text-muted-foreground">
+            Sent with requests to CAs and DNS provider APIs. Some require an
+            operator contact before granting rate-limit exemptions; an email
+            address is also sent as the From header.
+          </p>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-3">
+        {error ? (
+          <div className="rounded-lg border border-destructive/50 bg-destructive/10 px-4 py-3 text-sm text-destructive">
+            {error}
+          </div>
+        ) : null}
+        <div className="grid gap-4 md:grid-cols-2">
+          <div className="space-y-1">
+            <Label htmlFor="outbound-contact">Operator contact</Label>
+            <Input
+              id="outbound-contact"
+              placeholder="ops@example.com"
+              value={contact}
+              onChange={(event) => setContact(event.target.value)}
+              onBlur={() => void save(OUTBOUND_CONTACT_PREFERENCE, contact)}
+            />
+          </div>
+          <div className="space-y-1">
+            <Label htmlFor="outbound-user-agent">User-Agent</Label>
+            <Input
+              id="outbound-user-agent"
+              placeholder="SSLBoard/<version>"
+              value={userAgent}
+              onChange={(event) => setUserAgent(event.target.value)}
+              onBlur={() => void save(OUTBOUND_USER_AGENT_PREFERENCE, userAgent)}
+            />
+          </div>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
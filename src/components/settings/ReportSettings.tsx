@@ -0,0 +1,153 @@
+import { FileText, FolderOpen } from "lucide-react";
+import { Button } from "../ui/button";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+import { Label } from "../ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "../ui/select";
+import type { ReportFormat } from "../../lib/reports";
+import {
+  useReportSettings,
+  type ReportSchedule,
+} from "../../hooks/useReportSettings";
+
+function formatDate(iso: string) {
+  const date = new Date(iso);
+  return Number.isNaN(date.getTime())
+    ? "—"
+    : date.toLocaleString(undefined, {
+      month: "short",
+      day: "numeric",
+      year: "numeric",
+      hour: "2-digit",
+      minute: "2-digit",
+    });
+}
+
+export function ReportSettings() {
+  const {
+    schedule,
+    format,
+    folder,
+    lastRun,
+    generating,
+    lastResult,
+    error,
+    updateSchedule,
+    updateFormat,
+    selectFolder,
+    generateNow,
+  } = useReportSettings();
+
+  return (
+    <Card className="shadow-soft">
+      <CardHeader className="flex-row items-center gap-3 space-y-0">
+        <FileText className="h-5 w-5 text-primary" />
+        <div>
+          <CardTitle className="text-sm font-semibold">
+            Certificate reports
+          </CardTitle>
+          <p className="text-sm text-muted-foreground">
+            Summaries of issued, renewed, failed, and expiring certificates,
+            saved to a folder on a schedule. HTML reports print cleanly to PDF.
+          </p>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {error ? (
+          <div className="rounded-lg border border-destructive/50 bg-destructive/10 px-4 py-3 text-sm text-destructive">
+            {error}
+          </div>
+        ) : null}
+
+        <div className="grid gap-4 md:grid-cols-2">
+          <div className="space-y-1">
+            <Label>Schedule</Label>
+            <Select
+              value={schedule}
+              onValueChange={(value) =>
+                void updateSchedule(value as ReportSchedule)
+              }
+            >
+              <SelectTrigger>
+                <SelectValue placeholder="Select schedule" />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="off">Off</SelectItem>
+                <SelectItem value="weekly">Weekly</SelectItem>
+                <SelectItem value="monthly">Monthly</SelectItem>
+              </SelectContent>
+            </Select>
+          </div>
+          <div className="space-y-1">
+            <Label>Format</Label>
+            <Select
+              value={format}
+              onValueChange={(value) => void updateFormat(value as ReportFormat)}
+            >
+              <SelectTrigger>
+                <SelectValue placeholder="Select format" />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="markdown">Markdown</SelectItem>
+                <SelectItem value="html">HTML</SelectItem>
+              </SelectContent>
+            </Select>
+          </div>
+        </div>
+
+        <div className="flex flex-wrap items-center justify-between gap-3">
+          <div className="text-sm text-muted-foreground">
+            {folder ?? "No report folder selected"}
+          </div>
+          <Button
+            variant="outline"
+            size="sm"
+            className="gap-2"
+            onClick={() => void selectFolder()}
+          >
+            <FolderOpen className="h-4 w-4" />
+            Choose folder
+          </Button>
+        </div>
+
+        <div className="flex flex-wrap items-center gap-2">
+          <Button
+            size="sm"
+            disabled={generating || !folder}
+            onClick={() => void generateNow("weekly")}
+          >
+            Generate weekly report
+          </Button>
+          <Button
+            size="sm"
+            variant="secondary"
+            disabled={generating || !folder}
+            onClick={() => void generateNow("monthly")}
+          >
+            Generate monthly report
+          </Button>
+          {lastRun ? (
+            <span className="text-xs text-muted-foreground">
+              Last report {formatDate(lastRun)}
+            </span>
+          ) : null}
+        </div>
+
+        {lastResult ? (
+          <div className="rounded-lg border bg-background/80 p-3 text-sm">
+            <div className="font-medium">{lastResult.path}</div>
+            <div className="text-muted-foreground">
+              {lastResult.issued} issued · {lastResult.renewed} renewed ·{" "}
+              {lastResult.failed} failed · {lastResult.expiring} expiring
+            </div>
+          </div>
+        ) : null}
+      </CardContent>
+    </Card>
+  );
+}
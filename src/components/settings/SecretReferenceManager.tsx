@@ -5,6 +5,7 @@ import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
 import { cn } from "../../lib/utils";
 import { type SecretKind } from "../../lib/secrets";
 import { useSecretReferences } from "../../hooks/useSecretReferences";
+import { ManagedKeyStorage } from "./ManagedKeyStorage";
 
 export function SecretReferenceManager() {
   const {
@@ -28,6 +29,8 @@ export function SecretReferenceManager() {
         return "ACME account key";
       case "managed_private_key":
         return "Managed private key";
+      case "deploy_target_credentials":
+        return "Deployment target credentials";
       default:
         return kind;
     }
@@ -108,12 +111,18 @@ export function SecretReferenceManager() {
                     <div className="mt-1 text-sm text-muted-foreground">
                       {formatKind(secret.kind)} · Created {formatDate(secret.created_at)}
                     </div>
+                    {secret.storage_backend === "directory" ? (
+                      <div className="mt-1 text-xs text-muted-foreground">
+                        Stored in {secret.storage_path}
+                      </div>
+                    ) : null}
                   </div>
                 </div>
               </div>
             ))}
           </CardContent>
         </Card>
+        <ManagedKeyStorage />
       </div>
     </div>
   );
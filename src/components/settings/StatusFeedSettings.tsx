@@ -0,0 +1,121 @@
+import { useEffect, useState } from "react";
+import { save } from "@tauri-apps/plugin-dialog";
+import { FileJson, FolderOpen } from "lucide-react";
+import { Button } from "../ui/button";
+import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
+import { normalizeError } from "../../lib/errors";
+import { getPreference, setPreference } from "../../lib/preferences";
+import {
+  STATUS_FEED_PATH_PREFERENCE,
+  writeStatusFeed,
+  type StatusFeedResult,
+} from "../../lib/reports";
+
+export function StatusFeedSettings() {
+  const [path, setPath] = useState<string | null>(null);
+  const [lastResult, setLastResult] = useState<StatusFeedResult | null>(null);
+  const [writing, setWriting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getPreference(STATUS_FEED_PATH_PREFERENCE)
+      .then((pref) => setPath(pref?.value || null))
+      .catch((err) => setError(normalizeError(err)));
+  }, []);
+
+  async function updatePath(next: string) {
+    setError(null);
+    try {
+      await setPreference(STATUS_FEED_PATH_PREFERENCE, next);
+      setPath(next || null);
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  async function selectPath() {
+    const next = await save({
+      defaultPath: path ?? "sslboard-status.json",
+      filters: [{ name: "JSON", extensions: ["json"] }],
+    });
+    if (next) {
+      await updatePath(next);
+    }
+  }
+
+  async function writeNow() {
+    setWriting(true);
+    setError(null);
+    try {
+      setLastResult(await writeStatusFeed());
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setWriting(false);
+    }
+  }
+
+  const feed = lastResult?.feed;
+
+  return (
+    <Card className="shadow-soft">
+      <CardHeader className="flex-row items-center gap-3 space-y-0">
+        <FileJson className="h-5 w-5 text-primary" />
+        <div>
+          <CardTitle className="text-sm font-semibold">Status feed</CardTitle>
+          <p className="text-sm text-muted-foreground">
+            A small JSON file with expiring counts and the soonest expiry,
+            refreshed every 15 minutes. Point Home Assistant, Grafana, or any
+            dashboard that reads files at it.
+          </p>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {error ? (
+          <div className="rounded-lg border border-destructive/50 bg-destructive/10 px-4 py-3 text-sm text-destructive">
+            {error}
+          </div>
+        ) : null}
+
+        <div className="flex flex-wrap items-center justify-between gap-3">
+          <div className="text-sm text-muted-foreground">
+            {path ?? "Status feed off"}
+          </div>
+          <div className="flex gap-2">
+            {path ? (
+              <Button variant="ghost" size="sm" onClick={() => void updatePath("")}>
+                Turn off
+              </Button>
+            ) : null}
+            <Button
+              variant="outline"
+              size="sm"
+              className="gap-2"
+              onClick={() => void selectPath()}
+            >
+              <FolderOpen className="h-4 w-4" />
+              Choose file
+            </Button>
+          </div>
+        </div>
+
+        <Button size="sm" disabled={writing || !path} onClick={() => void writeNow()}>
+          Write now
+        </Button>
+
+        {lastResult && feed ? (
+          <div className="rounded-lg border bg-background/80 p-3 text-sm">
+            <div className="font-medium">{lastResult.path}</div>
+            <div className="text-muted-foreground">
+              {feed.health} · {feed.total} certificates · {feed.expiring_soon} expiring
+              · {feed.expired} expired
+              {feed.worst_expiry
+                ? ` · next expiry in ${feed.worst_expiry.days_left} days`
+                : ""}
+            </div>
+          </div>
+        ) : null}
+      </CardContent>
+    </Card>
+  );
+}
@@ -4,6 +4,7 @@ import { Button } from "../../ui/button";
 import { Checkbox } from "../../ui/checkbox";
 import { Input } from "../../ui/input";
 import { Label } from "../../ui/label";
+import { Textarea } from "../../ui/textarea";
 import {
   Select,
   SelectContent,
@@ -83,6 +84,45 @@ export function IssuerForm({
         />
       </div>
 
+      <div className="space-y-1">
+        <Label htmlFor="issuer-preferred-chain">Preferred chain (optional)</Label>
+        <Input
+          id="issuer-preferred-chain"
+          value={formState.preferred_chain}
+          onChange={(e) => onFormStateChange({ preferred_chain: e.target.value })}
+          placeholder="ISRG Root X1"
+        />
+      </div>
+
+      <div className="space-y-1">
+        <Label htmlFor="issuer-ca-pins">Pinned issuing CA (optional)</Label>
+        <Textarea
+          id="issuer-ca-pins"
+          rows={2}
+          value={formState.ca_pins}
+          onChange={(e) => onFormStateChange({ ca_pins: e.target.value })}
+          placeholder={"R11\nsha256/<base64 SPKI hash>"}
+        />
+        <p className="text-xs text-muted-foreground">
+          One per line: a CA subject or common name, or an SPKI hash prefixed with sha256/.
+          Certificates from any other CA are flagged.
+        </p>
+        {formState.ca_pins.trim() ? (
+          <div className="flex items-center gap-2 pt-1">
+            <Checkbox
+              id="issuer-enforce-ca-pins"
+              checked={formState.enforce_ca_pins}
+              onCheckedChange={(checked) =>
+                onFormStateChange({ enforce_ca_pins: checked === true })
+              }
+            />
+            <Label htmlFor="issuer-enforce-ca-pins" className="text-sm font-medium">
+              Reject certificates from other CAs
+            </Label>
+          </div>
+        ) : null}
+      </div>
+
       <div className="flex items-center gap-2">
         <Checkbox
           id="issuer-tos-agreed"
@@ -97,6 +137,21 @@ export function IssuerForm({
         </Label>
       </div>
 
+      {formMode === "edit" ? (
+        <div className="flex items-center gap-2">
+          <Checkbox
+            id="issuer-disabled"
+            checked={formState.disabled}
+            onCheckedChange={(checked) =>
+              onFormStateChange({ disabled: checked === true })
+            }
+          />
+          <Label htmlFor="issuer-disabled" className="text-sm font-medium">
+            Disable this issuer
+          </Label>
+        </div>
+      ) : null}
+
       <Button type="submit" className="w-full gap-2" disabled={saving}>
         {saving ? (
           <RefreshCw className="h-4 w-4 animate-spin" />
@@ -1,6 +1,6 @@
 import { Button } from "../../ui/button";
 import { Card, CardContent } from "../../ui/card";
-import type { IssuerConfig } from "../../../lib/issuers";
+import { issuerIsFailing, type IssuerConfig } from "../../../lib/issuers";
 import { formatEnvironment, formatIssuerType } from "../../../lib/issuers/format";
 
 interface IssuerListProps {
@@ -24,6 +24,11 @@ export function IssuerList({
             <div>
               <div className="flex flex-wrap items-center gap-2 text-sm font-semibold">
                 {issuer.label}
+                {issuer.disabled ? (
+                  <span className="rounded-full bg-muted px-2 py-0.5 text-[11px] font-semibold uppercase text-muted-foreground">
+                    Disabled
+                  </span>
+                ) : null}
               </div>
               <div className="mt-1 text-xs text-muted-foreground">
                 {formatIssuerType(issuer.issuer_type)} · {formatEnvironment(issuer.environment)} ·{" "}
@@ -32,6 +37,21 @@ export function IssuerList({
               <div className="mt-1 text-xs text-muted-foreground">
                 {issuer.directory_url}
               </div>
+              {issuer.stats?.orders || issuer.stats?.failures ? (
+                <div className="mt-1 text-xs text-muted-foreground">
+                  {issuer.stats.orders} orders · {issuer.stats.successes} issued ·{" "}
+                  {issuer.stats.failures} failed
+                </div>
+              ) : null}
+              {issuerIsFailing(issuer.stats) ? (
+                <div className="mt-1 max-w-xl text-xs text-red-500">
+                  Last attempt failed
+                  {issuer.stats.last_error_at
+                    ? ` (${new Date(issuer.stats.last_error_at).toLocaleString()})`
+                    : ""}
+                  : {issuer.stats.last_error}
+                </div>
+              ) : null}
             </div>
             <div className="flex flex-wrap gap-2">
               <Button
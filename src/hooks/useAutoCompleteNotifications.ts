@@ -0,0 +1,20 @@
+import { useEffect } from "react";
+import { toast } from "sonner";
+import { listenAutoComplete } from "../lib/issuance";
+
+/** Announces issuances completed in the background, whichever page is open. */
+export function useAutoCompleteNotifications() {
+  useEffect(() => {
+    const unlisten = listenAutoComplete((outcome) => {
+      if (outcome.certificate) {
+        const name = outcome.certificate.subjects[0] ?? outcome.certificate.id;
+        toast.success(`Records propagated; certificate for ${name} issued.`);
+      } else {
+        toast.error(`Automatic completion failed: ${outcome.error ?? "unknown error"}`);
+      }
+    });
+    return () => {
+      void unlisten.then((stop) => stop());
+    };
+  }, []);
+}
@@ -0,0 +1,140 @@
+import { open } from "@tauri-apps/plugin-dialog";
+import { useEffect, useState } from "react";
+import {
+  BACKUP_FOLDER_PREFERENCE,
+  BACKUP_LAST_RUN_PREFERENCE,
+  BACKUP_RETAIN_PREFERENCE,
+  BACKUP_SCHEDULE_PREFERENCE,
+  DEFAULT_BACKUP_RETAIN,
+  createBackup,
+  verifyBackup,
+  type BackupResult,
+  type BackupSchedule,
+  type BackupVerification,
+} from "../lib/backups";
+import { normalizeError } from "../lib/errors";
+import { getPreference, setPreference } from "../lib/preferences";
+
+export function useBackupSettings() {
+  const [schedule, setSchedule] = useState<BackupSchedule>("off");
+  const [folder, setFolder] = useState<string | null>(null);
+  const [retain, setRetain] = useState(DEFAULT_BACKUP_RETAIN);
+  const [lastRun, setLastRun] = useState<string | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [lastResult, setLastResult] = useState<BackupResult | null>(null);
+  const [verification, setVerification] = useState<BackupVerification | null>(
+    null,
+  );
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    void load();
+  }, []);
+
+  async function load() {
+    try {
+      const [savedSchedule, savedFolder, savedRetain, savedLastRun] =
+        await Promise.all([
+          getPreference(BACKUP_SCHEDULE_PREFERENCE),
+          getPreference(BACKUP_FOLDER_PREFERENCE),
+          getPreference(BACKUP_RETAIN_PREFERENCE),
+          getPreference(BACKUP_LAST_RUN_PREFERENCE),
+        ]);
+      const scheduleValue = savedSchedule?.value;
+      setSchedule(
+        scheduleValue === "daily" || scheduleValue === "weekly"
+          ? scheduleValue
+          : "off",
+      );
+      setFolder(savedFolder?.value || null);
+      const parsedRetain = Number.parseInt(savedRetain?.value ?? "", 10);
+      setRetain(parsedRetain > 0 ? parsedRetain : DEFAULT_BACKUP_RETAIN);
+      setLastRun(savedLastRun?.value || null);
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  async function save(name: string, value: string) {
+    setError(null);
+    try {
+      await setPreference(name, value);
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  async function updateSchedule(next: BackupSchedule) {
+    setSchedule(next);
+    await save(BACKUP_SCHEDULE_PREFERENCE, next);
+  }
+
+  async function updateRetain(next: number) {
+    if (!Number.isFinite(next) || next < 1) return;
+    setRetain(next);
+    await save(BACKUP_RETAIN_PREFERENCE, String(next));
+  }
+
+  async function selectFolder() {
+    const selection = await open({ directory: true, multiple: false });
+    const next = Array.isArray(selection) ? selection[0] : selection;
+    if (typeof next === "string" && next) {
+      setFolder(next);
+      await save(BACKUP_FOLDER_PREFERENCE, next);
+    }
+  }
+
+  async function backupNow() {
+    setBusy(true);
+    setError(null);
+    try {
+      const result = await createBackup(folder);
+      setLastResult(result);
+      setLastRun(result.created_at);
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function verify(path?: string) {
+    let target = path;
+    if (!target) {
+      const selection = await open({
+        multiple: false,
+        defaultPath: folder ?? undefined,
+        filters: [{ name: "SSLBoard backup", extensions: ["sqlite"] }],
+      });
+      const picked = Array.isArray(selection) ? selection[0] : selection;
+      if (typeof picked !== "string" || !picked) return;
+      target = picked;
+    }
+    setBusy(true);
+    setError(null);
+    try {
+      setVerification(await verifyBackup(target));
+    } catch (err) {
+      setVerification(null);
+      setError(normalizeError(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  return {
+    schedule,
+    folder,
+    retain,
+    lastRun,
+    busy,
+    lastResult,
+    verification,
+    error,
+    updateSchedule,
+    updateRetain,
+    selectFolder,
+    backupNow,
+    verify,
+  };
+}
@@ -0,0 +1,67 @@
+import { useEffect, useState } from "react";
+import { normalizeError } from "../lib/errors";
+import {
+  addCertificateUsage,
+  checkCertificateUsages,
+  listCertificateUsages,
+  removeCertificateUsage,
+  type CertificateUsage,
+  type EndpointCheck,
+} from "../lib/usages";
+
+export function useCertificateUsages(certificateId: string) {
+  const [usages, setUsages] = useState<CertificateUsage[]>([]);
+  const [checks, setChecks] = useState<EndpointCheck[]>([]);
+  const [checkedAt, setCheckedAt] = useState(0);
+  const [checking, setChecking] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setUsages([]);
+    setChecks([]);
+    setError(null);
+    listCertificateUsages(certificateId)
+      .then(setUsages)
+      .catch((err) => setError(normalizeError(err)));
+  }, [certificateId]);
+
+  async function add(link: { endpoint?: string; deployTargetId?: string }) {
+    setError(null);
+    try {
+      const usage = await addCertificateUsage(certificateId, link);
+      setUsages((prev) =>
+        prev.some((existing) => existing.id === usage.id) ? prev : [...prev, usage],
+      );
+      return true;
+    } catch (err) {
+      setError(normalizeError(err));
+      return false;
+    }
+  }
+
+  async function remove(usageId: string) {
+    setError(null);
+    try {
+      await removeCertificateUsage(usageId);
+      setUsages((prev) => prev.filter((usage) => usage.id !== usageId));
+      setChecks((prev) => prev.filter((check) => check.usage_id !== usageId));
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  async function check() {
+    setChecking(true);
+    setError(null);
+    try {
+      setChecks(await checkCertificateUsages(certificateId));
+      setCheckedAt(Date.now());
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setChecking(false);
+    }
+  }
+
+  return { usages, checks, checkedAt, checking, error, add, remove, check };
+}
@@ -0,0 +1,26 @@
+import { useEffect, useState } from "react";
+import { checkIssuerClockSkew, type ClockSkewCheck } from "../lib/issuance";
+
+/**
+ * Compares the local clock with the selected issuer's ACME server. Failures
+ * are ignored: the check only exists to warn, never to block issuance.
+ */
+export function useClockSkewCheck(issuerId: string | null) {
+  const [clockSkew, setClockSkew] = useState<ClockSkewCheck | null>(null);
+
+  useEffect(() => {
+    setClockSkew(null);
+    if (!issuerId) return;
+    let active = true;
+    checkIssuerClockSkew(issuerId)
+      .then((result) => {
+        if (active) setClockSkew(result);
+      })
+      .catch(() => undefined);
+    return () => {
+      active = false;
+    };
+  }, [issuerId]);
+
+  return clockSkew;
+}
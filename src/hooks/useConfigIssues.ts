@@ -0,0 +1,30 @@
+import { useEffect, useState } from "react";
+import { normalizeError } from "../lib/errors";
+import { getConfigIssues, repairConfigIssue, type ConfigIssue } from "../lib/recovery";
+
+export function useConfigIssues() {
+  const [issues, setIssues] = useState<ConfigIssue[]>([]);
+  const [pendingId, setPendingId] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getConfigIssues()
+      .then(setIssues)
+      .catch((err) => setError(normalizeError(err)));
+  }, []);
+
+  async function repair(issueId: string) {
+    setPendingId(issueId);
+    setError(null);
+    try {
+      await repairConfigIssue(issueId);
+      setIssues((prev) => prev.filter((issue) => issue.id !== issueId));
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setPendingId(null);
+    }
+  }
+
+  return { issues, pendingId, error, repair };
+}
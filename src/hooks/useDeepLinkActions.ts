@@ -0,0 +1,56 @@
+import { useEffect } from "react";
+import { useNavigate } from "react-router-dom";
+import { toast } from "sonner";
+import {
+  listenDeepLinkActions,
+  takePendingDeepLink,
+  type DeepLinkAction,
+} from "../lib/deep-links";
+
+/** Navigates to the page for each `sslboard://` link the backend resolves. */
+export function useDeepLinkActions() {
+  const navigate = useNavigate();
+
+  useEffect(() => {
+    let active = true;
+
+    function handle(action: DeepLinkAction) {
+      switch (action.action) {
+        case "renew":
+          navigate(
+            `/issue?domains=${encodeURIComponent(action.domains.join(","))}`,
+          );
+          break;
+        case "check":
+          if (action.certificate_ids.length === 0) {
+            toast.info(`No current certificate covers ${action.domain}.`);
+            navigate(`/issue?domains=${encodeURIComponent(action.domain)}`);
+          } else {
+            navigate(
+              `/certificates?select=${encodeURIComponent(action.certificate_ids[0])}`,
+            );
+          }
+          break;
+        case "invalid":
+          toast.error(`Unable to open link: ${action.message}`);
+          break;
+      }
+    }
+
+    const unlisten = listenDeepLinkActions((action) => {
+      // The event also left the action pending; clear it so it is not replayed.
+      void takePendingDeepLink();
+      handle(action);
+    });
+    void takePendingDeepLink().then((action) => {
+      if (active && action) {
+        handle(action);
+      }
+    });
+
+    return () => {
+      active = false;
+      void unlisten.then((stop) => stop());
+    };
+  }, [navigate]);
+}
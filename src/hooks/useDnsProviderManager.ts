@@ -8,8 +8,16 @@ import {
   type CreateDnsProviderRequest,
   type DnsProviderRecord,
   type DnsProviderTestResult,
+  type DomainSuffixVerification,
+  type ProviderTestMode,
+  type SuffixShadowWarning,
 } from "../lib/dns-providers";
-import { maybeToastVaultUnlockError, normalizeError } from "../lib/errors";
+import {
+  chooseConflictResolution,
+  maybeToastVaultUnlockError,
+  normalizeError,
+  withConfirmationPin,
+} from "../lib/errors";
 
 export type ProviderFormState = CreateDnsProviderRequest & { provider_id?: string };
 
@@ -25,6 +33,7 @@ export function useDnsProviderManager() {
     route53_access_key: "",
     route53_secret_key: "",
     config: null,
+    verify_suffixes: true,
   });
   const [formMode, setFormMode] = useState<"create" | "edit">("create");
   const [saving, setSaving] = useState(false);
@@ -33,6 +42,10 @@ export function useDnsProviderManager() {
   );
   const [testLoading, setTestLoading] = useState<Record<string, boolean>>({});
   const [confirmDeleteId, setConfirmDeleteId] = useState<string | null>(null);
+  const [verification, setVerification] = useState<DomainSuffixVerification[] | null>(
+    null,
+  );
+  const [suffixWarnings, setSuffixWarnings] = useState<SuffixShadowWarning[]>([]);
 
   useEffect(() => {
     void refreshProviders();
@@ -65,6 +78,7 @@ export function useDnsProviderManager() {
       route53_access_key: "",
       route53_secret_key: "",
       config: null,
+      verify_suffixes: true,
     });
   }
 
@@ -88,9 +102,11 @@ export function useDnsProviderManager() {
     if (saving) return;
     setSaving(true);
     setError(null);
+    setVerification(null);
+    setSuffixWarnings([]);
     try {
       if (formMode === "create") {
-        const created = await createDnsProvider({
+        const request = {
           provider_type: formState.provider_type,
           label: formState.label.trim(),
           domain_suffixes: formState.domain_suffixes,
@@ -98,8 +114,26 @@ export function useDnsProviderManager() {
           route53_access_key: formState.route53_access_key,
           route53_secret_key: formState.route53_secret_key,
           config: formState.config ?? null,
-        });
-        setProviders((prev) => [created, ...prev]);
+          verify_suffixes: formState.verify_suffixes,
+        };
+        let created: DnsProviderRecord;
+        try {
+          created = await createDnsProvider(request);
+        } catch (err) {
+          const resolution = chooseConflictResolution(err);
+          if (!resolution) throw err;
+          created = await createDnsProvider({
+            ...request,
+            on_conflict: resolution,
+          });
+        }
+        // A merge returns the existing provider with the added suffixes.
+        setProviders((prev) => [
+          created,
+          ...prev.filter((entry) => entry.id !== created.id),
+        ]);
+        setVerification(created.verification ?? null);
+        setSuffixWarnings(created.suffix_warnings ?? []);
       } else if (formState.provider_id) {
         const updated = await updateDnsProvider({
           provider_id: formState.provider_id,
@@ -113,6 +147,7 @@ export function useDnsProviderManager() {
         setProviders((prev) =>
           prev.map((entry) => (entry.id === updated.id ? updated : entry)),
         );
+        setSuffixWarnings(updated.suffix_warnings ?? []);
       }
       resetForm();
     } catch (err) {
@@ -128,7 +163,7 @@ export function useDnsProviderManager() {
     setConfirmDeleteId(null);
     setError(null);
     try {
-      await deleteDnsProvider(providerId);
+      await withConfirmationPin((pin) => deleteDnsProvider(providerId, pin));
       setProviders((prev) => prev.filter((entry) => entry.id !== providerId));
     } catch (err) {
       const message = normalizeError(err);
@@ -137,11 +172,17 @@ export function useDnsProviderManager() {
     }
   }
 
-  async function handleTest(providerId: string) {
+  function replaceProvider(updated: DnsProviderRecord) {
+    setProviders((prev) =>
+      prev.map((entry) => (entry.id === updated.id ? updated : entry)),
+    );
+  }
+
+  async function handleTest(providerId: string, mode: ProviderTestMode = "full") {
     if (testLoading[providerId]) return;
     setTestLoading((prev) => ({ ...prev, [providerId]: true }));
     try {
-      const result = await testDnsProvider(providerId);
+      const result = await testDnsProvider(providerId, mode);
       setTestResults((prev) => ({ ...prev, [providerId]: result }));
     } catch (err) {
       const message = normalizeError(err);
@@ -177,5 +218,8 @@ export function useDnsProviderManager() {
     testResults,
     testLoading,
     handleTest,
+    verification,
+    suffixWarnings,
+    replaceProvider,
   };
 }
@@ -1,12 +1,20 @@
 import { useEffect, useState } from "react";
-import { listIssuers, type IssuerConfig } from "../lib/issuers";
+import {
+  listIssuers,
+  suggestIssuer,
+  type IssuerConfig,
+  type IssuerSuggestion,
+} from "../lib/issuers";
 import { normalizeError } from "../lib/errors";
 
-export function useIssuerOptions() {
+export function useIssuerOptions(domains: string[]) {
   const [issuers, setIssuers] = useState<IssuerConfig[]>([]);
   const [issuerLoading, setIssuerLoading] = useState(false);
   const [issuerError, setIssuerError] = useState<string | null>(null);
   const [selectedIssuer, setSelectedIssuer] = useState<IssuerConfig | null>(null);
+  const [suggestion, setSuggestion] = useState<IssuerSuggestion | null>(null);
+  // Once the user picks an issuer, suggestions no longer override it.
+  const [manualChoice, setManualChoice] = useState(false);
 
   useEffect(() => {
     let active = true;
@@ -16,8 +24,10 @@ export function useIssuerOptions() {
       try {
         const issuerList = await listIssuers();
         if (!active) return;
-        setIssuers(issuerList);
-        setSelectedIssuer(issuerList[0] ?? null);
+        // Disabled issuers can't issue, so they are never offered.
+        const enabled = issuerList.filter((issuer) => !issuer.disabled);
+        setIssuers(enabled);
+        setSelectedIssuer(enabled[0] ?? null);
       } catch (err) {
         if (active) {
           setIssuerError(normalizeError(err, "Failed to load issuers."));
@@ -40,8 +50,39 @@ export function useIssuerOptions() {
     }
   }, [issuers, selectedIssuer]);
 
+  useEffect(() => {
+    let active = true;
+    if (!domains.length || !issuers.length) {
+      setSuggestion(null);
+      return undefined;
+    }
+    const timer = window.setTimeout(() => {
+      suggestIssuer(domains)
+        .then((next) => {
+          if (active) setSuggestion(next);
+        })
+        .catch(() => {
+          // Invalid names are reported when issuance starts.
+          if (active) setSuggestion(null);
+        });
+    }, 300);
+    return () => {
+      active = false;
+      window.clearTimeout(timer);
+    };
+  }, [domains.join("|"), issuers]);
+
+  useEffect(() => {
+    if (manualChoice || !suggestion) return;
+    const issuer = issuers.find((entry) => entry.issuer_id === suggestion.issuer_id);
+    if (issuer) {
+      setSelectedIssuer(issuer);
+    }
+  }, [suggestion, issuers, manualChoice]);
+
   function selectIssuerById(issuerId: string) {
     const issuer = issuers.find((entry) => entry.issuer_id === issuerId) ?? null;
+    setManualChoice(true);
     setSelectedIssuer(issuer);
   }
 
@@ -50,6 +91,7 @@ export function useIssuerOptions() {
     issuerLoading,
     issuerError,
     selectedIssuer,
+    suggestion,
     selectIssuerById,
   };
 }
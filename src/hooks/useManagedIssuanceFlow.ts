@@ -1,19 +1,27 @@
-import { useRef, useState } from "react";
+import { useEffect, useRef, useState } from "react";
 import {
   completeManagedIssuance,
   keyOptionToParams,
+  listenAutoComplete,
+  listenIssuanceProgress,
+  promoteToProduction,
   startManagedIssuance,
   type IssuanceKeyOption,
+  type IssuanceProgress,
   type StartIssuanceResponse,
 } from "../lib/issuance";
-import { normalizeError } from "../lib/errors";
+import { getIssuanceInProgress, getIssuanceTimeout, normalizeError } from "../lib/errors";
 import type { CertificateRecord } from "../lib/certificates";
+import type { PropagationWait } from "../lib/dns-providers";
 
 
 export function useManagedIssuanceFlow(
   selectedIssuerId: string | null,
   parsedDomains: string[],
   keyOption: IssuanceKeyOption,
+  promoteFrom: string | null = null,
+  propagationWait: PropagationWait | null = null,
+  autoComplete = false,
 ) {
   const [startResult, setStartResult] = useState<StartIssuanceResponse | null>(null);
   const [loadingStart, setLoadingStart] = useState(false);
@@ -22,7 +30,40 @@ export function useManagedIssuanceFlow(
   const [certificate, setCertificate] = useState<CertificateRecord | null>(null);
   const [awaitingManual, setAwaitingManual] = useState(false);
   const [finalizeFailed, setFinalizeFailed] = useState(false);
+  const [finalizeResumable, setFinalizeResumable] = useState(false);
+  const [progress, setProgress] = useState<IssuanceProgress | null>(null);
   const flowTokenRef = useRef(0);
+  const requestId = startResult?.request_id ?? null;
+
+  useEffect(() => {
+    if (!requestId) return;
+    const unlistenPromise = listenIssuanceProgress((event) => {
+      if (event.request_id === requestId) {
+        setProgress(event);
+      }
+    });
+    return () => {
+      void unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, [requestId]);
+
+  useEffect(() => {
+    if (!requestId || !autoComplete) return;
+    // The backend finishes the issuance once the records added by hand propagate
+    const unlistenPromise = listenAutoComplete((outcome) => {
+      if (outcome.request_id !== requestId) return;
+      setAwaitingManual(false);
+      if (outcome.certificate) {
+        setCertificate(outcome.certificate);
+      } else {
+        setFinalizeFailed(true);
+        setError(outcome.error);
+      }
+    });
+    return () => {
+      void unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, [requestId, autoComplete]);
 
   function nextFlowToken() {
     flowTokenRef.current += 1;
@@ -36,10 +77,12 @@ export function useManagedIssuanceFlow(
   async function finalizeIssuance(result: StartIssuanceResponse, token: number) {
     setFinalizing(true);
     setFinalizeFailed(false);
+    setFinalizeResumable(false);
     setError(null);
     try {
       const record = await completeManagedIssuance({
         request_id: result.request_id,
+        propagation_wait: propagationWait,
       });
       if (isStale(token)) {
         return;
@@ -50,6 +93,8 @@ export function useManagedIssuanceFlow(
         return;
       }
       setFinalizeFailed(true);
+      // Timeouts before the CSR is submitted keep the order; retrying resumes it.
+      setFinalizeResumable(getIssuanceTimeout(err)?.resumable ?? false);
       setError(normalizeError(err));
     } finally {
       if (!isStale(token)) {
@@ -65,23 +110,31 @@ export function useManagedIssuanceFlow(
     setCertificate(null);
     setAwaitingManual(false);
     setFinalizeFailed(false);
+    setFinalizeResumable(false);
+    setProgress(null);
     try {
       if (!selectedIssuerId) {
         throw new Error("Select an issuer before starting issuance.");
       }
-      const keyParams = keyOptionToParams(keyOption);
-      const result = await startManagedIssuance({
-        domains: parsedDomains,
-        issuer_id: selectedIssuerId,
-        ...keyParams,
-      });
+      // Promotions reuse the staging certificate's names and key parameters.
+      const result = promoteFrom
+        ? await promoteToProduction(promoteFrom, selectedIssuerId)
+        : await startManagedIssuance({
+          domains: parsedDomains,
+          issuer_id: selectedIssuerId,
+          ...keyOptionToParams(keyOption),
+          auto_complete: autoComplete,
+        });
       if (isStale(token)) {
         return;
       }
       setStartResult(result);
-      const hasManualRecords = result.dns_records.some((rec) => rec.adapter === "manual");
-      setAwaitingManual(hasManualRecords);
-      if (!hasManualRecords) {
+      // Missing acme-dns CNAMEs must be created before propagation can succeed
+      const needsUser = result.dns_records.some(
+        (rec) => rec.adapter === "manual" || rec.cname_target,
+      );
+      setAwaitingManual(needsUser);
+      if (!needsUser) {
         // For managed records, the backend handles DNS propagation checking
         await finalizeIssuance(result, token);
       }
@@ -89,7 +142,12 @@ export function useManagedIssuanceFlow(
       if (isStale(token)) {
         return;
       }
-      setError(normalizeError(err));
+      // Another session's challenge records sit at these names until it ends
+      setError(
+        getIssuanceInProgress(err)
+          ? `${normalizeError(err)}. Complete or wait for that issuance before starting another.`
+          : normalizeError(err),
+      );
       setStartResult(null);
     } finally {
       if (!isStale(token)) {
@@ -118,12 +176,15 @@ export function useManagedIssuanceFlow(
     setCertificate(null);
     setAwaitingManual(false);
     setFinalizeFailed(false);
+    setFinalizeResumable(false);
+    setProgress(null);
     setLoadingStart(false);
     setFinalizing(false);
   }
 
   const manualRecords = startResult?.dns_records.filter((rec) => rec.adapter === "manual") ?? [];
   const managedRecords = startResult?.dns_records.filter((rec) => rec.adapter !== "manual") ?? [];
+  const cnameRecords = startResult?.dns_records.filter((rec) => rec.cname_target) ?? [];
   const hasManual = manualRecords.length > 0;
   const hasManaged = managedRecords.length > 0;
   const dnsModeLabel = hasManual && hasManaged
@@ -140,11 +201,14 @@ export function useManagedIssuanceFlow(
     certificate,
     manualRecords,
     managedRecords,
+    cnameRecords,
     hasManual,
     hasManaged,
     dnsModeLabel,
     awaitingManual,
     finalizeFailed,
+    finalizeResumable,
+    progress,
     handleStart,
     continueIssuance,
     retryFinalization,
@@ -0,0 +1,47 @@
+import { open } from "@tauri-apps/plugin-dialog";
+import { useEffect, useState } from "react";
+import { normalizeError } from "../lib/errors";
+import {
+  getManagedKeyDirectory,
+  setManagedKeyDirectory,
+} from "../lib/secrets";
+
+export function useManagedKeyDirectory() {
+  const [directory, setDirectory] = useState<string | null>(null);
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getManagedKeyDirectory()
+      .then(setDirectory)
+      .catch((err) => setError(normalizeError(err)));
+  }, []);
+
+  async function save(next: string | null) {
+    setSaving(true);
+    setError(null);
+    try {
+      setDirectory(await setManagedKeyDirectory(next));
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  async function selectDirectory() {
+    const selection = await open({ directory: true, multiple: false });
+    const next = Array.isArray(selection) ? selection[0] : selection;
+    if (typeof next === "string" && next) {
+      await save(next);
+    }
+  }
+
+  return {
+    directory,
+    saving,
+    error,
+    selectDirectory,
+    resetToVault: () => save(null),
+  };
+}
@@ -0,0 +1,35 @@
+import { useEffect, useState } from "react";
+import { normalizeError } from "../lib/errors";
+import {
+  applyRecoveryAction,
+  listRecoveryActions,
+  type InterruptedJob,
+  type RecoveryAction,
+} from "../lib/recovery";
+
+export function useRecoveryActions() {
+  const [jobs, setJobs] = useState<InterruptedJob[]>([]);
+  const [pendingId, setPendingId] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    listRecoveryActions()
+      .then(setJobs)
+      .catch((err) => setError(normalizeError(err)));
+  }, []);
+
+  async function apply(jobId: string, action: RecoveryAction) {
+    setPendingId(jobId);
+    setError(null);
+    try {
+      await applyRecoveryAction(jobId, action);
+      setJobs((prev) => prev.filter((job) => job.id !== jobId));
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setPendingId(null);
+    }
+  }
+
+  return { jobs, pendingId, error, apply };
+}
@@ -0,0 +1,111 @@
+import { open } from "@tauri-apps/plugin-dialog";
+import { useEffect, useState } from "react";
+import { normalizeError } from "../lib/errors";
+import { getPreference, setPreference } from "../lib/preferences";
+import {
+  REPORT_FOLDER_PREFERENCE,
+  REPORT_FORMAT_PREFERENCE,
+  REPORT_LAST_RUN_PREFERENCE,
+  REPORT_SCHEDULE_PREFERENCE,
+  generateCertificateReport,
+  type CertificateReportResult,
+  type ReportFormat,
+  type ReportPeriod,
+} from "../lib/reports";
+
+export type ReportSchedule = ReportPeriod | "off";
+
+export function useReportSettings() {
+  const [schedule, setSchedule] = useState<ReportSchedule>("off");
+  const [format, setFormat] = useState<ReportFormat>("markdown");
+  const [folder, setFolder] = useState<string | null>(null);
+  const [lastRun, setLastRun] = useState<string | null>(null);
+  const [generating, setGenerating] = useState(false);
+  const [lastResult, setLastResult] = useState<CertificateReportResult | null>(
+    null,
+  );
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    void load();
+  }, []);
+
+  async function load() {
+    try {
+      const [savedSchedule, savedFormat, savedFolder, savedLastRun] =
+        await Promise.all([
+          getPreference(REPORT_SCHEDULE_PREFERENCE),
+          getPreference(REPORT_FORMAT_PREFERENCE),
+          getPreference(REPORT_FOLDER_PREFERENCE),
+          getPreference(REPORT_LAST_RUN_PREFERENCE),
+        ]);
+      const scheduleValue = savedSchedule?.value;
+      setSchedule(
+        scheduleValue === "weekly" || scheduleValue === "monthly"
+          ? scheduleValue
+          : "off",
+      );
+      setFormat(savedFormat?.value === "html" ? "html" : "markdown");
+      setFolder(savedFolder?.value || null);
+      setLastRun(savedLastRun?.value || null);
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  async function save(name: string, value: string) {
+    setError(null);
+    try {
+      await setPreference(name, value);
+    } catch (err) {
+      setError(normalizeError(err));
+    }
+  }
+
+  async function updateSchedule(next: ReportSchedule) {
+    setSchedule(next);
+    await save(REPORT_SCHEDULE_PREFERENCE, next);
+  }
+
+  async function updateFormat(next: ReportFormat) {
+    setFormat(next);
+    await save(REPORT_FORMAT_PREFERENCE, next);
+  }
+
+  async function selectFolder() {
+    const selection = await open({ directory: true, multiple: false });
+    const next = Array.isArray(selection) ? selection[0] : selection;
+    if (typeof next === "string" && next) {
+      setFolder(next);
+      await save(REPORT_FOLDER_PREFERENCE, next);
+    }
+  }
+
+  async function generateNow(period: ReportPeriod) {
+    setGenerating(true);
+    setError(null);
+    try {
+      const result = await generateCertificateReport({ period, format });
+      setLastResult(result);
+      setLastRun(result.period_end);
+    } catch (err) {
+      setError(normalizeError(err));
+    } finally {
+      setGenerating(false);
+    }
+  }
+
+  return {
+    schedule,
+    format,
+    folder,
+    lastRun,
+    generating,
+    lastResult,
+    error,
+    updateSchedule,
+    updateFormat,
+    selectFolder,
+    generateNow,
+  };
+}
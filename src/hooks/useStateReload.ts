@@ -0,0 +1,29 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import { listenStateReloaded } from "../lib/state";
+
+/**
+ * Counts state reloads announced by the backend. Keying the routed pages on
+ * the count remounts them, so every window fetches the restored data.
+ */
+export function useStateReload(): number {
+  const [generation, setGeneration] = useState(0);
+
+  useEffect(() => {
+    const unlisten = listenStateReloaded((reloaded) => {
+      setGeneration((current) => current + 1);
+      if (reloaded.abandoned_issuances > 0) {
+        toast.warning(
+          `Data reloaded; ${reloaded.abandoned_issuances} issuance(s) in progress were cancelled.`,
+        );
+      } else {
+        toast.info("Data reloaded.");
+      }
+    });
+    return () => {
+      void unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  return generation;
+}
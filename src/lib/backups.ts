@@ -0,0 +1,37 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type BackupSchedule = "off" | "daily" | "weekly";
+
+export const BACKUP_SCHEDULE_PREFERENCE = "backup_schedule";
+export const BACKUP_FOLDER_PREFERENCE = "backup_folder";
+export const BACKUP_RETAIN_PREFERENCE = "backup_retain";
+export const BACKUP_LAST_RUN_PREFERENCE = "backup_last_created_at";
+export const BACKUP_CREATED_EVENT = "backup-created";
+export const DEFAULT_BACKUP_RETAIN = 7;
+
+export type BackupResult = {
+  path: string;
+  created_at: string;
+  removed: string[];
+};
+
+export type BackupVerification = {
+  path: string;
+  valid: boolean;
+  schema_version: number;
+  supported_schema_version: number;
+  integrity_ok: boolean;
+  certificates: number;
+  secrets_in_backup: number;
+  secrets_decryptable: number;
+  secrets_external: number;
+  problems: string[];
+};
+
+export async function createBackup(folder?: string | null): Promise<BackupResult> {
+  return invoke<BackupResult>("create_backup", { folder: folder ?? null });
+}
+
+export async function verifyBackup(path: string): Promise<BackupVerification> {
+  return invoke<BackupVerification>("verify_backup", { path });
+}
@@ -3,6 +3,25 @@ import { invoke } from "@tauri-apps/api/core";
 export type CertificateSource = "External" | "Managed";
 export type KeyAlgorithm = "rsa" | "ecdsa";
 export type KeyCurve = "p256" | "p384";
+export type CertificateStatus =
+  | "active"
+  | "expiring_soon"
+  | "expired"
+  | "revoked"
+  | "superseded"
+  | "pending_renewal";
+
+/** A name as stored (punycode) and as shown to users. */
+export type DomainForms = {
+  ascii: string;
+  unicode: string;
+};
+
+/** The punycode form of a displayed name, when it differs. */
+export function punycodeOf(forms: DomainForms[] | undefined, name: string): string | undefined {
+  const match = forms?.find((form) => form.unicode === name || form.ascii === name);
+  return match && match.ascii !== match.unicode ? match.ascii : undefined;
+}
 
 export type CertificateRecord = {
   id: string;
@@ -18,22 +37,64 @@ export type CertificateRecord = {
   tags: string[];
   managed_key_ref?: string | null;
   chain_pem?: string | null;
+  chain_name?: string | null;
   key_algorithm?: KeyAlgorithm | null;
   key_size?: number | null;
   key_curve?: KeyCurve | null;
+  status: CertificateStatus;
+  /** Issuer configuration that issued a managed certificate */
+  issuer_id?: string | null;
+  /** Staging certificate this one was promoted from */
+  promoted_from?: string | null;
+  /** Fixes applied to chain_pem when it was stored */
+  chain_repairs?: ChainRepair[];
+  /** ASCII and Unicode forms of every SAN and domain root */
+  name_forms?: DomainForms[];
 };
 
+export type ChainRepair =
+  | "reordered"
+  | "duplicate_removed"
+  | "root_removed"
+  | "unrelated_removed"
+  | "intermediate_fetched";
+
 export type ExportBundle = "cert" | "chain" | "fullchain";
 
 export type ExportCertificateRequest = {
   certificateId: string;
   destinationDir: string;
   folderName: string;
+  fileNameTemplate?: string;
   includePrivateKey: boolean;
+  /** Encrypts the exported private key with this passphrase */
+  keyPassphrase?: string;
   bundle: ExportBundle;
   overwrite: boolean;
 };
 
+export const KEY_EXPORT_POLICY_PREFERENCE = "key_export_policy";
+
+export type KeyExportPolicy = "allowed" | "encrypted_only" | "forbidden";
+
+/** Unknown values count as forbidden, matching the backend. */
+export function parseKeyExportPolicy(value: string | undefined): KeyExportPolicy {
+  if (!value || value === "allowed") {
+    return "allowed";
+  }
+  return value === "encrypted_only" ? "encrypted_only" : "forbidden";
+}
+
+/** Relaxing the policy needs the confirmation PIN when one is set. */
+export async function setKeyExportPolicy(
+  policy: KeyExportPolicy,
+  confirmationPin?: string,
+): Promise<KeyExportPolicy> {
+  return invoke<KeyExportPolicy>("set_key_export_policy", {
+    setReq: { policy, confirmation_pin: confirmationPin },
+  });
+}
+
 export type ExportedFile = {
   label: string;
   path: string;
@@ -51,6 +112,24 @@ export type ExportCertificateResponse =
       existing_files: string[];
     };
 
+/** Encoding an imported certificate file was read from. */
+export type CertificateFormat = "pem" | "der" | "pkcs7";
+
+export type CertificateImport = {
+  record: CertificateRecord;
+  format: CertificateFormat;
+};
+
+/**
+ * Adds a PEM bundle, DER or PKCS#7 file to the inventory. UTF-16 text and
+ * CRLF line endings are accepted; the chain is stored as clean PEM.
+ */
+export async function importCertificate(contents: Uint8Array): Promise<CertificateImport> {
+  return invoke<CertificateImport>("import_certificate", {
+    contents: Array.from(contents),
+  });
+}
+
 export async function listCertificates(): Promise<CertificateRecord[]> {
   return invoke<CertificateRecord[]>("list_certificates");
 }
@@ -61,6 +140,11 @@ export async function getCertificate(
   return invoke<CertificateRecord>("get_certificate", { id });
 }
 
+/** The certificate chain rendered like `openssl x509 -text`, leaf first. */
+export async function getCertificateText(id: string): Promise<string> {
+  return invoke<string>("get_certificate_text", { id });
+}
+
 export async function exportCertificatePem(
   exportReq: ExportCertificateRequest,
 ): Promise<ExportCertificateResponse> {
@@ -69,9 +153,145 @@ export async function exportCertificatePem(
       certificate_id: exportReq.certificateId,
       destination_dir: exportReq.destinationDir,
       folder_name: exportReq.folderName,
+      file_name_template: exportReq.fileNameTemplate ?? null,
       include_private_key: exportReq.includePrivateKey,
+      key_passphrase: exportReq.keyPassphrase ?? null,
       bundle: exportReq.bundle,
       overwrite: exportReq.overwrite,
     },
   });
 }
+
+export type ExportProfile = {
+  folderNameTemplate?: string;
+  fileNameTemplate?: string;
+  bundle: ExportBundle;
+  includePrivateKey?: boolean;
+  overwrite?: boolean;
+};
+
+export type BulkExportFilter = {
+  tag?: string;
+  expiringWithinDays?: number;
+  statuses?: CertificateStatus[];
+};
+
+export type BulkExportRequest = {
+  destinationDir: string;
+  filter: BulkExportFilter;
+  profile: ExportProfile;
+  keyPassphrase?: string;
+};
+
+export type BulkExportReport = {
+  exported: {
+    certificate_id: string;
+    domain: string;
+    output_dir: string;
+    files: ExportedFile[];
+  }[];
+  skipped: {
+    certificate_id: string;
+    domain: string;
+    reason: string;
+  }[];
+};
+
+export async function exportCertificatesBulk(
+  bulkReq: BulkExportRequest,
+): Promise<BulkExportReport> {
+  return invoke<BulkExportReport>("export_certificates_bulk", {
+    bulkReq: {
+      destination_dir: bulkReq.destinationDir,
+      filter: {
+        tag: bulkReq.filter.tag ?? null,
+        expiring_within_days: bulkReq.filter.expiringWithinDays ?? null,
+        statuses: bulkReq.filter.statuses ?? [],
+      },
+      profile: {
+        folder_name_template: bulkReq.profile.folderNameTemplate ?? "{domain}",
+        file_name_template: bulkReq.profile.fileNameTemplate ?? null,
+        bundle: bulkReq.profile.bundle,
+        include_private_key: bulkReq.profile.includePrivateKey ?? false,
+        overwrite: bulkReq.profile.overwrite ?? false,
+      },
+      key_passphrase: bulkReq.keyPassphrase ?? null,
+    },
+  });
+}
+
+export type ExportVerification = {
+  path: string;
+  fingerprint: string;
+  certificate_id?: string | null;
+  status?: CertificateStatus | null;
+  matches_inventory: boolean;
+  chain_complete: boolean;
+  key_matches?: boolean | null;
+  issues: string[];
+};
+
+export async function verifyExport(path: string): Promise<ExportVerification> {
+  return invoke<ExportVerification>("verify_export", { path });
+}
+
+export type CertificateQrCode = {
+  payload: string;
+  png: number[];
+};
+
+export async function certificateVerificationQr(
+  id: string,
+): Promise<CertificateQrCode> {
+  return invoke<CertificateQrCode>("certificate_verification_qr", { id });
+}
+
+export type KeyEscrowRecipient = {
+  label: string;
+  passphrase: string;
+};
+
+export type KeyEscrowRequest = {
+  certificateId: string;
+  destinationDir: string;
+  folderName: string;
+  threshold: number;
+  recipients: KeyEscrowRecipient[];
+};
+
+export type KeyEscrowResponse = {
+  output_dir: string;
+  threshold: number;
+  files: ExportedFile[];
+};
+
+export async function exportKeyEscrow(
+  escrowReq: KeyEscrowRequest,
+): Promise<KeyEscrowResponse> {
+  return invoke<KeyEscrowResponse>("export_key_escrow", {
+    escrowReq: {
+      certificate_id: escrowReq.certificateId,
+      destination_dir: escrowReq.destinationDir,
+      folder_name: escrowReq.folderName,
+      threshold: escrowReq.threshold,
+      recipients: escrowReq.recipients,
+    },
+  });
+}
+
+export async function recoverEscrowedKey(recoverReq: {
+  shares: { path: string; passphrase: string }[];
+  destinationDir: string;
+  folderName: string;
+  /** Encrypts the recovered key; required under the encrypted-only policy */
+  keyPassphrase?: string;
+}): Promise<ExportedFile> {
+  return invoke<ExportedFile>("recover_escrowed_key", {
+    recoverReq: {
+      shares: recoverReq.shares,
+      destination_dir: recoverReq.destinationDir,
+      folder_name: recoverReq.folderName,
+      key_passphrase: recoverReq.keyPassphrase,
+    },
+  });
+}
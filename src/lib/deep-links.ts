@@ -0,0 +1,21 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+export const DEEP_LINK_EVENT = "deep-link-action";
+
+export type DeepLinkAction =
+  | { action: "renew"; certificate_id: string; domains: string[] }
+  | { action: "check"; domain: string; certificate_ids: string[] }
+  | { action: "invalid"; url: string; message: string };
+
+export async function takePendingDeepLink(): Promise<DeepLinkAction | null> {
+  return invoke<DeepLinkAction | null>("take_pending_deep_link");
+}
+
+export function listenDeepLinkActions(
+  handler: (action: DeepLinkAction) => void,
+): Promise<UnlistenFn> {
+  return listen<DeepLinkAction>(DEEP_LINK_EVENT, (event) => {
+    handler(event.payload);
+  });
+}
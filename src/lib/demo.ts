@@ -0,0 +1,18 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type DemoModeStatus = {
+  enabled: boolean;
+  certificates: number;
+  issuers: number;
+  providers: number;
+};
+
+export async function getDemoMode(): Promise<DemoModeStatus> {
+  return invoke<DemoModeStatus>("get_demo_mode");
+}
+
+export async function setDemoMode(enabled: boolean): Promise<DemoModeStatus> {
+  return invoke<DemoModeStatus>("set_demo_mode", {
+    demoReq: { enabled },
+  });
+}
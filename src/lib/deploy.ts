@@ -0,0 +1,119 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type DeployTargetType =
+  | "s3"
+  | "digitalocean"
+  | "nginx_proxy_manager"
+  | "traefik"
+  | "iis";
+
+export type S3Encryption = "none" | "aes256" | "aws_kms";
+
+export type S3TargetConfig = {
+  endpoint?: string | null;
+  region: string;
+  bucket: string;
+  prefix?: string;
+  path_style?: boolean;
+  encryption?: S3Encryption;
+  kms_key_id?: string | null;
+  include_private_key?: boolean;
+};
+
+export type DigitalOceanTargetConfig = {
+  dns_provider_id?: string | null;
+  load_balancer_id?: string | null;
+  app_id?: string | null;
+  certificate_name?: string;
+};
+
+export type NpmTargetConfig = {
+  data_dir: string;
+  certificate_id: number;
+};
+
+export type TraefikTargetConfig = {
+  cert_dir: string;
+  dynamic_config_dir: string;
+  container_cert_dir?: string | null;
+  file_name?: string;
+};
+
+export type IisTargetConfig = {
+  site_name?: string | null;
+  ip?: string;
+  port?: number;
+  hostname?: string | null;
+};
+
+export type DigitalOceanCredentials = {
+  api_token: string;
+};
+
+export type S3Credentials = {
+  access_key_id: string;
+  secret_access_key: string;
+};
+
+export type DeployTargetRecord = {
+  id: string;
+  target_type: DeployTargetType;
+  label: string;
+  config: Record<string, unknown>;
+  has_credentials: boolean;
+  created_at: string;
+  updated_at: string;
+};
+
+export type CreateDeployTargetRequest = {
+  target_type: DeployTargetType;
+  label: string;
+  config: Record<string, unknown>;
+  credentials?: Record<string, unknown> | null;
+};
+
+export type UpdateDeployTargetRequest = {
+  target_id: string;
+  label: string;
+  config: Record<string, unknown>;
+  credentials?: Record<string, unknown> | null;
+};
+
+export type DeployResult = {
+  target_id: string;
+  certificate_id: string;
+  locations: string[];
+  deployed_at: string;
+};
+
+export async function listDeployTargets(): Promise<DeployTargetRecord[]> {
+  return invoke("deploy_target_list");
+}
+
+export async function createDeployTarget(
+  req: CreateDeployTargetRequest,
+): Promise<DeployTargetRecord> {
+  return invoke("deploy_target_create", { createReq: req });
+}
+
+export async function updateDeployTarget(
+  req: UpdateDeployTargetRequest,
+): Promise<DeployTargetRecord> {
+  return invoke("deploy_target_update", { updateReq: req });
+}
+
+export async function deleteDeployTarget(
+  targetId: string,
+  confirmationPin?: string,
+): Promise<void> {
+  return invoke("deploy_target_delete", { targetId, confirmationPin });
+}
+
+export async function deployCertificate(
+  targetId: string,
+  certificateId: string,
+): Promise<DeployResult> {
+  return invoke("deploy_certificate", {
+    deployReq: { target_id: targetId, certificate_id: certificateId },
+  });
+}
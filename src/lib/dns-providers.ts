@@ -1,20 +1,55 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { DomainForms } from "./certificates";
 import type { PropagationResult } from "./dns";
+import type { ConflictResolution } from "./errors";
+import { setPreference } from "./preferences";
 
 export type DnsProviderType =
+  | "acme_dns"
   | "cloudflare"
   | "digitalocean"
+  | "desec"
+  | "powerdns"
+  | "rfc2136"
   | "route53"
+  | "script"
   | "manual";
 
 export type DnsProviderRecord = {
   id: string;
   provider_type: DnsProviderType;
   label: string;
+  /** Unicode form, for display */
   domain_suffixes: string[];
+  /** ASCII and Unicode forms of each suffix */
+  suffix_forms?: DomainForms[];
   config?: Record<string, unknown> | null;
+  /** Higher wins when providers match a hostname with equally long suffixes */
+  priority: number;
   created_at: string;
   updated_at: string;
+  verification?: DomainSuffixVerification[] | null;
+  suffix_warnings?: SuffixShadowWarning[];
+};
+
+export type SuffixShadowWarning = {
+  suffix: string;
+  kind: "shadows" | "shadowed_by";
+  existing_suffix: string;
+  provider_id: string;
+  provider_label: string;
+  message: string;
+};
+
+export type DomainSuffixVerification = {
+  suffix: string;
+  zone?: string | null;
+  nameservers: string[];
+  delegated?: boolean | null;
+  zone_accessible?: boolean | null;
+  missing_scopes?: string[];
+  issues: string[];
 };
 
 export type DnsProviderResolution = {
@@ -31,6 +66,8 @@ export type CreateDnsProviderRequest = {
   route53_access_key?: string;
   route53_secret_key?: string;
   config?: Record<string, unknown> | null;
+  on_conflict?: ConflictResolution | null;
+  verify_suffixes?: boolean;
 };
 
 export type UpdateDnsProviderRequest = {
@@ -43,6 +80,12 @@ export type UpdateDnsProviderRequest = {
   config?: Record<string, unknown> | null;
 };
 
+export type DiscoveredZone = {
+  name: string;
+  configured: boolean;
+  claimed_by?: string | null;
+};
+
 export type DnsProviderErrorCategory =
   | "auth_error"
   | "not_found"
@@ -50,6 +93,15 @@ export type DnsProviderErrorCategory =
   | "network_error"
   | "unknown";
 
+export type ProviderTestMode = "full" | "credentials_only" | "dry_run";
+
+/** A TXT record change a dry run logged instead of sending. */
+export type PlannedRecordOperation = {
+  operation: "create" | "delete";
+  record_name: string;
+  value?: string | null;
+};
+
 export type DnsProviderTestResult = {
   success: boolean;
   record_name?: string | null;
@@ -62,6 +114,28 @@ export type DnsProviderTestResult = {
   create_ms?: number | null;
   propagation_ms?: number | null;
   cleanup_ms?: number | null;
+  mode?: ProviderTestMode;
+  /** Zones the credentials can see; credentials-only tests */
+  zones?: string[] | null;
+  /** Whether write permission was confirmed without a real change */
+  write_access?: "verified" | "not_supported" | null;
+  /** Record changes a dry-run test would have made */
+  planned_operations?: PlannedRecordOperation[] | null;
+};
+
+export type LeftoverRecordKind = "provider_test" | "stale_challenge";
+
+export type LeftoverTxtRecord = {
+  record_name: string;
+  values: string[];
+  kind: LeftoverRecordKind;
+};
+
+export type TestRecordCleanup = {
+  dry_run: boolean;
+  records: LeftoverTxtRecord[];
+  deleted: string[];
+  failed: string[];
 };
 
 export type DnsProviderTokenValidationResult = {
@@ -77,6 +151,46 @@ export type ValidateDnsProviderTokenRequest = {
   route53_secret_key?: string;
 };
 
+export type DnsCredentialField =
+  | "api_token"
+  | "route53_access_key"
+  | "route53_secret_key";
+
+export type DnsCredentialPlaceholder = {
+  field: DnsCredentialField;
+  required: boolean;
+};
+
+/** A provider from an export file: its settings without credentials. */
+export type DnsProviderImportCandidate = {
+  provider_type: DnsProviderType;
+  label: string;
+  domain_suffixes: string[];
+  config?: Record<string, unknown> | null;
+  credentials: DnsCredentialPlaceholder[];
+  /** Present when the file was exported with a passphrase */
+  sealed_credentials?: unknown;
+  existing_label?: string | null;
+};
+
+export type DnsProviderExportResult = {
+  path: string;
+  exported: number;
+  credentials_included: boolean;
+};
+
+export type DnsProviderFileImport = {
+  provider_type: DnsProviderType;
+  label: string;
+  provider_id?: string | null;
+  skipped_reason?: string | null;
+};
+
+export type DnsProviderFileImportResult = {
+  imported: DnsProviderFileImport[];
+  skipped: DnsProviderFileImport[];
+};
+
 export async function listDnsProviders(): Promise<DnsProviderRecord[]> {
   return invoke("dns_provider_list");
 }
@@ -93,16 +207,49 @@ export async function updateDnsProvider(
   return invoke("dns_provider_update", { updateReq: req });
 }
 
-export async function deleteDnsProvider(providerId: string): Promise<string> {
+export async function deleteDnsProvider(
+  providerId: string,
+  confirmationPin?: string,
+): Promise<string> {
   return invoke("dns_provider_delete", {
-    deleteReq: { provider_id: providerId },
+    deleteReq: { provider_id: providerId, confirmation_pin: confirmationPin },
   });
 }
 
+export async function setDnsProviderPriority(
+  providerId: string,
+  priority: number,
+): Promise<DnsProviderRecord> {
+  return invoke<DnsProviderRecord>("dns_provider_set_priority", {
+    priorityReq: { provider_id: providerId, priority },
+  });
+}
+
+/**
+ * `credentials_only` checks access without creating or deleting records;
+ * `dry_run` also lists the test record changes it would have made.
+ */
 export async function testDnsProvider(
   providerId: string,
+  mode: ProviderTestMode = "full",
 ): Promise<DnsProviderTestResult> {
-  return invoke("dns_provider_test", { testReq: { provider_id: providerId } });
+  return invoke("dns_provider_test", { testReq: { provider_id: providerId, mode } });
+}
+
+/** Lists leftover test and challenge TXT records; deletes them unless `dryRun`. */
+export async function cleanupProviderTestRecords(
+  providerId: string,
+  dryRun: boolean,
+): Promise<TestRecordCleanup> {
+  return invoke("cleanup_provider_test_records", { providerId, dryRun });
+}
+
+/**
+ * Account the provider's credentials belong to, as its API names it; null
+ * for provider types whose API does not say.
+ */
+export async function getDnsProviderAccount(providerId: string): Promise<string | null> {
+  return invoke("dns_provider_account", { providerId });
 }
 
 export async function resolveDnsProvider(
@@ -110,3 +257,150 @@ export async function resolveDnsProvider(
 ): Promise<DnsProviderResolution> {
   return invoke("dns_resolve_provider", { resolveReq: { hostname } });
 }
+
+export async function discoverProviderZones(
+  providerId: string,
+): Promise<DiscoveredZone[]> {
+  return invoke("discover_provider_zones", {
+    discoverReq: { provider_id: providerId },
+  });
+}
+
+export const PROPAGATION_WAIT_PREFERENCE = "propagation_wait_overrides";
+/** Test record prefix and TTL used by providers that set none. */
+export const TEST_RECORD_PREFIX_PREFERENCE = "dns_test_record_prefix";
+export const TEST_RECORD_TTL_PREFERENCE = "dns_test_record_ttl";
+
+export type PropagationWait = {
+  timeout_secs: number;
+  interval_secs: number;
+};
+
+export type PropagationPreset = {
+  provider_type: DnsProviderType;
+  preset: PropagationWait;
+  /** Wait used during issuance and tests, after the user's override */
+  effective: PropagationWait;
+  overridden: boolean;
+};
+
+export async function getPropagationPresets(): Promise<PropagationPreset[]> {
+  return invoke("dns_propagation_presets");
+}
+
+/** Saves the overrides, keyed by provider type; omitted types use their preset. */
+export async function setPropagationOverrides(
+  overrides: Partial<Record<DnsProviderType, PropagationWait>>,
+): Promise<void> {
+  await setPreference(PROPAGATION_WAIT_PREFERENCE, JSON.stringify(overrides));
+}
+
+export type ProviderDebugEntry = {
+  captured_at: string;
+  method: string;
+  url: string;
+  request_body?: string | null;
+  status?: number | null;
+  response_body?: string | null;
+  error?: string | null;
+  elapsed_ms: number;
+};
+
+export async function getProviderDebugLog(
+  providerId: string,
+): Promise<ProviderDebugEntry[]> {
+  return invoke("get_provider_debug_log", { providerId });
+}
+
+export async function clearProviderDebugLog(providerId: string): Promise<void> {
+  return invoke("clear_provider_debug_log", { providerId });
+}
+
+/** With a passphrase, credentials are included encrypted to it. */
+export async function exportDnsProviders(
+  path: string,
+  providerIds: string[] = [],
+  passphrase?: string,
+): Promise<DnsProviderExportResult> {
+  return invoke("dns_provider_export", {
+    exportReq: { path, provider_ids: providerIds, passphrase: passphrase || null },
+  });
+}
+
+/** Creates the providers of an export file that need no further input. */
+export async function importDnsProviders(
+  path: string,
+  passphrase?: string,
+): Promise<DnsProviderFileImportResult> {
+  return invoke("dns_provider_import", {
+    importReq: { path, passphrase: passphrase || null },
+  });
+}
+
+export type EnvProviderImport = {
+  provider_type: DnsProviderType;
+  label: string;
+  source_keys: string[];
+  provider_id?: string | null;
+  domain_suffixes: string[];
+  skipped_reason?: string | null;
+};
+
+export type EnvProviderImportResult = {
+  imported: EnvProviderImport[];
+  skipped: EnvProviderImport[];
+};
+
+/**
+ * Creates providers from a lego `.env` file or acme.sh `account.conf`.
+ * Without `domainSuffixes`, each provider gets its unclaimed zones.
+ */
+export async function importDnsProvidersFromEnv(
+  path: string,
+  domainSuffixes?: string,
+): Promise<EnvProviderImportResult> {
+  return invoke("import_dns_providers_from_env", {
+    importReq: { path, domain_suffixes: domainSuffixes ?? null },
+  });
+}
+
+export async function previewDnsProviderImport(
+  path: string,
+): Promise<DnsProviderImportCandidate[]> {
+  return invoke("preview_dns_provider_import", { path });
+}
+
+export const HEALTH_INTERVAL_PREFERENCE = "provider_health_interval_hours";
+export const HEALTH_CHECK_MODE_PREFERENCE = "provider_health_check_mode";
+export const HEALTH_FAILED_EVENT = "provider-health-failed";
+
+export type ProviderHealthCheckKind = "credentials" | "full_test";
+
+export type DnsProviderHealthCheck = {
+  provider_id: string;
+  checked_at: string;
+  kind: ProviderHealthCheckKind;
+  success: boolean;
+  error?: string | null;
+  error_category?: DnsProviderErrorCategory | null;
+  elapsed_ms: number;
+};
+
+/** Scheduled health checks, newest first; all providers when none is given. */
+export async function getDnsProviderHealthHistory(
+  providerId?: string,
+  limit?: number,
+): Promise<DnsProviderHealthCheck[]> {
+  return invoke("dns_provider_health_history", {
+    providerId: providerId ?? null,
+    limit: limit ?? null,
+  });
+}
+
+export function listenProviderHealthFailed(
+  handler: (check: DnsProviderHealthCheck) => void,
+): Promise<UnlistenFn> {
+  return listen<DnsProviderHealthCheck>(HEALTH_FAILED_EVENT, (event) => {
+    handler(event.payload);
+  });
+}
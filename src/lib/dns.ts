@@ -1,3 +1,7 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { localizeMessage, type MessageCode } from "./messages";
+
 export type PropagationState =
   | "pending"
   | "found"
@@ -5,8 +9,147 @@ export type PropagationState =
   | "wrong_content"
   | "error";
 
+/** What one propagation resolver saw. */
+export type ResolverOutcome = {
+  resolver: string;
+  state: PropagationState;
+  observed_values: string[];
+  rtt_ms: number;
+  /** Why the query failed; `state` is then `error` */
+  error: string | null;
+};
+
 export type PropagationResult = {
   state: PropagationState;
   reason?: string;
+  reason_code?: MessageCode;
   observed_values: string[];
+  /** Per-resolver outcomes of a public check */
+  resolvers?: ResolverOutcome[];
+};
+
+/** Why the record is not visible yet, in the active locale. */
+export function propagationReason(
+  result: PropagationResult | null | undefined,
+): string | undefined {
+  if (!result?.reason) return undefined;
+  return result.reason_code
+    ? localizeMessage(result.reason_code, result.reason)
+    : result.reason;
+}
+
+/** "3/4 resolvers see the record", or undefined without per-resolver outcomes. */
+export function resolverConsensus(
+  result: PropagationResult | null | undefined,
+): string | undefined {
+  const resolvers = result?.resolvers;
+  if (!resolvers?.length) return undefined;
+  const found = resolvers.filter((outcome) => outcome.state === "found").length;
+  const noun = resolvers.length === 1 ? "resolver sees" : "resolvers see";
+  return `${found}/${resolvers.length} ${noun} the record`;
+}
+
+export type PropagationWatchOutcome = "found" | "timed_out" | "cancelled";
+
+/** Public resolvers, or the zone's authoritative nameservers queried directly. */
+/** `serial_sync` is `authoritative` plus a comparison of zone SOA serials */
+export type PropagationCheckMode = "public" | "authoritative" | "serial_sync";
+
+export type NameserverCheck = {
+  nameserver: string;
+  address: string | null;
+  transport: "udp" | "tcp" | null;
+  result: PropagationResult;
+  /** Zone SOA serial served, when serials are compared */
+  soa_serial?: number;
+};
+
+export type SerialSync = {
+  /** Oldest serial among the nameservers already serving the record */
+  target_serial: number | null;
+  /** Nameservers still behind `target_serial` */
+  lagging: string[];
+  in_sync: boolean;
 };
+
+export type AuthoritativePropagationResult = {
+  /** Name actually queried, after following a CNAME */
+  record_name: string;
+  zone: string;
+  nameservers: NameserverCheck[];
+  all_found: boolean;
+  serial_sync?: SerialSync;
+};
+
+export type DnsPropagationUpdate = {
+  watch_id: string;
+  record_name: string;
+  result: PropagationResult;
+  attempt: number;
+  elapsed_secs: number;
+  /** Set on the last update of a watch */
+  outcome?: PropagationWatchOutcome;
+  /** Per-nameserver results of an authoritative watch */
+  nameservers?: NameserverCheck[];
+};
+
+export const DNS_PROPAGATION_EVENT = "dns-propagation";
+
+/**
+ * Comma-separated resolvers for public propagation checks: `google`,
+ * `cloudflare`, `https://` DoH URLs or DNS server IPs. Empty means Google
+ * and Cloudflare.
+ */
+export const DNS_RESOLVERS_PREFERENCE = "dns_propagation_resolvers";
+
+const IPV4_PATTERN = /^\d{1,3}(\.\d{1,3}){3}(:\d+)?$/;
+const IPV6_PATTERN = /^(\[[0-9a-f:]+\](:\d+)?|[0-9a-f]*:[0-9a-f]*:[0-9a-f:]*)$/i;
+
+/** Entries of a resolver list the backend would reject. */
+export function invalidResolverEntries(raw: string): string[] {
+  return raw
+    .split(/[\s,]+/)
+    .filter(Boolean)
+    .filter((entry) => {
+      const lower = entry.toLowerCase();
+      if (lower === "google" || lower === "cloudflare") return false;
+      if (lower.startsWith("https://")) return lower.length <= "https://".length;
+      return !IPV4_PATTERN.test(entry) && !IPV6_PATTERN.test(entry);
+    });
+}
+
+/** Polls DNS for a TXT record in the background; returns the watch id. */
+export async function watchDnsPropagation(
+  recordName: string,
+  value: string,
+  providerType?: string,
+  mode: PropagationCheckMode = "public",
+): Promise<string> {
+  return invoke<string>("watch_dns_propagation", {
+    watchReq: { record_name: recordName, value, provider_type: providerType, mode },
+  });
+}
+
+export async function checkAuthoritativePropagation(
+  recordName: string,
+  value: string,
+  compareSerials = false,
+): Promise<AuthoritativePropagationResult> {
+  return invoke<AuthoritativePropagationResult>("check_authoritative_propagation", {
+    recordName,
+    value,
+    compareSerials,
+  });
+}
+
+export async function cancelDnsPropagationWatch(watchId: string): Promise<boolean> {
+  return invoke<boolean>("cancel_dns_propagation_watch", { watchId });
+}
+
+export function listenDnsPropagation(
+  handler: (update: DnsPropagationUpdate) => void,
+): Promise<UnlistenFn> {
+  return listen<DnsPropagationUpdate>(DNS_PROPAGATION_EVENT, (event) => {
+    handler(event.payload);
+  });
+}
@@ -1,11 +1,134 @@
 import { toast } from "sonner";
+import type { DomainIssue, FinalizationStage } from "./issuance";
+import { localizeMessage, type MessageCode } from "./messages";
+
+export type ConflictResolution = "create_anyway" | "merge";
+
+export type DuplicateConflict = {
+  entity: "dns_provider" | "issuer";
+  existing_id: string;
+  existing_label: string;
+  overlapping: string[];
+  can_merge: boolean;
+};
+
+export type CommandError = {
+  code:
+    | "permission_denied"
+    | "pin_required"
+    | "conflict"
+    | "timed_out"
+    | "invalid_domains"
+    | "issuance_in_progress"
+    | "failed";
+  message: string;
+  /** Set when `message` comes from the backend message catalog. */
+  message_code?: MessageCode;
+  conflicts?: DuplicateConflict[];
+  /**
+   * Set on `timed_out` errors from issuance finalization, and on
+   * `issuance_in_progress` errors to the session holding the names.
+   */
+  request_id?: string;
+  stage?: FinalizationStage;
+  resumable?: boolean;
+  /** Set on `invalid_domains` errors, one per malformed name. */
+  issues?: DomainIssue[];
+  /** Set on `issuance_in_progress` errors: names another session holds. */
+  domains?: string[];
+};
+
+function isCommandError(err: unknown): err is CommandError {
+  return (
+    typeof err === "object" &&
+    err !== null &&
+    typeof (err as CommandError).code === "string" &&
+    typeof (err as CommandError).message === "string"
+  );
+}
 
 export function normalizeError(err: unknown, fallback = "Unexpected error") {
   if (err instanceof Error) return err.message;
   if (typeof err === "string") return err;
+  if (isCommandError(err)) {
+    return err.message_code
+      ? localizeMessage(err.message_code, err.message)
+      : err.message;
+  }
   return fallback;
 }
 
+export function isPermissionDenied(err: unknown): boolean {
+  return isCommandError(err) && err.code === "permission_denied";
+}
+
+export function isPinRequired(err: unknown): boolean {
+  return isCommandError(err) && err.code === "pin_required";
+}
+
+/**
+ * Runs a destructive command, asking for the confirmation PIN and retrying
+ * while the backend reports one is needed. Rethrows if the user backs out.
+ */
+export async function withConfirmationPin<T>(
+  run: (pin?: string) => Promise<T>,
+): Promise<T> {
+  let pin: string | undefined;
+  for (;;) {
+    try {
+      return await run(pin);
+    } catch (err) {
+      if (!isPinRequired(err)) throw err;
+      const entered = window.prompt(normalizeError(err));
+      if (!entered) throw err;
+      pin = entered;
+    }
+  }
+}
+
+export function getConflicts(err: unknown): DuplicateConflict[] | null {
+  if (!isCommandError(err) || err.code !== "conflict") return null;
+  return err.conflicts ?? [];
+}
+
+export function getIssuanceTimeout(
+  err: unknown,
+): { stage: FinalizationStage; resumable: boolean } | null {
+  if (!isCommandError(err) || err.code !== "timed_out" || !err.stage) return null;
+  return { stage: err.stage, resumable: err.resumable ?? false };
+}
+
+export function getIssuanceInProgress(
+  err: unknown,
+): { domains: string[]; requestId: string | null } | null {
+  if (!isCommandError(err) || err.code !== "issuance_in_progress") return null;
+  return { domains: err.domains ?? [], requestId: err.request_id ?? null };
+}
+
+/**
+ * Asks how to proceed with a create request that overlaps existing records.
+ * Returns null when the error is not a conflict or the user backs out.
+ */
+export function chooseConflictResolution(
+  err: unknown,
+): ConflictResolution | null {
+  const conflicts = getConflicts(err);
+  if (!conflicts) return null;
+  const mergeTarget = conflicts.find((conflict) => conflict.can_merge);
+  if (
+    mergeTarget &&
+    window.confirm(
+      `${normalizeError(err)}\n\nMerge into "${mergeTarget.existing_label}"?`,
+    )
+  ) {
+    return "merge";
+  }
+  if (window.confirm("Create a separate entry anyway?")) {
+    return "create_anyway";
+  }
+  return null;
+}
+
 const VAULT_ERROR_HINTS = ["vault", "keyring", "biometric", "credential"];
 
 export function maybeToastVaultUnlockError(message: string) {
@@ -1,5 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { CertificateRecord } from "./certificates";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { CertificateRecord, DomainForms } from "./certificates";
+import type { PropagationWait } from "./dns-providers";
 
 export type KeyAlgorithm = "rsa" | "ecdsa";
 export type KeyCurve = "p256" | "p384";
@@ -13,26 +15,99 @@ export type IssuanceKeyOption =
 
 export type StartIssuanceRequest = {
   domains: string[];
-  issuer_id: string;
+  /** Picked by the issuer selection policy when omitted */
+  issuer_id?: string;
   key_algorithm?: KeyAlgorithm;
   key_size?: number;
   key_curve?: KeyCurve;
+  /** Per-name challenge solver; names without one use dns-01 */
+  solvers?: Record<string, ChallengeSolverConfig>;
+  /** Complete in the background once records added by hand propagate */
+  auto_complete?: boolean;
+};
+
+export type ChallengeSolverKind =
+  | "dns01_provider"
+  | "dns01_manual"
+  | "http01_webroot"
+  | "http01_standalone"
+  | "tls_alpn01";
+
+export type ChallengeSolverConfig = {
+  kind: ChallengeSolverKind;
+  /** Web root folder, for http01_webroot */
+  webroot?: string;
+  /** Listen address of the built-in listener, e.g. 0.0.0.0:80 */
+  listen?: string;
+};
+
+export type DnsRecordInstruction = {
+  adapter: string;
+  record_name: string;
+  value: string;
+  zone: string;
+  /** One-time CNAME target when the provider answers outside the zone */
+  cname_target?: string;
+  /** Challenge name that is a CNAME to `record_name` */
+  delegated_from?: string;
+  /** ASCII and Unicode forms of `record_name` */
+  record_name_forms?: DomainForms;
 };
 
 export type StartIssuanceResponse = {
   request_id: string;
-  dns_records: Array<{
-    adapter: string;
-    record_name: string;
-    value: string;
-    zone: string;
-  }>;
+  issuer_id: string;
+  dns_records: DnsRecordInstruction[];
 };
 
 export type CompleteIssuanceRequest = {
   request_id: string;
+  /** Replaces the provider and preset waits for every challenge record. */
+  propagation_wait?: PropagationWait | null;
 };
 
+export type FinalizationStage =
+  | "checking_dns"
+  | "validating_challenges"
+  | "awaiting_order"
+  | "finalizing"
+  | "completed";
+
+export type IssuanceProgress = {
+  request_id: string;
+  stage: FinalizationStage;
+  attempt: number;
+  elapsed_secs: number;
+  message: string;
+};
+
+export const ISSUANCE_PROGRESS_EVENT = "issuance-progress";
+
+export function listenIssuanceProgress(
+  handler: (progress: IssuanceProgress) => void,
+): Promise<UnlistenFn> {
+  return listen<IssuanceProgress>(ISSUANCE_PROGRESS_EVENT, (event) => {
+    handler(event.payload);
+  });
+}
+
+export const AUTO_COMPLETE_EVENT = "issuance-auto-complete";
+
+/** Issuance completed in the background after its records propagated. */
+export type AutoCompleteOutcome = {
+  request_id: string;
+  certificate: CertificateRecord | null;
+  error: string | null;
+};
+
+export function listenAutoComplete(
+  handler: (outcome: AutoCompleteOutcome) => void,
+): Promise<UnlistenFn> {
+  return listen<AutoCompleteOutcome>(AUTO_COMPLETE_EVENT, (event) => {
+    handler(event.payload);
+  });
+}
+
 export async function startManagedIssuance(
   req: StartIssuanceRequest,
 ): Promise<StartIssuanceResponse> {
@@ -49,6 +124,152 @@ export async function completeManagedIssuance(
   });
 }
 
+export type ClockSkewCheck = {
+  local_time: string;
+  server_time: string;
+  /** Positive when the local clock is ahead of the ACME server. */
+  skew_secs: number;
+  threshold_secs: number;
+  exceeds_threshold: boolean;
+};
+
+export async function checkIssuerClockSkew(
+  issuerId: string,
+): Promise<ClockSkewCheck> {
+  return invoke<ClockSkewCheck>("check_issuer_clock_skew", { issuerId });
+}
+
+export type PreflightStatus = "pass" | "warn" | "fail";
+
+export type PreflightCheckKind =
+  | "provider"
+  | "caa"
+  | "delegation"
+  | "record_plan"
+  | "inventory"
+  | "rate_limit";
+
+export type PreflightCheck = {
+  kind: PreflightCheckKind;
+  status: PreflightStatus;
+  detail: string;
+};
+
+export type DomainPreflight = {
+  domain: string;
+  /** Worst status of the domain's checks */
+  status: PreflightStatus;
+  checks: PreflightCheck[];
+};
+
+export type PreflightReport = {
+  issuer_id: string;
+  /** False when any check failed */
+  go: boolean;
+  domains: DomainPreflight[];
+  /** Checks that apply to the order as a whole */
+  order_checks: PreflightCheck[];
+};
+
+/** Go/no-go checks for the names before issuance starts. */
+export type DomainIssueKind =
+  | "empty"
+  | "invalid_character"
+  | "empty_label"
+  | "label_too_long"
+  | "name_too_long"
+  | "punycode"
+  | "wildcard";
+
+export type DomainIssue = {
+  /** Position of the name in the submitted list */
+  index?: number | null;
+  input: string;
+  kind: DomainIssueKind;
+  message: string;
+  /** Character offset in `input` where the problem starts */
+  position?: number | null;
+  suggestion?: string | null;
+};
+
+/** What is wrong with each malformed name; empty when all are valid. */
+export async function validateDomains(domains: string[]): Promise<DomainIssue[]> {
+  return invoke<DomainIssue[]>("validate_domains", { domains });
+}
+
+export type ParsedDomainList = {
+  domains: string[];
+  rejected: Array<{ entry: string; reason: string }>;
+};
+
+/** Extracts names from pasted text such as spreadsheets, URLs or nginx configs. */
+export async function parseDomainList(text: string): Promise<ParsedDomainList> {
+  return invoke<ParsedDomainList>("parse_domain_list", { text });
+}
+
+export async function preflightDomains(
+  domains: string[],
+  issuerId?: string | null,
+): Promise<PreflightReport> {
+  return invoke<PreflightReport>("preflight_domains", {
+    domains,
+    issuerId: issuerId ?? null,
+  });
+}
+
+export type DnsChallengeInput = {
+  domain: string;
+  txt_value: string;
+};
+
+export type PreparedDnsChallenge = {
+  domain: string;
+  record: DnsRecordInstruction;
+  /** Written by a configured provider rather than by hand */
+  automated: boolean;
+  provider_id: string | null;
+  provider_label: string | null;
+  /** Providers matching the name equally well; none is automated then */
+  ambiguous_provider_ids: string[];
+};
+
+/** Challenge records for several names in one call; nothing is written to DNS. */
+export async function prepareDnsChallenges(
+  challenges: DnsChallengeInput[],
+): Promise<PreparedDnsChallenge[]> {
+  return invoke<PreparedDnsChallenge[]>("prepare_dns_challenges", { challenges });
+}
+
+/** Re-issues a staging certificate from a production issuer with the same names and key. */
+export async function promoteToProduction(
+  certificateId: string,
+  issuerId?: string | null,
+): Promise<StartIssuanceResponse> {
+  return invoke<StartIssuanceResponse>("promote_to_production", {
+    promoteReq: { certificate_id: certificateId, issuer_id: issuerId ?? null },
+  });
+}
+
+export type IssuanceArtifactSummary = {
+  request_id: string;
+  domains: string[];
+  started_at: string;
+  last_error: string | null;
+};
+
+/** Issuance attempts since startup that did not end with a certificate, newest first. */
+export async function listIssuanceArtifacts(): Promise<IssuanceArtifactSummary[]> {
+  return invoke<IssuanceArtifactSummary[]>("list_issuance_artifacts");
+}
+
+/**
+ * Writes the ACME objects, challenge records and redacted errors of an
+ * issuance attempt to a JSON file for troubleshooting. Returns the path.
+ */
+export async function exportIssuanceArtifacts(requestId: string, path: string): Promise<string> {
+  return invoke<string>("export_issuance_artifacts", { requestId, path });
+}
+
 export function keyOptionToParams(option: IssuanceKeyOption): {
   key_algorithm: KeyAlgorithm;
   key_size?: number;
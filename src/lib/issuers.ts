@@ -1,8 +1,16 @@
 import { invoke } from "@tauri-apps/api/core";
+import type { ConflictResolution } from "./errors";
 
 export type IssuerEnvironment = "staging" | "production";
 export type IssuerType = "acme";
 
+export type CaPinKind = "subject" | "spki_sha256";
+
+export type CaPin = {
+  kind: CaPinKind;
+  value: string;
+};
+
 export type IssuerConfig = {
   issuer_id: string;
   label: string;
@@ -13,8 +21,31 @@ export type IssuerConfig = {
   account_key_ref?: string | null;
   tos_agreed: boolean;
   is_selected: boolean;
+  preferred_chain?: string | null;
+  ca_pins: CaPin[];
+  enforce_ca_pins: boolean;
+  /** Disabled issuers stay configured but cannot be selected or issue. */
+  disabled: boolean;
+  stats: IssuerStats;
 };
 
+/** ACME activity counted for an issuer since it was configured. */
+export type IssuerStats = {
+  orders: number;
+  successes: number;
+  failures: number;
+  last_error?: string | null;
+  last_error_at?: string | null;
+  last_success_at?: string | null;
+};
+
+/** Whether the issuer's most recent ACME activity was a failure. */
+export function issuerIsFailing(stats: IssuerStats | undefined): boolean {
+  if (!stats?.last_error_at) return false;
+  if (!stats.last_success_at) return true;
+  return new Date(stats.last_error_at) > new Date(stats.last_success_at);
+}
+
 export type CreateIssuerRequest = {
   label: string;
   issuer_type: IssuerType;
@@ -22,6 +53,10 @@ export type CreateIssuerRequest = {
   directory_url: string;
   contact_email?: string;
   tos_agreed: boolean;
+  preferred_chain?: string | null;
+  ca_pins?: CaPin[];
+  enforce_ca_pins?: boolean;
+  on_conflict?: ConflictResolution | null;
 };
 
 export type UpdateIssuerRequest = {
@@ -31,12 +66,61 @@ export type UpdateIssuerRequest = {
   directory_url: string;
   contact_email?: string;
   tos_agreed: boolean;
+  preferred_chain?: string | null;
+  ca_pins?: CaPin[];
+  enforce_ca_pins?: boolean;
+  disabled?: boolean;
+};
+
+export const ISSUER_POLICY_PREFERENCE = "issuer_selection_policy";
+
+/** How an issuer is picked when issuance starts without one. */
+export type IssuerSelectionPolicy = "smart" | "staging" | "production" | "selected";
+
+export type IssuerSelectionReason =
+  | "test_domain"
+  | "new_domain"
+  | "previously_issued"
+  | "policy"
+  | "selected_issuer"
+  | "fallback";
+
+export type IssuerSuggestion = {
+  issuer_id: string;
+  environment: IssuerEnvironment;
+  policy: IssuerSelectionPolicy;
+  reason: IssuerSelectionReason;
 };
 
 export type DeleteIssuerRequest = {
   issuer_id: string;
+  /** Required when a confirmation PIN is set. */
+  confirmation_pin?: string;
 };
 
+const SPKI_PIN_PREFIX = "sha256/";
+
+/** Parses one pin per line; `sha256/<hash>` lines are SPKI pins, others CA subjects. */
+export function parseCaPins(text: string): CaPin[] {
+  return text
+    .split("\n")
+    .map((line) => line.trim())
+    .filter(Boolean)
+    .map((line): CaPin =>
+      line.startsWith(SPKI_PIN_PREFIX)
+        ? { kind: "spki_sha256", value: line.slice(SPKI_PIN_PREFIX.length) }
+        : { kind: "subject", value: line },
+    );
+}
+
+export function formatCaPins(pins: CaPin[]): string {
+  return pins
+    .map((pin) =>
+      pin.kind === "spki_sha256" ? `${SPKI_PIN_PREFIX}${pin.value}` : pin.value,
+    )
+    .join("\n");
+}
+
 export async function listIssuers(): Promise<IssuerConfig[]> {
   return invoke<IssuerConfig[]>("list_issuers");
 }
@@ -64,3 +148,25 @@ export async function deleteIssuer(
 ): Promise<string> {
   return invoke<string>("delete_issuer", { deleteReq: req });
 }
+
+/**
+ * Whether a certificate came from a staging issuer. Older certificates
+ * without an issuer id fall back to Let's Encrypt's "(STAGING)" CA names.
+ */
+export function isStagingCertificate(
+  certificate: { issuer: string; issuer_id?: string | null },
+  issuers: IssuerConfig[],
+): boolean {
+  if (certificate.issuer_id) {
+    return issuers.some(
+      (issuer) =>
+        issuer.issuer_id === certificate.issuer_id && issuer.environment === "staging",
+    );
+  }
+  return certificate.issuer.toUpperCase().includes("(STAGING)");
+}
+
+/** Issuer the selection policy would use for these domains. */
+export async function suggestIssuer(domains: string[]): Promise<IssuerSuggestion> {
+  return invoke<IssuerSuggestion>("suggest_issuer", { domains });
+}
@@ -7,6 +7,11 @@ export type IssuerFormState = {
   directory_url: string;
   contact_email: string;
   tos_agreed: boolean;
+  preferred_chain: string;
+  /** One pin per line, see `parseCaPins` */
+  ca_pins: string;
+  enforce_ca_pins: boolean;
+  disabled: boolean;
 };
 
 export function validateIssuerForm(form: IssuerFormState): string | null {
@@ -0,0 +1,90 @@
+import { getPreference, setPreference } from "./preferences";
+
+/** Stable codes of backend messages; English text comes from the backend. */
+export type MessageCode =
+  | "issuer_label_required"
+  | "directory_url_required"
+  | "contact_email_required"
+  | "tos_acceptance_required"
+  | "provider_label_required"
+  | "domain_suffix_required"
+  | "api_token_required"
+  | "domain_required"
+  | "preference_name_required"
+  | "propagation_wrong_content"
+  | "propagation_pending"
+  | "propagation_nx_domain"
+  | "propagation_no_responses";
+
+export const LOCALE_PREFERENCE = "locale";
+
+type MessageCatalog = Partial<Record<MessageCode, string>>;
+
+const CATALOGS: Record<string, MessageCatalog> = {
+  de: {
+    issuer_label_required: "Eine Bezeichnung für den Aussteller ist erforderlich",
+    directory_url_required: "Die Verzeichnis-URL ist erforderlich",
+    contact_email_required:
+      "Für ACME-Aussteller ist eine Kontakt-E-Mail erforderlich",
+    tos_acceptance_required:
+      "Für ACME-Aussteller müssen die Nutzungsbedingungen akzeptiert werden",
+    provider_label_required: "Eine Bezeichnung für den Anbieter ist erforderlich",
+    domain_suffix_required: "Mindestens ein Domain-Suffix ist erforderlich",
+    api_token_required: "Für diesen Anbieter ist ein API-Token erforderlich",
+    domain_required: "Ein Domainname ist erforderlich",
+    preference_name_required: "Der Name der Einstellung ist erforderlich",
+    propagation_wrong_content: "TXT-Eintrag mit abweichendem Wert vorhanden",
+    propagation_pending: "Eintrag noch nicht gefunden",
+    propagation_nx_domain: "Eintrag nicht gefunden (NXDOMAIN)",
+    propagation_no_responses: "Keine Antwort von den DNS-Resolvern",
+  },
+  fr: {
+    issuer_label_required: "Le nom de l'émetteur est obligatoire",
+    directory_url_required: "L'URL du répertoire est obligatoire",
+    contact_email_required:
+      "Une adresse e-mail de contact est obligatoire pour les émetteurs ACME",
+    tos_acceptance_required:
+      "Les conditions d'utilisation doivent être acceptées pour les émetteurs ACME",
+    provider_label_required: "Le nom du fournisseur est obligatoire",
+    domain_suffix_required: "Au moins un suffixe de domaine est obligatoire",
+    api_token_required: "Un jeton d'API est obligatoire pour ce fournisseur",
+    domain_required: "Le nom de domaine est obligatoire",
+    preference_name_required: "Le nom de la préférence est obligatoire",
+    propagation_wrong_content:
+      "Enregistrement TXT présent avec une valeur différente",
+    propagation_pending: "Enregistrement pas encore trouvé",
+    propagation_nx_domain: "Enregistrement introuvable (NXDOMAIN)",
+    propagation_no_responses: "Aucune réponse des résolveurs DNS",
+  },
+};
+
+export const SUPPORTED_LOCALES: { value: string; label: string }[] = [
+  { value: "en", label: "English" },
+  { value: "de", label: "Deutsch" },
+  { value: "fr", label: "Français" },
+];
+
+let activeLocale =
+  typeof navigator === "undefined" ? "en" : navigator.language;
+
+/** Locale preference, or an empty string when following the system. */
+export async function loadMessageLocale(): Promise<string> {
+  const saved = (await getPreference(LOCALE_PREFERENCE))?.value ?? "";
+  activeLocale = saved || navigator.language;
+  return saved;
+}
+
+export async function saveMessageLocale(locale: string): Promise<void> {
+  await setPreference(LOCALE_PREFERENCE, locale);
+  activeLocale = locale || navigator.language;
+}
+
+/**
+ * Text for a catalog message in the active locale, trying the full tag
+ * (`de-CH`) before its language (`de`) and falling back to `english`.
+ */
+export function localizeMessage(code: string, english: string): string {
+  const tag = activeLocale.toLowerCase();
+  const catalog = CATALOGS[tag] ?? CATALOGS[tag.split("-")[0]];
+  return catalog?.[code as MessageCode] ?? english;
+}
@@ -0,0 +1,25 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type MetricKind = "command" | "dns_provider";
+
+/** Local call statistics for one command or DNS provider. */
+export type MetricSummary = {
+  kind: MetricKind;
+  /** Command name or provider id */
+  name: string;
+  label: string;
+  calls: number;
+  failures: number;
+  avg_ms: number;
+  max_ms: number;
+  last_error?: string | null;
+  last_called_at: string;
+};
+
+export async function getMetrics(): Promise<MetricSummary[]> {
+  return invoke("get_metrics");
+}
+
+export async function clearMetrics(): Promise<void> {
+  return invoke("clear_metrics");
+}
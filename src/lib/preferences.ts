@@ -6,6 +6,16 @@ export type PreferenceEntry = {
 };
 
 export const EXPORT_DESTINATION_PREFERENCE = "export_destination_dir";
+export const UPDATE_CHECK_PREFERENCE = "update_check_enabled";
+export const UPDATER_PREFERENCE = "updater_enabled";
+export const READ_ONLY_PREFERENCE = "read_only_mode";
+export const OUTBOUND_USER_AGENT_PREFERENCE = "outbound_user_agent";
+export const OUTBOUND_CONTACT_PREFERENCE = "outbound_contact";
+
+export type ReadOnlyStatus = {
+  enabled: boolean;
+  forced: boolean;
+};
 
 export async function getPreference(
   name: string,
@@ -23,3 +33,21 @@ export async function setPreference(
     setReq: { name, value },
   });
 }
+
+export async function getReadOnlyStatus(): Promise<ReadOnlyStatus> {
+  return invoke<ReadOnlyStatus>("get_read_only_status");
+}
+
+export async function getConfirmationPinStatus(): Promise<boolean> {
+  return invoke<boolean>("get_confirmation_pin_status");
+}
+
+/** Sets, changes or (with `newPin` null) removes the confirmation PIN. */
+export async function setConfirmationPin(
+  currentPin: string | null,
+  newPin: string | null,
+): Promise<void> {
+  return invoke<void>("set_confirmation_pin", {
+    setReq: { current_pin: currentPin, new_pin: newPin },
+  });
+}
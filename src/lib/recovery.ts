@@ -0,0 +1,50 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type JobKind = "issuance" | "export" | "deployment";
+
+export type RecoveryAction = "cleanup" | "resume" | "discard";
+
+export type InterruptedJob = {
+  id: string;
+  kind: JobKind;
+  started_at: string;
+  summary: string;
+  actions: RecoveryAction[];
+};
+
+/** Operations a previous run left unfinished (crash or forced quit). */
+export async function listRecoveryActions(): Promise<InterruptedJob[]> {
+  return invoke<InterruptedJob[]>("list_recovery_actions");
+}
+
+export async function applyRecoveryAction(
+  jobId: string,
+  action: RecoveryAction,
+): Promise<void> {
+  return invoke("apply_recovery_action", {
+    recoveryReq: { job_id: jobId, action },
+  });
+}
+
+export type ConfigIssueKind = "provider_secret" | "issuer_account_key" | "certificate_key";
+
+export type ConfigRepair = "create_account_key" | "detach_managed_key";
+
+export type ConfigIssue = {
+  id: string;
+  kind: ConfigIssueKind;
+  subject_id: string;
+  subject_label: string;
+  detail: string;
+  /** Null when the issue has to be fixed by hand */
+  repair: ConfigRepair | null;
+};
+
+/** Stored configuration referring to secrets that no longer exist. */
+export async function getConfigIssues(): Promise<ConfigIssue[]> {
+  return invoke<ConfigIssue[]>("get_config_issues");
+}
+
+export async function repairConfigIssue(issueId: string): Promise<void> {
+  return invoke("repair_config_issue", { issueId });
+}
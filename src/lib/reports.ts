@@ -0,0 +1,65 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type ReportPeriod = "weekly" | "monthly";
+export type ReportFormat = "markdown" | "html";
+
+export const REPORT_SCHEDULE_PREFERENCE = "report_schedule";
+export const REPORT_FORMAT_PREFERENCE = "report_format";
+export const REPORT_FOLDER_PREFERENCE = "report_folder";
+export const REPORT_LAST_RUN_PREFERENCE = "report_last_generated_at";
+export const REPORT_GENERATED_EVENT = "report-generated";
+
+export type GenerateReportRequest = {
+  period: ReportPeriod;
+  format?: ReportFormat | null;
+  folder?: string | null;
+};
+
+export type CertificateReportResult = {
+  path: string;
+  period: ReportPeriod;
+  period_start: string;
+  period_end: string;
+  issued: number;
+  renewed: number;
+  failed: number;
+  expiring: number;
+};
+
+export async function generateCertificateReport(
+  reportReq: GenerateReportRequest,
+): Promise<CertificateReportResult> {
+  return invoke<CertificateReportResult>("generate_certificate_report", {
+    reportReq,
+  });
+}
+
+export const STATUS_FEED_PATH_PREFERENCE = "status_feed_path";
+
+export type StatusFeedHealth = "ok" | "warning" | "critical";
+
+export type StatusFeed = {
+  generated_at: string;
+  health: StatusFeedHealth;
+  total: number;
+  active: number;
+  expiring_soon: number;
+  pending_renewal: number;
+  expired: number;
+  worst_expiry: {
+    names: string[];
+    not_after: string;
+    days_left: number;
+  } | null;
+};
+
+export type StatusFeedResult = {
+  path: string;
+  feed: StatusFeed;
+};
+
+export async function writeStatusFeed(
+  path?: string | null,
+): Promise<StatusFeedResult> {
+  return invoke<StatusFeedResult>("write_status_feed", { path });
+}
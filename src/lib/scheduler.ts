@@ -0,0 +1,22 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export const QUIET_HOURS_PREFERENCE = "scheduler_quiet_hours";
+export const PAUSE_ON_BATTERY_PREFERENCE = "scheduler_pause_on_battery";
+export const PAUSE_ON_METERED_PREFERENCE = "scheduler_pause_on_metered";
+
+export type BackgroundDeferral = "quiet_hours" | "on_battery" | "metered_connection";
+
+export type BackgroundStatus = {
+  deferred_by: BackgroundDeferral | null;
+  in_quiet_hours: boolean;
+  on_battery: boolean | null;
+  metered: boolean | null;
+};
+
+export async function getBackgroundStatus(): Promise<BackgroundStatus> {
+  return invoke<BackgroundStatus>("get_background_status");
+}
+
+export async function runBackgroundTasksNow(): Promise<void> {
+  return invoke<void>("run_background_tasks_now");
+}
@@ -5,13 +5,18 @@ export type SecretKind =
   | "dns_provider_access_key"
   | "dns_provider_secret_key"
   | "acme_account_key"
-  | "managed_private_key";
+  | "managed_private_key"
+  | "deploy_target_credentials";
+
+export type SecretStorageBackend = "vault" | "directory";
 
 export type SecretRefRecord = {
   id: string;
   kind: SecretKind;
   label: string;
   created_at: string;
+  storage_backend: SecretStorageBackend;
+  storage_path?: string | null;
 };
 
 export async function listSecretRefs(): Promise<SecretRefRecord[]> {
@@ -21,3 +26,14 @@ export async function listSecretRefs(): Promise<SecretRefRecord[]> {
 export async function lockVault(): Promise<void> {
   return invoke("lock_vault");
 }
+
+export async function getManagedKeyDirectory(): Promise<string | null> {
+  return invoke<string | null>("get_managed_key_directory");
+}
+
+/** Pass `null` to keep new managed keys in the vault database. */
+export async function setManagedKeyDirectory(
+  directory: string | null,
+): Promise<string | null> {
+  return invoke<string | null>("set_managed_key_directory", { directory });
+}
@@ -0,0 +1,31 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+export const STATE_RELOADED_EVENT = "state://reloaded";
+
+export type StateReloadReason =
+  | "backup_restore"
+  | "profile_switch"
+  | "legacy_import"
+  | "manual";
+
+export type StateReloaded = {
+  reason: StateReloadReason;
+  abandoned_issuances: number;
+  interrupted_jobs: number;
+  reloaded_at: string;
+};
+
+export async function reloadAppState(
+  reason: StateReloadReason = "manual",
+): Promise<StateReloaded> {
+  return invoke<StateReloaded>("reload_app_state", { reason });
+}
+
+export function listenStateReloaded(
+  handler: (reloaded: StateReloaded) => void,
+): Promise<UnlistenFn> {
+  return listen<StateReloaded>(STATE_RELOADED_EVENT, (event) => {
+    handler(event.payload);
+  });
+}
@@ -0,0 +1,15 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type UpdateCheckResult = {
+  current_version: string;
+  latest_version: string;
+  update_available: boolean;
+  release_notes?: string | null;
+  download_url?: string | null;
+  published_at?: string | null;
+  updater_enabled: boolean;
+};
+
+export async function checkForUpdates(): Promise<UpdateCheckResult> {
+  return invoke<UpdateCheckResult>("check_for_updates");
+}
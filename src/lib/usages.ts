@@ -0,0 +1,74 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type CertificateUsage = {
+  id: string;
+  certificate_id: string;
+  endpoint?: string | null;
+  deploy_target_id?: string | null;
+  deploy_target_label?: string | null;
+  /** Linked to an earlier certificate with the same names */
+  inherited: boolean;
+  created_at: string;
+};
+
+export type EndpointServingState =
+  | "current"
+  | "outdated"
+  | "newer"
+  | "other"
+  | "unreachable";
+
+export type EndpointCheck = {
+  usage_id: string;
+  endpoint: string;
+  state: EndpointServingState;
+  served_fingerprint?: string | null;
+  served_certificate_id?: string | null;
+  error?: string | null;
+};
+
+export async function listCertificateUsages(
+  certificateId: string,
+): Promise<CertificateUsage[]> {
+  return invoke("list_certificate_usages", { certificateId });
+}
+
+export async function addCertificateUsage(
+  certificateId: string,
+  link: { endpoint?: string; deployTargetId?: string },
+): Promise<CertificateUsage> {
+  return invoke("add_certificate_usage", {
+    usageReq: {
+      certificate_id: certificateId,
+      endpoint: link.endpoint ?? null,
+      deploy_target_id: link.deployTargetId ?? null,
+    },
+  });
+}
+
+export async function removeCertificateUsage(usageId: string): Promise<void> {
+  return invoke("remove_certificate_usage", { usageId });
+}
+
+/** Connects to each linked endpoint and compares what it serves. */
+export async function checkCertificateUsages(
+  certificateId: string,
+): Promise<EndpointCheck[]> {
+  return invoke("check_certificate_usages", { certificateId });
+}
+
+export type EndpointObservation = {
+  endpoint: string;
+  fingerprint: string;
+  /** Null when the certificate is not in the inventory */
+  certificate_id?: string | null;
+  first_seen: string;
+  last_seen: string;
+};
+
+/** Certificates an endpoint has served, most recent first. */
+export async function getEndpointTimeline(
+  endpoint: string,
+): Promise<EndpointObservation[]> {
+  return invoke("get_endpoint_timeline", { endpoint });
+}
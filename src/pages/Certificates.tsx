@@ -1,18 +1,21 @@
-import { AlertCircle, Compass, ShieldCheck } from "lucide-react";
-import { useEffect, useMemo, useState } from "react";
-import { Link, useNavigate } from "react-router-dom";
+import { AlertCircle, Compass, ShieldCheck, Upload } from "lucide-react";
+import { useEffect, useMemo, useRef, useState } from "react";
+import { Link, useNavigate, useSearchParams } from "react-router-dom";
 import { CertificateDetail } from "../components/certificates/CertificateDetail";
 import { CertificatesEmptyState } from "../components/certificates/CertificatesEmptyState";
 import { CertificateStats } from "../components/certificates/CertificateStats";
 import { Inventory } from "../components/certificates/Inventory";
 import { PageHeader } from "../components/page-header";
+import { ConfigIssuesPanel } from "../components/recovery/ConfigIssuesPanel";
+import { RecoveryPanel } from "../components/recovery/RecoveryPanel";
 import { Button } from "../components/ui/button";
-import { daysUntil } from "../components/certificates/certificate-utils";
 import {
   getCertificate,
+  importCertificate,
   listCertificates,
   type CertificateRecord,
 } from "../lib/certificates";
+import { normalizeError } from "../lib/errors";
 
 export function CertificatesPage() {
   const [records, setRecords] = useState<CertificateRecord[]>([]);
@@ -22,7 +25,11 @@ export function CertificatesPage() {
   const [loadingDetail, setLoadingDetail] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [detailError, setDetailError] = useState<string | null>(null);
+  const [importing, setImporting] = useState(false);
+  const fileInput = useRef<HTMLInputElement>(null);
   const navigate = useNavigate();
+  const [searchParams] = useSearchParams();
+  const requestedId = searchParams.get("select");
 
   const managedCount = useMemo(
     () => records.filter((r) => r.source === "Managed").length,
@@ -33,7 +40,7 @@ export function CertificatesPage() {
     [records],
   );
   const expiringSoon = useMemo(
-    () => records.filter((r) => daysUntil(r.not_after) < 30).length,
+    () => records.filter((r) => r.status === "expiring_soon").length,
     [records],
   );
 
@@ -41,6 +48,13 @@ export function CertificatesPage() {
     refreshList();
   }, []);
 
+  useEffect(() => {
+    // Links such as sslboard://check/<domain> select a certificate.
+    if (requestedId) {
+      setSelectedId(requestedId);
+    }
+  }, [requestedId]);
+
   useEffect(() => {
     if (!selectedId) {
       setSelected(null);
@@ -95,6 +109,19 @@ export function CertificatesPage() {
     }
   }
 
+  async function importFile(file: File) {
+    setImporting(true);
+    setError(null);
+    try {
+      const imported = await importCertificate(new Uint8Array(await file.arrayBuffer()));
+      await refreshList();
+      setSelectedId(imported.record.id);
+    } catch (err) {
+      setError(`Could not import ${file.name}: ${normalizeError(err)}`);
+    } finally {
+      setImporting(false);
+    }
+  }
 
   return (
     <div className="space-y-6">
@@ -103,6 +130,26 @@ export function CertificatesPage() {
         description="Metadata-first inventory for issued and discovered certificates."
         action={
           <div className="flex gap-2">
+            <input
+              ref={fileInput}
+              type="file"
+              className="hidden"
+              accept=".pem,.crt,.cer,.der,.p7b,.p7c"
+              onChange={(event) => {
+                const file = event.target.files?.[0];
+                event.target.value = "";
+                if (file) void importFile(file);
+              }}
+            />
+            <Button
+              variant="outline"
+              title="PEM bundles, DER and PKCS#7 (.p7b) files"
+              disabled={importing}
+              onClick={() => fileInput.current?.click()}
+            >
+              <Upload className="mr-2 h-4 w-4" />
+              Import
+            </Button>
             <Button asChild variant="outline">
               <Link to="/discover">
                 <Compass className="mr-2 h-4 w-4" />
@@ -119,6 +166,9 @@ export function CertificatesPage() {
         }
       />
 
+      <RecoveryPanel />
+      <ConfigIssuesPanel />
+
       {error ? (
         <div className="flex items-center gap-2 rounded-lg border border-red-200 bg-red-50 p-3 text-sm text-red-600 dark:border-red-900 dark:bg-red-950/30">
           <AlertCircle className="h-4 w-4" />
@@ -1,34 +1,62 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { AlertTriangle, ShieldCheck } from "lucide-react";
-import { Link } from "react-router-dom";
+import { Link, useSearchParams } from "react-router-dom";
 import { Button } from "../components/ui/button";
 import { PageHeader } from "../components/page-header";
 import { useIssuerOptions } from "../hooks/useIssuerOptions";
 import { useProviderPreview } from "../hooks/useProviderPreview";
 import { useManagedIssuanceFlow } from "../hooks/useManagedIssuanceFlow";
+import { useClockSkewCheck } from "../hooks/useClockSkewCheck";
 import { IssuerSelectionCard } from "../components/issue/IssuerSelectionCard";
 import { DomainsInputCard } from "../components/issue/DomainsInputCard";
 import { DnsInstructionsPanel } from "../components/issue/DnsInstructionsPanel";
 import { IssuanceResultBanner } from "../components/issue/IssuanceResultBanner";
 import { CompletedCertificateCard } from "../components/issue/CompletedCertificateCard";
+import type { PropagationWait } from "../lib/dns-providers";
 import type { IssuanceKeyOption } from "../lib/issuance";
 
 export function IssuePage() {
-  const [domainsInput, setDomainsInput] = useState("test.ezs3.net");
+  const [searchParams, setSearchParams] = useSearchParams();
+  const requestedDomains = searchParams.get("domains");
+  // Set when promoting a staging certificate from its detail view
+  const promoteFrom = searchParams.get("promote");
+  const [domainsInput, setDomainsInput] = useState(
+    requestedDomains ?? "test.ezs3.net",
+  );
   const [keyOption, setKeyOption] = useState<IssuanceKeyOption>("rsa-2048");
+  const [waitTimeout, setWaitTimeout] = useState("");
+  const [waitInterval, setWaitInterval] = useState("");
+  const [autoComplete, setAutoComplete] = useState(false);
+
+  useEffect(() => {
+    // Links such as sslboard://renew/<id> prefill the names to issue.
+    if (requestedDomains) {
+      setDomainsInput(requestedDomains.split(",").join(", "));
+    }
+  }, [requestedDomains]);
+
+  const normalizedInput = domainsInput.normalize("NFC");
+  const parsedDomains = normalizedInput
+    .split(/[\s,]+/)
+    .map((d) => d.normalize("NFC").trim().toLowerCase())
+    .filter(Boolean);
+
   const {
     issuers,
     issuerLoading,
     issuerError,
     selectedIssuer,
+    suggestion,
     selectIssuerById,
-  } = useIssuerOptions();
+  } = useIssuerOptions(parsedDomains);
 
-  const normalizedInput = domainsInput.normalize("NFC");
-  const parsedDomains = normalizedInput
-    .split(/[\s,]+/)
-    .map((d) => d.normalize("NFC").trim().toLowerCase())
-    .filter(Boolean);
+  useEffect(() => {
+    if (!promoteFrom || selectedIssuer?.environment === "production") return;
+    const production = issuers.find((issuer) => issuer.environment === "production");
+    if (production) {
+      selectIssuerById(production.issuer_id);
+    }
+  }, [promoteFrom, issuers, selectedIssuer]);
 
   const { providerPreview, providerLoading, providerError } =
     useProviderPreview(parsedDomains);
@@ -40,16 +68,28 @@ export function IssuePage() {
     error,
     certificate,
     manualRecords,
+    cnameRecords,
     hasManual,
     hasManaged,
     dnsModeLabel,
     awaitingManual,
     finalizeFailed,
+    finalizeResumable,
+    progress,
     handleStart,
     continueIssuance,
     retryFinalization,
     reset,
-  } = useManagedIssuanceFlow(selectedIssuer?.issuer_id ?? null, parsedDomains, keyOption);
+  } = useManagedIssuanceFlow(
+    selectedIssuer?.issuer_id ?? null,
+    parsedDomains,
+    keyOption,
+    promoteFrom,
+    parsePropagationWait(waitTimeout, waitInterval),
+    autoComplete,
+  );
+
+  const clockSkew = useClockSkewCheck(selectedIssuer?.issuer_id ?? null);
 
   const issuerLabel = selectedIssuer?.label ?? "No issuer selected";
   const issuerEnvironment = selectedIssuer?.environment ?? "staging";
@@ -69,6 +109,12 @@ export function IssuePage() {
   function handleReset() {
     setDomainsInput("test.ezs3.net");
     setKeyOption("rsa-2048");
+    setWaitTimeout("");
+    setWaitInterval("");
+    setAutoComplete(false);
+    if (promoteFrom) {
+      setSearchParams({});
+    }
     reset();
   }
 
@@ -87,6 +133,19 @@ export function IssuePage() {
         }
       />
 
+      {promoteFrom ? (
+        <div className="flex items-start gap-3 rounded-xl border border-primary/40 bg-primary/5 px-4 py-3 text-sm shadow-soft">
+          <ShieldCheck className="mt-0.5 h-4 w-4 text-primary" />
+          <div>
+            <div className="font-semibold">Promoting a staging certificate</div>
+            <p className="text-[13px] text-muted-foreground">
+              The names and key parameters of the staging certificate are reissued
+              with a production issuer, and the new certificate is linked to it.
+            </p>
+          </div>
+        </div>
+      ) : null}
+
       {selectedIssuer && issuerEnvironment === "staging" ? (
         <div className="flex items-start gap-3 rounded-xl border border-amber-300 bg-amber-50 px-4 py-3 text-sm text-amber-900 shadow-soft">
           <AlertTriangle className="mt-0.5 h-4 w-4" />
@@ -99,9 +158,25 @@ export function IssuePage() {
         </div>
       ) : null}
 
+      {clockSkew?.exceeds_threshold ? (
+        <div className="flex items-start gap-3 rounded-xl border border-amber-300 bg-amber-50 px-4 py-3 text-sm text-amber-900 shadow-soft">
+          <AlertTriangle className="mt-0.5 h-4 w-4" />
+          <div>
+            <div className="font-semibold">System clock looks wrong</div>
+            <p className="text-[13px] text-amber-900/80">
+              This computer's clock is {formatSkew(clockSkew.skew_secs)}{" "}
+              {clockSkew.skew_secs > 0 ? "ahead of" : "behind"} the ACME
+              server. Certificates may appear "not yet valid" and requests
+              can be rejected; sync the system clock before issuing.
+            </p>
+          </div>
+        </div>
+      ) : null}
+
       <IssuerSelectionCard
         issuers={issuers}
         selectedIssuer={selectedIssuer}
+        suggestion={suggestion}
         issuerLoading={issuerLoading}
         issuerError={issuerError}
         issuerReady={issuerReady}
@@ -111,6 +186,7 @@ export function IssuePage() {
       <DomainsInputCard
         domainsInput={domainsInput}
         parsedDomains={parsedDomains}
+        issuerId={selectedIssuer?.issuer_id ?? null}
         issuerLabel={issuerLabel}
         issuerEnvironment={issuerEnvironment}
         issuerReady={issuerReady}
@@ -120,8 +196,14 @@ export function IssuePage() {
         providerLoading={providerLoading}
         providerError={providerError}
         keyOption={keyOption}
+        waitTimeout={waitTimeout}
+        waitInterval={waitInterval}
+        autoComplete={autoComplete}
         onDomainsChange={setDomainsInput}
         onKeyOptionChange={setKeyOption}
+        onWaitTimeoutChange={setWaitTimeout}
+        onWaitIntervalChange={setWaitInterval}
+        onAutoCompleteChange={setAutoComplete}
         onStart={handleStart}
         onReset={handleReset}
       />
@@ -134,9 +216,12 @@ export function IssuePage() {
           hasManaged={hasManaged}
           dnsModeLabel={dnsModeLabel}
           manualRecords={manualRecords}
+          cnameRecords={cnameRecords}
           finalizing={finalizing}
           awaitingManual={awaitingManual}
           finalizeFailed={finalizeFailed}
+          finalizeResumable={finalizeResumable}
+          progress={progress}
           hasCertificate={Boolean(certificate)}
           onContinue={continueIssuance}
           onRetryFinalize={retryFinalization}
@@ -147,3 +232,20 @@ export function IssuePage() {
     </div>
   );
 }
+
+/** A wait for this issuance only; both fields are needed to override. */
+function parsePropagationWait(timeout: string, interval: string): PropagationWait | null {
+  const timeoutSecs = Number.parseInt(timeout, 10);
+  const intervalSecs = Number.parseInt(interval, 10);
+  if (Number.isNaN(timeoutSecs) || Number.isNaN(intervalSecs)) {
+    return null;
+  }
+  return { timeout_secs: timeoutSecs, interval_secs: intervalSecs };
+}
+
+function formatSkew(skewSecs: number) {
+  const seconds = Math.abs(skewSecs);
+  if (seconds < 120) return `${seconds} seconds`;
+  if (seconds < 7200) return `${Math.round(seconds / 60)} minutes`;
+  return `${Math.round(seconds / 3600)} hours`;
+}
@@ -1,7 +1,17 @@
 import { Shield, Lock, KeyRound } from "lucide-react";
 import { PageHeader } from "../components/page-header";
+import { BackgroundActivitySettings } from "../components/settings/BackgroundActivitySettings";
+import { BackupSettings } from "../components/settings/BackupSettings";
+import { ConfirmationPinSettings } from "../components/settings/ConfirmationPinSettings";
 import { IssuerManager } from "../components/settings/IssuerManager";
+import { IssuerPolicySettings } from "../components/settings/IssuerPolicySettings";
+import { KeyExportPolicySettings } from "../components/settings/KeyExportPolicySettings";
+import { LanguageSettings } from "../components/settings/LanguageSettings";
+import { MetricsSettings } from "../components/settings/MetricsSettings";
+import { OutboundIdentitySettings } from "../components/settings/OutboundIdentitySettings";
+import { ReportSettings } from "../components/settings/ReportSettings";
 import { SecretReferenceManager } from "../components/settings/SecretReferenceManager";
+import { StatusFeedSettings } from "../components/settings/StatusFeedSettings";
 import { Card, CardContent, CardHeader, CardTitle } from "../components/ui/card";
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "../components/ui/tabs";
 
@@ -16,12 +26,34 @@ export function SettingsPage() {
         <TabsList>
           <TabsTrigger value="issuers">Issuers</TabsTrigger>
           <TabsTrigger value="secrets">Secret references</TabsTrigger>
+          <TabsTrigger value="reports">Reports</TabsTrigger>
+          <TabsTrigger value="backups">Backups</TabsTrigger>
+          <TabsTrigger value="metrics">Metrics</TabsTrigger>
+          <TabsTrigger value="language">Language</TabsTrigger>
         </TabsList>
-        <TabsContent value="issuers">
+        <TabsContent value="issuers" className="space-y-4">
           <IssuerManager />
+          <IssuerPolicySettings />
+          <OutboundIdentitySettings />
         </TabsContent>
-        <TabsContent value="secrets">
+        <TabsContent value="secrets" className="space-y-4">
           <SecretReferenceManager />
+          <ConfirmationPinSettings />
+          <KeyExportPolicySettings />
+        </TabsContent>
+        <TabsContent value="reports" className="space-y-4">
+          <ReportSettings />
+          <StatusFeedSettings />
+        </TabsContent>
+        <TabsContent value="backups" className="space-y-4">
+          <BackupSettings />
+          <BackgroundActivitySettings />
+        </TabsContent>
+        <TabsContent value="metrics">
+          <MetricsSettings />
+        </TabsContent>
+        <TabsContent value="language">
+          <LanguageSettings />
         </TabsContent>
       </Tabs>
 
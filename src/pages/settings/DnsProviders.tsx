@@ -1,6 +1,11 @@
 import { PageHeader } from "../../components/page-header";
 import { DnsProviderForm } from "../../components/dns-providers/DnsProviderForm";
 import { DnsProviderList } from "../../components/dns-providers/DnsProviderList";
+import { ProviderImportExport } from "../../components/dns-providers/ProviderImportExport";
+import { PropagationWaitSettings } from "../../components/dns-providers/PropagationWaitSettings";
+import { ProviderHealthDashboard } from "../../components/dns-providers/ProviderHealthDashboard";
+import { ResolverSettings } from "../../components/dns-providers/ResolverSettings";
+import { TestRecordSettings } from "../../components/dns-providers/TestRecordSettings";
 import { useDnsProviderManager } from "../../hooks/useDnsProviderManager";
 
 export function DnsProvidersPage() {
@@ -22,7 +27,13 @@ export function DnsProvidersPage() {
     testResults,
     testLoading,
     handleTest,
+    verification,
+    suffixWarnings,
+    replaceProvider,
   } = useDnsProviderManager();
+  const verificationIssues = (verification ?? []).filter(
+    (entry) => entry.issues.length > 0,
+  );
 
   return (
     <div className="space-y-6">
@@ -37,6 +48,32 @@ export function DnsProvidersPage() {
         </div>
       ) : null}
 
+      {verificationIssues.length > 0 ? (
+        <div className="rounded-lg border border-amber-200/60 bg-amber-50/70 px-4 py-3 text-sm text-amber-900">
+          <p className="font-medium">Provider saved, but some suffixes need attention:</p>
+          <ul className="mt-1 list-disc space-y-1 pl-5">
+            {verificationIssues.map((entry) => (
+              <li key={entry.suffix}>
+                <span className="font-mono">{entry.suffix}</span>: {entry.issues.join("; ")}
+              </li>
+            ))}
+          </ul>
+        </div>
+      ) : null}
+
+      {suffixWarnings.length > 0 ? (
+        <div className="rounded-lg border border-amber-200/60 bg-amber-50/70 px-4 py-3 text-sm text-amber-900">
+          <p className="font-medium">Provider saved; some suffixes nest with other providers:</p>
+          <ul className="mt-1 list-disc space-y-1 pl-5">
+            {suffixWarnings.map((warning) => (
+              <li key={`${warning.suffix}-${warning.provider_id}-${warning.existing_suffix}`}>
+                {warning.message}
+              </li>
+            ))}
+          </ul>
+        </div>
+      ) : null}
+
       <div className="grid gap-6 lg:grid-cols-[1.2fr,1fr]">
         <DnsProviderList
           providers={providers}
@@ -49,7 +86,8 @@ export function DnsProvidersPage() {
           onCancelDelete={() => setConfirmDeleteId(null)}
           testResults={testResults}
           testLoading={testLoading}
-          onTest={(providerId) => void handleTest(providerId)}
+          onTest={(providerId, mode) => void handleTest(providerId, mode)}
+          onProviderUpdated={replaceProvider}
         />
         <DnsProviderForm
           formState={formState}
@@ -60,6 +98,16 @@ export function DnsProvidersPage() {
           onCancel={resetForm}
         />
       </div>
+
+      <ProviderHealthDashboard providers={providers} />
+
+      <PropagationWaitSettings />
+
+      <ResolverSettings />
+
+      <TestRecordSettings />
+
+      <ProviderImportExport onImported={() => void refreshProviders(true)} />
     </div>
   );
 }